@@ -1,12 +1,25 @@
 use scrypto::prelude::*;
 
+/// A component method which authorizes the withdrawals and proofs of an account, in place of an
+/// access rule.
+#[derive(TypeId, Encode, Decode, Describe)]
+pub struct AuthDelegate {
+    pub component_address: ComponentAddress,
+    pub method: String,
+}
+
 blueprint! {
     struct Account {
         vaults: LazyMap<ResourceAddress, Vault>,
+        auth_delegate: Option<AuthDelegate>,
     }
 
     impl Account {
-        fn internal_new(withdraw_rule: AccessRule, bucket: Option<Bucket>) -> ComponentAddress {
+        fn internal_new(
+            withdraw_rule: AccessRule,
+            auth_delegate: Option<AuthDelegate>,
+            bucket: Option<Bucket>,
+        ) -> ComponentAddress {
             let vaults = LazyMap::new();
             if let Some(b) = bucket {
                 vaults.insert(b.resource_address(), Vault::with_bucket(b));
@@ -16,17 +29,56 @@ blueprint! {
                 .method("balance", rule!(allow_all))
                 .method("deposit", rule!(allow_all))
                 .method("deposit_batch", rule!(allow_all))
+                .method("deposit_batch_safe", rule!(allow_all))
                 .default(withdraw_rule);
 
-            Self { vaults }.instantiate().add_access_check(access_rules).globalize()
+            Self {
+                vaults,
+                auth_delegate,
+            }
+            .instantiate()
+            .add_access_check(access_rules)
+            .globalize()
         }
 
         pub fn new(withdraw_rule: AccessRule) -> ComponentAddress {
-            Self::internal_new(withdraw_rule, Option::None)
+            Self::internal_new(withdraw_rule, Option::None, Option::None)
         }
 
         pub fn new_with_resource(withdraw_rule: AccessRule, bucket: Bucket) -> ComponentAddress {
-            Self::internal_new(withdraw_rule, Option::Some(bucket))
+            Self::internal_new(withdraw_rule, Option::None, Option::Some(bucket))
+        }
+
+        /// Creates an account whose withdrawals and proofs are authorized by a component method,
+        /// instead of an access rule.
+        ///
+        /// The method is called with the account address, the name of the account method and
+        /// its encoded arguments, and returns whether the call is authorized.
+        pub fn new_with_auth_delegate(
+            delegate: ComponentAddress,
+            method: String,
+        ) -> ComponentAddress {
+            Self::internal_new(
+                rule!(allow_all),
+                Option::Some(AuthDelegate {
+                    component_address: delegate,
+                    method,
+                }),
+                Option::None,
+            )
+        }
+
+        fn authorize(&self, method: &str, args: Vec<Vec<u8>>) {
+            if let Some(auth_delegate) = &self.auth_delegate {
+                let account = Runtime::actor().component_address().unwrap();
+                let rtn = Runtime::call_method(
+                    auth_delegate.component_address,
+                    auth_delegate.method.as_str(),
+                    args![account, method.to_owned(), args],
+                );
+                let authorized: bool = scrypto_decode(&rtn).unwrap();
+                assert!(authorized, "Not authorized by delegate");
+            }
         }
 
         pub fn balance(&self, resource_address: ResourceAddress) -> Decimal {
@@ -57,8 +109,25 @@ blueprint! {
             }
         }
 
+        /// Deposit a batch of buckets into this account, failing if any of them holds a resource
+        /// which isn't allowed.
+        pub fn deposit_batch_safe(
+            &mut self,
+            buckets: Vec<Bucket>,
+            allowed_resources: BTreeSet<ResourceAddress>,
+        ) {
+            for bucket in &buckets {
+                assert!(
+                    allowed_resources.contains(&bucket.resource_address()),
+                    "Resource not allowed"
+                );
+            }
+            self.deposit_batch(buckets);
+        }
+
         /// Withdraws resource from this account.
         pub fn withdraw(&mut self, resource_address: ResourceAddress) -> Bucket {
+            self.authorize("withdraw", args![resource_address]);
             let vault = self.vaults.get(&resource_address);
             match vault {
                 Some(mut vault) => vault.take_all(),
@@ -74,6 +143,7 @@ blueprint! {
             amount: Decimal,
             resource_address: ResourceAddress,
         ) -> Bucket {
+            self.authorize("withdraw_by_amount", args![amount, resource_address]);
             let vault = self.vaults.get(&resource_address);
             match vault {
                 Some(mut vault) => vault.take(amount),
@@ -89,6 +159,7 @@ blueprint! {
             ids: BTreeSet<NonFungibleId>,
             resource_address: ResourceAddress,
         ) -> Bucket {
+            self.authorize("withdraw_by_ids", args![ids.clone(), resource_address]);
             let vault = self.vaults.get(&resource_address);
             match vault {
                 Some(mut vault) => vault.take_non_fungibles(&ids),
@@ -100,6 +171,7 @@ blueprint! {
 
         /// Create proof of resource.
         pub fn create_proof(&self, resource_address: ResourceAddress) -> Proof {
+            self.authorize("create_proof", args![resource_address]);
             let vault = self.vaults.get(&resource_address);
             match vault {
                 Some(vault) => vault.create_proof(),
@@ -118,6 +190,7 @@ blueprint! {
             amount: Decimal,
             resource_address: ResourceAddress,
         ) -> Proof {
+            self.authorize("create_proof_by_amount", args![amount, resource_address]);
             let vault = self.vaults.get(&resource_address);
             match vault {
                 Some(vault) => vault.create_proof_by_amount(amount),
@@ -136,6 +209,7 @@ blueprint! {
             ids: BTreeSet<NonFungibleId>,
             resource_address: ResourceAddress,
         ) -> Proof {
+            self.authorize("create_proof_by_ids", args![ids.clone(), resource_address]);
             let vault = self.vaults.get(&resource_address);
             match vault {
                 Some(vault) => vault.create_proof_by_ids(&ids),
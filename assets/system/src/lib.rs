@@ -12,10 +12,41 @@ blueprint! {
             component_system().publish_package(&code)
         }
 
+        /// Publishes a package, which can be upgraded by whoever presents the owner badge.
+        pub fn publish_package_with_owner(
+            code: Vec<u8>,
+            owner_badge: ResourceAddress,
+        ) -> PackageAddress {
+            component_system().publish_package_with_owner(&code, owner_badge)
+        }
+
+        /// Upgrades a package, given the owner badge is in the auth zone.
+        pub fn upgrade_package(package_address: PackageAddress, code: Vec<u8>) -> u32 {
+            borrow_package!(package_address).upgrade(&code)
+        }
+
+        /// Updates a metadata entry of a package, given the owner badge is in the auth zone.
+        pub fn set_package_metadata(
+            package_address: PackageAddress,
+            name: String,
+            value: String,
+        ) {
+            borrow_package!(package_address).set_metadata(&name, &value)
+        }
+
+        /// Disables or enables a method of a component, given its method toggle rule is satisfied.
+        pub fn set_method_enabled(
+            component_address: ComponentAddress,
+            method: String,
+            enabled: bool,
+        ) {
+            borrow_component!(component_address).set_method_enabled(&method, enabled)
+        }
+
         /// Creates a resource.
         pub fn new_resource(
             resource_type: ResourceType,
-            metadata: HashMap<String, String>,
+            metadata: HashMap<String, MetadataValue>,
             access_rules: HashMap<ResourceMethod, (AccessRule, Mutability)>,
             initial_supply: Option<MintParams>,
         ) -> (ResourceAddress, Option<Bucket>) {
@@ -0,0 +1,128 @@
+use scrypto::prelude::*;
+
+#[derive(NonFungibleData)]
+pub struct Member {
+    pub name: String,
+}
+
+#[derive(TypeId, Encode, Decode, Describe)]
+pub struct Proposal {
+    pub description: String,
+    // The amount paid from the treasury if the proposal passes
+    pub amount: Decimal,
+    // The account which receives the payment
+    pub recipient: ComponentAddress,
+    // The members who voted for the proposal
+    pub votes: BTreeSet<NonFungibleId>,
+    pub executed: bool,
+}
+
+blueprint! {
+    struct Dao {
+        // The funds managed by the members
+        treasury: Vault,
+        // The badge which authorizes minting membership badges
+        member_minter: Vault,
+        // The resource address of membership badges
+        member_resource: ResourceAddress,
+        // The ID of the next member
+        next_member_id: u64,
+        // The number of votes a proposal needs to pass
+        quorum: u32,
+        proposals: Vec<Proposal>,
+    }
+
+    impl Dao {
+        // Creates a DAO with a treasury, and returns the component with an admin badge
+        pub fn instantiate_dao(treasury: Bucket, quorum: u32) -> (ComponentAddress, Bucket) {
+            let member_minter: Bucket = ResourceBuilder::new_fungible()
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata("name", "Member Minter Badge")
+                .initial_supply(1);
+            let admin: Bucket = ResourceBuilder::new_fungible()
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata("name", "Admin Badge")
+                .initial_supply(1);
+            let member_resource = ResourceBuilder::new_non_fungible()
+                .metadata("name", "Member Badge")
+                .mintable(rule!(require(member_minter.resource_address())), LOCKED)
+                .no_initial_supply();
+
+            // Only the admin can add members
+            let access_rules = AccessRules::new()
+                .method("add_member", rule!(require(admin.resource_address())))
+                .default(rule!(allow_all));
+            let component = Self {
+                treasury: Vault::with_bucket(treasury),
+                member_minter: Vault::with_bucket(member_minter),
+                member_resource,
+                next_member_id: 0,
+                quorum,
+                proposals: Vec::new(),
+            }
+            .instantiate()
+            .add_access_check(access_rules)
+            .globalize();
+
+            (component, admin)
+        }
+
+        // Mints a membership badge
+        pub fn add_member(&mut self, name: String) -> Bucket {
+            let id = NonFungibleId::from_u64(self.next_member_id);
+            self.next_member_id += 1;
+
+            let resource_manager = borrow_resource_manager!(self.member_resource);
+            self.member_minter
+                .authorize(|| resource_manager.mint_non_fungible(&id, Member { name }))
+        }
+
+        // Proposes a payment from the treasury, and returns the proposal ID
+        pub fn propose(
+            &mut self,
+            member: Proof,
+            description: String,
+            amount: Decimal,
+            recipient: ComponentAddress,
+        ) -> u32 {
+            member
+                .validate(self.member_resource, 1)
+                .expect("Not a member")
+                .drop();
+            self.proposals.push(Proposal {
+                description,
+                amount,
+                recipient,
+                votes: BTreeSet::new(),
+                executed: false,
+            });
+            (self.proposals.len() - 1) as u32
+        }
+
+        // Votes for a proposal, with each membership badge in the proof
+        pub fn vote(&mut self, member: Proof, proposal_id: u32) -> u32 {
+            let member = member
+                .validate(self.member_resource, 1)
+                .expect("Not a member");
+            let proposal = &mut self.proposals[proposal_id as usize];
+            assert!(!proposal.executed, "Proposal already executed");
+            proposal.votes.extend(member.non_fungible_ids());
+            member.drop();
+            proposal.votes.len() as u32
+        }
+
+        // Pays a proposal which reached the quorum
+        pub fn execute(&mut self, proposal_id: u32) {
+            let proposal = &mut self.proposals[proposal_id as usize];
+            assert!(!proposal.executed, "Proposal already executed");
+            assert!(
+                proposal.votes.len() as u32 >= self.quorum,
+                "Proposal has not reached the quorum"
+            );
+            proposal.executed = true;
+
+            let payment = self.treasury.take(proposal.amount);
+            borrow_component!(proposal.recipient).call::<()>("deposit", args![payment]);
+        }
+    }
+}
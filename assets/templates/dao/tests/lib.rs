@@ -0,0 +1,133 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::ledger::*;
+use radix_engine::model::Receipt;
+use scrypto::prelude::*;
+
+fn vote(
+    test_runner: &mut TestRunner,
+    (pk, sk, account): (EcdsaPublicKey, &EcdsaPrivateKey, ComponentAddress),
+    dao: ComponentAddress,
+    member_badge: ResourceAddress,
+) -> Receipt {
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(member_badge, account)
+        .create_proof_from_auth_zone(member_badge, |builder, proof_id| {
+            builder.call_method(dao, "vote", args![Proof(proof_id), 0u32])
+        })
+        .build(test_runner.get_nonce([pk]))
+        .sign([sk]);
+    test_runner.validate_and_execute(&transaction)
+}
+
+fn execute(
+    test_runner: &mut TestRunner,
+    (pk, sk, _): (EcdsaPublicKey, &EcdsaPrivateKey, ComponentAddress),
+    dao: ComponentAddress,
+) -> Receipt {
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(dao, "execute", args![0u32])
+        .build(test_runner.get_nonce([pk]))
+        .sign([sk]);
+    test_runner.validate_and_execute(&transaction)
+}
+
+fn add_member(
+    test_runner: &mut TestRunner,
+    (pk, sk, account): (EcdsaPublicKey, &EcdsaPrivateKey, ComponentAddress),
+    dao: ComponentAddress,
+    admin_badge: ResourceAddress,
+    name: &str,
+) -> Receipt {
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(admin_badge, account)
+        .call_method(dao, "add_member", args![name.to_owned()])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([sk]);
+    test_runner.validate_and_execute(&transaction)
+}
+
+#[test]
+fn test_proposal_is_paid_after_quorum() {
+    // Set up environment.
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut ledger);
+    let (pk, sk, account) = test_runner.new_account();
+    let (_, _, recipient) = test_runner.new_account();
+
+    // Test the `instantiate_dao` function, with a treasury of 1000 XRD and a quorum of 2.
+    let transaction1 = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_amount(dec!("1000"), RADIX_TOKEN, account)
+        .take_from_worktop(RADIX_TOKEN, |builder, bucket| {
+            builder.call_function(
+                test_runner.package_address(),
+                "Dao",
+                "instantiate_dao",
+                args![Bucket(bucket), 2u32],
+            )
+        })
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt1 = test_runner.validate_and_execute(&transaction1);
+    assert!(receipt1.result.is_ok());
+    let dao = receipt1.new_component_addresses[0];
+    let admin_badge = receipt1.new_resource_addresses[1];
+    let member_badge = receipt1.new_resource_addresses[2];
+
+    // Test the `add_member` and `propose` methods.
+    let receipt2 = add_member(
+        &mut test_runner,
+        (pk, &sk, account),
+        dao,
+        admin_badge,
+        "Alice",
+    );
+    assert!(receipt2.result.is_ok());
+    let transaction3 = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(member_badge, account)
+        .create_proof_from_auth_zone(member_badge, |builder, proof_id| {
+            builder.call_method(
+                dao,
+                "propose",
+                args![
+                    Proof(proof_id),
+                    "Fund a community event".to_owned(),
+                    dec!("100"),
+                    recipient
+                ],
+            )
+        })
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt3 = test_runner.validate_and_execute(&transaction3);
+    assert!(receipt3.result.is_ok());
+
+    // With a single vote, the proposal can't be executed.
+    let receipt4 = vote(&mut test_runner, (pk, &sk, account), dao, member_badge);
+    assert_eq!(TestRunner::output::<u32>(&receipt4, 2), 1);
+    let receipt5 = execute(&mut test_runner, (pk, &sk, account), dao);
+    assert!(receipt5.result.is_err());
+
+    // With a second member voting, the proposal reaches the quorum.
+    let receipt6 = add_member(
+        &mut test_runner,
+        (pk, &sk, account),
+        dao,
+        admin_badge,
+        "Bob",
+    );
+    assert!(receipt6.result.is_ok());
+    let receipt7 = vote(&mut test_runner, (pk, &sk, account), dao, member_badge);
+    assert_eq!(TestRunner::output::<u32>(&receipt7, 2), 2);
+    let receipt8 = execute(&mut test_runner, (pk, &sk, account), dao);
+    assert!(receipt8.result.is_ok());
+}
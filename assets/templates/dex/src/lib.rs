@@ -0,0 +1,101 @@
+use scrypto::prelude::*;
+
+blueprint! {
+    struct Pool {
+        // The reserves of the two tokens
+        a_pool: Vault,
+        b_pool: Vault,
+        // The fraction of each swap kept by the pool, between 0 and 1
+        fee: Decimal,
+        // The badge which authorizes minting and burning liquidity provider tokens
+        lp_minter: Vault,
+        // The resource address of liquidity provider tokens
+        lp_resource: ResourceAddress,
+    }
+
+    impl Pool {
+        // Creates a constant product pool, and returns it with the initial liquidity provider tokens
+        pub fn instantiate_pool(
+            a_tokens: Bucket,
+            b_tokens: Bucket,
+            fee: Decimal,
+        ) -> (ComponentAddress, Bucket) {
+            assert!(
+                !a_tokens.is_empty() && !b_tokens.is_empty(),
+                "Both tokens must be provided"
+            );
+            assert!(
+                a_tokens.resource_address() != b_tokens.resource_address(),
+                "The tokens must be different"
+            );
+            assert!(
+                fee >= Decimal::zero() && fee < Decimal::one(),
+                "The fee must be between 0 and 1"
+            );
+
+            let lp_minter: Bucket = ResourceBuilder::new_fungible()
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata("name", "LP Minter Badge")
+                .initial_supply(1);
+            let lp_tokens: Bucket = ResourceBuilder::new_fungible()
+                .metadata("name", "LP Token")
+                .mintable(rule!(require(lp_minter.resource_address())), LOCKED)
+                .burnable(rule!(require(lp_minter.resource_address())), LOCKED)
+                .initial_supply(100);
+
+            let component = Self {
+                a_pool: Vault::with_bucket(a_tokens),
+                b_pool: Vault::with_bucket(b_tokens),
+                fee,
+                lp_resource: lp_tokens.resource_address(),
+                lp_minter: Vault::with_bucket(lp_minter),
+            }
+            .instantiate()
+            .globalize();
+
+            (component, lp_tokens)
+        }
+
+        // Swaps tokens of one kind for the other
+        pub fn swap(&mut self, input: Bucket) -> Bucket {
+            let fee = self.fee;
+            let (input_pool, output_pool) =
+                if input.resource_address() == self.a_pool.resource_address() {
+                    (&mut self.a_pool, &mut self.b_pool)
+                } else if input.resource_address() == self.b_pool.resource_address() {
+                    (&mut self.b_pool, &mut self.a_pool)
+                } else {
+                    panic!("Token not in pool");
+                };
+
+            // Keep the product of the reserves constant, excluding the fee
+            let input_amount = input.amount() * (Decimal::one() - fee);
+            let output_amount =
+                output_pool.amount() * input_amount / (input_pool.amount() + input_amount);
+
+            input_pool.put(input);
+            output_pool.take(output_amount)
+        }
+
+        // Returns a share of the reserves, in exchange for liquidity provider tokens
+        pub fn remove_liquidity(&mut self, lp_tokens: Bucket) -> (Bucket, Bucket) {
+            assert!(
+                lp_tokens.resource_address() == self.lp_resource,
+                "Wrong liquidity provider token"
+            );
+
+            let total_supply = borrow_resource_manager!(self.lp_resource).total_supply();
+            let share = lp_tokens.amount() / total_supply;
+            let a_tokens = self.a_pool.take(self.a_pool.amount() * share);
+            let b_tokens = self.b_pool.take(self.b_pool.amount() * share);
+            self.lp_minter.authorize(|| lp_tokens.burn());
+
+            (a_tokens, b_tokens)
+        }
+
+        // Returns the price of token A, in token B
+        pub fn price(&self) -> Decimal {
+            self.b_pool.amount() / self.a_pool.amount()
+        }
+    }
+}
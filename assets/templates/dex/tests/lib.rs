@@ -0,0 +1,78 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::ledger::*;
+use scrypto::prelude::*;
+
+#[test]
+fn test_swap_and_remove_liquidity() {
+    // Set up environment.
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut ledger);
+    let (pk, sk, account) = test_runner.new_account();
+
+    // Create two tokens.
+    let transaction1 = test_runner
+        .new_transaction_builder()
+        .new_token_fixed(HashMap::new(), dec!("1000"))
+        .new_token_fixed(HashMap::new(), dec!("1000"))
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt1 = test_runner.validate_and_execute(&transaction1);
+    assert!(receipt1.result.is_ok());
+    let token_a = receipt1.new_resource_addresses[0];
+    let token_b = receipt1.new_resource_addresses[1];
+
+    // Test the `instantiate_pool` function.
+    let transaction2 = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_amount(dec!("500"), token_a, account)
+        .withdraw_from_account_by_amount(dec!("500"), token_b, account)
+        .take_from_worktop(token_a, |builder, a_bucket| {
+            builder.take_from_worktop(token_b, |builder, b_bucket| {
+                builder.call_function(
+                    test_runner.package_address(),
+                    "Pool",
+                    "instantiate_pool",
+                    args![Bucket(a_bucket), Bucket(b_bucket), dec!("0.01")],
+                )
+            })
+        })
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt2 = test_runner.validate_and_execute(&transaction2);
+    assert!(receipt2.result.is_ok());
+    let pool = receipt2.new_component_addresses[0];
+    let lp_token = receipt2.new_resource_addresses[1];
+
+    // Test the `swap` method, which makes token A cheaper.
+    let transaction3 = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_amount(dec!("100"), token_a, account)
+        .take_from_worktop(token_a, |builder, bucket| {
+            builder.call_method(pool, "swap", args![Bucket(bucket)])
+        })
+        .call_method(pool, "price", args![])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt3 = test_runner.validate_and_execute(&transaction3);
+    assert!(receipt3.result.is_ok());
+    assert!(TestRunner::output::<Decimal>(&receipt3, 3) < dec!("1"));
+
+    // Test the `remove_liquidity` method.
+    let transaction4 = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account(lp_token, account)
+        .take_from_worktop(lp_token, |builder, bucket| {
+            builder.call_method(pool, "remove_liquidity", args![Bucket(bucket)])
+        })
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt4 = test_runner.validate_and_execute(&transaction4);
+    assert!(receipt4.result.is_ok());
+}
@@ -0,0 +1,87 @@
+use scrypto::prelude::*;
+
+#[derive(NonFungibleData)]
+pub struct Collectible {
+    // Immutable data, set when the non-fungible is minted
+    pub name: String,
+    // Mutable data, which the collection can update later
+    #[scrypto(mutable)]
+    pub level: u32,
+}
+
+blueprint! {
+    struct NftCollection {
+        // The badge which authorizes minting and updating non-fungibles
+        minter: Vault,
+        // The resource address of the collection
+        collection: ResourceAddress,
+        // The ID of the next non-fungible
+        next_id: u64,
+    }
+
+    impl NftCollection {
+        // Creates an empty collection, and returns the component with an admin badge
+        pub fn instantiate_collection(name: String) -> (ComponentAddress, Bucket) {
+            let minter: Bucket = ResourceBuilder::new_fungible()
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata("name", "Minter Badge")
+                .initial_supply(1);
+            let admin: Bucket = ResourceBuilder::new_fungible()
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata("name", "Admin Badge")
+                .initial_supply(1);
+            let collection = ResourceBuilder::new_non_fungible()
+                .metadata("name", name)
+                .mintable(rule!(require(minter.resource_address())), LOCKED)
+                .updateable_non_fungible_data(rule!(require(minter.resource_address())), LOCKED)
+                .no_initial_supply();
+
+            // Only the admin can mint and level up non-fungibles
+            let access_rules = AccessRules::new()
+                .method("mint", rule!(require(admin.resource_address())))
+                .method("level_up", rule!(require(admin.resource_address())))
+                .default(rule!(allow_all));
+            let component = Self {
+                minter: Vault::with_bucket(minter),
+                collection,
+                next_id: 0,
+            }
+            .instantiate()
+            .add_access_check(access_rules)
+            .globalize();
+
+            (component, admin)
+        }
+
+        // Mints a non-fungible with the given name
+        pub fn mint(&mut self, name: String) -> Bucket {
+            let id = NonFungibleId::from_u64(self.next_id);
+            self.next_id += 1;
+
+            let resource_manager = borrow_resource_manager!(self.collection);
+            self.minter.authorize(|| {
+                resource_manager.mint_non_fungible(&id, Collectible { name, level: 1 })
+            })
+        }
+
+        // Increases the level of a non-fungible
+        pub fn level_up(&mut self, id: NonFungibleId) {
+            let resource_manager = borrow_resource_manager!(self.collection);
+            let mut data: Collectible = resource_manager.get_non_fungible_data(&id);
+            data.level += 1;
+            self.minter
+                .authorize(|| resource_manager.update_non_fungible_data(&id, data));
+        }
+
+        // Returns the level of a non-fungible
+        pub fn level(&self, id: NonFungibleId) -> u32 {
+            let data: Collectible = borrow_resource_manager!(self.collection).get_non_fungible_data(&id);
+            data.level
+        }
+
+        // Returns the number of non-fungibles minted so far
+        pub fn total_minted(&self) -> u64 {
+            self.next_id
+        }
+    }
+}
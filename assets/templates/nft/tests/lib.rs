@@ -0,0 +1,48 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::ledger::*;
+use scrypto::prelude::*;
+
+#[test]
+fn test_mint_and_level_up() {
+    // Set up environment.
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut ledger);
+    let (pk, sk, account) = test_runner.new_account();
+
+    // Test the `instantiate_collection` function.
+    let transaction1 = test_runner
+        .new_transaction_builder()
+        .call_function(
+            test_runner.package_address(),
+            "NftCollection",
+            "instantiate_collection",
+            args!["My Collection".to_owned()],
+        )
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt1 = test_runner.validate_and_execute(&transaction1);
+    assert!(receipt1.result.is_ok());
+    let component = receipt1.new_component_addresses[0];
+    let admin_badge = receipt1.new_resource_addresses[1];
+
+    // Test the `mint` and `level_up` methods, which require the admin badge.
+    let transaction2 = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(admin_badge, account)
+        .call_method(component, "mint", args!["First".to_owned()])
+        .call_method(component, "mint", args!["Second".to_owned()])
+        .call_method(component, "level_up", args![NonFungibleId::from_u64(0)])
+        .call_method(component, "level", args![NonFungibleId::from_u64(0)])
+        .call_method(component, "total_minted", args![])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt2 = test_runner.validate_and_execute(&transaction2);
+    assert!(receipt2.result.is_ok());
+    assert_eq!(TestRunner::output::<u32>(&receipt2, 4), 2);
+    assert_eq!(TestRunner::output::<u64>(&receipt2, 5), 2);
+}
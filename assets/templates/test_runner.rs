@@ -0,0 +1,49 @@
+use radix_engine::ledger::*;
+use radix_engine::model::{Receipt, SignedTransaction};
+use radix_engine::transaction::*;
+use scrypto::prelude::*;
+
+/// Runs transactions against an in-memory ledger, with this package published.
+pub struct TestRunner<'l> {
+    executor: TransactionExecutor<'l, InMemorySubstateStore>,
+    package_address: PackageAddress,
+}
+
+impl<'l> TestRunner<'l> {
+    pub fn new(ledger: &'l mut InMemorySubstateStore) -> Self {
+        let mut executor = TransactionExecutor::new(ledger, false);
+        let package_address = executor.publish_package(compile_package!()).unwrap();
+
+        Self {
+            executor,
+            package_address,
+        }
+    }
+
+    pub fn package_address(&self) -> PackageAddress {
+        self.package_address
+    }
+
+    pub fn new_account(&mut self) -> (EcdsaPublicKey, EcdsaPrivateKey, ComponentAddress) {
+        self.executor.new_account()
+    }
+
+    pub fn new_transaction_builder(&self) -> TransactionBuilder {
+        TransactionBuilder::new()
+    }
+
+    pub fn get_nonce<PKS: AsRef<[EcdsaPublicKey]>>(&self, intended_signers: PKS) -> u64 {
+        self.executor.get_nonce(intended_signers)
+    }
+
+    pub fn validate_and_execute(&mut self, transaction: &SignedTransaction) -> Receipt {
+        let receipt = self.executor.validate_and_execute(transaction).unwrap();
+        println!("{:?}\n", receipt);
+        receipt
+    }
+
+    /// Returns the output of an instruction of a successful transaction.
+    pub fn output<T: Decode>(receipt: &Receipt, instruction_index: usize) -> T {
+        scrypto_decode(&receipt.outputs[instruction_index].raw).unwrap()
+    }
+}
@@ -0,0 +1,70 @@
+use scrypto::prelude::*;
+
+blueprint! {
+    struct BasicToken {
+        // The tokens held by the component
+        vault: Vault,
+        // The badge which authorizes minting more tokens
+        minter: Vault,
+    }
+
+    impl BasicToken {
+        // Creates a token with an initial supply, and returns the component with an admin badge
+        pub fn instantiate_token(
+            name: String,
+            symbol: String,
+            initial_supply: Decimal,
+        ) -> (ComponentAddress, Bucket) {
+            let minter: Bucket = ResourceBuilder::new_fungible()
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata("name", "Minter Badge")
+                .initial_supply(1);
+            let admin: Bucket = ResourceBuilder::new_fungible()
+                .divisibility(DIVISIBILITY_NONE)
+                .metadata("name", "Admin Badge")
+                .initial_supply(1);
+            let tokens: Bucket = ResourceBuilder::new_fungible()
+                .metadata("name", name)
+                .metadata("symbol", symbol)
+                .mintable(rule!(require(minter.resource_address())), LOCKED)
+                .initial_supply(initial_supply);
+
+            // Only the admin can mint and withdraw tokens
+            let access_rules = AccessRules::new()
+                .method("mint", rule!(require(admin.resource_address())))
+                .method("withdraw", rule!(require(admin.resource_address())))
+                .default(rule!(allow_all));
+            let component = Self {
+                vault: Vault::with_bucket(tokens),
+                minter: Vault::with_bucket(minter),
+            }
+            .instantiate()
+            .add_access_check(access_rules)
+            .globalize();
+
+            (component, admin)
+        }
+
+        // Mints more tokens into the vault
+        pub fn mint(&mut self, amount: Decimal) {
+            let resource_manager = borrow_resource_manager!(self.vault.resource_address());
+            let tokens = self.minter.authorize(|| resource_manager.mint(amount));
+            self.vault.put(tokens);
+        }
+
+        // Withdraws tokens from the vault
+        pub fn withdraw(&mut self, amount: Decimal) -> Bucket {
+            self.vault.take(amount)
+        }
+
+        // Returns the number of tokens held by the component
+        pub fn balance(&self) -> Decimal {
+            self.vault.amount()
+        }
+
+        // Returns the total supply of the token
+        pub fn total_supply(&self) -> Decimal {
+            borrow_resource_manager!(self.vault.resource_address()).total_supply()
+        }
+    }
+}
@@ -0,0 +1,77 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::ledger::*;
+use scrypto::prelude::*;
+
+#[test]
+fn test_mint_and_withdraw() {
+    // Set up environment.
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut ledger);
+    let (pk, sk, account) = test_runner.new_account();
+
+    // Test the `instantiate_token` function.
+    let transaction1 = test_runner
+        .new_transaction_builder()
+        .call_function(
+            test_runner.package_address(),
+            "BasicToken",
+            "instantiate_token",
+            args!["My Token".to_owned(), "MYT".to_owned(), dec!("1000")],
+        )
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt1 = test_runner.validate_and_execute(&transaction1);
+    assert!(receipt1.result.is_ok());
+    let component = receipt1.new_component_addresses[0];
+    let admin_badge = receipt1.new_resource_addresses[1];
+
+    // Test the `mint` and `withdraw` methods, which require the admin badge.
+    let transaction2 = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(admin_badge, account)
+        .call_method(component, "mint", args![dec!("500")])
+        .call_method(component, "withdraw", args![dec!("100")])
+        .call_method(component, "balance", args![])
+        .call_method(component, "total_supply", args![])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt2 = test_runner.validate_and_execute(&transaction2);
+    assert!(receipt2.result.is_ok());
+    assert_eq!(TestRunner::output::<Decimal>(&receipt2, 3), dec!("1400"));
+    assert_eq!(TestRunner::output::<Decimal>(&receipt2, 4), dec!("1500"));
+}
+
+#[test]
+fn test_mint_requires_admin_badge() {
+    // Set up environment.
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut ledger);
+    let (pk, sk, account) = test_runner.new_account();
+    let transaction1 = test_runner
+        .new_transaction_builder()
+        .call_function(
+            test_runner.package_address(),
+            "BasicToken",
+            "instantiate_token",
+            args!["My Token".to_owned(), "MYT".to_owned(), dec!("1000")],
+        )
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt1 = test_runner.validate_and_execute(&transaction1);
+    let component = receipt1.new_component_addresses[0];
+
+    // Minting without the admin badge fails.
+    let transaction2 = test_runner
+        .new_transaction_builder()
+        .call_method(component, "mint", args![dec!("500")])
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt2 = test_runner.validate_and_execute(&transaction2);
+    assert!(receipt2.result.is_err());
+}
@@ -15,6 +15,7 @@ cd "$(dirname "$0")"
 (cd radix-engine; cargo fmt)
 (cd simulator; cargo fmt)
 (cd transaction-manifest; cargo fmt)
+(cd fuzz; cargo fmt)
 
 (cd assets/account; scrypto fmt)
 (cd assets/system; scrypto fmt)
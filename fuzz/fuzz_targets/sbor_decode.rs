@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sbor::*;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(value) = decode_any(data) {
+        // Anything which decodes must survive a round trip
+        let mut bytes = Vec::new();
+        encode_any(None, &value, &mut Encoder::with_type(&mut bytes));
+        assert_eq!(decode_any(&bytes), Ok(value));
+    }
+    let _ = decode_with_type::<Vec<String>>(data);
+    let _ = decode_with_type::<(u32, Option<i64>, Vec<u8>)>(data);
+});
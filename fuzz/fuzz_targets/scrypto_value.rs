@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use scrypto::values::ScryptoValue;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = ScryptoValue::from_slice(data);
+});
@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use radix_engine::model::{SignedTransaction, Transaction};
+use scrypto::buffer::scrypto_decode;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(transaction) = scrypto_decode::<Transaction>(data) {
+        let _ = SignedTransaction {
+            transaction,
+            signatures: Vec::new(),
+        }
+        .validate();
+    }
+});
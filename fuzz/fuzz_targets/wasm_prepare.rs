@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use radix_engine::model::Package;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Package::new(data.to_vec());
+});
@@ -1,27 +1,146 @@
-#[macro_use]
-extern crate bencher;
-use bencher::Bencher;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
 
 use radix_engine::ledger::*;
+use radix_engine::model::SignedTransaction;
 use radix_engine::transaction::*;
 use scrypto::prelude::*;
 
-fn bench_transfer(b: &mut Bencher) {
+/// Benchmarks the execution of a transaction.
+///
+/// Each iteration executes a transaction built with a fresh nonce, so that no iteration replays
+/// the transaction, and the new entity addresses, of another.
+fn bench_transaction<F>(
+    c: &mut Criterion,
+    name: &str,
+    executor: &mut TransactionExecutor<'_, InMemorySubstateStore>,
+    build: F,
+) where
+    F: Fn(u64) -> SignedTransaction,
+{
+    let mut nonce = executor.get_nonce([]);
+    c.bench_function(name, |b| {
+        b.iter_batched(
+            || {
+                nonce += 1;
+                build(nonce)
+            },
+            |transaction| {
+                let receipt = executor.validate_and_execute(&transaction).unwrap();
+                assert!(receipt.result.is_ok());
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn bench_transfer(c: &mut Criterion) {
     let mut ledger = InMemorySubstateStore::with_bootstrap();
     let mut executor = TransactionExecutor::new(&mut ledger, false);
-    let (pk, sk, account1) = executor.new_account();
+    let (_, sk, account1) = executor.new_account();
     let (_, _, account2) = executor.new_account();
+
+    bench_transaction(c, "transfer", &mut executor, |nonce| {
+        TransactionBuilder::new()
+            .withdraw_from_account_by_amount(1.into(), RADIX_TOKEN, account1)
+            .call_method_with_all_resources(account2, "deposit_batch")
+            .build(nonce)
+            .sign([&sk])
+    });
+}
+
+fn bench_publish_and_instantiate(c: &mut Criterion) {
+    let code = compile_package!(format!("./tests/{}", "core"));
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, false);
+
+    c.bench_function("publish_package", |b| {
+        b.iter(|| executor.publish_package(&code).unwrap())
+    });
+
+    let package = executor.publish_package(&code).unwrap();
+    bench_transaction(c, "instantiate_component", &mut executor, |nonce| {
+        TransactionBuilder::new()
+            .call_function(package, "BenchTest", "new", args![])
+            .build(nonce)
+            .sign([])
+    });
+}
+
+fn bench_mint_non_fungibles(c: &mut Criterion) {
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, false);
+    let (_, _, account) = executor.new_account();
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
+
+    bench_transaction(c, "mint_non_fungibles_100", &mut executor, |nonce| {
+        TransactionBuilder::new()
+            .call_function(package, "BenchTest", "mint_non_fungibles", args![100u32])
+            .call_method_with_all_resources(account, "deposit_batch")
+            .build(nonce)
+            .sign([])
+    });
+}
+
+fn bench_deep_call(c: &mut Criterion) {
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, false);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
     let transaction = TransactionBuilder::new()
-        .withdraw_from_account_by_amount(1.into(), RADIX_TOKEN, account1)
-        .call_method_with_all_resources(account2, "deposit_batch")
-        .build(executor.get_nonce([pk]))
-        .sign([&sk]);
+        .call_function(package, "BenchTest", "new_chain", args![8u32])
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+    let component: ComponentAddress = scrypto_decode(&receipt.outputs[0].raw).unwrap();
+
+    bench_transaction(c, "call_chain_8", &mut executor, |nonce| {
+        TransactionBuilder::new()
+            .call_method(component, "call_chain", args![])
+            .build(nonce)
+            .sign([])
+    });
+}
+
+fn bench_engine_calls(c: &mut Criterion) {
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, false);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
+
+    bench_transaction(c, "engine_calls_1000", &mut executor, |nonce| {
+        TransactionBuilder::new()
+            .call_function(package, "CoreTest", "query_repeatedly", args![1000u32])
+            .build(nonce)
+            .sign([])
+    });
+}
+
+fn bench_large_call_data(c: &mut Criterion) {
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, false);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
 
-    b.iter(|| {
-        let receipt = executor.validate_and_execute(&transaction).unwrap();
-        assert!(receipt.result.is_ok());
+    bench_transaction(c, "large_call_data_64k", &mut executor, |nonce| {
+        TransactionBuilder::new()
+            .call_function(package, "CoreTest", "echo", args![vec![7u8; 64 * 1024]])
+            .build(nonce)
+            .sign([])
     });
 }
 
-benchmark_group!(radix_engine, bench_transfer);
-benchmark_main!(radix_engine);
+criterion_group!(
+    radix_engine,
+    bench_transfer,
+    bench_publish_and_instantiate,
+    bench_mint_non_fungibles,
+    bench_deep_call,
+    bench_engine_calls,
+    bench_large_call_data
+);
+criterion_main!(radix_engine);
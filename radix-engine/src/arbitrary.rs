@@ -0,0 +1,184 @@
+use proptest::collection::{btree_set, vec};
+use proptest::prelude::*;
+use scrypto::crypto::Hash;
+use scrypto::engine::types::*;
+use scrypto::resource::AccessRule;
+use scrypto::rust::string::String;
+use scrypto::rust::vec::Vec;
+use scrypto::values::ScryptoValue;
+
+use crate::model::{Instruction, ResourceContainer};
+
+/// A strategy for amounts which are valid for the given divisibility, up to a billion.
+pub fn amount(divisibility: u8) -> impl Strategy<Value = Decimal> {
+    let unit = 10i128.pow((18 - divisibility).into());
+    let max_units = 1_000_000_000i128 * 10i128.pow(divisibility.into());
+    (0..=max_units).prop_map(move |units| Decimal(units * unit))
+}
+
+impl Arbitrary for ResourceContainer {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<ResourceContainer>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            (any::<ResourceAddress>(), 0u8..=18).prop_flat_map(
+                |(resource_address, divisibility)| {
+                    amount(divisibility).prop_map(move |amount| {
+                        ResourceContainer::new_fungible(resource_address, divisibility, amount)
+                    })
+                }
+            ),
+            (
+                any::<ResourceAddress>(),
+                btree_set(any::<NonFungibleId>(), 0..8)
+            )
+                .prop_map(|(resource_address, ids)| {
+                    ResourceContainer::new_non_fungible(resource_address, ids)
+                }),
+        ]
+        .boxed()
+    }
+}
+
+fn name() -> impl Strategy<Value = String> {
+    "[a-z_]{1,16}"
+}
+
+fn args() -> impl Strategy<Value = Vec<Vec<u8>>> {
+    vec(any::<ScryptoValue>().prop_map(|value| value.raw), 0..4)
+}
+
+impl Arbitrary for Instruction {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Instruction>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            any::<ResourceAddress>()
+                .prop_map(|resource_address| Instruction::TakeFromWorktop { resource_address }),
+            (any::<Decimal>(), any::<ResourceAddress>()).prop_map(|(amount, resource_address)| {
+                Instruction::TakeFromWorktopByAmount {
+                    amount,
+                    resource_address,
+                }
+            }),
+            (
+                btree_set(any::<NonFungibleId>(), 0..4),
+                any::<ResourceAddress>()
+            )
+                .prop_map(|(ids, resource_address)| {
+                    Instruction::TakeFromWorktopByIds {
+                        ids,
+                        resource_address,
+                    }
+                }),
+            any::<BucketId>().prop_map(|bucket_id| Instruction::ReturnToWorktop { bucket_id }),
+            any::<ResourceAddress>().prop_map(|resource_address| {
+                Instruction::AssertWorktopContains { resource_address }
+            }),
+            (any::<Decimal>(), any::<ResourceAddress>()).prop_map(|(amount, resource_address)| {
+                Instruction::AssertWorktopContainsByAmount {
+                    amount,
+                    resource_address,
+                }
+            }),
+            (
+                btree_set(any::<NonFungibleId>(), 0..4),
+                any::<ResourceAddress>()
+            )
+                .prop_map(|(ids, resource_address)| {
+                    Instruction::AssertWorktopContainsByIds {
+                        ids,
+                        resource_address,
+                    }
+                }),
+            Just(Instruction::PopFromAuthZone),
+            any::<ProofId>().prop_map(|proof_id| Instruction::PushToAuthZone { proof_id }),
+            Just(Instruction::ClearAuthZone),
+            any::<ResourceAddress>().prop_map(|resource_address| {
+                Instruction::CreateProofFromAuthZone { resource_address }
+            }),
+            (any::<Decimal>(), any::<ResourceAddress>()).prop_map(|(amount, resource_address)| {
+                Instruction::CreateProofFromAuthZoneByAmount {
+                    amount,
+                    resource_address,
+                }
+            }),
+            (
+                btree_set(any::<NonFungibleId>(), 0..4),
+                any::<ResourceAddress>()
+            )
+                .prop_map(|(ids, resource_address)| {
+                    Instruction::CreateProofFromAuthZoneByIds {
+                        ids,
+                        resource_address,
+                    }
+                }),
+            any::<BucketId>()
+                .prop_map(|bucket_id| Instruction::CreateProofFromBucket { bucket_id }),
+            any::<ProofId>().prop_map(|proof_id| Instruction::CloneProof { proof_id }),
+            any::<ProofId>().prop_map(|proof_id| Instruction::DropProof { proof_id }),
+            Just(Instruction::DropAllProofs),
+            (any::<PackageAddress>(), name(), name(), args()).prop_map(
+                |(package_address, blueprint_name, function, args)| Instruction::CallFunction {
+                    package_address,
+                    blueprint_name,
+                    function,
+                    args,
+                }
+            ),
+            (any::<ComponentAddress>(), name(), args()).prop_map(
+                |(component_address, method, args)| Instruction::CallMethod {
+                    component_address,
+                    method,
+                    args,
+                }
+            ),
+            (any::<ComponentAddress>(), name()).prop_map(|(component_address, method)| {
+                Instruction::CallMethodWithAllResources {
+                    component_address,
+                    method,
+                    args: Vec::new(),
+                }
+            }),
+            vec(any::<u8>(), 0..64).prop_map(|code| Instruction::PublishPackage { code }),
+            (vec(any::<u8>(), 0..64), any::<ResourceAddress>()).prop_map(|(code, owner_badge)| {
+                Instruction::PublishPackageWithOwner { code, owner_badge }
+            }),
+            any::<u64>().prop_map(|nonce| Instruction::Nonce { nonce }),
+            (any::<ResourceAddress>(), name(), args()).prop_map(
+                |(resource_address, method, args)| Instruction::CallResourceMethod {
+                    resource_address,
+                    method,
+                    args,
+                }
+            ),
+            (
+                any::<ComponentAddress>(),
+                any::<BucketId>(),
+                any::<AccessRule>()
+            )
+                .prop_map(|(component_address, bucket_id, claim_rule)| {
+                    Instruction::CreateLocker {
+                        component_address,
+                        bucket_id,
+                        claim_rule,
+                    }
+                }),
+            (any::<ComponentAddress>(), any::<[u8; 32]>(), any::<u32>()).prop_map(
+                |(component_address, hash, index)| Instruction::ClaimLocker {
+                    component_address,
+                    locker_id: (Hash(hash), index),
+                }
+            ),
+            (any::<AccessRule>(), any::<Option<BucketId>>()).prop_map(
+                |(withdraw_auth, bucket_id)| Instruction::CreateAccount {
+                    withdraw_auth,
+                    bucket_id,
+                }
+            ),
+        ]
+        .boxed()
+    }
+}
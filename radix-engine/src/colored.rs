@@ -0,0 +1,37 @@
+use scrypto::rust::string::String;
+use scrypto::rust::string::ToString;
+
+/// A stand-in for the `colored` crate without `std`, which leaves text uncolored.
+pub trait Colorize: ToString {
+    fn bold(&self) -> String {
+        self.to_string()
+    }
+
+    fn normal(&self) -> String {
+        self.to_string()
+    }
+
+    fn red(&self) -> String {
+        self.to_string()
+    }
+
+    fn green(&self) -> String {
+        self.to_string()
+    }
+
+    fn yellow(&self) -> String {
+        self.to_string()
+    }
+
+    fn blue(&self) -> String {
+        self.to_string()
+    }
+
+    fn cyan(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Colorize for str {}
+
+impl Colorize for String {}
@@ -0,0 +1,516 @@
+use scrypto::engine::types::*;
+use scrypto::rust::collections::*;
+use scrypto::rust::mem;
+use scrypto::rust::vec::Vec;
+
+use crate::engine::*;
+use crate::errors::*;
+use crate::model::*;
+
+/// How buckets and proofs leave a call frame.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MoveMethod {
+    AsReturn,
+    AsArgument,
+}
+
+/// The nodes owned and referenced by a single invocation.
+///
+/// A call frame owns buckets and proofs, unclaimed vaults and lazy maps, and optionally a
+/// worktop and an auth zone. Nodes only leave a frame through `move_nodes`, which applies the
+/// rules of `RENodeId` and `RENode`, or by being stored through `take_owned_objects`.
+pub struct CallFrame {
+    /// Owned buckets and proofs, and vaults and lazy maps which haven't been stored yet
+    nodes: BTreeMap<RENodeId, RENode>,
+    /// The vault borrowed by a callee, and the unclaimed lazy map storing it, if any
+    borrowed_vault: Option<(VaultId, Option<LazyMapId>)>,
+    /// Vaults and lazy maps of a component, which are visible to this frame
+    refs: ComponentObjectRefs,
+    worktop: Option<Worktop>,
+    auth_zone: Option<AuthZone>,
+    /// Allocator of bucket and proof IDs, scoped to this frame
+    id_allocator: IdAllocator,
+}
+
+impl CallFrame {
+    /// Creates a call frame, which owns the given nodes.
+    pub fn new(
+        call_index: u32,
+        auth_zone: Option<AuthZone>,
+        worktop: Option<Worktop>,
+        nodes: BTreeMap<RENodeId, RENode>,
+    ) -> Self {
+        Self {
+            nodes,
+            borrowed_vault: None,
+            refs: ComponentObjectRefs::new(),
+            worktop,
+            auth_zone,
+            id_allocator: IdAllocator::new(IdSpace::Call(call_index)),
+        }
+    }
+
+    pub fn nodes(&self) -> &BTreeMap<RENodeId, RENode> {
+        &self.nodes
+    }
+
+    fn take_node(&mut self, node_id: RENodeId) -> Result<RENode, RuntimeError> {
+        self.nodes
+            .remove(&node_id)
+            .ok_or_else(|| node_id.not_found())
+    }
+
+    pub fn create_bucket(
+        &mut self,
+        container: ResourceContainer,
+    ) -> Result<BucketId, RuntimeError> {
+        let bucket_id = self
+            .id_allocator
+            .new_bucket_id()
+            .map_err(RuntimeError::IdAllocatorError)?;
+        self.nodes.insert(
+            RENodeId::Bucket(bucket_id),
+            RENode::Bucket(Bucket::new(container)),
+        );
+        Ok(bucket_id)
+    }
+
+    pub fn take_bucket(&mut self, bucket_id: BucketId) -> Result<Bucket, RuntimeError> {
+        match self.take_node(RENodeId::Bucket(bucket_id))? {
+            RENode::Bucket(bucket) => Ok(bucket),
+            _ => panic!("Node should be a bucket"),
+        }
+    }
+
+    pub fn get_bucket(&self, bucket_id: BucketId) -> Result<&Bucket, RuntimeError> {
+        match self.nodes.get(&RENodeId::Bucket(bucket_id)) {
+            Some(RENode::Bucket(bucket)) => Ok(bucket),
+            _ => Err(RuntimeError::BucketNotFound(bucket_id)),
+        }
+    }
+
+    /// Returns a bucket which has been taken to be borrowed by a callee.
+    pub fn return_bucket(&mut self, bucket_id: BucketId, bucket: Bucket) {
+        self.nodes
+            .insert(RENodeId::Bucket(bucket_id), RENode::Bucket(bucket));
+    }
+
+    pub fn create_proof(&mut self, proof: Proof) -> Result<ProofId, RuntimeError> {
+        let proof_id = self
+            .id_allocator
+            .new_proof_id()
+            .map_err(RuntimeError::IdAllocatorError)?;
+        self.nodes
+            .insert(RENodeId::Proof(proof_id), RENode::Proof(proof));
+        Ok(proof_id)
+    }
+
+    pub fn take_proof(&mut self, proof_id: ProofId) -> Result<Proof, RuntimeError> {
+        match self.take_node(RENodeId::Proof(proof_id))? {
+            RENode::Proof(proof) => Ok(proof),
+            _ => panic!("Node should be a proof"),
+        }
+    }
+
+    /// Returns a proof which has been taken to be borrowed by a callee.
+    pub fn return_proof(&mut self, proof_id: ProofId, proof: Proof) {
+        self.nodes
+            .insert(RENodeId::Proof(proof_id), RENode::Proof(proof));
+    }
+
+    pub fn get_proof(&self, proof_id: ProofId) -> Result<&Proof, RuntimeError> {
+        match self.nodes.get(&RENodeId::Proof(proof_id)) {
+            Some(RENode::Proof(proof)) => Ok(proof),
+            _ => Err(RuntimeError::ProofNotFound(proof_id)),
+        }
+    }
+
+    /// Moves nodes out of this frame, either as arguments or as return values.
+    ///
+    /// All nodes are checked before any is taken, so that the frame is left untouched when one of
+    /// them can't move.
+    pub fn move_nodes(
+        &mut self,
+        node_ids: &[RENodeId],
+        method: MoveMethod,
+    ) -> Result<BTreeMap<RENodeId, RENode>, RuntimeError> {
+        for node_id in node_ids {
+            node_id.verify_can_move()?;
+            self.nodes
+                .get(node_id)
+                .ok_or_else(|| node_id.not_found())?
+                .verify_can_move(*node_id)?;
+        }
+
+        let mut nodes = BTreeMap::new();
+        for node_id in node_ids {
+            let mut node = self.take_node(*node_id)?;
+            node.prepare_move(method);
+            nodes.insert(*node_id, node);
+        }
+        Ok(nodes)
+    }
+
+    /// Receives the nodes moved out of another frame.
+    pub fn receive(&mut self, nodes: BTreeMap<RENodeId, RENode>) {
+        self.nodes.extend(nodes);
+    }
+
+    /// Drops all proofs owned by this frame.
+    pub fn drop_proofs(&mut self) {
+        let (proofs, nodes): (BTreeMap<_, _>, BTreeMap<_, _>) = mem::take(&mut self.nodes)
+            .into_iter()
+            .partition(|(node_id, _)| matches!(node_id, RENodeId::Proof(_)));
+        self.nodes = nodes;
+        for (_, node) in proofs {
+            if let RENode::Proof(proof) = node {
+                proof.drop();
+            }
+        }
+    }
+
+    pub fn create_vault(&mut self, vault_id: VaultId, vault: Vault) {
+        self.nodes
+            .insert(RENodeId::Vault(vault_id), RENode::Vault(vault));
+    }
+
+    pub fn create_lazy_map(&mut self, lazy_map_id: LazyMapId) {
+        self.nodes.insert(
+            RENodeId::LazyMap(lazy_map_id),
+            RENode::LazyMap(UnclaimedLazyMap::new()),
+        );
+    }
+
+    /// Takes the owned vaults and lazy maps which are about to be stored.
+    ///
+    /// Fails without taking any of them if one isn't owned by this frame.
+    pub fn take_owned_objects(
+        &mut self,
+        refs: ComponentObjectRefs,
+    ) -> Result<ComponentObjects, RuntimeError> {
+        if self.borrowed_vault.is_some() {
+            panic!("Should not be taking while value is being borrowed");
+        }
+
+        let node_ids: Vec<RENodeId> = refs
+            .vault_ids
+            .iter()
+            .map(|vault_id| RENodeId::Vault(*vault_id))
+            .chain(
+                refs.lazy_map_ids
+                    .iter()
+                    .map(|lazy_map_id| RENodeId::LazyMap(*lazy_map_id)),
+            )
+            .collect();
+        for node_id in &node_ids {
+            if !self.nodes.contains_key(node_id) {
+                return Err(node_id.not_found());
+            }
+        }
+
+        let mut objects = ComponentObjects::new();
+        for node_id in node_ids {
+            match (node_id, self.take_node(node_id)?) {
+                (RENodeId::Vault(vault_id), RENode::Vault(vault)) => {
+                    objects.vaults.insert(vault_id, vault);
+                }
+                (RENodeId::LazyMap(lazy_map_id), RENode::LazyMap(lazy_map)) => {
+                    objects.lazy_maps.insert(lazy_map_id, lazy_map);
+                }
+                _ => panic!("Node should be a vault or a lazy map"),
+            }
+        }
+        Ok(objects)
+    }
+
+    /// Stores vaults and lazy maps in an unclaimed lazy map, given its root.
+    pub fn insert_objects_into_map(&mut self, new_objects: ComponentObjects, root: &LazyMapId) {
+        if self.borrowed_vault.is_some() {
+            panic!("Should not be taking while value is being borrowed");
+        }
+
+        match self.nodes.get_mut(&RENodeId::LazyMap(*root)) {
+            Some(RENode::LazyMap(unclaimed)) => unclaimed.insert_descendents(new_objects),
+            _ => panic!("Root lazy map should be owned by the frame"),
+        }
+    }
+
+    pub fn insert_lazy_map_entry(&mut self, lazy_map_id: &LazyMapId, key: Vec<u8>, value: Vec<u8>) {
+        let (_, lazy_map) = self.get_lazy_map_mut(lazy_map_id).unwrap();
+        lazy_map.insert(key, value);
+    }
+
+    /// Returns an entry of an owned lazy map, along with the root of the lazy map, if owned.
+    pub fn get_lazy_map_entry(
+        &mut self,
+        lazy_map_id: &LazyMapId,
+        key: &[u8],
+    ) -> Option<(LazyMapId, Option<Vec<u8>>)> {
+        self.get_lazy_map_mut(lazy_map_id)
+            .map(|(lazy_map_id, lazy_map)| (lazy_map_id, lazy_map.get(key).map(|v| v.to_vec())))
+    }
+
+    fn get_lazy_map_mut(
+        &mut self,
+        lazy_map_id: &LazyMapId,
+    ) -> Option<(LazyMapId, &mut BTreeMap<Vec<u8>, Vec<u8>>)> {
+        if self.borrowed_vault.is_some() {
+            panic!("Should not be taking while value is being borrowed");
+        }
+
+        // TODO: Optimize to prevent iteration
+        for (node_id, node) in self.nodes.iter_mut() {
+            if let (RENodeId::LazyMap(root), RENode::LazyMap(unclaimed)) = (node_id, node) {
+                if lazy_map_id.eq(root) {
+                    return Some((*root, &mut unclaimed.lazy_map));
+                }
+                if let Some(lazy_map) = unclaimed.descendent_lazy_maps.get_mut(lazy_map_id) {
+                    return Some((*root, lazy_map));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Takes an owned vault, which may be stored in an unclaimed lazy map, to be borrowed by a
+    /// callee.
+    pub fn borrow_vault_mut(&mut self, vault_id: &VaultId) -> Option<Vault> {
+        if self.borrowed_vault.is_some() {
+            panic!("Should not be able to borrow multiple times");
+        }
+
+        if let Some(RENode::Vault(vault)) = self.nodes.remove(&RENodeId::Vault(*vault_id)) {
+            self.borrowed_vault = Some((*vault_id, None));
+            return Some(vault);
+        }
+
+        for (node_id, node) in self.nodes.iter_mut() {
+            if let (RENodeId::LazyMap(lazy_map_id), RENode::LazyMap(unclaimed)) = (node_id, node) {
+                if let Some(vault) = unclaimed.descendent_vaults.remove(vault_id) {
+                    self.borrowed_vault = Some((*vault_id, Some(*lazy_map_id)));
+                    return Some(vault);
+                }
+            }
+        }
+
+        None
+    }
+
+    pub fn return_borrowed_vault_mut(&mut self, vault: Vault) {
+        match self.borrowed_vault.take() {
+            Some((vault_id, Some(ancestor_id))) => {
+                match self.nodes.get_mut(&RENodeId::LazyMap(ancestor_id)) {
+                    Some(RENode::LazyMap(unclaimed)) => {
+                        unclaimed.descendent_vaults.insert(vault_id, vault);
+                    }
+                    _ => panic!("Ancestor lazy map should be owned by the frame"),
+                }
+            }
+            Some((vault_id, None)) => {
+                self.nodes
+                    .insert(RENodeId::Vault(vault_id), RENode::Vault(vault));
+            }
+            None => panic!("Should never get here"),
+        }
+    }
+
+    pub fn add_refs(&mut self, refs: ComponentObjectRefs) {
+        self.refs.extend(refs);
+    }
+
+    pub fn add_vault_ref(&mut self, vault_id: VaultId) {
+        self.refs.vault_ids.insert(vault_id);
+    }
+
+    pub fn has_vault_ref(&self, vault_id: &VaultId) -> bool {
+        self.refs.vault_ids.contains(vault_id)
+    }
+
+    pub fn has_lazy_map_ref(&self, lazy_map_id: &LazyMapId) -> bool {
+        self.refs.lazy_map_ids.contains(lazy_map_id)
+    }
+
+    pub fn worktop(&self) -> Option<&Worktop> {
+        self.worktop.as_ref()
+    }
+
+    /// Takes the worktop, to be borrowed by a callee.
+    pub fn take_worktop(&mut self) -> Option<Worktop> {
+        self.worktop.take()
+    }
+
+    pub fn return_worktop(&mut self, worktop: Worktop) {
+        self.worktop = Some(worktop);
+    }
+
+    pub fn auth_zone(&self) -> Option<&AuthZone> {
+        self.auth_zone.as_ref()
+    }
+
+    /// Takes the auth zone, to be borrowed by a callee.
+    pub fn take_auth_zone(&mut self) -> Option<AuthZone> {
+        self.auth_zone.take()
+    }
+
+    pub fn return_auth_zone(&mut self, auth_zone: AuthZone) {
+        self.auth_zone = Some(auth_zone);
+    }
+
+    /// Checks that no resource is left behind when this frame ends.
+    ///
+    /// Proofs are expected to have been dropped already.
+    pub fn check_resource(&self) -> Result<(), ResourceFailure> {
+        let mut result = Ok(());
+        // Unclaimed lazy maps come first, followed by vaults and buckets
+        if let Some(failure) = self
+            .nodes
+            .values()
+            .filter_map(RENode::resource_failure)
+            .next()
+        {
+            result = Err(failure);
+        }
+        if let Some(worktop) = &self.worktop {
+            if !worktop.is_empty() {
+                result = Err(ResourceFailure::Resources(worktop.resource_addresses()));
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scrypto::constants::*;
+    use scrypto::math::Decimal;
+
+    fn new_frame() -> CallFrame {
+        CallFrame::new(1, None, None, BTreeMap::new())
+    }
+
+    fn xrd(amount: u32) -> ResourceContainer {
+        ResourceContainer::new_fungible(RADIX_TOKEN, 18, Decimal::from(amount))
+    }
+
+    #[test]
+    fn test_move_bucket() {
+        let mut frame = new_frame();
+        let bucket_id = frame.create_bucket(xrd(10)).unwrap();
+
+        let moved = frame
+            .move_nodes(&[RENodeId::Bucket(bucket_id)], MoveMethod::AsArgument)
+            .unwrap();
+
+        assert!(moved.contains_key(&RENodeId::Bucket(bucket_id)));
+        assert_eq!(frame.check_resource(), Ok(()));
+        let mut other = new_frame();
+        other.receive(moved);
+        assert_eq!(
+            other.check_resource(),
+            Err(ResourceFailure::Resource(RADIX_TOKEN))
+        );
+    }
+
+    #[test]
+    fn test_cant_move_locked_bucket() {
+        let mut frame = new_frame();
+        let bucket_id = frame.create_bucket(xrd(10)).unwrap();
+        let mut bucket = frame.take_bucket(bucket_id).unwrap();
+        let proof = bucket.create_proof(bucket_id).unwrap();
+        frame.return_bucket(bucket_id, bucket);
+        frame.create_proof(proof).unwrap();
+
+        let result = frame.move_nodes(&[RENodeId::Bucket(bucket_id)], MoveMethod::AsReturn);
+
+        assert_eq!(result.err(), Some(RuntimeError::CantMoveLockedBucket));
+    }
+
+    #[test]
+    fn test_proof_moved_as_argument_is_restricted() {
+        let mut frame = new_frame();
+        let bucket_id = frame.create_bucket(xrd(10)).unwrap();
+        let mut bucket = frame.take_bucket(bucket_id).unwrap();
+        let proof_id = frame
+            .create_proof(bucket.create_proof(bucket_id).unwrap())
+            .unwrap();
+        let proof_ids = [RENodeId::Proof(proof_id)];
+
+        let moved = frame
+            .move_nodes(&proof_ids, MoveMethod::AsArgument)
+            .unwrap();
+        let mut callee = new_frame();
+        callee.receive(moved);
+        let result = callee.move_nodes(&proof_ids, MoveMethod::AsReturn);
+
+        assert_eq!(
+            result.err(),
+            Some(RuntimeError::CantMoveRestrictedProof(proof_id))
+        );
+    }
+
+    #[test]
+    fn test_cant_move_vault() {
+        let mut frame = new_frame();
+        let vault_id = (Hash([0u8; 32]), 0);
+
+        let result = frame.move_nodes(&[RENodeId::Vault(vault_id)], MoveMethod::AsArgument);
+
+        assert_eq!(result.err(), Some(RuntimeError::VaultNotAllowed));
+    }
+
+    #[test]
+    fn test_move_missing_bucket() {
+        let mut frame = new_frame();
+
+        let result = frame.move_nodes(&[RENodeId::Bucket(5)], MoveMethod::AsReturn);
+
+        assert_eq!(result.err(), Some(RuntimeError::BucketNotFound(5)));
+    }
+
+    #[test]
+    fn test_failed_move_keeps_all_nodes() {
+        let mut frame = new_frame();
+        let bucket_id = frame.create_bucket(xrd(10)).unwrap();
+        let locked_bucket_id = frame.create_bucket(xrd(5)).unwrap();
+        let mut locked_bucket = frame.take_bucket(locked_bucket_id).unwrap();
+        let proof = locked_bucket.create_proof(locked_bucket_id).unwrap();
+        frame.return_bucket(locked_bucket_id, locked_bucket);
+
+        let result = frame.move_nodes(
+            &[
+                RENodeId::Bucket(bucket_id),
+                RENodeId::Bucket(locked_bucket_id),
+            ],
+            MoveMethod::AsArgument,
+        );
+
+        assert_eq!(result.err(), Some(RuntimeError::CantMoveLockedBucket));
+        assert!(frame.get_bucket(bucket_id).is_ok());
+        assert!(frame.get_bucket(locked_bucket_id).is_ok());
+        proof.drop();
+    }
+
+    #[test]
+    fn test_failed_store_keeps_all_objects() {
+        let mut frame = new_frame();
+        let vault_id = (Hash([0u8; 32]), 0);
+        frame.create_vault(vault_id, Vault::new(xrd(10)));
+        let lazy_map_id = (Hash([0u8; 32]), 1);
+        let mut refs = ComponentObjectRefs::new();
+        refs.vault_ids.insert(vault_id);
+        refs.lazy_map_ids.insert(lazy_map_id);
+
+        let result = frame.take_owned_objects(refs);
+
+        assert_eq!(
+            result.err(),
+            Some(RuntimeError::LazyMapNotFound(lazy_map_id))
+        );
+        assert_eq!(
+            frame.check_resource(),
+            Err(ResourceFailure::Resource(RADIX_TOKEN))
+        );
+    }
+}
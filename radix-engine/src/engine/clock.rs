@@ -0,0 +1,48 @@
+/// A monotonic clock, which the engine reads to measure execution time.
+///
+/// Embedders without an operating system, or which need receipts to be fully deterministic,
+/// can use `NoClock`.
+pub trait Clock {
+    /// Returns the milliseconds elapsed since a fixed instant, or `None` if there's no time.
+    fn now_millis(&self) -> Option<u128>;
+}
+
+/// A clock which doesn't tell the time, so that execution time isn't measured.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoClock;
+
+impl Clock for NoClock {
+    fn now_millis(&self) -> Option<u128> {
+        None
+    }
+}
+
+/// The monotonic clock of the operating system.
+#[cfg(not(feature = "alloc"))]
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    origin: std::time::Instant,
+}
+
+#[cfg(not(feature = "alloc"))]
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self {
+            origin: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl Clock for SystemClock {
+    fn now_millis(&self) -> Option<u128> {
+        Some(self.origin.elapsed().as_millis())
+    }
+}
+
+/// The clock used unless another one is set.
+#[cfg(not(feature = "alloc"))]
+pub type DefaultClock = SystemClock;
+/// The clock used unless another one is set.
+#[cfg(feature = "alloc")]
+pub type DefaultClock = NoClock;
@@ -0,0 +1,232 @@
+use sbor::*;
+
+use crate::engine::{WasmFeatures, WasmLimits};
+
+/// A version of the engine behavior, tied to the network state version.
+///
+/// Every behavior change that affects transaction outcomes is gated on a feature flag, so that
+/// transactions committed under an older version are replayed with the same results.
+///
+/// The version is recorded in the ledger state, see `SubstateStore::get_engine_version`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode, TypeId)]
+pub enum EngineVersion {
+    /// The initial behavior.
+    V1,
+
+    /// Adds caller identity virtual badges and limits the size of package code.
+    V2,
+
+    /// Charges substate reads and writes by payload size.
+    V3,
+
+    /// Restricts wasm imports and export names, and limits the structure of wasm modules.
+    V4,
+
+    /// Derives global addresses from the entity type and a per-type index.
+    V5,
+
+    /// Limits the size of component state, lazy map values, non-fungible data and call data.
+    V6,
+
+    /// Charges a fee for each substate lock.
+    V7,
+}
+
+impl EngineVersion {
+    /// The latest engine version.
+    pub const LATEST: EngineVersion = EngineVersion::V7;
+
+    /// All engine versions, from the oldest to the latest.
+    pub const ALL: [EngineVersion; 7] = [
+        EngineVersion::V1,
+        EngineVersion::V2,
+        EngineVersion::V3,
+        EngineVersion::V4,
+        EngineVersion::V5,
+        EngineVersion::V6,
+        EngineVersion::V7,
+    ];
+
+    /// Returns the features enabled in this version.
+    pub fn features(&self) -> EngineFeatures {
+        match self {
+            EngineVersion::V1 => EngineFeatures {
+                caller_identity_proofs: false,
+                max_package_code_size: None,
+                wasm_features: DEFAULT_WASM_FEATURES,
+                substate_fees: false,
+                wasm_limits: None,
+                typed_addresses: false,
+                state_limits: None,
+                lock_fees: false,
+            },
+            EngineVersion::V2 => EngineFeatures {
+                caller_identity_proofs: true,
+                max_package_code_size: Some(MAX_PACKAGE_CODE_SIZE),
+                wasm_features: DEFAULT_WASM_FEATURES,
+                substate_fees: false,
+                wasm_limits: None,
+                typed_addresses: false,
+                state_limits: None,
+                lock_fees: false,
+            },
+            EngineVersion::V3 => EngineFeatures {
+                caller_identity_proofs: true,
+                max_package_code_size: Some(MAX_PACKAGE_CODE_SIZE),
+                wasm_features: DEFAULT_WASM_FEATURES,
+                substate_fees: true,
+                wasm_limits: None,
+                typed_addresses: false,
+                state_limits: None,
+                lock_fees: false,
+            },
+            EngineVersion::V4 => EngineFeatures {
+                caller_identity_proofs: true,
+                max_package_code_size: Some(MAX_PACKAGE_CODE_SIZE),
+                wasm_features: DEFAULT_WASM_FEATURES,
+                substate_fees: true,
+                wasm_limits: Some(DEFAULT_WASM_LIMITS),
+                typed_addresses: false,
+                state_limits: None,
+                lock_fees: false,
+            },
+            EngineVersion::V5 => EngineFeatures {
+                caller_identity_proofs: true,
+                max_package_code_size: Some(MAX_PACKAGE_CODE_SIZE),
+                wasm_features: DEFAULT_WASM_FEATURES,
+                substate_fees: true,
+                wasm_limits: Some(DEFAULT_WASM_LIMITS),
+                typed_addresses: true,
+                state_limits: None,
+                lock_fees: false,
+            },
+            EngineVersion::V6 => EngineFeatures {
+                caller_identity_proofs: true,
+                max_package_code_size: Some(MAX_PACKAGE_CODE_SIZE),
+                wasm_features: DEFAULT_WASM_FEATURES,
+                substate_fees: true,
+                wasm_limits: Some(DEFAULT_WASM_LIMITS),
+                typed_addresses: true,
+                state_limits: Some(DEFAULT_STATE_LIMITS),
+                lock_fees: false,
+            },
+            EngineVersion::V7 => EngineFeatures {
+                caller_identity_proofs: true,
+                max_package_code_size: Some(MAX_PACKAGE_CODE_SIZE),
+                wasm_features: DEFAULT_WASM_FEATURES,
+                substate_fees: true,
+                wasm_limits: Some(DEFAULT_WASM_LIMITS),
+                typed_addresses: true,
+                state_limits: Some(DEFAULT_STATE_LIMITS),
+                lock_fees: true,
+            },
+        }
+    }
+}
+
+impl Default for EngineVersion {
+    fn default() -> Self {
+        Self::LATEST
+    }
+}
+
+/// The maximum size of package code, since `EngineVersion::V2`.
+pub const MAX_PACKAGE_CODE_SIZE: usize = 4 * 1024 * 1024;
+
+/// The wasm features accepted by all engine versions.
+///
+/// Sign-extension operators are left to the interpreter, as recent Rust toolchains emit them.
+pub const DEFAULT_WASM_FEATURES: WasmFeatures = WasmFeatures {
+    sign_extension: true,
+    ..WasmFeatures::MVP
+};
+
+/// The wasm limits enforced since `EngineVersion::V4`.
+pub const DEFAULT_WASM_LIMITS: WasmLimits = WasmLimits {
+    max_functions: 16 * 1024,
+    max_function_size: 128 * 1024,
+    max_globals: 64,
+    max_table_size: 4 * 1024,
+};
+
+/// The state and call data size limits enforced since `EngineVersion::V6`.
+pub const DEFAULT_STATE_LIMITS: StateLimits = StateLimits {
+    max_component_state_size: 1024 * 1024,
+    max_lazy_map_key_size: 1024,
+    max_lazy_map_value_size: 1024 * 1024,
+    max_non_fungible_data_size: 64 * 1024,
+    max_call_data_size: 1024 * 1024,
+};
+
+/// The maximum encoded sizes of the state written by blueprints and of call arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateLimits {
+    /// The maximum size of the state of a component.
+    pub max_component_state_size: usize,
+
+    /// The maximum size of a lazy map key.
+    pub max_lazy_map_key_size: usize,
+
+    /// The maximum size of a lazy map value.
+    pub max_lazy_map_value_size: usize,
+
+    /// The maximum size of the immutable or mutable data of a non-fungible.
+    pub max_non_fungible_data_size: usize,
+
+    /// The maximum total size of the arguments of a blueprint function or component method call.
+    pub max_call_data_size: usize,
+}
+
+/// A kind of size-limited value, see `StateLimits`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeLimitKind {
+    ComponentState,
+    LazyMapKey,
+    LazyMapValue,
+    NonFungibleData,
+    CallData,
+}
+
+impl StateLimits {
+    /// Returns the limit which applies to the given kind of value.
+    pub fn limit(&self, kind: SizeLimitKind) -> usize {
+        match kind {
+            SizeLimitKind::ComponentState => self.max_component_state_size,
+            SizeLimitKind::LazyMapKey => self.max_lazy_map_key_size,
+            SizeLimitKind::LazyMapValue => self.max_lazy_map_value_size,
+            SizeLimitKind::NonFungibleData => self.max_non_fungible_data_size,
+            SizeLimitKind::CallData => self.max_call_data_size,
+        }
+    }
+}
+
+/// The set of behavior flags of an engine version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EngineFeatures {
+    /// Whether the package and global caller virtual badges are present in callee auth checks.
+    pub caller_identity_proofs: bool,
+
+    /// The maximum size of package code accepted by wasm validation, if any.
+    pub max_package_code_size: Option<usize>,
+
+    /// The post-MVP wasm features accepted by wasm validation.
+    pub wasm_features: WasmFeatures,
+
+    /// Whether the bytes of the substates read and written by a transaction consume cost units.
+    pub substate_fees: bool,
+
+    /// The import allow-list, export naming rules and structural limits enforced by wasm
+    /// validation, if any.
+    pub wasm_limits: Option<WasmLimits>,
+
+    /// Whether global addresses are derived with `derive_address`, from the entity type and a
+    /// per-type index, rather than from the ID sequence shared with vaults and lazy maps.
+    pub typed_addresses: bool,
+
+    /// The size limits of component state, lazy map entries, non-fungible data and call data,
+    /// if any.
+    pub state_limits: Option<StateLimits>,
+
+    /// Whether locking a substate, to borrow or read it, consumes cost units.
+    pub lock_fees: bool,
+}
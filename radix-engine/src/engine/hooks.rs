@@ -0,0 +1,98 @@
+use scrypto::core::SNodeRef;
+use scrypto::values::ScryptoValue;
+
+use crate::errors::RuntimeError;
+use crate::fee::CostUnitCounter;
+
+/// Callbacks into external tools, such as tracers, custom fee logic or invariant checkers,
+/// which are layered on top of the engine.
+///
+/// All methods default to doing nothing. Returning an error aborts the transaction.
+pub trait EngineHooks {
+    /// Called before an SNode is invoked, by a caller at the given call depth.
+    ///
+    /// Cost units consumed through the counter are billed to the transaction.
+    fn pre_invoke(
+        &mut self,
+        _depth: usize,
+        _snode_ref: &SNodeRef,
+        _function: &str,
+        _args: &[ScryptoValue],
+        _cost_unit_counter: &mut CostUnitCounter,
+    ) -> Result<(), RuntimeError> {
+        Ok(())
+    }
+
+    /// Called after an SNode invocation has returned, successfully or not.
+    fn post_invoke(
+        &mut self,
+        _depth: usize,
+        _snode_ref: &SNodeRef,
+        _function: &str,
+        _result: &Result<ScryptoValue, RuntimeError>,
+        _cost_unit_counter: &mut CostUnitCounter,
+    ) -> Result<(), RuntimeError> {
+        Ok(())
+    }
+
+    /// Called whenever a committed substate is read, including reads served by a cache, with
+    /// `None` if it doesn't exist.
+    fn on_substate_read(&mut self, _substate_id: &[u8], _value: Option<&[u8]>) {}
+
+    /// Called for every substate created or updated by a successful transaction, before it
+    /// is committed.
+    fn on_substate_write(
+        &mut self,
+        _substate_id: &[u8],
+        _value: &[u8],
+    ) -> Result<(), RuntimeError> {
+        Ok(())
+    }
+}
+
+/// Hooks which do nothing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoOpHooks;
+
+impl EngineHooks for NoOpHooks {}
+
+/// Two sets of hooks, which are called in order.
+impl<A: EngineHooks, B: EngineHooks> EngineHooks for (A, B) {
+    fn pre_invoke(
+        &mut self,
+        depth: usize,
+        snode_ref: &SNodeRef,
+        function: &str,
+        args: &[ScryptoValue],
+        cost_unit_counter: &mut CostUnitCounter,
+    ) -> Result<(), RuntimeError> {
+        self.0
+            .pre_invoke(depth, snode_ref, function, args, cost_unit_counter)?;
+        self.1
+            .pre_invoke(depth, snode_ref, function, args, cost_unit_counter)
+    }
+
+    fn post_invoke(
+        &mut self,
+        depth: usize,
+        snode_ref: &SNodeRef,
+        function: &str,
+        result: &Result<ScryptoValue, RuntimeError>,
+        cost_unit_counter: &mut CostUnitCounter,
+    ) -> Result<(), RuntimeError> {
+        self.0
+            .post_invoke(depth, snode_ref, function, result, cost_unit_counter)?;
+        self.1
+            .post_invoke(depth, snode_ref, function, result, cost_unit_counter)
+    }
+
+    fn on_substate_read(&mut self, substate_id: &[u8], value: Option<&[u8]>) {
+        self.0.on_substate_read(substate_id, value);
+        self.1.on_substate_read(substate_id, value);
+    }
+
+    fn on_substate_write(&mut self, substate_id: &[u8], value: &[u8]) -> Result<(), RuntimeError> {
+        self.0.on_substate_write(substate_id, value)?;
+        self.1.on_substate_write(substate_id, value)
+    }
+}
@@ -3,12 +3,53 @@ use scrypto::engine::types::*;
 use scrypto::rust::ops::Range;
 
 pub const ECDSA_TOKEN_BUCKET_ID: BucketId = 0;
+pub const PACKAGE_TOKEN_BUCKET_ID: BucketId = 1;
+pub const GLOBAL_CALLER_TOKEN_BUCKET_ID: BucketId = 2;
+pub const SYSTEM_TOKEN_BUCKET_ID: BucketId = 3;
+
+/// The entity type byte of packages, which is also the prefix of their text representation.
+pub const PACKAGE_ENTITY_TYPE: u8 = 0x01;
+/// The entity type byte of components, which is also the prefix of their text representation.
+pub const COMPONENT_ENTITY_TYPE: u8 = 0x02;
+/// The entity type byte of resources, which is also the prefix of their text representation.
+pub const RESOURCE_ENTITY_TYPE: u8 = 0x03;
+
+/// Derives the address of a global entity created by a transaction.
+///
+/// The address is the lower 26 bytes of `sha256(transaction_hash || entity_type || index)`,
+/// where `entity_type` is a single byte and `index` is the little-endian `u32` number of
+/// entities of the same type created earlier in the transaction.
+///
+/// This derivation is part of the protocol since `EngineVersion::V5` and must not change.
+pub fn derive_address(transaction_hash: Hash, entity_type: u8, index: u32) -> [u8; 26] {
+    let mut data = transaction_hash.to_vec();
+    data.push(entity_type);
+    data.extend(index.to_le_bytes());
+    hash(data).lower_26_bytes()
+}
+
+/// Derives the address of the `index`-th package created by a transaction.
+pub fn derive_package_address(transaction_hash: Hash, index: u32) -> PackageAddress {
+    PackageAddress(derive_address(transaction_hash, PACKAGE_ENTITY_TYPE, index))
+}
+
+/// Derives the address of the `index`-th component created by a transaction.
+pub fn derive_component_address(transaction_hash: Hash, index: u32) -> ComponentAddress {
+    ComponentAddress(derive_address(transaction_hash, COMPONENT_ENTITY_TYPE, index))
+}
+
+/// Derives the address of the `index`-th resource created by a transaction.
+pub fn derive_resource_address(transaction_hash: Hash, index: u32) -> ResourceAddress {
+    ResourceAddress(derive_address(transaction_hash, RESOURCE_ENTITY_TYPE, index))
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum IdSpace {
     System,
     Transaction,
     Application,
+    /// Bucket and proof IDs of a call frame, identified by its index within the transaction.
+    Call(u32),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -19,6 +60,12 @@ pub enum IdAllocatorError {
 /// An ID allocator defines how identities are generated.
 pub struct IdAllocator {
     available: Range<u32>,
+    /// Whether global addresses are derived with `derive_address`, rather than from the
+    /// shared ID sequence.
+    typed_addresses: bool,
+    package_count: u32,
+    component_count: u32,
+    resource_count: u32,
 }
 
 impl IdAllocator {
@@ -29,10 +76,26 @@ impl IdAllocator {
                 IdSpace::System => (0..512),
                 IdSpace::Transaction => (512..1024),
                 IdSpace::Application => (1024..u32::MAX),
+                // Each call frame owns a disjoint block of 2^16 IDs, so that its IDs don't
+                // depend on the allocations made by other frames.
+                IdSpace::Call(index) => match index {
+                    1..=0xFFFF => ((index << 16)..(index << 16 | 0xFFFF)),
+                    _ => (0..0),
+                },
             },
+            typed_addresses: false,
+            package_count: 0,
+            component_count: 0,
+            resource_count: 0,
         }
     }
 
+    /// Sets whether global addresses are derived from the entity type and a per-type index.
+    pub fn with_typed_addresses(mut self, typed_addresses: bool) -> Self {
+        self.typed_addresses = typed_addresses;
+        self
+    }
+
     fn next(&mut self) -> Result<u32, IdAllocatorError> {
         if self.available.len() > 0 {
             let id = self.available.start;
@@ -43,11 +106,21 @@ impl IdAllocator {
         }
     }
 
+    fn next_index(count: &mut u32) -> Result<u32, IdAllocatorError> {
+        let index = *count;
+        *count = index.checked_add(1).ok_or(IdAllocatorError::OutOfID)?;
+        Ok(index)
+    }
+
     /// Creates a new package ID.
     pub fn new_package_address(
         &mut self,
         transaction_hash: Hash,
     ) -> Result<PackageAddress, IdAllocatorError> {
+        if self.typed_addresses {
+            let index = Self::next_index(&mut self.package_count)?;
+            return Ok(derive_package_address(transaction_hash, index));
+        }
         let mut data = transaction_hash.to_vec();
         data.extend(self.next()?.to_le_bytes());
         Ok(PackageAddress(hash(data).lower_26_bytes()))
@@ -58,6 +131,10 @@ impl IdAllocator {
         &mut self,
         transaction_hash: Hash,
     ) -> Result<ComponentAddress, IdAllocatorError> {
+        if self.typed_addresses {
+            let index = Self::next_index(&mut self.component_count)?;
+            return Ok(derive_component_address(transaction_hash, index));
+        }
         let mut data = transaction_hash.to_vec();
         data.extend(self.next()?.to_le_bytes());
         Ok(ComponentAddress(hash(data).lower_26_bytes()))
@@ -68,6 +145,10 @@ impl IdAllocator {
         &mut self,
         transaction_hash: Hash,
     ) -> Result<ResourceAddress, IdAllocatorError> {
+        if self.typed_addresses {
+            let index = Self::next_index(&mut self.resource_count)?;
+            return Ok(derive_resource_address(transaction_hash, index));
+        }
         let mut data = transaction_hash.to_vec();
         data.extend(self.next()?.to_le_bytes());
         Ok(ResourceAddress(hash(data).lower_26_bytes()))
@@ -102,4 +183,80 @@ impl IdAllocator {
     ) -> Result<LazyMapId, IdAllocatorError> {
         Ok((transaction_hash, self.next()?))
     }
+
+    /// Creates a new locker ID.
+    pub fn new_locker_id(&mut self, transaction_hash: Hash) -> Result<LockerId, IdAllocatorError> {
+        Ok((transaction_hash, self.next()?))
+    }
+
+    /// Creates a new allowance ID.
+    pub fn new_allowance_id(
+        &mut self,
+        transaction_hash: Hash,
+    ) -> Result<AllowanceId, IdAllocatorError> {
+        Ok((transaction_hash, self.next()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scrypto::address::Bech32Encoder;
+
+    #[test]
+    fn test_call_id_spaces_are_disjoint() {
+        let mut first = IdAllocator::new(IdSpace::Call(1));
+        let mut second = IdAllocator::new(IdSpace::Call(2));
+        assert_eq!(first.new_bucket_id(), Ok(0x10000));
+        assert_eq!(first.new_proof_id(), Ok(0x10001));
+        assert_eq!(second.new_bucket_id(), Ok(0x20000));
+    }
+
+    #[test]
+    fn test_derived_addresses_are_stable() {
+        let transaction_hash = hash("transaction");
+        let encoder = Bech32Encoder::for_simulator();
+        assert_eq!(
+            encoder.encode_package_address(&derive_package_address(transaction_hash, 0)),
+            "package_sim1q9ghmwnn2hgdusa0t0pg82ka0k22vv69ftyth4rnqe8q9fpa26"
+        );
+        assert_eq!(
+            encoder.encode_component_address(&derive_component_address(transaction_hash, 0)),
+            "component_sim1qtj2g2d6xq2pxhmzu5qcrh906nzv22wqp7f9tdxl530qwr8q3s"
+        );
+        assert_eq!(
+            encoder.encode_component_address(&derive_component_address(transaction_hash, 1)),
+            "component_sim1qfqg9zwr876lksqahtnfwu4c674f9sz3xt8k35zp66psaq6r92"
+        );
+        assert_eq!(
+            encoder.encode_resource_address(&derive_resource_address(transaction_hash, 0)),
+            "resource_sim1qvsz2frqwezjj5vwhk972tz8z4pcxqkxyyta9pxwpekscmnwjs"
+        );
+    }
+
+    #[test]
+    fn test_typed_addresses_use_per_type_index() {
+        let transaction_hash = hash("transaction");
+        let mut allocator = IdAllocator::new(IdSpace::Application).with_typed_addresses(true);
+        allocator.new_vault_id(transaction_hash).unwrap();
+        allocator.new_resource_address(transaction_hash).unwrap();
+        assert_eq!(
+            allocator.new_component_address(transaction_hash),
+            Ok(derive_component_address(transaction_hash, 0))
+        );
+        assert_eq!(
+            allocator.new_component_address(transaction_hash),
+            Ok(derive_component_address(transaction_hash, 1))
+        );
+        assert_eq!(
+            allocator.new_resource_address(transaction_hash),
+            Ok(derive_resource_address(transaction_hash, 1))
+        );
+    }
+
+    #[test]
+    fn test_call_id_space_out_of_range() {
+        let mut allocator = IdAllocator::new(IdSpace::Call(0x10000));
+        assert_eq!(allocator.new_bucket_id(), Err(IdAllocatorError::OutOfID));
+    }
 }
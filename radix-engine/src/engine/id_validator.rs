@@ -114,6 +114,19 @@ impl IdValidator {
         }
     }
 
+    pub fn drop_all_proofs(&mut self) -> Result<(), IdValidatorError> {
+        for (_, kind) in self.proof_ids.drain() {
+            if let ProofKind::BucketProof(bucket_id) = kind {
+                if let Some(cnt) = self.bucket_ids.get_mut(&bucket_id) {
+                    *cnt -= 1;
+                } else {
+                    panic!("Illegal state");
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn move_all_resources(&mut self) -> Result<(), IdValidatorError> {
         self.proof_ids.clear();
         self.bucket_ids.clear();
@@ -0,0 +1,2421 @@
+use sbor::*;
+use scrypto::buffer::*;
+use scrypto::core::{SNodeRef, ScryptoActor};
+use scrypto::engine::api::*;
+use scrypto::engine::types::*;
+use scrypto::resource::{AccessRule, Mutability};
+use scrypto::rust::borrow::ToOwned;
+use scrypto::rust::collections::*;
+use scrypto::rust::fmt;
+use scrypto::rust::format;
+use scrypto::rust::string::String;
+use scrypto::rust::string::ToString;
+use scrypto::rust::vec;
+use scrypto::rust::vec::Vec;
+use scrypto::values::*;
+use wasmi::*;
+
+use crate::engine::kernel::LazyMapState::{Committed, Uncommitted};
+use crate::engine::*;
+use crate::engine::CallFrame;
+use crate::errors::*;
+use crate::fee::*;
+use crate::ledger::*;
+use crate::model::*;
+use crate::model::fmt::{format_bucket, format_proof, format_vault, format_worktop};
+
+macro_rules! re_trace {
+    ($proc:expr, $($args: expr),+) => {
+        if $proc.trace {
+            $proc.log(Level::Trace, format!($($args),+));
+        }
+    };
+}
+
+macro_rules! re_debug {
+    ($proc:expr, $($args: expr),+) => {
+        if $proc.trace {
+            $proc.log(Level::Debug, format!($($args),+));
+        }
+    };
+}
+
+macro_rules! re_info {
+    ($proc:expr, $($args: expr),+) => {
+        if $proc.trace {
+            $proc.log(Level::Info, format!($($args),+));
+        }
+    };
+}
+
+macro_rules! re_warn {
+    ($proc:expr, $($args: expr),+) => {
+        if $proc.trace {
+            $proc.log(Level::Warn, format!($($args),+));
+        }
+    };
+}
+
+pub trait SystemApi {
+    fn invoke_snode(
+        &mut self,
+        snode_ref: SNodeRef,
+        function: String,
+        args: Vec<ScryptoValue>,
+    ) -> Result<ScryptoValue, RuntimeError>;
+
+    fn get_non_fungible(
+        &mut self,
+        non_fungible_address: &NonFungibleAddress,
+    ) -> Option<&NonFungible>;
+
+    fn set_non_fungible(
+        &mut self,
+        non_fungible_address: NonFungibleAddress,
+        non_fungible: Option<NonFungible>,
+    );
+
+    fn borrow_global_mut_resource_manager(
+        &mut self,
+        resource_address: ResourceAddress,
+    ) -> Result<ResourceManager, RuntimeError>;
+
+    fn return_borrowed_global_resource_manager(
+        &mut self,
+        resource_address: ResourceAddress,
+        resource_manager: ResourceManager,
+    );
+
+    fn create_bucket(&mut self, container: ResourceContainer) -> Result<BucketId, RuntimeError>;
+
+    fn take_bucket(&mut self, bucket_id: BucketId) -> Result<Bucket, RuntimeError>;
+
+    fn create_proof(&mut self, proof: Proof) -> Result<ProofId, RuntimeError>;
+
+    fn create_signature_proof(
+        &mut self,
+        signers: &[EcdsaPublicKey],
+    ) -> Result<ProofId, RuntimeError>;
+
+    fn take_proof(&mut self, proof_id: ProofId) -> Result<Proof, RuntimeError>;
+
+    fn create_resource(&mut self, resource_manager: ResourceManager) -> ResourceAddress;
+
+    fn create_package(&mut self, package: Package) -> PackageAddress;
+
+    fn create_component(&mut self, component: Component) -> ComponentAddress;
+
+    fn create_vault(
+        &mut self,
+        component_address: ComponentAddress,
+        resource_address: ResourceAddress,
+    ) -> Result<VaultId, RuntimeError>;
+
+    fn create_locker(
+        &mut self,
+        component_address: ComponentAddress,
+        locker: Locker,
+    ) -> Result<LockerId, RuntimeError>;
+
+    /// Creates an empty lazy map, to be owned by the state of a native component.
+    fn create_lazy_map(&mut self) -> LazyMapId;
+
+    /// Returns an entry of a lazy map owned by a component.
+    fn get_lazy_map_entry(
+        &mut self,
+        component_address: ComponentAddress,
+        lazy_map_id: &LazyMapId,
+        key: &[u8],
+    ) -> Option<Vec<u8>>;
+
+    /// Puts an entry into a lazy map owned by a component.
+    ///
+    /// Unlike entries put by WASM, the entry is not checked for the objects it owns, which must
+    /// already belong to the component.
+    fn put_lazy_map_entry(
+        &mut self,
+        component_address: ComponentAddress,
+        lazy_map_id: LazyMapId,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<(), RuntimeError>;
+
+    fn create_allowance(&mut self, allowance: Allowance) -> AllowanceId;
+
+    fn revoke_allowance(
+        &mut self,
+        allowance_id: AllowanceId,
+        vault_id: VaultId,
+    ) -> Result<(), RuntimeError>;
+
+    fn take_from_component_vault(
+        &mut self,
+        component_address: ComponentAddress,
+        vault_id: VaultId,
+        amount: Decimal,
+    ) -> Result<ResourceContainer, RuntimeError>;
+
+    fn current_epoch(&self) -> u64;
+
+    fn set_epoch(&mut self, epoch: u64);
+
+    /// Changes the engine version, which takes effect from the next transaction.
+    fn set_engine_version(&mut self, engine_version: EngineVersion);
+
+    fn set_instruction_index(&mut self, instruction_index: usize);
+
+    /// Replaces the signers seen by `Runtime::transaction_signers`, e.g. at the start of a
+    /// partial transaction.
+    fn set_transaction_signers(&mut self, signers: Vec<EcdsaPublicKey>);
+
+    fn engine_version(&self) -> EngineVersion;
+
+    /// Checks the encoded size of a value against the limits of the engine version.
+    fn check_size_limit(&self, kind: SizeLimitKind, size: usize) -> Result<(), RuntimeError>;
+
+    /// Returns the ABI of a blueprint.
+    fn blueprint_abi(
+        &mut self,
+        package_address: PackageAddress,
+        blueprint_name: &str,
+    ) -> Result<BlueprintAbi, RuntimeError>;
+
+    fn fee_table(&self) -> &FeeTable;
+
+    fn cost_unit_counter(&self) -> &CostUnitCounter;
+
+    fn set_cost_unit_limit(&mut self, limit: u32);
+
+    fn consume_cost_units(
+        &mut self,
+        amount: u32,
+        reason: &'static str,
+    ) -> Result<(), CostUnitCounterError>;
+}
+
+pub enum SNodeState {
+    Transaction(TransactionProcess),
+    PackageStatic,
+    PackageRef(PackageAddress, Package),
+    AuthZone(AuthZone),
+    Worktop(Worktop),
+    Scrypto(ScryptoActorInfo, Option<Component>),
+    ResourceStatic,
+    ResourceRef(ResourceAddress, ResourceManager),
+    BucketRef(BucketId, Bucket),
+    Bucket(Bucket),
+    ProofRef(ProofId, Proof),
+    Proof(Proof),
+    VaultRef(VaultId, Option<ComponentAddress>, Vault),
+    LockerStatic,
+    /// A component, locker or allowance, borrowed from the track
+    GlobalNodeRef(RENodeId, RENode),
+}
+
+/// Represents an interpreter instance.
+pub struct Interpreter {
+    actor: ScryptoActorInfo,
+    function: String,
+    args: Vec<ScryptoValue>,
+    module: ModuleRef,
+    memory: MemoryRef,
+}
+
+/// Qualitative states for a WASM process
+#[derive(Debug)]
+enum InterpreterState<'a> {
+    Blueprint,
+    Component {
+        component_address: ComponentAddress,
+        component: &'a mut Component,
+        initial_loaded_object_refs: ComponentObjectRefs,
+    },
+}
+
+/// Top level state machine for a process. Empty currently only
+/// refers to the initial process since it doesn't run on a wasm interpreter (yet)
+#[allow(dead_code)]
+struct WasmProcess<'a> {
+    /// The call depth
+    depth: usize,
+    trace: bool,
+    vm: Interpreter,
+    interpreter_state: InterpreterState<'a>,
+}
+
+///TODO: Remove
+#[derive(Debug)]
+enum LazyMapState {
+    Uncommitted { root: LazyMapId },
+    Committed { component_address: ComponentAddress },
+}
+
+/// The encoded input of a system call.
+enum RequestData {
+    /// A request in guest memory.
+    Guest { ptr: u32, len: u32 },
+    /// A request which is part of a batch.
+    Batched(Vec<u8>),
+}
+
+/// Borrows `len` bytes of guest memory starting at `ptr`, or `None` if out of bounds.
+fn guest_slice(memory: &[u8], ptr: u32, len: usize) -> Option<&[u8]> {
+    let start = ptr as usize;
+    memory.get(start..start.checked_add(len)?)
+}
+
+/// Mutably borrows `len` bytes of guest memory starting at `ptr`, or `None` if out of bounds.
+fn guest_slice_mut(memory: &mut [u8], ptr: u32, len: usize) -> Option<&mut [u8]> {
+    let start = ptr as usize;
+    memory.get_mut(start..start.checked_add(len)?)
+}
+
+impl<'s, S: SubstateStore> Track<'s, S> {
+    fn insert_objects_into_component(
+        &mut self,
+        new_objects: ComponentObjects,
+        component_address: ComponentAddress,
+    ) {
+        for (vault_id, vault) in new_objects.vaults {
+            self.put_vault(component_address, vault_id, vault);
+        }
+        for (lazy_map_id, unclaimed) in new_objects.lazy_maps {
+            for (k, v) in unclaimed.lazy_map {
+                self.put_lazy_map_entry(component_address, lazy_map_id, k, v);
+            }
+            for (child_lazy_map_id, child_lazy_map) in unclaimed.descendent_lazy_maps {
+                for (k, v) in child_lazy_map {
+                    self.put_lazy_map_entry(component_address, child_lazy_map_id, k, v);
+                }
+            }
+            for (vault_id, vault) in unclaimed.descendent_vaults {
+                self.put_vault(component_address, vault_id, vault);
+            }
+        }
+    }
+}
+
+/// The kernel runs an invocation: it loads and authorizes SNodes, executes their code and
+/// moves nodes between its call frame and the frames of the invocations it makes.
+pub struct Kernel<'r, 'l, L: SubstateStore> {
+    /// The call depth
+    depth: usize,
+    /// Whether to show trace messages
+    trace: bool,
+    /// Transactional state updates
+    track: &'r mut Track<'l, L>,
+
+    /// The nodes owned and referenced by this invocation
+    frame: CallFrame,
+
+    /// The caller's auth zone
+    caller_auth_zone: Option<&'r AuthZone>,
+
+    /// The actor which called this process, if any
+    caller: Option<ScryptoActorInfo>,
+
+    /// State for the given wasm process, empty only on the root process
+    /// (root process cannot create components nor is a component itself)
+    wasm_process_state: Option<WasmProcess<'r>>,
+
+    /// The actor being executed by a native blueprint, if any
+    native_actor: Option<ScryptoActorInfo>,
+}
+
+impl<'r, 'l, L: SubstateStore> Kernel<'r, 'l, L> {
+    /// Create a new kernel, which is not started.
+    pub fn new(
+        depth: usize,
+        trace: bool,
+        track: &'r mut Track<'l, L>,
+        auth_zone: Option<AuthZone>,
+        worktop: Option<Worktop>,
+        nodes: BTreeMap<RENodeId, RENode>,
+    ) -> Self {
+        let frame = CallFrame::new(track.new_call_index(), auth_zone, worktop, nodes);
+        Self {
+            depth,
+            trace,
+            track,
+            frame,
+            caller_auth_zone: None,
+            caller: None,
+            wasm_process_state: None,
+            native_actor: None,
+        }
+    }
+
+    /// Returns the name of the type of an SNode, for metrics.
+    fn snode_type(snode_ref: &SNodeRef) -> &'static str {
+        match snode_ref {
+            SNodeRef::PackageStatic => "PackageStatic",
+            SNodeRef::PackageRef(_) => "PackageRef",
+            SNodeRef::AuthZoneRef => "AuthZoneRef",
+            SNodeRef::WorktopRef => "WorktopRef",
+            SNodeRef::Scrypto(_) => "Scrypto",
+            SNodeRef::ComponentRef(_) => "ComponentRef",
+            SNodeRef::ResourceStatic => "ResourceStatic",
+            SNodeRef::ResourceRef(_) => "ResourceRef",
+            SNodeRef::Bucket(_) => "Bucket",
+            SNodeRef::BucketRef(_) => "BucketRef",
+            SNodeRef::ProofRef(_) => "ProofRef",
+            SNodeRef::Proof(_) => "Proof",
+            SNodeRef::VaultRef(_) => "VaultRef",
+            SNodeRef::LockerStatic => "LockerStatic",
+            SNodeRef::LockerRef(..) => "LockerRef",
+            SNodeRef::AllowanceRef(_) => "AllowanceRef",
+        }
+    }
+
+    /// Returns the actor this process is running, if any.
+    fn actor(&self) -> Option<ScryptoActorInfo> {
+        match &self.wasm_process_state {
+            Some(wasm_process) => Some(wasm_process.vm.actor.clone()),
+            None => self.native_actor.clone(),
+        }
+    }
+
+    /// Returns the address of the component being executed, if any.
+    fn actor_component_address(&self) -> Option<ComponentAddress> {
+        match &self.wasm_process_state {
+            Some(WasmProcess {
+                interpreter_state: InterpreterState::Component { component_address, .. },
+                ..
+            }) => Some(*component_address),
+            _ => self
+                .native_actor
+                .as_ref()
+                .and_then(|actor| actor.component_address()),
+        }
+    }
+
+    /// Returns the component whose method this process is running.
+    fn current_component_mut(&mut self) -> Result<&mut Component, RuntimeError> {
+        let wasm_process = self
+            .wasm_process_state
+            .as_mut()
+            .ok_or(RuntimeError::IllegalSystemCall)?;
+        match &mut wasm_process.interpreter_state {
+            InterpreterState::Component { component, .. } => Ok(&mut **component),
+            _ => Err(RuntimeError::IllegalSystemCall),
+        }
+    }
+
+    /// Checks a rule guarding an update of the current component, which the component makes on
+    /// behalf of its caller.
+    fn check_component_update_auth(
+        &self,
+        function: &str,
+        rule: &AccessRule,
+    ) -> Result<(), RuntimeError> {
+        let caller_identity = AuthZone::new_with_proofs(self.caller_identity_proofs()?);
+        let mut auth_zones = vec![&caller_identity];
+        if let Some(self_auth_zone) = self.frame.auth_zone() {
+            auth_zones.push(self_auth_zone);
+        }
+        if let Some(caller_auth_zone) = self.caller_auth_zone {
+            auth_zones.push(caller_auth_zone);
+        }
+
+        let method_auth = convert(&Type::Unit, &Value::Unit, rule);
+        method_auth
+            .check(&auth_zones)
+            .map_err(|error| RuntimeError::AuthorizationError {
+                function: function.to_owned(),
+                explanation: method_auth.explain(&auth_zones),
+                authorization: method_auth,
+                error,
+            })
+    }
+
+    /// Returns the virtual proofs identifying this process as a caller.
+    ///
+    /// A process running a blueprint holds a badge of its package, and a badge of its component
+    /// if it runs a component method. The root process has no caller identity.
+    fn caller_identity_proofs(&self) -> Result<Vec<Proof>, RuntimeError> {
+        if !self.track.engine_version().features().caller_identity_proofs {
+            return Ok(Vec::new());
+        }
+
+        let actor = match &self.wasm_process_state {
+            Some(wasm_process) => Some(&wasm_process.vm.actor),
+            None => self.native_actor.as_ref(),
+        };
+
+        let mut proofs = Vec::new();
+        if let Some(actor) = actor {
+            proofs.push(Self::virtual_proof(
+                PACKAGE_TOKEN,
+                PACKAGE_TOKEN_BUCKET_ID,
+                actor.package_address().to_vec(),
+            )?);
+            if let Some(component_address) = actor.component_address() {
+                proofs.push(Self::virtual_proof(
+                    GLOBAL_CALLER_TOKEN,
+                    GLOBAL_CALLER_TOKEN_BUCKET_ID,
+                    component_address.to_vec(),
+                )?);
+            }
+        }
+        Ok(proofs)
+    }
+
+    fn virtual_proof(
+        resource_address: ResourceAddress,
+        bucket_id: BucketId,
+        id: Vec<u8>,
+    ) -> Result<Proof, RuntimeError> {
+        let ids = BTreeSet::from([NonFungibleId::from_bytes(id)]);
+        let mut bucket = Bucket::new(ResourceContainer::new_non_fungible(resource_address, ids));
+        bucket
+            .create_proof(bucket_id)
+            .map_err(RuntimeError::ProofError)
+    }
+
+    /// Runs a blueprint which is implemented natively by the engine.
+    fn run_native(
+        &mut self,
+        actor: &ScryptoActorInfo,
+        component_state: &mut Option<Component>,
+        function: &str,
+        args: Vec<ScryptoValue>,
+    ) -> Result<ScryptoValue, RuntimeError> {
+        self.native_actor = Some(actor.clone());
+
+        if let Some(component) = component_state {
+            let component_address = actor.component_address().unwrap();
+            let data = ScryptoValueIds::from_slice(component.state()).unwrap();
+            for vault_id in data.vault_ids {
+                self.frame.add_vault_ref(vault_id);
+            }
+
+            if actor.package_address().eq(&ACCOUNT_PACKAGE) {
+                Account::main(component_address, component, function, args, self)
+            } else {
+                System::main(component, function, args, self)
+            }
+        } else if actor.package_address().eq(&ACCOUNT_PACKAGE) {
+            Account::static_main(function, args, self)
+        } else {
+            System::static_main(function, args, self)
+        }
+    }
+
+    /// Describes an SNode, for call stacks.
+    fn describe_snode(snode: &SNodeState) -> String {
+        match snode {
+            SNodeState::Transaction(_) => "Transaction".to_string(),
+            SNodeState::PackageStatic => "Package".to_string(),
+            SNodeState::PackageRef(package_address, _) => format!("Package[{}]", package_address),
+            SNodeState::AuthZone(_) => "AuthZone".to_string(),
+            SNodeState::Worktop(_) => "Worktop".to_string(),
+            SNodeState::Scrypto(actor, _) => match actor.component_address() {
+                Some(component_address) => format!(
+                    "{}/{}[{}]",
+                    actor.package_address(),
+                    actor.blueprint_name(),
+                    component_address
+                ),
+                None => format!("{}/{}", actor.package_address(), actor.blueprint_name()),
+            },
+            SNodeState::ResourceStatic => "ResourceManager".to_string(),
+            SNodeState::ResourceRef(resource_address, _) => {
+                format!("ResourceManager[{}]", resource_address)
+            }
+            SNodeState::BucketRef(bucket_id, _) => format!("Bucket[{}]", bucket_id),
+            SNodeState::Bucket(_) => "Bucket".to_string(),
+            SNodeState::ProofRef(proof_id, _) => format!("Proof[{}]", proof_id),
+            SNodeState::Proof(_) => "Proof".to_string(),
+            SNodeState::VaultRef(vault_id, _, _) => format!("Vault[{:?}]", vault_id),
+            SNodeState::LockerStatic => "Locker".to_string(),
+            SNodeState::GlobalNodeRef(node_id, _) => match node_id {
+                RENodeId::Component(component_address) => {
+                    format!("Component[{}]", component_address)
+                }
+                RENodeId::Locker(component_address, locker_id) => {
+                    format!("Locker[{}, {:?}]", component_address, locker_id)
+                }
+                RENodeId::Allowance(allowance_id) => format!("Allowance[{:?}]", allowance_id),
+                _ => format!("{:?}", node_id),
+            },
+        }
+    }
+
+    /// Runs the given export within this process.
+    ///
+    /// The call stack is tracked, so that failures can be reported with the path that failed.
+    pub fn run(
+        &mut self,
+        snode: &'r mut SNodeState,
+        function: String,
+        args: Vec<ScryptoValue>,
+    ) -> Result<(ScryptoValue, BTreeMap<RENodeId, RENode>), RuntimeError> {
+        self.track.enter_frame(crate::errors::CallFrame {
+            snode: Self::describe_snode(snode),
+            function: function.clone(),
+        });
+        let result = self.run_frame(snode, function, args);
+        self.track.exit_frame(result.is_err());
+        result
+    }
+
+    fn run_frame(
+        &mut self,
+        snode: &'r mut SNodeState,
+        function: String,
+        args: Vec<ScryptoValue>,
+    ) -> Result<(ScryptoValue, BTreeMap<RENodeId, RENode>), RuntimeError> {
+        let start = self.track.now_millis();
+        re_info!(self, "Run started: function = {:?}", function);
+
+        // Execution
+        let output = match snode {
+            SNodeState::Transaction(transaction_process) => {
+                transaction_process.main(self)
+            }
+            SNodeState::PackageStatic => {
+                Package::static_main(&function, args, self).map_err(RuntimeError::PackageError)
+            }
+            SNodeState::PackageRef(_, package) => package
+                .main(function.as_str(), args, self)
+                .map_err(RuntimeError::PackageError),
+            SNodeState::AuthZone(auth_zone) => {
+                auth_zone
+                    .main(function.as_str(), args, self)
+                    .map_err(RuntimeError::AuthZoneError)
+            }
+            SNodeState::Worktop(worktop) => {
+                worktop
+                    .main(function.as_str(), args, self)
+                    .map_err(RuntimeError::WorktopError)
+            }
+            SNodeState::Scrypto(actor, component_state)
+                if actor.package_address().eq(&ACCOUNT_PACKAGE)
+                    || actor.package_address().eq(&SYSTEM_PACKAGE) =>
+            {
+                self.run_native(actor, component_state, function.as_str(), args)
+            }
+            SNodeState::Scrypto(actor, component_state) => {
+                let package = self.track.get_package(actor.package_address())?;
+
+                let exists = match actor.export_name().strip_prefix(TEST_EXPORT_PREFIX) {
+                    Some(test_name) => package.contains_test(test_name),
+                    None => package.contains_blueprint(actor.blueprint_name()),
+                };
+                if !exists {
+                    return Err(RuntimeError::BlueprintNotFound(
+                        actor.package_address().clone(),
+                        actor.blueprint_name().to_string(),
+                    ));
+                }
+
+                // Pooled instances are charged as if freshly instantiated, so that the cost
+                // doesn't depend on the state of the pool.
+                // SECURITY: bill before instantiating, which allocates the linear memory
+                let instantiation_cost = (package.code().len() as u32)
+                    .saturating_mul(self.track.fee_table().wasm_instantiation_per_byte());
+                self.track
+                    .consume_cost_units(instantiation_cost, "wasm_instantiation")
+                    .map_err(RuntimeError::CostingError)?;
+                let instance = self
+                    .track
+                    .load_wasm_instance(actor.package_address())
+                    .unwrap();
+                let module = instance.module().clone();
+                let memory = instance.memory().clone();
+
+                let (interpreter_state, args) = if let Some(component) = component_state {
+                    let component_address = actor.component_address().unwrap().clone();
+                    let data = ScryptoValueIds::from_slice(component.state()).unwrap();
+                    let initial_loaded_object_refs = ComponentObjectRefs {
+                        vault_ids: data.vault_ids.into_iter().collect(),
+                        lazy_map_ids: data.lazy_map_ids.into_iter().collect(),
+                    };
+                    let istate = InterpreterState::Component {
+                        component_address,
+                        component,
+                        initial_loaded_object_refs,
+                    };
+                    let mut args_with_self = vec![ScryptoValue::from_value(&component_address)];
+                    args_with_self.extend(args);
+
+                    (istate, args_with_self)
+                } else {
+                    (InterpreterState::Blueprint, args)
+                };
+
+                self.wasm_process_state = Some(WasmProcess {
+                    depth: self.depth,
+                    trace: self.trace,
+                    vm: Interpreter {
+                        function,
+                        args,
+                        actor: actor.clone(),
+                        module: module.clone(),
+                        memory,
+                    },
+                    interpreter_state,
+                });
+
+                // Execution
+                let result = module.invoke_export(actor.export_name(), &[], self);
+
+                // Return value
+                re_debug!(self, "Invoke result: {:?}", result);
+                let rtn = result
+                    .map_err(|e| {
+                        match e.into_host_error() {
+                            // Pass-through runtime errors
+                            Some(host_error) => *host_error.downcast::<RuntimeError>().unwrap(),
+                            None => RuntimeError::InvokeError,
+                        }
+                    })?
+                    .ok_or(RuntimeError::NoReturnData)?;
+                let output = match rtn {
+                    RuntimeValue::I32(ptr) => self.read_return_value(ptr as u32),
+                    _ => Err(RuntimeError::InvalidReturnType),
+                };
+                self.track.release_wasm_instance(instance);
+                output
+            }
+            SNodeState::ResourceStatic => {
+                ResourceManager::static_main(function.as_str(), args, self)
+            }
+            SNodeState::ResourceRef(resource_address, resource_manager) => {
+                let return_value =
+                    resource_manager.main(*resource_address, function.as_str(), args, self)?;
+
+                Ok(return_value)
+            }
+            SNodeState::BucketRef(bucket_id, bucket) => bucket
+                .main(*bucket_id, function.as_str(), args, self)
+                .map_err(RuntimeError::BucketError),
+            SNodeState::ProofRef(_, proof) => proof
+                .main(function.as_str(), args, self)
+                .map_err(RuntimeError::ProofError),
+            SNodeState::VaultRef(vault_id, component_address, vault) =>
+                vault
+                    .main(*vault_id, *component_address, function.as_str(), args, self)
+                    .map_err(RuntimeError::VaultError),
+            SNodeState::LockerStatic => Locker::static_main(function.as_str(), args, self)
+                .map_err(RuntimeError::LockerError),
+            SNodeState::GlobalNodeRef(_, node) => match node {
+                RENode::Component(component) => component.main(function.as_str(), args, self),
+                RENode::Locker(locker) => locker
+                    .main(function.as_str(), args, self)
+                    .map_err(RuntimeError::LockerError),
+                RENode::Allowance(allowance) => allowance
+                    .main(function.as_str(), args, self)
+                    .map_err(RuntimeError::AllowanceError),
+                _ => Err(RuntimeError::IllegalSystemCall),
+            },
+            _ => Err(RuntimeError::IllegalSystemCall),
+        }?;
+
+        // figure out what buckets and resources to return
+        let moving_nodes = self.send_nodes(&RENodeId::from_value(&output), MoveMethod::AsReturn)?;
+
+        // drop proofs and check resource leak
+        self.frame.drop_proofs();
+
+        if self.frame.auth_zone().is_some() {
+            self.invoke_snode(SNodeRef::AuthZoneRef, "clear".to_string(), vec![])?;
+        }
+        self.check_resource()?;
+
+        match start.zip(self.track.now_millis()) {
+            Some((start, end)) => re_info!(self, "Run ended: time elapsed = {} ms", end - start),
+            None => re_info!(self, "Run ended"),
+        }
+
+        Ok((output, moving_nodes))
+    }
+
+    /// Calls a function/method.
+    pub fn invoke_snode(
+        &mut self,
+        snode_ref: SNodeRef,
+        function: String,
+        args: Vec<ScryptoValue>,
+    ) -> Result<ScryptoValue, RuntimeError> {
+        if !self.track.has_hooks() {
+            return self.invoke_snode_internal(snode_ref, function, args);
+        }
+
+        self.track.pre_invoke(self.depth, &snode_ref, &function, &args)?;
+        let result = self.invoke_snode_internal(snode_ref.clone(), function.clone(), args);
+        self.track.post_invoke(self.depth, &snode_ref, &function, &result)?;
+        result
+    }
+
+    fn invoke_snode_internal(
+        &mut self,
+        snode_ref: SNodeRef,
+        function: String,
+        args: Vec<ScryptoValue>,
+    ) -> Result<ScryptoValue, RuntimeError> {
+        self.track
+            .consume_cost_units(self.track.fee_table().invoke_snode(), "invoke_snode")
+            .map_err(RuntimeError::CostingError)?;
+        if let SNodeRef::Scrypto(..) = snode_ref {
+            self.track.check_size_limit(
+                SizeLimitKind::CallData,
+                args.iter().map(|arg| arg.raw.len()).sum(),
+            )?;
+        }
+        self.track.record_invocation(Self::snode_type(&snode_ref));
+
+        // Authorization and state load
+        let (mut snode, method_auths) = match &snode_ref {
+            SNodeRef::PackageStatic => Ok((SNodeState::PackageStatic, vec![])),
+            SNodeRef::PackageRef(package_address) => {
+                let package = self.track.borrow_global_mut_package(*package_address)?;
+                let method_auth = package.get_auth(&function);
+                Ok((SNodeState::PackageRef(*package_address, package), vec![method_auth]))
+            }
+            SNodeRef::AuthZoneRef => {
+                if let Some(auth_zone) = self.frame.take_auth_zone() {
+                    Ok((SNodeState::AuthZone(auth_zone), vec![]))
+                } else {
+                    Err(RuntimeError::AuthZoneDoesNotExist)
+                }
+            }
+            SNodeRef::WorktopRef => {
+                if let Some(worktop) = self.frame.take_worktop() {
+                    Ok((SNodeState::Worktop(worktop), vec![]))
+                } else {
+                    Err(RuntimeError::WorktopDoesNotExist)
+                }
+            }
+            SNodeRef::Scrypto(actor) => {
+                match actor {
+                    ScryptoActor::Blueprint(package_address, blueprint_name) => {
+                        let export_name = format!("{}_main", blueprint_name);
+                        Ok((
+                            SNodeState::Scrypto(
+                                ScryptoActorInfo::blueprint(
+                                    package_address.clone(),
+                                    blueprint_name.clone(),
+                                    export_name.clone(),
+                                ),
+                                None,
+                            ),
+                            vec![],
+                        ))
+                    }
+                    ScryptoActor::Component(component_address) => {
+                        let mut component = self
+                            .track
+                            .borrow_global_mut_component(component_address.clone())?;
+                        let package_address = component.package_address();
+                        let blueprint_name = component.blueprint_name().to_string();
+                        let export_name = format!("{}_main", blueprint_name);
+
+                        let package = self.track.get_package(&package_address)?;
+                        let package_version = package.version();
+                        let migratable = package.is_migratable(&blueprint_name);
+                        // TODO: Remove clone
+                        let schema = package
+                            .load_blueprint_schema(&blueprint_name)
+                            .unwrap()
+                            .clone();
+
+                        // The state is migrated the first time the component is called after
+                        // its package has been upgraded
+                        if component.package_version() < package_version {
+                            self.migrate_component_state(
+                                &mut component,
+                                package_version,
+                                migratable,
+                            )?;
+                        }
+
+                        if !component.is_method_enabled(&function) {
+                            return Err(RuntimeError::MethodDisabled {
+                                component_address: component_address.clone(),
+                                method: function,
+                            });
+                        }
+
+                        let (_, mut method_auths) =
+                            component.method_authorization(&schema, &function);
+                        if component_address.eq(&SYSTEM_COMPONENT) {
+                            method_auths.push(System::get_auth(&function));
+                        }
+                        Ok((
+                            SNodeState::Scrypto(
+                                ScryptoActorInfo::component(
+                                    package_address,
+                                    blueprint_name,
+                                    export_name,
+                                    component_address.clone(),
+                                ),
+                                Some(component),
+                            ),
+                            method_auths,
+                        ))
+                    }
+                }
+            }
+            SNodeRef::ComponentRef(component_address) => {
+                let component = self.track.borrow_global_mut_component(*component_address)?;
+                let method_auth = component.get_auth(&function);
+                Ok((
+                    SNodeState::GlobalNodeRef(
+                        RENodeId::Component(*component_address),
+                        RENode::Component(component),
+                    ),
+                    vec![method_auth],
+                ))
+            }
+            SNodeRef::ResourceStatic => Ok((SNodeState::ResourceStatic, vec![])),
+            SNodeRef::ResourceRef(resource_address) => {
+                let resource_manager: ResourceManager = self
+                    .track
+                    .borrow_global_mut_resource_manager(resource_address.clone())?;
+                let method_auth = resource_manager.get_auth(&function, &args).clone();
+                Ok((
+                    SNodeState::ResourceRef(resource_address.clone(), resource_manager),
+                    vec![method_auth],
+                ))
+            }
+            SNodeRef::Bucket(bucket_id) => {
+                let bucket = self.frame.take_bucket(*bucket_id)?;
+                let resource_address = bucket.resource_address();
+                let method_auth = self
+                    .track
+                    .get_resource_manager(&resource_address)?
+                    .get_auth(&function, &args);
+                Ok((SNodeState::Bucket(bucket), vec![method_auth.clone()]))
+            }
+            SNodeRef::BucketRef(bucket_id) => {
+                let bucket = self.frame.take_bucket(*bucket_id)?;
+                let resource_address = bucket.resource_address();
+                let method_auth = self
+                    .track
+                    .get_resource_manager(&resource_address)?
+                    .get_auth(&function, &args);
+                Ok((
+                    SNodeState::BucketRef(bucket_id.clone(), bucket),
+                    vec![method_auth.clone()],
+                ))
+            }
+            SNodeRef::ProofRef(proof_id) => {
+                let proof = self.frame.take_proof(*proof_id)?;
+                Ok((SNodeState::ProofRef(proof_id.clone(), proof), vec![]))
+            }
+            SNodeRef::Proof(proof_id) => {
+                let proof = self.frame.take_proof(*proof_id)?;
+                Ok((SNodeState::Proof(proof), vec![]))
+            }
+            SNodeRef::VaultRef(vault_id) => {
+                let (component, vault) = if let Some(vault) = self.frame.borrow_vault_mut(vault_id) {
+                    (None, vault)
+                } else if !self.frame.has_vault_ref(vault_id) {
+                    return Err(RuntimeError::VaultNotFound(*vault_id));
+                } else if let Some(component_address) = self.actor_component_address() {
+                    let vault = self.track.borrow_vault_mut(&component_address, vault_id)?;
+                    (Some(component_address), vault)
+                } else {
+                    panic!("Should never get here");
+                };
+
+                let resource_address = vault.resource_address();
+                let method_auth = self
+                    .track
+                    .get_resource_manager(&resource_address)?
+                    .get_auth(&function, &args);
+                Ok((
+                    SNodeState::VaultRef(vault_id.clone(), component, vault),
+                    vec![method_auth.clone()],
+                ))
+            }
+            SNodeRef::LockerStatic => {
+                // Creating a locker deposits the bucket, so the resource's deposit rule applies
+                let mut method_auths = Vec::new();
+                if function == "create" {
+                    // Lockers are owned by a component, so unless the component creates one
+                    // itself, its rule for `create_locker` applies, which is its default rule
+                    // unless declared, e.g. the owner's signature for accounts
+                    if let Some(Ok(component_address)) = args
+                        .get(0)
+                        .map(|arg| scrypto_decode::<ComponentAddress>(&arg.raw))
+                    {
+                        if self.actor_component_address() != Some(component_address)
+                            && self.track.component_exists(component_address)
+                        {
+                            let component = self.track.get_component(component_address)?;
+                            let package_address = component.package_address();
+                            let blueprint_name = component.blueprint_name().to_string();
+                            let schema = self
+                                .track
+                                .get_package(&package_address)?
+                                .load_blueprint_schema(&blueprint_name)
+                                .unwrap()
+                                .clone();
+                            let (_, component_auths) = self
+                                .track
+                                .get_component(component_address)?
+                                .method_authorization(&schema, "create_locker");
+                            method_auths.extend(component_auths);
+                        }
+                    }
+                    if let Some(Ok(bucket)) = args
+                        .get(1)
+                        .map(|arg| scrypto_decode::<scrypto::resource::Bucket>(&arg.raw))
+                    {
+                        let resource_address = self.frame.get_bucket(bucket.0)?.resource_address();
+                        let method_auth = self
+                            .track
+                            .get_resource_manager(&resource_address)?
+                            .get_auth("put_into_vault", &[]);
+                        method_auths.push(method_auth.clone());
+                    }
+                }
+                Ok((SNodeState::LockerStatic, method_auths))
+            }
+            SNodeRef::LockerRef(component_address, locker_id) => {
+                let locker = self
+                    .track
+                    .borrow_locker_mut(*component_address, *locker_id)?;
+                let mut method_auths = vec![locker.get_auth(&function)];
+                // Claiming withdraws from the locker, so the resource's withdraw rule applies
+                if function == "claim" {
+                    let resource_address = locker.resource_address();
+                    let method_auth = self
+                        .track
+                        .get_resource_manager(&resource_address)?
+                        .get_auth("take_from_vault", &[]);
+                    method_auths.push(method_auth.clone());
+                }
+                Ok((
+                    SNodeState::GlobalNodeRef(
+                        RENodeId::Locker(*component_address, *locker_id),
+                        RENode::Locker(locker),
+                    ),
+                    method_auths,
+                ))
+            }
+            SNodeRef::AllowanceRef(allowance_id) => {
+                let allowance = self.track.borrow_global_mut_allowance(*allowance_id)?;
+                let mut method_auths = vec![allowance.get_auth(&function)];
+                // Redeeming withdraws from the granting vault, so the resource's current
+                // withdraw rule applies, not just the one in place when it was granted
+                if function == "redeem_allowance" {
+                    let resource_address = allowance.resource_address();
+                    let method_auth = self
+                        .track
+                        .get_resource_manager(&resource_address)?
+                        .get_auth("take_from_vault", &[]);
+                    method_auths.push(method_auth.clone());
+                }
+                Ok((
+                    SNodeState::GlobalNodeRef(
+                        RENodeId::Allowance(*allowance_id),
+                        RENode::Allowance(allowance),
+                    ),
+                    method_auths,
+                ))
+            }
+        }?;
+
+        // Authorization check
+        if !method_auths.is_empty() {
+            let caller_identity = AuthZone::new_with_proofs(self.caller_identity_proofs()?);
+            let mut auth_zones = vec![&caller_identity];
+            if let Some(self_auth_zone) = self.frame.auth_zone() {
+                auth_zones.push(self_auth_zone);
+            }
+
+            match &snode {
+                // Resource auth check includes caller
+                SNodeState::ResourceRef(_, _) | SNodeState::VaultRef(_, _, _) | SNodeState::BucketRef(_, _) | SNodeState::Bucket(_) | SNodeState::PackageRef(_, _) | SNodeState::GlobalNodeRef(RENodeId::Component(_), _) => {
+                    if let Some(auth_zone) = self.caller_auth_zone {
+                        auth_zones.push(auth_zone);
+                    }
+                }
+                // Extern call auth check
+                _ => { }
+            };
+
+            for method_auth in method_auths {
+                method_auth
+                    .check(&auth_zones)
+                    .map_err(|error| RuntimeError::AuthorizationError {
+                        function: function.clone(),
+                        explanation: method_auth.explain(&auth_zones),
+                        authorization: method_auth,
+                        error
+                    })?;
+            }
+        }
+
+        // Execution
+        let result = match snode {
+            SNodeState::Proof(proof) => {
+                proof.main_consume(function.as_str())
+                    .map_err(RuntimeError::ProofError)
+            },
+            SNodeState::Bucket(bucket) => match function.as_str() {
+                "burn" => bucket.drop(self).map_err(RuntimeError::BucketError),
+                _ => Err(RuntimeError::IllegalSystemCall),
+            },
+            _ => {
+                // Figure out what nodes to move from this frame
+                let mut moving_nodes = BTreeMap::new();
+                for arg in &args {
+                    moving_nodes.extend(
+                        self.send_nodes(&RENodeId::from_value(arg), MoveMethod::AsArgument)?,
+                    );
+                }
+
+                // start a new process
+                let process_auth_zone = if matches!(snode, SNodeState::Scrypto(_, _)) {
+                    Some(AuthZone::new())
+                } else {
+                    None
+                };
+
+                let mut process = Kernel::new(
+                    self.depth + 1,
+                    self.trace,
+                    self.track,
+                    process_auth_zone,
+                    None,
+                    moving_nodes,
+                );
+                if let Some(auth_zone) = self.frame.auth_zone() {
+                    process.caller_auth_zone = Option::Some(auth_zone);
+                }
+                if matches!(snode, SNodeState::Scrypto(_, _)) {
+                    process.caller = self.actor();
+                }
+
+                // invoke the main function
+                let (result, received_nodes) = process.run(&mut snode, function, args)?;
+
+                // move nodes to this frame.
+                self.frame.receive(received_nodes);
+
+                // Return borrowed snodes
+                match snode {
+                    SNodeState::AuthZone(auth_zone) => {
+                        self.frame.return_auth_zone(auth_zone);
+                    }
+                    SNodeState::Worktop(worktop) => {
+                        self.frame.return_worktop(worktop);
+                    }
+                    SNodeState::Scrypto(actor, component_state) => {
+                        if let Some(component_address) = actor.component_address() {
+                            self.track.return_borrowed_global_component(
+                                component_address,
+                                component_state.unwrap(),
+                            );
+                        }
+                    }
+                    SNodeState::GlobalNodeRef(node_id, node) => {
+                        self.track.return_borrowed_node(node_id, node);
+                    }
+                    SNodeState::PackageRef(package_address, package) => {
+                        self.track.return_borrowed_global_package(package_address, package);
+                    }
+                    SNodeState::ResourceRef(resource_address, resource_manager) => {
+                        self.track.return_borrowed_global_resource_manager(
+                            resource_address,
+                            resource_manager,
+                        );
+                    }
+                    SNodeState::BucketRef(bucket_id, bucket) => {
+                        self.frame.return_bucket(bucket_id, bucket);
+                    }
+                    SNodeState::ProofRef(proof_id, proof) => {
+                        self.frame.return_proof(proof_id, proof);
+                    }
+                    SNodeState::VaultRef(vault_id, maybe_component_address, vault) => {
+                        if let Some(component_address) = maybe_component_address {
+                            self.track.return_borrowed_vault(&component_address, &vault_id, vault);
+                        } else {
+                            self.frame.return_borrowed_vault_mut(vault);
+                        }
+                    }
+                    _ => {}
+                }
+
+                Ok(result)
+            }
+        }?;
+
+        Ok(result)
+    }
+
+    /// Calls the ABI generator of a blueprint.
+    // TODO: Remove
+    pub fn call_abi(
+        &mut self,
+        package_address: PackageAddress,
+        blueprint_name: &str,
+    ) -> Result<ScryptoValue, RuntimeError> {
+        re_debug!(self, "Call abi started");
+
+        let mut snode = SNodeState::Scrypto(
+            ScryptoActorInfo::blueprint(
+                package_address,
+                blueprint_name.to_string(),
+                format!("{}_abi", blueprint_name),
+            ),
+            None,
+        );
+
+        let mut process = Kernel::new(self.depth + 1, self.trace, self.track, None, None, BTreeMap::new());
+        let result = process
+            .run(&mut snode, String::new(), Vec::new())
+            .map(|(r, _)| r);
+
+        re_debug!(self, "Call abi ended");
+        result
+    }
+
+    /// Migrates the state of a component to the latest version of its package, without calling
+    /// the component.
+    ///
+    /// Returns whether the state was behind the package.
+    pub fn migrate_component(
+        &mut self,
+        component_address: ComponentAddress,
+    ) -> Result<bool, RuntimeError> {
+        let mut component = self.track.borrow_global_mut_component(component_address)?;
+        let package_address = component.package_address();
+        let package = self.track.get_package(&package_address)?;
+        let package_version = package.version();
+        let migratable = package.is_migratable(component.blueprint_name());
+
+        let outdated = component.package_version() < package_version;
+        if outdated {
+            self.migrate_component_state(&mut component, package_version, migratable)?;
+        }
+        self.track
+            .return_borrowed_global_component(component_address, component);
+        Ok(outdated)
+    }
+
+    /// Converts the state of a component written by an older version of its package, with the
+    /// `migrate_state` function of its blueprint.
+    ///
+    /// Blueprints without the function are assumed to have kept their state schema.
+    fn migrate_component_state(
+        &mut self,
+        component: &mut Component,
+        package_version: u32,
+        migratable: bool,
+    ) -> Result<(), RuntimeError> {
+        if migratable {
+            re_debug!(
+                self,
+                "Migrating component state to version {}",
+                package_version
+            );
+            let old_state = ScryptoValue::from_slice(component.state())
+                .map_err(RuntimeError::ParseScryptoValueError)?;
+            let rtn = self.invoke_snode(
+                SNodeRef::Scrypto(ScryptoActor::Blueprint(
+                    component.package_address(),
+                    component.blueprint_name().to_string(),
+                )),
+                MIGRATE_STATE_FUNCTION.to_string(),
+                vec![ScryptoValue::from_value(&old_state.raw)],
+            )?;
+            let new_state: Vec<u8> =
+                scrypto_decode(&rtn.raw).map_err(|_| RuntimeError::InvalidStateMigration)?;
+            let new_value = ScryptoValue::from_slice(&new_state)
+                .map_err(|_| RuntimeError::InvalidStateMigration)?;
+
+            // The new state must own exactly the vaults and lazy maps of the old state
+            if !new_value.bucket_ids.is_empty()
+                || !new_value.proof_ids.is_empty()
+                || new_value.vault_ids != old_state.vault_ids
+                || new_value.lazy_map_ids != old_state.lazy_map_ids
+            {
+                return Err(RuntimeError::InvalidStateMigration);
+            }
+            self.track
+                .check_size_limit(SizeLimitKind::ComponentState, new_state.len())?;
+            component.set_state(new_state);
+        }
+        component.set_package_version(package_version);
+        Ok(())
+    }
+
+    /// Runs a `#[scrypto_test]` function of a package.
+    pub fn call_test(
+        &mut self,
+        package_address: PackageAddress,
+        test_name: &str,
+    ) -> Result<ScryptoValue, RuntimeError> {
+        re_debug!(self, "Call test started");
+
+        let mut snode = SNodeState::Scrypto(
+            ScryptoActorInfo::blueprint(
+                package_address,
+                test_name.to_string(),
+                format!("{}{}", TEST_EXPORT_PREFIX, test_name),
+            ),
+            None,
+        );
+
+        let mut process = Kernel::new(self.depth + 1, self.trace, self.track, None, None, BTreeMap::new());
+        let result = process
+            .run(&mut snode, String::new(), Vec::new())
+            .map(|(r, _)| r);
+
+        re_debug!(self, "Call test ended");
+        result
+    }
+
+    /// Checks resource leak.
+    fn check_resource(&self) -> Result<(), RuntimeError> {
+        re_debug!(self, "Resource check started");
+        for (node_id, node) in self.frame.nodes() {
+            match node {
+                RENode::Bucket(bucket) => re_warn!(
+                    self,
+                    "Dangling bucket: {:?}, {}",
+                    node_id,
+                    format_bucket(bucket, &|r| self.track.resource_symbol(r))
+                ),
+                RENode::Vault(vault) => re_warn!(
+                    self,
+                    "Dangling vault: {:?}, {}",
+                    node_id,
+                    format_vault(vault, &|r| self.track.resource_symbol(r))
+                ),
+                RENode::LazyMap(lazy_map) => {
+                    re_warn!(self, "Dangling lazy map: {:?}, {:?}", node_id, lazy_map)
+                }
+                _ => {}
+            }
+        }
+        if let Some(worktop) = self.frame.worktop() {
+            if !worktop.is_empty() {
+                re_warn!(
+                    self,
+                    "Resource worktop is not empty: {}",
+                    format_worktop(worktop, &|r| self.track.resource_symbol(r))
+                );
+            }
+        }
+        re_debug!(self, "Resource check ended");
+
+        self.frame
+            .check_resource()
+            .map_err(RuntimeError::ResourceCheckFailure)
+    }
+
+    /// Writes a trace message to the log sink of the track.
+    pub fn log(&self, level: Level, msg: String) {
+        self.track.write_trace(self.depth, level, &msg);
+    }
+
+    /// Rejects buckets and proofs, which can't outlive the transaction.
+    fn check_no_transient_values(validated: &ScryptoValueIds) -> Result<(), RuntimeError> {
+        for node_id in RENodeId::from_value_ids(validated) {
+            node_id.verify_can_persist()?;
+        }
+        Ok(())
+    }
+
+    /// Process and validate the key of a lazy map entry, which can't own any object.
+    fn process_key_data(data: &[u8]) -> Result<(), RuntimeError> {
+        let validated =
+            ScryptoValueIds::from_slice(data).map_err(RuntimeError::ParseScryptoValueError)?;
+        Self::check_no_transient_values(&validated)?;
+        for node_id in RENodeId::from_value_ids(&validated) {
+            node_id.verify_can_move()?;
+        }
+        Ok(())
+    }
+
+    /// Process and parse entry data from any component object (components and maps)
+    fn process_entry_data(data: &[u8]) -> Result<ComponentObjectRefs, RuntimeError> {
+        let validated =
+            ScryptoValueIds::from_slice(data).map_err(RuntimeError::ParseScryptoValueError)?;
+        Self::check_no_transient_values(&validated)?;
+
+        let mut lazy_map_ids = BTreeSet::new();
+        for lazy_map_id in validated.lazy_map_ids {
+            if lazy_map_ids.contains(&lazy_map_id) {
+                return Err(RuntimeError::DuplicateLazyMap(lazy_map_id));
+            }
+            lazy_map_ids.insert(lazy_map_id);
+        }
+
+        let mut vault_ids = BTreeSet::new();
+        for vault_id in validated.vault_ids {
+            if vault_ids.contains(&vault_id) {
+                return Err(RuntimeError::DuplicateVault(vault_id));
+            }
+            vault_ids.insert(vault_id);
+        }
+
+        // lazy map allowed
+        // vaults allowed
+        Ok(ComponentObjectRefs {
+            lazy_map_ids,
+            vault_ids,
+        })
+    }
+
+    /// Sends nodes to another component/blueprint, either as argument or return
+    fn send_nodes(
+        &mut self,
+        node_ids: &[RENodeId],
+        method: MoveMethod,
+    ) -> Result<BTreeMap<RENodeId, RENode>, RuntimeError> {
+        let nodes = self.frame.move_nodes(node_ids, method)?;
+        for (node_id, node) in &nodes {
+            match node {
+                RENode::Bucket(bucket) => re_debug!(
+                    self,
+                    "Moving bucket: {:?}, {}",
+                    node_id,
+                    format_bucket(bucket, &|r| self.track.resource_symbol(r))
+                ),
+                RENode::Proof(proof) => re_debug!(
+                    self,
+                    "Moving proof: {:?}, {}",
+                    node_id,
+                    format_proof(proof, &|r| self.track.resource_symbol(r))
+                ),
+                // Only buckets and proofs can move
+                _ => {}
+            }
+        }
+        Ok(nodes)
+    }
+
+    /// Send a byte array to wasm instance.
+    ///
+    /// The bytes are copied straight into the buffer allocated by the guest.
+    fn send_bytes(&mut self, bytes: &[u8]) -> Result<i32, RuntimeError> {
+        let wasm_process = self.wasm_process_state.as_ref().unwrap();
+        let result = wasm_process.vm.module.invoke_export(
+            "scrypto_alloc",
+            &[RuntimeValue::I32((bytes.len()) as i32)],
+            &mut NopExternals,
+        );
+
+        if let Ok(Some(RuntimeValue::I32(ptr))) = result {
+            let mut direct = wasm_process.vm.memory.direct_access_mut();
+            let start = (ptr as u32).checked_add(4);
+            if let Some(buffer) =
+                start.and_then(|s| guest_slice_mut(direct.as_mut(), s, bytes.len()))
+            {
+                buffer.copy_from_slice(bytes);
+                return Ok(ptr);
+            }
+        }
+
+        Err(RuntimeError::MemoryAllocError)
+    }
+
+    fn read_return_value(&mut self, ptr: u32) -> Result<ScryptoValue, RuntimeError> {
+        let wasm_process = self.wasm_process_state.as_ref().unwrap();
+        let direct = wasm_process.vm.memory.direct_access();
+        let buffer = direct.as_ref();
+
+        // read length
+        let len = guest_slice(buffer, ptr, 4).ok_or(RuntimeError::MemoryAccessError)?;
+        let len = u32::from_le_bytes(len.try_into().unwrap());
+
+        let start = ptr.checked_add(4).ok_or(RuntimeError::MemoryAccessError)?;
+        let value =
+            guest_slice(buffer, start, len as usize).ok_or(RuntimeError::MemoryAccessError)?;
+
+        ScryptoValue::from_slice(value).map_err(RuntimeError::ParseScryptoValueError)
+    }
+
+    /// Handles a system call.
+    fn handle(&mut self, args: RuntimeArgs) -> Result<Option<RuntimeValue>, Trap> {
+        let op: u32 = args.nth_checked(0)?;
+        let input_ptr: u32 = args.nth_checked(1)?;
+        let input_len: u32 = args.nth_checked(2)?;
+        // SECURITY: bill before allocating memory
+        let fee_table = self.track.fee_table();
+        let input_cost = input_len
+            .saturating_mul(fee_table.engine_call_per_byte())
+            .saturating_add(fee_table.engine_call());
+        self.track
+            .consume_cost_units(input_cost, "engine_call")
+            .map_err(|e| Trap::from(RuntimeError::CostingError(e)))?;
+
+        let output_bytes = self
+            .dispatch(
+                op,
+                RequestData::Guest {
+                    ptr: input_ptr,
+                    len: input_len,
+                },
+            )
+            .map_err(Trap::from)?;
+        let output_cost = (output_bytes.len() as u32)
+            .saturating_mul(self.track.fee_table().engine_call_per_byte());
+        self.track
+            .consume_cost_units(output_cost, "engine_call")
+            .map_err(|e| Trap::from(RuntimeError::CostingError(e)))?;
+        let output_ptr = self.send_bytes(&output_bytes).map_err(Trap::from)?;
+
+        Ok(Some(RuntimeValue::I32(output_ptr)))
+    }
+
+    /// Routes a system call to its handler, returning the encoded output.
+    fn dispatch(&mut self, op: u32, input: RequestData) -> Result<Vec<u8>, RuntimeError> {
+        match op {
+            CREATE_COMPONENT => self.call_handler(op, input, Self::handle_create_component),
+            GET_COMPONENT_INFO => self.call_handler(op, input, Self::handle_get_component_info),
+            GET_COMPONENT_STATE => self.call_handler(op, input, Self::handle_get_component_state),
+            PUT_COMPONENT_STATE => self.call_handler(op, input, Self::handle_put_component_state),
+            SET_ACCESS_RULE => self.call_handler(op, input, Self::handle_set_access_rule),
+            SET_METHOD_ENABLED => self.call_handler(op, input, Self::handle_set_method_enabled),
+            IS_FEATURE_ENABLED => self.call_handler(op, input, Self::handle_is_feature_enabled),
+
+            CREATE_LAZY_MAP => self.call_handler(op, input, Self::handle_create_lazy_map),
+            GET_LAZY_MAP_ENTRY => self.call_handler(op, input, Self::handle_get_lazy_map_entry),
+            PUT_LAZY_MAP_ENTRY => self.call_handler(op, input, Self::handle_put_lazy_map_entry),
+            CREATE_READABLE_LAZY_MAP => {
+                self.call_handler(op, input, Self::handle_create_readable_lazy_map)
+            }
+            GET_READABLE_LAZY_MAP_ENTRY => {
+                self.call_handler(op, input, Self::handle_get_readable_lazy_map_entry)
+            }
+
+            CREATE_EMPTY_VAULT => self.call_handler(op, input, Self::handle_create_vault),
+
+            INVOKE_SNODE => self.call_handler(op, input, Self::handle_invoke_snode),
+            BATCH => self.call_handler(op, input, Self::handle_batch),
+
+            EMIT_LOG => self.call_handler(op, input, Self::handle_emit_log),
+            GET_CALL_DATA => self.call_handler(op, input, Self::handle_get_call_data),
+            GET_TRANSACTION_HASH => self.call_handler(op, input, Self::handle_get_transaction_hash),
+            GET_TRANSACTION_SIGNERS => {
+                self.call_handler(op, input, Self::handle_get_transaction_signers)
+            }
+            GET_CURRENT_EPOCH => self.call_handler(op, input, Self::handle_get_current_epoch),
+            GENERATE_UUID => self.call_handler(op, input, Self::handle_generate_uuid),
+            GET_ACTOR => self.call_handler(op, input, Self::handle_get_actor),
+            GET_CALLER => self.call_handler(op, input, Self::handle_get_caller),
+
+            EMIT_EVENT => self.call_handler(op, input, Self::handle_emit_event),
+            SUBSCRIBE_TO_EVENT => self.call_handler(op, input, Self::handle_subscribe_to_event),
+            UNSUBSCRIBE_FROM_EVENT => {
+                self.call_handler(op, input, Self::handle_unsubscribe_from_event)
+            }
+            TAKE_NOTIFICATIONS => self.call_handler(op, input, Self::handle_take_notifications),
+            SET_EVENT_SUBSCRIBABLE => {
+                self.call_handler(op, input, Self::handle_set_event_subscribable)
+            }
+
+            CHECK_ACCESS_RULE => self.call_handler(op, input, Self::handle_check_access_rule),
+            RAISE_ERROR => self.call_handler(op, input, Self::handle_raise_error),
+            RAISE_PANIC => self.call_handler(op, input, Self::handle_raise_panic),
+
+            _ => Err(RuntimeError::InvalidRequestCode(op)),
+        }
+    }
+
+    fn call_handler<I: Decode + fmt::Debug, O: Encode + fmt::Debug>(
+        &mut self,
+        op: u32,
+        input: RequestData,
+        handler: fn(&mut Self, input: I) -> Result<O, RuntimeError>,
+    ) -> Result<Vec<u8>, RuntimeError> {
+        let (input, input_len): (I, usize) = match input {
+            // The request is decoded in place, so no intermediate buffer is allocated and zeroed
+            RequestData::Guest { ptr, len } => {
+                let wasm_process = self.wasm_process_state.as_ref().unwrap();
+                let direct = wasm_process.vm.memory.direct_access();
+                let input_bytes = guest_slice(direct.as_ref(), ptr, len as usize)
+                    .ok_or(RuntimeError::MemoryAccessError)?;
+                let input =
+                    scrypto_decode(input_bytes).map_err(RuntimeError::InvalidRequestData)?;
+                (input, len as usize)
+            }
+            RequestData::Batched(bytes) => (
+                scrypto_decode(&bytes).map_err(RuntimeError::InvalidRequestData)?,
+                bytes.len(),
+            ),
+        };
+        if input_len <= 1024 {
+            re_trace!(self, "{:?}", input);
+        } else {
+            re_trace!(self, "Large request: op = {:02x}, len = {}", op, input_len);
+        }
+
+        let output: O = handler(self, input)?;
+        let output_bytes = scrypto_encode(&output);
+        if output_bytes.len() <= 1024 {
+            re_trace!(self, "{:?}", output);
+        } else {
+            re_trace!(
+                self,
+                "Large response: op = {:02x}, len = {}",
+                op,
+                output_bytes.len()
+            );
+        }
+
+        Ok(output_bytes)
+    }
+
+    //============================
+    // SYSTEM CALL HANDLERS START
+    //============================
+
+    fn handle_create_component(
+        &mut self,
+        input: CreateComponentInput,
+    ) -> Result<CreateComponentOutput, RuntimeError> {
+        self.track
+            .check_size_limit(SizeLimitKind::ComponentState, input.state.len())?;
+        let data = Self::process_entry_data(&input.state)?;
+        let new_objects = self.frame.take_owned_objects(data)?;
+
+        let wasm_process = self
+            .wasm_process_state
+            .as_mut()
+            .ok_or(RuntimeError::IllegalSystemCall)?;
+        let package_address = wasm_process.vm.actor.package_address().clone();
+        let package = self.track.get_package(&package_address)?;
+        if let Some(feature) = input
+            .features
+            .iter()
+            .find(|feature| !package.is_feature_declared(&input.blueprint_name, feature))
+        {
+            return Err(RuntimeError::FeatureNotDeclared {
+                blueprint_name: input.blueprint_name,
+                feature: feature.clone(),
+            });
+        }
+        let package_version = package.version();
+        // Slots are resolved against the component access rules, so they can't refer to slots
+        if input.method_toggle_auth.is_slot()
+            || input.access_rule_slots.values().any(|(rule, mutability)| {
+                rule.is_slot() || matches!(mutability, Mutability::MUTABLE(rule) if rule.is_slot())
+            })
+        {
+            return Err(RuntimeError::AccessRuleSlotNotAllowed);
+        }
+        let mut component = Component::new(
+            package_address,
+            input.blueprint_name,
+            input.access_rules_list,
+            input.state,
+        );
+        component.set_access_rule_slots(input.access_rule_slots);
+        component.set_method_toggle_auth(input.method_toggle_auth);
+        component.set_features(input.features);
+        component.set_package_version(package_version);
+        component.add_readable_lazy_maps(
+            self.track.readable_lazy_map_ids(new_objects.lazy_map_ids()),
+        );
+        let component_address = self.track.create_component(component);
+        self.track
+            .insert_objects_into_component(new_objects, component_address);
+
+        Ok(CreateComponentOutput { component_address })
+    }
+
+    fn handle_get_component_info(
+        &mut self,
+        input: GetComponentInfoInput,
+    ) -> Result<GetComponentInfoOutput, RuntimeError> {
+        // The component whose method is running is borrowed, so it's read from the process
+        if self.actor_component_address() == Some(input.component_address) {
+            if let Ok(component) = self.current_component_mut() {
+                return Ok(GetComponentInfoOutput {
+                    package_address: component.package_address(),
+                    blueprint_name: component.blueprint_name().to_owned(),
+                });
+            }
+        }
+        let component = self.track.get_component(input.component_address)?;
+
+        Ok(GetComponentInfoOutput {
+            package_address: component.package_address(),
+            blueprint_name: component.blueprint_name().to_owned(),
+        })
+    }
+
+    fn handle_get_component_state(
+        &mut self,
+        _: GetComponentStateInput,
+    ) -> Result<GetComponentStateOutput, RuntimeError> {
+        let wasm_process = self
+            .wasm_process_state
+            .as_mut()
+            .ok_or(RuntimeError::IllegalSystemCall)?;
+        let component_state = match &wasm_process.interpreter_state {
+            InterpreterState::Component { component, initial_loaded_object_refs, .. } => {
+                self.frame.add_refs(initial_loaded_object_refs.clone());
+                Ok(component.state())
+            },
+            _ => Err(RuntimeError::IllegalSystemCall),
+        }?;
+        let state = component_state.to_vec();
+        Ok(GetComponentStateOutput { state })
+    }
+
+    fn handle_put_component_state(
+        &mut self,
+        input: PutComponentStateInput,
+    ) -> Result<PutComponentStateOutput, RuntimeError> {
+        self.track
+            .check_size_limit(SizeLimitKind::ComponentState, input.state.len())?;
+        let wasm_process = self
+            .wasm_process_state
+            .as_mut()
+            .ok_or(RuntimeError::IllegalSystemCall)?;
+        let (component, new_set, component_address) = match &mut wasm_process.interpreter_state {
+            InterpreterState::Component {
+                ref mut component,
+                component_address,
+                initial_loaded_object_refs,
+                ..
+            } => {
+                let mut new_set = Self::process_entry_data(&input.state)?;
+                new_set.remove(&initial_loaded_object_refs)?;
+                Ok((component, new_set, component_address))
+            }
+            _ => Err(RuntimeError::IllegalSystemCall),
+        }?;
+
+        let new_objects = self.frame.take_owned_objects(new_set)?;
+        component.add_readable_lazy_maps(
+            self.track.readable_lazy_map_ids(new_objects.lazy_map_ids()),
+        );
+        self.track.insert_objects_into_component(new_objects, *component_address);
+
+        // TODO: Verify that process_owned_objects is empty
+
+        component.set_state(input.state);
+
+        Ok(PutComponentStateOutput {})
+    }
+
+    fn handle_set_access_rule(
+        &mut self,
+        input: SetAccessRuleInput,
+    ) -> Result<SetAccessRuleOutput, RuntimeError> {
+        if input.rule.is_slot() {
+            return Err(RuntimeError::AccessRuleSlotNotAllowed);
+        }
+        let update_auth = self
+            .current_component_mut()?
+            .access_rule_slot_update_auth(&input.slot)
+            .ok_or(RuntimeError::AccessRuleSlotNotFound(input.slot.clone()))?
+            .clone();
+        self.check_component_update_auth("set_access_rule", &update_auth)?;
+
+        self.current_component_mut()?
+            .set_access_rule(&input.slot, input.rule);
+
+        Ok(SetAccessRuleOutput {})
+    }
+
+    fn handle_set_method_enabled(
+        &mut self,
+        input: SetMethodEnabledInput,
+    ) -> Result<SetMethodEnabledOutput, RuntimeError> {
+        let component = self.current_component_mut()?;
+        let package_address = component.package_address();
+        let blueprint_name = component.blueprint_name().to_owned();
+        let toggle_auth = component.method_toggle_auth().clone();
+        self.check_component_update_auth("set_method_enabled", &toggle_auth)?;
+        Component::check_method_declared(self, package_address, &blueprint_name, &input.method)?;
+
+        self.current_component_mut()?
+            .set_method_enabled(&input.method, input.enabled);
+
+        Ok(SetMethodEnabledOutput {})
+    }
+
+    fn handle_is_feature_enabled(
+        &mut self,
+        input: IsFeatureEnabledInput,
+    ) -> Result<IsFeatureEnabledOutput, RuntimeError> {
+        let wasm_process = self
+            .wasm_process_state
+            .as_ref()
+            .ok_or(RuntimeError::IllegalSystemCall)?;
+        let actor = &wasm_process.vm.actor;
+        let package = self.track.get_package(actor.package_address())?;
+        if !package.is_feature_declared(actor.blueprint_name(), &input.feature) {
+            return Err(RuntimeError::FeatureNotDeclared {
+                blueprint_name: actor.blueprint_name().to_owned(),
+                feature: input.feature,
+            });
+        }
+
+        let enabled = match &wasm_process.interpreter_state {
+            InterpreterState::Component { component, .. } => {
+                component.is_feature_enabled(&input.feature)
+            }
+            _ => return Err(RuntimeError::IllegalSystemCall),
+        };
+
+        Ok(IsFeatureEnabledOutput { enabled })
+    }
+
+    fn handle_create_lazy_map(
+        &mut self,
+        _input: CreateLazyMapInput,
+    ) -> Result<CreateLazyMapOutput, RuntimeError> {
+        let lazy_map_id = self.track.new_lazy_map_id();
+        self.frame.create_lazy_map(lazy_map_id);
+        Ok(CreateLazyMapOutput { lazy_map_id })
+    }
+
+    fn handle_create_readable_lazy_map(
+        &mut self,
+        _input: CreateReadableLazyMapInput,
+    ) -> Result<CreateReadableLazyMapOutput, RuntimeError> {
+        let lazy_map_id = self.track.new_readable_lazy_map_id();
+        self.frame.create_lazy_map(lazy_map_id);
+        Ok(CreateReadableLazyMapOutput { lazy_map_id })
+    }
+
+    fn handle_get_lazy_map_entry(
+        &mut self,
+        input: GetLazyMapEntryInput,
+    ) -> Result<GetLazyMapEntryOutput, RuntimeError> {
+        if let Some((_, value)) = self
+            .frame
+            .get_lazy_map_entry(&input.lazy_map_id, &input.key) {
+            return Ok(GetLazyMapEntryOutput { value });
+        }
+
+        if !self.frame.has_lazy_map_ref(&input.lazy_map_id) {
+            return Err(RuntimeError::LazyMapNotFound(input.lazy_map_id));
+        }
+
+        if let Some(WasmProcess { interpreter_state: InterpreterState::Component { component_address, .. }, .. }) = &self.wasm_process_state {
+            let value = self.track.get_lazy_map_entry(
+                *component_address,
+                &input.lazy_map_id,
+                &input.key,
+            );
+            if value.is_some() {
+                let map_entry_objects =
+                    Self::process_entry_data(&value.as_ref().unwrap()).unwrap();
+                self.frame.add_refs(map_entry_objects);
+            }
+
+            return Ok(GetLazyMapEntryOutput { value });
+        }
+
+        panic!("Should not get here.");
+    }
+
+    fn handle_get_readable_lazy_map_entry(
+        &mut self,
+        input: GetReadableLazyMapEntryInput,
+    ) -> Result<GetReadableLazyMapEntryOutput, RuntimeError> {
+        // Objects within the entry are not added to the references of this process, which
+        // keeps them read-only.
+        let value = self.track.get_readable_lazy_map_entry(
+            input.component_address,
+            &input.lazy_map_id,
+            &input.key,
+        )?;
+        Ok(GetReadableLazyMapEntryOutput { value })
+    }
+
+    fn handle_put_lazy_map_entry(
+        &mut self,
+        input: PutLazyMapEntryInput,
+    ) -> Result<PutLazyMapEntryOutput, RuntimeError> {
+        self.track
+            .check_size_limit(SizeLimitKind::LazyMapKey, input.key.len())?;
+        self.track
+            .check_size_limit(SizeLimitKind::LazyMapValue, input.value.len())?;
+        let wasm_process = self
+            .wasm_process_state
+            .as_mut()
+            .ok_or(RuntimeError::IllegalSystemCall)?;
+        let (old_value, lazy_map_state) = match self
+            .frame
+            .get_lazy_map_entry(&input.lazy_map_id, &input.key)
+        {
+            None => match &wasm_process.interpreter_state {
+                InterpreterState::Component {
+                    component_address,
+                    ..
+                } => {
+                    if !self.frame.has_lazy_map_ref(&input.lazy_map_id) {
+                        return Err(RuntimeError::LazyMapNotFound(input.lazy_map_id));
+                    }
+                    let old_value = self.track.get_lazy_map_entry(
+                        *component_address,
+                        &input.lazy_map_id,
+                        &input.key,
+                    );
+                    Ok((
+                        old_value,
+                        Committed {
+                            component_address: *component_address,
+                        },
+                    ))
+                }
+                _ => Err(RuntimeError::LazyMapNotFound(input.lazy_map_id)),
+            },
+            Some((root, value)) => Ok((value, Uncommitted { root })),
+        }?;
+        Self::process_key_data(&input.key)?;
+        let mut new_entry_object_refs = Self::process_entry_data(&input.value)?;
+        let old_entry_object_refs = match old_value {
+            None => ComponentObjectRefs::new(),
+            Some(e) => Self::process_entry_data(&e).unwrap(),
+        };
+        new_entry_object_refs.remove(&old_entry_object_refs)?;
+
+        // Check for cycles
+        if let Uncommitted { root } = lazy_map_state {
+            if new_entry_object_refs.lazy_map_ids.contains(&root) {
+                return Err(RuntimeError::CyclicLazyMap(root));
+            }
+        }
+
+        let new_objects = self.frame.take_owned_objects(new_entry_object_refs)?;
+
+        match lazy_map_state {
+            Uncommitted { root } => {
+                self.frame
+                    .insert_lazy_map_entry(&input.lazy_map_id, input.key, input.value);
+                self.frame.insert_objects_into_map(new_objects, &root);
+            }
+            Committed { component_address } => {
+                if let InterpreterState::Component { component, .. } =
+                    &mut wasm_process.interpreter_state
+                {
+                    component.add_readable_lazy_maps(
+                        self.track.readable_lazy_map_ids(new_objects.lazy_map_ids()),
+                    );
+                }
+                self.track.put_lazy_map_entry(
+                    component_address,
+                    input.lazy_map_id,
+                    input.key,
+                    input.value,
+                );
+                self.track
+                    .insert_objects_into_component(new_objects, component_address);
+            }
+        }
+
+        Ok(PutLazyMapEntryOutput {})
+    }
+
+    fn handle_create_vault(
+        &mut self,
+        input: CreateEmptyVaultInput,
+    ) -> Result<CreateEmptyVaultOutput, RuntimeError> {
+        let definition = self.track.get_resource_manager(&input.resource_address)?;
+
+        let new_vault = Vault::new(ResourceContainer::new_empty(
+            input.resource_address,
+            definition.resource_type(),
+        ));
+        let vault_id = self.track.new_vault_id();
+        self.frame.create_vault(vault_id, new_vault);
+
+        Ok(CreateEmptyVaultOutput { vault_id })
+    }
+
+    fn handle_invoke_snode(
+        &mut self,
+        input: InvokeSNodeInput,
+    ) -> Result<InvokeSNodeOutput, RuntimeError> {
+        let mut validated_args = Vec::new();
+        for arg in input.args {
+            validated_args.push(
+                ScryptoValue::from_slice(&arg).map_err(RuntimeError::ParseScryptoValueError)?,
+            );
+        }
+
+        let result = self.invoke_snode(input.snode_ref, input.function, validated_args)?;
+        Ok(InvokeSNodeOutput { rtn: result.raw })
+    }
+
+    fn handle_batch(&mut self, input: BatchInput) -> Result<BatchOutput, RuntimeError> {
+        let mut outputs = Vec::new();
+        for call in input.calls {
+            if call.op == BATCH {
+                return Err(RuntimeError::InvalidRequestCode(call.op));
+            }
+            // The bytes of the batch are billed once, by the enclosing engine call
+            self.track
+                .consume_cost_units(self.track.fee_table().engine_call(), "engine_call")
+                .map_err(RuntimeError::CostingError)?;
+            outputs.push(self.dispatch(call.op, RequestData::Batched(call.input))?);
+        }
+        Ok(BatchOutput { outputs })
+    }
+
+    fn handle_emit_log(&mut self, input: EmitLogInput) -> Result<EmitLogOutput, RuntimeError> {
+        let cost_units =
+            (input.message.len() as u32).saturating_mul(self.track.fee_table().emit_log_per_byte());
+        self.track
+            .consume_cost_units(cost_units, "emit_log")
+            .map_err(RuntimeError::CostingError)?;
+        self.track.add_log(input.level, input.message)?;
+
+        Ok(EmitLogOutput {})
+    }
+
+    /// Returns the address of the running component, as events can only be emitted and
+    /// observed by components.
+    fn running_component_address(&self) -> Result<ComponentAddress, RuntimeError> {
+        match &self.wasm_process_state {
+            Some(WasmProcess {
+                interpreter_state: InterpreterState::Component { component_address, .. },
+                ..
+            }) => Ok(*component_address),
+            _ => Err(RuntimeError::IllegalSystemCall),
+        }
+    }
+
+    fn handle_emit_event(&mut self, input: EmitEventInput) -> Result<EmitEventOutput, RuntimeError> {
+        let emitter = self.running_component_address()?;
+        self.track.emit_event(emitter, input.event, input.data)?;
+
+        Ok(EmitEventOutput {})
+    }
+
+    fn handle_set_event_subscribable(
+        &mut self,
+        input: SetEventSubscribableInput,
+    ) -> Result<SetEventSubscribableOutput, RuntimeError> {
+        let emitter = self.running_component_address()?;
+        self.track
+            .set_event_subscribable(emitter, input.event, input.subscribable);
+
+        Ok(SetEventSubscribableOutput {})
+    }
+
+    fn handle_subscribe_to_event(
+        &mut self,
+        input: SubscribeToEventInput,
+    ) -> Result<SubscribeToEventOutput, RuntimeError> {
+        let observer = self.running_component_address()?;
+        self.track
+            .subscribe_to_event(observer, input.emitter, input.event)?;
+
+        Ok(SubscribeToEventOutput {})
+    }
+
+    fn handle_unsubscribe_from_event(
+        &mut self,
+        input: UnsubscribeFromEventInput,
+    ) -> Result<UnsubscribeFromEventOutput, RuntimeError> {
+        let observer = self.running_component_address()?;
+        self.track
+            .unsubscribe_from_event(observer, input.emitter, &input.event);
+
+        Ok(UnsubscribeFromEventOutput {})
+    }
+
+    fn handle_take_notifications(
+        &mut self,
+        _input: TakeNotificationsInput,
+    ) -> Result<TakeNotificationsOutput, RuntimeError> {
+        let observer = self.running_component_address()?;
+        let notifications = self.track.take_notifications(observer);
+
+        Ok(TakeNotificationsOutput { notifications })
+    }
+
+    fn handle_get_call_data(
+        &mut self,
+        _input: GetCallDataInput,
+    ) -> Result<GetCallDataOutput, RuntimeError> {
+        let wasm_process = self
+            .wasm_process_state
+            .as_ref()
+            .ok_or(RuntimeError::InterpreterNotStarted)?;
+        Ok(GetCallDataOutput {
+            function: wasm_process.vm.function.clone(),
+            args: wasm_process
+                .vm
+                .args
+                .iter()
+                .cloned()
+                .map(|v| v.raw)
+                .collect(),
+        })
+    }
+
+    fn handle_get_transaction_hash(
+        &mut self,
+        _input: GetTransactionHashInput,
+    ) -> Result<GetTransactionHashOutput, RuntimeError> {
+        Ok(GetTransactionHashOutput {
+            transaction_hash: self.track.transaction_hash(),
+        })
+    }
+
+    fn handle_get_transaction_signers(
+        &mut self,
+        _input: GetTransactionSignersInput,
+    ) -> Result<GetTransactionSignersOutput, RuntimeError> {
+        let transaction_signers = self
+            .track
+            .transaction_signers()
+            .iter()
+            .map(|public_key| {
+                NonFungibleAddress::new(ECDSA_TOKEN, NonFungibleId::from_bytes(public_key.to_vec()))
+            })
+            .collect();
+        Ok(GetTransactionSignersOutput { transaction_signers })
+    }
+
+    fn handle_get_current_epoch(
+        &mut self,
+        _input: GetCurrentEpochInput,
+    ) -> Result<GetCurrentEpochOutput, RuntimeError> {
+        Ok(GetCurrentEpochOutput {
+            current_epoch: self.track.current_epoch(),
+        })
+    }
+
+    fn handle_generate_uuid(
+        &mut self,
+        _input: GenerateUuidInput,
+    ) -> Result<GenerateUuidOutput, RuntimeError> {
+        Ok(GenerateUuidOutput {
+            uuid: self.track.new_uuid(),
+        })
+    }
+
+    fn handle_get_actor(&mut self, _input: GetActorInput) -> Result<GetActorOutput, RuntimeError> {
+        let wasm_process = self
+            .wasm_process_state
+            .as_ref()
+            .ok_or(RuntimeError::InterpreterNotStarted)?;
+
+        return Ok(GetActorOutput {
+            actor: wasm_process.vm.actor.clone(),
+        });
+    }
+    
+    fn handle_get_caller(
+        &mut self,
+        _input: GetCallerInput,
+    ) -> Result<GetCallerOutput, RuntimeError> {
+        Ok(GetCallerOutput {
+            caller: self.caller.clone(),
+        })
+    }
+
+    fn handle_check_access_rule(&mut self, input: CheckAccessRuleInput) -> Result<CheckAccessRuleOutput, RuntimeError> {
+        if input.access_rule.is_slot() {
+            return Err(RuntimeError::AccessRuleSlotNotAllowed);
+        }
+        let proofs = input.proof_ids
+            .iter()
+            .map(|proof_id| self.frame.get_proof(*proof_id).unwrap().clone())
+            .collect::<Vec<Proof>>();
+        let mut simulated_auth_zone = AuthZone::new_with_proofs(proofs);
+
+        let method_authorization = convert(&Type::Unit, &Value::Unit, &input.access_rule);
+        let is_authorized = method_authorization.check(&[&simulated_auth_zone]).is_ok();
+        simulated_auth_zone.main("clear", Vec::new(), self).map_err(RuntimeError::AuthZoneError)?;
+
+        return Ok(CheckAccessRuleOutput{
+            is_authorized
+        });
+    }
+
+    fn handle_raise_error(
+        &mut self,
+        input: RaiseErrorInput,
+    ) -> Result<RaiseErrorOutput, RuntimeError> {
+        let wasm_process = self
+            .wasm_process_state
+            .as_ref()
+            .ok_or(RuntimeError::InterpreterNotStarted)?;
+        let error =
+            ScryptoValue::from_slice(&input.error).map_err(RuntimeError::ParseScryptoValueError)?;
+
+        Err(RuntimeError::BlueprintError {
+            package_address: *wasm_process.vm.actor.package_address(),
+            blueprint_name: wasm_process.vm.actor.blueprint_name().to_string(),
+            error,
+        })
+    }
+
+    fn handle_raise_panic(
+        &mut self,
+        input: RaisePanicInput,
+    ) -> Result<RaisePanicOutput, RuntimeError> {
+        Err(RuntimeError::Panic {
+            message: input.message,
+            location: input.location,
+        })
+    }
+
+    //============================
+    // SYSTEM CALL HANDLERS END
+    //============================
+}
+
+impl<'r, 'l, L: SubstateStore> SystemApi for Kernel<'r, 'l, L> {
+    fn invoke_snode(
+        &mut self,
+        snode_ref: SNodeRef,
+        function: String,
+        args: Vec<ScryptoValue>,
+    ) -> Result<ScryptoValue, RuntimeError> {
+        self.invoke_snode(snode_ref, function, args)
+    }
+
+    fn get_non_fungible(
+        &mut self,
+        non_fungible_address: &NonFungibleAddress,
+    ) -> Option<&NonFungible> {
+        self.track.get_non_fungible(non_fungible_address)
+    }
+
+    fn set_non_fungible(
+        &mut self,
+        non_fungible_address: NonFungibleAddress,
+        non_fungible: Option<NonFungible>,
+    ) {
+        self.track
+            .set_non_fungible(non_fungible_address, non_fungible)
+    }
+
+    fn borrow_global_mut_resource_manager(
+        &mut self,
+        resource_address: ResourceAddress,
+    ) -> Result<ResourceManager, RuntimeError> {
+        self.track
+            .borrow_global_mut_resource_manager(resource_address)
+    }
+
+    fn return_borrowed_global_resource_manager(
+        &mut self,
+        resource_address: ResourceAddress,
+        resource_manager: ResourceManager,
+    ) {
+        self.track
+            .return_borrowed_global_resource_manager(resource_address, resource_manager)
+    }
+
+    fn create_proof(&mut self, proof: Proof) -> Result<ProofId, RuntimeError> {
+        let proof_id = self.frame.create_proof(proof)?;
+        re_debug!(
+            self,
+            "Creating proof: {}, {}",
+            proof_id,
+            format_proof(
+                self.frame.get_proof(proof_id)?,
+                &|r| self.track.resource_symbol(r)
+            )
+        );
+        Ok(proof_id)
+    }
+
+    fn create_signature_proof(
+        &mut self,
+        signers: &[EcdsaPublicKey],
+    ) -> Result<ProofId, RuntimeError> {
+        let ids: BTreeSet<NonFungibleId> = signers
+            .iter()
+            .map(|public_key| NonFungibleId::from_bytes(public_key.to_vec()))
+            .collect();
+        let mut ecdsa_bucket = Bucket::new(ResourceContainer::new_non_fungible(ECDSA_TOKEN, ids));
+        let ecdsa_proof = ecdsa_bucket
+            .create_proof(ECDSA_TOKEN_BUCKET_ID)
+            .map_err(RuntimeError::ProofError)?;
+        self.create_proof(ecdsa_proof)
+    }
+
+    fn take_proof(&mut self, proof_id: ProofId) -> Result<Proof, RuntimeError> {
+        self.frame.take_proof(proof_id)
+    }
+
+    fn create_bucket(&mut self, container: ResourceContainer) -> Result<BucketId, RuntimeError> {
+        self.frame.create_bucket(container)
+    }
+
+    fn take_bucket(&mut self, bucket_id: BucketId) -> Result<Bucket, RuntimeError> {
+        self.frame.take_bucket(bucket_id)
+    }
+
+    fn create_resource(&mut self, resource_manager: ResourceManager) -> ResourceAddress {
+        self.track.create_resource_manager(resource_manager)
+    }
+
+    fn create_package(&mut self, package: Package) -> PackageAddress {
+        self.track.create_package(package)
+    }
+
+    fn create_component(&mut self, component: Component) -> ComponentAddress {
+        self.track.create_component(component)
+    }
+
+    fn create_vault(
+        &mut self,
+        component_address: ComponentAddress,
+        resource_address: ResourceAddress,
+    ) -> Result<VaultId, RuntimeError> {
+        let resource_type = self
+            .track
+            .get_resource_manager(&resource_address)?
+            .resource_type();
+
+        let vault = Vault::new(ResourceContainer::new_empty(resource_address, resource_type));
+        let vault_id = self.track.new_vault_id();
+        self.track.put_vault(component_address, vault_id, vault);
+        self.frame.add_vault_ref(vault_id);
+        Ok(vault_id)
+    }
+
+    fn create_locker(
+        &mut self,
+        component_address: ComponentAddress,
+        locker: Locker,
+    ) -> Result<LockerId, RuntimeError> {
+        if !self.track.component_exists(component_address) {
+            return Err(RuntimeError::ComponentNotFound(component_address));
+        }
+        Ok(self.track.create_locker(component_address, locker))
+    }
+
+    fn create_lazy_map(&mut self) -> LazyMapId {
+        self.track.new_lazy_map_id()
+    }
+
+    fn get_lazy_map_entry(
+        &mut self,
+        component_address: ComponentAddress,
+        lazy_map_id: &LazyMapId,
+        key: &[u8],
+    ) -> Option<Vec<u8>> {
+        self.track
+            .get_lazy_map_entry(component_address, lazy_map_id, key)
+    }
+
+    fn put_lazy_map_entry(
+        &mut self,
+        component_address: ComponentAddress,
+        lazy_map_id: LazyMapId,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<(), RuntimeError> {
+        self.track
+            .check_size_limit(SizeLimitKind::LazyMapKey, key.len())?;
+        self.track
+            .check_size_limit(SizeLimitKind::LazyMapValue, value.len())?;
+        self.track
+            .put_lazy_map_entry(component_address, lazy_map_id, key, value);
+        Ok(())
+    }
+
+    fn create_allowance(&mut self, allowance: Allowance) -> AllowanceId {
+        self.track.create_allowance(allowance)
+    }
+
+    fn revoke_allowance(
+        &mut self,
+        allowance_id: AllowanceId,
+        vault_id: VaultId,
+    ) -> Result<(), RuntimeError> {
+        let mut allowance = self.track.borrow_global_mut_allowance(allowance_id)?;
+        let result = allowance
+            .revoke(vault_id)
+            .map_err(RuntimeError::AllowanceError);
+        self.track
+            .return_borrowed_global_allowance(allowance_id, allowance);
+        result
+    }
+
+    fn take_from_component_vault(
+        &mut self,
+        component_address: ComponentAddress,
+        vault_id: VaultId,
+        amount: Decimal,
+    ) -> Result<ResourceContainer, RuntimeError> {
+        self.track.take_from_vault(component_address, vault_id, amount)
+    }
+
+    fn current_epoch(&self) -> u64 {
+        self.track.current_epoch()
+    }
+
+    fn set_epoch(&mut self, epoch: u64) {
+        self.track.set_epoch(epoch);
+    }
+
+    fn set_engine_version(&mut self, engine_version: EngineVersion) {
+        self.track.set_engine_version(engine_version);
+    }
+
+    fn set_instruction_index(&mut self, instruction_index: usize) {
+        self.track.set_instruction_index(instruction_index);
+    }
+
+    fn set_transaction_signers(&mut self, signers: Vec<EcdsaPublicKey>) {
+        self.track.set_transaction_signers(signers);
+    }
+
+    fn engine_version(&self) -> EngineVersion {
+        self.track.engine_version()
+    }
+
+    fn check_size_limit(&self, kind: SizeLimitKind, size: usize) -> Result<(), RuntimeError> {
+        self.track.check_size_limit(kind, size)
+    }
+
+    fn blueprint_abi(
+        &mut self,
+        package_address: PackageAddress,
+        blueprint_name: &str,
+    ) -> Result<BlueprintAbi, RuntimeError> {
+        self.call_abi(package_address, blueprint_name)
+            .and_then(|rtn| {
+                decode_blueprint_abi(&rtn.raw).map_err(RuntimeError::AbiValidationError)
+            })
+    }
+
+    fn fee_table(&self) -> &FeeTable {
+        self.track.fee_table()
+    }
+
+    fn cost_unit_counter(&self) -> &CostUnitCounter {
+        self.track.cost_unit_counter()
+    }
+
+    fn set_cost_unit_limit(&mut self, limit: u32) {
+        self.track.set_cost_unit_limit(limit);
+    }
+
+    fn consume_cost_units(
+        &mut self,
+        amount: u32,
+        reason: &'static str,
+    ) -> Result<(), CostUnitCounterError> {
+        self.track.consume_cost_units(amount, reason)
+    }
+}
+
+impl<'r, 'l, L: SubstateStore> Externals for Kernel<'r, 'l, L> {
+    fn invoke_index(
+        &mut self,
+        index: usize,
+        args: RuntimeArgs,
+    ) -> Result<Option<RuntimeValue>, Trap> {
+        match index {
+            ENGINE_FUNCTION_INDEX => self.handle(args),
+            _ => Err(RuntimeError::HostFunctionNotFound(index).into()),
+        }
+    }
+}
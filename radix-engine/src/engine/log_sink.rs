@@ -0,0 +1,80 @@
+use scrypto::core::Level;
+use scrypto::rust::cell::RefCell;
+use scrypto::rust::rc::Rc;
+use scrypto::rust::string::String;
+use scrypto::rust::string::ToString;
+use scrypto::rust::vec::Vec;
+
+/// A destination of the trace messages emitted by the engine.
+pub trait LogSink {
+    /// Writes a message emitted by a process at the given call depth.
+    fn write(&self, depth: usize, level: Level, message: &str);
+}
+
+/// A sink which discards all messages.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoOpLogSink;
+
+impl LogSink for NoOpLogSink {
+    fn write(&self, _depth: usize, _level: Level, _message: &str) {}
+}
+
+/// A sink which prints messages to the console, indented by call depth.
+#[cfg(not(feature = "alloc"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConsoleLogSink;
+
+#[cfg(not(feature = "alloc"))]
+impl LogSink for ConsoleLogSink {
+    fn write(&self, depth: usize, level: Level, message: &str) {
+        use colored::*;
+
+        let (l, m) = match level {
+            Level::Error => ("ERROR".red(), message.red()),
+            Level::Warn => ("WARN".yellow(), message.yellow()),
+            Level::Info => ("INFO".green(), message.green()),
+            Level::Debug => ("DEBUG".cyan(), message.cyan()),
+            Level::Trace => ("TRACE".normal(), message.normal()),
+        };
+        println!("{}[{:5}] {}", "  ".repeat(depth), l, m);
+    }
+}
+
+/// A sink which keeps messages in memory, for environments without a console.
+#[derive(Debug, Default)]
+pub struct BufferLogSink {
+    messages: RefCell<Vec<(usize, Level, String)>>,
+}
+
+impl BufferLogSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes and returns the buffered messages, with their call depth and level.
+    pub fn take(&self) -> Vec<(usize, Level, String)> {
+        self.messages.take()
+    }
+}
+
+impl LogSink for BufferLogSink {
+    fn write(&self, depth: usize, level: Level, message: &str) {
+        self.messages
+            .borrow_mut()
+            .push((depth, level, message.to_string()));
+    }
+}
+
+/// A shared sink, whose messages can be inspected while the executor holds it.
+impl<T: LogSink> LogSink for Rc<T> {
+    fn write(&self, depth: usize, level: Level, message: &str) {
+        self.as_ref().write(depth, level, message)
+    }
+}
+
+/// The sink used unless another one is set.
+#[cfg(not(feature = "alloc"))]
+pub type DefaultLogSink = ConsoleLogSink;
+/// The sink used unless another one is set.
+#[cfg(feature = "alloc")]
+pub type DefaultLogSink = NoOpLogSink;
@@ -0,0 +1,142 @@
+use scrypto::rust::collections::BTreeMap;
+use scrypto::rust::vec;
+use scrypto::rust::vec::Vec;
+
+use crate::engine::SubstateReadStats;
+
+/// A sink of engine metrics, which node integrators can export to their monitoring system.
+///
+/// All methods default to doing nothing.
+pub trait EngineMetrics {
+    /// Records the number of invocations of an SNode type within a transaction.
+    fn record_invocations(&mut self, _snode_type: &'static str, _count: u64) {}
+
+    /// Records the substate reads made by a transaction.
+    fn record_substate_reads(&mut self, _stats: SubstateReadStats) {}
+
+    /// Records the number of substates written by a committed transaction.
+    fn record_substate_writes(&mut self, _count: u64) {}
+
+    /// Records the cost units consumed by a transaction.
+    fn record_cost_units(&mut self, _cost_units: u32) {}
+
+    /// Records the execution time of a transaction, in milliseconds.
+    fn record_execution_time(&mut self, _millis: u128) {}
+}
+
+/// Metrics which are discarded.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoOpMetrics;
+
+impl EngineMetrics for NoOpMetrics {}
+
+/// A histogram of observed values, over fixed buckets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    /// The inclusive upper bounds of the buckets, in increasing order.
+    bounds: Vec<u64>,
+    /// The number of observations per bucket, with an extra bucket for values above all bounds.
+    counts: Vec<u64>,
+    sum: u128,
+}
+
+impl Histogram {
+    pub fn new(bounds: Vec<u64>) -> Self {
+        let counts = vec![0; bounds.len() + 1];
+        Self {
+            bounds,
+            counts,
+            sum: 0,
+        }
+    }
+
+    pub fn observe(&mut self, value: u64) {
+        let index = self
+            .bounds
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(self.bounds.len());
+        self.counts[index] += 1;
+        self.sum += value as u128;
+    }
+
+    pub fn bounds(&self) -> &[u64] {
+        &self.bounds
+    }
+
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    pub fn sum(&self) -> u128 {
+        self.sum
+    }
+}
+
+/// Metrics aggregated in memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InMemoryMetrics {
+    pub invocations: BTreeMap<&'static str, u64>,
+    pub substate_reads: SubstateReadStats,
+    pub substate_writes: u64,
+    pub cost_units: Histogram,
+    pub execution_time_millis: Histogram,
+}
+
+impl Default for InMemoryMetrics {
+    fn default() -> Self {
+        Self {
+            invocations: BTreeMap::new(),
+            substate_reads: SubstateReadStats::default(),
+            substate_writes: 0,
+            cost_units: Histogram::new(vec![1_000, 10_000, 100_000, 1_000_000, 10_000_000]),
+            execution_time_millis: Histogram::new(vec![1, 10, 100, 1_000, 10_000]),
+        }
+    }
+}
+
+impl EngineMetrics for InMemoryMetrics {
+    fn record_invocations(&mut self, snode_type: &'static str, count: u64) {
+        *self.invocations.entry(snode_type).or_default() += count;
+    }
+
+    fn record_substate_reads(&mut self, stats: SubstateReadStats) {
+        self.substate_reads.transaction_cache_hits += stats.transaction_cache_hits;
+        self.substate_reads.substate_cache_hits += stats.substate_cache_hits;
+        self.substate_reads.store_reads += stats.store_reads;
+    }
+
+    fn record_substate_writes(&mut self, count: u64) {
+        self.substate_writes += count;
+    }
+
+    fn record_cost_units(&mut self, cost_units: u32) {
+        self.cost_units.observe(cost_units as u64);
+    }
+
+    fn record_execution_time(&mut self, millis: u128) {
+        self.execution_time_millis
+            .observe(u64::try_from(millis).unwrap_or(u64::MAX));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets() {
+        let mut histogram = Histogram::new(vec![10, 100]);
+        histogram.observe(5);
+        histogram.observe(10);
+        histogram.observe(50);
+        histogram.observe(1000);
+        assert_eq!(histogram.counts(), &[2, 1, 1]);
+        assert_eq!(histogram.count(), 4);
+        assert_eq!(histogram.sum(), 1065);
+    }
+}
@@ -1,13 +1,39 @@
+mod call_frame;
+mod clock;
 mod component_objects;
+mod engine_version;
+mod hooks;
 mod id_allocator;
 mod id_validator;
-mod process;
+mod kernel;
+mod log_sink;
+mod metrics;
+mod node;
+mod resource_audit;
+mod substate_lock;
+mod substate_cache;
 mod track;
 mod wasm_env;
+mod wasm_features;
+mod wasm_instance_pool;
 
+pub use call_frame::{CallFrame, MoveMethod};
+pub use clock::*;
 pub use component_objects::*;
+pub use engine_version::*;
+pub use hooks::*;
 pub use id_allocator::*;
 pub use id_validator::*;
-pub use process::{Process, SNodeState, SystemApi};
-pub use track::{CommitReceipt, Track};
+pub use kernel::{Kernel, SNodeState, SystemApi};
+pub use log_sink::*;
+pub use metrics::*;
+pub use node::{RENode, RENodeId};
+pub use resource_audit::*;
+pub use substate_lock::{LockMode, SubstateId, SubstateLocks};
+pub use substate_cache::*;
+pub use track::{
+    CommitReceipt, EntityAddress, NewEntity, Track, DEFAULT_MAX_LOGS, DEFAULT_MAX_LOG_BYTES,
+};
 pub use wasm_env::{EnvModuleResolver, ENGINE_FUNCTION_INDEX, ENGINE_FUNCTION_NAME};
+pub use wasm_features::*;
+pub use wasm_instance_pool::*;
@@ -0,0 +1,222 @@
+use scrypto::engine::types::*;
+use scrypto::rust::collections::*;
+use scrypto::rust::vec::Vec;
+use scrypto::values::*;
+
+use crate::engine::*;
+use crate::errors::*;
+use crate::model::*;
+
+/// Identifies a node, an engine object which is owned by a single call frame, component or
+/// lazy map at a time.
+///
+/// Components and allowances are global, and lockers are owned by their component. These are
+/// kept by the track and borrowed by address, never owned by a call frame.
+///
+/// The rules of where a node may go are defined here, based on its type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RENodeId {
+    LazyMap(LazyMapId),
+    Vault(VaultId),
+    Bucket(BucketId),
+    Proof(ProofId),
+    Component(ComponentAddress),
+    Locker(ComponentAddress, LockerId),
+    Allowance(AllowanceId),
+}
+
+impl RENodeId {
+    /// Returns the nodes referred to by a value passed between call frames.
+    pub fn from_value(value: &ScryptoValue) -> Vec<RENodeId> {
+        Self::collect(
+            &value.lazy_map_ids,
+            &value.vault_ids,
+            value.bucket_ids.keys(),
+            value.proof_ids.keys(),
+        )
+    }
+
+    /// Returns the nodes referred to by data to be stored in a component or lazy map.
+    pub fn from_value_ids(value: &ScryptoValueIds) -> Vec<RENodeId> {
+        Self::collect(
+            &value.lazy_map_ids,
+            &value.vault_ids,
+            value.bucket_ids.keys(),
+            value.proof_ids.keys(),
+        )
+    }
+
+    fn collect<'a>(
+        lazy_map_ids: &BTreeSet<LazyMapId>,
+        vault_ids: &BTreeSet<VaultId>,
+        bucket_ids: impl Iterator<Item = &'a BucketId>,
+        proof_ids: impl Iterator<Item = &'a ProofId>,
+    ) -> Vec<RENodeId> {
+        lazy_map_ids
+            .iter()
+            .map(|id| RENodeId::LazyMap(*id))
+            .chain(vault_ids.iter().map(|id| RENodeId::Vault(*id)))
+            .chain(bucket_ids.map(|id| RENodeId::Bucket(*id)))
+            .chain(proof_ids.map(|id| RENodeId::Proof(*id)))
+            .collect()
+    }
+
+    /// Checks whether the node can be passed to or returned from another call frame.
+    ///
+    /// Vaults and lazy maps only leave a frame by being stored, and nodes kept by the track are
+    /// only ever referred to by address.
+    pub fn verify_can_move(&self) -> Result<(), RuntimeError> {
+        match self {
+            RENodeId::LazyMap(_) => Err(RuntimeError::LazyMapNotAllowed),
+            RENodeId::Vault(_) => Err(RuntimeError::VaultNotAllowed),
+            RENodeId::Bucket(_) | RENodeId::Proof(_) => Ok(()),
+            RENodeId::Component(_) | RENodeId::Locker(..) | RENodeId::Allowance(_) => {
+                Err(RuntimeError::GlobalNodeNotAllowed(*self))
+            }
+        }
+    }
+
+    /// Checks whether the node can be stored in a component or a lazy map.
+    ///
+    /// Buckets and proofs can't outlive the transaction.
+    pub fn verify_can_persist(&self) -> Result<(), RuntimeError> {
+        match self {
+            RENodeId::LazyMap(_)
+            | RENodeId::Vault(_)
+            | RENodeId::Component(_)
+            | RENodeId::Locker(..)
+            | RENodeId::Allowance(_) => Ok(()),
+            RENodeId::Bucket(bucket_id) => Err(RuntimeError::TransientValuePersisted(
+                TransientValue::Bucket(*bucket_id),
+            )),
+            RENodeId::Proof(proof_id) => Err(RuntimeError::TransientValuePersisted(
+                TransientValue::Proof(*proof_id),
+            )),
+        }
+    }
+
+    /// Returns the error of a node which isn't owned by the frame looking for it.
+    pub fn not_found(&self) -> RuntimeError {
+        match self {
+            RENodeId::LazyMap(lazy_map_id) => RuntimeError::LazyMapNotFound(*lazy_map_id),
+            RENodeId::Vault(vault_id) => RuntimeError::VaultNotFound(*vault_id),
+            RENodeId::Bucket(bucket_id) => RuntimeError::BucketNotFound(*bucket_id),
+            RENodeId::Proof(proof_id) => RuntimeError::ProofNotFound(*proof_id),
+            RENodeId::Component(component_address) => {
+                RuntimeError::ComponentNotFound(*component_address)
+            }
+            RENodeId::Locker(_, locker_id) => RuntimeError::LockerNotFound(*locker_id),
+            RENodeId::Allowance(allowance_id) => RuntimeError::AllowanceNotFound(*allowance_id),
+        }
+    }
+}
+
+/// A node, either owned by a call frame or borrowed from the track.
+///
+/// Unstored lazy maps keep the vaults and lazy maps stored in them, until they're stored
+/// themselves.
+#[derive(Debug)]
+pub enum RENode {
+    Bucket(Bucket),
+    Proof(Proof),
+    Vault(Vault),
+    LazyMap(UnclaimedLazyMap),
+    Component(Component),
+    Locker(Locker),
+    Allowance(Allowance),
+}
+
+impl RENode {
+    /// Checks whether the node, in its current state, can be moved out of its call frame.
+    ///
+    /// Locked buckets and restricted proofs can't move. The rules based on the type of the node
+    /// are checked by `RENodeId::verify_can_move`.
+    pub fn verify_can_move(&self, node_id: RENodeId) -> Result<(), RuntimeError> {
+        match self {
+            RENode::Bucket(bucket) => {
+                if bucket.is_locked() {
+                    return Err(RuntimeError::CantMoveLockedBucket);
+                }
+            }
+            RENode::Proof(proof) => {
+                if proof.is_restricted() {
+                    if let RENodeId::Proof(proof_id) = node_id {
+                        return Err(RuntimeError::CantMoveRestrictedProof(proof_id));
+                    }
+                }
+            }
+            RENode::Vault(_)
+            | RENode::LazyMap(_)
+            | RENode::Component(_)
+            | RENode::Locker(_)
+            | RENode::Allowance(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Prepares a node which has been verified to move, before it leaves its call frame.
+    ///
+    /// Proofs passed as arguments become restricted.
+    pub fn prepare_move(&mut self, method: MoveMethod) {
+        if let RENode::Proof(proof) = self {
+            if matches!(method, MoveMethod::AsArgument) {
+                proof.change_to_restricted();
+            }
+        }
+    }
+
+    /// Returns the resource which would be lost if the node was left behind by its frame.
+    pub fn resource_failure(&self) -> Option<ResourceFailure> {
+        match self {
+            RENode::Bucket(bucket) => Some(ResourceFailure::Resource(bucket.resource_address())),
+            RENode::Vault(vault) => Some(ResourceFailure::Resource(vault.resource_address())),
+            RENode::LazyMap(_) => Some(ResourceFailure::UnclaimedLazyMap),
+            RENode::Proof(_) | RENode::Component(_) | RENode::Locker(_) | RENode::Allowance(_) => {
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stored_nodes_cant_move() {
+        assert_eq!(
+            RENodeId::Vault((Hash([0u8; 32]), 0)).verify_can_move(),
+            Err(RuntimeError::VaultNotAllowed)
+        );
+        assert_eq!(RENodeId::Bucket(1).verify_can_move(), Ok(()));
+    }
+
+    #[test]
+    fn test_global_nodes_cant_move() {
+        let allowance_id = (Hash([0u8; 32]), 0);
+        assert_eq!(
+            RENodeId::Allowance(allowance_id).verify_can_move(),
+            Err(RuntimeError::GlobalNodeNotAllowed(RENodeId::Allowance(
+                allowance_id
+            )))
+        );
+        assert_eq!(
+            RENodeId::Allowance(allowance_id).verify_can_persist(),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_transient_nodes_cant_persist() {
+        assert_eq!(
+            RENodeId::Proof(1).verify_can_persist(),
+            Err(RuntimeError::TransientValuePersisted(
+                TransientValue::Proof(1)
+            ))
+        );
+        assert_eq!(
+            RENodeId::LazyMap((Hash([0u8; 32]), 0)).verify_can_persist(),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use scrypto::engine::types::*;
+use scrypto::rust::collections::BTreeMap;
+use scrypto::rust::vec::Vec;
+
+use crate::errors::RuntimeError;
+
+/// The net change of a resource within a transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceChange {
+    /// The change of the total supply, as recorded by the resource manager.
+    pub supply_change: Decimal,
+    /// The change of the amount held by vaults and lockers.
+    pub held_change: Decimal,
+}
+
+impl ResourceChange {
+    /// Returns whether the resource has been conserved, i.e. the amount held changed by as
+    /// much as what was minted minus what was burned.
+    pub fn is_conserved(&self) -> bool {
+        self.supply_change == self.held_change
+    }
+}
+
+/// The resource changes of a transaction, recomputed from its state updates so that engine
+/// bugs which create or destroy resources are caught before they are committed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResourceAudit {
+    changes: BTreeMap<ResourceAddress, ResourceChange>,
+}
+
+impl ResourceAudit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_supply_change(&mut self, resource_address: ResourceAddress, amount: Decimal) {
+        self.changes
+            .entry(resource_address)
+            .or_default()
+            .supply_change += amount;
+    }
+
+    pub fn record_held_change(&mut self, resource_address: ResourceAddress, amount: Decimal) {
+        self.changes
+            .entry(resource_address)
+            .or_default()
+            .held_change += amount;
+    }
+
+    /// Returns the change of every resource touched by the transaction.
+    pub fn changes(&self) -> &BTreeMap<ResourceAddress, ResourceChange> {
+        &self.changes
+    }
+
+    /// Returns the resources which haven't been conserved.
+    pub fn violations(&self) -> Vec<(ResourceAddress, ResourceChange)> {
+        self.changes
+            .iter()
+            .filter(|(_, change)| !change.is_conserved())
+            .map(|(resource_address, change)| (*resource_address, *change))
+            .collect()
+    }
+
+    /// Fails with the first resource which hasn't been conserved.
+    pub fn check(&self) -> Result<(), RuntimeError> {
+        match self.violations().into_iter().next() {
+            Some((resource_address, change)) => Err(RuntimeError::ResourceConservationViolated {
+                resource_address,
+                supply_change: change.supply_change,
+                held_change: change.held_change,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Whether transactions are audited for resource conservation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceAuditMode {
+    /// Transactions aren't audited.
+    Off,
+    /// The audit is attached to the receipt.
+    Report,
+    /// The audit is attached to the receipt, and transactions which violate resource
+    /// conservation are rejected.
+    Enforce,
+}
+
+impl ResourceAuditMode {
+    /// Checks the audit of a transaction, failing only if conservation is enforced.
+    pub fn check(&self, audit: &ResourceAudit) -> Result<(), RuntimeError> {
+        match self {
+            ResourceAuditMode::Enforce => audit.check(),
+            ResourceAuditMode::Off | ResourceAuditMode::Report => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scrypto::constants::RADIX_TOKEN;
+
+    #[test]
+    fn test_conserved_resource() {
+        let mut audit = ResourceAudit::new();
+        audit.record_supply_change(RADIX_TOKEN, 100.into());
+        audit.record_held_change(RADIX_TOKEN, 150.into());
+        audit.record_held_change(RADIX_TOKEN, (-50).into());
+        assert!(audit.violations().is_empty());
+        assert_eq!(audit.check(), Ok(()));
+    }
+
+    #[test]
+    fn test_violated_resource() {
+        let mut audit = ResourceAudit::new();
+        audit.record_held_change(RADIX_TOKEN, 1.into());
+        assert_eq!(
+            audit.check(),
+            Err(RuntimeError::ResourceConservationViolated {
+                resource_address: RADIX_TOKEN,
+                supply_change: 0.into(),
+                held_change: 1.into(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_violation_is_only_rejected_when_enforced() {
+        let mut audit = ResourceAudit::new();
+        audit.record_supply_change(RADIX_TOKEN, 1.into());
+        assert_eq!(ResourceAuditMode::Report.check(&audit), Ok(()));
+        assert_eq!(
+            ResourceAuditMode::Enforce.check(&audit),
+            Err(RuntimeError::ResourceConservationViolated {
+                resource_address: RADIX_TOKEN,
+                supply_change: 1.into(),
+                held_change: 0.into(),
+            })
+        );
+    }
+}
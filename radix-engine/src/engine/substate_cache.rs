@@ -0,0 +1,93 @@
+use lru::LruCache;
+use scrypto::rust::vec::Vec;
+
+use crate::ledger::Substate;
+
+/// The default number of substates kept by a `SubstateCache`.
+pub const DEFAULT_SUBSTATE_CACHE_CAPACITY: usize = 1024;
+
+/// Statistics of the substate reads made by a transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SubstateReadStats {
+    /// Reads served by the per-transaction read cache.
+    pub transaction_cache_hits: u64,
+    /// Reads served by the cross-transaction substate cache.
+    pub substate_cache_hits: u64,
+    /// Reads which went to the substate store.
+    pub store_reads: u64,
+}
+
+/// Statistics of a `SubstateCache` over its lifetime.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SubstateCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub invalidations: u64,
+}
+
+/// A least-recently-used cache of committed substates, shared by transactions.
+///
+/// Substates are keyed by their substate store id. Missing substates are cached as well, so
+/// the cache must be invalidated whenever the underlying substate store is written to.
+pub struct SubstateCache {
+    entries: LruCache<Vec<u8>, Option<Substate>>,
+    stats: SubstateCacheStats,
+}
+
+impl SubstateCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: LruCache::new(capacity),
+            stats: SubstateCacheStats::default(),
+        }
+    }
+
+    /// Returns the cached substate, or `None` if the id is not cached.
+    pub fn get(&mut self, id: &Vec<u8>) -> Option<Option<Substate>> {
+        match self.entries.get(id) {
+            Some(substate) => {
+                self.stats.hits += 1;
+                Some(substate.clone())
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn put(&mut self, id: Vec<u8>, substate: Option<Substate>) {
+        self.entries.put(id, substate);
+    }
+
+    /// Removes a substate which has been written to the substate store.
+    pub fn invalidate(&mut self, id: &Vec<u8>) {
+        if self.entries.pop(id).is_some() {
+            self.stats.invalidations += 1;
+        }
+    }
+
+    /// Removes all substates.
+    pub fn clear(&mut self) {
+        self.stats.invalidations += self.entries.len() as u64;
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn stats(&self) -> SubstateCacheStats {
+        self.stats
+    }
+}
+
+impl Default for SubstateCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_SUBSTATE_CACHE_CAPACITY)
+    }
+}
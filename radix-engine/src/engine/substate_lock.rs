@@ -0,0 +1,134 @@
+use scrypto::engine::types::*;
+use scrypto::rust::collections::*;
+
+/// Identifies a substate which can be locked by the track.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SubstateId {
+    Package(PackageAddress),
+    Component(ComponentAddress),
+    ResourceManager(ResourceAddress),
+    Vault(ComponentAddress, VaultId),
+    Locker(ComponentAddress, LockerId),
+    Allowance(AllowanceId),
+}
+
+/// How a substate is locked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+    /// Shared with other readers, excluding writers.
+    Read,
+    /// Exclusive.
+    Write,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LockState {
+    Read(u32),
+    Write,
+}
+
+/// The locks held on substates.
+///
+/// Conflicts only depend on the order in which locks are acquired and released, so a
+/// transaction fails with the same error wherever it is executed.
+#[derive(Debug)]
+pub struct SubstateLocks {
+    locks: HashMap<SubstateId, LockState>,
+}
+
+impl SubstateLocks {
+    pub fn new() -> Self {
+        Self {
+            locks: HashMap::new(),
+        }
+    }
+
+    /// Acquires a lock, returning the mode of the conflicting lock if there is one.
+    pub fn acquire(&mut self, substate_id: &SubstateId, mode: LockMode) -> Result<(), LockMode> {
+        match (self.locks.get_mut(substate_id), mode) {
+            (None, LockMode::Read) => {
+                self.locks.insert(substate_id.clone(), LockState::Read(1));
+                Ok(())
+            }
+            (None, LockMode::Write) => {
+                self.locks.insert(substate_id.clone(), LockState::Write);
+                Ok(())
+            }
+            (Some(LockState::Read(count)), LockMode::Read) => {
+                *count += 1;
+                Ok(())
+            }
+            (Some(LockState::Read(_)), LockMode::Write) => Err(LockMode::Read),
+            (Some(LockState::Write), _) => Err(LockMode::Write),
+        }
+    }
+
+    /// Releases a lock.
+    ///
+    /// Panics if the lock isn't held, which is a bug of the engine.
+    pub fn release(&mut self, substate_id: &SubstateId, mode: LockMode) {
+        match (self.locks.get_mut(substate_id), mode) {
+            (Some(LockState::Read(count)), LockMode::Read) if *count > 1 => {
+                *count -= 1;
+            }
+            (Some(LockState::Read(_)), LockMode::Read)
+            | (Some(LockState::Write), LockMode::Write) => {
+                self.locks.remove(substate_id);
+            }
+            _ => panic!("Substate was never locked: {:?}", substate_id),
+        }
+    }
+
+    /// Returns the mode of the lock held on a substate, if any.
+    pub fn lock_mode(&self, substate_id: &SubstateId) -> Option<LockMode> {
+        self.locks.get(substate_id).map(|state| match state {
+            LockState::Read(_) => LockMode::Read,
+            LockState::Write => LockMode::Write,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.locks.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scrypto::constants::*;
+
+    #[test]
+    fn test_read_locks_are_shared() {
+        let mut locks = SubstateLocks::new();
+        let substate_id = SubstateId::ResourceManager(RADIX_TOKEN);
+
+        assert_eq!(locks.acquire(&substate_id, LockMode::Read), Ok(()));
+        assert_eq!(locks.acquire(&substate_id, LockMode::Read), Ok(()));
+        assert_eq!(
+            locks.acquire(&substate_id, LockMode::Write),
+            Err(LockMode::Read)
+        );
+        locks.release(&substate_id, LockMode::Read);
+        assert_eq!(locks.lock_mode(&substate_id), Some(LockMode::Read));
+        locks.release(&substate_id, LockMode::Read);
+        assert!(locks.is_empty());
+    }
+
+    #[test]
+    fn test_write_lock_is_exclusive() {
+        let mut locks = SubstateLocks::new();
+        let substate_id = SubstateId::Component(SYSTEM_COMPONENT);
+
+        assert_eq!(locks.acquire(&substate_id, LockMode::Write), Ok(()));
+        assert_eq!(
+            locks.acquire(&substate_id, LockMode::Read),
+            Err(LockMode::Write)
+        );
+        assert_eq!(
+            locks.acquire(&substate_id, LockMode::Write),
+            Err(LockMode::Write)
+        );
+        locks.release(&substate_id, LockMode::Write);
+        assert_eq!(locks.acquire(&substate_id, LockMode::Read), Ok(()));
+    }
+}
@@ -1,24 +1,43 @@
-use indexmap::IndexMap;
+use sbor::{Decode, Encode};
+use scrypto::buffer::{scrypto_decode, scrypto_encode};
 use scrypto::constants::*;
+use scrypto::core::{Notification, SNodeRef};
+use scrypto::crypto::hash;
 use scrypto::engine::types::*;
 use scrypto::rust::collections::*;
 use scrypto::rust::string::String;
+use scrypto::rust::string::ToString;
 use scrypto::rust::vec::Vec;
+use scrypto::values::ScryptoValue;
 
 use crate::engine::*;
-use crate::errors::RuntimeError;
+use crate::errors::{CallFrame, RuntimeError};
+use crate::fee::*;
 use crate::ledger::*;
 use crate::model::*;
 
+/// An insertion-ordered map, so that substates are committed in a deterministic order.
+#[cfg(not(feature = "alloc"))]
+type IndexMap<K, V> = indexmap::IndexMap<K, V>;
+/// Without `std`, there's no randomly seeded hasher for `IndexMap` to default to.
+#[cfg(feature = "alloc")]
+type IndexMap<K, V> = indexmap::IndexMap<K, V, hashbrown::hash_map::DefaultHashBuilder>;
+
+/// The default maximum number of log messages a transaction can emit.
+pub const DEFAULT_MAX_LOGS: usize = 1024;
+
+/// The default maximum total size in bytes of the log messages a transaction can emit.
+pub const DEFAULT_MAX_LOG_BYTES: usize = 64 * 1024;
+
 pub struct CommitReceipt {
-    pub down_substates: HashSet<(Hash, u32)>,
+    pub down_substates: BTreeSet<(Hash, u32)>,
     pub up_substates: Vec<(Hash, u32)>,
 }
 
 impl CommitReceipt {
     fn new() -> Self {
         CommitReceipt {
-            down_substates: HashSet::new(),
+            down_substates: BTreeSet::new(),
             up_substates: Vec::new(),
         }
     }
@@ -32,6 +51,45 @@ impl CommitReceipt {
     }
 }
 
+/// The address of an entity created by a transaction, which also tells its type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntityAddress {
+    Package(PackageAddress),
+    Component(ComponentAddress),
+    Resource(ResourceAddress),
+    Allowance(AllowanceId),
+    /// A vault, owned by the given component.
+    Vault(ComponentAddress, VaultId),
+    /// A locker, owned by the given component.
+    Locker(ComponentAddress, LockerId),
+}
+
+impl EntityAddress {
+    /// Returns whether the entity is global, i.e. not owned by a component.
+    pub fn is_global(&self) -> bool {
+        !matches!(self, EntityAddress::Vault(..) | EntityAddress::Locker(..))
+    }
+}
+
+/// An entity whose ID has been allocated, which isn't committed if it doesn't exist at the end
+/// of the transaction.
+enum AllocatedEntity {
+    Package(PackageAddress),
+    Component(ComponentAddress),
+    Resource(ResourceAddress),
+    Allowance(AllowanceId),
+    Vault(VaultId),
+    Locker(LockerId),
+}
+
+/// An entity created by a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewEntity {
+    pub address: EntityAddress,
+    /// The index of the instruction which created the entity, if any.
+    pub instruction_index: Option<usize>,
+}
+
 struct SubstateUpdate<T> {
     prev_id: Option<(Hash, u32)>,
     value: T,
@@ -48,10 +106,20 @@ pub struct Track<'s, S: SubstateStore> {
     substate_store: &'s mut S,
     transaction_hash: Hash,
     transaction_signers: Vec<EcdsaPublicKey>,
+    engine_version: EngineVersion,
     id_allocator: IdAllocator,
+    call_count: u32,
+    instruction_index: Option<usize>,
+    /// The entities allocated so far, with the index of the instruction which allocated them.
+    allocated_entities: Vec<(AllocatedEntity, Option<usize>)>,
     logs: Vec<(Level, String)>,
+    log_bytes: usize,
+    max_logs: usize,
+    max_log_bytes: usize,
+    events: Vec<Notification>,
 
     packages: IndexMap<PackageAddress, SubstateUpdate<Package>>,
+    borrowed_packages: HashMap<PackageAddress, Option<(Hash, u32)>>,
 
     components: IndexMap<ComponentAddress, SubstateUpdate<Component>>,
     borrowed_components: HashMap<ComponentAddress, Option<(Hash, u32)>>,
@@ -59,12 +127,44 @@ pub struct Track<'s, S: SubstateStore> {
     resource_managers: IndexMap<ResourceAddress, SubstateUpdate<ResourceManager>>,
     borrowed_resource_managers: HashMap<ResourceAddress, Option<(Hash, u32)>>,
 
-    vaults: HashMap<(ComponentAddress, VaultId), SubstateUpdate<Vault>>,
+    vaults: IndexMap<(ComponentAddress, VaultId), SubstateUpdate<Vault>>,
     borrowed_vaults: HashMap<(ComponentAddress, VaultId), Option<(Hash, u32)>>,
 
-    non_fungibles: HashMap<NonFungibleAddress, SubstateUpdate<Option<NonFungible>>>,
-
-    lazy_map_entries: HashMap<(ComponentAddress, LazyMapId, Vec<u8>), SubstateUpdate<Vec<u8>>>,
+    /// Lockers by owning component, where emptied lockers are `None`
+    lockers: IndexMap<(ComponentAddress, LockerId), SubstateUpdate<Option<Locker>>>,
+    borrowed_lockers: HashMap<(ComponentAddress, LockerId), Option<(Hash, u32)>>,
+
+    allowances: IndexMap<AllowanceId, SubstateUpdate<Allowance>>,
+    borrowed_allowances: HashMap<AllowanceId, Option<(Hash, u32)>>,
+    /// Locks held on the substates above, for as long as they're borrowed or accessed.
+    substate_locks: SubstateLocks,
+
+    notification_registries: IndexMap<ComponentAddress, SubstateUpdate<NotificationRegistry>>,
+
+    non_fungibles: IndexMap<NonFungibleAddress, SubstateUpdate<Option<NonFungible>>>,
+
+    lazy_map_entries: IndexMap<(ComponentAddress, LazyMapId, Vec<u8>), SubstateUpdate<Vec<u8>>>,
+    /// Lazy maps created as readable by other components within this transaction.
+    readable_lazy_map_ids: HashSet<LazyMapId>,
+
+    new_epoch: Option<u64>,
+    new_engine_version: Option<EngineVersion>,
+
+    read_cache: HashMap<Vec<u8>, Option<Substate>>,
+    read_stats: SubstateReadStats,
+    substate_cache: Option<&'s mut SubstateCache>,
+    wasm_instance_pool: Option<&'s mut WasmInstancePool>,
+    hooks: Option<&'s mut dyn EngineHooks>,
+    clock: Option<&'s dyn Clock>,
+    log_sink: Option<&'s dyn LogSink>,
+    invocation_counts: BTreeMap<&'static str, u64>,
+    call_stack: Vec<CallFrame>,
+    failed_call_stack: Option<Vec<CallFrame>>,
+
+    fee_table: FeeTable,
+    cost_unit_counter: CostUnitCounter,
+    /// The bytes of substates read since cost units were last consumed.
+    unbilled_read_bytes: usize,
 }
 
 impl<'s, S: SubstateStore> Track<'s, S> {
@@ -73,26 +173,132 @@ impl<'s, S: SubstateStore> Track<'s, S> {
         transaction_hash: Hash,
         transaction_signers: Vec<EcdsaPublicKey>,
     ) -> Self {
+        let engine_version = substate_store.get_engine_version();
         Self {
             substate_store,
             transaction_hash,
             transaction_signers,
-            id_allocator: IdAllocator::new(IdSpace::Application),
+            engine_version,
+            id_allocator: IdAllocator::new(IdSpace::Application)
+                .with_typed_addresses(engine_version.features().typed_addresses),
+            call_count: 0,
+            instruction_index: None,
+            allocated_entities: Vec::new(),
             logs: Vec::new(),
-            packages: IndexMap::new(),
-            components: IndexMap::new(),
+            log_bytes: 0,
+            max_logs: DEFAULT_MAX_LOGS,
+            max_log_bytes: DEFAULT_MAX_LOG_BYTES,
+            events: Vec::new(),
+            packages: IndexMap::default(),
+            borrowed_packages: HashMap::new(),
+            components: IndexMap::default(),
             borrowed_components: HashMap::new(),
-            resource_managers: IndexMap::new(),
+            resource_managers: IndexMap::default(),
             borrowed_resource_managers: HashMap::new(),
-            lazy_map_entries: HashMap::new(),
-            vaults: HashMap::new(),
+            lazy_map_entries: IndexMap::default(),
+            readable_lazy_map_ids: HashSet::new(),
+            vaults: IndexMap::default(),
             borrowed_vaults: HashMap::new(),
-            non_fungibles: HashMap::new(),
+            lockers: IndexMap::default(),
+            borrowed_lockers: HashMap::new(),
+            allowances: IndexMap::default(),
+            borrowed_allowances: HashMap::new(),
+            substate_locks: SubstateLocks::new(),
+            notification_registries: IndexMap::default(),
+            non_fungibles: IndexMap::default(),
+            new_epoch: None,
+            new_engine_version: None,
+            read_cache: HashMap::new(),
+            read_stats: SubstateReadStats::default(),
+            substate_cache: None,
+            wasm_instance_pool: None,
+            hooks: None,
+            clock: None,
+            log_sink: None,
+            invocation_counts: BTreeMap::new(),
+            call_stack: Vec::new(),
+            failed_call_stack: None,
+            fee_table: FeeTable::new(),
+            cost_unit_counter: CostUnitCounter::default(),
+            unbilled_read_bytes: 0,
+        }
+    }
+
+    /// Sets the maximum number of cost units the transaction can consume.
+    pub fn with_cost_unit_limit(mut self, limit: u32) -> Self {
+        self.cost_unit_counter = CostUnitCounter::new(limit);
+        self
+    }
+
+    /// Sets the maximum number of log messages, and their maximum total size in bytes, the
+    /// transaction can emit.
+    pub fn with_log_limits(mut self, max_logs: usize, max_log_bytes: usize) -> Self {
+        self.max_logs = max_logs;
+        self.max_log_bytes = max_log_bytes;
+        self
+    }
+
+    /// Shares a substate cache with this track, which is consulted before the substate store
+    /// and invalidated on commit.
+    pub fn with_substate_cache(mut self, substate_cache: &'s mut SubstateCache) -> Self {
+        self.substate_cache = Some(substate_cache);
+        self
+    }
+
+    /// Shares a WASM instance pool with this track, so that instances are reused across calls
+    /// and transactions instead of being instantiated for every call.
+    pub fn with_wasm_instance_pool(mut self, wasm_instance_pool: &'s mut WasmInstancePool) -> Self {
+        self.wasm_instance_pool = Some(wasm_instance_pool);
+        self
+    }
+
+    /// Sets the hooks which are called around SNode invocations and substate accesses.
+    pub fn with_hooks(mut self, hooks: &'s mut dyn EngineHooks) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    /// Sets the clock which execution time is measured with.
+    ///
+    /// Without a clock, execution time isn't measured.
+    pub fn with_clock(mut self, clock: &'s dyn Clock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Sets the sink which trace messages are written to, instead of the `DefaultLogSink`.
+    pub fn with_log_sink(mut self, log_sink: &'s dyn LogSink) -> Self {
+        self.log_sink = Some(log_sink);
+        self
+    }
+
+    /// Returns the current time of the clock, in milliseconds.
+    pub fn now_millis(&self) -> Option<u128> {
+        self.clock.and_then(|clock| clock.now_millis())
+    }
+
+    /// Writes a trace message to the log sink.
+    pub fn write_trace(&self, depth: usize, level: Level, message: &str) {
+        match self.log_sink {
+            Some(log_sink) => log_sink.write(depth, level, message),
+            None => DefaultLogSink::default().write(depth, level, message),
         }
     }
 
     /// Start a process.
-    pub fn start_process<'r>(&'r mut self, verbose: bool) -> Process<'r, 's, S> {
+    pub fn start_process<'r>(&'r mut self, verbose: bool) -> Kernel<'r, 's, S> {
+        self.start_root_process(verbose, false)
+    }
+
+    /// Start a process for a system transaction, whose auth zone also holds the system badge.
+    ///
+    /// The transaction process drops the badge before the first instruction which calls user
+    /// code, so that it only authorizes the system instructions before it.
+    pub fn start_system_process<'r>(&'r mut self, verbose: bool) -> Kernel<'r, 's, S> {
+        self.start_root_process(verbose, true)
+    }
+
+    fn start_root_process<'r>(&'r mut self, verbose: bool, system: bool) -> Kernel<'r, 's, S> {
         let signers: BTreeSet<NonFungibleId> = self
             .transaction_signers
             .clone()
@@ -115,15 +321,22 @@ impl<'s, S: SubstateStore> Track<'s, S> {
             let ecdsa_proof = ecdsa_bucket.create_proof(ECDSA_TOKEN_BUCKET_ID).unwrap();
             initial_auth_zone_proofs.push(ecdsa_proof);
         }
+        if system {
+            let mut system_bucket = Bucket::new(ResourceContainer::new_non_fungible(
+                SYSTEM_TOKEN,
+                BTreeSet::from([NonFungibleId::from_u32(0)]),
+            ));
+            let system_proof = system_bucket.create_proof(SYSTEM_TOKEN_BUCKET_ID).unwrap();
+            initial_auth_zone_proofs.push(system_proof);
+        }
 
-        Process::new(
+        Kernel::new(
             0,
             verbose,
             self,
             Some(AuthZone::new_with_proofs(initial_auth_zone_proofs)),
             Some(Worktop::new()),
-            HashMap::new(),
-            HashMap::new(),
+            BTreeMap::new(),
         )
     }
 
@@ -132,9 +345,250 @@ impl<'s, S: SubstateStore> Track<'s, S> {
         self.transaction_hash
     }
 
-    /// Returns the current epoch.
+    /// Returns the public keys which signed the transaction.
+    pub fn transaction_signers(&self) -> &[EcdsaPublicKey] {
+        &self.transaction_signers
+    }
+
+    /// Replaces the transaction signers, which only affects `transaction_signers` and not the
+    /// auth zone of the running process.
+    pub fn set_transaction_signers(&mut self, signers: Vec<EcdsaPublicKey>) {
+        self.transaction_signers = signers;
+    }
+
+    /// Returns the engine version this transaction is executed with.
+    pub fn engine_version(&self) -> EngineVersion {
+        self.engine_version
+    }
+
+    /// Checks the encoded size of a value against the limits of the engine version.
+    pub fn check_size_limit(&self, kind: SizeLimitKind, size: usize) -> Result<(), RuntimeError> {
+        if let Some(limits) = self.engine_version.features().state_limits {
+            let limit = limits.limit(kind);
+            if size > limit {
+                return Err(RuntimeError::SizeLimitExceeded { kind, size, limit });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the current epoch, including any epoch change made by this transaction.
     pub fn current_epoch(&self) -> u64 {
-        self.substate_store.get_epoch()
+        self.new_epoch
+            .unwrap_or_else(|| self.substate_store.get_epoch())
+    }
+
+    /// Changes the epoch, which takes effect on ledger when the transaction is committed.
+    pub fn set_epoch(&mut self, epoch: u64) {
+        self.new_epoch = Some(epoch);
+    }
+
+    /// Changes the engine version, which is recorded on ledger when the transaction is
+    /// committed. This transaction keeps executing with the current version.
+    pub fn set_engine_version(&mut self, engine_version: EngineVersion) {
+        self.new_engine_version = Some(engine_version);
+    }
+
+    /// Returns the cost units charged for each kind of engine work.
+    pub fn fee_table(&self) -> &FeeTable {
+        &self.fee_table
+    }
+
+    /// Returns the cost unit counter of this transaction.
+    pub fn cost_unit_counter(&self) -> &CostUnitCounter {
+        &self.cost_unit_counter
+    }
+
+    /// Changes the maximum number of cost units the transaction can consume.
+    pub fn set_cost_unit_limit(&mut self, limit: u32) {
+        self.cost_unit_counter.set_limit(limit);
+    }
+
+    /// Consumes cost units for the given reason, failing if the cost unit limit is exceeded.
+    ///
+    /// The substate reads made since the previous call are billed first.
+    pub fn consume_cost_units(
+        &mut self,
+        amount: u32,
+        reason: &'static str,
+    ) -> Result<(), CostUnitCounterError> {
+        self.consume_substate_read_cost_units()?;
+        self.cost_unit_counter.consume(amount, reason)
+    }
+
+    /// Locks a substate, charging the lock fee since `EngineVersion::V7`.
+    ///
+    /// Fails if the substate is already locked in a conflicting mode, e.g. when it's mutably
+    /// borrowed twice, or read through the `get_*` methods while it's borrowed.
+    pub fn acquire_lock(
+        &mut self,
+        substate_id: SubstateId,
+        mode: LockMode,
+    ) -> Result<(), RuntimeError> {
+        if self.engine_version.features().lock_fees {
+            self.consume_cost_units(self.fee_table.lock_substate(), "lock_substate")
+                .map_err(RuntimeError::CostingError)?;
+        }
+        self.substate_locks
+            .acquire(&substate_id, mode)
+            .map_err(|held| RuntimeError::SubstateLocked {
+                substate_id,
+                requested: mode,
+                held,
+            })
+    }
+
+    /// Releases a lock acquired with `acquire_lock`.
+    pub fn release_lock(&mut self, substate_id: SubstateId, mode: LockMode) {
+        self.substate_locks.release(&substate_id, mode);
+    }
+
+    /// Takes and releases a read lock, so that a substate isn't read while it's borrowed.
+    fn check_read_lock(&mut self, substate_id: SubstateId) -> Result<(), RuntimeError> {
+        self.acquire_lock(substate_id.clone(), LockMode::Read)?;
+        self.release_lock(substate_id, LockMode::Read);
+        Ok(())
+    }
+
+    /// Returns whether a component exists, including when it's borrowed.
+    pub fn component_exists(&mut self, component_address: ComponentAddress) -> bool {
+        self.substate_locks
+            .lock_mode(&SubstateId::Component(component_address))
+            .is_some()
+            || self.get_component(component_address).is_ok()
+    }
+
+    /// Consumes cost units for the substate reads not billed yet, and for all substates created
+    /// or updated, proportionally to their size, since `EngineVersion::V3`.
+    ///
+    /// Called once the transaction has finished.
+    pub fn consume_substate_cost_units(&mut self) -> Result<(), CostUnitCounterError> {
+        if !self.engine_version.features().substate_fees {
+            return Ok(());
+        }
+        self.consume_substate_read_cost_units()?;
+        let write_bytes: usize = self
+            .updated_substates()
+            .iter()
+            .map(|(_, value)| value.len())
+            .sum();
+        if write_bytes > 0 {
+            let cost_units = (write_bytes.min(u32::MAX as usize) as u32)
+                .saturating_mul(self.fee_table.substate_write_per_byte());
+            self.cost_unit_counter
+                .consume(cost_units, "substate_write")?;
+        }
+        Ok(())
+    }
+
+    fn consume_substate_read_cost_units(&mut self) -> Result<(), CostUnitCounterError> {
+        if !self.engine_version.features().substate_fees {
+            return Ok(());
+        }
+        if self.unbilled_read_bytes > 0 {
+            let cost_units = (self.unbilled_read_bytes.min(u32::MAX as usize) as u32)
+                .saturating_mul(self.fee_table.substate_read_per_byte());
+            self.unbilled_read_bytes = 0;
+            self.cost_unit_counter
+                .consume(cost_units, "substate_read")?;
+        }
+        Ok(())
+    }
+
+    /// Returns the statistics of the substate reads made so far.
+    pub fn read_stats(&self) -> SubstateReadStats {
+        self.read_stats
+    }
+
+    /// Counts an invocation of the given SNode type.
+    pub fn record_invocation(&mut self, snode_type: &'static str) {
+        *self.invocation_counts.entry(snode_type).or_default() += 1;
+    }
+
+    /// Returns whether hooks are set.
+    pub fn has_hooks(&self) -> bool {
+        self.hooks.is_some()
+    }
+
+    /// Calls the hooks before an SNode is invoked.
+    pub fn pre_invoke(
+        &mut self,
+        depth: usize,
+        snode_ref: &SNodeRef,
+        function: &str,
+        args: &[ScryptoValue],
+    ) -> Result<(), RuntimeError> {
+        match self.hooks.as_mut() {
+            Some(hooks) => hooks.pre_invoke(
+                depth,
+                snode_ref,
+                function,
+                args,
+                &mut self.cost_unit_counter,
+            ),
+            None => Ok(()),
+        }
+    }
+
+    /// Calls the hooks after an SNode invocation has returned.
+    pub fn post_invoke(
+        &mut self,
+        depth: usize,
+        snode_ref: &SNodeRef,
+        function: &str,
+        result: &Result<ScryptoValue, RuntimeError>,
+    ) -> Result<(), RuntimeError> {
+        match self.hooks.as_mut() {
+            Some(hooks) => hooks.post_invoke(
+                depth,
+                snode_ref,
+                function,
+                result,
+                &mut self.cost_unit_counter,
+            ),
+            None => Ok(()),
+        }
+    }
+
+    /// Passes the substates created or updated by the transaction to the hooks.
+    ///
+    /// Called once the transaction has finished, before it is committed.
+    pub fn run_substate_write_hooks(&mut self) -> Result<(), RuntimeError> {
+        if self.hooks.is_none() {
+            return Ok(());
+        }
+        let updates = self.updated_substates();
+        let hooks = self.hooks.as_mut().unwrap();
+        for (id, value) in &updates {
+            hooks.on_substate_write(id, value)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of invocations made so far, per SNode type.
+    pub fn invocation_counts(&self) -> &BTreeMap<&'static str, u64> {
+        &self.invocation_counts
+    }
+
+    /// Pushes a frame onto the call stack.
+    pub fn enter_frame(&mut self, frame: CallFrame) {
+        self.call_stack.push(frame);
+    }
+
+    /// Pops the innermost frame off the call stack.
+    ///
+    /// If the frame failed, the call stack is kept as the failure path, unless a deeper frame
+    /// has already failed.
+    pub fn exit_frame(&mut self, failed: bool) {
+        if failed && self.failed_call_stack.is_none() {
+            self.failed_call_stack = Some(self.call_stack.clone());
+        }
+        self.call_stack.pop();
+    }
+
+    /// Returns the call stack at the point where the transaction failed, if it did.
+    pub fn failed_call_stack(&self) -> Option<&Vec<CallFrame>> {
+        self.failed_call_stack.as_ref()
     }
 
     /// Returns the logs collected so far.
@@ -142,6 +596,11 @@ impl<'s, S: SubstateStore> Track<'s, S> {
         &self.logs
     }
 
+    /// Returns the events emitted by components so far, in emission order.
+    pub fn events(&self) -> &Vec<Notification> {
+        &self.events
+    }
+
     /// Returns new packages created so far.
     pub fn new_package_addresses(&self) -> Vec<PackageAddress> {
         let mut package_addresses = Vec::new();
@@ -175,19 +634,111 @@ impl<'s, S: SubstateStore> Track<'s, S> {
         resource_addresses
     }
 
-    /// Adds a log message.
-    pub fn add_log(&mut self, level: Level, message: String) {
+    /// Sets the index of the instruction being executed, which new entities are attributed to.
+    pub fn set_instruction_index(&mut self, instruction_index: usize) {
+        self.instruction_index = Some(instruction_index);
+    }
+
+    /// Returns the entities which are committed with the current state updates, in creation
+    /// order.
+    ///
+    /// Only entities which still exist are returned, e.g. not lockers which have been claimed,
+    /// and vaults and lockers are given the component which owns them.
+    pub fn new_entities(&self) -> Vec<NewEntity> {
+        self.allocated_entities
+            .iter()
+            .filter_map(|(entity, instruction_index)| {
+                let address = match entity {
+                    AllocatedEntity::Package(package_address) => self
+                        .packages
+                        .contains_key(package_address)
+                        .then(|| EntityAddress::Package(*package_address)),
+                    AllocatedEntity::Component(component_address) => self
+                        .components
+                        .contains_key(component_address)
+                        .then(|| EntityAddress::Component(*component_address)),
+                    AllocatedEntity::Resource(resource_address) => self
+                        .resource_managers
+                        .contains_key(resource_address)
+                        .then(|| EntityAddress::Resource(*resource_address)),
+                    AllocatedEntity::Allowance(allowance_id) => self
+                        .allowances
+                        .contains_key(allowance_id)
+                        .then(|| EntityAddress::Allowance(*allowance_id)),
+                    AllocatedEntity::Vault(vault_id) => self
+                        .vaults
+                        .keys()
+                        .find(|(_, id)| id == vault_id)
+                        .map(|(owner, _)| EntityAddress::Vault(*owner, *vault_id)),
+                    AllocatedEntity::Locker(locker_id) => self
+                        .lockers
+                        .iter()
+                        .find(|((_, id), locker)| id == locker_id && locker.value.is_some())
+                        .map(|((owner, _), _)| EntityAddress::Locker(*owner, *locker_id)),
+                }?;
+                Some(NewEntity {
+                    address,
+                    instruction_index: *instruction_index,
+                })
+            })
+            .collect()
+    }
+
+    fn record_allocated_entity(&mut self, entity: AllocatedEntity) {
+        self.allocated_entities
+            .push((entity, self.instruction_index));
+    }
+
+    /// Adds a log message, failing if the log limits are exceeded.
+    pub fn add_log(&mut self, level: Level, message: String) -> Result<(), RuntimeError> {
+        let log_bytes = self.log_bytes.saturating_add(message.len());
+        if self.logs.len() >= self.max_logs || log_bytes > self.max_log_bytes {
+            return Err(RuntimeError::LogLimitExceeded {
+                max_logs: self.max_logs,
+                max_log_bytes: self.max_log_bytes,
+            });
+        }
+        self.log_bytes = log_bytes;
         self.logs.push((level, message));
+        Ok(())
     }
 
-    /// Returns an immutable reference to a package, if exists.
-    pub fn get_package(&mut self, package_address: &PackageAddress) -> Option<&Package> {
-        if self.packages.contains_key(package_address) {
-            return self.packages.get(package_address).map(|p| &p.value);
+    /// Returns an instance of the package code, taken from the WASM instance pool if possible.
+    pub fn load_wasm_instance(&mut self, package_address: &PackageAddress) -> Option<WasmInstance> {
+        self.get_package(package_address).ok()?;
+        let package = &self.packages.get(package_address)?.value;
+        let code_hash = hash(package.code());
+        let instantiate = || package.load_module().unwrap();
+        Some(match self.wasm_instance_pool.as_mut() {
+            Some(pool) => pool.acquire(code_hash, instantiate),
+            None => {
+                let (module, memory) = instantiate();
+                WasmInstance::new(code_hash, module, memory)
+            }
+        })
+    }
+
+    /// Returns an instance to the WASM instance pool after a successful invocation.
+    pub fn release_wasm_instance(&mut self, instance: WasmInstance) {
+        if let Some(pool) = self.wasm_instance_pool.as_mut() {
+            pool.release(instance);
         }
+    }
 
-        if let Some((package, phys_id)) = self.substate_store.get_decoded_substate(package_address)
-        {
+    /// Returns an immutable reference to a package, if exists.
+    ///
+    /// Fails if the package is borrowed.
+    pub fn get_package(
+        &mut self,
+        package_address: &PackageAddress,
+    ) -> Result<&Package, RuntimeError> {
+        self.check_read_lock(SubstateId::Package(*package_address))?;
+        if !self.packages.contains_key(package_address) {
+            let (mut package, phys_id) = self
+                .read_decoded_substate(package_address)
+                .ok_or_else(|| self.not_found(RuntimeError::PackageNotFound(*package_address)))?;
+            self.load_package_schemas(&mut package);
+            self.check_fetched()?;
             self.packages.insert(
                 package_address.clone(),
                 SubstateUpdate {
@@ -195,12 +746,61 @@ impl<'s, S: SubstateStore> Track<'s, S> {
                     value: package,
                 },
             );
-            self.packages.get(package_address).map(|p| &p.value)
+        }
+        Ok(&self.packages.get(package_address).unwrap().value)
+    }
+
+    pub fn borrow_global_mut_package(
+        &mut self,
+        package_address: PackageAddress,
+    ) -> Result<Package, RuntimeError> {
+        self.acquire_lock(SubstateId::Package(package_address), LockMode::Write)?;
+        if let Some(SubstateUpdate { value, prev_id }) = self.packages.remove(&package_address) {
+            self.borrowed_packages.insert(package_address, prev_id);
+            Ok(value)
+        } else if let Some((mut package, phys_id)) = self.read_decoded_substate(&package_address) {
+            self.load_package_schemas(&mut package);
+            if let Err(e) = self.check_fetched() {
+                self.release_lock(SubstateId::Package(package_address), LockMode::Write);
+                return Err(e);
+            }
+            self.borrowed_packages.insert(package_address, Some(phys_id));
+            Ok(package)
+        } else {
+            self.release_lock(SubstateId::Package(package_address), LockMode::Write);
+            Err(self.not_found(RuntimeError::PackageNotFound(package_address)))
+        }
+    }
+
+    pub fn return_borrowed_global_package(
+        &mut self,
+        package_address: PackageAddress,
+        package: Package,
+    ) {
+        if let Some(prev_id) = self.borrowed_packages.remove(&package_address) {
+            self.release_lock(SubstateId::Package(package_address), LockMode::Write);
+            self.packages.insert(
+                package_address,
+                SubstateUpdate {
+                    prev_id,
+                    value: package,
+                },
+            );
         } else {
-            None
+            panic!("Package was never borrowed");
         }
     }
 
+    /// Loads the blueprint schemas of a package from the schema registry.
+    ///
+    /// Schemas which haven't been fetched are left unloaded, so callers must `check_fetched`.
+    fn load_package_schemas(&mut self, package: &mut Package) {
+        package.load_schemas(|schema_hash| {
+            self.read_decoded_substate(&SchemaAddress(*schema_hash))
+                .map(|(schema, _)| schema)
+        });
+    }
+
     /// Inserts a new package.
     pub fn create_package(&mut self, package: Package) -> PackageAddress {
         let package_address = self.new_package_address();
@@ -218,20 +818,19 @@ impl<'s, S: SubstateStore> Track<'s, S> {
         &mut self,
         component_address: ComponentAddress,
     ) -> Result<Component, RuntimeError> {
-        let maybe_component = self.components.remove(&component_address);
-        if let Some(SubstateUpdate { value, prev_id }) = maybe_component {
+        self.acquire_lock(SubstateId::Component(component_address), LockMode::Write)
+            .map_err(|e| component_reentrancy(e, component_address))?;
+        if let Some(SubstateUpdate { value, prev_id }) = self.components.remove(&component_address)
+        {
             self.borrowed_components.insert(component_address, prev_id);
             Ok(value)
-        } else if self.borrowed_components.contains_key(&component_address) {
-            Err(RuntimeError::ComponentReentrancy(component_address))
-        } else if let Some((component, phys_id)) =
-            self.substate_store.get_decoded_substate(&component_address)
-        {
+        } else if let Some((component, phys_id)) = self.read_decoded_substate(&component_address) {
             self.borrowed_components
                 .insert(component_address, Some(phys_id));
             Ok(component)
         } else {
-            Err(RuntimeError::ComponentNotFound(component_address))
+            self.release_lock(SubstateId::Component(component_address), LockMode::Write);
+            Err(self.not_found(RuntimeError::ComponentNotFound(component_address)))
         }
     }
 
@@ -241,6 +840,7 @@ impl<'s, S: SubstateStore> Track<'s, S> {
         component: Component,
     ) {
         if let Some(prev_id) = self.borrowed_components.remove(&component_address) {
+            self.release_lock(SubstateId::Component(component_address), LockMode::Write);
             self.components.insert(
                 component_address,
                 SubstateUpdate {
@@ -254,14 +854,19 @@ impl<'s, S: SubstateStore> Track<'s, S> {
     }
 
     /// Returns an immutable reference to a component, if exists.
-    pub fn get_component(&mut self, component_address: ComponentAddress) -> Option<&Component> {
-        if self.components.contains_key(&component_address) {
-            return self.components.get(&component_address).map(|c| &c.value);
-        }
-
-        if let Some((component, phys_id)) =
-            self.substate_store.get_decoded_substate(&component_address)
-        {
+    ///
+    /// Fails if the component is borrowed.
+    pub fn get_component(
+        &mut self,
+        component_address: ComponentAddress,
+    ) -> Result<&Component, RuntimeError> {
+        self.check_read_lock(SubstateId::Component(component_address))?;
+        if !self.components.contains_key(&component_address) {
+            let (component, phys_id) =
+                self.read_decoded_substate(&component_address)
+                    .ok_or_else(|| {
+                        self.not_found(RuntimeError::ComponentNotFound(component_address))
+                    })?;
             self.components.insert(
                 component_address,
                 SubstateUpdate {
@@ -269,10 +874,8 @@ impl<'s, S: SubstateStore> Track<'s, S> {
                     value: component,
                 },
             );
-            self.components.get(&component_address).map(|c| &c.value)
-        } else {
-            None
         }
+        Ok(&self.components.get(&component_address).unwrap().value)
     }
 
     /// Inserts a new component.
@@ -301,7 +904,7 @@ impl<'s, S: SubstateStore> Track<'s, S> {
                 .unwrap_or(Option::None);
         }
 
-        if let Some((non_fungible, phys_id)) = self.substate_store.get_decoded_child_substate(
+        if let Some((non_fungible, phys_id)) = self.read_decoded_child_substate(
             &non_fungible_address.resource_address(),
             &non_fungible_address.non_fungible_id(),
         ) {
@@ -327,11 +930,10 @@ impl<'s, S: SubstateStore> Track<'s, S> {
         non_fungible_address: NonFungibleAddress,
         non_fungible: Option<NonFungible>,
     ) {
-        let cur: Option<(Option<NonFungible>, (Hash, u32))> =
-            self.substate_store.get_decoded_child_substate(
-                &non_fungible_address.resource_address(),
-                &non_fungible_address.non_fungible_id(),
-            );
+        let cur: Option<(Option<NonFungible>, (Hash, u32))> = self.read_decoded_child_substate(
+            &non_fungible_address.resource_address(),
+            &non_fungible_address.non_fungible_id(),
+        );
         let prev_id = cur.map(|(_, cur_id)| cur_id);
 
         self.non_fungibles.insert(
@@ -361,11 +963,8 @@ impl<'s, S: SubstateStore> Track<'s, S> {
         }
 
         let grand_child_key = key.to_vec();
-        let value = self.substate_store.get_decoded_grand_child_substate(
-            &component_address,
-            lazy_map_id,
-            &grand_child_key,
-        );
+        let value =
+            self.read_grand_child_substate(&component_address, lazy_map_id, &grand_child_key);
         if let Some((ref entry_bytes, phys_id)) = value {
             self.lazy_map_entries.insert(
                 canonical_id,
@@ -378,6 +977,27 @@ impl<'s, S: SubstateStore> Track<'s, S> {
         value.map(|r| r.0)
     }
 
+    /// Returns an entry of a lazy map which the owning component allows other components to read.
+    pub fn get_readable_lazy_map_entry(
+        &mut self,
+        component_address: ComponentAddress,
+        lazy_map_id: &LazyMapId,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, RuntimeError> {
+        self.acquire_lock(SubstateId::Component(component_address), LockMode::Read)
+            .map_err(|e| component_reentrancy(e, component_address))?;
+        let readable = self
+            .get_component(component_address)
+            .map(|component| component.is_lazy_map_readable(lazy_map_id));
+        let result = match readable {
+            Err(e) => Err(e),
+            Ok(false) => Err(RuntimeError::LazyMapNotReadable(*lazy_map_id)),
+            Ok(true) => Ok(self.get_lazy_map_entry(component_address, lazy_map_id, key)),
+        };
+        self.release_lock(SubstateId::Component(component_address), LockMode::Read);
+        result
+    }
+
     pub fn put_lazy_map_entry(
         &mut self,
         component_address: ComponentAddress,
@@ -388,11 +1008,7 @@ impl<'s, S: SubstateStore> Track<'s, S> {
         let canonical_id = (component_address.clone(), lazy_map_id.clone(), key.clone());
 
         if !self.lazy_map_entries.contains_key(&canonical_id) {
-            let entry = self.substate_store.get_decoded_grand_child_substate(
-                &component_address,
-                &lazy_map_id,
-                &key,
-            );
+            let entry = self.read_grand_child_substate(&component_address, &lazy_map_id, &key);
             if let Some((_, phys_id)) = entry {
                 self.lazy_map_entries.insert(
                     canonical_id,
@@ -420,20 +1036,19 @@ impl<'s, S: SubstateStore> Track<'s, S> {
     }
 
     /// Returns an immutable reference to a resource manager, if exists.
+    ///
+    /// Fails if the resource manager is borrowed.
     pub fn get_resource_manager(
         &mut self,
         resource_address: &ResourceAddress,
-    ) -> Option<&ResourceManager> {
-        if self.resource_managers.contains_key(resource_address) {
-            return self
-                .resource_managers
-                .get(resource_address)
-                .map(|r| &r.value);
-        }
-
-        if let Some((resource_manager, phys_id)) =
-            self.substate_store.get_decoded_substate(resource_address)
-        {
+    ) -> Result<&ResourceManager, RuntimeError> {
+        self.check_read_lock(SubstateId::ResourceManager(*resource_address))?;
+        if !self.resource_managers.contains_key(resource_address) {
+            let (resource_manager, phys_id) = self
+                .read_decoded_substate(resource_address)
+                .ok_or_else(|| {
+                    self.not_found(RuntimeError::ResourceManagerNotFound(*resource_address))
+                })?;
             self.resource_managers.insert(
                 resource_address.clone(),
                 SubstateUpdate {
@@ -441,36 +1056,47 @@ impl<'s, S: SubstateStore> Track<'s, S> {
                     value: resource_manager,
                 },
             );
-            self.resource_managers
-                .get(resource_address)
-                .map(|r| &r.value)
-        } else {
-            None
         }
+        Ok(&self.resource_managers.get(resource_address).unwrap().value)
+    }
+
+    /// Returns the symbol of a resource whose manager has already been loaded.
+    ///
+    /// Unlike `get_resource_manager`, this never reads the substate store, so that tracing
+    /// doesn't affect the cost units consumed.
+    pub fn resource_symbol(&self, resource_address: ResourceAddress) -> Option<String> {
+        self.resource_managers
+            .get(&resource_address)
+            .and_then(|r| r.value.metadata().get("symbol"))
+            .map(|symbol| symbol.to_string())
     }
 
     pub fn borrow_global_mut_resource_manager(
         &mut self,
         resource_address: ResourceAddress,
     ) -> Result<ResourceManager, RuntimeError> {
-        let maybe_resource = self.resource_managers.remove(&resource_address);
-        if self
-            .borrowed_resource_managers
-            .contains_key(&resource_address)
+        self.acquire_lock(
+            SubstateId::ResourceManager(resource_address),
+            LockMode::Write,
+        )?;
+        if let Some(SubstateUpdate { value, prev_id }) =
+            self.resource_managers.remove(&resource_address)
         {
-            panic!("Invalid resource manager reentrancy");
-        } else if let Some(SubstateUpdate { value, prev_id }) = maybe_resource {
             self.borrowed_resource_managers
                 .insert(resource_address, prev_id);
             Ok(value)
         } else if let Some((resource_manager, phys_id)) =
-            self.substate_store.get_decoded_substate(&resource_address)
+            self.read_decoded_substate(&resource_address)
         {
             self.borrowed_resource_managers
                 .insert(resource_address, Some(phys_id));
             Ok(resource_manager)
         } else {
-            Err(RuntimeError::ResourceManagerNotFound(resource_address))
+            self.release_lock(
+                SubstateId::ResourceManager(resource_address),
+                LockMode::Write,
+            );
+            Err(self.not_found(RuntimeError::ResourceManagerNotFound(resource_address)))
         }
     }
 
@@ -480,6 +1106,10 @@ impl<'s, S: SubstateStore> Track<'s, S> {
         resource_manager: ResourceManager,
     ) {
         if let Some(prev_id) = self.borrowed_resource_managers.remove(&resource_address) {
+            self.release_lock(
+                SubstateId::ResourceManager(resource_address),
+                LockMode::Write,
+            );
             self.resource_managers.insert(
                 resource_address,
                 SubstateUpdate {
@@ -508,24 +1138,27 @@ impl<'s, S: SubstateStore> Track<'s, S> {
         resource_address
     }
 
-    pub fn borrow_vault_mut(&mut self, component_address: &ComponentAddress, vid: &VaultId) -> Vault {
+    pub fn borrow_vault_mut(
+        &mut self,
+        component_address: &ComponentAddress,
+        vid: &VaultId,
+    ) -> Result<Vault, RuntimeError> {
         let canonical_id = (component_address.clone(), vid.clone());
-        if self.borrowed_vaults.contains_key(&canonical_id) {
-            panic!("Invalid vault reentrancy");
-        }
+        self.acquire_lock(SubstateId::Vault(*component_address, *vid), LockMode::Write)?;
 
         if let Some(SubstateUpdate { value, prev_id }) = self.vaults.remove(&canonical_id) {
             self.borrowed_vaults.insert(canonical_id, prev_id);
-            return value;
+            return Ok(value);
         }
 
-        if let Some((vault, phys_id)) = self.substate_store.get_decoded_child_substate(component_address, vid) {
+        if let Some((vault, phys_id)) = self.read_decoded_child_substate(component_address, vid) {
             self.borrowed_vaults
                 .insert(canonical_id, Some(phys_id));
-            return vault;
+            return Ok(vault);
         }
 
-        panic!("Should not get here");
+        self.release_lock(SubstateId::Vault(*component_address, *vid), LockMode::Write);
+        Err(self.not_found(RuntimeError::VaultNotFound(*vid)))
     }
 
     pub fn return_borrowed_vault(
@@ -536,6 +1169,7 @@ impl<'s, S: SubstateStore> Track<'s, S> {
     ) {
         let canonical_id = (component_address.clone(), vid.clone());
         if let Some(prev_id) = self.borrowed_vaults.remove(&canonical_id) {
+            self.release_lock(SubstateId::Vault(*component_address, *vid), LockMode::Write);
             self.vaults.insert(
                 canonical_id,
                 SubstateUpdate {
@@ -565,6 +1199,296 @@ impl<'s, S: SubstateStore> Track<'s, S> {
         );
     }
 
+    pub fn borrow_locker_mut(
+        &mut self,
+        component_address: ComponentAddress,
+        locker_id: LockerId,
+    ) -> Result<Locker, RuntimeError> {
+        let canonical_id = (component_address, locker_id);
+        let substate_id = SubstateId::Locker(component_address, locker_id);
+        self.acquire_lock(substate_id.clone(), LockMode::Write)?;
+
+        let update = match self.lockers.remove(&canonical_id) {
+            Some(update) => Some(update),
+            None => self
+                .read_decoded_child_substate(&component_address, &LockerKey(locker_id))
+                .map(|(value, phys_id)| SubstateUpdate {
+                    prev_id: Some(phys_id),
+                    value,
+                }),
+        };
+        match update {
+            Some(SubstateUpdate {
+                prev_id,
+                value: Some(locker),
+            }) => {
+                self.borrowed_lockers.insert(canonical_id, prev_id);
+                Ok(locker)
+            }
+            update => {
+                // Emptied lockers are deleted, which is equivalent to never having existed
+                if let Some(update) = update {
+                    self.lockers.insert(canonical_id, update);
+                }
+                self.release_lock(substate_id, LockMode::Write);
+                Err(self.not_found(RuntimeError::LockerNotFound(locker_id)))
+            }
+        }
+    }
+
+    /// Returns a borrowed locker, deleting it if it has been emptied.
+    pub fn return_borrowed_locker(
+        &mut self,
+        component_address: ComponentAddress,
+        locker_id: LockerId,
+        locker: Locker,
+    ) {
+        let canonical_id = (component_address, locker_id);
+        if let Some(prev_id) = self.borrowed_lockers.remove(&canonical_id) {
+            self.release_lock(
+                SubstateId::Locker(component_address, locker_id),
+                LockMode::Write,
+            );
+            let value = if locker.is_empty() {
+                None
+            } else {
+                Some(locker)
+            };
+            self.lockers
+                .insert(canonical_id, SubstateUpdate { prev_id, value });
+        } else {
+            panic!("Locker was never borrowed");
+        }
+    }
+
+    /// Inserts a new locker, owned by the given component.
+    pub fn create_locker(
+        &mut self,
+        component_address: ComponentAddress,
+        locker: Locker,
+    ) -> LockerId {
+        let locker_id = self.new_locker_id();
+        self.lockers.insert(
+            (component_address, locker_id),
+            SubstateUpdate {
+                prev_id: None,
+                value: Some(locker),
+            },
+        );
+        locker_id
+    }
+
+    /// Takes some amount of resource from a vault of a component, on behalf of an allowance.
+    pub fn take_from_vault(
+        &mut self,
+        component_address: ComponentAddress,
+        vault_id: VaultId,
+        amount: Decimal,
+    ) -> Result<ResourceContainer, RuntimeError> {
+        let canonical_id = (component_address, vault_id);
+        let substate_id = SubstateId::Vault(component_address, vault_id);
+        self.acquire_lock(substate_id.clone(), LockMode::Write)
+            .map_err(|e| component_reentrancy(e, component_address))?;
+
+        let SubstateUpdate { prev_id, mut value } = match self.vaults.remove(&canonical_id) {
+            Some(update) => update,
+            None => match self.read_decoded_child_substate(&component_address, &vault_id) {
+                Some((vault, phys_id)) => SubstateUpdate {
+                    prev_id: Some(phys_id),
+                    value: vault,
+                },
+                None => {
+                    self.release_lock(substate_id, LockMode::Write);
+                    return Err(self.not_found(RuntimeError::VaultNotFound(vault_id)));
+                }
+            },
+        };
+        let result = value.take(amount).map_err(RuntimeError::VaultError);
+        self.vaults
+            .insert(canonical_id, SubstateUpdate { prev_id, value });
+        self.release_lock(substate_id, LockMode::Write);
+        result
+    }
+
+    pub fn borrow_global_mut_allowance(
+        &mut self,
+        allowance_id: AllowanceId,
+    ) -> Result<Allowance, RuntimeError> {
+        self.acquire_lock(SubstateId::Allowance(allowance_id), LockMode::Write)?;
+        if let Some(SubstateUpdate { value, prev_id }) = self.allowances.remove(&allowance_id) {
+            self.borrowed_allowances.insert(allowance_id, prev_id);
+            Ok(value)
+        } else if let Some((allowance, phys_id)) = self.read_decoded_substate(&allowance_id) {
+            self.borrowed_allowances.insert(allowance_id, Some(phys_id));
+            Ok(allowance)
+        } else {
+            self.release_lock(SubstateId::Allowance(allowance_id), LockMode::Write);
+            Err(self.not_found(RuntimeError::AllowanceNotFound(allowance_id)))
+        }
+    }
+
+    pub fn return_borrowed_global_allowance(
+        &mut self,
+        allowance_id: AllowanceId,
+        allowance: Allowance,
+    ) {
+        if let Some(prev_id) = self.borrowed_allowances.remove(&allowance_id) {
+            self.release_lock(SubstateId::Allowance(allowance_id), LockMode::Write);
+            self.allowances.insert(
+                allowance_id,
+                SubstateUpdate {
+                    prev_id,
+                    value: allowance,
+                },
+            );
+        } else {
+            panic!("Allowance was never borrowed");
+        }
+    }
+
+    /// Returns a borrowed component, locker or allowance.
+    pub fn return_borrowed_node(&mut self, node_id: RENodeId, node: RENode) {
+        match (node_id, node) {
+            (RENodeId::Component(component_address), RENode::Component(component)) => {
+                self.return_borrowed_global_component(component_address, component)
+            }
+            (RENodeId::Locker(component_address, locker_id), RENode::Locker(locker)) => {
+                self.return_borrowed_locker(component_address, locker_id, locker)
+            }
+            (RENodeId::Allowance(allowance_id), RENode::Allowance(allowance)) => {
+                self.return_borrowed_global_allowance(allowance_id, allowance)
+            }
+            (node_id, _) => panic!("{:?} can't be borrowed from the track", node_id),
+        }
+    }
+
+    /// Inserts a new allowance.
+    pub fn create_allowance(&mut self, allowance: Allowance) -> AllowanceId {
+        let allowance_id = self.new_allowance_id();
+        self.allowances.insert(
+            allowance_id,
+            SubstateUpdate {
+                prev_id: None,
+                value: allowance,
+            },
+        );
+        allowance_id
+    }
+
+    /// Makes a component observe the events of the given name emitted by another component.
+    pub fn subscribe_to_event(
+        &mut self,
+        observer: ComponentAddress,
+        emitter: ComponentAddress,
+        event: String,
+    ) -> Result<(), RuntimeError> {
+        // The emitter may be locked, as a component can observe its own events or its caller's
+        if !self.component_exists(emitter) {
+            return Err(self.not_found(RuntimeError::ComponentNotFound(emitter)));
+        }
+        let registry = self.notification_registry_mut(emitter);
+        if observer != emitter && !registry.is_subscribable(&event) {
+            return Err(RuntimeError::EventNotSubscribable { emitter, event });
+        }
+        if !registry.add_observer(event, observer) {
+            return Err(RuntimeError::TooManyEventObservers(emitter));
+        }
+        Ok(())
+    }
+
+    /// Allows or disallows other components to observe the events of the given name emitted by a
+    /// component.
+    pub fn set_event_subscribable(
+        &mut self,
+        emitter: ComponentAddress,
+        event: String,
+        subscribable: bool,
+    ) {
+        self.notification_registry_mut(emitter)
+            .set_subscribable(event, subscribable);
+    }
+
+    /// Makes a component stop observing the events of the given name emitted by another component.
+    pub fn unsubscribe_from_event(
+        &mut self,
+        observer: ComponentAddress,
+        emitter: ComponentAddress,
+        event: &str,
+    ) {
+        self.notification_registry_mut(emitter)
+            .remove_observer(event, observer);
+    }
+
+    /// Emits an event of a component, by recording it for the receipt and adding a notification
+    /// for each of its observers.
+    ///
+    /// Each delivered notification is charged for, while the ones dropped because the observer
+    /// has too many pending notifications aren't.
+    pub fn emit_event(
+        &mut self,
+        emitter: ComponentAddress,
+        event: String,
+        data: Vec<u8>,
+    ) -> Result<(), RuntimeError> {
+        self.events.push(Notification {
+            emitter,
+            event: event.clone(),
+            data: data.clone(),
+        });
+        let observers = self.notification_registry_mut(emitter).observers(&event);
+        for observer in observers {
+            let notification = Notification {
+                emitter,
+                event: event.clone(),
+                data: data.clone(),
+            };
+            if self
+                .notification_registry_mut(observer)
+                .push_notification(notification)
+            {
+                let cost = self.fee_table.deliver_notification();
+                self.consume_cost_units(cost, "deliver_notification")
+                    .map_err(RuntimeError::CostingError)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes and returns the notifications pending for a component.
+    pub fn take_notifications(&mut self, observer: ComponentAddress) -> Vec<Notification> {
+        self.notification_registry_mut(observer)
+            .take_notifications()
+    }
+
+    fn notification_registry_mut(
+        &mut self,
+        component_address: ComponentAddress,
+    ) -> &mut NotificationRegistry {
+        if !self
+            .notification_registries
+            .contains_key(&component_address)
+        {
+            let update =
+                match self.read_decoded_substate(&NotificationRegistryAddress(component_address)) {
+                    Some((registry, phys_id)) => SubstateUpdate {
+                        prev_id: Some(phys_id),
+                        value: registry,
+                    },
+                    None => SubstateUpdate {
+                        prev_id: None,
+                        value: NotificationRegistry::new(),
+                    },
+                };
+            self.notification_registries
+                .insert(component_address, update);
+        }
+        &mut self
+            .notification_registries
+            .get_mut(&component_address)
+            .unwrap()
+            .value
+    }
+
     /// Creates a new package ID.
     fn new_package_address(&mut self) -> PackageAddress {
         // Security Alert: ensure ID allocating will practically never fail
@@ -572,6 +1496,7 @@ impl<'s, S: SubstateStore> Track<'s, S> {
             .id_allocator
             .new_package_address(self.transaction_hash())
             .unwrap();
+        self.record_allocated_entity(AllocatedEntity::Package(package_address));
         package_address
     }
 
@@ -581,6 +1506,7 @@ impl<'s, S: SubstateStore> Track<'s, S> {
             .id_allocator
             .new_component_address(self.transaction_hash())
             .unwrap();
+        self.record_allocated_entity(AllocatedEntity::Component(component_address));
         component_address
     }
 
@@ -590,6 +1516,7 @@ impl<'s, S: SubstateStore> Track<'s, S> {
             .id_allocator
             .new_resource_address(self.transaction_hash())
             .unwrap();
+        self.record_allocated_entity(AllocatedEntity::Resource(resource_address));
         resource_address
     }
 
@@ -598,21 +1525,40 @@ impl<'s, S: SubstateStore> Track<'s, S> {
         self.id_allocator.new_uuid(self.transaction_hash()).unwrap()
     }
 
-    /// Creates a new bucket ID.
-    pub fn new_bucket_id(&mut self) -> BucketId {
-        self.id_allocator.new_bucket_id().unwrap()
+    /// Returns the index of a new call frame within this transaction, starting from 1.
+    pub fn new_call_index(&mut self) -> u32 {
+        self.call_count += 1;
+        self.call_count
     }
 
     /// Creates a new vault ID.
     pub fn new_vault_id(&mut self) -> VaultId {
-        self.id_allocator
+        let vault_id = self
+            .id_allocator
             .new_vault_id(self.transaction_hash())
-            .unwrap()
+            .unwrap();
+        self.record_allocated_entity(AllocatedEntity::Vault(vault_id));
+        vault_id
     }
 
-    /// Creates a new reference id.
-    pub fn new_proof_id(&mut self) -> ProofId {
-        self.id_allocator.new_proof_id().unwrap()
+    /// Creates a new locker ID.
+    fn new_locker_id(&mut self) -> LockerId {
+        let locker_id = self
+            .id_allocator
+            .new_locker_id(self.transaction_hash())
+            .unwrap();
+        self.record_allocated_entity(AllocatedEntity::Locker(locker_id));
+        locker_id
+    }
+
+    /// Creates a new allowance ID.
+    fn new_allowance_id(&mut self) -> AllowanceId {
+        let allowance_id = self
+            .id_allocator
+            .new_allowance_id(self.transaction_hash())
+            .unwrap();
+        self.record_allocated_entity(AllocatedEntity::Allowance(allowance_id));
+        allowance_id
     }
 
     /// Creates a new map id.
@@ -622,10 +1568,262 @@ impl<'s, S: SubstateStore> Track<'s, S> {
             .unwrap()
     }
 
+    /// Creates a new map id, for a map whose entries can be read by other components.
+    pub fn new_readable_lazy_map_id(&mut self) -> LazyMapId {
+        let lazy_map_id = self.new_lazy_map_id();
+        self.readable_lazy_map_ids.insert(lazy_map_id);
+        lazy_map_id
+    }
+
+    /// Returns the given lazy maps which were created as readable by other components.
+    pub fn readable_lazy_map_ids(&self, lazy_map_ids: Vec<LazyMapId>) -> Vec<LazyMapId> {
+        lazy_map_ids
+            .into_iter()
+            .filter(|lazy_map_id| self.readable_lazy_map_ids.contains(lazy_map_id))
+            .collect()
+    }
+
+    fn read_decoded_substate<A: Encode, T: Decode>(
+        &mut self,
+        address: &A,
+    ) -> Option<(T, (Hash, u32))> {
+        let id = scrypto_encode(address);
+        self.read_substate(id, |store| store.get_substate(address))
+            .map(|s| (scrypto_decode(&s.value).unwrap(), s.phys_id))
+    }
+
+    fn read_decoded_child_substate<A: Encode, K: Encode, T: Decode>(
+        &mut self,
+        address: &A,
+        key: &K,
+    ) -> Option<(T, (Hash, u32))> {
+        let child_key = scrypto_encode(key);
+        let id = Self::child_substate_id(address, &child_key);
+        self.read_substate(id, |store| store.get_child_substate(address, &child_key))
+            .map(|s| (scrypto_decode(&s.value).unwrap(), s.phys_id))
+    }
+
+    fn read_grand_child_substate<A: Encode, C: Encode>(
+        &mut self,
+        address: &A,
+        child_key: &C,
+        grand_child_key: &[u8],
+    ) -> Option<(Vec<u8>, (Hash, u32))> {
+        let mut key = scrypto_encode(child_key);
+        key.extend(grand_child_key.to_vec());
+        let id = Self::child_substate_id(address, &key);
+        self.read_substate(id, |store| store.get_child_substate(address, &key))
+            .map(|s| (s.value, s.phys_id))
+    }
+
+    /// Reads a committed substate, through the per-transaction read cache and the shared
+    /// substate cache.
+    fn read_substate<F: FnOnce(&S) -> Option<Substate>>(
+        &mut self,
+        id: Vec<u8>,
+        read: F,
+    ) -> Option<Substate> {
+        if let Some(substate) = self.read_cache.get(&id) {
+            self.read_stats.transaction_cache_hits += 1;
+            if let Some(hooks) = self.hooks.as_mut() {
+                hooks.on_substate_read(&id, substate.as_ref().map(|s| s.value.as_slice()));
+            }
+            return substate.clone();
+        }
+
+        let cached = self
+            .substate_cache
+            .as_mut()
+            .and_then(|cache| cache.get(&id));
+        let substate = match cached {
+            Some(substate) => {
+                self.read_stats.substate_cache_hits += 1;
+                substate
+            }
+            None => {
+                self.read_stats.store_reads += 1;
+                let substate = read(&*self.substate_store);
+                // A substate which hasn't been fetched may still exist, so it isn't cached
+                if substate.is_none() && self.substate_store.has_unfetched_reads() {
+                    return None;
+                }
+                if let Some(cache) = self.substate_cache.as_mut() {
+                    cache.put(id.clone(), substate.clone());
+                }
+                substate
+            }
+        };
+        // Billed whether or not the shared cache was hit, so that costs are deterministic
+        self.unbilled_read_bytes += substate.as_ref().map_or(0, |s| s.value.len());
+        if let Some(hooks) = self.hooks.as_mut() {
+            hooks.on_substate_read(&id, substate.as_ref().map(|s| s.value.as_slice()));
+        }
+        self.read_cache.insert(id, substate.clone());
+        substate
+    }
+
+    /// Returns the error of a substate which wasn't found, unless the store hasn't fetched it.
+    fn not_found(&self, error: RuntimeError) -> RuntimeError {
+        if self.substate_store.has_unfetched_reads() {
+            RuntimeError::SubstateNotFetched
+        } else {
+            error
+        }
+    }
+
+    /// Fails if a substate has been read which the store hasn't fetched yet.
+    ///
+    /// Reads which are allowed to find nothing can't tell a missing substate from one which
+    /// hasn't been fetched, so the outcome of the transaction can't be trusted either way.
+    pub fn check_fetched(&self) -> Result<(), RuntimeError> {
+        if self.substate_store.has_unfetched_reads() {
+            Err(RuntimeError::SubstateNotFetched)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn child_substate_id<A: Encode>(address: &A, key: &[u8]) -> Vec<u8> {
+        let mut id = scrypto_encode(address);
+        id.extend(key.to_vec());
+        id
+    }
+
+    fn invalidate_cached_substate(&mut self, id: Vec<u8>) {
+        if let Some(cache) = self.substate_cache.as_mut() {
+            cache.invalidate(&id);
+        }
+    }
+
+    /// Recomputes the resource changes of the transaction, by comparing the resource managers,
+    /// vaults and lockers it touched with their committed values.
+    pub fn resource_audit(&self) -> ResourceAudit {
+        let mut audit = ResourceAudit::new();
+        for (address, update) in &self.resource_managers {
+            let committed =
+                self.committed_value(&scrypto_encode(address))
+                    .map_or(Decimal::zero(), |value| {
+                        scrypto_decode::<ResourceManager>(value)
+                            .unwrap()
+                            .total_supply()
+                    });
+            audit.record_supply_change(*address, update.value.total_supply() - committed);
+        }
+        for ((component_address, vault_id), update) in &self.vaults {
+            let id = Self::child_substate_id(component_address, &scrypto_encode(vault_id));
+            let committed = self.committed_value(&id).map_or(Decimal::zero(), |value| {
+                scrypto_decode::<Vault>(value).unwrap().total_amount()
+            });
+            audit.record_held_change(
+                update.value.resource_address(),
+                update.value.total_amount() - committed,
+            );
+        }
+        for ((component_address, locker_id), update) in &self.lockers {
+            let id =
+                Self::child_substate_id(component_address, &scrypto_encode(&LockerKey(*locker_id)));
+            let committed = self
+                .committed_value(&id)
+                .and_then(|value| scrypto_decode::<Option<Locker>>(value).unwrap());
+            // The resource of a deleted locker is only known from its committed value
+            let resource_address = match (&update.value, &committed) {
+                (Some(locker), _) | (None, Some(locker)) => locker.resource_address(),
+                (None, None) => continue,
+            };
+            let amount = |locker: &Option<Locker>| {
+                locker
+                    .as_ref()
+                    .map_or(Decimal::zero(), |locker| locker.total_amount())
+            };
+            audit.record_held_change(resource_address, amount(&update.value) - amount(&committed));
+        }
+        audit
+    }
+
+    /// Returns whether any substate has been created or differs from its committed value.
+    pub fn has_state_updates(&self) -> bool {
+        self.new_epoch.is_some()
+            || self.new_engine_version.is_some()
+            || !self.updated_substates().is_empty()
+    }
+
+    /// Returns the ids and encoded values of the substates which have been created or differ
+    /// from their committed values.
+    fn updated_substates(&self) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let mut values = Vec::new();
+        for (address, update) in &self.packages {
+            self.push_if_updated(&mut values, scrypto_encode(address), &update.value);
+        }
+        for (address, update) in &self.components {
+            self.push_if_updated(&mut values, scrypto_encode(address), &update.value);
+        }
+        for (address, update) in &self.resource_managers {
+            self.push_if_updated(&mut values, scrypto_encode(address), &update.value);
+        }
+        for ((component_address, vault_id), update) in &self.vaults {
+            let id = Self::child_substate_id(component_address, &scrypto_encode(vault_id));
+            self.push_if_updated(&mut values, id, &update.value);
+        }
+        for ((component_address, locker_id), update) in &self.lockers {
+            let id =
+                Self::child_substate_id(component_address, &scrypto_encode(&LockerKey(*locker_id)));
+            self.push_if_updated(&mut values, id, &update.value);
+        }
+        for (allowance_id, update) in &self.allowances {
+            self.push_if_updated(&mut values, scrypto_encode(allowance_id), &update.value);
+        }
+        for (component_address, update) in &self.notification_registries {
+            if update.prev_id.is_none() && update.value.is_empty() {
+                continue;
+            }
+            let id = scrypto_encode(&NotificationRegistryAddress(*component_address));
+            self.push_if_updated(&mut values, id, &update.value);
+        }
+        for (address, update) in &self.non_fungibles {
+            let id = Self::child_substate_id(
+                &address.resource_address(),
+                &scrypto_encode(&address.non_fungible_id()),
+            );
+            self.push_if_updated(&mut values, id, &update.value);
+        }
+        for ((component_address, lazy_map_id, key), update) in &self.lazy_map_entries {
+            let mut child_key = scrypto_encode(lazy_map_id);
+            child_key.extend(key);
+            let id = Self::child_substate_id(component_address, &child_key);
+            // Lazy map entries are stored without further encoding
+            if self.committed_value(&id) != Some(&update.value) {
+                values.push((id, update.value.clone()));
+            }
+        }
+        values
+    }
+
+    fn push_if_updated<V: Encode>(
+        &self,
+        values: &mut Vec<(Vec<u8>, Vec<u8>)>,
+        id: Vec<u8>,
+        value: &V,
+    ) {
+        let value = scrypto_encode(value);
+        if self.committed_value(&id) != Some(&value) {
+            values.push((id, value));
+        }
+    }
+
+    fn committed_value(&self, id: &Vec<u8>) -> Option<&Vec<u8>> {
+        self.read_cache
+            .get(id)
+            .and_then(|s| s.as_ref())
+            .map(|s| &s.value)
+    }
+
     /// Commits changes to the underlying ledger.
     /// Currently none of these objects are deleted so all commits are puts
     pub fn commit(&mut self) -> CommitReceipt {
         // Sanity check
+        if !self.borrowed_packages.is_empty() {
+            panic!("Borrowed packages should be empty by end of transaction.");
+        }
         if !self.borrowed_components.is_empty() {
             panic!("Borrowed components should be empty by end of transaction.");
         }
@@ -635,10 +1833,26 @@ impl<'s, S: SubstateStore> Track<'s, S> {
         if !self.borrowed_vaults.is_empty() {
             panic!("Borrowed vaults should be empty by end of transaction.");
         }
+        if !self.borrowed_lockers.is_empty() {
+            panic!("Borrowed lockers should be empty by end of transaction.");
+        }
+        if !self.borrowed_allowances.is_empty() {
+            panic!("Borrowed allowances should be empty by end of transaction.");
+        }
+        if !self.substate_locks.is_empty() {
+            panic!("Substate locks should be released by end of transaction.");
+        }
 
         let mut receipt = CommitReceipt::new();
         let mut id_gen = SubstateIdGenerator::new(self.transaction_hash());
 
+        if let Some(epoch) = self.new_epoch.take() {
+            self.substate_store.set_epoch(epoch);
+        }
+        if let Some(engine_version) = self.new_engine_version.take() {
+            self.substate_store.set_engine_version(engine_version);
+        }
+
         let package_addresses: Vec<PackageAddress> = self.packages.keys().cloned().collect();
         for package_address in package_addresses {
             let package = self.packages.remove(&package_address).unwrap();
@@ -651,6 +1865,18 @@ impl<'s, S: SubstateStore> Track<'s, S> {
 
             self.substate_store
                 .put_encoded_substate(&package_address, &package.value, phys_id);
+            self.invalidate_cached_substate(scrypto_encode(&package_address));
+
+            // Schemas are immutable and keyed by hash, so they're only stored once
+            for (schema_hash, schema) in package.value.schemas() {
+                if let Some(phys_id) =
+                    self.substate_store
+                        .put_schema(*schema_hash, schema, &mut id_gen)
+                {
+                    receipt.up(phys_id);
+                    self.invalidate_cached_substate(scrypto_encode(&SchemaAddress(*schema_hash)));
+                }
+            }
         }
 
         let component_addresses: Vec<ComponentAddress> = self.components.keys().cloned().collect();
@@ -665,6 +1891,7 @@ impl<'s, S: SubstateStore> Track<'s, S> {
 
             self.substate_store
                 .put_encoded_substate(&component_address, &component.value, phys_id);
+            self.invalidate_cached_substate(scrypto_encode(&component_address));
         }
 
         let resource_addresses: Vec<ResourceAddress> =
@@ -683,6 +1910,7 @@ impl<'s, S: SubstateStore> Track<'s, S> {
                 &resource_manager.value,
                 phys_id,
             );
+            self.invalidate_cached_substate(scrypto_encode(&resource_address));
         }
 
         let entry_ids: Vec<(ComponentAddress, LazyMapId, Vec<u8>)> =
@@ -703,8 +1931,70 @@ impl<'s, S: SubstateStore> Track<'s, S> {
                 &entry.value,
                 phys_id,
             );
+            let mut child_key = scrypto_encode(&lazy_map_id);
+            child_key.extend(key);
+            self.invalidate_cached_substate(Self::child_substate_id(
+                &component_address,
+                &child_key,
+            ));
+        }
+
+        // Non-fungibles which leave a vault for a locker are no longer in any vault. Lockers are
+        // committed first, so that ids claimed into a vault within the same transaction are
+        // indexed by the vault.
+        let index_non_fungibles = self.substate_store.is_non_fungible_index_enabled();
+        let locker_ids: Vec<(ComponentAddress, LockerId)> = self.lockers.keys().cloned().collect();
+        for (component_address, locker_id) in locker_ids {
+            let locker = self
+                .lockers
+                .remove(&(component_address, locker_id))
+                .unwrap();
+            if let Some(prev_id) = locker.prev_id {
+                receipt.down(prev_id);
+            }
+            let phys_id = id_gen.next();
+            receipt.up(phys_id);
+
+            if let (true, Some(value)) = (index_non_fungibles, &locker.value) {
+                if let Ok(ids) = value.total_ids() {
+                    let resource_address = value.resource_address();
+                    for id in ids {
+                        self.substate_store.put_non_fungible_location(
+                            NonFungibleAddress::new(resource_address, id),
+                            None,
+                        );
+                    }
+                }
+            }
+            self.substate_store.put_encoded_child_substate(
+                &component_address,
+                &LockerKey(locker_id),
+                &locker.value,
+                phys_id,
+            );
+            self.invalidate_cached_substate(Self::child_substate_id(
+                &component_address,
+                &scrypto_encode(&LockerKey(locker_id)),
+            ));
+        }
+
+        let allowance_ids: Vec<AllowanceId> = self.allowances.keys().cloned().collect();
+        for allowance_id in allowance_ids {
+            let allowance = self.allowances.remove(&allowance_id).unwrap();
+            if let Some(prev_id) = allowance.prev_id {
+                receipt.down(prev_id);
+            }
+            let phys_id = id_gen.next();
+            receipt.up(phys_id);
+
+            self.substate_store
+                .put_encoded_substate(&allowance_id, &allowance.value, phys_id);
+            self.invalidate_cached_substate(scrypto_encode(&allowance_id));
         }
 
+        // Non-fungibles can otherwise only leave a vault for another vault in the same
+        // transaction, or be burned, so indexing the ids of the committed vaults keeps the index
+        // up to date.
         let vault_ids: Vec<(ComponentAddress, VaultId)> = self.vaults.keys().cloned().collect();
         for vault_id in vault_ids {
             let vault = self.vaults.remove(&vault_id).unwrap();
@@ -715,18 +2005,40 @@ impl<'s, S: SubstateStore> Track<'s, S> {
             receipt.up(phys_id);
 
             let (component_address, vault_id) = vault_id;
+            if index_non_fungibles {
+                if let Ok(ids) = vault.value.total_ids() {
+                    let resource_address = vault.value.resource_address();
+                    for id in ids {
+                        self.substate_store.put_non_fungible_location(
+                            NonFungibleAddress::new(resource_address, id),
+                            Some(NonFungibleLocation {
+                                component_address,
+                                vault_id,
+                            }),
+                        );
+                    }
+                }
+            }
             self.substate_store.put_encoded_child_substate(
                 &component_address,
                 &vault_id,
                 &vault.value,
                 phys_id,
             );
+            self.invalidate_cached_substate(Self::child_substate_id(
+                &component_address,
+                &scrypto_encode(&vault_id),
+            ));
         }
 
         let non_fungible_addresses: Vec<NonFungibleAddress> =
             self.non_fungibles.keys().cloned().collect();
         for non_fungible_address in non_fungible_addresses {
             let non_fungible = self.non_fungibles.remove(&non_fungible_address).unwrap();
+            if index_non_fungibles && non_fungible.value.is_none() {
+                self.substate_store
+                    .put_non_fungible_location(non_fungible_address.clone(), None);
+            }
             if let Some(prev_id) = non_fungible.prev_id {
                 receipt.down(prev_id);
             }
@@ -739,8 +2051,46 @@ impl<'s, S: SubstateStore> Track<'s, S> {
                 &non_fungible.value,
                 phys_id,
             );
+            self.invalidate_cached_substate(Self::child_substate_id(
+                &non_fungible_address.resource_address(),
+                &scrypto_encode(&non_fungible_address.non_fungible_id()),
+            ));
         }
 
+        let component_addresses: Vec<ComponentAddress> =
+            self.notification_registries.keys().cloned().collect();
+        for component_address in component_addresses {
+            let registry = self
+                .notification_registries
+                .remove(&component_address)
+                .unwrap();
+            // Registries are loaded by any emitted event, so don't create empty ones
+            if registry.prev_id.is_none() && registry.value.is_empty() {
+                continue;
+            }
+            if let Some(prev_id) = registry.prev_id {
+                receipt.down(prev_id);
+            }
+            let phys_id = id_gen.next();
+            receipt.up(phys_id);
+
+            let address = NotificationRegistryAddress(component_address);
+            self.substate_store
+                .put_encoded_substate(&address, &registry.value, phys_id);
+            self.invalidate_cached_substate(scrypto_encode(&address));
+        }
+
+        // Committed state has changed
+        self.read_cache.clear();
+
         receipt
     }
 }
+
+/// Reports a conflicting lock on a component, or on one of its vaults, as reentrancy.
+fn component_reentrancy(error: RuntimeError, component_address: ComponentAddress) -> RuntimeError {
+    match error {
+        RuntimeError::SubstateLocked { .. } => RuntimeError::ComponentReentrancy(component_address),
+        error => error,
+    }
+}
@@ -0,0 +1,1254 @@
+use scrypto::rust::str;
+use scrypto::rust::string::ToString;
+use scrypto::rust::vec::Vec;
+
+use crate::engine::ENGINE_FUNCTION_NAME;
+use crate::errors::WasmValidationError;
+
+/// A post-MVP wasm feature which is subject to the feature policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WasmFeature {
+    BulkMemory,
+    SignExtension,
+    Simd,
+    ReferenceTypes,
+    MultiMemory,
+}
+
+/// The set of post-MVP wasm features accepted by package validation.
+///
+/// Features accepted by the policy are still rejected if the interpreter can't parse them.
+/// Floating point is rejected unless NaN canonicalization is enabled, as the bits of the NaNs
+/// it produces aren't deterministic across platforms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasmFeatures {
+    pub bulk_memory: bool,
+    pub sign_extension: bool,
+    pub simd: bool,
+    pub reference_types: bool,
+    pub multi_memory: bool,
+    /// Whether floating point is accepted, with the modules rewritten by `canonicalize_nans`
+    /// before they're instantiated.
+    pub nan_canonicalization: bool,
+}
+
+impl WasmFeatures {
+    /// Accepts the MVP instruction set only.
+    pub const MVP: WasmFeatures = WasmFeatures {
+        bulk_memory: false,
+        sign_extension: false,
+        simd: false,
+        reference_types: false,
+        multi_memory: false,
+        nan_canonicalization: false,
+    };
+
+    pub fn is_enabled(&self, feature: WasmFeature) -> bool {
+        match feature {
+            WasmFeature::BulkMemory => self.bulk_memory,
+            WasmFeature::SignExtension => self.sign_extension,
+            WasmFeature::Simd => self.simd,
+            WasmFeature::ReferenceTypes => self.reference_types,
+            WasmFeature::MultiMemory => self.multi_memory,
+        }
+    }
+
+    /// Checks that a wasm module only uses the features accepted by this policy, and doesn't use
+    /// floating point types or instructions.
+    pub fn check(&self, code: &[u8]) -> Result<(), WasmValidationError> {
+        self.check_with_limits(code, None)
+    }
+
+    /// Checks a wasm module against this policy and, if given, the import allow-list, export
+    /// naming rules and structural limits.
+    pub fn check_with_limits(
+        &self,
+        code: &[u8],
+        limits: Option<&WasmLimits>,
+    ) -> Result<(), WasmValidationError> {
+        WasmFeatureScanner::new(self, limits, code).scan()
+    }
+
+    /// Rewrites a wasm module accepted by this policy so that the float operations which may
+    /// produce a NaN produce the canonical NaN instead, whose bits are the same on every
+    /// platform.
+    ///
+    /// Each result is checked with `select` on a scratch local, which is added to the functions
+    /// which need one. Loads, stores, constants and bitwise operations such as `f32.neg` and
+    /// `f32.copysign` are left as they are, as they preserve the bits of their operands.
+    pub fn canonicalize_nans(&self, code: &[u8]) -> Result<Vec<u8>, WasmValidationError> {
+        let mut scanner = WasmFeatureScanner::new(self, None, code);
+        scanner.function_bodies = Some(Vec::new());
+        scanner.scan()?;
+
+        let (section_start, section_end) = match scanner.code_section {
+            Some(section) => section,
+            None => return Ok(code.to_vec()),
+        };
+        let bodies = scanner.function_bodies.unwrap_or_default();
+        if bodies.len() != scanner.function_types.len() {
+            return Err(WasmValidationError::InvalidModule);
+        }
+
+        let mut section = Vec::new();
+        write_u32(&mut section, bodies.len() as u32);
+        for (body, type_index) in bodies.iter().zip(&scanner.function_types) {
+            let params = *scanner
+                .type_params
+                .get(*type_index as usize)
+                .ok_or(WasmValidationError::InvalidModule)?;
+            let rewritten = body.canonicalize_nans(code, params)?;
+            write_u32(&mut section, rewritten.len() as u32);
+            section.extend(rewritten);
+        }
+
+        let mut output = code[..section_start].to_vec();
+        output.push(10);
+        write_u32(&mut output, section.len() as u32);
+        output.extend(section);
+        output.extend_from_slice(&code[section_end..]);
+        Ok(output)
+    }
+}
+
+/// The bits of the canonical `f32` NaN.
+const CANONICAL_NAN_F32: u32 = 0x7FC0_0000;
+
+/// The bits of the canonical `f64` NaN.
+const CANONICAL_NAN_F64: u64 = 0x7FF8_0000_0000_0000;
+
+/// A float type, of a value which may need NaN canonicalization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FloatType {
+    F32,
+    F64,
+}
+
+/// The layout of a function body, as recorded for NaN canonicalization.
+#[derive(Debug)]
+struct FunctionBody {
+    /// The offset of the first local declaration, after their count.
+    locals_start: usize,
+    /// The number of local declarations.
+    local_declarations: u32,
+    /// The number of locals declared.
+    locals: u64,
+    /// The offset of the first instruction.
+    instructions_start: usize,
+    /// The offset after the `end` of the body.
+    end: usize,
+    /// The offsets after the instructions whose result must be canonicalized.
+    nan_results: Vec<(usize, FloatType)>,
+}
+
+impl FunctionBody {
+    fn canonicalize_nans(&self, code: &[u8], params: u32) -> Result<Vec<u8>, WasmValidationError> {
+        let mut body = Vec::new();
+        if self.nan_results.is_empty() {
+            write_u32(&mut body, self.local_declarations);
+            body.extend_from_slice(&code[self.locals_start..self.end]);
+            return Ok(body);
+        }
+
+        // The scratch locals come after the parameters and the declared locals
+        let f32_local = u32::try_from(params as u64 + self.locals)
+            .ok()
+            .filter(|index| *index < u32::MAX)
+            .ok_or(WasmValidationError::InvalidModule)?;
+        let f64_local = f32_local + 1;
+        write_u32(&mut body, self.local_declarations + 2);
+        body.extend_from_slice(&code[self.locals_start..self.instructions_start]);
+        body.extend([0x01, 0x7D, 0x01, 0x7C]);
+
+        let mut offset = self.instructions_start;
+        for (result_end, float_type) in &self.nan_results {
+            body.extend_from_slice(&code[offset..*result_end]);
+            offset = *result_end;
+
+            // local.set $x, <canonical NaN>, local.get $x, local.get $x, local.get $x, ne,
+            // select: the canonical NaN if $x is a NaN, $x otherwise
+            let local = match float_type {
+                FloatType::F32 => f32_local,
+                FloatType::F64 => f64_local,
+            };
+            body.push(0x21);
+            write_u32(&mut body, local);
+            match float_type {
+                FloatType::F32 => {
+                    body.push(0x43);
+                    body.extend(CANONICAL_NAN_F32.to_le_bytes());
+                }
+                FloatType::F64 => {
+                    body.push(0x44);
+                    body.extend(CANONICAL_NAN_F64.to_le_bytes());
+                }
+            }
+            for _ in 0..3 {
+                body.push(0x20);
+                write_u32(&mut body, local);
+            }
+            body.push(match float_type {
+                FloatType::F32 => 0x5C,
+                FloatType::F64 => 0x62,
+            });
+            body.push(0x1B);
+        }
+        body.extend_from_slice(&code[offset..self.end]);
+        Ok(body)
+    }
+}
+
+/// Appends the unsigned LEB128 encoding of a value.
+fn write_u32(output: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            output.push(byte);
+            return;
+        }
+        output.push(byte | 0x80);
+    }
+}
+
+/// Limits on the structure of a wasm module.
+///
+/// Where limits are enforced, a module may only import the engine function, and may only export
+/// names which are identifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasmLimits {
+    /// The maximum number of functions defined by the module.
+    pub max_functions: u32,
+    /// The maximum size in bytes of a function body.
+    pub max_function_size: usize,
+    /// The maximum number of globals defined by the module.
+    pub max_globals: u32,
+    /// The maximum size of a table, which also bounds the entries initialized by element
+    /// segments.
+    pub max_table_size: u32,
+}
+
+/// Walks the sections and instructions of a wasm module, looking for post-MVP features and
+/// floating point, and checking the structural limits if any.
+struct WasmFeatureScanner<'a> {
+    features: &'a WasmFeatures,
+    limits: Option<&'a WasmLimits>,
+    code: &'a [u8],
+    offset: usize,
+    memories: u32,
+    tables: u32,
+    /// The number of parameters of each function type.
+    type_params: Vec<u32>,
+    /// The type of each function defined by the module.
+    function_types: Vec<u32>,
+    /// The offsets of the code section header and of its end, if any.
+    code_section: Option<(usize, usize)>,
+    /// The function bodies, if recorded for NaN canonicalization.
+    function_bodies: Option<Vec<FunctionBody>>,
+}
+
+impl<'a> WasmFeatureScanner<'a> {
+    fn new(features: &'a WasmFeatures, limits: Option<&'a WasmLimits>, code: &'a [u8]) -> Self {
+        Self {
+            features,
+            limits,
+            code,
+            offset: 0,
+            memories: 0,
+            tables: 0,
+            type_params: Vec::new(),
+            function_types: Vec::new(),
+            code_section: None,
+            function_bodies: None,
+        }
+    }
+
+    fn scan(&mut self) -> Result<(), WasmValidationError> {
+        if self.read_bytes(8)? != b"\0asm\x01\0\0\0" {
+            return Err(WasmValidationError::InvalidModule);
+        }
+
+        while self.offset < self.code.len() {
+            let section_start = self.offset;
+            let id = self.read_byte()?;
+            let size = self.read_u32()? as usize;
+            let end = self
+                .offset
+                .checked_add(size)
+                .filter(|end| *end <= self.code.len())
+                .ok_or(WasmValidationError::InvalidModule)?;
+            match id {
+                1 => self.scan_types()?,
+                2 => self.scan_imports()?,
+                3 => self.scan_functions()?,
+                4 => self.scan_tables()?,
+                5 => self.scan_memories()?,
+                6 => self.scan_globals()?,
+                7 => self.scan_exports()?,
+                9 => self.scan_elements()?,
+                10 => {
+                    self.code_section = Some((section_start, end));
+                    self.scan_code(end)?
+                }
+                11 => self.scan_data()?,
+                12 => self.require(WasmFeature::BulkMemory)?,
+                _ => {}
+            }
+            self.offset = end;
+        }
+
+        if self.memories > 1 {
+            self.require(WasmFeature::MultiMemory)?;
+        }
+        if self.tables > 1 {
+            self.require(WasmFeature::ReferenceTypes)?;
+        }
+        Ok(())
+    }
+
+    fn scan_types(&mut self) -> Result<(), WasmValidationError> {
+        for _ in 0..self.read_u32()? {
+            if self.read_byte()? != 0x60 {
+                return Err(WasmValidationError::InvalidModule);
+            }
+            let params = self.read_u32()?;
+            for _ in 0..params {
+                self.scan_value_type()?;
+            }
+            for _ in 0..self.read_u32()? {
+                self.scan_value_type()?;
+            }
+            self.type_params.push(params);
+        }
+        Ok(())
+    }
+
+    fn scan_imports(&mut self) -> Result<(), WasmValidationError> {
+        for _ in 0..self.read_u32()? {
+            let module = self.read_name()?;
+            let name = self.read_name()?;
+            if self.limits.is_some()
+                && (module != "env"
+                    || name != ENGINE_FUNCTION_NAME
+                    || self.code.get(self.offset) != Some(&0x00))
+            {
+                return Err(WasmValidationError::ImportNotAllowed {
+                    module: module.to_string(),
+                    name: name.to_string(),
+                });
+            }
+            match self.read_byte()? {
+                0x00 => {
+                    self.read_u32()?;
+                }
+                0x01 => self.scan_table_type()?,
+                0x02 => {
+                    self.memories += 1;
+                    self.skip_limits()?;
+                }
+                0x03 => {
+                    self.scan_value_type()?;
+                    self.read_byte()?;
+                }
+                _ => return Err(WasmValidationError::InvalidModule),
+            }
+        }
+        Ok(())
+    }
+
+    fn scan_functions(&mut self) -> Result<(), WasmValidationError> {
+        let count = self.read_u32()?;
+        if let Some(limits) = self.limits {
+            if count > limits.max_functions {
+                return Err(WasmValidationError::TooManyFunctions(count));
+            }
+        }
+        for _ in 0..count {
+            let type_index = self.read_u32()?;
+            self.function_types.push(type_index);
+        }
+        Ok(())
+    }
+
+    fn scan_tables(&mut self) -> Result<(), WasmValidationError> {
+        for _ in 0..self.read_u32()? {
+            self.scan_table_type()?;
+        }
+        Ok(())
+    }
+
+    fn scan_table_type(&mut self) -> Result<(), WasmValidationError> {
+        self.tables += 1;
+        self.scan_reference_type()?;
+        let (min, max) = self.read_limits()?;
+        if let Some(limits) = self.limits {
+            // A table without a maximum can grow without bound
+            let max = max.unwrap_or(u32::MAX).max(min);
+            if max > limits.max_table_size {
+                return Err(WasmValidationError::TableTooLarge(max));
+            }
+        }
+        Ok(())
+    }
+
+    fn scan_memories(&mut self) -> Result<(), WasmValidationError> {
+        for _ in 0..self.read_u32()? {
+            self.memories += 1;
+            self.skip_limits()?;
+        }
+        Ok(())
+    }
+
+    fn scan_globals(&mut self) -> Result<(), WasmValidationError> {
+        let count = self.read_u32()?;
+        if let Some(limits) = self.limits {
+            if count > limits.max_globals {
+                return Err(WasmValidationError::TooManyGlobals(count));
+            }
+        }
+        for _ in 0..count {
+            self.scan_value_type()?;
+            self.read_byte()?;
+            self.scan_expression()?;
+        }
+        Ok(())
+    }
+
+    fn scan_exports(&mut self) -> Result<(), WasmValidationError> {
+        for _ in 0..self.read_u32()? {
+            let name = self.read_name()?;
+            if self.limits.is_some() && !is_identifier(name) {
+                return Err(WasmValidationError::InvalidExportName(name.to_string()));
+            }
+            self.read_byte()?;
+            self.read_u32()?;
+        }
+        Ok(())
+    }
+
+    fn scan_elements(&mut self) -> Result<(), WasmValidationError> {
+        let mut elements = 0u32;
+        for _ in 0..self.read_u32()? {
+            // Bit 0 marks a passive or declarative segment, bit 1 an explicit table index or a
+            // declarative segment, and bit 2 elements given as expressions. Only flags 0, an
+            // active segment of function indices for table 0, are MVP.
+            let flags = self.read_u32()?;
+            if flags > 7 {
+                return Err(WasmValidationError::InvalidModule);
+            }
+            if flags != 0 {
+                self.require(WasmFeature::BulkMemory)?;
+            }
+            if flags & 0x03 == 0x03 {
+                self.require(WasmFeature::ReferenceTypes)?;
+            }
+            if flags & 0x01 == 0 {
+                if flags & 0x02 != 0 {
+                    self.scan_table_index()?;
+                }
+                self.scan_expression()?;
+            }
+            let expressions = flags & 0x04 != 0;
+            if flags & 0x03 != 0 {
+                if expressions {
+                    self.scan_reference_type()?;
+                } else if self.read_byte()? != 0x00 {
+                    return Err(WasmValidationError::InvalidModule);
+                }
+            }
+
+            let count = self.read_u32()?;
+            elements = elements.saturating_add(count);
+            if let Some(limits) = self.limits {
+                if elements > limits.max_table_size {
+                    return Err(WasmValidationError::TooManyElements(elements));
+                }
+            }
+            for _ in 0..count {
+                if expressions {
+                    self.scan_element_expression()?;
+                } else {
+                    self.read_u32()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Scans an element given as an expression, which is a `ref.func`, `ref.null` or
+    /// `global.get` followed by `end`.
+    fn scan_element_expression(&mut self) -> Result<(), WasmValidationError> {
+        match self.read_byte()? {
+            0xD0 => self.scan_reference_type()?,
+            0xD2 | 0x23 => {
+                self.read_u32()?;
+            }
+            _ => return Err(WasmValidationError::InvalidModule),
+        }
+        if self.read_byte()? != 0x0B {
+            return Err(WasmValidationError::InvalidModule);
+        }
+        Ok(())
+    }
+
+    fn scan_code(&mut self, section_end: usize) -> Result<(), WasmValidationError> {
+        for _ in 0..self.read_u32()? {
+            let size = self.read_u32()? as usize;
+            if let Some(limits) = self.limits {
+                if size > limits.max_function_size {
+                    return Err(WasmValidationError::FunctionTooLarge(size));
+                }
+            }
+            let end = self
+                .offset
+                .checked_add(size)
+                .filter(|end| *end <= section_end)
+                .ok_or(WasmValidationError::InvalidModule)?;
+            let local_declarations = self.read_u32()?;
+            let locals_start = self.offset;
+            let mut locals = 0u64;
+            for _ in 0..local_declarations {
+                locals += self.read_u32()? as u64;
+                self.scan_value_type()?;
+            }
+            if let Some(bodies) = &mut self.function_bodies {
+                bodies.push(FunctionBody {
+                    locals_start,
+                    local_declarations,
+                    locals,
+                    instructions_start: self.offset,
+                    end,
+                    nan_results: Vec::new(),
+                });
+            }
+            while self.offset < end {
+                self.scan_instruction()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn scan_data(&mut self) -> Result<(), WasmValidationError> {
+        for _ in 0..self.read_u32()? {
+            match self.read_u32()? {
+                0 => self.scan_expression()?,
+                1 => self.require(WasmFeature::BulkMemory)?,
+                2 => {
+                    self.require(WasmFeature::BulkMemory)?;
+                    self.scan_memory_index()?;
+                    self.scan_expression()?;
+                }
+                _ => return Err(WasmValidationError::InvalidModule),
+            }
+            let size = self.read_u32()? as usize;
+            self.read_bytes(size)?;
+        }
+        Ok(())
+    }
+
+    /// Scans a constant expression, up to and including its `end`.
+    fn scan_expression(&mut self) -> Result<(), WasmValidationError> {
+        loop {
+            if self.read_byte()? == 0x0B {
+                return Ok(());
+            }
+            self.offset -= 1;
+            self.scan_instruction()?;
+        }
+    }
+
+    fn scan_instruction(&mut self) -> Result<(), WasmValidationError> {
+        match self.read_byte()? {
+            // Control
+            0x00 | 0x01 | 0x05 | 0x0B | 0x0F => {}
+            0x02..=0x04 => self.scan_block_type()?,
+            0x0C | 0x0D | 0x10 => {
+                self.read_u32()?;
+            }
+            0x0E => {
+                for _ in 0..self.read_u32()? {
+                    self.read_u32()?;
+                }
+                self.read_u32()?;
+            }
+            0x11 => {
+                self.read_u32()?;
+                self.scan_table_index()?;
+            }
+            // Parametric
+            0x1A | 0x1B => {}
+            0x1C => {
+                self.require(WasmFeature::ReferenceTypes)?;
+                for _ in 0..self.read_u32()? {
+                    self.scan_value_type()?;
+                }
+            }
+            // Variable
+            0x20..=0x24 => {
+                self.read_u32()?;
+            }
+            // Table
+            0x25 | 0x26 => {
+                self.require(WasmFeature::ReferenceTypes)?;
+                self.read_u32()?;
+            }
+            // Memory
+            0x2A | 0x2B | 0x38 | 0x39 => {
+                self.allow_floating_point()?;
+                self.scan_memory_argument()?;
+            }
+            0x28..=0x3E => self.scan_memory_argument()?,
+            0x3F | 0x40 => self.scan_memory_index()?,
+            // Numeric
+            0x41 => self.skip_leb128(5)?,
+            0x42 => self.skip_leb128(10)?,
+            // Float constants
+            0x43 => {
+                self.allow_floating_point()?;
+                self.read_bytes(4)?;
+            }
+            0x44 => {
+                self.allow_floating_point()?;
+                self.read_bytes(8)?;
+            }
+            // Float arithmetic and conversions which may produce a NaN
+            0x8D..=0x97 | 0xB6 => {
+                self.allow_floating_point()?;
+                self.record_nan_result(FloatType::F32);
+            }
+            0x9B..=0xA5 | 0xBB => {
+                self.allow_floating_point()?;
+                self.record_nan_result(FloatType::F64);
+            }
+            // Other float comparisons, arithmetic and conversions
+            0x5B..=0x66 | 0x8B..=0xA6 | 0xA8..=0xAB | 0xAE..=0xBF => self.allow_floating_point()?,
+            0x45..=0xBF => {}
+            0xC0..=0xC4 => self.require(WasmFeature::SignExtension)?,
+            // Reference
+            0xD0 => {
+                self.require(WasmFeature::ReferenceTypes)?;
+                self.read_byte()?;
+            }
+            0xD1 => self.require(WasmFeature::ReferenceTypes)?,
+            0xD2 => {
+                self.require(WasmFeature::ReferenceTypes)?;
+                self.read_u32()?;
+            }
+            0xFC => self.scan_misc_instruction()?,
+            0xFD => self.scan_simd_instruction()?,
+            _ => return Err(WasmValidationError::InvalidModule),
+        }
+        Ok(())
+    }
+
+    fn scan_misc_instruction(&mut self) -> Result<(), WasmValidationError> {
+        match self.read_u32()? {
+            // Non-trapping float-to-int conversions
+            0..=7 => self.allow_floating_point()?,
+            // memory.init
+            8 => {
+                self.require(WasmFeature::BulkMemory)?;
+                self.read_u32()?;
+                self.scan_memory_index()?;
+            }
+            // data.drop
+            9 => {
+                self.require(WasmFeature::BulkMemory)?;
+                self.read_u32()?;
+            }
+            // memory.copy
+            10 => {
+                self.require(WasmFeature::BulkMemory)?;
+                self.scan_memory_index()?;
+                self.scan_memory_index()?;
+            }
+            // memory.fill
+            11 => {
+                self.require(WasmFeature::BulkMemory)?;
+                self.scan_memory_index()?;
+            }
+            // table.init
+            12 => {
+                self.require(WasmFeature::BulkMemory)?;
+                self.read_u32()?;
+                self.scan_table_index()?;
+            }
+            // elem.drop
+            13 => {
+                self.require(WasmFeature::BulkMemory)?;
+                self.read_u32()?;
+            }
+            // table.copy
+            14 => {
+                self.require(WasmFeature::BulkMemory)?;
+                self.scan_table_index()?;
+                self.scan_table_index()?;
+            }
+            // table.grow, table.size, table.fill
+            15..=17 => {
+                self.require(WasmFeature::ReferenceTypes)?;
+                self.read_u32()?;
+            }
+            _ => return Err(WasmValidationError::InvalidModule),
+        }
+        Ok(())
+    }
+
+    fn scan_simd_instruction(&mut self) -> Result<(), WasmValidationError> {
+        self.require(WasmFeature::Simd)?;
+        match self.read_u32()? {
+            // v128 loads and stores
+            0..=11 | 92 | 93 => self.scan_memory_argument()?,
+            // v128.const, i8x16.shuffle
+            12 | 13 => {
+                self.read_bytes(16)?;
+            }
+            // Lane accesses
+            21..=34 => {
+                self.read_byte()?;
+            }
+            // Lane loads and stores
+            84..=91 => {
+                self.scan_memory_argument()?;
+                self.read_byte()?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn scan_block_type(&mut self) -> Result<(), WasmValidationError> {
+        match self.code.get(self.offset) {
+            Some(0x40) => {
+                self.offset += 1;
+                Ok(())
+            }
+            Some(0x7F) | Some(0x7E) | Some(0x7D) | Some(0x7C) | Some(0x7B) | Some(0x70)
+            | Some(0x6F) => self.scan_value_type(),
+            // Type indices are left to the interpreter to validate
+            Some(_) => self.skip_leb128(5),
+            None => Err(WasmValidationError::InvalidModule),
+        }
+    }
+
+    fn scan_value_type(&mut self) -> Result<(), WasmValidationError> {
+        match self.read_byte()? {
+            0x7F | 0x7E => Ok(()),
+            0x7D | 0x7C => self.allow_floating_point(),
+            0x7B => self.require(WasmFeature::Simd),
+            0x70 | 0x6F => self.require(WasmFeature::ReferenceTypes),
+            _ => Err(WasmValidationError::InvalidModule),
+        }
+    }
+
+    fn scan_reference_type(&mut self) -> Result<(), WasmValidationError> {
+        match self.read_byte()? {
+            0x70 => Ok(()),
+            0x6F => self.require(WasmFeature::ReferenceTypes),
+            _ => Err(WasmValidationError::InvalidModule),
+        }
+    }
+
+    fn scan_table_index(&mut self) -> Result<(), WasmValidationError> {
+        if self.read_u32()? != 0 {
+            self.require(WasmFeature::ReferenceTypes)?;
+        }
+        Ok(())
+    }
+
+    fn scan_memory_argument(&mut self) -> Result<(), WasmValidationError> {
+        let align = self.read_u32()?;
+        // Bit 6 of the alignment flags an explicit memory index
+        if align & 0x40 != 0 {
+            self.scan_memory_index()?;
+        }
+        self.read_u32()?;
+        Ok(())
+    }
+
+    fn scan_memory_index(&mut self) -> Result<(), WasmValidationError> {
+        if self.read_u32()? != 0 {
+            self.require(WasmFeature::MultiMemory)?;
+        }
+        Ok(())
+    }
+
+    fn skip_limits(&mut self) -> Result<(), WasmValidationError> {
+        match self.read_byte()? {
+            0x00 => {
+                self.read_u32()?;
+            }
+            0x01 => {
+                self.read_u32()?;
+                self.read_u32()?;
+            }
+            _ => return Err(WasmValidationError::InvalidModule),
+        }
+        Ok(())
+    }
+
+    fn read_limits(&mut self) -> Result<(u32, Option<u32>), WasmValidationError> {
+        match self.read_byte()? {
+            0x00 => Ok((self.read_u32()?, None)),
+            0x01 => Ok((self.read_u32()?, Some(self.read_u32()?))),
+            _ => Err(WasmValidationError::InvalidModule),
+        }
+    }
+
+    fn read_name(&mut self) -> Result<&'a str, WasmValidationError> {
+        let len = self.read_u32()? as usize;
+        let bytes = self.read_bytes(len)?;
+        str::from_utf8(bytes).map_err(|_| WasmValidationError::InvalidModule)
+    }
+
+    fn require(&self, feature: WasmFeature) -> Result<(), WasmValidationError> {
+        if self.features.is_enabled(feature) {
+            Ok(())
+        } else {
+            Err(WasmValidationError::WasmFeatureNotAllowed(feature))
+        }
+    }
+
+    fn allow_floating_point(&self) -> Result<(), WasmValidationError> {
+        if self.features.nan_canonicalization {
+            Ok(())
+        } else {
+            Err(WasmValidationError::FloatingPointNotAllowed)
+        }
+    }
+
+    /// Records that the result of the instruction just read must be canonicalized, if it's in
+    /// a function body.
+    fn record_nan_result(&mut self, float_type: FloatType) {
+        let offset = self.offset;
+        if let Some(body) = self
+            .function_bodies
+            .as_mut()
+            .and_then(|bodies| bodies.last_mut())
+            .filter(|body| offset <= body.end)
+        {
+            body.nan_results.push((offset, float_type));
+        }
+    }
+
+    fn read_byte(&mut self) -> Result<u8, WasmValidationError> {
+        let byte = *self
+            .code
+            .get(self.offset)
+            .ok_or(WasmValidationError::InvalidModule)?;
+        self.offset += 1;
+        Ok(byte)
+    }
+
+    fn read_bytes(&mut self, n: usize) -> Result<&'a [u8], WasmValidationError> {
+        let code = self.code;
+        let bytes = self
+            .offset
+            .checked_add(n)
+            .and_then(|end| code.get(self.offset..end))
+            .ok_or(WasmValidationError::InvalidModule)?;
+        self.offset += n;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, WasmValidationError> {
+        let mut result = 0u32;
+        for i in 0..5 {
+            let byte = self.read_byte()?;
+            result |= ((byte & 0x7F) as u32) << (i * 7);
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+        Err(WasmValidationError::InvalidModule)
+    }
+
+    fn skip_leb128(&mut self, max_bytes: usize) -> Result<(), WasmValidationError> {
+        for _ in 0..max_bytes {
+            if self.read_byte()? & 0x80 == 0 {
+                return Ok(());
+            }
+        }
+        Err(WasmValidationError::InvalidModule)
+    }
+}
+
+/// Returns whether a name is an ASCII identifier, such as `package_init` or `Account_main`.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scrypto::rust::vec;
+    use scrypto::rust::vec::Vec;
+    use wasmi::{ImportsBuilder, Module, ModuleInstance, NopExternals, RuntimeValue};
+
+    /// Builds a module with a memory and a single `() -> ()` function with the given body.
+    fn module_with_body(instructions: &[u8]) -> Vec<u8> {
+        build_module(None, instructions)
+    }
+
+    /// Builds a module with a table of one function, the given element section contents, and a
+    /// single `() -> ()` function with the given body.
+    fn module_with_elements(elements: &[u8], instructions: &[u8]) -> Vec<u8> {
+        build_module(Some(elements), instructions)
+    }
+
+    fn build_module(elements: Option<&[u8]>, instructions: &[u8]) -> Vec<u8> {
+        let mut body = vec![0x00];
+        body.extend_from_slice(instructions);
+        body.push(0x0B);
+
+        let mut code = b"\0asm\x01\0\0\0".to_vec();
+        code.extend([0x01, 0x04, 0x01, 0x60, 0x00, 0x00]);
+        code.extend([0x03, 0x02, 0x01, 0x00]);
+        if elements.is_some() {
+            code.extend([0x04, 0x04, 0x01, 0x70, 0x00, 0x01]);
+        }
+        code.extend([0x05, 0x03, 0x01, 0x00, 0x01]);
+        if let Some(elements) = elements {
+            code.extend([0x09, elements.len() as u8]);
+            code.extend_from_slice(elements);
+        }
+        code.extend([0x0A, body.len() as u8 + 2, 0x01, body.len() as u8]);
+        code.extend(body);
+        code
+    }
+
+    #[test]
+    fn should_accept_mvp_instructions() {
+        // i32.const 0, i32.load offset=4, i32.const 1, i32.add, drop
+        let code = module_with_body(&[0x41, 0x00, 0x28, 0x02, 0x04, 0x41, 0x01, 0x6A, 0x1A]);
+        assert_eq!(WasmFeatures::MVP.check(&code), Ok(()));
+    }
+
+    #[test]
+    fn should_reject_sign_extension_unless_enabled() {
+        // i32.const 0, i32.extend8_s, drop
+        let code = module_with_body(&[0x41, 0x00, 0xC0, 0x1A]);
+        assert_eq!(
+            WasmFeatures::MVP.check(&code),
+            Err(WasmValidationError::WasmFeatureNotAllowed(
+                WasmFeature::SignExtension
+            ))
+        );
+        let features = WasmFeatures {
+            sign_extension: true,
+            ..WasmFeatures::MVP
+        };
+        assert_eq!(features.check(&code), Ok(()));
+    }
+
+    #[test]
+    fn should_reject_bulk_memory() {
+        // i32.const 0, i32.const 0, i32.const 0, memory.fill
+        let code = module_with_body(&[0x41, 0x00, 0x41, 0x00, 0x41, 0x00, 0xFC, 0x0B, 0x00]);
+        assert_eq!(
+            WasmFeatures::MVP.check(&code),
+            Err(WasmValidationError::WasmFeatureNotAllowed(
+                WasmFeature::BulkMemory
+            ))
+        );
+    }
+
+    #[test]
+    fn should_accept_mvp_element_segment() {
+        // (elem (i32.const 0) func 0)
+        let code = module_with_elements(&[0x01, 0x00, 0x41, 0x00, 0x0B, 0x01, 0x00], &[]);
+        assert_eq!(WasmFeatures::MVP.check(&code), Ok(()));
+    }
+
+    #[test]
+    fn should_scan_passive_element_segment_and_table_instructions() {
+        let bulk_memory = WasmFeatures {
+            bulk_memory: true,
+            ..WasmFeatures::MVP
+        };
+        // (elem func 0)
+        let elements = [0x01, 0x01, 0x00, 0x01, 0x00];
+        // i32.const 0, i32.const 0, i32.const 1, table.init 67 0, elem.drop 67,
+        // i32.const 0, i32.const 0, i32.const 1, table.copy 0 0
+        let instructions = [
+            0x41, 0x00, 0x41, 0x00, 0x41, 0x01, 0xFC, 0x0C, 0x43, 0x00, 0xFC, 0x0D, 0x43, 0x41,
+            0x00, 0x41, 0x00, 0x41, 0x01, 0xFC, 0x0E, 0x00, 0x00,
+        ];
+        let code = module_with_elements(&elements, &instructions);
+        assert_eq!(bulk_memory.check(&code), Ok(()));
+        assert_eq!(
+            WasmFeatures::MVP.check(&code),
+            Err(WasmValidationError::WasmFeatureNotAllowed(
+                WasmFeature::BulkMemory
+            ))
+        );
+
+        // A segment type other than the function reference element kind is invalid
+        let code = module_with_elements(&[0x01, 0x01, 0x01, 0x01, 0x00], &[]);
+        assert_eq!(
+            bulk_memory.check(&code),
+            Err(WasmValidationError::InvalidModule)
+        );
+    }
+
+    #[test]
+    fn should_scan_element_expressions() {
+        let bulk_memory = WasmFeatures {
+            bulk_memory: true,
+            ..WasmFeatures::MVP
+        };
+        // (elem (i32.const 0) funcref (ref.func 0))
+        let code =
+            module_with_elements(&[0x01, 0x04, 0x41, 0x00, 0x0B, 0x01, 0xD2, 0x00, 0x0B], &[]);
+        assert_eq!(bulk_memory.check(&code), Ok(()));
+
+        // An element expression which isn't a reference is invalid
+        let code =
+            module_with_elements(&[0x01, 0x04, 0x41, 0x00, 0x0B, 0x01, 0x41, 0x00, 0x0B], &[]);
+        assert_eq!(
+            bulk_memory.check(&code),
+            Err(WasmValidationError::InvalidModule)
+        );
+    }
+
+    #[test]
+    fn should_require_reference_types_for_other_tables_and_declarative_segments() {
+        let bulk_memory = WasmFeatures {
+            bulk_memory: true,
+            ..WasmFeatures::MVP
+        };
+        // (elem (table 1) (i32.const 0) func 0)
+        let code =
+            module_with_elements(&[0x01, 0x02, 0x01, 0x41, 0x00, 0x0B, 0x00, 0x01, 0x00], &[]);
+        assert_eq!(
+            bulk_memory.check(&code),
+            Err(WasmValidationError::WasmFeatureNotAllowed(
+                WasmFeature::ReferenceTypes
+            ))
+        );
+
+        // (elem declare func 0)
+        let code = module_with_elements(&[0x01, 0x03, 0x00, 0x01, 0x00], &[]);
+        assert_eq!(
+            bulk_memory.check(&code),
+            Err(WasmValidationError::WasmFeatureNotAllowed(
+                WasmFeature::ReferenceTypes
+            ))
+        );
+
+        // i32.const 0, i32.const 0, i32.const 1, table.copy 1 0
+        let code = module_with_elements(
+            &[0x00],
+            &[0x41, 0x00, 0x41, 0x00, 0x41, 0x01, 0xFC, 0x0E, 0x01, 0x00],
+        );
+        assert_eq!(
+            bulk_memory.check(&code),
+            Err(WasmValidationError::WasmFeatureNotAllowed(
+                WasmFeature::ReferenceTypes
+            ))
+        );
+    }
+
+    #[test]
+    fn should_reject_unknown_element_segment_flags() {
+        let all = WasmFeatures {
+            bulk_memory: true,
+            sign_extension: true,
+            simd: true,
+            reference_types: true,
+            multi_memory: true,
+            nan_canonicalization: true,
+        };
+        let code = module_with_elements(&[0x01, 0x08, 0x00], &[]);
+        assert_eq!(all.check(&code), Err(WasmValidationError::InvalidModule));
+    }
+
+    #[test]
+    fn should_reject_simd() {
+        // v128.const 0, drop
+        let mut instructions = vec![0xFD, 0x0C];
+        instructions.extend([0u8; 16]);
+        instructions.push(0x1A);
+        let code = module_with_body(&instructions);
+        assert_eq!(
+            WasmFeatures::MVP.check(&code),
+            Err(WasmValidationError::WasmFeatureNotAllowed(
+                WasmFeature::Simd
+            ))
+        );
+    }
+
+    #[test]
+    fn should_reject_reference_types() {
+        // ref.null func, drop
+        let code = module_with_body(&[0xD0, 0x70, 0x1A]);
+        assert_eq!(
+            WasmFeatures::MVP.check(&code),
+            Err(WasmValidationError::WasmFeatureNotAllowed(
+                WasmFeature::ReferenceTypes
+            ))
+        );
+    }
+
+    #[test]
+    fn should_reject_multi_memory() {
+        // memory.size 1, drop
+        let code = module_with_body(&[0x3F, 0x01, 0x1A]);
+        assert_eq!(
+            WasmFeatures::MVP.check(&code),
+            Err(WasmValidationError::WasmFeatureNotAllowed(
+                WasmFeature::MultiMemory
+            ))
+        );
+    }
+
+    #[test]
+    fn should_reject_float_instructions() {
+        for instructions in [
+            // f32.const 0, drop
+            vec![0x43, 0x00, 0x00, 0x00, 0x00, 0x1A],
+            // i32.const 0, f64.load, drop
+            vec![0x41, 0x00, 0x2B, 0x03, 0x00, 0x1A],
+            // i32.const 0, f32.reinterpret_i32, drop
+            vec![0x41, 0x00, 0xBE, 0x1A],
+            // block (result f64), unreachable, end, drop
+            vec![0x02, 0x7C, 0x00, 0x0B, 0x1A],
+        ] {
+            let code = module_with_body(&instructions);
+            assert_eq!(
+                WasmFeatures::MVP.check(&code),
+                Err(WasmValidationError::FloatingPointNotAllowed)
+            );
+        }
+    }
+
+    #[test]
+    fn should_reject_float_types() {
+        // (type (func (param f64)))
+        let mut code = b"\0asm\x01\0\0\0".to_vec();
+        code.extend([0x01, 0x05, 0x01, 0x60, 0x01, 0x7C, 0x00]);
+        assert_eq!(
+            WasmFeatures::MVP.check(&code),
+            Err(WasmValidationError::FloatingPointNotAllowed)
+        );
+
+        // (global f32 (f32.const 0))
+        let mut code = b"\0asm\x01\0\0\0".to_vec();
+        code.extend([
+            0x06, 0x09, 0x01, 0x7D, 0x00, 0x43, 0x00, 0x00, 0x00, 0x00, 0x0B,
+        ]);
+        assert_eq!(
+            WasmFeatures::MVP.check(&code),
+            Err(WasmValidationError::FloatingPointNotAllowed)
+        );
+    }
+
+    #[test]
+    fn should_accept_integer_conversions() {
+        // i32.const 0, i64.extend_i32_s, i32.wrap_i64, drop
+        let code = module_with_body(&[0x41, 0x00, 0xAC, 0xA7, 0x1A]);
+        assert_eq!(WasmFeatures::MVP.check(&code), Ok(()));
+    }
+
+    #[test]
+    fn should_accept_floating_point_with_nan_canonicalization() {
+        let features = WasmFeatures {
+            nan_canonicalization: true,
+            ..WasmFeatures::MVP
+        };
+        // f32.const 0, f32.neg, drop
+        let code = module_with_body(&[0x43, 0x00, 0x00, 0x00, 0x00, 0x8C, 0x1A]);
+        assert_eq!(features.check(&code), Ok(()));
+        assert_eq!(features.canonicalize_nans(&code), Ok(code));
+    }
+
+    /// Calls an export of a module, after NaN canonicalization.
+    fn call_canonicalized(code: &[u8], export: &str, args: &[RuntimeValue]) -> RuntimeValue {
+        let features = WasmFeatures {
+            nan_canonicalization: true,
+            ..WasmFeatures::MVP
+        };
+        let code = features.canonicalize_nans(code).unwrap();
+        let module = Module::from_buffer(code).unwrap();
+        ModuleInstance::new(&module, &ImportsBuilder::default())
+            .unwrap()
+            .assert_no_start()
+            .invoke_export(export, args, &mut NopExternals)
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn canonicalized_nans_should_not_depend_on_operands() {
+        let code = wabt::wat2wasm(
+            r#"
+            (module
+                (func (export "f32_add") (param i32 i32) (result i32)
+                    (local i64)
+                    (i32.reinterpret_f32
+                        (f32.add
+                            (f32.reinterpret_i32 (local.get 0))
+                            (f32.reinterpret_i32 (local.get 1)))))
+                (func (export "f64_div") (param i64 i64) (result i64)
+                    (i64.reinterpret_f64
+                        (f64.div
+                            (f64.reinterpret_i64 (local.get 0))
+                            (f64.reinterpret_i64 (local.get 1)))))
+                (func (export "f64_promote") (param i32) (result i64)
+                    (i64.reinterpret_f64 (f64.promote_f32 (f32.reinterpret_i32 (local.get 0))))))
+            "#,
+        )
+        .unwrap();
+        let f32_bits = |value: f32| RuntimeValue::I32(value.to_bits() as i32);
+        let f64_bits = |value: f64| RuntimeValue::I64(value.to_bits() as i64);
+        let canonical_f32 = RuntimeValue::I32(CANONICAL_NAN_F32 as i32);
+        let canonical_f64 = RuntimeValue::I64(CANONICAL_NAN_F64 as i64);
+
+        // NaN payloads and signs, which platforms propagate differently
+        for args in [
+            [RuntimeValue::I32(0x7FA0_0001), f32_bits(1.0)],
+            [f32_bits(1.0), RuntimeValue::I32(0xFFC0_0002u32 as i32)],
+            [f32_bits(f32::INFINITY), f32_bits(f32::NEG_INFINITY)],
+        ] {
+            assert_eq!(call_canonicalized(&code, "f32_add", &args), canonical_f32);
+        }
+        for args in [
+            [f64_bits(0.0), f64_bits(0.0)],
+            [
+                RuntimeValue::I64(0xFFF8_0000_0000_0003u64 as i64),
+                f64_bits(1.0),
+            ],
+        ] {
+            assert_eq!(call_canonicalized(&code, "f64_div", &args), canonical_f64);
+        }
+        assert_eq!(
+            call_canonicalized(
+                &code,
+                "f64_promote",
+                &[RuntimeValue::I32(0xFFC0_0001u32 as i32)]
+            ),
+            canonical_f64
+        );
+
+        // Other results are left as they are
+        assert_eq!(
+            call_canonicalized(&code, "f32_add", &[f32_bits(1.5), f32_bits(2.0)]),
+            f32_bits(3.5)
+        );
+        assert_eq!(
+            call_canonicalized(&code, "f64_div", &[f64_bits(3.0), f64_bits(2.0)]),
+            f64_bits(1.5)
+        );
+    }
+
+    #[test]
+    fn should_reject_truncated_module() {
+        let code = module_with_body(&[0x41, 0x00, 0x1A]);
+        assert_eq!(
+            WasmFeatures::MVP.check(&code[..code.len() - 2]),
+            Err(WasmValidationError::InvalidModule)
+        );
+    }
+}
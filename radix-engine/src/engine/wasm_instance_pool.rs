@@ -0,0 +1,494 @@
+use lru::LruCache;
+use scrypto::crypto::Hash;
+use scrypto::rust::format;
+use scrypto::rust::string::{String, ToString};
+use scrypto::rust::vec::Vec;
+use wasmi::{ExternVal, GlobalRef, MemoryRef, ModuleRef, RuntimeValue};
+
+use crate::errors::WasmValidationError;
+
+/// The default number of distinct code hashes kept by a `WasmInstancePool`.
+pub const DEFAULT_WASM_INSTANCE_POOL_CAPACITY: usize = 64;
+
+/// The maximum number of idle instances kept for a single code hash.
+pub const MAX_IDLE_WASM_INSTANCES: usize = 4;
+
+/// Statistics of a `WasmInstancePool` over its lifetime.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WasmInstancePoolStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub discards: u64,
+}
+
+/// The prefix of the names under which the mutable globals of a module are exported.
+///
+/// Names with this prefix aren't identifiers, so the engine never invokes them.
+pub const GLOBAL_EXPORT_PREFIX: &str = "$global";
+
+/// An instantiated WASM module, together with its exported memory and mutable globals.
+pub struct WasmInstance {
+    code_hash: Hash,
+    module: ModuleRef,
+    memory: MemoryRef,
+    globals: Vec<GlobalRef>,
+}
+
+impl WasmInstance {
+    pub fn new(code_hash: Hash, module: ModuleRef, memory: MemoryRef) -> Self {
+        let mut globals: Vec<(String, GlobalRef)> = module
+            .exports()
+            .iter()
+            .filter_map(|(name, val)| match val {
+                ExternVal::Global(global) if name.starts_with(GLOBAL_EXPORT_PREFIX) => {
+                    Some((name.to_string(), global.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        globals.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Self {
+            code_hash,
+            module,
+            memory,
+            globals: globals.into_iter().map(|(_, global)| global).collect(),
+        }
+    }
+
+    pub fn code_hash(&self) -> &Hash {
+        &self.code_hash
+    }
+
+    pub fn module(&self) -> &ModuleRef {
+        &self.module
+    }
+
+    pub fn memory(&self) -> &MemoryRef {
+        &self.memory
+    }
+}
+
+struct PooledCode {
+    /// The size of the memory of a freshly instantiated module.
+    memory_size: usize,
+    /// The memory of a freshly instantiated module, up to its last non-zero byte. The rest of
+    /// the memory is zeroed rather than copied when an instance is reset.
+    initial_memory: Vec<u8>,
+    /// The values of the exported mutable globals of a freshly instantiated module.
+    initial_globals: Vec<RuntimeValue>,
+    idle: Vec<WasmInstance>,
+}
+
+/// A least-recently-used pool of WASM instances, shared by transactions.
+///
+/// Instances are keyed by the hash of their code. An instance is returned to the pool only
+/// after a successful invocation, at which point its memory and mutable globals are reset to the
+/// initial snapshot. Instances which trapped, or whose memory grew, are discarded.
+///
+/// Only the globals exported by `export_mutable_globals` can be reset, so pooled instances must
+/// be instantiated from code rewritten by it.
+pub struct WasmInstancePool {
+    codes: LruCache<Hash, PooledCode>,
+    stats: WasmInstancePoolStats,
+}
+
+impl WasmInstancePool {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            codes: LruCache::new(capacity),
+            stats: WasmInstancePoolStats::default(),
+        }
+    }
+
+    /// Takes an idle instance of the given code, or creates one with `instantiate`.
+    pub fn acquire<F>(&mut self, code_hash: Hash, instantiate: F) -> WasmInstance
+    where
+        F: FnOnce() -> (ModuleRef, MemoryRef),
+    {
+        if let Some(instance) = self.codes.get_mut(&code_hash).and_then(|c| c.idle.pop()) {
+            self.stats.hits += 1;
+            return instance;
+        }
+
+        self.stats.misses += 1;
+        let (module, memory) = instantiate();
+        let instance = WasmInstance::new(code_hash, module, memory);
+        if !self.codes.contains(&code_hash) {
+            let memory = &instance.memory;
+            let direct = memory.direct_access();
+            let buffer = direct.as_ref();
+            let data_end = buffer.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+            self.codes.put(
+                code_hash,
+                PooledCode {
+                    memory_size: buffer.len(),
+                    initial_memory: buffer[..data_end].to_vec(),
+                    initial_globals: instance.globals.iter().map(|g| g.get()).collect(),
+                    idle: Vec::new(),
+                },
+            );
+        }
+        instance
+    }
+
+    /// Resets an instance after a successful invocation and keeps it for reuse.
+    pub fn release(&mut self, instance: WasmInstance) {
+        let code = match self.codes.get_mut(&instance.code_hash) {
+            Some(code) if code.idle.len() < MAX_IDLE_WASM_INSTANCES => code,
+            _ => {
+                self.stats.discards += 1;
+                return;
+            }
+        };
+
+        {
+            let mut direct = instance.memory.direct_access_mut();
+            let buffer = direct.as_mut();
+            if buffer.len() != code.memory_size {
+                self.stats.discards += 1;
+                return;
+            }
+            let (data, rest) = buffer.split_at_mut(code.initial_memory.len());
+            data.copy_from_slice(&code.initial_memory);
+            rest.fill(0);
+        }
+        if instance.globals.len() != code.initial_globals.len()
+            || instance
+                .globals
+                .iter()
+                .zip(&code.initial_globals)
+                .any(|(global, value)| global.set(*value).is_err())
+        {
+            self.stats.discards += 1;
+            return;
+        }
+        code.idle.push(instance);
+    }
+
+    /// Removes all instances.
+    pub fn clear(&mut self) {
+        self.codes.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    pub fn stats(&self) -> WasmInstancePoolStats {
+        self.stats
+    }
+}
+
+impl Default for WasmInstancePool {
+    fn default() -> Self {
+        Self::new(DEFAULT_WASM_INSTANCE_POOL_CAPACITY)
+    }
+}
+
+/// Rewrites a wasm module so that each of its mutable globals is exported as
+/// `GLOBAL_EXPORT_PREFIX` followed by its index, which lets the pool reset the globals (such as
+/// the stack pointer) which the module doesn't export itself.
+///
+/// Exports of the module which already use the prefix are dropped.
+pub fn export_mutable_globals(code: &[u8]) -> Result<Vec<u8>, WasmValidationError> {
+    let mut reader = WasmReader { code, offset: 0 };
+    if reader.read_bytes(8)? != b"\0asm\x01\0\0\0" {
+        return Err(WasmValidationError::InvalidModule);
+    }
+
+    // Find the mutable globals, and the sections as (id, payload)
+    let mut sections = Vec::new();
+    let mut imported_globals = 0u32;
+    let mut mutable_globals = Vec::new();
+    while reader.offset < code.len() {
+        let id = reader.read_byte()?;
+        let size = reader.read_u32()? as usize;
+        let payload = reader.read_bytes(size)?;
+        let mut section = WasmReader {
+            code: payload,
+            offset: 0,
+        };
+        match id {
+            2 => {
+                for _ in 0..section.read_u32()? {
+                    section.read_name()?;
+                    section.read_name()?;
+                    match section.read_byte()? {
+                        0 => {
+                            section.read_u32()?;
+                        }
+                        1 => {
+                            section.read_byte()?;
+                            section.skip_limits()?;
+                        }
+                        2 => section.skip_limits()?,
+                        3 => {
+                            section.read_byte()?;
+                            section.read_byte()?;
+                            imported_globals += 1;
+                        }
+                        _ => return Err(WasmValidationError::InvalidModule),
+                    }
+                }
+            }
+            6 => {
+                for index in 0..section.read_u32()? {
+                    section.read_byte()?;
+                    if section.read_byte()? == 1 {
+                        mutable_globals.push(imported_globals + index);
+                    }
+                    section.skip_constant_expression()?;
+                }
+            }
+            _ => {}
+        }
+        sections.push((id, payload));
+    }
+
+    if mutable_globals.is_empty() {
+        return Ok(code.to_vec());
+    }
+
+    let mut exports = Vec::new();
+    let mut count = 0u32;
+    if let Some((_, payload)) = sections.iter().find(|(id, _)| *id == 7) {
+        let mut section = WasmReader {
+            code: payload,
+            offset: 0,
+        };
+        for _ in 0..section.read_u32()? {
+            let start = section.offset;
+            let name = section.read_name()?;
+            section.read_byte()?;
+            section.read_u32()?;
+            if !name.starts_with(GLOBAL_EXPORT_PREFIX.as_bytes()) {
+                exports.extend_from_slice(&payload[start..section.offset]);
+                count += 1;
+            }
+        }
+    }
+    for index in &mutable_globals {
+        let name = format!("{}{}", GLOBAL_EXPORT_PREFIX, index);
+        write_u32(&mut exports, name.len() as u32);
+        exports.extend_from_slice(name.as_bytes());
+        exports.push(3);
+        write_u32(&mut exports, *index);
+        count += 1;
+    }
+    let mut export_section = Vec::new();
+    write_u32(&mut export_section, count);
+    export_section.extend(exports);
+
+    // The export section goes after the global section and before the start section
+    let mut output = code[..8].to_vec();
+    let mut exported = false;
+    for (id, payload) in sections {
+        if !exported && (id == 7 || (8..=12).contains(&id)) {
+            write_section(&mut output, 7, &export_section);
+            exported = true;
+        }
+        if id != 7 {
+            write_section(&mut output, id, payload);
+        }
+    }
+    if !exported {
+        write_section(&mut output, 7, &export_section);
+    }
+    Ok(output)
+}
+
+fn write_section(output: &mut Vec<u8>, id: u8, payload: &[u8]) {
+    output.push(id);
+    write_u32(output, payload.len() as u32);
+    output.extend_from_slice(payload);
+}
+
+fn write_u32(output: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            output.push(byte);
+            return;
+        }
+        output.push(byte | 0x80);
+    }
+}
+
+struct WasmReader<'a> {
+    code: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> WasmReader<'a> {
+    fn skip_constant_expression(&mut self) -> Result<(), WasmValidationError> {
+        loop {
+            match self.read_byte()? {
+                0x0B => return Ok(()),
+                0x41 => self.skip_leb128(5)?,
+                0x42 => self.skip_leb128(10)?,
+                0x43 => {
+                    self.read_bytes(4)?;
+                }
+                0x44 => {
+                    self.read_bytes(8)?;
+                }
+                0x23 | 0xD2 => {
+                    self.read_u32()?;
+                }
+                0xD0 => {
+                    self.read_byte()?;
+                }
+                _ => return Err(WasmValidationError::InvalidModule),
+            }
+        }
+    }
+
+    fn skip_limits(&mut self) -> Result<(), WasmValidationError> {
+        let flags = self.read_byte()?;
+        self.read_u32()?;
+        if flags & 1 != 0 {
+            self.read_u32()?;
+        }
+        Ok(())
+    }
+
+    fn read_name(&mut self) -> Result<&'a [u8], WasmValidationError> {
+        let len = self.read_u32()? as usize;
+        self.read_bytes(len)
+    }
+
+    fn read_byte(&mut self) -> Result<u8, WasmValidationError> {
+        let byte = *self
+            .code
+            .get(self.offset)
+            .ok_or(WasmValidationError::InvalidModule)?;
+        self.offset += 1;
+        Ok(byte)
+    }
+
+    fn read_bytes(&mut self, n: usize) -> Result<&'a [u8], WasmValidationError> {
+        let code = self.code;
+        let bytes = self
+            .offset
+            .checked_add(n)
+            .and_then(|end| code.get(self.offset..end))
+            .ok_or(WasmValidationError::InvalidModule)?;
+        self.offset += n;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, WasmValidationError> {
+        let mut result = 0u32;
+        for i in 0..5 {
+            let byte = self.read_byte()?;
+            result |= ((byte & 0x7F) as u32) << (i * 7);
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+        Err(WasmValidationError::InvalidModule)
+    }
+
+    fn skip_leb128(&mut self, max_bytes: usize) -> Result<(), WasmValidationError> {
+        for _ in 0..max_bytes {
+            if self.read_byte()? & 0x80 == 0 {
+                return Ok(());
+            }
+        }
+        Err(WasmValidationError::InvalidModule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module(sections: &[(u8, &[u8])]) -> Vec<u8> {
+        let mut code = b"\0asm\x01\0\0\0".to_vec();
+        for (id, payload) in sections {
+            write_section(&mut code, *id, payload);
+        }
+        code
+    }
+
+    #[test]
+    fn should_export_mutable_globals() {
+        // An imported global, a mutable and an immutable global, and an export using the prefix
+        let code = module(&[
+            (2, &[1, 3, b'e', b'n', b'v', 1, b'g', 3, 0x7F, 0]),
+            (5, &[1, 0, 1]),
+            (
+                6,
+                &[
+                    2, 0x7F, 1, 0x41, 0x80, 0x80, 0x04, 0x0B, 0x7F, 0, 0x41, 5, 0x0B,
+                ],
+            ),
+            (
+                7,
+                &[
+                    2, 1, b'm', 2, 0, 8, b'$', b'g', b'l', b'o', b'b', b'a', b'l', b'9', 3, 2,
+                ],
+            ),
+        ]);
+
+        let expected = module(&[
+            (2, &[1, 3, b'e', b'n', b'v', 1, b'g', 3, 0x7F, 0]),
+            (5, &[1, 0, 1]),
+            (
+                6,
+                &[
+                    2, 0x7F, 1, 0x41, 0x80, 0x80, 0x04, 0x0B, 0x7F, 0, 0x41, 5, 0x0B,
+                ],
+            ),
+            (
+                7,
+                &[
+                    2, 1, b'm', 2, 0, 8, b'$', b'g', b'l', b'o', b'b', b'a', b'l', b'1', 3, 1,
+                ],
+            ),
+        ]);
+        assert_eq!(export_mutable_globals(&code), Ok(expected));
+    }
+
+    #[test]
+    fn should_add_export_section_before_data() {
+        let code = module(&[(6, &[1, 0x7F, 1, 0x41, 0, 0x0B]), (11, &[0])]);
+
+        let expected = module(&[
+            (6, &[1, 0x7F, 1, 0x41, 0, 0x0B]),
+            (
+                7,
+                &[1, 8, b'$', b'g', b'l', b'o', b'b', b'a', b'l', b'0', 3, 0],
+            ),
+            (11, &[0]),
+        ]);
+        assert_eq!(export_mutable_globals(&code), Ok(expected));
+    }
+
+    #[test]
+    fn should_keep_module_without_mutable_globals() {
+        let code = module(&[(6, &[1, 0x7F, 0, 0x41, 0, 0x0B])]);
+
+        assert_eq!(export_mutable_globals(&code), Ok(code.clone()));
+    }
+
+    #[test]
+    fn should_reject_truncated_module() {
+        let code = module(&[(6, &[1, 0x7F, 1, 0x41])]);
+
+        assert_eq!(
+            export_mutable_globals(&code),
+            Err(WasmValidationError::InvalidModule)
+        );
+        assert_eq!(
+            export_mutable_globals(&[0, 1]),
+            Err(WasmValidationError::InvalidModule)
+        );
+    }
+}
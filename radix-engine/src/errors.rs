@@ -7,6 +7,7 @@ use scrypto::values::*;
 use wasmi::*;
 
 use crate::engine::*;
+use crate::fee::*;
 use crate::model::*;
 
 /// Error coming from WASMI module which maps to wasmi:Error but is cloneable
@@ -64,6 +65,33 @@ pub enum WasmValidationError {
     NoPackageInitExport(WasmiError),
     /// package_init function is not the correct interface
     InvalidPackageInit,
+    /// The wasm module exceeds the maximum code size.
+    CodeTooLarge(usize),
+    /// The wasm module uses a feature which is not accepted by the feature policy.
+    WasmFeatureNotAllowed(WasmFeature),
+    /// The exported types are not structs or enums, or can't be decoded.
+    InvalidTypesExport,
+    /// The wasm module imports something other than the engine function.
+    ImportNotAllowed { module: String, name: String },
+    /// The wasm module exports a name which is not an identifier.
+    InvalidExportName(String),
+    /// The wasm module defines more functions than allowed.
+    TooManyFunctions(u32),
+    /// A function body of the wasm module exceeds the maximum size.
+    FunctionTooLarge(usize),
+    /// The wasm module defines more globals than allowed.
+    TooManyGlobals(u32),
+    /// A table of the wasm module can grow beyond the maximum size.
+    TableTooLarge(u32),
+    /// The element segments of the wasm module initialize more entries than allowed.
+    TooManyElements(u32),
+    /// A blueprint doesn't have a method of an interface it declares to implement, with the
+    /// same inputs and output.
+    InterfaceNotImplemented {
+        blueprint_name: String,
+        interface_name: String,
+        method_name: String,
+    },
 }
 
 /// Represents an error when validating a transaction.
@@ -74,6 +102,8 @@ pub enum TransactionValidationError {
     VaultNotAllowed(VaultId),
     LazyMapNotAllowed(LazyMapId),
     InvalidSignature,
+    EmptyCompositeTransaction,
+    InvalidNetwork { expected: u8, actual: u8 },
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -166,6 +196,9 @@ pub enum RuntimeError {
     /// Cyclic LazyMap added
     CyclicLazyMap(LazyMapId),
 
+    /// Lazy map can't be read by other components.
+    LazyMapNotReadable(LazyMapId),
+
     /// Vault does not exist.
     VaultNotFound(VaultId),
 
@@ -199,11 +232,9 @@ pub enum RuntimeError {
     /// Error when generating or accessing proof.
     ProofError(ProofError),
 
-    /// Bucket is not allowed.
-    BucketNotAllowed,
-
-    /// Proof is not allowed.
-    ProofNotAllowed,
+    /// A bucket or proof was found in data to be persisted, such as component state or lazy map
+    /// entries.
+    TransientValuePersisted(TransientValue),
 
     /// Vault is not allowed
     VaultNotAllowed,
@@ -211,6 +242,12 @@ pub enum RuntimeError {
     /// Lazy Map is not allowed
     LazyMapNotAllowed,
 
+    /// A node kept by the track, which can only be referred to by address, was moved.
+    GlobalNodeNotAllowed(RENodeId),
+
+    /// A substate was read which the substate store hasn't fetched yet.
+    SubstateNotFetched,
+
     /// Interpreter is not started.
     InterpreterNotStarted,
 
@@ -223,11 +260,22 @@ pub enum RuntimeError {
     /// AuthZone error
     AuthZoneError(AuthZoneError),
 
+    /// Account error
+    AccountError(AccountError),
+
+    /// System component error
+    SystemError(SystemError),
+
+    /// Component error
+    ComponentError(ComponentError),
+
     /// System Authorization Failure
     AuthorizationError {
         function: String,
         authorization: MethodAuthorization,
         error: MethodAuthorizationError,
+        /// The failed rule nodes and the proofs which were present.
+        explanation: AuthorizationExplanation,
     },
 
     /// Index out of bounds.
@@ -242,12 +290,271 @@ pub enum RuntimeError {
     /// Can't move restricted proof.
     CantMoveRestrictedProof(ProofId),
 
+    /// Buckets can't be carried over into the next partial transaction.
+    BucketsCrossPartialTransaction,
+
+    /// Error when consuming cost units.
+    CostingError(CostUnitCounterError),
+
+    /// A blueprint panicked, with source location if compiled in debug mode.
+    Panic {
+        message: String,
+        location: Option<String>,
+    },
+
+    /// Error raised by a blueprint, of the error type declared in its ABI.
+    BlueprintError {
+        package_address: PackageAddress,
+        blueprint_name: String,
+        error: ScryptoValue,
+    },
+
+    /// A read-only call attempted to change state.
+    StateChangedInReadOnlyCall,
+
+    /// The `migrate_state` function of a blueprint returned invalid state.
+    InvalidStateMigration,
+
+    /// The transaction emitted more log messages, or more log bytes, than allowed.
+    LogLimitExceeded {
+        max_logs: usize,
+        max_log_bytes: usize,
+    },
+
+    /// A value written by a blueprint, or the arguments of a call, exceeded the size limit.
+    SizeLimitExceeded {
+        kind: SizeLimitKind,
+        size: usize,
+        limit: usize,
+    },
+
+    /// The component has no rule slot with the given name.
+    AccessRuleSlotNotFound(String),
+
+    /// A rule referring to a rule slot was used outside the access rules of a component.
+    AccessRuleSlotNotAllowed,
+
+    /// The method has been disabled by the owner of the component.
+    MethodDisabled {
+        component_address: ComponentAddress,
+        method: String,
+    },
+
+    /// The blueprint doesn't declare the method.
+    MethodNotDeclared {
+        blueprint_name: String,
+        method: String,
+    },
+
+    /// The blueprint doesn't declare the feature flag.
+    FeatureNotDeclared {
+        blueprint_name: String,
+        feature: String,
+    },
+
+    /// The substate is already locked in a mode which conflicts with the requested one.
+    SubstateLocked {
+        substate_id: SubstateId,
+        requested: LockMode,
+        held: LockMode,
+    },
+
+    /// The emitter doesn't allow other components to observe the event.
+    EventNotSubscribable {
+        emitter: ComponentAddress,
+        event: String,
+    },
+
+    /// The component already has the maximum number of observers.
+    TooManyEventObservers(ComponentAddress),
+
+    /// Locker does not exist.
+    LockerNotFound(LockerId),
+
+    /// Locker access error.
+    LockerError(LockerError),
+
+    /// Allowance does not exist.
+    AllowanceNotFound(AllowanceId),
+
+    /// Allowance access error.
+    AllowanceError(AllowanceError),
+
+    /// An engine hook rejected the transaction.
+    HookError(String),
+
+    /// The amount of a resource held changed by more or less than its total supply.
+    ResourceConservationViolated {
+        resource_address: ResourceAddress,
+        supply_change: Decimal,
+        held_change: Decimal,
+    },
 }
 
 impl fmt::Display for RuntimeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            RuntimeError::AuthorizationError {
+                function,
+                error,
+                explanation,
+                ..
+            } => write!(
+                f,
+                "AuthorizationError({}, {:?})\n{}",
+                function, error, explanation
+            ),
+            _ => write!(f, "{:?}", self),
+        }
     }
 }
 
 impl HostError for RuntimeError {}
+
+impl From<ResourceManagerError> for RuntimeError {
+    fn from(e: ResourceManagerError) -> Self {
+        RuntimeError::ResourceManagerError(e)
+    }
+}
+
+impl RuntimeError {
+    /// Returns the numeric code of this error.
+    ///
+    /// Codes are stable across releases; new variants must be assigned a new code.
+    pub fn code(&self) -> u16 {
+        match self {
+            RuntimeError::AssertionFailed => 1,
+            RuntimeError::ParseScryptoValueError(..) => 2,
+            RuntimeError::AbiValidationError(..) => 3,
+            RuntimeError::AuthZoneDoesNotExist => 4,
+            RuntimeError::WorktopDoesNotExist => 5,
+            RuntimeError::IdAllocatorError(..) => 6,
+            RuntimeError::InvokeError => 7,
+            RuntimeError::MemoryAccessError => 8,
+            RuntimeError::MemoryAllocError => 9,
+            RuntimeError::NoReturnData => 10,
+            RuntimeError::InvalidReturnType => 11,
+            RuntimeError::InvalidRequestCode(..) => 12,
+            RuntimeError::InvalidRequestData(..) => 13,
+            RuntimeError::HostFunctionNotFound(..) => 14,
+            RuntimeError::PackageNotFound(..) => 15,
+            RuntimeError::PackageError(..) => 16,
+            RuntimeError::BlueprintNotFound(..) => 17,
+            RuntimeError::IllegalSystemCall => 18,
+            RuntimeError::ComponentReentrancy(..) => 19,
+            RuntimeError::ComponentNotFound(..) => 20,
+            RuntimeError::ComponentAlreadyLoaded(..) => 21,
+            RuntimeError::ResourceManagerNotFound(..) => 22,
+            RuntimeError::NonFungibleNotFound(..) => 23,
+            RuntimeError::NonFungibleAlreadyExists(..) => 24,
+            RuntimeError::LazyMapNotFound(..) => 25,
+            RuntimeError::LazyMapRemoved(..) => 26,
+            RuntimeError::DuplicateLazyMap(..) => 27,
+            RuntimeError::CyclicLazyMap(..) => 28,
+            RuntimeError::LazyMapNotReadable(..) => 29,
+            RuntimeError::VaultNotFound(..) => 30,
+            RuntimeError::VaultRemoved(..) => 31,
+            RuntimeError::DuplicateVault(..) => 32,
+            RuntimeError::BucketNotFound(..) => 33,
+            RuntimeError::ProofNotFound(..) => 34,
+            RuntimeError::EmptyProof => 35,
+            RuntimeError::ResourceManagerError(..) => 36,
+            RuntimeError::BucketError(..) => 37,
+            RuntimeError::VaultError(..) => 38,
+            RuntimeError::WorktopError(..) => 39,
+            RuntimeError::ProofError(..) => 40,
+            // 41 and 42 were used by errors replaced with `TransientValuePersisted`.
+            RuntimeError::VaultNotAllowed => 43,
+            RuntimeError::LazyMapNotAllowed => 44,
+            RuntimeError::InterpreterNotStarted => 45,
+            RuntimeError::InvalidLevel => 46,
+            RuntimeError::ResourceCheckFailure(..) => 47,
+            RuntimeError::AuthZoneError(..) => 48,
+            RuntimeError::AccountError(..) => 49,
+            RuntimeError::SystemError(..) => 50,
+            RuntimeError::AuthorizationError { .. } => 51,
+            RuntimeError::IndexOutOfBounds { .. } => 52,
+            RuntimeError::CantMoveLockedBucket => 53,
+            RuntimeError::CantMoveRestrictedProof(..) => 54,
+            RuntimeError::BucketsCrossPartialTransaction => 55,
+            RuntimeError::CostingError(..) => 56,
+            RuntimeError::Panic { .. } => 57,
+            RuntimeError::BlueprintError { .. } => 58,
+            RuntimeError::TransientValuePersisted(..) => 59,
+            RuntimeError::StateChangedInReadOnlyCall => 60,
+            RuntimeError::InvalidStateMigration => 61,
+            RuntimeError::LogLimitExceeded { .. } => 62,
+            RuntimeError::LockerNotFound(..) => 63,
+            RuntimeError::LockerError(..) => 64,
+            RuntimeError::AllowanceNotFound(..) => 65,
+            RuntimeError::AllowanceError(..) => 66,
+            RuntimeError::HookError(..) => 67,
+            RuntimeError::ResourceConservationViolated { .. } => 68,
+            RuntimeError::SizeLimitExceeded { .. } => 69,
+            RuntimeError::AccessRuleSlotNotFound(..) => 70,
+            RuntimeError::MethodDisabled { .. } => 71,
+            RuntimeError::FeatureNotDeclared { .. } => 72,
+            RuntimeError::SubstateLocked { .. } => 73,
+            RuntimeError::EventNotSubscribable { .. } => 74,
+            RuntimeError::TooManyEventObservers(..) => 75,
+            RuntimeError::AccessRuleSlotNotAllowed => 76,
+            RuntimeError::ComponentError(..) => 77,
+            RuntimeError::MethodNotDeclared { .. } => 78,
+            RuntimeError::GlobalNodeNotAllowed(..) => 79,
+            RuntimeError::SubstateNotFetched => 80,
+        }
+    }
+}
+
+/// A value which only lives within a transaction and can't be persisted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransientValue {
+    Bucket(BucketId),
+    Proof(ProofId),
+}
+
+/// A frame of the call stack of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallFrame {
+    /// The SNode being invoked.
+    pub snode: String,
+    /// The function or method being invoked.
+    pub function: String,
+}
+
+impl fmt::Display for CallFrame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}::{}", self.snode, self.function)
+    }
+}
+
+/// Represents the failure of a transaction.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TransactionError {
+    /// The numeric code of the cause.
+    pub code: u16,
+    /// The call stack at the point of failure, outermost frame first.
+    pub actor_stack: Vec<CallFrame>,
+    /// The error which caused the failure.
+    pub cause: RuntimeError,
+}
+
+impl TransactionError {
+    pub fn new(cause: RuntimeError, actor_stack: Vec<CallFrame>) -> Self {
+        Self {
+            code: cause.code(),
+            actor_stack,
+            cause,
+        }
+    }
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] {}", self.code, self.cause)?;
+        for frame in &self.actor_stack {
+            write!(f, "\n  at {}", frame)?;
+        }
+        Ok(())
+    }
+}
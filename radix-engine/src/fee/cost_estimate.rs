@@ -0,0 +1,41 @@
+use scrypto::engine::types::*;
+
+use crate::errors::TransactionError;
+
+/// The default cost unit price, in XRD.
+pub const DEFAULT_COST_UNIT_PRICE: &str = "0.000001";
+
+/// The default headroom added to an estimated cost, as a percentage.
+pub const DEFAULT_FEE_HEADROOM_PERCENTAGE: u32 = 20;
+
+/// The estimated cost of a transaction, measured by a preview execution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostEstimate {
+    /// The cost units consumed by the preview.
+    pub cost_units: u32,
+    /// The suggested cost unit limit, including headroom.
+    pub suggested_cost_unit_limit: u32,
+    /// The suggested fee in XRD, for the suggested cost unit limit.
+    pub suggested_fee: Decimal,
+    /// The result of the preview; the estimate of a failed preview only covers the work done
+    /// until the failure.
+    pub preview_result: Result<(), TransactionError>,
+}
+
+impl CostEstimate {
+    pub fn new(
+        cost_units: u32,
+        cost_unit_price: Decimal,
+        headroom_percentage: u32,
+        preview_result: Result<(), TransactionError>,
+    ) -> Self {
+        let headroom = cost_units as u64 * headroom_percentage as u64 / 100;
+        let suggested_cost_unit_limit = (cost_units as u64 + headroom).min(u32::MAX as u64) as u32;
+        Self {
+            cost_units,
+            suggested_cost_unit_limit,
+            suggested_fee: cost_unit_price * suggested_cost_unit_limit,
+            preview_result,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use scrypto::rust::collections::BTreeMap;
+
+/// The default maximum number of cost units a transaction can consume.
+pub const DEFAULT_COST_UNIT_LIMIT: u32 = 10_000_000;
+
+/// Represents an error when consuming cost units.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CostUnitCounterError {
+    /// The transaction has consumed more cost units than its limit.
+    LimitExceeded { limit: u32, consumed: u64 },
+}
+
+/// Keeps track of the cost units consumed by a transaction.
+#[derive(Debug, Clone)]
+pub struct CostUnitCounter {
+    limit: u32,
+    consumed: u32,
+    breakdown: BTreeMap<&'static str, u32>,
+}
+
+impl CostUnitCounter {
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            consumed: 0,
+            breakdown: BTreeMap::new(),
+        }
+    }
+
+    /// Consumes cost units for the given reason, failing if the limit is exceeded.
+    pub fn consume(
+        &mut self,
+        amount: u32,
+        reason: &'static str,
+    ) -> Result<(), CostUnitCounterError> {
+        let consumed = self.consumed as u64 + amount as u64;
+        if consumed > self.limit as u64 {
+            return Err(CostUnitCounterError::LimitExceeded {
+                limit: self.limit,
+                consumed,
+            });
+        }
+        self.consumed = consumed as u32;
+        *self.breakdown.entry(reason).or_insert(0) += amount;
+        Ok(())
+    }
+
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    /// Changes the limit, which may be below the cost units already consumed.
+    pub fn set_limit(&mut self, limit: u32) {
+        self.limit = limit;
+    }
+
+    pub fn consumed(&self) -> u32 {
+        self.consumed
+    }
+
+    pub fn remaining(&self) -> u32 {
+        self.limit - self.consumed
+    }
+
+    /// Returns the cost units consumed for each reason.
+    pub fn breakdown(&self) -> &BTreeMap<&'static str, u32> {
+        &self.breakdown
+    }
+}
+
+impl Default for CostUnitCounter {
+    fn default() -> Self {
+        Self::new(DEFAULT_COST_UNIT_LIMIT)
+    }
+}
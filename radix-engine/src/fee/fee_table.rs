@@ -0,0 +1,94 @@
+/// The cost units charged for each kind of engine work.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeTable {
+    tx_base_fee: u32,
+    invoke_snode: u32,
+    engine_call: u32,
+    engine_call_per_byte: u32,
+    wasm_instantiation_per_byte: u32,
+    publish_package_per_byte: u32,
+    emit_log_per_byte: u32,
+    substate_read_per_byte: u32,
+    substate_write_per_byte: u32,
+    lock_substate: u32,
+    deliver_notification: u32,
+}
+
+impl FeeTable {
+    pub fn new() -> Self {
+        Self {
+            tx_base_fee: 10_000,
+            invoke_snode: 1_000,
+            engine_call: 100,
+            engine_call_per_byte: 1,
+            wasm_instantiation_per_byte: 1,
+            publish_package_per_byte: 5,
+            emit_log_per_byte: 10,
+            substate_read_per_byte: 1,
+            substate_write_per_byte: 10,
+            lock_substate: 100,
+            deliver_notification: 500,
+        }
+    }
+
+    /// The cost of executing a transaction, regardless of what it does.
+    pub fn tx_base_fee(&self) -> u32 {
+        self.tx_base_fee
+    }
+
+    /// The cost of invoking a function or method.
+    pub fn invoke_snode(&self) -> u32 {
+        self.invoke_snode
+    }
+
+    /// The cost of a call from wasm into the engine, excluding its data.
+    pub fn engine_call(&self) -> u32 {
+        self.engine_call
+    }
+
+    /// The cost of each byte passed between wasm and the engine.
+    pub fn engine_call_per_byte(&self) -> u32 {
+        self.engine_call_per_byte
+    }
+
+    /// The cost of each byte of package code instantiated for a call.
+    pub fn wasm_instantiation_per_byte(&self) -> u32 {
+        self.wasm_instantiation_per_byte
+    }
+
+    /// The cost of each byte of package code published.
+    pub fn publish_package_per_byte(&self) -> u32 {
+        self.publish_package_per_byte
+    }
+
+    /// The cost of each byte of log message emitted.
+    pub fn emit_log_per_byte(&self) -> u32 {
+        self.emit_log_per_byte
+    }
+
+    /// The cost of each byte of substate read from the ledger.
+    pub fn substate_read_per_byte(&self) -> u32 {
+        self.substate_read_per_byte
+    }
+
+    /// The cost of each byte of substate created or updated.
+    pub fn substate_write_per_byte(&self) -> u32 {
+        self.substate_write_per_byte
+    }
+
+    /// The cost of locking a substate for reading or writing.
+    pub fn lock_substate(&self) -> u32 {
+        self.lock_substate
+    }
+
+    /// The cost of adding a notification to the pending notifications of an observer.
+    pub fn deliver_notification(&self) -> u32 {
+        self.deliver_notification
+    }
+}
+
+impl Default for FeeTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,7 @@
+mod cost_estimate;
+mod cost_unit_counter;
+mod fee_table;
+
+pub use cost_estimate::*;
+pub use cost_unit_counter::*;
+pub use fee_table::*;
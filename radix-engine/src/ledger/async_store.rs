@@ -0,0 +1,164 @@
+use core::future::Future;
+use core::pin::Pin;
+use sbor::Encode;
+use scrypto::buffer::scrypto_encode;
+use scrypto::rust::boxed::Box;
+use scrypto::rust::cell::RefCell;
+use scrypto::rust::collections::*;
+use scrypto::rust::vec::Vec;
+
+use crate::ledger::*;
+
+/// A future returned by an async substate store.
+pub type StoreFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// The key of a substate: the encoded address and, for child substates, the child key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SubstateKey {
+    pub address: Vec<u8>,
+    pub child_key: Option<Vec<u8>>,
+}
+
+impl SubstateKey {
+    pub fn substate<T: Encode>(address: &T) -> Self {
+        Self {
+            address: scrypto_encode(address),
+            child_key: None,
+        }
+    }
+
+    pub fn child_substate<T: Encode>(address: &T, key: &[u8]) -> Self {
+        Self {
+            address: scrypto_encode(address),
+            child_key: Some(key.to_vec()),
+        }
+    }
+}
+
+/// A substate store whose reads are asynchronous, e.g. one backed by a network database or
+/// object storage.
+///
+/// Reads are batched, so that a transaction costs a few round trips rather than one per
+/// substate.
+pub trait AsyncReadableSubstateStore: Sync {
+    /// Returns the substates with the given keys, in the same order.
+    fn fetch_substates(&self, keys: Vec<SubstateKey>) -> StoreFuture<'_, Vec<Option<Substate>>>;
+
+    fn fetch_epoch(&self) -> StoreFuture<'_, u64>;
+
+    fn fetch_nonce(&self) -> StoreFuture<'_, u64>;
+}
+
+/// The state changes of a transaction, which are written to an async store at once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriteBatch {
+    pub substates: Vec<(SubstateKey, Substate)>,
+    pub epoch: Option<u64>,
+    pub nonce_increment: u64,
+}
+
+/// An async substate store which can be written to.
+pub trait AsyncWriteableSubstateStore: AsyncReadableSubstateStore {
+    /// Writes the state changes of a committed transaction atomically.
+    fn write_batch(&mut self, batch: WriteBatch) -> StoreFuture<'_, ()>;
+}
+
+/// A synchronous view of an async store, which the engine executes against.
+///
+/// Reads are served from substates fetched beforehand. A read of a substate which hasn't been
+/// fetched is recorded as a miss and returns `None`, which the track turns into
+/// `RuntimeError::SubstateNotFetched`, so the execution must be retried once the misses are
+/// fetched. Writes are buffered until they are taken as a `WriteBatch`.
+pub struct BufferedSubstateStore<'s> {
+    fetched: &'s HashMap<SubstateKey, Option<Substate>>,
+    misses: RefCell<BTreeSet<SubstateKey>>,
+    writes: BTreeMap<SubstateKey, Substate>,
+    epoch: u64,
+    new_epoch: Option<u64>,
+    nonce: u64,
+    nonce_increment: u64,
+}
+
+impl<'s> BufferedSubstateStore<'s> {
+    pub fn new(
+        fetched: &'s HashMap<SubstateKey, Option<Substate>>,
+        epoch: u64,
+        nonce: u64,
+    ) -> Self {
+        Self {
+            fetched,
+            misses: RefCell::new(BTreeSet::new()),
+            writes: BTreeMap::new(),
+            epoch,
+            new_epoch: None,
+            nonce,
+            nonce_increment: 0,
+        }
+    }
+
+    /// Returns the keys which were read but not fetched.
+    pub fn misses(&self) -> Vec<SubstateKey> {
+        self.misses.borrow().iter().cloned().collect()
+    }
+
+    /// Takes the buffered writes.
+    pub fn into_write_batch(self) -> WriteBatch {
+        WriteBatch {
+            substates: self.writes.into_iter().collect(),
+            epoch: self.new_epoch,
+            nonce_increment: self.nonce_increment,
+        }
+    }
+
+    fn get(&self, key: SubstateKey) -> Option<Substate> {
+        if let Some(substate) = self.writes.get(&key) {
+            return Some(substate.clone());
+        }
+        match self.fetched.get(&key) {
+            Some(substate) => substate.clone(),
+            None => {
+                self.misses.borrow_mut().insert(key);
+                None
+            }
+        }
+    }
+}
+
+impl<'s> SubstateStore for BufferedSubstateStore<'s> {
+    fn get_substate<T: Encode>(&self, address: &T) -> Option<Substate> {
+        self.get(SubstateKey::substate(address))
+    }
+
+    fn put_substate<T: Encode>(&mut self, address: &T, substate: Substate) {
+        self.writes.insert(SubstateKey::substate(address), substate);
+    }
+
+    fn get_child_substate<T: Encode>(&self, address: &T, key: &[u8]) -> Option<Substate> {
+        self.get(SubstateKey::child_substate(address, key))
+    }
+
+    fn put_child_substate<T: Encode>(&mut self, address: &T, key: &[u8], substate: Substate) {
+        self.writes
+            .insert(SubstateKey::child_substate(address, key), substate);
+    }
+
+    fn has_unfetched_reads(&self) -> bool {
+        !self.misses.borrow().is_empty()
+    }
+
+    fn get_epoch(&self) -> u64 {
+        self.new_epoch.unwrap_or(self.epoch)
+    }
+
+    fn set_epoch(&mut self, epoch: u64) {
+        self.new_epoch = Some(epoch);
+    }
+
+    fn get_nonce(&self) -> u64 {
+        self.nonce + self.nonce_increment
+    }
+
+    fn increase_nonce(&mut self) {
+        self.nonce_increment += 1;
+    }
+}
@@ -1,5 +1,7 @@
 use sbor::Encode;
-use scrypto::buffer::scrypto_encode;
+use scrypto::buffer::{scrypto_decode, scrypto_encode};
+use scrypto::engine::types::*;
+use scrypto::rust::boxed::Box;
 use scrypto::rust::collections::HashMap;
 use scrypto::rust::vec::Vec;
 
@@ -7,12 +9,13 @@ use crate::ledger::traits::Substate;
 use crate::ledger::*;
 
 /// An in-memory ledger stores all substates in host memory.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct InMemorySubstateStore {
     substates: HashMap<Vec<u8>, Substate>,
     child_substates: HashMap<Vec<u8>, Substate>,
     current_epoch: u64,
     nonce: u64,
+    non_fungible_locations: Option<HashMap<NonFungibleAddress, NonFungibleLocation>>,
 }
 
 impl InMemorySubstateStore {
@@ -22,6 +25,7 @@ impl InMemorySubstateStore {
             child_substates: HashMap::new(),
             current_epoch: 0,
             nonce: 0,
+            non_fungible_locations: None,
         }
     }
 
@@ -30,6 +34,12 @@ impl InMemorySubstateStore {
         ledger.bootstrap();
         ledger
     }
+
+    /// Enables the non-fungible location index, for non-fungibles committed from now on.
+    pub fn with_non_fungible_index(mut self) -> Self {
+        self.non_fungible_locations = Some(HashMap::new());
+        self
+    }
 }
 
 impl Default for InMemorySubstateStore {
@@ -74,4 +84,131 @@ impl SubstateStore for InMemorySubstateStore {
     fn increase_nonce(&mut self) {
         self.nonce += 1;
     }
+
+    fn is_non_fungible_index_enabled(&self) -> bool {
+        self.non_fungible_locations.is_some()
+    }
+
+    fn get_non_fungible_location(
+        &self,
+        non_fungible_address: &NonFungibleAddress,
+    ) -> Option<NonFungibleLocation> {
+        self.non_fungible_locations
+            .as_ref()
+            .and_then(|locations| locations.get(non_fungible_address).cloned())
+    }
+
+    fn put_non_fungible_location(
+        &mut self,
+        non_fungible_address: NonFungibleAddress,
+        location: Option<NonFungibleLocation>,
+    ) {
+        if let Some(locations) = self.non_fungible_locations.as_mut() {
+            match location {
+                Some(location) => locations.insert(non_fungible_address, location),
+                None => locations.remove(&non_fungible_address),
+            };
+        }
+    }
+}
+
+impl InMemorySubstateStore {
+    fn substate_id(key: &SubstateKey) -> (bool, Vec<u8>) {
+        match &key.child_key {
+            Some(child_key) => {
+                let mut id = key.address.clone();
+                id.extend(child_key);
+                (true, id)
+            }
+            None => (false, key.address.clone()),
+        }
+    }
+}
+
+/// Async access, with futures which are ready immediately, for testing async executors.
+impl AsyncReadableSubstateStore for InMemorySubstateStore {
+    fn fetch_substates(&self, keys: Vec<SubstateKey>) -> StoreFuture<'_, Vec<Option<Substate>>> {
+        let substates = keys
+            .iter()
+            .map(|key| match Self::substate_id(key) {
+                (true, id) => self.child_substates.get(&id).cloned(),
+                (false, id) => self.substates.get(&id).cloned(),
+            })
+            .collect();
+        Box::pin(async move { substates })
+    }
+
+    fn fetch_epoch(&self) -> StoreFuture<'_, u64> {
+        let epoch = self.current_epoch;
+        Box::pin(async move { epoch })
+    }
+
+    fn fetch_nonce(&self) -> StoreFuture<'_, u64> {
+        let nonce = self.nonce;
+        Box::pin(async move { nonce })
+    }
+}
+
+impl AsyncWriteableSubstateStore for InMemorySubstateStore {
+    fn write_batch(&mut self, batch: WriteBatch) -> StoreFuture<'_, ()> {
+        for (key, substate) in batch.substates {
+            match Self::substate_id(&key) {
+                (true, id) => self.child_substates.insert(id, substate),
+                (false, id) => self.substates.insert(id, substate),
+            };
+        }
+        if let Some(epoch) = batch.epoch {
+            self.current_epoch = epoch;
+        }
+        self.nonce += batch.nonce_increment;
+        Box::pin(async {})
+    }
+}
+
+impl QueryableSubstateStore for InMemorySubstateStore {
+    fn get_lazy_map_entries(
+        &self,
+        component_address: ComponentAddress,
+        lazy_map_id: &LazyMapId,
+    ) -> HashMap<Vec<u8>, Vec<u8>> {
+        let mut prefix = scrypto_encode(&component_address);
+        prefix.extend(scrypto_encode(lazy_map_id));
+
+        self.child_substates
+            .iter()
+            .filter(|(id, _)| id.starts_with(&prefix) && id.len() > prefix.len())
+            .map(|(id, substate)| (id[prefix.len()..].to_vec(), substate.value.clone()))
+            .collect()
+    }
+}
+
+impl PrunableSubstateStore for InMemorySubstateStore {
+    fn get_component_addresses(&self) -> Vec<ComponentAddress> {
+        self.substates
+            .keys()
+            .filter_map(|key| scrypto_decode(key).ok())
+            .collect()
+    }
+
+    fn get_resource_addresses(&self) -> Vec<ResourceAddress> {
+        self.substates
+            .keys()
+            .filter_map(|key| scrypto_decode(key).ok())
+            .collect()
+    }
+
+    fn get_child_substates<T: Encode>(&self, address: &T) -> Vec<(Vec<u8>, Substate)> {
+        let prefix = scrypto_encode(address);
+        self.child_substates
+            .iter()
+            .filter(|(id, _)| id.starts_with(&prefix))
+            .map(|(id, substate)| (id[prefix.len()..].to_vec(), substate.clone()))
+            .collect()
+    }
+
+    fn delete_child_substate<T: Encode>(&mut self, address: &T, key: &[u8]) {
+        let mut id = scrypto_encode(address);
+        id.extend(key.to_vec());
+        self.child_substates.remove(&id);
+    }
 }
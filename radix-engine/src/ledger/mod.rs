@@ -1,7 +1,17 @@
+mod async_store;
 mod memory;
+mod pruner;
+mod query;
+mod read_only;
 mod traits;
 
+pub use async_store::*;
 pub use memory::InMemorySubstateStore;
+pub use pruner::*;
+pub use query::*;
+pub use read_only::ReadOnlySubstateStore;
+pub use traits::NonFungibleLocation;
+pub use traits::PrunableSubstateStore;
 pub use traits::QueryableSubstateStore;
 pub use traits::Substate;
 pub use traits::SubstateIdGenerator;
@@ -0,0 +1,218 @@
+use sbor::Encode;
+use scrypto::buffer::*;
+use scrypto::engine::types::*;
+use scrypto::rust::collections::*;
+use scrypto::rust::vec::Vec;
+use scrypto::values::ScryptoValueIds;
+
+use crate::ledger::*;
+use crate::model::*;
+
+/// Specifies which kinds of unreachable substates the pruner removes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrunerConfig {
+    /// Whether to remove the data of burned non-fungibles.
+    pub prune_burned_non_fungibles: bool,
+
+    /// Whether to remove lazy map entries which are no longer reachable from their component.
+    pub prune_unreachable_lazy_map_entries: bool,
+
+    /// Whether to remove empty vaults which are no longer reachable from their component.
+    ///
+    /// Non-empty vaults are always retained, as they still account for resource supply.
+    pub prune_unreachable_vaults: bool,
+
+    /// Whether to remove lockers which have been emptied by a claim.
+    pub prune_emptied_lockers: bool,
+
+    /// Whether to only collect statistics, without removing anything.
+    pub dry_run: bool,
+}
+
+impl Default for PrunerConfig {
+    fn default() -> Self {
+        Self {
+            prune_burned_non_fungibles: true,
+            prune_unreachable_lazy_map_entries: true,
+            prune_unreachable_vaults: true,
+            prune_emptied_lockers: true,
+            dry_run: false,
+        }
+    }
+}
+
+/// Statistics of a pruning run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrunerStats {
+    pub components_scanned: usize,
+    pub resource_managers_scanned: usize,
+    pub burned_non_fungibles: usize,
+    pub unreachable_lazy_map_entries: usize,
+    pub unreachable_vaults: usize,
+    pub retained_non_empty_vaults: usize,
+    pub emptied_lockers: usize,
+    pub substates_pruned: usize,
+    pub bytes_pruned: usize,
+}
+
+/// Removes substates which are no longer reachable from the ledger state.
+///
+/// Component state is the root of reachability: a vault or lazy map is reachable if it's
+/// referenced by the component state or by a reachable lazy map entry. Components can't be
+/// destroyed by the engine yet, so every component is treated as a root.
+pub struct Pruner {
+    config: PrunerConfig,
+}
+
+impl Pruner {
+    pub fn new(config: PrunerConfig) -> Self {
+        Self { config }
+    }
+
+    /// Prunes the substate store and returns the statistics.
+    pub fn prune<S: SubstateStore + PrunableSubstateStore>(
+        &self,
+        substate_store: &mut S,
+    ) -> PrunerStats {
+        let mut stats = PrunerStats::default();
+
+        for component_address in substate_store.get_component_addresses() {
+            self.prune_component(substate_store, component_address, &mut stats);
+        }
+
+        for resource_address in substate_store.get_resource_addresses() {
+            self.prune_resource_manager(substate_store, resource_address, &mut stats);
+        }
+
+        stats
+    }
+
+    fn prune_component<S: SubstateStore + PrunableSubstateStore>(
+        &self,
+        substate_store: &mut S,
+        component_address: ComponentAddress,
+        stats: &mut PrunerStats,
+    ) {
+        let component: Component = match substate_store.get_decoded_substate(&component_address) {
+            Some((component, _)) => component,
+            None => return,
+        };
+        stats.components_scanned += 1;
+
+        // Skip components whose state can't be parsed, as reachability is unknown.
+        let state = match ScryptoValueIds::from_slice(component.state()) {
+            Ok(state) => state,
+            Err(_) => return,
+        };
+
+        // Vault and lazy map ids share the same encoding; lazy map entries extend it with the key.
+        let id_len = scrypto_encode(&(Hash([0u8; 32]), 0u32)).len();
+        let children = substate_store.get_child_substates(&component_address);
+        let mut lazy_map_entries: HashMap<LazyMapId, Vec<&Substate>> = HashMap::new();
+        for (key, substate) in &children {
+            if key.len() > id_len {
+                if let Ok(lazy_map_id) = scrypto_decode::<LazyMapId>(&key[..id_len]) {
+                    lazy_map_entries
+                        .entry(lazy_map_id)
+                        .or_insert(Vec::new())
+                        .push(substate);
+                }
+            }
+        }
+
+        // Find all vaults and lazy maps reachable from the component state
+        let mut reachable_vaults: BTreeSet<VaultId> = state.vault_ids.clone();
+        let mut reachable_lazy_maps: HashSet<LazyMapId> = HashSet::new();
+        let mut queue: Vec<LazyMapId> = state.lazy_map_ids.iter().cloned().collect();
+        while let Some(lazy_map_id) = queue.pop() {
+            if !reachable_lazy_maps.insert(lazy_map_id) {
+                continue;
+            }
+            for substate in lazy_map_entries.get(&lazy_map_id).into_iter().flatten() {
+                if let Ok(value) = ScryptoValueIds::from_slice(&substate.value) {
+                    reachable_vaults.extend(value.vault_ids);
+                    queue.extend(value.lazy_map_ids);
+                }
+            }
+        }
+
+        for (key, substate) in &children {
+            // Emptied lockers are kept as `None`, which is equivalent to a missing substate
+            if scrypto_decode::<LockerKey>(key).is_ok() {
+                if let Ok(None) = scrypto_decode::<Option<Locker>>(&substate.value) {
+                    stats.emptied_lockers += 1;
+                    if self.config.prune_emptied_lockers {
+                        self.delete(substate_store, &component_address, key, substate, stats);
+                    }
+                }
+                continue;
+            }
+
+            let prune = if key.len() == id_len {
+                let vault_id: VaultId = match scrypto_decode(key) {
+                    Ok(vault_id) => vault_id,
+                    Err(_) => continue,
+                };
+                if reachable_vaults.contains(&vault_id) {
+                    continue;
+                }
+                stats.unreachable_vaults += 1;
+                let is_empty = scrypto_decode::<Vault>(&substate.value)
+                    .map(|vault| vault.is_empty())
+                    .unwrap_or(false);
+                if !is_empty {
+                    stats.retained_non_empty_vaults += 1;
+                }
+                self.config.prune_unreachable_vaults && is_empty
+            } else {
+                let lazy_map_id: LazyMapId = match scrypto_decode(&key[..id_len]) {
+                    Ok(lazy_map_id) => lazy_map_id,
+                    Err(_) => continue,
+                };
+                if reachable_lazy_maps.contains(&lazy_map_id) {
+                    continue;
+                }
+                stats.unreachable_lazy_map_entries += 1;
+                self.config.prune_unreachable_lazy_map_entries
+            };
+
+            if prune {
+                self.delete(substate_store, &component_address, key, substate, stats);
+            }
+        }
+    }
+
+    fn prune_resource_manager<S: SubstateStore + PrunableSubstateStore>(
+        &self,
+        substate_store: &mut S,
+        resource_address: ResourceAddress,
+        stats: &mut PrunerStats,
+    ) {
+        stats.resource_managers_scanned += 1;
+
+        for (key, substate) in substate_store.get_child_substates(&resource_address) {
+            // Burned non-fungibles are kept as `None`, which is equivalent to a missing substate
+            if let Ok(None) = scrypto_decode::<Option<NonFungible>>(&substate.value) {
+                stats.burned_non_fungibles += 1;
+                if self.config.prune_burned_non_fungibles {
+                    self.delete(substate_store, &resource_address, &key, &substate, stats);
+                }
+            }
+        }
+    }
+
+    fn delete<S: PrunableSubstateStore, A: Encode>(
+        &self,
+        substate_store: &mut S,
+        address: &A,
+        key: &[u8],
+        substate: &Substate,
+        stats: &mut PrunerStats,
+    ) {
+        if !self.config.dry_run {
+            substate_store.delete_child_substate(address, key);
+        }
+        stats.substates_pruned += 1;
+        stats.bytes_pruned += key.len() + substate.value.len();
+    }
+}
@@ -0,0 +1,57 @@
+use scrypto::engine::types::*;
+use scrypto::rust::collections::*;
+use scrypto::rust::vec::Vec;
+use scrypto::values::ScryptoValueIds;
+
+use crate::ledger::*;
+use crate::model::*;
+
+/// Returns the ids of all vaults owned by a component, or `None` if the component doesn't exist.
+///
+/// Vaults are found by walking the component state and its lazy maps, assuming a tree structure.
+pub fn get_component_vaults<S: SubstateStore + QueryableSubstateStore>(
+    substate_store: &S,
+    component_address: ComponentAddress,
+) -> Option<Vec<VaultId>> {
+    let component: Component = substate_store
+        .get_decoded_substate(&component_address)
+        .map(|(component, _)| component)?;
+    let state = ScryptoValueIds::from_slice(component.state()).unwrap();
+
+    let mut vaults_found: Vec<VaultId> = state.vault_ids.iter().cloned().collect();
+    let mut lazy_maps_visited: HashSet<LazyMapId> = HashSet::new();
+    let mut queue: Vec<LazyMapId> = state.lazy_map_ids.iter().cloned().collect();
+    while let Some(lazy_map_id) = queue.pop() {
+        if !lazy_maps_visited.insert(lazy_map_id) {
+            continue;
+        }
+        let map = substate_store.get_lazy_map_entries(component_address, &lazy_map_id);
+        for (_, value) in map {
+            let value = ScryptoValueIds::from_slice(&value).unwrap();
+            vaults_found.extend(value.vault_ids);
+            queue.extend(value.lazy_map_ids);
+        }
+    }
+
+    Some(vaults_found)
+}
+
+/// Returns the total amount of each resource held by a component, across all its vaults.
+///
+/// An empty map is returned if the component doesn't exist.
+pub fn get_component_resources<S: SubstateStore + QueryableSubstateStore>(
+    substate_store: &S,
+    component_address: ComponentAddress,
+) -> HashMap<ResourceAddress, Decimal> {
+    let mut resources = HashMap::new();
+    for vault_id in get_component_vaults(substate_store, component_address).unwrap_or_default() {
+        let vault: Vault = substate_store
+            .get_decoded_child_substate(&component_address, &vault_id)
+            .map(|(vault, _)| vault)
+            .unwrap();
+        *resources
+            .entry(vault.resource_address())
+            .or_insert(Decimal::zero()) += vault.total_amount();
+    }
+    resources
+}
@@ -0,0 +1,91 @@
+use sbor::Encode;
+use scrypto::engine::types::*;
+use scrypto::rust::collections::*;
+use scrypto::rust::vec::Vec;
+
+use crate::ledger::*;
+
+/// A view of a substate store through a shared reference, so that several threads can execute
+/// read-only calls and previews against the same store.
+///
+/// Nothing is ever committed on those paths, so writes are a bug and panic.
+pub struct ReadOnlySubstateStore<'s, S: SubstateStore> {
+    substate_store: &'s S,
+}
+
+impl<'s, S: SubstateStore> ReadOnlySubstateStore<'s, S> {
+    pub fn new(substate_store: &'s S) -> Self {
+        Self { substate_store }
+    }
+}
+
+impl<'s, S: SubstateStore> SubstateStore for ReadOnlySubstateStore<'s, S> {
+    fn get_substate<T: Encode>(&self, address: &T) -> Option<Substate> {
+        self.substate_store.get_substate(address)
+    }
+
+    fn put_substate<T: Encode>(&mut self, _address: &T, _substate: Substate) {
+        panic!("Read-only substate store can't be written to");
+    }
+
+    fn get_child_substate<T: Encode>(&self, address: &T, key: &[u8]) -> Option<Substate> {
+        self.substate_store.get_child_substate(address, key)
+    }
+
+    fn put_child_substate<T: Encode>(&mut self, _address: &T, _key: &[u8], _substate: Substate) {
+        panic!("Read-only substate store can't be written to");
+    }
+
+    fn get_epoch(&self) -> u64 {
+        self.substate_store.get_epoch()
+    }
+
+    fn set_epoch(&mut self, _epoch: u64) {
+        panic!("Read-only substate store can't be written to");
+    }
+
+    fn get_nonce(&self) -> u64 {
+        self.substate_store.get_nonce()
+    }
+
+    fn increase_nonce(&mut self) {
+        panic!("Read-only substate store can't be written to");
+    }
+
+    fn has_unfetched_reads(&self) -> bool {
+        self.substate_store.has_unfetched_reads()
+    }
+
+    fn is_non_fungible_index_enabled(&self) -> bool {
+        self.substate_store.is_non_fungible_index_enabled()
+    }
+
+    fn get_non_fungible_location(
+        &self,
+        non_fungible_address: &NonFungibleAddress,
+    ) -> Option<NonFungibleLocation> {
+        self.substate_store
+            .get_non_fungible_location(non_fungible_address)
+    }
+
+    fn put_non_fungible_location(
+        &mut self,
+        _non_fungible_address: NonFungibleAddress,
+        _location: Option<NonFungibleLocation>,
+    ) {
+        panic!("Read-only substate store can't be written to");
+    }
+}
+
+impl<'s, S: SubstateStore + QueryableSubstateStore> QueryableSubstateStore
+    for ReadOnlySubstateStore<'s, S>
+{
+    fn get_lazy_map_entries(
+        &self,
+        component_address: ComponentAddress,
+        lazy_map_id: &LazyMapId,
+    ) -> HashMap<Vec<u8>, Vec<u8>> {
+        self.substate_store
+            .get_lazy_map_entries(component_address, lazy_map_id)
+    }
+}
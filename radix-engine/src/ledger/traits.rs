@@ -1,16 +1,20 @@
 use sbor::*;
-use scrypto::rule;
+use scrypto::{access_rule_node, rule};
 use scrypto::buffer::*;
 use scrypto::constants::*;
 use scrypto::crypto::*;
 use scrypto::engine::types::*;
 use scrypto::prelude::LOCKED;
 use scrypto::resource::ResourceMethod::Withdraw;
+use scrypto::resource::{
+    require, AccessRules, METADATA_DESCRIPTION, METADATA_NAME, METADATA_SYMBOL, METADATA_URL,
+};
 use scrypto::rust::borrow::ToOwned;
 use scrypto::rust::collections::*;
 use scrypto::rust::vec;
 use scrypto::rust::vec::Vec;
 
+use crate::engine::EngineVersion;
 use crate::model::*;
 
 const XRD_SYMBOL: &str = "XRD";
@@ -23,10 +27,7 @@ const XRD_VAULT: scrypto::resource::Vault = scrypto::resource::Vault(XRD_VAULT_I
 
 const SYSTEM_COMPONENT_NAME: &str = "System";
 
-#[derive(TypeId, Encode, Decode)]
-struct SystemComponentState {
-    xrd: scrypto::resource::Vault,
-}
+const ENGINE_VERSION_KEY: &str = "engine_version";
 
 pub trait QueryableSubstateStore {
     fn get_lazy_map_entries(
@@ -36,7 +37,26 @@ pub trait QueryableSubstateStore {
     ) -> HashMap<Vec<u8>, Vec<u8>>;
 }
 
-#[derive(Clone, Debug, Encode, Decode, TypeId)]
+/// A substate store which can enumerate and remove substates, as required by the pruner.
+pub trait PrunableSubstateStore {
+    fn get_component_addresses(&self) -> Vec<ComponentAddress>;
+
+    fn get_resource_addresses(&self) -> Vec<ResourceAddress>;
+
+    /// Returns all child substates of an address, keyed by their child key.
+    fn get_child_substates<T: Encode>(&self, address: &T) -> Vec<(Vec<u8>, Substate)>;
+
+    fn delete_child_substate<T: Encode>(&mut self, address: &T, key: &[u8]);
+}
+
+/// The vault which holds a non-fungible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeId)]
+pub struct NonFungibleLocation {
+    pub component_address: ComponentAddress,
+    pub vault_id: VaultId,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeId)]
 pub struct Substate {
     pub value: Vec<u8>,
     pub phys_id: (Hash, u32),
@@ -144,6 +164,58 @@ pub trait SubstateStore {
         );
     }
 
+    /// Stores a schema in the schema registry, unless it's already there.
+    ///
+    /// Returns the id of the new substate, if the schema was stored.
+    fn put_schema(
+        &mut self,
+        schema_hash: Hash,
+        schema: &Type,
+        id_gen: &mut SubstateIdGenerator,
+    ) -> Option<(Hash, u32)> {
+        let address = SchemaAddress(schema_hash);
+        if self.get_substate(&address).is_some() {
+            return None;
+        }
+        let phys_id = id_gen.next();
+        self.put_encoded_substate(&address, schema, phys_id);
+        Some(phys_id)
+    }
+
+    /// Stores a package, and the schemas of its blueprints in the schema registry.
+    fn put_package(
+        &mut self,
+        package_address: &PackageAddress,
+        package: &Package,
+        id_gen: &mut SubstateIdGenerator,
+    ) {
+        self.put_encoded_substate(package_address, package, id_gen.next());
+        for (schema_hash, schema) in package.schemas() {
+            self.put_schema(*schema_hash, schema, id_gen);
+        }
+    }
+
+    /// Returns a package, with the schemas of its blueprints loaded from the schema registry.
+    fn get_package(&self, package_address: &PackageAddress) -> Option<Package> {
+        self.get_decoded_substate(package_address)
+            .map(|(mut package, _): (Package, _)| {
+                package.load_schemas(|schema_hash| self.get_schema(schema_hash));
+                package
+            })
+    }
+
+    /// Returns the schema with the given hash from the schema registry.
+    fn get_schema(&self, schema_hash: &Hash) -> Option<Type> {
+        self.get_decoded_substate(&SchemaAddress(*schema_hash))
+            .map(|(schema, _)| schema)
+    }
+
+    /// Writes the genesis state, unless the ledger already has it.
+    ///
+    /// Genesis is written directly rather than executed as a system transaction, as the system
+    /// component which authorizes system transactions, and the packages and resources it
+    /// depends on, don't exist yet and live at fixed addresses. Changes made after genesis, such
+    /// as advancing the epoch, go through system transactions instead.
     fn bootstrap(&mut self) {
         let package: Option<Package> = self
             .get_decoded_substate(&SYSTEM_PACKAGE)
@@ -152,22 +224,25 @@ pub trait SubstateStore {
             let tx_hash = hash(self.get_and_increase_nonce().to_le_bytes());
             let mut id_gen = SubstateIdGenerator::new(tx_hash);
 
-            // System package
+            // System package, kept for its ABI; the blueprint is executed natively
             let system_package =
                 Package::new(include_bytes!("../../../assets/system.wasm").to_vec()).unwrap();
-            self.put_encoded_substate(&SYSTEM_PACKAGE, &system_package, id_gen.next());
+            self.put_package(&SYSTEM_PACKAGE, &system_package, &mut id_gen);
 
-            // Account package
+            // Account package, kept for its ABI; the blueprint is executed natively
             let account_package =
                 Package::new(include_bytes!("../../../assets/account.wasm").to_vec()).unwrap();
-            self.put_encoded_substate(&ACCOUNT_PACKAGE, &account_package, id_gen.next());
+            self.put_package(&ACCOUNT_PACKAGE, &account_package, &mut id_gen);
 
             // Radix token resource address
             let mut metadata = HashMap::new();
-            metadata.insert("symbol".to_owned(), XRD_SYMBOL.to_owned());
-            metadata.insert("name".to_owned(), XRD_NAME.to_owned());
-            metadata.insert("description".to_owned(), XRD_DESCRIPTION.to_owned());
-            metadata.insert("url".to_owned(), XRD_URL.to_owned());
+            metadata.insert(METADATA_SYMBOL.to_owned(), XRD_SYMBOL.into());
+            metadata.insert(METADATA_NAME.to_owned(), XRD_NAME.into());
+            metadata.insert(METADATA_DESCRIPTION.to_owned(), XRD_DESCRIPTION.into());
+            metadata.insert(
+                METADATA_URL.to_owned(),
+                MetadataValue::Url(XRD_URL.to_owned()),
+            );
 
             let mut resource_auth = HashMap::new();
             resource_auth.insert(Withdraw, (rule!(allow_all), LOCKED));
@@ -193,6 +268,14 @@ pub trait SubstateStore {
             .unwrap();
             self.put_encoded_substate(&ECDSA_TOKEN, &ecdsa_token, id_gen.next());
 
+            // Caller identity and system badge virtual resources
+            for virtual_token in [PACKAGE_TOKEN, GLOBAL_CALLER_TOKEN, SYSTEM_TOKEN] {
+                let resource_manager =
+                    ResourceManager::new(ResourceType::NonFungible, HashMap::new(), HashMap::new())
+                        .unwrap();
+                self.put_encoded_substate(&virtual_token, &resource_manager, id_gen.next());
+            }
+
             // Instantiate system component
             let system_vault = Vault::new(minted_xrd);
             self.put_encoded_child_substate(
@@ -202,13 +285,20 @@ pub trait SubstateStore {
                 id_gen.next(),
             );
 
+            // Privileged methods may only be called by system transactions
+            let system_access_rules = AccessRules::new()
+                .method("set_epoch", rule!(require(SYSTEM_TOKEN)))
+                .method("set_engine_version", rule!(require(SYSTEM_TOKEN)))
+                .default(rule!(allow_all));
             let system_component = Component::new(
                 SYSTEM_PACKAGE,
                 SYSTEM_COMPONENT_NAME.to_owned(),
-                vec![],
+                vec![system_access_rules],
                 scrypto_encode(&SystemComponentState { xrd: XRD_VAULT }),
             );
             self.put_encoded_substate(&SYSTEM_COMPONENT, &system_component, id_gen.next());
+
+            self.set_engine_version(EngineVersion::LATEST);
         }
     }
 
@@ -216,6 +306,22 @@ pub trait SubstateStore {
 
     fn set_epoch(&mut self, epoch: u64);
 
+    /// Returns the engine version transactions are executed with.
+    ///
+    /// Ledgers bootstrapped before the version was recorded run `EngineVersion::V1`, until a
+    /// system transaction calls `set_engine_version` on the system component.
+    fn get_engine_version(&self) -> EngineVersion {
+        self.get_decoded_substate(&ENGINE_VERSION_KEY)
+            .map(|(engine_version, _)| engine_version)
+            .unwrap_or(EngineVersion::V1)
+    }
+
+    /// Records the engine version transactions are executed with from now on.
+    fn set_engine_version(&mut self, engine_version: EngineVersion) {
+        let phys_id = (hash(ENGINE_VERSION_KEY), engine_version as u32);
+        self.put_encoded_substate(&ENGINE_VERSION_KEY, &engine_version, phys_id);
+    }
+
     // TODO: redefine what nonce is and how it's updated
     // For now, we bump nonce only when a transaction has been committed
     // or when an account is created (for testing).
@@ -229,4 +335,35 @@ pub trait SubstateStore {
         self.increase_nonce();
         nonce
     }
+
+    /// Returns whether a substate has been read which the store hasn't fetched yet.
+    ///
+    /// Only stores which are fetched ahead of execution, like `BufferedSubstateStore`, miss
+    /// reads. Such a read returns `None`, which must not be taken as the substate not existing.
+    fn has_unfetched_reads(&self) -> bool {
+        false
+    }
+
+    /// Returns whether this store keeps an index of non-fungible locations.
+    ///
+    /// The index is opt-in, as it's updated for every non-fungible in a committed vault.
+    fn is_non_fungible_index_enabled(&self) -> bool {
+        false
+    }
+
+    /// Returns the location of a non-fungible, if indexed.
+    fn get_non_fungible_location(
+        &self,
+        _non_fungible_address: &NonFungibleAddress,
+    ) -> Option<NonFungibleLocation> {
+        None
+    }
+
+    /// Updates the location of a non-fungible, or removes it if it no longer exists.
+    fn put_non_fungible_location(
+        &mut self,
+        _non_fungible_address: NonFungibleAddress,
+        _location: Option<NonFungibleLocation>,
+    ) {
+    }
 }
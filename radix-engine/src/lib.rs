@@ -5,13 +5,24 @@ compile_error!("Either feature `std` or `alloc` must be enabled for this crate."
 #[cfg(all(feature = "std", feature = "alloc"))]
 compile_error!("Feature `std` and `alloc` can't be enabled at the same time.");
 
+/// Terminal colors, which are left out without `std`.
+#[cfg(not(feature = "alloc"))]
+use ::colored;
+#[cfg(feature = "alloc")]
+mod colored;
+
 /// Radix Engine implementation.
 pub mod engine;
 /// Radix Engine errors.
 pub mod errors;
+/// Radix Engine fee accounting.
+pub mod fee;
 /// Radix ledger abstraction.
 pub mod ledger;
 /// Radix Engine transaction and state models.
 pub mod model;
 /// Transaction builder, validator and executor.
 pub mod transaction;
+/// Proptest strategies for Radix Engine models.
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
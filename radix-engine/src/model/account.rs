@@ -0,0 +1,461 @@
+use sbor::*;
+use scrypto::buffer::{scrypto_decode, scrypto_encode};
+use scrypto::component::LazyMap;
+use scrypto::core::{SNodeRef, ScryptoActor};
+use scrypto::engine::types::*;
+use scrypto::resource::{AccessRule, AccessRules};
+use scrypto::rule;
+use scrypto::rust::collections::*;
+use scrypto::rust::marker::PhantomData;
+use scrypto::rust::string::String;
+use scrypto::rust::string::ToString;
+use scrypto::rust::vec;
+use scrypto::rust::vec::Vec;
+use scrypto::values::ScryptoValue;
+
+use crate::engine::SystemApi;
+use crate::errors::RuntimeError;
+use crate::fee::CostUnitCounterError;
+use crate::model::Component;
+
+/// The maximum number of cost units an auth delegate can consume to authorize a call.
+pub const MAX_AUTH_DELEGATE_COST_UNITS: u32 = 500_000;
+
+/// The account methods authorized by the auth delegate of an account, if any.
+const AUTH_DELEGATE_METHODS: [&str; 6] = [
+    "withdraw",
+    "withdraw_by_amount",
+    "withdraw_by_ids",
+    "create_proof",
+    "create_proof_by_amount",
+    "create_proof_by_ids",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccountError {
+    InvalidRequestData(DecodeError),
+    MethodNotFound(String),
+    ResourceNotFound(ResourceAddress),
+    ResourceNotAllowed(ResourceAddress),
+    InvalidAuthDelegateOutput(DecodeError),
+    NotAuthorizedByDelegate(String),
+    AuthDelegateCostLimitExceeded,
+}
+
+/// A component method which authorizes the withdrawals and proofs of an account, in place of an
+/// access rule.
+///
+/// The method is called with the account address, the name of the account method and its
+/// encoded arguments, and returns whether the call is authorized.
+#[derive(Debug, Clone, PartialEq, Eq, TypeId, Encode, Decode)]
+pub struct AuthDelegate {
+    pub component_address: ComponentAddress,
+    pub method: String,
+}
+
+/// The state of an account component.
+///
+/// Vaults are kept in a lazy map, so that the state doesn't grow with the resources held.
+#[derive(Debug, TypeId, Encode, Decode)]
+struct AccountState {
+    vaults: LazyMap<ResourceAddress, scrypto::resource::Vault>,
+    auth_delegate: Option<AuthDelegate>,
+}
+
+/// The state of accounts created before their vaults were moved into a lazy map.
+#[derive(Debug, TypeId, Encode, Decode)]
+struct InlineAccountState {
+    vaults: BTreeMap<ResourceAddress, scrypto::resource::Vault>,
+    auth_delegate: Option<AuthDelegate>,
+}
+
+/// The state of accounts created before auth delegates.
+#[derive(Debug, TypeId, Encode, Decode)]
+struct LegacyAccountState {
+    vaults: BTreeMap<ResourceAddress, scrypto::resource::Vault>,
+}
+
+impl AccountState {
+    /// Decodes the state of an account, moving the vaults of older accounts into a lazy map.
+    fn decode<S: SystemApi>(
+        component_address: ComponentAddress,
+        state: &[u8],
+        system_api: &mut S,
+    ) -> Result<Self, RuntimeError> {
+        if let Ok(state) = scrypto_decode(state) {
+            return Ok(state);
+        }
+
+        let inline: InlineAccountState = scrypto_decode(state).unwrap_or_else(|_| {
+            let legacy: LegacyAccountState = scrypto_decode(state).unwrap();
+            InlineAccountState {
+                vaults: legacy.vaults,
+                auth_delegate: None,
+            }
+        });
+        let vaults = LazyMap {
+            id: system_api.create_lazy_map(),
+            key: PhantomData,
+            value: PhantomData,
+        };
+        for (resource_address, vault) in inline.vaults {
+            system_api.put_lazy_map_entry(
+                component_address,
+                vaults.id,
+                scrypto_encode(&resource_address),
+                scrypto_encode(&vault),
+            )?;
+        }
+        Ok(Self {
+            vaults,
+            auth_delegate: inline.auth_delegate,
+        })
+    }
+
+    /// Returns the vault of a resource, if the account holds one.
+    fn vault_id<S: SystemApi>(
+        &self,
+        component_address: ComponentAddress,
+        resource_address: ResourceAddress,
+        system_api: &mut S,
+    ) -> Option<VaultId> {
+        system_api
+            .get_lazy_map_entry(
+                component_address,
+                &self.vaults.id,
+                &scrypto_encode(&resource_address),
+            )
+            .map(|value| {
+                let vault: scrypto::resource::Vault = scrypto_decode(&value).unwrap();
+                vault.0
+            })
+    }
+}
+
+/// The account blueprint, executed natively by the engine.
+///
+/// Accounts are regular components of `ACCOUNT_PACKAGE`, which is kept on ledger for its ABI,
+/// but their functions and methods never run in WASM.
+pub struct Account;
+
+impl Account {
+    fn decode_arg<T: Decode>(args: &[ScryptoValue], index: usize) -> Result<T, RuntimeError> {
+        let arg = args.get(index).ok_or(RuntimeError::IndexOutOfBounds {
+            index,
+            max: args.len(),
+        })?;
+        scrypto_decode(&arg.raw)
+            .map_err(|e| RuntimeError::AccountError(AccountError::InvalidRequestData(e)))
+    }
+
+    fn instantiate<S: SystemApi>(
+        withdraw_rule: AccessRule,
+        auth_delegate: Option<AuthDelegate>,
+        system_api: &mut S,
+    ) -> ComponentAddress {
+        let access_rules = AccessRules::new()
+            .method("balance", rule!(allow_all))
+            .method("deposit", rule!(allow_all))
+            .method("deposit_batch", rule!(allow_all))
+            .method("deposit_batch_safe", rule!(allow_all))
+            .default(withdraw_rule);
+        let state = AccountState {
+            vaults: LazyMap {
+                id: system_api.create_lazy_map(),
+                key: PhantomData,
+                value: PhantomData,
+            },
+            auth_delegate,
+        };
+
+        system_api.create_component(Component::new(
+            ACCOUNT_PACKAGE,
+            "Account".to_string(),
+            vec![access_rules],
+            scrypto_encode(&state),
+        ))
+    }
+
+    fn deposit<S: SystemApi>(
+        component_address: ComponentAddress,
+        state: &AccountState,
+        bucket: scrypto::resource::Bucket,
+        system_api: &mut S,
+    ) -> Result<(), RuntimeError> {
+        let resource_address = Self::resource_address(&bucket, system_api)?;
+
+        let vault_id = match state.vault_id(component_address, resource_address, system_api) {
+            Some(vault_id) => vault_id,
+            None => {
+                let vault_id = system_api.create_vault(component_address, resource_address)?;
+                system_api.put_lazy_map_entry(
+                    component_address,
+                    state.vaults.id,
+                    scrypto_encode(&resource_address),
+                    scrypto_encode(&scrypto::resource::Vault(vault_id)),
+                )?;
+                vault_id
+            }
+        };
+
+        system_api.invoke_snode(
+            SNodeRef::VaultRef(vault_id),
+            "put_into_vault".to_string(),
+            vec![ScryptoValue::from_value(&bucket)],
+        )?;
+        Ok(())
+    }
+
+    fn resource_address<S: SystemApi>(
+        bucket: &scrypto::resource::Bucket,
+        system_api: &mut S,
+    ) -> Result<ResourceAddress, RuntimeError> {
+        let rtn = system_api.invoke_snode(
+            SNodeRef::BucketRef(bucket.0),
+            "get_bucket_resource_address".to_string(),
+            vec![],
+        )?;
+        Ok(scrypto_decode(&rtn.raw).unwrap())
+    }
+
+    /// Calls the auth delegate of an account, with a cost unit limit of
+    /// `MAX_AUTH_DELEGATE_COST_UNITS`.
+    fn authorize<S: SystemApi>(
+        component_address: ComponentAddress,
+        auth_delegate: &AuthDelegate,
+        function: &str,
+        args: &[ScryptoValue],
+        system_api: &mut S,
+    ) -> Result<(), RuntimeError> {
+        let limit = system_api.cost_unit_counter().limit();
+        let delegate_limit = system_api
+            .cost_unit_counter()
+            .consumed()
+            .saturating_add(MAX_AUTH_DELEGATE_COST_UNITS)
+            .min(limit);
+        system_api.set_cost_unit_limit(delegate_limit);
+        let result = system_api.invoke_snode(
+            SNodeRef::Scrypto(ScryptoActor::Component(auth_delegate.component_address)),
+            auth_delegate.method.clone(),
+            vec![
+                ScryptoValue::from_value(&component_address),
+                ScryptoValue::from_value(&function.to_string()),
+                ScryptoValue::from_value(
+                    &args.iter().map(|a| a.raw.clone()).collect::<Vec<Vec<u8>>>(),
+                ),
+            ],
+        );
+        system_api.set_cost_unit_limit(limit);
+
+        let rtn = match result {
+            Err(RuntimeError::CostingError(CostUnitCounterError::LimitExceeded {
+                limit: exceeded,
+                ..
+            })) if exceeded < limit => {
+                return Err(RuntimeError::AccountError(
+                    AccountError::AuthDelegateCostLimitExceeded,
+                ))
+            }
+            result => result?,
+        };
+        let authorized: bool = scrypto_decode(&rtn.raw)
+            .map_err(|e| RuntimeError::AccountError(AccountError::InvalidAuthDelegateOutput(e)))?;
+        if authorized {
+            Ok(())
+        } else {
+            Err(RuntimeError::AccountError(
+                AccountError::NotAuthorizedByDelegate(function.to_string()),
+            ))
+        }
+    }
+
+    fn vault_id<S: SystemApi>(
+        component_address: ComponentAddress,
+        state: &AccountState,
+        resource_address: ResourceAddress,
+        system_api: &mut S,
+    ) -> Result<VaultId, RuntimeError> {
+        state
+            .vault_id(component_address, resource_address, system_api)
+            .ok_or(RuntimeError::AccountError(AccountError::ResourceNotFound(
+                resource_address,
+            )))
+    }
+
+    pub fn static_main<S: SystemApi>(
+        function: &str,
+        args: Vec<ScryptoValue>,
+        system_api: &mut S,
+    ) -> Result<ScryptoValue, RuntimeError> {
+        match function {
+            "new" => {
+                let withdraw_rule: AccessRule = Self::decode_arg(&args, 0)?;
+                let component_address = Self::instantiate(withdraw_rule, None, system_api);
+                Ok(ScryptoValue::from_value(&component_address))
+            }
+            "new_with_resource" => {
+                let withdraw_rule: AccessRule = Self::decode_arg(&args, 0)?;
+                let bucket: scrypto::resource::Bucket = Self::decode_arg(&args, 1)?;
+                let component_address = Self::instantiate(withdraw_rule, None, system_api);
+                system_api.invoke_snode(
+                    SNodeRef::Scrypto(ScryptoActor::Component(component_address)),
+                    "deposit".to_string(),
+                    vec![ScryptoValue::from_value(&bucket)],
+                )?;
+                Ok(ScryptoValue::from_value(&component_address))
+            }
+            "new_with_auth_delegate" => {
+                let auth_delegate = AuthDelegate {
+                    component_address: Self::decode_arg(&args, 0)?,
+                    method: Self::decode_arg(&args, 1)?,
+                };
+                let component_address =
+                    Self::instantiate(rule!(allow_all), Some(auth_delegate), system_api);
+                Ok(ScryptoValue::from_value(&component_address))
+            }
+            _ => Err(RuntimeError::AccountError(AccountError::MethodNotFound(
+                function.to_string(),
+            ))),
+        }
+    }
+
+    pub fn main<S: SystemApi>(
+        component_address: ComponentAddress,
+        component: &mut Component,
+        function: &str,
+        args: Vec<ScryptoValue>,
+        system_api: &mut S,
+    ) -> Result<ScryptoValue, RuntimeError> {
+        let state = AccountState::decode(component_address, component.state(), system_api)?;
+
+        if let Some(auth_delegate) = &state.auth_delegate {
+            if AUTH_DELEGATE_METHODS.contains(&function) {
+                Self::authorize(
+                    component_address,
+                    auth_delegate,
+                    function,
+                    &args,
+                    system_api,
+                )?;
+            }
+        }
+
+        let rtn = match function {
+            "balance" => {
+                let resource_address: ResourceAddress = Self::decode_arg(&args, 0)?;
+                match state.vault_id(component_address, resource_address, system_api) {
+                    Some(vault_id) => system_api.invoke_snode(
+                        SNodeRef::VaultRef(vault_id),
+                        "get_vault_amount".to_string(),
+                        vec![],
+                    ),
+                    None => Ok(ScryptoValue::from_value(&Decimal::zero())),
+                }
+            }
+            "deposit" => {
+                let bucket: scrypto::resource::Bucket = Self::decode_arg(&args, 0)?;
+                Self::deposit(component_address, &state, bucket, system_api)?;
+                Ok(ScryptoValue::from_value(&()))
+            }
+            "deposit_batch" => {
+                let buckets: Vec<scrypto::resource::Bucket> = Self::decode_arg(&args, 0)?;
+                for bucket in buckets {
+                    Self::deposit(component_address, &state, bucket, system_api)?;
+                }
+                Ok(ScryptoValue::from_value(&()))
+            }
+            "deposit_batch_safe" => {
+                let buckets: Vec<scrypto::resource::Bucket> = Self::decode_arg(&args, 0)?;
+                let allowed_resources: BTreeSet<ResourceAddress> = Self::decode_arg(&args, 1)?;
+                // Checks all buckets before depositing any of them
+                for bucket in &buckets {
+                    let resource_address = Self::resource_address(bucket, system_api)?;
+                    if !allowed_resources.contains(&resource_address) {
+                        return Err(RuntimeError::AccountError(
+                            AccountError::ResourceNotAllowed(resource_address),
+                        ));
+                    }
+                }
+                for bucket in buckets {
+                    Self::deposit(component_address, &state, bucket, system_api)?;
+                }
+                Ok(ScryptoValue::from_value(&()))
+            }
+            "withdraw" => {
+                let resource_address: ResourceAddress = Self::decode_arg(&args, 0)?;
+                let vault_id =
+                    Self::vault_id(component_address, &state, resource_address, system_api)?;
+                let amount = system_api.invoke_snode(
+                    SNodeRef::VaultRef(vault_id),
+                    "get_vault_amount".to_string(),
+                    vec![],
+                )?;
+                system_api.invoke_snode(
+                    SNodeRef::VaultRef(vault_id),
+                    "take_from_vault".to_string(),
+                    vec![amount],
+                )
+            }
+            "withdraw_by_amount" => {
+                let amount: Decimal = Self::decode_arg(&args, 0)?;
+                let resource_address: ResourceAddress = Self::decode_arg(&args, 1)?;
+                let vault_id =
+                    Self::vault_id(component_address, &state, resource_address, system_api)?;
+                system_api.invoke_snode(
+                    SNodeRef::VaultRef(vault_id),
+                    "take_from_vault".to_string(),
+                    vec![ScryptoValue::from_value(&amount)],
+                )
+            }
+            "withdraw_by_ids" => {
+                let ids: BTreeSet<NonFungibleId> = Self::decode_arg(&args, 0)?;
+                let resource_address: ResourceAddress = Self::decode_arg(&args, 1)?;
+                let vault_id =
+                    Self::vault_id(component_address, &state, resource_address, system_api)?;
+                system_api.invoke_snode(
+                    SNodeRef::VaultRef(vault_id),
+                    "take_non_fungibles_from_vault".to_string(),
+                    vec![ScryptoValue::from_value(&ids)],
+                )
+            }
+            "create_proof" => {
+                let resource_address: ResourceAddress = Self::decode_arg(&args, 0)?;
+                let vault_id =
+                    Self::vault_id(component_address, &state, resource_address, system_api)?;
+                system_api.invoke_snode(
+                    SNodeRef::VaultRef(vault_id),
+                    "create_vault_proof".to_string(),
+                    vec![],
+                )
+            }
+            "create_proof_by_amount" => {
+                let amount: Decimal = Self::decode_arg(&args, 0)?;
+                let resource_address: ResourceAddress = Self::decode_arg(&args, 1)?;
+                let vault_id =
+                    Self::vault_id(component_address, &state, resource_address, system_api)?;
+                system_api.invoke_snode(
+                    SNodeRef::VaultRef(vault_id),
+                    "create_vault_proof_by_amount".to_string(),
+                    vec![ScryptoValue::from_value(&amount)],
+                )
+            }
+            "create_proof_by_ids" => {
+                let ids: BTreeSet<NonFungibleId> = Self::decode_arg(&args, 0)?;
+                let resource_address: ResourceAddress = Self::decode_arg(&args, 1)?;
+                let vault_id =
+                    Self::vault_id(component_address, &state, resource_address, system_api)?;
+                system_api.invoke_snode(
+                    SNodeRef::VaultRef(vault_id),
+                    "create_vault_proof_by_ids".to_string(),
+                    vec![ScryptoValue::from_value(&ids)],
+                )
+            }
+            _ => Err(RuntimeError::AccountError(AccountError::MethodNotFound(
+                function.to_string(),
+            ))),
+        }?;
+
+        component.set_state(scrypto_encode(&state));
+        Ok(rtn)
+    }
+}
@@ -0,0 +1,154 @@
+use sbor::*;
+use scrypto::buffer::scrypto_decode;
+use scrypto::engine::types::*;
+use scrypto::resource::AccessRule;
+use scrypto::rust::string::String;
+use scrypto::rust::string::ToString;
+use scrypto::rust::vec::Vec;
+use scrypto::values::ScryptoValue;
+
+use crate::engine::SystemApi;
+use crate::model::{convert, MethodAuthorization};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AllowanceError {
+    InvalidRequestData(DecodeError),
+    MethodNotFound(String),
+    InvalidAmount(Decimal),
+    AmountExceeded {
+        requested: Decimal,
+        remaining: Decimal,
+    },
+    Expired {
+        expiry_epoch: u64,
+        current_epoch: u64,
+    },
+    Revoked,
+    NotGrantedByVault(VaultId),
+    CouldNotTakeFromVault,
+    CouldNotCreateBucket,
+}
+
+/// The right to withdraw up to some amount of resource from a vault of a component, until an
+/// expiry epoch.
+///
+/// Allowances are granted by the component which owns the vault, and are redeemed by whoever
+/// satisfies their redeem rule. The resource stays in the vault until it is redeemed.
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct Allowance {
+    component_address: ComponentAddress,
+    vault_id: VaultId,
+    resource_address: ResourceAddress,
+    remaining_amount: Decimal,
+    expiry_epoch: u64,
+    redeem_rule: AccessRule,
+    revoked: bool,
+}
+
+impl Allowance {
+    pub fn new(
+        component_address: ComponentAddress,
+        vault_id: VaultId,
+        resource_address: ResourceAddress,
+        max_amount: Decimal,
+        expiry_epoch: u64,
+        redeem_rule: AccessRule,
+    ) -> Result<Self, AllowanceError> {
+        if max_amount.is_negative() {
+            return Err(AllowanceError::InvalidAmount(max_amount));
+        }
+        Ok(Self {
+            component_address,
+            vault_id,
+            resource_address,
+            remaining_amount: max_amount,
+            expiry_epoch,
+            redeem_rule,
+            revoked: false,
+        })
+    }
+
+    pub fn resource_address(&self) -> ResourceAddress {
+        self.resource_address
+    }
+
+    pub fn remaining_amount(&self) -> Decimal {
+        self.remaining_amount
+    }
+
+    pub fn expiry_epoch(&self) -> u64 {
+        self.expiry_epoch
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked
+    }
+
+    /// Revokes this allowance, on behalf of the vault which granted it.
+    pub fn revoke(&mut self, vault_id: VaultId) -> Result<(), AllowanceError> {
+        if self.vault_id != vault_id {
+            return Err(AllowanceError::NotGrantedByVault(vault_id));
+        }
+        self.revoked = true;
+        Ok(())
+    }
+
+    pub fn get_auth(&self, function: &str) -> MethodAuthorization {
+        match function {
+            "redeem_allowance" => convert(&Type::Unit, &Value::Unit, &self.redeem_rule),
+            _ => MethodAuthorization::AllowAll,
+        }
+    }
+
+    pub fn main<S: SystemApi>(
+        &mut self,
+        function: &str,
+        args: Vec<ScryptoValue>,
+        system_api: &mut S,
+    ) -> Result<ScryptoValue, AllowanceError> {
+        match function {
+            "redeem_allowance" => {
+                let amount: Decimal =
+                    scrypto_decode(&args[0].raw).map_err(AllowanceError::InvalidRequestData)?;
+                if amount.is_negative() {
+                    return Err(AllowanceError::InvalidAmount(amount));
+                }
+                if self.revoked {
+                    return Err(AllowanceError::Revoked);
+                }
+                let current_epoch = system_api.current_epoch();
+                if current_epoch > self.expiry_epoch {
+                    return Err(AllowanceError::Expired {
+                        expiry_epoch: self.expiry_epoch,
+                        current_epoch,
+                    });
+                }
+                if amount > self.remaining_amount {
+                    return Err(AllowanceError::AmountExceeded {
+                        requested: amount,
+                        remaining: self.remaining_amount,
+                    });
+                }
+
+                let container = system_api
+                    .take_from_component_vault(self.component_address, self.vault_id, amount)
+                    .map_err(|_| AllowanceError::CouldNotTakeFromVault)?;
+                self.remaining_amount -= amount;
+                let bucket_id = system_api
+                    .create_bucket(container)
+                    .map_err(|_| AllowanceError::CouldNotCreateBucket)?;
+                Ok(ScryptoValue::from_value(&scrypto::resource::Bucket(
+                    bucket_id,
+                )))
+            }
+            "get_allowance_remaining_amount" => {
+                Ok(ScryptoValue::from_value(&self.remaining_amount()))
+            }
+            "get_allowance_expiry_epoch" => Ok(ScryptoValue::from_value(&self.expiry_epoch())),
+            "get_allowance_resource_address" => {
+                Ok(ScryptoValue::from_value(&self.resource_address()))
+            }
+            _ => Err(AllowanceError::MethodNotFound(function.to_string())),
+        }
+    }
+}
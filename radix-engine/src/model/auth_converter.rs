@@ -235,5 +235,8 @@ pub fn convert(schema: &Type, dom: &Value, method_auth: &AccessRule) -> MethodAu
         }
         AccessRule::AllowAll => MethodAuthorization::AllowAll,
         AccessRule::DenyAll => MethodAuthorization::DenyAll,
+        // Slots are only allowed in component access rules and are resolved by the component
+        // beforehand, so an unresolved slot denies all
+        AccessRule::Slot(_) => MethodAuthorization::DenyAll,
     }
 }
@@ -1,4 +1,5 @@
 use sbor::DecodeError;
+use scrypto::constants::{ECDSA_TOKEN, SYSTEM_TOKEN};
 use scrypto::engine::types::*;
 use scrypto::prelude::scrypto_decode;
 use scrypto::rust::collections::BTreeSet;
@@ -62,11 +63,53 @@ impl AuthZone {
         }
     }
 
+    /// Drops the proofs of transaction signatures, keeping all other proofs.
+    fn clear_signature_proofs(&mut self) {
+        let (signature_proofs, proofs): (Vec<Proof>, Vec<Proof>) = self
+            .proofs
+            .drain(..)
+            .partition(|proof| proof.resource_address() == ECDSA_TOKEN);
+        self.proofs = proofs;
+        for proof in signature_proofs {
+            proof.drop();
+        }
+    }
+
+    /// Drops the proofs of the system badge, keeping all other proofs.
+    fn clear_system_proofs(&mut self) {
+        let (system_proofs, proofs): (Vec<Proof>, Vec<Proof>) = self
+            .proofs
+            .drain(..)
+            .partition(|proof| proof.resource_address() == SYSTEM_TOKEN);
+        self.proofs = proofs;
+        for proof in system_proofs {
+            proof.drop();
+        }
+    }
+
     fn create_proof(&self, resource_address: ResourceAddress, resource_type: ResourceType) -> Result<Proof, AuthZoneError> {
         Proof::compose(&self.proofs, resource_address, resource_type)
             .map_err(AuthZoneError::ProofError)
     }
 
+    /// Creates a proof of each of the given resources, dropping the proofs already created if
+    /// any of them can't be created.
+    fn create_proofs(&self, resources: Vec<(ResourceAddress, ResourceType)>) -> Result<Vec<Proof>, AuthZoneError> {
+        let mut proofs = Vec::new();
+        for (resource_address, resource_type) in resources {
+            match self.create_proof(resource_address, resource_type) {
+                Ok(proof) => proofs.push(proof),
+                Err(e) => {
+                    for proof in proofs {
+                        proof.drop();
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(proofs)
+    }
+
     fn create_proof_by_amount(&self, amount:Decimal, resource_address: ResourceAddress, resource_type: ResourceType) -> Result<Proof, AuthZoneError> {
         Proof::compose_by_amount(&self.proofs, amount, resource_address, resource_type)
             .map_err(AuthZoneError::ProofError)
@@ -89,6 +132,14 @@ impl AuthZone {
                 self.clear();
                 Ok(ScryptoValue::from_value(&()))
             }
+            "clear_signature_proofs" => {
+                self.clear_signature_proofs();
+                Ok(ScryptoValue::from_value(&()))
+            }
+            "clear_system_proofs" => {
+                self.clear_system_proofs();
+                Ok(ScryptoValue::from_value(&()))
+            }
             "pop" => {
                 let proof = self.pop()?;
                 let proof_id = system_api.create_proof(proof).map_err(|_| AuthZoneError::CouldNotCreateProof)?;
@@ -114,6 +165,35 @@ impl AuthZone {
                 let proof_id = system_api.create_proof(proof).map_err(|_| AuthZoneError::CouldNotCreateProof)?;
                 Ok(ScryptoValue::from_value(&scrypto::resource::Proof(proof_id)))
             }
+            "create_proofs" => {
+                let resource_addresses: Vec<ResourceAddress> = scrypto_decode(&args[0].raw).map_err(|e| AuthZoneError::InvalidRequestData(e))?;
+                let mut resources = Vec::new();
+                for resource_address in resource_addresses {
+                    let resource_manager: ResourceManager = system_api.borrow_global_mut_resource_manager(resource_address).map_err(|_| AuthZoneError::CouldNotGetResource)?;
+                    resources.push((resource_address, resource_manager.resource_type()));
+                    system_api.return_borrowed_global_resource_manager(resource_address, resource_manager);
+                }
+                let mut proof_ids = Vec::new();
+                let mut proofs = self.create_proofs(resources)?.into_iter();
+                while let Some(proof) = proofs.next() {
+                    match system_api.create_proof(proof) {
+                        Ok(proof_id) => proof_ids.push(scrypto::resource::Proof(proof_id)),
+                        Err(_) => {
+                            // Release the locks of every proof, whether registered or not
+                            for proof in proofs {
+                                proof.drop();
+                            }
+                            for proof_id in proof_ids {
+                                if let Ok(proof) = system_api.take_proof(proof_id.0) {
+                                    proof.drop();
+                                }
+                            }
+                            return Err(AuthZoneError::CouldNotCreateProof);
+                        }
+                    }
+                }
+                Ok(ScryptoValue::from_value(&proof_ids))
+            }
             "create_proof_by_amount" => {
                 let amount = scrypto_decode(&args[0].raw).map_err(|e| AuthZoneError::InvalidRequestData(e))?;
                 let resource_address = scrypto_decode(&args[1].raw).map_err(|e| AuthZoneError::InvalidRequestData(e))?;
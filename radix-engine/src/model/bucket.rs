@@ -73,21 +73,19 @@ impl Bucket {
         container_id: ResourceContainerId,
     ) -> Result<Proof, ProofError> {
         // lock the specified amount
-        let locked_amount_or_ids = self
+        let lock = self
             .borrow_container_mut()
             .lock_by_amount(amount)
             .map_err(ProofError::ResourceContainerError)?;
 
         // produce proof
+        let total_locked = lock.locked().clone();
         let mut evidence = HashMap::new();
-        evidence.insert(
-            container_id,
-            (self.container.clone(), locked_amount_or_ids.clone()),
-        );
+        evidence.insert(container_id, (self.container.clone(), lock));
         Proof::new(
             self.resource_address(),
             self.resource_type(),
-            locked_amount_or_ids,
+            total_locked,
             evidence,
         )
     }
@@ -98,21 +96,19 @@ impl Bucket {
         container_id: ResourceContainerId,
     ) -> Result<Proof, ProofError> {
         // lock the specified id set
-        let locked_amount_or_ids = self
+        let lock = self
             .borrow_container_mut()
             .lock_by_ids(ids)
             .map_err(ProofError::ResourceContainerError)?;
 
         // produce proof
+        let total_locked = lock.locked().clone();
         let mut evidence = HashMap::new();
-        evidence.insert(
-            container_id,
-            (self.container.clone(), locked_amount_or_ids.clone()),
-        );
+        evidence.insert(container_id, (self.container.clone(), lock));
         Proof::new(
             self.resource_address(),
             self.resource_type(),
-            locked_amount_or_ids,
+            total_locked,
             evidence,
         )
     }
@@ -125,14 +121,22 @@ impl Bucket {
         self.borrow_container().resource_type()
     }
 
-    fn total_amount(&self) -> Decimal {
+    pub fn total_amount(&self) -> Decimal {
         self.borrow_container().total_amount()
     }
 
-    fn total_ids(&self) -> Result<BTreeSet<NonFungibleId>, ResourceContainerError> {
+    pub fn total_ids(&self) -> Result<BTreeSet<NonFungibleId>, ResourceContainerError> {
         self.borrow_container().total_ids()
     }
 
+    fn locked_amount(&self) -> Decimal {
+        self.borrow_container().max_locked_amount()
+    }
+
+    fn liquid_amount(&self) -> Decimal {
+        self.borrow_container().liquid_amount()
+    }
+
     pub fn is_locked(&self) -> bool {
         self.borrow_container().is_locked()
     }
@@ -207,6 +211,9 @@ impl Bucket {
             }
             "get_bucket_amount" => Ok(ScryptoValue::from_value(&self.total_amount())),
             "get_bucket_resource_address" => Ok(ScryptoValue::from_value(&self.resource_address())),
+            "get_bucket_locked_amount" => Ok(ScryptoValue::from_value(&self.locked_amount())),
+            "get_bucket_liquid_amount" => Ok(ScryptoValue::from_value(&self.liquid_amount())),
+            "is_bucket_locked" => Ok(ScryptoValue::from_value(&self.is_locked())),
             "create_bucket_proof" => {
                 let proof = self
                     .create_proof(bucket_id)
@@ -1,19 +1,79 @@
 use sbor::*;
+use scrypto::buffer::scrypto_decode;
 use scrypto::engine::types::*;
-use scrypto::resource::AccessRules;
+use scrypto::resource::{AccessRule, AccessRules, Mutability};
+use scrypto::rust::borrow::ToOwned;
+use scrypto::rust::collections::{BTreeMap, BTreeSet};
 use scrypto::rust::string::String;
+use scrypto::rust::string::ToString;
 use scrypto::rust::vec::Vec;
 use scrypto::values::*;
 
+use crate::engine::SystemApi;
+use crate::errors::RuntimeError;
 use crate::model::{convert, MethodAuthorization};
 
+/// Represents an error when invoking an engine-level method of a component.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComponentError {
+    InvalidRequestData(DecodeError),
+    MethodNotFound(String),
+}
+
 /// A component is an instance of blueprint.
-#[derive(Debug, TypeId, Encode, Decode)]
+#[derive(Debug, TypeId, Encode)]
 pub struct Component {
     package_address: PackageAddress,
     blueprint_name: String,
     auths: Vec<AccessRules>,
+    /// The rule slots which access rules can refer to, with the rule to update each slot.
+    access_rule_slots: BTreeMap<String, (AccessRule, Mutability)>,
+    /// The methods which have been disabled, and can't be called until enabled again.
+    disabled_methods: BTreeSet<String>,
+    /// The rule to enable or disable methods.
+    method_toggle_auth: AccessRule,
+    /// The feature flags the component was instantiated with.
+    features: BTreeSet<String>,
     state: Vec<u8>,
+    readable_lazy_map_ids: Vec<LazyMapId>,
+    /// The version of the package the state was written by.
+    package_version: u32,
+}
+
+/// The number of encoded fields of a component.
+const COMPONENT_FIELDS: usize = 10;
+
+/// The number of encoded fields of a component instantiated before rule slots, method toggles,
+/// feature flags, readable lazy maps and state versions were added.
+const LEGACY_COMPONENT_FIELDS: usize = 4;
+
+impl Decode for Component {
+    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+        match decoder.read_len()? {
+            COMPONENT_FIELDS => Ok(Self {
+                package_address: PackageAddress::decode(decoder)?,
+                blueprint_name: String::decode(decoder)?,
+                auths: Vec::<AccessRules>::decode(decoder)?,
+                access_rule_slots: BTreeMap::<String, (AccessRule, Mutability)>::decode(decoder)?,
+                disabled_methods: BTreeSet::<String>::decode(decoder)?,
+                method_toggle_auth: AccessRule::decode(decoder)?,
+                features: BTreeSet::<String>::decode(decoder)?,
+                state: Vec::<u8>::decode(decoder)?,
+                readable_lazy_map_ids: Vec::<LazyMapId>::decode(decoder)?,
+                package_version: u32::decode(decoder)?,
+            }),
+            LEGACY_COMPONENT_FIELDS => Ok(Self::new(
+                PackageAddress::decode(decoder)?,
+                String::decode(decoder)?,
+                Vec::<AccessRules>::decode(decoder)?,
+                Vec::<u8>::decode(decoder)?,
+            )),
+            actual => Err(DecodeError::InvalidLength {
+                expected: COMPONENT_FIELDS,
+                actual,
+            }),
+        }
+    }
 }
 
 impl Component {
@@ -27,7 +87,13 @@ impl Component {
             package_address,
             blueprint_name,
             auths: method_auth,
+            access_rule_slots: BTreeMap::new(),
+            disabled_methods: BTreeSet::new(),
+            method_toggle_auth: AccessRule::DenyAll,
+            features: BTreeSet::new(),
             state,
+            readable_lazy_map_ids: Vec::new(),
+            package_version: 0,
         }
     }
 
@@ -40,7 +106,7 @@ impl Component {
 
         let mut authorizations = Vec::new();
         for auth in &self.auths {
-            let method_auth = auth.get(method_name);
+            let method_auth = self.resolve_access_rule(auth.get(method_name));
             let authorization = convert(schema, &data.dom, method_auth);
             authorizations.push(authorization);
         }
@@ -52,6 +118,106 @@ impl Component {
         &self.auths
     }
 
+    /// Resolves a rule which refers to a rule slot into the rule of the slot.
+    ///
+    /// Rules referring to missing slots are converted to deny all.
+    fn resolve_access_rule<'a>(&'a self, rule: &'a AccessRule) -> &'a AccessRule {
+        match rule {
+            AccessRule::Slot(name) => match self.access_rule_slots.get(name) {
+                Some((slot_rule, _)) => slot_rule,
+                None => &AccessRule::DenyAll,
+            },
+            _ => rule,
+        }
+    }
+
+    pub fn access_rule_slots(&self) -> &BTreeMap<String, (AccessRule, Mutability)> {
+        &self.access_rule_slots
+    }
+
+    pub fn set_access_rule_slots(
+        &mut self,
+        access_rule_slots: BTreeMap<String, (AccessRule, Mutability)>,
+    ) {
+        self.access_rule_slots = access_rule_slots;
+    }
+
+    /// Returns the rule which must be satisfied to update the given rule slot.
+    pub fn access_rule_slot_update_auth(&self, name: &str) -> Option<&AccessRule> {
+        self.access_rule_slots
+            .get(name)
+            .map(|(_, mutability)| match mutability {
+                Mutability::LOCKED => &AccessRule::DenyAll,
+                Mutability::MUTABLE(rule) => rule,
+            })
+    }
+
+    /// Updates the rule of the given rule slot, returning whether the slot exists.
+    pub fn set_access_rule(&mut self, name: &str, rule: AccessRule) -> bool {
+        match self.access_rule_slots.get_mut(name) {
+            Some((slot_rule, _)) => {
+                *slot_rule = rule;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the rule to enable or disable methods.
+    pub fn method_toggle_auth(&self) -> &AccessRule {
+        &self.method_toggle_auth
+    }
+
+    pub fn set_method_toggle_auth(&mut self, method_toggle_auth: AccessRule) {
+        self.method_toggle_auth = method_toggle_auth;
+    }
+
+    /// Returns the authorization of an engine-level method of this component.
+    pub fn get_auth(&self, function: &str) -> MethodAuthorization {
+        match function {
+            "set_method_enabled" => convert(&Type::Unit, &Value::Unit, &self.method_toggle_auth),
+            _ => MethodAuthorization::AllowAll,
+        }
+    }
+
+    /// Checks that the blueprint declares the given method.
+    pub fn check_method_declared<S: SystemApi>(
+        system_api: &mut S,
+        package_address: PackageAddress,
+        blueprint_name: &str,
+        method_name: &str,
+    ) -> Result<(), RuntimeError> {
+        let (_, _, methods, ..) = system_api.blueprint_abi(package_address, blueprint_name)?;
+        if methods.iter().any(|method| method.name == method_name) {
+            Ok(())
+        } else {
+            Err(RuntimeError::MethodNotDeclared {
+                blueprint_name: blueprint_name.to_owned(),
+                method: method_name.to_owned(),
+            })
+        }
+    }
+
+    pub fn is_method_enabled(&self, method_name: &str) -> bool {
+        !self.disabled_methods.contains(method_name)
+    }
+
+    pub fn set_method_enabled(&mut self, method_name: &str, enabled: bool) {
+        if enabled {
+            self.disabled_methods.remove(method_name);
+        } else {
+            self.disabled_methods.insert(method_name.to_owned());
+        }
+    }
+
+    pub fn is_feature_enabled(&self, feature: &str) -> bool {
+        self.features.contains(feature)
+    }
+
+    pub fn set_features(&mut self, features: BTreeSet<String>) {
+        self.features = features;
+    }
+
     pub fn package_address(&self) -> PackageAddress {
         self.package_address.clone()
     }
@@ -67,4 +233,115 @@ impl Component {
     pub fn set_state(&mut self, new_state: Vec<u8>) {
         self.state = new_state;
     }
+
+    /// Returns the version of the package the state was written by.
+    pub fn package_version(&self) -> u32 {
+        self.package_version
+    }
+
+    pub fn set_package_version(&mut self, package_version: u32) {
+        self.package_version = package_version;
+    }
+
+    /// Whether the entries of the given lazy map can be read by other components.
+    pub fn is_lazy_map_readable(&self, lazy_map_id: &LazyMapId) -> bool {
+        self.readable_lazy_map_ids.contains(lazy_map_id)
+    }
+
+    /// Allows other components to read the entries of the given lazy maps.
+    pub fn add_readable_lazy_maps(&mut self, lazy_map_ids: Vec<LazyMapId>) {
+        for lazy_map_id in lazy_map_ids {
+            if !self.readable_lazy_map_ids.contains(&lazy_map_id) {
+                self.readable_lazy_map_ids.push(lazy_map_id);
+            }
+        }
+    }
+
+    fn decode_arg<T: Decode>(args: &[ScryptoValue], index: usize) -> Result<T, RuntimeError> {
+        let arg = args.get(index).ok_or(RuntimeError::IndexOutOfBounds {
+            index,
+            max: args.len(),
+        })?;
+        scrypto_decode(&arg.raw)
+            .map_err(|e| RuntimeError::ComponentError(ComponentError::InvalidRequestData(e)))
+    }
+
+    /// Runs an engine-level method of this component, which doesn't call into the blueprint.
+    pub fn main<S: SystemApi>(
+        &mut self,
+        function: &str,
+        args: Vec<ScryptoValue>,
+        system_api: &mut S,
+    ) -> Result<ScryptoValue, RuntimeError> {
+        match function {
+            "set_method_enabled" => {
+                let method_name: String = Self::decode_arg(&args, 0)?;
+                let enabled: bool = Self::decode_arg(&args, 1)?;
+                Self::check_method_declared(
+                    system_api,
+                    self.package_address,
+                    &self.blueprint_name,
+                    &method_name,
+                )?;
+                self.set_method_enabled(&method_name, enabled);
+                Ok(ScryptoValue::from_value(&()))
+            }
+            _ => Err(RuntimeError::ComponentError(
+                ComponentError::MethodNotFound(function.to_string()),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scrypto::buffer::{scrypto_decode, scrypto_encode};
+    use scrypto::constants::SYSTEM_PACKAGE;
+    use scrypto::{access_rule_node, rule};
+
+    #[derive(TypeId, Encode)]
+    struct LegacyComponent {
+        package_address: PackageAddress,
+        blueprint_name: String,
+        auths: Vec<AccessRules>,
+        state: Vec<u8>,
+    }
+
+    #[test]
+    fn should_decode_legacy_component() {
+        let bytes = scrypto_encode(&LegacyComponent {
+            package_address: SYSTEM_PACKAGE,
+            blueprint_name: "Hello".to_owned(),
+            auths: vec![AccessRules::new().default(rule!(allow_all))],
+            state: vec![1, 2, 3],
+        });
+
+        let component: Component = scrypto_decode(&bytes).unwrap();
+
+        assert_eq!(component.package_address(), SYSTEM_PACKAGE);
+        assert_eq!(component.blueprint_name(), "Hello");
+        assert_eq!(component.authorization().len(), 1);
+        assert_eq!(component.state(), &[1, 2, 3]);
+        assert_eq!(component.method_toggle_auth(), &AccessRule::DenyAll);
+        assert!(component.is_method_enabled("hello"));
+    }
+
+    #[test]
+    fn should_keep_component_fields_on_decode() {
+        let mut component = Component::new(
+            SYSTEM_PACKAGE,
+            "Hello".to_owned(),
+            Vec::new(),
+            vec![1, 2, 3],
+        );
+        component.set_method_enabled("hello", false);
+        component.set_package_version(2);
+        let bytes = scrypto_encode(&component);
+
+        let decoded: Component = scrypto_decode(&bytes).unwrap();
+
+        assert!(!decoded.is_method_enabled("hello"));
+        assert_eq!(scrypto_encode(&decoded), bytes);
+    }
 }
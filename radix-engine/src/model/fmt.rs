@@ -0,0 +1,218 @@
+//! Human-readable formatting of resource containers, for traces and the simulator.
+//!
+//! Resources are shown by their symbol when the caller can resolve it, e.g. from the
+//! resource manager metadata, and by their address otherwise.
+
+use scrypto::engine::types::*;
+use scrypto::rust::collections::BTreeSet;
+use scrypto::rust::format;
+use scrypto::rust::string::String;
+use scrypto::rust::string::ToString;
+use scrypto::rust::vec::Vec;
+
+use crate::model::{Bucket, Proof, ResourceContainerId, Vault, Worktop};
+
+/// Formats an amount of a resource, followed by the non-fungible ids if any, e.g.
+/// `100 XRD` or `2 GOLD [#0a, #0b]`.
+pub fn format_resources<F>(
+    resource_address: ResourceAddress,
+    amount: Decimal,
+    ids: Option<&BTreeSet<NonFungibleId>>,
+    symbols: &F,
+) -> String
+where
+    F: Fn(ResourceAddress) -> Option<String>,
+{
+    let name = symbols(resource_address).unwrap_or_else(|| resource_address.to_string());
+    match ids {
+        Some(ids) if !ids.is_empty() => format!(
+            "{} {} [{}]",
+            amount,
+            name,
+            ids.iter()
+                .map(|id| format!("#{}", id))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        _ => format!("{} {}", amount, name),
+    }
+}
+
+/// Formats a bucket, e.g. `Bucket { 100 XRD }`.
+pub fn format_bucket<F>(bucket: &Bucket, symbols: &F) -> String
+where
+    F: Fn(ResourceAddress) -> Option<String>,
+{
+    let ids = bucket.total_ids().ok();
+    format!(
+        "Bucket {{ {}{} }}",
+        format_resources(
+            bucket.resource_address(),
+            bucket.total_amount(),
+            ids.as_ref(),
+            symbols
+        ),
+        if bucket.is_locked() { ", locked" } else { "" }
+    )
+}
+
+/// Formats a vault, e.g. `Vault { 100 XRD }`.
+pub fn format_vault<F>(vault: &Vault, symbols: &F) -> String
+where
+    F: Fn(ResourceAddress) -> Option<String>,
+{
+    let ids = vault.total_ids().ok();
+    format!(
+        "Vault {{ {}{} }}",
+        format_resources(
+            vault.resource_address(),
+            vault.total_amount(),
+            ids.as_ref(),
+            symbols
+        ),
+        if vault.is_locked() { ", locked" } else { "" }
+    )
+}
+
+/// Formats a proof and the containers backing it, e.g.
+/// `Proof { 5 XRD, from: bucket 1 (3), vault 1f2e..:0 (2) }`.
+pub fn format_proof<F>(proof: &Proof, symbols: &F) -> String
+where
+    F: Fn(ResourceAddress) -> Option<String>,
+{
+    let ids = proof.total_ids().ok();
+    let mut sources: Vec<String> = proof
+        .provenance()
+        .into_iter()
+        .map(|(container_id, locked)| {
+            format!(
+                "{} ({})",
+                format_container_id(&container_id),
+                locked.amount()
+            )
+        })
+        .collect();
+    sources.sort();
+    format!(
+        "Proof {{ {}{}, from: {} }}",
+        format_resources(
+            proof.resource_address(),
+            proof.total_amount(),
+            ids.as_ref(),
+            symbols
+        ),
+        if proof.is_restricted() {
+            ", restricted"
+        } else {
+            ""
+        },
+        sources.join(", ")
+    )
+}
+
+/// Formats the content of a worktop, e.g. `Worktop { 100 XRD, 2 GOLD [#0a, #0b] }`.
+pub fn format_worktop<F>(worktop: &Worktop, symbols: &F) -> String
+where
+    F: Fn(ResourceAddress) -> Option<String>,
+{
+    let resources: Vec<String> = worktop
+        .resource_addresses()
+        .into_iter()
+        .filter(|resource_address| !worktop.total_amount(*resource_address).is_zero())
+        .map(|resource_address| {
+            let ids = worktop.total_ids(resource_address).ok();
+            format_resources(
+                resource_address,
+                worktop.total_amount(resource_address),
+                ids.as_ref(),
+                symbols,
+            )
+        })
+        .collect();
+    if resources.is_empty() {
+        "Worktop { empty }".to_string()
+    } else {
+        format!("Worktop {{ {} }}", resources.join(", "))
+    }
+}
+
+fn format_container_id(container_id: &ResourceContainerId) -> String {
+    match container_id {
+        ResourceContainerId::Bucket(bucket_id) => format!("bucket {}", bucket_id),
+        ResourceContainerId::Vault((hash, index)) => format!("vault {}:{}", hash, index),
+        ResourceContainerId::Worktop(_, _) => "worktop".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ResourceContainer;
+
+    fn symbols(resource_address: ResourceAddress) -> Option<String> {
+        if resource_address == RADIX_TOKEN {
+            Some("XRD".to_string())
+        } else {
+            None
+        }
+    }
+
+    #[test]
+    fn test_format_bucket() {
+        let bucket = Bucket::new(ResourceContainer::new_fungible(RADIX_TOKEN, 18, 100.into()));
+        assert_eq!(format_bucket(&bucket, &symbols), "Bucket { 100 XRD }");
+    }
+
+    #[test]
+    fn test_format_non_fungible_bucket() {
+        let ids = [1u32, 2]
+            .iter()
+            .cloned()
+            .map(NonFungibleId::from_u32)
+            .collect::<BTreeSet<NonFungibleId>>();
+        let bucket = Bucket::new(ResourceContainer::new_non_fungible(
+            ECDSA_TOKEN,
+            ids.clone(),
+        ));
+        let formatted_ids = ids
+            .iter()
+            .map(|id| format!("#{}", id))
+            .collect::<Vec<String>>()
+            .join(", ");
+        assert_eq!(
+            format_bucket(&bucket, &symbols),
+            format!("Bucket {{ 2 {} [{}] }}", ECDSA_TOKEN, formatted_ids)
+        );
+    }
+
+    #[test]
+    fn test_format_proof() {
+        let mut bucket = Bucket::new(ResourceContainer::new_fungible(RADIX_TOKEN, 18, 100.into()));
+        let proof = bucket
+            .create_proof_by_amount(5.into(), ResourceContainerId::Bucket(1))
+            .unwrap();
+        assert_eq!(
+            format_proof(&proof, &symbols),
+            "Proof { 5 XRD, from: bucket 1 (5) }"
+        );
+        assert_eq!(
+            format_bucket(&bucket, &symbols),
+            "Bucket { 100 XRD, locked }"
+        );
+        proof.drop();
+    }
+
+    #[test]
+    fn test_format_worktop() {
+        let mut worktop = Worktop::new();
+        assert_eq!(format_worktop(&worktop, &symbols), "Worktop { empty }");
+        worktop
+            .put(Bucket::new(ResourceContainer::new_fungible(
+                RADIX_TOKEN,
+                18,
+                100.into(),
+            )))
+            .unwrap();
+        assert_eq!(format_worktop(&worktop, &symbols), "Worktop { 100 XRD }");
+    }
+}
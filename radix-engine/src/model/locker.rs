@@ -0,0 +1,129 @@
+use sbor::*;
+use scrypto::buffer::scrypto_decode;
+use scrypto::engine::types::*;
+use scrypto::resource::AccessRule;
+use scrypto::rust::collections::BTreeSet;
+use scrypto::rust::string::String;
+use scrypto::rust::string::ToString;
+use scrypto::rust::vec::Vec;
+use scrypto::values::ScryptoValue;
+
+use crate::engine::SystemApi;
+use crate::model::{convert, MethodAuthorization, ResourceContainer, ResourceContainerError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LockerError {
+    InvalidRequestData(DecodeError),
+    ResourceContainerError(ResourceContainerError),
+    MethodNotFound(String),
+    CouldNotCreateBucket,
+    CouldNotTakeBucket,
+    ComponentNotFound(ComponentAddress),
+    AccessRuleSlotNotAllowed,
+}
+
+/// The key of a locker among the child substates of the component which owns it.
+///
+/// The key is distinct from vault and lazy map keys, so that lockers are never mistaken for them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TypeId, Encode, Decode)]
+pub struct LockerKey(pub LockerId);
+
+/// A persistent resource container, which holds resources until they are claimed by whoever
+/// satisfies its claim rule.
+///
+/// Lockers are created from a bucket and owned by a component, so that resources can be
+/// escrowed for a party which can't accept them directly. A locker is deleted once it's emptied.
+/// Unless the component creates it itself, creating a locker requires the component's rule for
+/// `create_locker`, so that nobody else can fill up a component with lockers.
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct Locker {
+    container: ResourceContainer,
+    claim_rule: AccessRule,
+}
+
+impl Locker {
+    pub fn new(container: ResourceContainer, claim_rule: AccessRule) -> Self {
+        Self {
+            container,
+            claim_rule,
+        }
+    }
+
+    pub fn resource_address(&self) -> ResourceAddress {
+        self.container.resource_address()
+    }
+
+    pub fn total_amount(&self) -> Decimal {
+        self.container.total_amount()
+    }
+
+    pub fn total_ids(&self) -> Result<BTreeSet<NonFungibleId>, ResourceContainerError> {
+        self.container.total_ids()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.container.is_empty()
+    }
+
+    pub fn get_auth(&self, function: &str) -> MethodAuthorization {
+        match function {
+            "claim" => convert(&Type::Unit, &Value::Unit, &self.claim_rule),
+            _ => MethodAuthorization::AllowAll,
+        }
+    }
+
+    pub fn static_main<S: SystemApi>(
+        function: &str,
+        args: Vec<ScryptoValue>,
+        system_api: &mut S,
+    ) -> Result<ScryptoValue, LockerError> {
+        match function {
+            "create" => {
+                let component_address: ComponentAddress =
+                    scrypto_decode(&args[0].raw).map_err(LockerError::InvalidRequestData)?;
+                let bucket: scrypto::resource::Bucket =
+                    scrypto_decode(&args[1].raw).map_err(LockerError::InvalidRequestData)?;
+                let claim_rule: AccessRule =
+                    scrypto_decode(&args[2].raw).map_err(LockerError::InvalidRequestData)?;
+                if claim_rule.is_slot() {
+                    return Err(LockerError::AccessRuleSlotNotAllowed);
+                }
+                let container = system_api
+                    .take_bucket(bucket.0)
+                    .map_err(|_| LockerError::CouldNotTakeBucket)?
+                    .into_container()
+                    .map_err(LockerError::ResourceContainerError)?;
+                let locker_id = system_api
+                    .create_locker(component_address, Locker::new(container, claim_rule))
+                    .map_err(|_| LockerError::ComponentNotFound(component_address))?;
+                Ok(ScryptoValue::from_value(&locker_id))
+            }
+            _ => Err(LockerError::MethodNotFound(function.to_string())),
+        }
+    }
+
+    pub fn main<S: SystemApi>(
+        &mut self,
+        function: &str,
+        _args: Vec<ScryptoValue>,
+        system_api: &mut S,
+    ) -> Result<ScryptoValue, LockerError> {
+        match function {
+            "claim" => {
+                let container = self
+                    .container
+                    .take_all_liquid()
+                    .map_err(LockerError::ResourceContainerError)?;
+                let bucket_id = system_api
+                    .create_bucket(container)
+                    .map_err(|_| LockerError::CouldNotCreateBucket)?;
+                Ok(ScryptoValue::from_value(&scrypto::resource::Bucket(
+                    bucket_id,
+                )))
+            }
+            "get_locker_amount" => Ok(ScryptoValue::from_value(&self.total_amount())),
+            "get_locker_resource_address" => Ok(ScryptoValue::from_value(&self.resource_address())),
+            _ => Err(LockerError::MethodNotFound(function.to_string())),
+        }
+    }
+}
@@ -1,11 +1,17 @@
 use sbor::*;
 use scrypto::engine::types::*;
+use scrypto::rust::collections::BTreeSet;
+use scrypto::rust::fmt;
+use scrypto::rust::string::String;
+use scrypto::rust::string::ToString;
+use scrypto::rust::vec;
 use scrypto::rust::vec::Vec;
 
 use crate::model::method_authorization::MethodAuthorizationError::NotAuthorized;
 use crate::model::{AuthZone, Proof};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, TypeId, Encode, Decode)]
+#[sbor(indexed)]
 pub enum MethodAuthorizationError {
     NotAuthorized,
     UnsupportedMethod,
@@ -51,11 +57,12 @@ impl HardResourceOrNonFungible {
 
     pub fn check_has_amount(&self, amount: Decimal, auth_zones: &[&AuthZone]) -> bool {
         for auth_zone in auth_zones {
-            // FIXME: Need to check the composite max amount rather than just each proof individually
-            if auth_zone.proofs
+            let proofs: Vec<&Proof> = auth_zone
+                .proofs
                 .iter()
-                .any(|p| self.proof_matches(p) && p.total_amount() >= amount)
-            {
+                .filter(|p| self.proof_matches(p))
+                .collect();
+            if !proofs.is_empty() && Proof::compute_composite_amount(&proofs) >= amount {
                 return true;
             }
         }
@@ -72,6 +79,37 @@ impl HardResourceOrNonFungible {
 
         false
     }
+
+    /// Returns the largest amount proven by a single auth zone.
+    fn present_amount(&self, auth_zones: &[&AuthZone]) -> Decimal {
+        let mut present = Decimal::zero();
+        for auth_zone in auth_zones {
+            let proofs: Vec<&Proof> = auth_zone
+                .proofs
+                .iter()
+                .filter(|p| self.proof_matches(p))
+                .collect();
+            if !proofs.is_empty() {
+                let amount = Proof::compute_composite_amount(&proofs);
+                if amount > present {
+                    present = amount;
+                }
+            }
+        }
+        present
+    }
+
+    fn missing(&self) -> MissingProof {
+        match self {
+            HardResourceOrNonFungible::NonFungible(non_fungible_address) => {
+                MissingProof::NonFungible(non_fungible_address.clone())
+            }
+            HardResourceOrNonFungible::Resource(resource_address) => {
+                MissingProof::Resource(*resource_address)
+            }
+            HardResourceOrNonFungible::SoftResourceNotFound => MissingProof::Unresolved,
+        }
+    }
 }
 
 impl From<NonFungibleAddress> for HardResourceOrNonFungible {
@@ -154,6 +192,28 @@ impl HardProofRule {
             _ => Err(NotAuthorized),
         }
     }
+
+    /// Returns the proofs missing for this rule to pass.
+    fn explain(&self, auth_zones: &[&AuthZone]) -> Vec<MissingProof> {
+        match self {
+            HardProofRule::This(resource) => vec![resource.missing()],
+            HardProofRule::SomeOfResource(HardDecimal::Amount(amount), resource) => {
+                vec![MissingProof::Amount {
+                    resource: resource.clone(),
+                    required: *amount,
+                    present: resource.present_amount(auth_zones),
+                }]
+            }
+            HardProofRule::AllOf(HardProofRuleResourceList::List(resources))
+            | HardProofRule::AnyOf(HardProofRuleResourceList::List(resources))
+            | HardProofRule::CountOf(_, HardProofRuleResourceList::List(resources)) => resources
+                .iter()
+                .filter(|resource| !resource.check(auth_zones))
+                .map(HardResourceOrNonFungible::missing)
+                .collect(),
+            _ => vec![MissingProof::Unresolved],
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, TypeId, Encode, Decode)]
@@ -181,6 +241,24 @@ impl HardAuthRule {
             }
         }
     }
+
+    /// Collects the innermost proof rules which fail, skipping any passing sub-tree.
+    fn explain(&self, auth_zones: &[&AuthZone], failed_rules: &mut Vec<FailedRule>) {
+        if self.check(auth_zones).is_ok() {
+            return;
+        }
+        match self {
+            HardAuthRule::ProofRule(rule) => failed_rules.push(FailedRule::ProofRule {
+                rule: rule.clone(),
+                missing: rule.explain(auth_zones),
+            }),
+            HardAuthRule::AnyOf(rules) | HardAuthRule::AllOf(rules) => {
+                for rule in rules {
+                    rule.explain(auth_zones, failed_rules);
+                }
+            }
+        }
+    }
 }
 
 /// Snode which verifies authorization of a method call
@@ -201,4 +279,121 @@ impl MethodAuthorization {
             MethodAuthorization::Unsupported => Err(MethodAuthorizationError::UnsupportedMethod),
         }
     }
+
+    /// Explains why `check` fails against the given auth zones.
+    pub fn explain(&self, auth_zones: &[&AuthZone]) -> AuthorizationExplanation {
+        let mut failed_rules = Vec::new();
+        match self {
+            MethodAuthorization::Protected(rule) => rule.explain(auth_zones, &mut failed_rules),
+            MethodAuthorization::DenyAll => failed_rules.push(FailedRule::DenyAll),
+            MethodAuthorization::AllowAll | MethodAuthorization::Unsupported => {}
+        }
+
+        AuthorizationExplanation {
+            failed_rules,
+            auth_zones: auth_zones
+                .iter()
+                .map(|auth_zone| auth_zone.proofs.iter().map(ProofSummary::from).collect())
+                .collect(),
+        }
+    }
+}
+
+/// A proof which was required by a failed rule, but not present in any auth zone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MissingProof {
+    Resource(ResourceAddress),
+    NonFungible(NonFungibleAddress),
+    Amount {
+        resource: HardResourceOrNonFungible,
+        required: Decimal,
+        present: Decimal,
+    },
+    /// The rule refers to component state which could not be resolved.
+    Unresolved,
+}
+
+/// A rule node which failed an authorization check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FailedRule {
+    DenyAll,
+    ProofRule {
+        rule: HardProofRule,
+        missing: Vec<MissingProof>,
+    },
+}
+
+/// A proof in an auth zone considered by an authorization check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofSummary {
+    pub resource_address: ResourceAddress,
+    pub amount: Decimal,
+    /// The non-fungible IDs, for non-fungible proofs.
+    pub ids: Option<BTreeSet<NonFungibleId>>,
+}
+
+impl From<&Proof> for ProofSummary {
+    fn from(proof: &Proof) -> Self {
+        Self {
+            resource_address: proof.resource_address(),
+            amount: proof.total_amount(),
+            ids: proof.total_ids().ok(),
+        }
+    }
+}
+
+/// Why a method authorization failed: the failed rule nodes, and the proofs of each auth zone
+/// considered, in the order they were checked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorizationExplanation {
+    pub failed_rules: Vec<FailedRule>,
+    pub auth_zones: Vec<Vec<ProofSummary>>,
+}
+
+impl fmt::Display for MissingProof {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MissingProof::Resource(resource_address) => write!(f, "{}", resource_address),
+            MissingProof::NonFungible(non_fungible_address) => {
+                write!(f, "{}", non_fungible_address)
+            }
+            MissingProof::Amount {
+                resource,
+                required,
+                present,
+            } => write!(f, "{} of {:?} ({} present)", required, resource, present),
+            MissingProof::Unresolved => write!(f, "unresolved rule"),
+        }
+    }
+}
+
+impl fmt::Display for AuthorizationExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Failed rules:")?;
+        for failed_rule in &self.failed_rules {
+            match failed_rule {
+                FailedRule::DenyAll => write!(f, "\n  - deny all")?,
+                FailedRule::ProofRule { rule, missing } => {
+                    write!(f, "\n  - {:?}, missing:", rule)?;
+                    for (i, proof) in missing.iter().enumerate() {
+                        write!(f, "{} {}", if i == 0 { "" } else { "," }, proof)?;
+                    }
+                }
+            }
+        }
+        write!(f, "\nAuth zones:")?;
+        for (i, proofs) in self.auth_zones.iter().enumerate() {
+            if proofs.is_empty() {
+                write!(f, "\n  [{}] no proofs", i)?;
+            }
+            for proof in proofs {
+                write!(f, "\n  [{}] {} {}", i, proof.amount, proof.resource_address)?;
+                if let Some(ids) = &proof.ids {
+                    let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+                    write!(f, " [{}]", ids.join(", "))?;
+                }
+            }
+        }
+        Ok(())
+    }
 }
@@ -1,36 +1,57 @@
+mod account;
+mod allowance;
 mod auth_converter;
 mod auth_zone;
 mod bucket;
 mod component;
+pub mod fmt;
+mod locker;
 mod method_authorization;
 mod non_fungible;
+mod notification_registry;
 mod package;
 mod proof;
 mod receipt;
 mod resource;
 mod resource_manager;
+mod system;
 mod transaction;
 mod transaction_process;
 mod validated_transaction;
 mod vault;
 mod worktop;
 
+pub use account::{Account, AccountError, AuthDelegate, MAX_AUTH_DELEGATE_COST_UNITS};
+pub use allowance::{Allowance, AllowanceError};
 pub use auth_zone::{AuthZone, AuthZoneError};
 pub use auth_converter::convert;
 pub use bucket::{Bucket, BucketError};
-pub use component::Component;
+pub use component::{Component, ComponentError};
+pub use locker::{Locker, LockerError, LockerKey};
 pub use method_authorization::{
-    HardProofRule, HardResourceOrNonFungible, MethodAuthorization, MethodAuthorizationError,
+    AuthorizationExplanation, FailedRule, HardProofRule, HardResourceOrNonFungible,
+    MethodAuthorization, MethodAuthorizationError, MissingProof, ProofSummary,
+};
+pub use non_fungible::{
+    NonFungible, MAX_NON_FUNGIBLE_METADATA_ENTRIES, MAX_NON_FUNGIBLE_METADATA_SIZE,
+};
+pub use notification_registry::{
+    NotificationRegistry, NotificationRegistryAddress, MAX_EVENT_OBSERVERS,
+    MAX_PENDING_NOTIFICATIONS,
+};
+pub use package::{
+    decode_blueprint_abi, BlueprintAbi, Package, PackageError, SchemaAddress,
+    IMPORTED_TYPES_EXPORT_PREFIX, MIGRATE_STATE_FUNCTION, TEST_EXPORT_PREFIX, TYPES_EXPORT_NAME,
 };
-pub use non_fungible::NonFungible;
-pub use package::{Package, PackageError};
 pub use proof::*;
-pub use receipt::Receipt;
+pub use receipt::{PackageTestResult, Receipt, ReceiptDisplay};
 pub use resource::*;
 pub use resource_manager::{ResourceManager, ResourceManagerError};
+pub use system::{System, SystemComponentState, SystemError};
 pub use transaction_process::{TransactionProcess};
 pub use transaction::{
-    Instruction, SignedTransaction, Transaction,
+    CompositeTransaction, Instruction, SignedTransaction, SystemTransaction, Transaction,
+    TransactionHashes, TransactionHeader, TRANSACTION_HASHING_VERSION,
 };
 pub use validated_transaction::{ValidatedTransaction, ValidatedInstruction};
 pub use vault::{Vault, VaultError};
@@ -1,11 +1,24 @@
+use sbor::type_id::{TYPE_HASH_MAP, TYPE_TREE_MAP};
 use sbor::*;
+use scrypto::resource::MetadataValue;
+use scrypto::rust::collections::{BTreeMap, HashMap};
+use scrypto::rust::string::String;
 use scrypto::rust::vec::Vec;
 
+/// The maximum number of metadata entries of a non-fungible.
+pub const MAX_NON_FUNGIBLE_METADATA_ENTRIES: usize = 16;
+
+/// The maximum encoded size of the metadata of a non-fungible, in bytes.
+pub const MAX_NON_FUNGIBLE_METADATA_SIZE: usize = 4 * 1024;
+
 /// A non-fungible is a piece of data that is uniquely identified within a resource.
-#[derive(Debug, Clone, TypeId, Encode, Decode)]
+#[derive(Debug, Clone, TypeId, Encode)]
 pub struct NonFungible {
     immutable_data: Vec<u8>,
     mutable_data: Vec<u8>,
+    /// Owner-set display fields, e.g. `name` or `image_url`, kept apart from the data so that
+    /// they can be read without knowing the data schema.
+    metadata: BTreeMap<String, MetadataValue>,
 }
 
 impl NonFungible {
@@ -13,6 +26,7 @@ impl NonFungible {
         Self {
             immutable_data,
             mutable_data,
+            metadata: BTreeMap::new(),
         }
     }
 
@@ -24,7 +38,127 @@ impl NonFungible {
         self.mutable_data.clone()
     }
 
+    pub fn metadata(&self) -> &BTreeMap<String, MetadataValue> {
+        &self.metadata
+    }
+
     pub fn set_mutable_data(&mut self, new_mutable_data: Vec<u8>) {
         self.mutable_data = new_mutable_data;
     }
+
+    pub fn set_metadata(&mut self, new_metadata: BTreeMap<String, MetadataValue>) {
+        self.metadata = new_metadata;
+    }
+}
+
+/// Non-fungibles already on ledger are stored either without metadata or with the metadata as a
+/// `HashMap`, so both layouts are accepted and converted on decode.
+impl Decode for NonFungible {
+    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+        let len = decoder.read_len()?;
+        let immutable_data = Vec::<u8>::decode(decoder)?;
+        let mutable_data = Vec::<u8>::decode(decoder)?;
+        let metadata = match len {
+            2 => BTreeMap::new(),
+            3 => {
+                decoder.increase_depth()?;
+                let metadata = match decoder.read_type()? {
+                    TYPE_TREE_MAP => BTreeMap::<String, MetadataValue>::decode_value(decoder)?,
+                    TYPE_HASH_MAP => HashMap::<String, MetadataValue>::decode_value(decoder)?
+                        .into_iter()
+                        .collect(),
+                    actual => {
+                        return Err(DecodeError::InvalidType {
+                            expected: Some(TYPE_TREE_MAP),
+                            actual,
+                        })
+                    }
+                };
+                decoder.decrease_depth();
+                metadata
+            }
+            actual => {
+                return Err(DecodeError::InvalidLength {
+                    expected: 3,
+                    actual,
+                })
+            }
+        };
+
+        Ok(Self {
+            immutable_data,
+            mutable_data,
+            metadata,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scrypto::buffer::{scrypto_decode, scrypto_encode};
+
+    #[derive(TypeId, Encode)]
+    struct NonFungibleWithoutMetadata {
+        immutable_data: Vec<u8>,
+        mutable_data: Vec<u8>,
+    }
+
+    #[derive(TypeId, Encode)]
+    struct NonFungibleWithHashMapMetadata {
+        immutable_data: Vec<u8>,
+        mutable_data: Vec<u8>,
+        metadata: HashMap<String, MetadataValue>,
+    }
+
+    #[test]
+    fn should_decode_non_fungible_without_metadata() {
+        let bytes = scrypto_encode(&NonFungibleWithoutMetadata {
+            immutable_data: vec![1],
+            mutable_data: vec![2],
+        });
+
+        let non_fungible: NonFungible = scrypto_decode(&bytes).unwrap();
+
+        assert_eq!(non_fungible.immutable_data(), vec![1]);
+        assert_eq!(non_fungible.mutable_data(), vec![2]);
+        assert!(non_fungible.metadata().is_empty());
+    }
+
+    #[test]
+    fn should_decode_non_fungible_with_hash_map_metadata() {
+        let mut metadata = HashMap::new();
+        metadata.insert("name".to_owned(), MetadataValue::from("Reuben"));
+        metadata.insert("description".to_owned(), MetadataValue::from("A sandwich"));
+        let bytes = scrypto_encode(&NonFungibleWithHashMapMetadata {
+            immutable_data: vec![1],
+            mutable_data: vec![2],
+            metadata: metadata.clone(),
+        });
+
+        let non_fungible: NonFungible = scrypto_decode(&bytes).unwrap();
+
+        assert_eq!(
+            non_fungible.metadata(),
+            &metadata.into_iter().collect::<BTreeMap<_, _>>()
+        );
+    }
+
+    #[test]
+    fn should_encode_metadata_in_key_order() {
+        let mut non_fungible = NonFungible::new(vec![], vec![]);
+        non_fungible.set_metadata(BTreeMap::from([
+            ("b".to_owned(), MetadataValue::from("2")),
+            ("a".to_owned(), MetadataValue::from("1")),
+        ]));
+        let bytes = scrypto_encode(&non_fungible);
+
+        let decoded: NonFungible = scrypto_decode(&bytes).unwrap();
+
+        assert_eq!(scrypto_encode(&decoded), bytes);
+        assert_eq!(
+            decoded.metadata().keys().collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+    }
 }
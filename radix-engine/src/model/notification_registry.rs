@@ -0,0 +1,99 @@
+use sbor::*;
+use scrypto::core::Notification;
+use scrypto::engine::types::*;
+use scrypto::rust::mem;
+use scrypto::rust::string::String;
+use scrypto::rust::vec::Vec;
+
+/// The maximum number of subscriptions to the events of a component.
+pub const MAX_EVENT_OBSERVERS: usize = 64;
+
+/// The maximum number of notifications pending for a component. Further notifications are
+/// dropped until the component takes the pending ones.
+pub const MAX_PENDING_NOTIFICATIONS: usize = 256;
+
+/// The address of the notification registry of a component.
+///
+/// Registries are stored apart from the component substate, so that events can be emitted to a
+/// component while it's being called.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TypeId, Encode, Decode)]
+pub struct NotificationRegistryAddress(pub ComponentAddress);
+
+/// The notification state of a component, which keeps the events it allows other components to
+/// observe and their observers, and the notifications pending for it as an observer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, TypeId, Encode, Decode)]
+pub struct NotificationRegistry {
+    /// The events other components may observe.
+    subscribable: Vec<String>,
+    /// The observers of each event, in subscription order.
+    observers: Vec<(String, ComponentAddress)>,
+    /// The notifications pending for the component, oldest first.
+    pending: Vec<Notification>,
+}
+
+impl NotificationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows or disallows other components to observe an event. Disallowing an event removes
+    /// its observers.
+    pub fn set_subscribable(&mut self, event: String, subscribable: bool) {
+        if subscribable {
+            if !self.subscribable.contains(&event) {
+                self.subscribable.push(event);
+            }
+        } else {
+            self.subscribable.retain(|e| *e != event);
+            self.observers.retain(|(e, _)| *e != event);
+        }
+    }
+
+    pub fn is_subscribable(&self, event: &str) -> bool {
+        self.subscribable.iter().any(|e| e.as_str() == event)
+    }
+
+    /// Adds an observer of an event, returning false if there are already `MAX_EVENT_OBSERVERS`.
+    pub fn add_observer(&mut self, event: String, observer: ComponentAddress) -> bool {
+        let entry = (event, observer);
+        if !self.observers.contains(&entry) {
+            if self.observers.len() >= MAX_EVENT_OBSERVERS {
+                return false;
+            }
+            self.observers.push(entry);
+        }
+        true
+    }
+
+    pub fn remove_observer(&mut self, event: &str, observer: ComponentAddress) {
+        self.observers
+            .retain(|(e, o)| !(e.as_str() == event && *o == observer));
+    }
+
+    /// Returns the observers of an event.
+    pub fn observers(&self, event: &str) -> Vec<ComponentAddress> {
+        self.observers
+            .iter()
+            .filter(|(e, _)| e.as_str() == event)
+            .map(|(_, o)| *o)
+            .collect()
+    }
+
+    /// Adds a pending notification, returning false if it's dropped as there are already
+    /// `MAX_PENDING_NOTIFICATIONS`.
+    pub fn push_notification(&mut self, notification: Notification) -> bool {
+        if self.pending.len() >= MAX_PENDING_NOTIFICATIONS {
+            return false;
+        }
+        self.pending.push(notification);
+        true
+    }
+
+    pub fn take_notifications(&mut self) -> Vec<Notification> {
+        mem::take(&mut self.pending)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscribable.is_empty() && self.observers.is_empty() && self.pending.is_empty()
+    }
+}
@@ -1,45 +1,279 @@
+use sbor::type_id::{TYPE_HASH_MAP, TYPE_TREE_MAP};
 use sbor::*;
-use scrypto::abi::{Function, Method};
+use scrypto::abi::{Constant, Function, Interface, Method, Mutability};
 use scrypto::buffer::scrypto_decode;
-use scrypto::rust::collections::HashMap;
+use scrypto::crypto::{hash_schema, Hash};
+use scrypto::engine::types::*;
+use scrypto::resource::AccessRule;
+use scrypto::rust::collections::{BTreeMap, BTreeSet, HashMap};
 use scrypto::rust::string::String;
 use scrypto::rust::string::ToString;
 use scrypto::rust::vec;
 use scrypto::rust::vec::Vec;
 use scrypto::values::ScryptoValue;
+use scrypto::{access_rule_node, rule};
 use wasmi::{
     ExternVal, ImportsBuilder, MemoryRef, Module, ModuleInstance, ModuleRef, NopExternals,
     RuntimeValue,
 };
 
-use crate::engine::{EnvModuleResolver, SystemApi};
+use crate::engine::{
+    export_mutable_globals, EngineFeatures, EngineVersion, EnvModuleResolver, SystemApi,
+};
 use crate::errors::WasmValidationError;
+use crate::fee::CostUnitCounterError;
+use crate::model::{convert, MethodAuthorization};
+
+/// The address of a schema in the schema registry, which is the hash of the schema.
+///
+/// Packages refer to the schemas of their blueprints by hash, so that a schema shared by
+/// several packages is only stored once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TypeId, Encode, Decode)]
+pub struct SchemaAddress(pub Hash);
 
 /// A collection of blueprints, compiled and published as a single unit.
-#[derive(Debug, Clone, TypeId, Encode, Decode)]
+#[derive(Debug, Clone, TypeId, Encode)]
 pub struct Package {
     code: Vec<u8>,
-    blueprints: HashMap<String, Type>,
+    /// The hash of the schema of each blueprint.
+    blueprints: BTreeMap<String, Hash>,
+    /// The blueprint schemas, which are stored in the schema registry rather than the package.
+    #[sbor(skip)]
+    schemas: BTreeMap<Hash, Type>,
+    tests: BTreeSet<String>,
+    types: Vec<Type>,
+    /// The blueprints which define a `migrate_state` function.
+    migratable_blueprints: BTreeSet<String>,
+    /// The feature flags declared by each blueprint.
+    blueprint_features: BTreeMap<String, BTreeSet<String>>,
+    /// The number of times the package has been upgraded.
+    version: u32,
+    /// The badge of the package owner, if published with one.
+    owner_badge: Option<ResourceAddress>,
+    /// The rules protecting the package methods, e.g. `upgrade` and `set_metadata`.
+    access_rules: BTreeMap<String, AccessRule>,
+    metadata: BTreeMap<String, String>,
+}
+
+/// The number of encoded fields of a package.
+const PACKAGE_FIELDS: usize = 10;
+
+/// The number of encoded fields of a package published before schemas were moved to the schema
+/// registry, which only stored the code and the schema of each blueprint.
+const LEGACY_PACKAGE_FIELDS: usize = 2;
+
+impl Decode for Package {
+    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+        match decoder.read_len()? {
+            PACKAGE_FIELDS => Ok(Self {
+                code: Vec::<u8>::decode(decoder)?,
+                blueprints: BTreeMap::<String, Hash>::decode(decoder)?,
+                schemas: BTreeMap::new(),
+                tests: BTreeSet::<String>::decode(decoder)?,
+                types: Vec::<Type>::decode(decoder)?,
+                migratable_blueprints: BTreeSet::<String>::decode(decoder)?,
+                blueprint_features: BTreeMap::<String, BTreeSet<String>>::decode(decoder)?,
+                version: u32::decode(decoder)?,
+                owner_badge: Option::<ResourceAddress>::decode(decoder)?,
+                access_rules: BTreeMap::<String, AccessRule>::decode(decoder)?,
+                metadata: BTreeMap::<String, String>::decode(decoder)?,
+            }),
+            LEGACY_PACKAGE_FIELDS => {
+                let code = Vec::<u8>::decode(decoder)?;
+                decoder.increase_depth()?;
+                let legacy_schemas: Vec<(String, Type)> = match decoder.read_type()? {
+                    TYPE_HASH_MAP => HashMap::<String, Type>::decode_value(decoder)?
+                        .into_iter()
+                        .collect(),
+                    TYPE_TREE_MAP => BTreeMap::<String, Type>::decode_value(decoder)?
+                        .into_iter()
+                        .collect(),
+                    actual => {
+                        return Err(DecodeError::InvalidType {
+                            expected: Some(TYPE_TREE_MAP),
+                            actual,
+                        })
+                    }
+                };
+                decoder.decrease_depth();
+
+                // The schemas are kept in the package until it's written back, which moves them
+                // to the schema registry.
+                let mut blueprints = BTreeMap::new();
+                let mut schemas = BTreeMap::new();
+                for (name, schema) in legacy_schemas {
+                    let schema_hash = hash_schema(&schema);
+                    blueprints.insert(name, schema_hash);
+                    schemas.insert(schema_hash, schema);
+                }
+
+                Ok(Self {
+                    code,
+                    blueprints,
+                    schemas,
+                    tests: BTreeSet::new(),
+                    types: Vec::new(),
+                    migratable_blueprints: BTreeSet::new(),
+                    blueprint_features: BTreeMap::new(),
+                    version: 0,
+                    owner_badge: None,
+                    access_rules: Self::default_access_rules(None),
+                    metadata: BTreeMap::new(),
+                })
+            }
+            actual => Err(DecodeError::InvalidLength {
+                expected: PACKAGE_FIELDS,
+                actual,
+            }),
+        }
+    }
+}
+
+/// The package methods which are protected by the package access rules.
+pub const PACKAGE_METHODS: [&str; 2] = ["upgrade", "set_metadata"];
+
+/// The prefix of the exports generated for `#[scrypto_test]` functions.
+pub const TEST_EXPORT_PREFIX: &str = "scrypto_test_";
+
+/// The export generated by `export_types!`, which returns the schemas of the shared types.
+pub const TYPES_EXPORT_NAME: &str = "scrypto_types";
+
+/// The prefix of the exports generated by `import_types!`, which are followed by the address of
+/// the package the types are imported from.
+pub const IMPORTED_TYPES_EXPORT_PREFIX: &str = "scrypto_imported_types_";
+
+/// The blueprint function which converts component state written by an older version of the
+/// package.
+///
+/// It takes the encoded old state and returns the encoded new state.
+pub const MIGRATE_STATE_FUNCTION: &str = "migrate_state";
+
+/// The output of a blueprint ABI exporter: the blueprint schema, functions, methods,
+/// constants, error type, implemented interfaces and feature flags.
+pub type BlueprintAbi = (
+    Type,
+    Vec<Function>,
+    Vec<Method>,
+    Vec<Constant>,
+    Option<Type>,
+    Vec<Interface>,
+    Vec<String>,
+);
+
+/// Decodes the output of a blueprint ABI exporter, including the ones compiled before
+/// constants, error types, interfaces and feature flags were exported.
+pub fn decode_blueprint_abi(data: &[u8]) -> Result<BlueprintAbi, DecodeError> {
+    scrypto_decode(data).or_else(|e| {
+        scrypto_decode::<(
+            Type,
+            Vec<Function>,
+            Vec<Method>,
+            Vec<Constant>,
+            Option<Type>,
+            Vec<Interface>,
+        )>(data)
+        .map(
+            |(schema, functions, methods, constants, error, interfaces)| {
+                (
+                    schema,
+                    functions,
+                    methods,
+                    constants,
+                    error,
+                    interfaces,
+                    Vec::new(),
+                )
+            },
+        )
+        .or_else(|_| {
+            scrypto_decode::<(
+                Type,
+                Vec<Function>,
+                Vec<Method>,
+                Vec<Constant>,
+                Option<Type>,
+            )>(data)
+            .map(|(schema, functions, methods, constants, error)| {
+                (
+                    schema,
+                    functions,
+                    methods,
+                    constants,
+                    error,
+                    Vec::new(),
+                    Vec::new(),
+                )
+            })
+        })
+        .or_else(|_| {
+            scrypto_decode::<(Type, Vec<Function>, Vec<Method>)>(data).map(
+                |(schema, functions, methods)| {
+                    (
+                        schema,
+                        functions,
+                        methods,
+                        Vec::new(),
+                        None,
+                        Vec::new(),
+                        Vec::new(),
+                    )
+                },
+            )
+        })
+        .map_err(|_| e)
+    })
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum PackageError {
     InvalidRequestData(DecodeError),
     BlueprintNotFound,
+    /// The schema of the blueprint hasn't been loaded from the schema registry.
+    SchemaNotLoaded(Hash),
     WasmValidationError(WasmValidationError),
     MethodNotFound(String),
+    CostingError(CostUnitCounterError),
 }
 
 impl Package {
-    /// Validates and creates a package
+    /// Validates and creates a package, with the features of the latest engine version.
     pub fn new(code: Vec<u8>) -> Result<Self, WasmValidationError> {
+        Self::new_with_features(code, &EngineVersion::LATEST.features())
+    }
+
+    /// Validates and creates a package, with the given engine features.
+    pub fn new_with_features(
+        code: Vec<u8>,
+        features: &EngineFeatures,
+    ) -> Result<Self, WasmValidationError> {
+        // Check code size
+        if let Some(max_size) = features.max_package_code_size {
+            if code.len() > max_size {
+                return Err(WasmValidationError::CodeTooLarge(code.len()));
+            }
+        }
+
+        // Check wasm features
+        features
+            .wasm_features
+            .check_with_limits(&code, features.wasm_limits.as_ref())?;
+
+        // Canonicalize NaNs, if floating point is accepted
+        let code = if features.wasm_features.nan_canonicalization {
+            features.wasm_features.canonicalize_nans(&code)?
+        } else {
+            code
+        };
+
         // Parse
         let parsed = Self::parse_module(&code)?;
 
         // check floating point
-        parsed
-            .deny_floating_point()
-            .map_err(|_| WasmValidationError::FloatingPointNotAllowed)?;
+        if !features.wasm_features.nan_canonicalization {
+            parsed
+                .deny_floating_point()
+                .map_err(|_| WasmValidationError::FloatingPointNotAllowed)?;
+        }
 
         // Instantiate
         let instance = ModuleInstance::new(
@@ -71,41 +305,133 @@ impl Package {
             .map(|(name, _)| name.to_string())
             .collect();
 
-        let mut blueprints = HashMap::new();
+        let tests: BTreeSet<String> = exports
+            .iter()
+            .filter(|(_, val)| matches!(val, ExternVal::Func(_)))
+            .filter_map(|(name, _)| name.strip_prefix(TEST_EXPORT_PREFIX))
+            .map(|name| name.to_string())
+            .collect();
+
+        let mut blueprints = BTreeMap::new();
+        let mut schemas = BTreeMap::new();
+        let mut migratable_blueprints = BTreeSet::new();
+        let mut blueprint_features = BTreeMap::new();
 
         for method_name in blueprint_abi_methods {
-            let rtn = module
-                .invoke_export(&method_name, &[], &mut NopExternals)
-                .map_err(|e| WasmValidationError::NoPackageInitExport(e.into()))?
-                .ok_or(WasmValidationError::InvalidPackageInit)?;
-
-            let blueprint_type: Type = match rtn {
-                RuntimeValue::I32(ptr) => {
-                    let len: u32 = memory
-                        .get_value(ptr as u32)
-                        .map_err(|_| WasmValidationError::InvalidPackageInit)?;
-
-                    // SECURITY: meter before allocating memory
-                    let mut data = vec![0u8; len as usize];
-                    memory
-                        .get_into((ptr + 4) as u32, &mut data)
-                        .map_err(|_| WasmValidationError::InvalidPackageInit)?;
-
-                    let result: (Type, Vec<Function>, Vec<Method>) = scrypto_decode(&data)
-                        .map_err(|_| WasmValidationError::InvalidPackageInit)?;
-                    Ok(result.0)
-                }
-                _ => Err(WasmValidationError::InvalidPackageInit),
-            }?;
+            let data = Self::invoke_export(&module, &memory, &method_name)?;
+            let (blueprint_type, functions, methods, _, _, interfaces, features) =
+                decode_blueprint_abi(&data).map_err(|_| WasmValidationError::InvalidPackageInit)?;
 
             if let Type::Struct { name, fields: _ } = &blueprint_type {
-                blueprints.insert(name.clone(), blueprint_type);
+                check_interfaces(name, &methods, &interfaces)?;
+                if functions.iter().any(|f| f.name == MIGRATE_STATE_FUNCTION) {
+                    migratable_blueprints.insert(name.clone());
+                }
+                if !features.is_empty() {
+                    blueprint_features.insert(name.clone(), features.into_iter().collect());
+                }
+                let schema_hash = hash_schema(&blueprint_type);
+                blueprints.insert(name.clone(), schema_hash);
+                schemas.insert(schema_hash, blueprint_type);
             } else {
                 return Err(WasmValidationError::InvalidPackageInit);
             }
         }
 
-        Ok(Self { blueprints, code })
+        let types: Vec<Type> = match module.export_by_name(TYPES_EXPORT_NAME) {
+            Some(ExternVal::Func(_)) => {
+                let data = Self::invoke_export(&module, &memory, TYPES_EXPORT_NAME)?;
+                scrypto_decode(&data).map_err(|_| WasmValidationError::InvalidTypesExport)?
+            }
+            _ => Vec::new(),
+        };
+        if !types
+            .iter()
+            .all(|ty| matches!(ty, Type::Struct { .. } | Type::Enum { .. }))
+        {
+            return Err(WasmValidationError::InvalidTypesExport);
+        }
+
+        Ok(Self {
+            blueprints,
+            schemas,
+            tests,
+            types,
+            migratable_blueprints,
+            blueprint_features,
+            version: 0,
+            owner_badge: None,
+            access_rules: Self::default_access_rules(None),
+            metadata: BTreeMap::new(),
+            code,
+        })
+    }
+
+    /// Marks this package as the given version, when it replaces an older version.
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Sets the owner of this package, who is allowed to upgrade it and update its metadata.
+    pub fn with_owner(mut self, owner_badge: ResourceAddress) -> Self {
+        self.owner_badge = Some(owner_badge);
+        self.access_rules = Self::default_access_rules(Some(owner_badge));
+        self
+    }
+
+    /// Validates the new code and creates the next version of this package, which keeps the
+    /// owner, access rules and metadata.
+    pub fn upgrade(
+        &self,
+        code: Vec<u8>,
+        features: &EngineFeatures,
+    ) -> Result<Self, WasmValidationError> {
+        let mut package = Self::new_with_features(code, features)?.with_version(self.version + 1);
+        package.owner_badge = self.owner_badge;
+        package.access_rules = self.access_rules.clone();
+        package.metadata = self.metadata.clone();
+        Ok(package)
+    }
+
+    /// Packages without an owner can't be upgraded, except by the privileged executor API.
+    fn default_access_rules(owner_badge: Option<ResourceAddress>) -> BTreeMap<String, AccessRule> {
+        let rule = match owner_badge {
+            Some(owner_badge) => rule!(require(owner_badge)),
+            None => AccessRule::DenyAll,
+        };
+        PACKAGE_METHODS
+            .iter()
+            .map(|method| (method.to_string(), rule.clone()))
+            .collect()
+    }
+
+    /// Invokes an export which takes no arguments and returns a radix-style buffer.
+    fn invoke_export(
+        module: &ModuleRef,
+        memory: &MemoryRef,
+        name: &str,
+    ) -> Result<Vec<u8>, WasmValidationError> {
+        let rtn = module
+            .invoke_export(name, &[], &mut NopExternals)
+            .map_err(|e| WasmValidationError::NoPackageInitExport(e.into()))?
+            .ok_or(WasmValidationError::InvalidPackageInit)?;
+
+        match rtn {
+            RuntimeValue::I32(ptr) => {
+                let len: u32 = memory
+                    .get_value(ptr as u32)
+                    .map_err(|_| WasmValidationError::InvalidPackageInit)?;
+
+                // SECURITY: meter before allocating memory
+                let mut data = vec![0u8; len as usize];
+                memory
+                    .get_into((ptr + 4) as u32, &mut data)
+                    .map_err(|_| WasmValidationError::InvalidPackageInit)?;
+                Ok(data)
+            }
+            _ => Err(WasmValidationError::InvalidPackageInit),
+        }
     }
 
     pub fn code(&self) -> &[u8] {
@@ -116,14 +442,106 @@ impl Package {
         self.blueprints.contains_key(blueprint_name)
     }
 
+    /// Returns the names of the `#[scrypto_test]` functions compiled into this package.
+    pub fn tests(&self) -> &BTreeSet<String> {
+        &self.tests
+    }
+
+    /// Returns the schemas of the structs and enums exported with `export_types!`.
+    pub fn types(&self) -> &[Type] {
+        &self.types
+    }
+
+    /// Returns the addresses of the packages whose types are imported with `import_types!`.
+    pub fn imported_types(&self) -> Vec<String> {
+        let module = match Self::parse_module(&self.code)
+            .and_then(|module| Self::instantiate_module(&module))
+        {
+            Ok((module, _)) => module,
+            Err(_) => return Vec::new(),
+        };
+        module
+            .exports()
+            .iter()
+            .filter(|(_, val)| matches!(val, ExternVal::Func(_)))
+            .filter_map(|(name, _)| name.strip_prefix(IMPORTED_TYPES_EXPORT_PREFIX))
+            .map(|package_address| package_address.to_string())
+            .collect()
+    }
+
+    /// Returns the number of times the package has been upgraded.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the badge of the package owner, if any.
+    pub fn owner_badge(&self) -> Option<ResourceAddress> {
+        self.owner_badge
+    }
+
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    pub fn get_auth(&self, function: &str) -> MethodAuthorization {
+        match self.access_rules.get(function) {
+            Some(rule) => convert(&Type::Unit, &Value::Unit, rule),
+            None => MethodAuthorization::AllowAll,
+        }
+    }
+
+    /// Whether the blueprint defines a `migrate_state` function.
+    pub fn is_migratable(&self, blueprint_name: &str) -> bool {
+        self.migratable_blueprints.contains(blueprint_name)
+    }
+
+    /// Whether the blueprint declares the given feature flag.
+    pub fn is_feature_declared(&self, blueprint_name: &str, feature: &str) -> bool {
+        self.blueprint_features
+            .get(blueprint_name)
+            .map_or(false, |features| features.contains(feature))
+    }
+
+    pub fn contains_test(&self, test_name: &str) -> bool {
+        self.tests.contains(test_name)
+    }
+
     pub fn load_blueprint_schema(&self, blueprint_name: &str) -> Result<&Type, PackageError> {
-        self.blueprints
+        let schema_hash = self
+            .blueprints
             .get(blueprint_name)
-            .ok_or(PackageError::BlueprintNotFound)
+            .ok_or(PackageError::BlueprintNotFound)?;
+        self.schemas
+            .get(schema_hash)
+            .ok_or(PackageError::SchemaNotLoaded(*schema_hash))
+    }
+
+    /// Returns the hash of the schema of the given blueprint.
+    pub fn blueprint_schema_hash(&self, blueprint_name: &str) -> Option<Hash> {
+        self.blueprints.get(blueprint_name).cloned()
+    }
+
+    /// Returns the blueprint schemas which have been loaded, keyed by hash.
+    pub fn schemas(&self) -> &BTreeMap<Hash, Type> {
+        &self.schemas
+    }
+
+    /// Loads the blueprint schemas which haven't been loaded yet, from the schema registry.
+    pub fn load_schemas<F: FnMut(&Hash) -> Option<Type>>(&mut self, mut load: F) {
+        for schema_hash in self.blueprints.values() {
+            if !self.schemas.contains_key(schema_hash) {
+                if let Some(schema) = load(schema_hash) {
+                    self.schemas.insert(*schema_hash, schema);
+                }
+            }
+        }
     }
 
+    /// Instantiates the package code, with its mutable globals exported so that the instance
+    /// can be reset by the WASM instance pool.
     pub fn load_module(&self) -> Result<(ModuleRef, MemoryRef), PackageError> {
-        let module = Self::parse_module(&self.code).unwrap();
+        let code = export_mutable_globals(&self.code).unwrap();
+        let module = Self::parse_module(&code).unwrap();
         let inst = Self::instantiate_module(&module).unwrap();
         Ok(inst)
     }
@@ -156,13 +574,239 @@ impl Package {
     ) -> Result<ScryptoValue, PackageError> {
         match function {
             "publish" => {
-                let bytes =
+                let bytes: Vec<u8> =
                     scrypto_decode(&args[0].raw).map_err(PackageError::InvalidRequestData)?;
-                let package = Package::new(bytes).map_err(PackageError::WasmValidationError)?;
+                let publish_cost = (bytes.len() as u32)
+                    .saturating_mul(system_api.fee_table().publish_package_per_byte());
+                system_api
+                    .consume_cost_units(publish_cost, "publish_package")
+                    .map_err(PackageError::CostingError)?;
+                let features = system_api.engine_version().features();
+                let package = Package::new_with_features(bytes, &features)
+                    .map_err(PackageError::WasmValidationError)?;
                 let package_address = system_api.create_package(package);
                 Ok(ScryptoValue::from_value(&package_address))
             }
+            "publish_with_owner" => {
+                let bytes: Vec<u8> =
+                    scrypto_decode(&args[0].raw).map_err(PackageError::InvalidRequestData)?;
+                let owner_badge: ResourceAddress =
+                    scrypto_decode(&args[1].raw).map_err(PackageError::InvalidRequestData)?;
+                let publish_cost = (bytes.len() as u32)
+                    .saturating_mul(system_api.fee_table().publish_package_per_byte());
+                system_api
+                    .consume_cost_units(publish_cost, "publish_package")
+                    .map_err(PackageError::CostingError)?;
+                let features = system_api.engine_version().features();
+                let package = Package::new_with_features(bytes, &features)
+                    .map_err(PackageError::WasmValidationError)?
+                    .with_owner(owner_badge);
+                let package_address = system_api.create_package(package);
+                Ok(ScryptoValue::from_value(&package_address))
+            }
+            _ => Err(PackageError::MethodNotFound(function.to_string())),
+        }
+    }
+
+    pub fn main<S: SystemApi>(
+        &mut self,
+        function: &str,
+        args: Vec<ScryptoValue>,
+        system_api: &mut S,
+    ) -> Result<ScryptoValue, PackageError> {
+        match function {
+            "upgrade" => {
+                let bytes: Vec<u8> =
+                    scrypto_decode(&args[0].raw).map_err(PackageError::InvalidRequestData)?;
+                let publish_cost = (bytes.len() as u32)
+                    .saturating_mul(system_api.fee_table().publish_package_per_byte());
+                system_api
+                    .consume_cost_units(publish_cost, "publish_package")
+                    .map_err(PackageError::CostingError)?;
+                let features = system_api.engine_version().features();
+                *self = self
+                    .upgrade(bytes, &features)
+                    .map_err(PackageError::WasmValidationError)?;
+                Ok(ScryptoValue::from_value(&self.version))
+            }
+            "set_metadata" => {
+                let name: String =
+                    scrypto_decode(&args[0].raw).map_err(PackageError::InvalidRequestData)?;
+                let value: String =
+                    scrypto_decode(&args[1].raw).map_err(PackageError::InvalidRequestData)?;
+                self.metadata.insert(name, value);
+                Ok(ScryptoValue::from_value(&()))
+            }
+            "get_metadata" => Ok(ScryptoValue::from_value(&self.metadata)),
+            "get_owner_badge" => Ok(ScryptoValue::from_value(&self.owner_badge)),
             _ => Err(PackageError::MethodNotFound(function.to_string())),
         }
     }
 }
+
+/// Checks that a blueprint has every method of the interfaces it declares to implement, with the
+/// same inputs and output.
+///
+/// A method may be immutable where the interface allows it to be mutable, but not the other way
+/// around.
+fn check_interfaces(
+    blueprint_name: &str,
+    methods: &[Method],
+    interfaces: &[Interface],
+) -> Result<(), WasmValidationError> {
+    for interface in interfaces {
+        for expected in &interface.methods {
+            let conforms = methods.iter().any(|method| {
+                method.name == expected.name
+                    && method.inputs == expected.inputs
+                    && method.output == expected.output
+                    && (matches!(method.mutability, Mutability::Immutable)
+                        || matches!(expected.mutability, Mutability::Mutable))
+            });
+            if !conforms {
+                return Err(WasmValidationError::InterfaceNotImplemented {
+                    blueprint_name: blueprint_name.to_string(),
+                    interface_name: interface.name.clone(),
+                    method_name: expected.name.clone(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sbor::describe::Fields;
+    use scrypto::buffer::scrypto_encode;
+
+    #[derive(TypeId, Encode)]
+    struct LegacyPackage {
+        code: Vec<u8>,
+        blueprints: HashMap<String, Type>,
+    }
+
+    fn blueprint_schema(name: &str) -> Type {
+        Type::Struct {
+            name: name.to_string(),
+            fields: Fields::Unit,
+        }
+    }
+
+    fn method(name: &str, mutability: Mutability, output: Type) -> Method {
+        Method {
+            name: name.to_string(),
+            mutability,
+            inputs: Vec::new(),
+            output,
+        }
+    }
+
+    fn counter_interface() -> Interface {
+        Interface {
+            name: "Counter".to_string(),
+            methods: vec![
+                method("increment", Mutability::Mutable, Type::U32),
+                method("get", Mutability::Immutable, Type::U32),
+            ],
+        }
+    }
+
+    #[test]
+    fn should_accept_blueprint_implementing_interface() {
+        let methods = vec![
+            method("get", Mutability::Immutable, Type::U32),
+            method("increment", Mutability::Immutable, Type::U32),
+            method("reset", Mutability::Mutable, Type::Unit),
+        ];
+        assert_eq!(
+            check_interfaces("Counter", &methods, &[counter_interface()]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn should_reject_blueprint_not_implementing_interface() {
+        let interfaces = [counter_interface()];
+        let not_implemented = |method_name: &str| {
+            Err(WasmValidationError::InterfaceNotImplemented {
+                blueprint_name: "Counter".to_string(),
+                interface_name: "Counter".to_string(),
+                method_name: method_name.to_string(),
+            })
+        };
+
+        // Missing method
+        let methods = vec![method("increment", Mutability::Mutable, Type::U32)];
+        assert_eq!(
+            check_interfaces("Counter", &methods, &interfaces),
+            not_implemented("get")
+        );
+
+        // Different output
+        let methods = vec![
+            method("increment", Mutability::Mutable, Type::U64),
+            method("get", Mutability::Immutable, Type::U32),
+        ];
+        assert_eq!(
+            check_interfaces("Counter", &methods, &interfaces),
+            not_implemented("increment")
+        );
+
+        // Mutable where the interface requires immutable
+        let methods = vec![
+            method("increment", Mutability::Mutable, Type::U32),
+            method("get", Mutability::Mutable, Type::U32),
+        ];
+        assert_eq!(
+            check_interfaces("Counter", &methods, &interfaces),
+            not_implemented("get")
+        );
+    }
+
+    #[test]
+    fn should_decode_legacy_package() {
+        let mut blueprints = HashMap::new();
+        blueprints.insert("Hello".to_string(), blueprint_schema("Hello"));
+        let bytes = scrypto_encode(&LegacyPackage {
+            code: vec![0, 97, 115, 109],
+            blueprints,
+        });
+
+        let package: Package = scrypto_decode(&bytes).unwrap();
+
+        assert_eq!(package.code(), &[0, 97, 115, 109]);
+        assert_eq!(
+            package.load_blueprint_schema("Hello"),
+            Ok(&blueprint_schema("Hello"))
+        );
+        assert_eq!(
+            package.blueprint_schema_hash("Hello"),
+            Some(hash_schema(&blueprint_schema("Hello")))
+        );
+        assert_eq!(package.owner_badge, None);
+    }
+
+    #[test]
+    fn should_keep_package_fields_on_decode() {
+        let mut blueprints = HashMap::new();
+        blueprints.insert("Hello".to_string(), blueprint_schema("Hello"));
+        let legacy: Package = scrypto_decode(&scrypto_encode(&LegacyPackage {
+            code: vec![0, 97, 115, 109],
+            blueprints,
+        }))
+        .unwrap();
+        let bytes = scrypto_encode(&legacy.with_version(3));
+
+        let package: Package = scrypto_decode(&bytes).unwrap();
+
+        assert_eq!(package.version, 3);
+        assert_eq!(
+            package.blueprint_schema_hash("Hello"),
+            Some(hash_schema(&blueprint_schema("Hello")))
+        );
+        assert!(package.schemas().is_empty());
+        assert_eq!(scrypto_encode(&package), bytes);
+    }
+}
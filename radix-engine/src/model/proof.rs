@@ -1,4 +1,7 @@
+use sbor::DecodeError;
+use scrypto::buffer::scrypto_decode;
 use scrypto::engine::types::*;
+use scrypto::resource::ProofValidationError;
 use scrypto::rust::cell::RefCell;
 use scrypto::rust::collections::BTreeSet;
 use scrypto::rust::collections::HashMap;
@@ -10,7 +13,7 @@ use scrypto::values::ScryptoValue;
 use crate::engine::SystemApi;
 
 use crate::model::{
-    LockedAmountOrIds, ResourceContainer, ResourceContainerError, ResourceContainerId,
+    LockedAmountOrIds, ResourceContainer, ResourceContainerError, ResourceContainerId, ResourceLock,
 };
 
 #[derive(Debug)]
@@ -23,8 +26,8 @@ pub struct Proof {
     restricted: bool,
     /// The total locked amount or non-fungible ids.
     total_locked: LockedAmountOrIds,
-    /// The supporting containers.
-    evidence: HashMap<ResourceContainerId, (Rc<RefCell<ResourceContainer>>, LockedAmountOrIds)>,
+    /// The supporting containers, and the lock held on each of them.
+    evidence: HashMap<ResourceContainerId, (Rc<RefCell<ResourceContainer>>, ResourceLock)>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -40,6 +43,7 @@ pub enum ProofError {
     /// Can't apply a fungible operation on non-fungible proofs.
     FungibleOperationNotAllowed,
     CouldNotCreateProof,
+    InvalidRequestData(DecodeError),
     MethodNotFound(String),
 }
 
@@ -48,9 +52,10 @@ impl Proof {
         resource_address: ResourceAddress,
         resource_type: ResourceType,
         total_locked: LockedAmountOrIds,
-        evidence: HashMap<ResourceContainerId, (Rc<RefCell<ResourceContainer>>, LockedAmountOrIds)>,
+        evidence: HashMap<ResourceContainerId, (Rc<RefCell<ResourceContainer>>, ResourceLock)>,
     ) -> Result<Proof, ProofError> {
         if total_locked.is_empty() {
+            Self::release(evidence);
             return Err(ProofError::EmptyProofNotAllowed);
         }
 
@@ -78,13 +83,35 @@ impl Proof {
             .filter(|p| p.resource_address() == resource_address && !p.is_restricted())
             .collect();
 
-        // calculate the max locked amount (or ids) of each container
+        Self::compute_max_locked(&proofs, resource_type)
+    }
+
+    /// Computes the amount backed by proofs of the same resource, as a composite proof of
+    /// them would.
+    pub fn compute_composite_amount(proofs: &[&Proof]) -> Decimal {
+        match proofs.first() {
+            Some(proof) => Self::compute_max_locked(proofs, proof.resource_type)
+                .0
+                .amount(),
+            None => Decimal::zero(),
+        }
+    }
+
+    /// Computes the max locked amount (or ids) of each container, so that a container which
+    /// backs several proofs, directly or through nested compositions, is only counted once.
+    fn compute_max_locked(
+        proofs: &[&Proof],
+        resource_type: ResourceType,
+    ) -> (
+        LockedAmountOrIds,
+        HashMap<ResourceContainerId, LockedAmountOrIds>,
+    ) {
         match resource_type {
             ResourceType::Fungible { .. } => {
                 let mut max = HashMap::<ResourceContainerId, Decimal>::new();
-                for proof in &proofs {
-                    for (container_id, (_, locked_amount_or_ids)) in &proof.evidence {
-                        let new_amount = locked_amount_or_ids.amount();
+                for proof in proofs {
+                    for (container_id, (_, lock)) in &proof.evidence {
+                        let new_amount = lock.locked().amount();
                         if let Some(existing) = max.get_mut(&container_id) {
                             *existing = Decimal::max(*existing, new_amount);
                         } else {
@@ -105,9 +132,9 @@ impl Proof {
             }
             ResourceType::NonFungible => {
                 let mut max = HashMap::<ResourceContainerId, BTreeSet<NonFungibleId>>::new();
-                for proof in &proofs {
-                    for (container_id, (_, locked_amount_or_ids)) in &proof.evidence {
-                        let new_ids = locked_amount_or_ids.ids().unwrap();
+                for proof in proofs {
+                    for (container_id, (_, lock)) in &proof.evidence {
+                        let new_ids = lock.locked().ids().unwrap();
                         if let Some(ids) = max.get_mut(&container_id) {
                             ids.extend(new_ids);
                         } else {
@@ -172,15 +199,16 @@ impl Proof {
 
                         if let Some(quota) = per_container.remove(container_id) {
                             let amount = Decimal::min(remaining, quota.amount());
-                            container
-                                .borrow_mut()
-                                .lock_by_amount(amount)
-                                .map_err(ProofError::ResourceContainerError)?;
+                            let result = container.borrow_mut().lock_by_amount(amount);
+                            let lock = match result {
+                                Ok(lock) => lock,
+                                Err(e) => {
+                                    Self::release(evidence);
+                                    return Err(ProofError::ResourceContainerError(e));
+                                }
+                            };
                             remaining -= amount;
-                            evidence.insert(
-                                container_id.clone(),
-                                (container.clone(), LockedAmountOrIds::Amount(amount)),
-                            );
+                            evidence.insert(container_id.clone(), (container.clone(), lock));
                         }
                     }
                 }
@@ -235,17 +263,18 @@ impl Proof {
                                 .intersection(&quota.ids().unwrap())
                                 .cloned()
                                 .collect();
-                            container
-                                .borrow_mut()
-                                .lock_by_ids(&ids)
-                                .map_err(ProofError::ResourceContainerError)?;
+                            let result = container.borrow_mut().lock_by_ids(&ids);
+                            let lock = match result {
+                                Ok(lock) => lock,
+                                Err(e) => {
+                                    Self::release(evidence);
+                                    return Err(ProofError::ResourceContainerError(e));
+                                }
+                            };
                             for id in &ids {
                                 remaining.remove(id);
                             }
-                            evidence.insert(
-                                container_id.clone(),
-                                (container.clone(), LockedAmountOrIds::Ids(ids)),
-                            );
+                            evidence.insert(container_id.clone(), (container.clone(), lock));
                         }
                     }
                 }
@@ -262,37 +291,36 @@ impl Proof {
 
     /// Makes a clone of this proof.
     ///
-    /// Note that cloning a proof will update the ref count of the locked
-    /// resources in the source containers.
+    /// Note that cloning a proof takes a new lock on the same resources of the
+    /// source containers, which is released when the clone is dropped.
     pub fn clone(&self) -> Self {
-        for (_, (container, locked_amount_or_ids)) in &self.evidence {
-            match locked_amount_or_ids {
-                LockedAmountOrIds::Amount(amount) => {
-                    container
-                        .borrow_mut()
-                        .lock_by_amount(*amount)
-                        .expect("Cloning should always succeed");
-                }
-                LockedAmountOrIds::Ids(ids) => {
-                    container
-                        .borrow_mut()
-                        .lock_by_ids(ids)
-                        .expect("Cloning should always succeed");
-                }
+        let mut evidence = HashMap::new();
+        for (container_id, (container, lock)) in &self.evidence {
+            let lock = match lock.locked() {
+                LockedAmountOrIds::Amount(amount) => container.borrow_mut().lock_by_amount(*amount),
+                LockedAmountOrIds::Ids(ids) => container.borrow_mut().lock_by_ids(ids),
             }
+            .expect("Cloning should always succeed");
+            evidence.insert(container_id.clone(), (container.clone(), lock));
         }
         Self {
             resource_address: self.resource_address.clone(),
             resource_type: self.resource_type.clone(),
             restricted: self.restricted,
             total_locked: self.total_locked.clone(),
-            evidence: self.evidence.clone(),
+            evidence,
         }
     }
 
     pub fn drop(self) {
-        for (_, (container, locked_amount_or_ids)) in self.evidence {
-            container.borrow_mut().unlock(locked_amount_or_ids);
+        Self::release(self.evidence);
+    }
+
+    fn release(
+        evidence: HashMap<ResourceContainerId, (Rc<RefCell<ResourceContainer>>, ResourceLock)>,
+    ) {
+        for (_, (container, lock)) in evidence {
+            container.borrow_mut().unlock(lock);
         }
     }
 
@@ -310,6 +338,19 @@ impl Proof {
         self.resource_address
     }
 
+    pub fn resource_type(&self) -> ResourceType {
+        self.resource_type
+    }
+
+    /// Returns the containers which back this proof, and the amount or non-fungible ids
+    /// locked in each of them.
+    pub fn provenance(&self) -> HashMap<ResourceContainerId, LockedAmountOrIds> {
+        self.evidence
+            .iter()
+            .map(|(container_id, (_, lock))| (container_id.clone(), lock.locked().clone()))
+            .collect()
+    }
+
     pub fn total_amount(&self) -> Decimal {
         self.total_locked.amount()
     }
@@ -324,13 +365,40 @@ impl Proof {
         self.restricted
     }
 
+    /// Checks that this proof is of the expected resource and covers at least the given amount.
+    pub fn validate(
+        &self,
+        expected_resource_address: ResourceAddress,
+        min_amount: Decimal,
+    ) -> Result<(), ProofValidationError> {
+        if self.resource_address != expected_resource_address {
+            return Err(ProofValidationError::ResourceAddressDoesNotMatch(
+                self.resource_address,
+            ));
+        }
+        let amount = self.total_amount();
+        if amount < min_amount {
+            return Err(ProofValidationError::InsufficientAmount(amount));
+        }
+        Ok(())
+    }
+
     pub fn main<S: SystemApi>(
         &mut self,
         function: &str,
-        _: Vec<ScryptoValue>,
+        args: Vec<ScryptoValue>,
         system_api: &mut S,
     ) -> Result<ScryptoValue, ProofError> {
         match function {
+            "validate" => {
+                let resource_address: ResourceAddress = scrypto_decode(&args[0].raw)
+                    .map_err(|e| ProofError::InvalidRequestData(e))?;
+                let min_amount: Decimal = scrypto_decode(&args[1].raw)
+                    .map_err(|e| ProofError::InvalidRequestData(e))?;
+                Ok(ScryptoValue::from_value(
+                    &self.validate(resource_address, min_amount),
+                ))
+            }
             "get_total_amount" => Ok(ScryptoValue::from_value(&self.total_amount())),
             "get_non_fungible_ids" => {
                 let ids = self.total_ids()?;
@@ -356,3 +424,192 @@ impl Proof {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scrypto::constants::RADIX_TOKEN;
+
+    const BUCKET: ResourceContainerId = ResourceContainerId::Bucket(0);
+
+    fn new_container(amount: u32) -> Rc<RefCell<ResourceContainer>> {
+        Rc::new(RefCell::new(ResourceContainer::new_fungible(
+            RADIX_TOKEN,
+            18,
+            amount.into(),
+        )))
+    }
+
+    fn new_proof(
+        container: &Rc<RefCell<ResourceContainer>>,
+        container_id: ResourceContainerId,
+        locked: LockedAmountOrIds,
+    ) -> Result<Proof, ProofError> {
+        let lock = match &locked {
+            LockedAmountOrIds::Amount(amount) => container.borrow_mut().lock_by_amount(*amount),
+            LockedAmountOrIds::Ids(ids) => container.borrow_mut().lock_by_ids(ids),
+        }
+        .unwrap();
+        let resource_type = container.borrow().resource_type();
+        let mut evidence = HashMap::new();
+        evidence.insert(container_id, (container.clone(), lock));
+        Proof::new(RADIX_TOKEN, resource_type, locked, evidence)
+    }
+
+    fn ids(ids: &[u32]) -> BTreeSet<NonFungibleId> {
+        ids.iter().cloned().map(NonFungibleId::from_u32).collect()
+    }
+
+    #[test]
+    fn test_nested_fungible_proofs() {
+        let resource_type = ResourceType::Fungible { divisibility: 18 };
+        let container = new_container(10);
+        let base = [
+            new_proof(&container, BUCKET, LockedAmountOrIds::Amount(4.into())).unwrap(),
+            new_proof(&container, BUCKET, LockedAmountOrIds::Amount(7.into())).unwrap(),
+        ];
+        assert_eq!(container.borrow().liquid_amount(), 3.into());
+
+        let composite =
+            [Proof::compose_by_amount(&base, 5.into(), RADIX_TOKEN, resource_type).unwrap()];
+        let nested = Proof::compose(&composite, RADIX_TOKEN, resource_type).unwrap();
+        assert_eq!(nested.total_amount(), 5.into());
+        assert_eq!(container.borrow().liquid_amount(), 3.into());
+
+        for proof in base {
+            proof.drop();
+        }
+        assert_eq!(container.borrow().liquid_amount(), 5.into());
+        let cloned = nested.clone();
+        nested.drop();
+        for proof in composite {
+            proof.drop();
+        }
+        assert_eq!(container.borrow().liquid_amount(), 5.into());
+        cloned.drop();
+        assert_eq!(container.borrow().liquid_amount(), 10.into());
+        assert!(!container.borrow().is_locked());
+    }
+
+    #[test]
+    fn test_nested_proofs_can_be_dropped_in_any_order() {
+        let resource_type = ResourceType::Fungible { divisibility: 18 };
+        let container = new_container(10);
+        let base = [
+            new_proof(&container, BUCKET, LockedAmountOrIds::Amount(4.into())).unwrap(),
+            new_proof(&container, BUCKET, LockedAmountOrIds::Amount(7.into())).unwrap(),
+        ];
+        let composite = [Proof::compose(&base, RADIX_TOKEN, resource_type).unwrap()];
+        let nested =
+            Proof::compose_by_amount(&composite, 2.into(), RADIX_TOKEN, resource_type).unwrap();
+        let [p1, p2] = base;
+        let [c1] = composite;
+
+        p2.drop();
+        assert_eq!(container.borrow().liquid_amount(), 3.into());
+        c1.drop();
+        assert_eq!(container.borrow().liquid_amount(), 6.into());
+        p1.drop();
+        assert_eq!(container.borrow().liquid_amount(), 8.into());
+        nested.drop();
+        assert_eq!(container.borrow().liquid_amount(), 10.into());
+        assert!(!container.borrow().is_locked());
+    }
+
+    #[test]
+    fn test_nested_non_fungible_proofs() {
+        let resource_type = ResourceType::NonFungible;
+        let container = Rc::new(RefCell::new(ResourceContainer::new_non_fungible(
+            RADIX_TOKEN,
+            ids(&[1, 2, 3]),
+        )));
+        let base = [
+            new_proof(&container, BUCKET, LockedAmountOrIds::Ids(ids(&[1, 2]))).unwrap(),
+            new_proof(&container, BUCKET, LockedAmountOrIds::Ids(ids(&[2, 3]))).unwrap(),
+        ];
+        assert_eq!(container.borrow().liquid_ids(), Ok(ids(&[])));
+
+        let composite =
+            [Proof::compose_by_ids(&base, &ids(&[2]), RADIX_TOKEN, resource_type).unwrap()];
+        let nested = Proof::compose(&composite, RADIX_TOKEN, resource_type).unwrap();
+        assert_eq!(nested.total_ids(), Ok(ids(&[2])));
+
+        for proof in base {
+            proof.drop();
+        }
+        assert_eq!(container.borrow().liquid_ids(), Ok(ids(&[1, 3])));
+        for proof in composite {
+            proof.drop();
+        }
+        assert_eq!(container.borrow().liquid_ids(), Ok(ids(&[1, 3])));
+        nested.drop();
+        assert_eq!(container.borrow().liquid_ids(), Ok(ids(&[1, 2, 3])));
+        assert!(!container.borrow().is_locked());
+    }
+
+    #[test]
+    fn test_empty_proof_releases_its_lock() {
+        let container = new_container(10);
+
+        let result = new_proof(
+            &container,
+            BUCKET,
+            LockedAmountOrIds::Amount(Decimal::zero()),
+        );
+
+        assert_eq!(result.unwrap_err(), ProofError::EmptyProofNotAllowed);
+        assert!(!container.borrow().is_locked());
+    }
+
+    #[test]
+    fn test_composite_proof_records_its_provenance() {
+        let resource_type = ResourceType::Fungible { divisibility: 18 };
+        let bucket = new_container(3);
+        let vault = new_container(10);
+        let vault_id = ResourceContainerId::Vault((Hash([0u8; 32]), 0));
+        let base = [
+            new_proof(&bucket, BUCKET, LockedAmountOrIds::Amount(3.into())).unwrap(),
+            new_proof(
+                &vault,
+                vault_id.clone(),
+                LockedAmountOrIds::Amount(10.into()),
+            )
+            .unwrap(),
+        ];
+
+        let composite =
+            Proof::compose_by_amount(&base, 5.into(), RADIX_TOKEN, resource_type).unwrap();
+
+        let provenance = composite.provenance();
+        assert_eq!(provenance.len(), 2);
+        assert_eq!(provenance[&BUCKET].amount(), 3.into());
+        assert_eq!(provenance[&vault_id].amount(), 2.into());
+        composite.drop();
+        for proof in base {
+            proof.drop();
+        }
+    }
+
+    #[test]
+    fn test_composite_amount_counts_each_container_once() {
+        let resource_type = ResourceType::Fungible { divisibility: 18 };
+        let bucket = new_container(3);
+        let vault = new_container(10);
+        let vault_id = ResourceContainerId::Vault((Hash([0u8; 32]), 0));
+        let base = [
+            new_proof(&bucket, BUCKET, LockedAmountOrIds::Amount(3.into())).unwrap(),
+            new_proof(&vault, vault_id, LockedAmountOrIds::Amount(4.into())).unwrap(),
+        ];
+        let composite = Proof::compose(&base, RADIX_TOKEN, resource_type).unwrap();
+
+        let proofs = [&base[0], &base[1], &composite];
+        assert_eq!(Proof::compute_composite_amount(&proofs), 7.into());
+        assert_eq!(Proof::compute_composite_amount(&proofs[1..]), 7.into());
+        assert_eq!(Proof::compute_composite_amount(&proofs[..1]), 3.into());
+
+        composite.drop();
+        for proof in base {
+            proof.drop();
+        }
+    }
+}
@@ -1,6 +1,9 @@
-use colored::*;
+use crate::colored::*;
+use scrypto::address::Bech32Encoder;
+use scrypto::core::Notification;
 use scrypto::engine::types::*;
 use scrypto::rust::borrow::ToOwned;
+use scrypto::rust::collections::{BTreeMap, HashMap};
 use scrypto::rust::fmt;
 use scrypto::rust::format;
 use scrypto::rust::string::String;
@@ -8,7 +11,7 @@ use scrypto::rust::string::ToString;
 use scrypto::rust::vec::Vec;
 use scrypto::values::*;
 
-use crate::engine::CommitReceipt;
+use crate::engine::{CommitReceipt, NewEntity, ResourceAudit, SubstateReadStats};
 use crate::errors::*;
 use crate::model::*;
 
@@ -16,13 +19,38 @@ use crate::model::*;
 pub struct Receipt {
     pub commit_receipt: Option<CommitReceipt>,
     pub validated_transaction: ValidatedTransaction,
-    pub result: Result<(), RuntimeError>,
+    /// The hashes of the transaction, unless it is a composite one.
+    ///
+    /// Previews are executed without signatures, so their signed intent and payload hashes are
+    /// those of the unsigned transaction.
+    pub transaction_hashes: Option<TransactionHashes>,
+    pub result: Result<(), TransactionError>,
     pub outputs: Vec<ScryptoValue>,
     pub logs: Vec<(Level, String)>,
+    /// The events emitted by components, in emission order.
+    pub events: Vec<Notification>,
     pub new_package_addresses: Vec<PackageAddress>,
     pub new_component_addresses: Vec<ComponentAddress>,
     pub new_resource_addresses: Vec<ResourceAddress>,
+    /// The global entities committed by the transaction, i.e. packages, components, resources
+    /// and allowances, in creation order, with the instruction which created them.
+    pub new_global_entities: Vec<NewEntity>,
+    /// The vaults and lockers committed by the transaction, which are owned by components.
+    pub new_owned_entities: Vec<NewEntity>,
     pub execution_time: Option<u128>,
+    pub substate_read_stats: SubstateReadStats,
+    pub cost_units_consumed: u32,
+    pub cost_unit_breakdown: BTreeMap<&'static str, u32>,
+    /// The resource changes of the transaction, if it has been audited.
+    pub resource_audit: Option<ResourceAudit>,
+}
+
+/// Represents the outcome of running a `#[scrypto_test]` function.
+#[derive(Debug, Clone)]
+pub struct PackageTestResult {
+    pub test_name: String,
+    pub result: Result<(), RuntimeError>,
+    pub logs: Vec<(Level, String)>,
 }
 
 macro_rules! prefix {
@@ -35,48 +63,123 @@ macro_rules! prefix {
     };
 }
 
+impl Receipt {
+    /// Returns a displayable form of this receipt, with addresses encoded by the given encoder.
+    pub fn display<'a>(&'a self, encoder: &'a Bech32Encoder) -> ReceiptDisplay<'a> {
+        ReceiptDisplay {
+            receipt: self,
+            encoder: Some(encoder),
+        }
+    }
+}
+
+/// A receipt formatted for display, see `Receipt::display`.
+pub struct ReceiptDisplay<'a> {
+    receipt: &'a Receipt,
+    encoder: Option<&'a Bech32Encoder>,
+}
+
 impl fmt::Debug for Receipt {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(
+            &ReceiptDisplay {
+                receipt: self,
+                encoder: None,
+            },
+            f,
+        )
+    }
+}
+
+impl<'a> fmt::Display for ReceiptDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let receipt = self.receipt;
         write!(
             f,
             "{} {}",
             "Transaction Status:".bold().green(),
-            match &self.result {
+            match &receipt.result {
                 Ok(()) => "SUCCESS".blue(),
                 Err(e) => e.to_string().red(),
             }
             .bold()
         )?;
 
+        if let Some(hashes) = &receipt.transaction_hashes {
+            write!(
+                f,
+                "\n{} {}",
+                "Intent Hash:".bold().green(),
+                hashes.intent_hash
+            )?;
+            write!(
+                f,
+                "\n{} {}",
+                "Signed Intent Hash:".bold().green(),
+                hashes.signed_intent_hash
+            )?;
+            write!(
+                f,
+                "\n{} {}",
+                "Payload Hash:".bold().green(),
+                hashes.payload_hash
+            )?;
+        }
+
         write!(
             f,
             "\n{} {} ms",
             "Execution Time:".bold().green(),
-            self.execution_time
+            receipt
+                .execution_time
                 .map(|v| v.to_string())
                 .unwrap_or(String::from("?"))
         )?;
 
+        write!(
+            f,
+            "\n{} {}",
+            "Cost Units Consumed:".bold().green(),
+            receipt.cost_units_consumed
+        )?;
+
         write!(f, "\n{}", "Instructions:".bold().green())?;
-        for (i, inst) in self.validated_transaction.instructions.iter().enumerate() {
+        for (i, inst) in receipt
+            .validated_transaction
+            .instructions
+            .iter()
+            .enumerate()
+        {
             write!(
                 f,
                 "\n{} {}",
-                prefix!(i, self.validated_transaction.instructions),
+                prefix!(i, receipt.validated_transaction.instructions),
                 match inst {
                     ValidatedInstruction::PublishPackage { .. } => "PublishPackage {..}".to_owned(),
+                    ValidatedInstruction::PublishPackageWithOwner { owner_badge, .. } => format!(
+                        "PublishPackageWithOwner {{ owner_badge: {}, .. }}",
+                        match self.encoder {
+                            Some(encoder) => encoder.encode_resource_address(owner_badge),
+                            None => owner_badge.to_string(),
+                        }
+                    ),
                     i @ _ => format!("{:?}", i),
                 }
             )?;
         }
 
         write!(f, "\n{}", "Instruction Outputs:".bold().green())?;
-        for (i, result) in self.outputs.iter().enumerate() {
-            write!(f, "\n{} {:?}", prefix!(i, self.outputs), result)?;
+        for (i, result) in receipt.outputs.iter().enumerate() {
+            write!(
+                f,
+                "\n{} {}",
+                prefix!(i, receipt.outputs),
+                result.to_string_with_context(&HashMap::new(), &HashMap::new(), self.encoder)
+            )?;
         }
 
-        write!(f, "\n{} {}", "Logs:".bold().green(), self.logs.len())?;
-        for (i, (level, msg)) in self.logs.iter().enumerate() {
+        write!(f, "\n{} {}", "Logs:".bold().green(), receipt.logs.len())?;
+        for (i, (level, msg)) in receipt.logs.iter().enumerate() {
             let (l, m) = match level {
                 Level::Error => ("ERROR".red(), msg.red()),
                 Level::Warn => ("WARN".yellow(), msg.yellow()),
@@ -84,40 +187,49 @@ impl fmt::Debug for Receipt {
                 Level::Debug => ("DEBUG".cyan(), msg.cyan()),
                 Level::Trace => ("TRACE".normal(), msg.normal()),
             };
-            write!(f, "\n{} [{:5}] {}", prefix!(i, self.logs), l, m)?;
+            write!(f, "\n{} [{:5}] {}", prefix!(i, receipt.logs), l, m)?;
         }
 
         write!(
             f,
             "\n{} {}",
             "New Entities:".bold().green(),
-            self.new_package_addresses.len()
-                + self.new_component_addresses.len()
-                + self.new_resource_addresses.len()
+            receipt.new_package_addresses.len()
+                + receipt.new_component_addresses.len()
+                + receipt.new_resource_addresses.len()
         )?;
 
-        for (i, package_address) in self.new_package_addresses.iter().enumerate() {
+        for (i, package_address) in receipt.new_package_addresses.iter().enumerate() {
             write!(
                 f,
                 "\n{} Package: {}",
-                prefix!(i, self.new_package_addresses),
-                package_address
+                prefix!(i, receipt.new_package_addresses),
+                match self.encoder {
+                    Some(encoder) => encoder.encode_package_address(package_address),
+                    None => package_address.to_string(),
+                }
             )?;
         }
-        for (i, component_address) in self.new_component_addresses.iter().enumerate() {
+        for (i, component_address) in receipt.new_component_addresses.iter().enumerate() {
             write!(
                 f,
                 "\n{} Component: {}",
-                prefix!(i, self.new_component_addresses),
-                component_address
+                prefix!(i, receipt.new_component_addresses),
+                match self.encoder {
+                    Some(encoder) => encoder.encode_component_address(component_address),
+                    None => component_address.to_string(),
+                }
             )?;
         }
-        for (i, resource_address) in self.new_resource_addresses.iter().enumerate() {
+        for (i, resource_address) in receipt.new_resource_addresses.iter().enumerate() {
             write!(
                 f,
                 "\n{} Resource: {}",
-                prefix!(i, self.new_resource_addresses),
-                resource_address
+                prefix!(i, receipt.new_resource_addresses),
+                match self.encoder {
+                    Some(encoder) => encoder.encode_resource_address(resource_address),
+                    None => resource_address.to_string(),
+                }
             )?;
         }
 
@@ -3,6 +3,7 @@ use scrypto::engine::types::*;
 use scrypto::rust::collections::BTreeMap;
 use scrypto::rust::collections::BTreeSet;
 use scrypto::rust::collections::HashMap;
+use scrypto::rust::string::String;
 use scrypto::rust::string::ToString;
 
 /// Represents an error when manipulating resources in a container.
@@ -22,28 +23,94 @@ pub enum ResourceContainerError {
     ContainerLocked,
 }
 
-#[derive(Debug, TypeId, Encode, Decode)]
+/// Vaults persist their container, so it's encoded by variant name, like other persisted types.
+#[derive(Debug, TypeId, Encode)]
 pub enum ResourceContainer {
     Fungible {
         /// The resource address.
         resource_address: ResourceAddress,
         /// The resource divisibility.
         divisibility: u8,
-        /// The locked amounts and the corresponding times of being locked.
-        locked_amounts: BTreeMap<Decimal, usize>,
+        /// The amount held by each lock.
+        ///
+        /// Locks are held by proofs, which never outlive a transaction, so they're not persisted.
+        #[sbor(skip)]
+        locks: BTreeMap<u32, Decimal>,
         /// The liquid amount.
         liquid_amount: Decimal,
     },
     NonFungible {
         /// The resource address.
         resource_address: ResourceAddress,
-        /// The locked non-fungible ids and the corresponding times of being locked.
-        locked_ids: HashMap<NonFungibleId, usize>,
+        /// The non-fungible ids held by each lock, which aren't persisted either.
+        #[sbor(skip)]
+        locks: BTreeMap<u32, BTreeSet<NonFungibleId>>,
         /// The liquid non-fungible ids.
         liquid_ids: BTreeSet<NonFungibleId>,
     },
 }
 
+/// The number of encoded fields of a fungible container.
+const FUNGIBLE_FIELDS: usize = 3;
+
+/// The number of encoded fields of a non-fungible container.
+const NON_FUNGIBLE_FIELDS: usize = 2;
+
+impl Decode for ResourceContainer {
+    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+        // Containers persisted before locks were kept out of the encoding have an extra field
+        // with the lock counts, which are always empty at rest.
+        let name = String::decode_value(decoder)?;
+        match name.as_str() {
+            "Fungible" => {
+                let legacy = match decoder.read_len()? {
+                    FUNGIBLE_FIELDS => false,
+                    len if len == FUNGIBLE_FIELDS + 1 => true,
+                    actual => {
+                        return Err(DecodeError::InvalidLength {
+                            expected: FUNGIBLE_FIELDS,
+                            actual,
+                        })
+                    }
+                };
+                let resource_address = ResourceAddress::decode(decoder)?;
+                let divisibility = u8::decode(decoder)?;
+                if legacy {
+                    BTreeMap::<Decimal, usize>::decode(decoder)?;
+                }
+                Ok(Self::Fungible {
+                    resource_address,
+                    divisibility,
+                    locks: BTreeMap::new(),
+                    liquid_amount: Decimal::decode(decoder)?,
+                })
+            }
+            "NonFungible" => {
+                let legacy = match decoder.read_len()? {
+                    NON_FUNGIBLE_FIELDS => false,
+                    len if len == NON_FUNGIBLE_FIELDS + 1 => true,
+                    actual => {
+                        return Err(DecodeError::InvalidLength {
+                            expected: NON_FUNGIBLE_FIELDS,
+                            actual,
+                        })
+                    }
+                };
+                let resource_address = ResourceAddress::decode(decoder)?;
+                if legacy {
+                    HashMap::<NonFungibleId, usize>::decode(decoder)?;
+                }
+                Ok(Self::NonFungible {
+                    resource_address,
+                    locks: BTreeMap::new(),
+                    liquid_ids: BTreeSet::<NonFungibleId>::decode(decoder)?,
+                })
+            }
+            _ => Err(DecodeError::InvalidEnum(name)),
+        }
+    }
+}
+
 /// The locked amount or non-fungible IDs.
 ///
 /// Invariant: always consistent with resource fungibility.
@@ -53,6 +120,24 @@ pub enum LockedAmountOrIds {
     Ids(BTreeSet<NonFungibleId>),
 }
 
+/// A lock on the resources of a container, which keeps them from being withdrawn until it's
+/// released through `ResourceContainer::unlock`.
+///
+/// Locks of the same container may overlap: a fungible container holds the largest amount
+/// of all its locks, and a non-fungible container holds the union of their ids.
+#[derive(Debug)]
+pub struct ResourceLock {
+    id: u32,
+    locked: LockedAmountOrIds,
+}
+
+impl ResourceLock {
+    /// Returns the locked amount or non-fungible ids.
+    pub fn locked(&self) -> &LockedAmountOrIds {
+        &self.locked
+    }
+}
+
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub enum ResourceContainerId {
     Bucket(BucketId),
@@ -89,7 +174,7 @@ impl ResourceContainer {
         Self::Fungible {
             resource_address,
             divisibility,
-            locked_amounts: BTreeMap::new(),
+            locks: BTreeMap::new(),
             liquid_amount: amount,
         }
     }
@@ -100,7 +185,7 @@ impl ResourceContainer {
     ) -> Self {
         Self::NonFungible {
             resource_address,
-            locked_ids: HashMap::new(),
+            locks: BTreeMap::new(),
             liquid_ids: ids.clone(),
         }
     }
@@ -125,7 +210,17 @@ impl ResourceContainer {
 
         // update liquidity
         match self {
-            Self::Fungible { liquid_amount, .. } => {
+            Self::Fungible {
+                divisibility,
+                locks,
+                liquid_amount,
+                ..
+            } => {
+                // The divisibility can only change while there's no supply, so an empty container
+                // may have been created before the change and takes on that of the new resources.
+                if liquid_amount.is_zero() && locks.is_empty() && !other.is_empty() {
+                    *divisibility = other.resource_type().divisibility();
+                }
                 *liquid_amount += other.liquid_amount();
             }
             Self::NonFungible { liquid_ids, .. } => {
@@ -188,18 +283,18 @@ impl ResourceContainer {
     pub fn lock_by_amount(
         &mut self,
         amount: Decimal,
-    ) -> Result<LockedAmountOrIds, ResourceContainerError> {
+    ) -> Result<ResourceLock, ResourceContainerError> {
         // check amount granularity
         let divisibility = self.resource_type().divisibility();
         Self::check_amount(amount, divisibility)?;
 
         match self {
             Self::Fungible {
-                locked_amounts,
+                locks,
                 liquid_amount,
                 ..
             } => {
-                let max_locked = Self::largest_key(locked_amounts);
+                let max_locked = Self::largest_amount(locks);
                 if amount > max_locked {
                     let delta = amount - max_locked;
                     if *liquid_amount >= delta {
@@ -209,24 +304,25 @@ impl ResourceContainer {
                     }
                 }
 
-                locked_amounts.insert(
-                    amount,
-                    locked_amounts.get(&amount).cloned().unwrap_or(0) + 1,
-                );
+                let id = Self::next_lock_id(locks);
+                locks.insert(id, amount);
 
-                Ok(LockedAmountOrIds::Amount(amount))
+                Ok(ResourceLock {
+                    id,
+                    locked: LockedAmountOrIds::Amount(amount),
+                })
             }
             Self::NonFungible {
-                locked_ids,
-                liquid_ids,
-                ..
+                locks, liquid_ids, ..
             } => {
+                let locked_ids = Self::locked_id_set(locks);
                 if Decimal::from(locked_ids.len() + liquid_ids.len()) < amount {
                     return Err(ResourceContainerError::InsufficientBalance);
                 }
 
+                // prefer the non-fungibles which are already locked
                 let n: usize = amount.to_string().parse().unwrap();
-                let mut ids: BTreeSet<NonFungibleId> = locked_ids.keys().cloned().take(n).collect();
+                let mut ids: BTreeSet<NonFungibleId> = locked_ids.into_iter().take(n).collect();
                 if ids.len() < n {
                     ids.extend(liquid_ids.iter().cloned().take(n - ids.len()));
                 }
@@ -239,88 +335,81 @@ impl ResourceContainer {
     pub fn lock_by_ids(
         &mut self,
         ids: &BTreeSet<NonFungibleId>,
-    ) -> Result<LockedAmountOrIds, ResourceContainerError> {
+    ) -> Result<ResourceLock, ResourceContainerError> {
         match self {
             Self::NonFungible {
-                locked_ids,
-                liquid_ids,
-                ..
+                locks, liquid_ids, ..
             } => {
+                let locked_ids = Self::locked_id_set(locks);
+                if !ids
+                    .iter()
+                    .all(|id| liquid_ids.contains(id) || locked_ids.contains(id))
+                {
+                    return Err(ResourceContainerError::InsufficientBalance);
+                }
+
+                // move the liquid non-fungibles to locked
                 for id in ids {
-                    if liquid_ids.remove(id) {
-                        // if the non-fungible is liquid, move it to locked.
-                        locked_ids.insert(id.clone(), 1);
-                    } else if let Some(cnt) = locked_ids.get_mut(id) {
-                        // if the non-fungible is locked, increase the ref count.
-                        *cnt += 1;
-                    } else {
-                        return Err(ResourceContainerError::InsufficientBalance);
-                    }
+                    liquid_ids.remove(id);
                 }
 
-                Ok(LockedAmountOrIds::Ids(ids.clone()))
+                let id = Self::next_lock_id(locks);
+                locks.insert(id, ids.clone());
+
+                Ok(ResourceLock {
+                    id,
+                    locked: LockedAmountOrIds::Ids(ids.clone()),
+                })
             }
             Self::Fungible { .. } => Err(ResourceContainerError::NonFungibleOperationNotAllowed),
         }
     }
 
-    fn largest_key(map: &BTreeMap<Decimal, usize>) -> Decimal {
+    /// Releases a lock, returning the resources which are no longer held by any other lock
+    /// to the liquidity pool.
+    pub fn unlock(&mut self, lock: ResourceLock) {
+        match self {
+            Self::Fungible {
+                locks,
+                liquid_amount,
+                ..
+            } => {
+                let max_locked = Self::largest_amount(locks);
+                locks
+                    .remove(&lock.id)
+                    .expect("Attempted to unlock a lock that is not held on container");
+                let new_max_locked = Self::largest_amount(locks);
+                *liquid_amount += max_locked - new_max_locked;
+            }
+            Self::NonFungible {
+                locks, liquid_ids, ..
+            } => {
+                let ids = locks
+                    .remove(&lock.id)
+                    .expect("Attempted to unlock a lock that is not held on container");
+                let still_locked = Self::locked_id_set(locks);
+                liquid_ids.extend(ids.into_iter().filter(|id| !still_locked.contains(id)));
+            }
+        }
+    }
+
+    fn next_lock_id<T>(locks: &BTreeMap<u32, T>) -> u32 {
         // TODO: remove loop once `last_key_value` is stable.
-        map.keys().cloned().max().unwrap_or(Decimal::zero())
+        locks.keys().cloned().max().map_or(0, |id| id + 1)
     }
 
-    pub fn unlock(&mut self, resource: LockedAmountOrIds) {
-        match resource {
-            LockedAmountOrIds::Amount(amount) => match self {
-                Self::Fungible {
-                    locked_amounts,
-                    liquid_amount,
-                    ..
-                } => {
-                    let max_locked = Self::largest_key(locked_amounts);
-                    let count = locked_amounts
-                        .remove(&amount)
-                        .expect("Attempted to unlock an amount that is not locked in container");
-                    if count > 1 {
-                        locked_amounts.insert(amount, count - 1);
-                    } else {
-                        let new_max_locked = Self::largest_key(locked_amounts);
-                        *liquid_amount += max_locked - new_max_locked;
-                    }
-                }
-                Self::NonFungible { .. } => {
-                    panic!("Attempted to unlock amount of non-fungible resource")
-                }
-            },
-            LockedAmountOrIds::Ids(ids) => match self {
-                Self::NonFungible {
-                    locked_ids,
-                    liquid_ids,
-                    ..
-                } => {
-                    for id in ids {
-                        if let Some(cnt) = locked_ids.remove(&id) {
-                            if cnt > 1 {
-                                locked_ids.insert(id, cnt - 1);
-                            } else {
-                                liquid_ids.insert(id);
-                            }
-                        } else {
-                            panic!("Attempted to unlock a non-fungible that is not locked in container");
-                        }
-                    }
-                }
-                Self::Fungible { .. } => {
-                    panic!("Attempted to unlock non-fungibles of fungible resource")
-                }
-            },
-        }
+    fn largest_amount(locks: &BTreeMap<u32, Decimal>) -> Decimal {
+        locks.values().cloned().max().unwrap_or(Decimal::zero())
+    }
+
+    fn locked_id_set(locks: &BTreeMap<u32, BTreeSet<NonFungibleId>>) -> BTreeSet<NonFungibleId> {
+        locks.values().flatten().cloned().collect()
     }
 
     pub fn max_locked_amount(&self) -> Decimal {
         match self {
-            ResourceContainer::Fungible { locked_amounts, .. } => Self::largest_key(locked_amounts),
-            ResourceContainer::NonFungible { locked_ids, .. } => locked_ids.len().into(),
+            ResourceContainer::Fungible { locks, .. } => Self::largest_amount(locks),
+            ResourceContainer::NonFungible { locks, .. } => Self::locked_id_set(locks).len().into(),
         }
     }
 
@@ -329,9 +418,7 @@ impl ResourceContainer {
             ResourceContainer::Fungible { .. } => {
                 Err(ResourceContainerError::NonFungibleOperationNotAllowed)
             }
-            ResourceContainer::NonFungible { locked_ids, .. } => {
-                Ok(locked_ids.keys().cloned().collect())
-            }
+            ResourceContainer::NonFungible { locks, .. } => Ok(Self::locked_id_set(locks)),
         }
     }
 
@@ -362,8 +449,8 @@ impl ResourceContainer {
 
     pub fn is_locked(&self) -> bool {
         match self {
-            Self::Fungible { locked_amounts, .. } => !locked_amounts.is_empty(),
-            Self::NonFungible { locked_ids, .. } => !locked_ids.is_empty(),
+            Self::Fungible { locks, .. } => !locks.is_empty(),
+            Self::NonFungible { locks, .. } => !locks.is_empty(),
         }
     }
 
@@ -1,12 +1,18 @@
-use crate::engine::SystemApi;
-use crate::model::NonFungible;
+use crate::engine::{SizeLimitKind, SystemApi};
+use crate::errors::RuntimeError;
+use crate::model::{
+    NonFungible, MAX_NON_FUNGIBLE_METADATA_ENTRIES, MAX_NON_FUNGIBLE_METADATA_SIZE,
+};
 use sbor::*;
-use scrypto::buffer::scrypto_decode;
+use scrypto::buffer::{scrypto_decode, scrypto_encode};
 use scrypto::engine::types::*;
 use scrypto::prelude::AccessRule::{AllowAll, DenyAll};
 use scrypto::prelude::ResourceMethod::Withdraw;
 use scrypto::resource::Mutability::LOCKED;
-use scrypto::resource::ResourceMethod::{Burn, Mint, UpdateMetadata, UpdateNonFungibleData};
+use scrypto::resource::ResourceMethod::{
+    Burn, Mint, UpdateDivisibility, UpdateMetadata, UpdateNonFungibleData,
+    UpdateNonFungibleMetadata,
+};
 use scrypto::resource::*;
 use scrypto::rust::collections::*;
 use scrypto::rust::string::String;
@@ -28,14 +34,18 @@ macro_rules! convert_auth {
 #[derive(Debug, Clone, PartialEq)]
 pub enum ResourceManagerError {
     InvalidDivisibility,
+    TotalSupplyNotZero,
     InvalidAmount(Decimal, u8),
     InvalidResourceFlags(u64),
+    InvalidMetadata(String),
     InvalidMintPermission,
     ResourceTypeDoesNotMatch,
     MaxMintAmountExceeded,
     InvalidNonFungibleData,
     NonFungibleAlreadyExists(NonFungibleAddress),
     NonFungibleNotFound(NonFungibleAddress),
+    NonFungibleMetadataTooLarge,
+    AccessRuleSlotNotAllowed,
     InvalidRequestData(DecodeError),
     MethodNotFound(String),
     CouldNotCreateBucket,
@@ -68,7 +78,7 @@ impl MethodEntry {
             _ => return &MethodAuthorization::Unsupported,
         };
         match method.as_str() {
-            "lock" | "update" => &self.update_auth,
+            "lock" | "update" | "renounce" => &self.update_auth,
             _ => &MethodAuthorization::Unsupported,
         }
     }
@@ -80,9 +90,13 @@ impl MethodEntry {
     ) -> Result<ScryptoValue, ResourceManagerError> {
         match method {
             "lock" => self.lock(),
+            "renounce" => self.renounce(),
             "update" => {
                 let auth: AccessRule = scrypto_decode(&args[0].raw)
                     .map_err(|e| ResourceManagerError::InvalidRequestData(e))?;
+                if auth.is_slot() {
+                    return Err(ResourceManagerError::AccessRuleSlotNotAllowed);
+                }
                 self.update(auth);
             }
             _ => return Err(ResourceManagerError::MethodNotFound(method.to_string())),
@@ -98,32 +112,100 @@ impl MethodEntry {
     fn lock(&mut self) {
         self.update_auth = MethodAuthorization::DenyAll;
     }
+
+    /// Permanently denies the method, e.g. to renounce minting.
+    fn renounce(&mut self) {
+        self.auth = MethodAuthorization::DenyAll;
+        self.lock();
+    }
 }
 
 /// The definition of a resource.
-#[derive(Debug, Clone, TypeId, Encode, Decode)]
+#[derive(Debug, Clone, TypeId, Encode)]
 pub struct ResourceManager {
     resource_type: ResourceType,
-    metadata: HashMap<String, String>,
+    metadata: HashMap<String, MetadataValue>,
     method_table: HashMap<String, Option<ResourceMethod>>,
     authorization: HashMap<ResourceMethod, MethodEntry>,
     total_supply: Decimal,
 }
 
+/// The number of encoded fields of a resource manager.
+const RESOURCE_MANAGER_FIELDS: usize = 5;
+
+impl Decode for ResourceManager {
+    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+        decoder.check_len(RESOURCE_MANAGER_FIELDS)?;
+        Ok(Self {
+            resource_type: ResourceType::decode(decoder)?,
+            metadata: Self::decode_metadata(decoder)?,
+            method_table: HashMap::<String, Option<ResourceMethod>>::decode(decoder)?,
+            authorization: HashMap::<ResourceMethod, MethodEntry>::decode(decoder)?,
+            total_supply: Decimal::decode(decoder)?,
+        })
+    }
+}
+
+impl ResourceManager {
+    /// Decodes the metadata, which resources created before metadata was typed store as
+    /// strings. Legacy values of the URL keys become `Url` values, other values `String` ones.
+    fn decode_metadata(
+        decoder: &mut Decoder,
+    ) -> Result<HashMap<String, MetadataValue>, DecodeError> {
+        decoder.check_type(HashMap::<String, MetadataValue>::type_id())?;
+        decoder.check_type(String::type_id())?;
+        let value_type = decoder.read_type()?;
+        if value_type != MetadataValue::type_id() && value_type != String::type_id() {
+            return Err(DecodeError::InvalidType {
+                expected: Some(MetadataValue::type_id()),
+                actual: value_type,
+            });
+        }
+        let len = decoder.read_len()?;
+        let mut metadata = HashMap::new();
+        for _ in 0..len {
+            let key = String::decode_value(decoder)?;
+            let value = if value_type == MetadataValue::type_id() {
+                MetadataValue::decode_value(decoder)?
+            } else {
+                let value = String::decode_value(decoder)?;
+                match key.as_str() {
+                    METADATA_URL | METADATA_ICON_URL => MetadataValue::Url(value),
+                    _ => MetadataValue::String(value),
+                }
+            };
+            if metadata.insert(key, value).is_some() {
+                return Err(DecodeError::DuplicateEntry);
+            }
+        }
+        Ok(metadata)
+    }
+}
+
 impl ResourceManager {
     pub fn new(
         resource_type: ResourceType,
-        metadata: HashMap<String, String>,
+        metadata: HashMap<String, MetadataValue>,
         mut auth: HashMap<ResourceMethod, (AccessRule, Mutability)>,
     ) -> Result<Self, ResourceManagerError> {
+        Self::check_metadata(&metadata)?;
+        if auth.values().any(|(rule, mutability)| {
+            rule.is_slot() || matches!(mutability, Mutability::MUTABLE(rule) if rule.is_slot())
+        }) {
+            return Err(ResourceManagerError::AccessRuleSlotNotAllowed);
+        }
+
         let mut method_table: HashMap<String, Option<ResourceMethod>> = HashMap::new();
         method_table.insert("mint".to_string(), Some(Mint));
         method_table.insert("burn".to_string(), Some(Burn));
         method_table.insert("take_from_vault".to_string(), Some(Withdraw));
+        method_table.insert("create_vault_allowance".to_string(), Some(Withdraw));
         method_table.insert("put_into_vault".to_string(), Some(Deposit));
         method_table.insert("update_metadata".to_string(), Some(UpdateMetadata));
         if let ResourceType::NonFungible = resource_type {
             method_table.insert("take_non_fungibles_from_vault".to_string(), Some(Withdraw));
+        } else {
+            method_table.insert("update_divisibility".to_string(), Some(UpdateDivisibility));
         }
 
         for pub_method in [
@@ -137,11 +219,18 @@ impl ResourceManager {
             "put_into_bucket",
             "get_bucket_amount",
             "get_bucket_resource_address",
+            "get_bucket_locked_amount",
+            "get_bucket_liquid_amount",
+            "is_bucket_locked",
             "get_vault_amount",
             "get_vault_resource_address",
+            "get_vault_locked_amount",
+            "get_vault_liquid_amount",
+            "is_vault_locked",
             "create_vault_proof",
             "create_vault_proof_by_amount",
             "create_vault_proof_by_ids",
+            "revoke_vault_allowance",
         ] {
             method_table.insert(pub_method.to_string(), None);
         }
@@ -151,10 +240,15 @@ impl ResourceManager {
                 "update_non_fungible_mutable_data".to_string(),
                 Some(UpdateNonFungibleData),
             );
+            method_table.insert(
+                "update_non_fungible_metadata".to_string(),
+                Some(UpdateNonFungibleMetadata),
+            );
             for pub_method in [
                 "take_non_fungibles_from_bucket",
                 "non_fungible_exists",
                 "get_non_fungible",
+                "get_non_fungible_metadata",
                 "get_non_fungible_ids_in_bucket",
                 "get_non_fungible_ids_in_vault",
             ] {
@@ -170,6 +264,8 @@ impl ResourceManager {
             (Deposit, (AllowAll, LOCKED)),
             (UpdateMetadata, (DenyAll, LOCKED)),
             (UpdateNonFungibleData, (DenyAll, LOCKED)),
+            (UpdateNonFungibleMetadata, (DenyAll, LOCKED)),
+            (UpdateDivisibility, (DenyAll, LOCKED)),
         ] {
             let entry = auth.remove(&auth_entry_key).unwrap_or(default);
             authorization.insert(auth_entry_key, MethodEntry::new(entry));
@@ -213,7 +309,7 @@ impl ResourceManager {
         self.resource_type
     }
 
-    pub fn metadata(&self) -> &HashMap<String, String> {
+    pub fn metadata(&self) -> &HashMap<String, MetadataValue> {
         &self.metadata
     }
 
@@ -226,9 +322,9 @@ impl ResourceManager {
         mint_params: MintParams,
         self_address: ResourceAddress,
         system_api: &mut S,
-    ) -> Result<ResourceContainer, ResourceManagerError> {
+    ) -> Result<ResourceContainer, RuntimeError> {
         match mint_params {
-            MintParams::Fungible { amount } => self.mint_fungible(amount, self_address),
+            MintParams::Fungible { amount } => Ok(self.mint_fungible(amount, self_address)?),
             MintParams::NonFungible { entries } => {
                 self.mint_non_fungibles(entries, self_address, system_api)
             }
@@ -262,20 +358,24 @@ impl ResourceManager {
         }
     }
 
-    fn process_non_fungible_data(data: &[u8]) -> Result<ScryptoValue, ResourceManagerError> {
+    fn process_non_fungible_data<S: SystemApi>(
+        data: &[u8],
+        system_api: &S,
+    ) -> Result<ScryptoValue, RuntimeError> {
+        system_api.check_size_limit(SizeLimitKind::NonFungibleData, data.len())?;
         let validated = ScryptoValue::from_slice(data)
             .map_err(|_| ResourceManagerError::InvalidNonFungibleData)?;
         if !validated.bucket_ids.is_empty() {
-            return Err(ResourceManagerError::InvalidNonFungibleData);
+            return Err(ResourceManagerError::InvalidNonFungibleData.into());
         }
         if !validated.proof_ids.is_empty() {
-            return Err(ResourceManagerError::InvalidNonFungibleData);
+            return Err(ResourceManagerError::InvalidNonFungibleData.into());
         }
         if !validated.lazy_map_ids.is_empty() {
-            return Err(ResourceManagerError::InvalidNonFungibleData);
+            return Err(ResourceManagerError::InvalidNonFungibleData.into());
         }
         if !validated.vault_ids.is_empty() {
-            return Err(ResourceManagerError::InvalidNonFungibleData);
+            return Err(ResourceManagerError::InvalidNonFungibleData.into());
         }
         Ok(validated)
     }
@@ -285,10 +385,10 @@ impl ResourceManager {
         entries: HashMap<NonFungibleId, (Vec<u8>, Vec<u8>)>,
         self_address: ResourceAddress,
         system_api: &mut S,
-    ) -> Result<ResourceContainer, ResourceManagerError> {
+    ) -> Result<ResourceContainer, RuntimeError> {
         // check resource type
         if !matches!(self.resource_type, ResourceType::NonFungible) {
-            return Err(ResourceManagerError::ResourceTypeDoesNotMatch);
+            return Err(ResourceManagerError::ResourceTypeDoesNotMatch.into());
         }
 
         // check amount
@@ -298,7 +398,7 @@ impl ResourceManager {
         // It takes `1,701,411,835` mint operations to reach `Decimal::MAX`,
         // which will be impossible with metering.
         if amount > 100_000_000_000i128.into() {
-            return Err(ResourceManagerError::MaxMintAmountExceeded);
+            return Err(ResourceManagerError::MaxMintAmountExceeded.into());
         }
 
         self.total_supply += amount;
@@ -308,13 +408,13 @@ impl ResourceManager {
         for (id, data) in entries {
             let non_fungible_address = NonFungibleAddress::new(self_address, id.clone());
             if system_api.get_non_fungible(&non_fungible_address).is_some() {
-                return Err(ResourceManagerError::NonFungibleAlreadyExists(
-                    non_fungible_address,
-                ));
+                return Err(
+                    ResourceManagerError::NonFungibleAlreadyExists(non_fungible_address).into(),
+                );
             }
 
-            let immutable_data = Self::process_non_fungible_data(&data.0)?;
-            let mutable_data = Self::process_non_fungible_data(&data.1)?;
+            let immutable_data = Self::process_non_fungible_data(&data.0, system_api)?;
+            let mutable_data = Self::process_non_fungible_data(&data.1, system_api)?;
             let non_fungible = NonFungible::new(immutable_data.raw, mutable_data.raw);
 
             system_api.set_non_fungible(non_fungible_address, Some(non_fungible));
@@ -330,13 +430,66 @@ impl ResourceManager {
 
     fn update_metadata(
         &mut self,
-        new_metadata: HashMap<String, String>,
+        new_metadata: HashMap<String, MetadataValue>,
     ) -> Result<(), ResourceManagerError> {
+        Self::check_metadata(&new_metadata)?;
         self.metadata = new_metadata;
 
         Ok(())
     }
 
+    /// Changes the divisibility, which is only allowed while there's no supply, as every
+    /// container of the resource is empty then and no amount can become invalid.
+    fn update_divisibility(&mut self, divisibility: u8) -> Result<(), ResourceManagerError> {
+        if !matches!(self.resource_type, ResourceType::Fungible { .. }) {
+            return Err(ResourceManagerError::ResourceTypeDoesNotMatch);
+        }
+        if divisibility > DIVISIBILITY_MAXIMUM {
+            return Err(ResourceManagerError::InvalidDivisibility);
+        }
+        if !self.total_supply.is_zero() {
+            return Err(ResourceManagerError::TotalSupplyNotZero);
+        }
+        self.resource_type = ResourceType::Fungible { divisibility };
+
+        Ok(())
+    }
+
+    fn check_metadata<'a>(
+        metadata: impl IntoIterator<Item = (&'a String, &'a MetadataValue)>,
+    ) -> Result<(), ResourceManagerError> {
+        for (key, value) in metadata {
+            if !value.is_valid_for(key) {
+                return Err(ResourceManagerError::InvalidMetadata(key.clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_non_fungible_metadata(
+        metadata: &BTreeMap<String, MetadataValue>,
+    ) -> Result<(), ResourceManagerError> {
+        if metadata.len() > MAX_NON_FUNGIBLE_METADATA_ENTRIES
+            || scrypto_encode(metadata).len() > MAX_NON_FUNGIBLE_METADATA_SIZE
+        {
+            return Err(ResourceManagerError::NonFungibleMetadataTooLarge);
+        }
+        Self::check_metadata(metadata)
+    }
+
+    /// Checks that an initial supply is of the type of this resource and, if fungible, of its
+    /// divisibility, before the resource is created.
+    fn check_mint_params(&self, mint_params: &MintParams) -> Result<(), ResourceManagerError> {
+        if !mint_params.matches_type(&self.resource_type) {
+            return Err(ResourceManagerError::ResourceTypeDoesNotMatch);
+        }
+        match mint_params {
+            MintParams::Fungible { amount } => self.check_amount(*amount),
+            MintParams::NonFungible { .. } => Ok(()),
+        }
+    }
+
     fn check_amount(&self, amount: Decimal) -> Result<(), ResourceManagerError> {
         let divisibility = self.resource_type.divisibility();
 
@@ -351,7 +504,7 @@ impl ResourceManager {
         function: &str,
         args: Vec<ScryptoValue>,
         system_api: &mut S,
-    ) -> Result<ScryptoValue, ResourceManagerError> {
+    ) -> Result<ScryptoValue, RuntimeError> {
         match function {
             "create" => {
                 let resource_type = scrypto_decode(&args[0].raw)
@@ -363,6 +516,9 @@ impl ResourceManager {
                 let mint_params_maybe: Option<MintParams> = scrypto_decode(&args[3].raw)
                     .map_err(ResourceManagerError::InvalidRequestData)?;
                 let resource_manager = ResourceManager::new(resource_type, metadata, auth)?;
+                if let Some(mint_params) = &mint_params_maybe {
+                    resource_manager.check_mint_params(mint_params)?;
+                }
                 let resource_address = system_api.create_resource(resource_manager);
 
                 let bucket_id = if let Some(mint_params) = mint_params_maybe {
@@ -386,7 +542,7 @@ impl ResourceManager {
 
                 Ok(ScryptoValue::from_value(&(resource_address, bucket_id)))
             }
-            _ => Err(ResourceManagerError::MethodNotFound(function.to_string())),
+            _ => Err(ResourceManagerError::MethodNotFound(function.to_string()).into()),
         }
     }
 
@@ -396,7 +552,7 @@ impl ResourceManager {
         function: &str,
         mut args: Vec<ScryptoValue>,
         system_api: &mut S,
-    ) -> Result<ScryptoValue, ResourceManagerError> {
+    ) -> Result<ScryptoValue, RuntimeError> {
         match function {
             "method_auth" => {
                 let method: ResourceMethod = scrypto_decode(&args.remove(0).raw)
@@ -404,7 +560,7 @@ impl ResourceManager {
                 let method_entry = self.authorization.get_mut(&method).unwrap();
                 let method_entry_method: String = scrypto_decode(&args.remove(0).raw)
                     .map_err(|e| ResourceManagerError::InvalidRequestData(e))?;
-                method_entry.main(&method_entry_method, args)
+                Ok(method_entry.main(&method_entry_method, args)?)
             }
             "create_empty_bucket" => {
                 let container =
@@ -432,11 +588,17 @@ impl ResourceManager {
             "get_resource_type" => Ok(ScryptoValue::from_value(&self.resource_type)),
             "get_total_supply" => Ok(ScryptoValue::from_value(&self.total_supply)),
             "update_metadata" => {
-                let new_metadata: HashMap<String, String> = scrypto_decode(&args[0].raw)
+                let new_metadata: HashMap<String, MetadataValue> = scrypto_decode(&args[0].raw)
                     .map_err(|e| ResourceManagerError::InvalidRequestData(e))?;
                 self.update_metadata(new_metadata)?;
                 Ok(ScryptoValue::from_value(&()))
             }
+            "update_divisibility" => {
+                let divisibility: u8 = scrypto_decode(&args[0].raw)
+                    .map_err(|e| ResourceManagerError::InvalidRequestData(e))?;
+                self.update_divisibility(divisibility)?;
+                Ok(ScryptoValue::from_value(&()))
+            }
             "update_non_fungible_mutable_data" => {
                 let non_fungible_id: NonFungibleId = scrypto_decode(&args[0].raw)
                     .map_err(|e| ResourceManagerError::InvalidRequestData(e))?;
@@ -445,7 +607,7 @@ impl ResourceManager {
 
                 let non_fungible_address =
                     NonFungibleAddress::new(resource_address.clone(), non_fungible_id);
-                let data = Self::process_non_fungible_data(&new_mutable_data)?;
+                let data = Self::process_non_fungible_data(&new_mutable_data, system_api)?;
                 let mut non_fungible = system_api
                     .get_non_fungible(&non_fungible_address)
                     .cloned()
@@ -457,6 +619,36 @@ impl ResourceManager {
 
                 Ok(ScryptoValue::from_value(&()))
             }
+            "update_non_fungible_metadata" => {
+                let non_fungible_id: NonFungibleId = scrypto_decode(&args[0].raw)
+                    .map_err(|e| ResourceManagerError::InvalidRequestData(e))?;
+                let new_metadata: BTreeMap<String, MetadataValue> = scrypto_decode(&args[1].raw)
+                    .map_err(|e| ResourceManagerError::InvalidRequestData(e))?;
+                Self::check_non_fungible_metadata(&new_metadata)?;
+
+                let non_fungible_address =
+                    NonFungibleAddress::new(resource_address.clone(), non_fungible_id);
+                let mut non_fungible = system_api
+                    .get_non_fungible(&non_fungible_address)
+                    .cloned()
+                    .ok_or(ResourceManagerError::NonFungibleNotFound(
+                        non_fungible_address.clone(),
+                    ))?;
+                non_fungible.set_metadata(new_metadata);
+                system_api.set_non_fungible(non_fungible_address, Some(non_fungible));
+
+                Ok(ScryptoValue::from_value(&()))
+            }
+            "get_non_fungible_metadata" => {
+                let non_fungible_id: NonFungibleId = scrypto_decode(&args[0].raw)
+                    .map_err(|e| ResourceManagerError::InvalidRequestData(e))?;
+                let non_fungible_address =
+                    NonFungibleAddress::new(resource_address.clone(), non_fungible_id);
+                let non_fungible = system_api.get_non_fungible(&non_fungible_address).ok_or(
+                    ResourceManagerError::NonFungibleNotFound(non_fungible_address),
+                )?;
+                Ok(ScryptoValue::from_value(non_fungible.metadata()))
+            }
             "non_fungible_exists" => {
                 let non_fungible_id: NonFungibleId = scrypto_decode(&args[0].raw)
                     .map_err(|e| ResourceManagerError::InvalidRequestData(e))?;
@@ -478,7 +670,70 @@ impl ResourceManager {
                     non_fungible.mutable_data(),
                 ]))
             }
-            _ => Err(ResourceManagerError::MethodNotFound(function.to_string())),
+            _ => Err(ResourceManagerError::MethodNotFound(function.to_string()).into()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The layout of a resource manager before metadata was typed.
+    #[derive(TypeId, Encode)]
+    struct LegacyResourceManager {
+        resource_type: ResourceType,
+        metadata: HashMap<String, String>,
+        method_table: HashMap<String, Option<ResourceMethod>>,
+        authorization: HashMap<ResourceMethod, MethodEntry>,
+        total_supply: Decimal,
+    }
+
+    #[test]
+    fn should_decode_legacy_string_metadata() {
+        let mut metadata = HashMap::new();
+        metadata.insert(METADATA_NAME.to_string(), "Legacy".to_string());
+        metadata.insert(
+            METADATA_ICON_URL.to_string(),
+            "https://example.com/icon.png".to_string(),
+        );
+        let legacy = LegacyResourceManager {
+            resource_type: ResourceType::Fungible { divisibility: 18 },
+            metadata,
+            method_table: HashMap::new(),
+            authorization: HashMap::new(),
+            total_supply: Decimal::from(100),
+        };
+
+        let resource_manager: ResourceManager = scrypto_decode(&scrypto_encode(&legacy)).unwrap();
+
+        assert_eq!(
+            resource_manager.metadata.get(METADATA_NAME),
+            Some(&MetadataValue::String("Legacy".to_string()))
+        );
+        assert_eq!(
+            resource_manager.metadata.get(METADATA_ICON_URL),
+            Some(&MetadataValue::Url(
+                "https://example.com/icon.png".to_string()
+            ))
+        );
+        assert_eq!(resource_manager.total_supply, Decimal::from(100));
+    }
+
+    #[test]
+    fn should_decode_typed_metadata() {
+        let mut metadata = HashMap::new();
+        metadata.insert(METADATA_NAME.to_string(), MetadataValue::from("Typed"));
+        metadata.insert(
+            METADATA_TAGS.to_string(),
+            MetadataValue::from(vec!["a".to_string()]),
+        );
+        let resource_manager =
+            ResourceManager::new(ResourceType::NonFungible, metadata, HashMap::new()).unwrap();
+
+        let decoded: ResourceManager = scrypto_decode(&scrypto_encode(&resource_manager)).unwrap();
+
+        assert_eq!(decoded.metadata, resource_manager.metadata);
+        assert_eq!(decoded.method_table, resource_manager.method_table);
+    }
+}
@@ -0,0 +1,196 @@
+use sbor::*;
+use scrypto::buffer::scrypto_decode;
+use scrypto::constants::SYSTEM_TOKEN;
+use scrypto::core::SNodeRef;
+use scrypto::engine::types::*;
+use scrypto::rust::string::String;
+use scrypto::rust::string::ToString;
+use scrypto::rust::vec;
+use scrypto::rust::vec::Vec;
+use scrypto::values::ScryptoValue;
+
+use crate::engine::{EngineVersion, SystemApi};
+use crate::errors::RuntimeError;
+use crate::model::{
+    Component, HardAuthRule, HardProofRule, HardResourceOrNonFungible, MethodAuthorization,
+};
+
+/// The amount of XRD handed out by `free_xrd`.
+const FREE_XRD_AMOUNT: i128 = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SystemError {
+    InvalidRequestData(DecodeError),
+    MethodNotFound(String),
+    EngineVersionDowngrade {
+        current: EngineVersion,
+        requested: EngineVersion,
+    },
+}
+
+/// The state of the system component.
+#[derive(TypeId, Encode, Decode)]
+pub struct SystemComponentState {
+    pub xrd: scrypto::resource::Vault,
+}
+
+/// The system blueprint, including the XRD faucet, executed natively by the engine.
+///
+/// The system component is created at bootstrap; `SYSTEM_PACKAGE` is kept on ledger for its ABI.
+pub struct System;
+
+impl System {
+    fn decode_arg<T: Decode>(args: &[ScryptoValue], index: usize) -> Result<T, RuntimeError> {
+        let arg = args.get(index).ok_or(RuntimeError::IndexOutOfBounds {
+            index,
+            max: args.len(),
+        })?;
+        scrypto_decode(&arg.raw)
+            .map_err(|e| RuntimeError::SystemError(SystemError::InvalidRequestData(e)))
+    }
+
+    /// Returns the authorization of a method of the system component.
+    ///
+    /// Privileged methods require the system badge whatever the access rules stored with the
+    /// component, as ledgers bootstrapped before a method was added don't restrict it.
+    pub fn get_auth(function: &str) -> MethodAuthorization {
+        match function {
+            "set_epoch" | "set_engine_version" => {
+                MethodAuthorization::Protected(HardAuthRule::ProofRule(HardProofRule::This(
+                    HardResourceOrNonFungible::Resource(SYSTEM_TOKEN),
+                )))
+            }
+            _ => MethodAuthorization::AllowAll,
+        }
+    }
+
+    pub fn static_main<S: SystemApi>(
+        function: &str,
+        args: Vec<ScryptoValue>,
+        system_api: &mut S,
+    ) -> Result<ScryptoValue, RuntimeError> {
+        match function {
+            "publish_package" => {
+                let code: Vec<u8> = Self::decode_arg(&args, 0)?;
+                system_api.invoke_snode(
+                    SNodeRef::PackageStatic,
+                    "publish".to_string(),
+                    vec![ScryptoValue::from_value(&code)],
+                )
+            }
+            "publish_package_with_owner" => {
+                let code: Vec<u8> = Self::decode_arg(&args, 0)?;
+                let owner_badge: ResourceAddress = Self::decode_arg(&args, 1)?;
+                system_api.invoke_snode(
+                    SNodeRef::PackageStatic,
+                    "publish_with_owner".to_string(),
+                    vec![
+                        ScryptoValue::from_value(&code),
+                        ScryptoValue::from_value(&owner_badge),
+                    ],
+                )
+            }
+            "upgrade_package" => {
+                let package_address: PackageAddress = Self::decode_arg(&args, 0)?;
+                let code: Vec<u8> = Self::decode_arg(&args, 1)?;
+                system_api.invoke_snode(
+                    SNodeRef::PackageRef(package_address),
+                    "upgrade".to_string(),
+                    vec![ScryptoValue::from_value(&code)],
+                )
+            }
+            "set_package_metadata" => {
+                let package_address: PackageAddress = Self::decode_arg(&args, 0)?;
+                let name: String = Self::decode_arg(&args, 1)?;
+                let value: String = Self::decode_arg(&args, 2)?;
+                system_api.invoke_snode(
+                    SNodeRef::PackageRef(package_address),
+                    "set_metadata".to_string(),
+                    vec![
+                        ScryptoValue::from_value(&name),
+                        ScryptoValue::from_value(&value),
+                    ],
+                )
+            }
+            "set_method_enabled" => {
+                let component_address: ComponentAddress = Self::decode_arg(&args, 0)?;
+                let method: String = Self::decode_arg(&args, 1)?;
+                let enabled: bool = Self::decode_arg(&args, 2)?;
+                system_api.invoke_snode(
+                    SNodeRef::ComponentRef(component_address),
+                    "set_method_enabled".to_string(),
+                    vec![
+                        ScryptoValue::from_value(&method),
+                        ScryptoValue::from_value(&enabled),
+                    ],
+                )
+            }
+            "new_resource" => {
+                if args.len() != 4 {
+                    return Err(RuntimeError::IndexOutOfBounds {
+                        index: args.len(),
+                        max: 4,
+                    });
+                }
+                system_api.invoke_snode(SNodeRef::ResourceStatic, "create".to_string(), args)
+            }
+            "mint" => {
+                let amount: Decimal = Self::decode_arg(&args, 0)?;
+                let resource_address: ResourceAddress = Self::decode_arg(&args, 1)?;
+                system_api.invoke_snode(
+                    SNodeRef::ResourceRef(resource_address),
+                    "mint".to_string(),
+                    vec![ScryptoValue::from_value(&MintParams::fungible(amount))],
+                )
+            }
+            "burn" => {
+                let bucket: scrypto::resource::Bucket = Self::decode_arg(&args, 0)?;
+                system_api.invoke_snode(SNodeRef::Bucket(bucket.0), "burn".to_string(), vec![])
+            }
+            _ => Err(RuntimeError::SystemError(SystemError::MethodNotFound(
+                function.to_string(),
+            ))),
+        }
+    }
+
+    pub fn main<S: SystemApi>(
+        component: &mut Component,
+        function: &str,
+        args: Vec<ScryptoValue>,
+        system_api: &mut S,
+    ) -> Result<ScryptoValue, RuntimeError> {
+        let state: SystemComponentState = scrypto_decode(component.state()).unwrap();
+
+        match function {
+            "free_xrd" => system_api.invoke_snode(
+                SNodeRef::VaultRef(state.xrd.0),
+                "take_from_vault".to_string(),
+                vec![ScryptoValue::from_value(&Decimal::from(FREE_XRD_AMOUNT))],
+            ),
+            "set_epoch" => {
+                let epoch: u64 = Self::decode_arg(&args, 0)?;
+                system_api.set_epoch(epoch);
+                Ok(ScryptoValue::from_value(&()))
+            }
+            "set_engine_version" => {
+                let engine_version: EngineVersion = Self::decode_arg(&args, 0)?;
+                // Transactions are replayed under the version they were committed with, so
+                // the version only ever moves forward
+                let current = system_api.engine_version();
+                if engine_version < current {
+                    return Err(RuntimeError::SystemError(
+                        SystemError::EngineVersionDowngrade {
+                            current,
+                            requested: engine_version,
+                        },
+                    ));
+                }
+                system_api.set_engine_version(engine_version);
+                Ok(ScryptoValue::from_value(&()))
+            }
+            _ => Err(RuntimeError::SystemError(SystemError::MethodNotFound(
+                function.to_string(),
+            ))),
+        }
+    }
+}
@@ -2,6 +2,7 @@ use sbor::*;
 use scrypto::buffer::scrypto_encode;
 use scrypto::crypto::*;
 use scrypto::engine::types::*;
+use scrypto::resource::AccessRule;
 use scrypto::rust::collections::BTreeSet;
 use scrypto::rust::string::String;
 use scrypto::rust::vec;
@@ -12,12 +13,58 @@ use crate::engine::*;
 use crate::errors::*;
 use crate::model::{ValidatedInstruction, ValidatedTransaction};
 
+/// Represents the header of a transaction.
+#[derive(Debug, Clone, TypeId, Encode, Decode, PartialEq, Eq)]
+pub struct TransactionHeader {
+    /// The network the transaction is bound to. As it is signed, the transaction can't be
+    /// replayed on other networks.
+    pub network_id: u8,
+}
+
 /// Represents an unsigned transaction
 #[derive(Debug, Clone, TypeId, Encode, Decode, PartialEq, Eq)]
 pub struct Transaction {
+    pub header: TransactionHeader,
     pub instructions: Vec<Instruction>,
 }
 
+/// The version of the scheme transactions are hashed with.
+///
+/// Each hash is computed over the version, a byte identifying the kind of hash, and then:
+/// - intent hash: the SBOR encoding of the unsigned transaction;
+/// - signed intent hash: the intent hash, followed by the SBOR encoding of the signatures;
+/// - payload hash: the payload, i.e. `SignedTransaction::to_vec`;
+/// - composite intent hash: the SBOR encoding of the intent hashes of all parts, in order.
+///
+/// The parts of a composite transaction are signed over the version, the part signature kind,
+/// the composite intent hash and then the SBOR encoding of the part.
+///
+/// The scheme must not change without bumping the version, as hashes are used to reference
+/// transactions outside of the ledger.
+pub const TRANSACTION_HASHING_VERSION: u8 = 1;
+
+const INTENT_HASH_KIND: u8 = 1;
+const SIGNED_INTENT_HASH_KIND: u8 = 2;
+const PAYLOAD_HASH_KIND: u8 = 3;
+const COMPOSITE_INTENT_HASH_KIND: u8 = 4;
+const PART_SIGNATURE_KIND: u8 = 5;
+
+fn versioned_hash(kind: u8, parts: &[&[u8]]) -> Hash {
+    let mut bytes = vec![TRANSACTION_HASHING_VERSION, kind];
+    for part in parts {
+        bytes.extend_from_slice(part);
+    }
+    hash(bytes)
+}
+
+/// The hashes identifying a signed transaction, see `TRANSACTION_HASHING_VERSION`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionHashes {
+    pub intent_hash: Hash,
+    pub signed_intent_hash: Hash,
+    pub payload_hash: Hash,
+}
+
 /// Represents a signed transaction
 pub struct SignedTransaction {
     /// The unsigned transaction
@@ -26,9 +73,32 @@ pub struct SignedTransaction {
     pub signatures: Vec<(EcdsaPublicKey, EcdsaSignature)>,
 }
 
+/// Represents a transaction stitched together from independently signed partial transactions.
+///
+/// All parts share a single worktop and are executed atomically, in order. The signatures of
+/// a part only authorize the instructions of that part, and buckets and proofs can't cross
+/// part boundaries; resources are exchanged through the worktop.
+///
+/// Each part is signed over the composite intent hash, see `Transaction::sign_part`, so that
+/// it can't be executed on its own or within another composite transaction.
+pub struct CompositeTransaction {
+    pub parts: Vec<SignedTransaction>,
+}
+
+/// Represents a privileged transaction issued by the system, e.g. at genesis or epoch change.
+///
+/// System transactions carry no signatures. Instead, their auth zone starts with the system
+/// badge, which privileged methods require.
+pub struct SystemTransaction {
+    pub transaction: Transaction,
+}
+
 
 /// Represents an instruction
+///
+/// Instructions are encoded by variant index, so new instructions must be appended.
 #[derive(Debug, Clone, TypeId, Encode, Decode, PartialEq, Eq)]
+#[sbor(indexed)]
 pub enum Instruction {
     /// Takes resource from worktop.
     TakeFromWorktop { resource_address: ResourceAddress },
@@ -117,9 +187,12 @@ pub enum Instruction {
     },
 
     /// Calls a component method with all resources owned by the transaction.
+    ///
+    /// The buckets are passed as the first argument, followed by `args`.
     CallMethodWithAllResources {
         component_address: ComponentAddress,
         method: String,
+        args: Vec<Vec<u8>>,
     },
 
     /// Publishes a package.
@@ -129,6 +202,45 @@ pub enum Instruction {
     Nonce {
         nonce: u64, // TODO: may be replaced with substate id for entropy
     },
+
+    /// Calls a resource manager method, such as `mint` or `update_metadata`.
+    ///
+    /// Buckets and proofs in arguments moves from transaction context to the callee.
+    CallResourceMethod {
+        resource_address: ResourceAddress,
+        method: String,
+        args: Vec<Vec<u8>>,
+    },
+
+    /// Drops all named proofs and all proofs in the auth zone.
+    DropAllProofs,
+
+    /// Moves a bucket into a new locker owned by a component, which can be claimed by whoever
+    /// satisfies the claim rule.
+    CreateLocker {
+        component_address: ComponentAddress,
+        bucket_id: BucketId,
+        claim_rule: AccessRule,
+    },
+
+    /// Claims all resources in a locker and puts them onto worktop.
+    ClaimLocker {
+        component_address: ComponentAddress,
+        locker_id: LockerId,
+    },
+
+    /// Publishes a package, which can be upgraded by whoever presents the owner badge.
+    PublishPackageWithOwner {
+        code: Vec<u8>,
+        owner_badge: ResourceAddress,
+    },
+
+    /// Creates an account protected by the withdraw rule, and optionally deposits a bucket
+    /// into it.
+    CreateAccount {
+        withdraw_auth: AccessRule,
+        bucket_id: Option<BucketId>,
+    },
 }
 
 impl Transaction {
@@ -140,6 +252,13 @@ impl Transaction {
         hash(self.to_vec())
     }
 
+    /// Returns the hash of the intent, i.e. the unsigned transaction.
+    ///
+    /// It is known before signing, and stays the same however the transaction is signed.
+    pub fn intent_hash(&self) -> Hash {
+        versioned_hash(INTENT_HASH_KIND, &[&self.to_vec()])
+    }
+
     pub fn add_nonce(&mut self, nonce: u64) {
         self.instructions.push(Instruction::Nonce { nonce });
     }
@@ -158,17 +277,86 @@ impl Transaction {
             signatures: signatures,
         }
     }
+
+    /// Signs this transaction as a part of the composite transaction with the given intent
+    /// hash, see `CompositeTransaction::intent_hash_of`.
+    pub fn sign_part<'a, T: AsRef<[&'a EcdsaPrivateKey]>>(
+        self,
+        composite_intent_hash: Hash,
+        sks: T,
+    ) -> SignedTransaction {
+        let msg = self.part_message(&composite_intent_hash);
+        let signatures = sks
+            .as_ref()
+            .iter()
+            .map(|sk| (sk.public_key(), sk.sign(&msg)))
+            .collect();
+
+        SignedTransaction {
+            transaction: self,
+            signatures,
+        }
+    }
+
+    fn part_message(&self, composite_intent_hash: &Hash) -> Vec<u8> {
+        let mut msg = vec![TRANSACTION_HASHING_VERSION, PART_SIGNATURE_KIND];
+        msg.extend_from_slice(composite_intent_hash.as_ref());
+        msg.extend(self.to_vec());
+        msg
+    }
 }
 
 impl SignedTransaction {
+    /// Returns the payload of this transaction, i.e. the bytes submitted to the network.
+    pub fn to_vec(&self) -> Vec<u8> {
+        scrypto_encode(&(self.transaction.clone(), self.signatures.clone()))
+    }
+
+    /// Returns the hash of the intent, see `Transaction::intent_hash`.
+    pub fn intent_hash(&self) -> Hash {
+        self.transaction.intent_hash()
+    }
+
+    /// Returns the hash of the intent and its signatures.
+    pub fn signed_intent_hash(&self) -> Hash {
+        versioned_hash(
+            SIGNED_INTENT_HASH_KIND,
+            &[
+                self.intent_hash().as_ref(),
+                &scrypto_encode(&self.signatures),
+            ],
+        )
+    }
+
+    /// Returns the hash of the payload.
+    pub fn payload_hash(&self) -> Hash {
+        versioned_hash(PAYLOAD_HASH_KIND, &[&self.to_vec()])
+    }
+
+    /// Returns all the hashes identifying this transaction.
+    pub fn hashes(&self) -> TransactionHashes {
+        TransactionHashes {
+            intent_hash: self.intent_hash(),
+            signed_intent_hash: self.signed_intent_hash(),
+            payload_hash: self.payload_hash(),
+        }
+    }
+
     pub fn validate(&self) -> Result<ValidatedTransaction, TransactionValidationError> {
+        self.validate_signed(&self.transaction.to_vec())
+    }
+
+    /// Validates this transaction, whose signatures are over the given message.
+    fn validate_signed(
+        &self,
+        msg: &[u8],
+    ) -> Result<ValidatedTransaction, TransactionValidationError> {
         let mut instructions = vec![];
         let mut signers = vec![];
 
         // verify signature (may defer to runtime)
-        let msg = self.transaction.to_vec();
         for (pk, sig) in &self.signatures {
-            if !EcdsaVerifier::verify(&msg, pk, sig) {
+            if !EcdsaVerifier::verify(msg, pk, sig) {
                 return Err(TransactionValidationError::InvalidSignature);
             }
             signers.push(pk.clone());
@@ -300,6 +488,12 @@ impl SignedTransaction {
                         .map_err(TransactionValidationError::IdValidatorError)?;
                     instructions.push(ValidatedInstruction::DropProof { proof_id });
                 }
+                Instruction::DropAllProofs => {
+                    id_validator
+                        .drop_all_proofs()
+                        .map_err(TransactionValidationError::IdValidatorError)?;
+                    instructions.push(ValidatedInstruction::DropAllProofs);
+                }
                 Instruction::CallFunction {
                     package_address,
                     blueprint_name,
@@ -327,18 +521,73 @@ impl SignedTransaction {
                 Instruction::CallMethodWithAllResources {
                     component_address,
                     method,
+                    args,
                 } => {
+                    let args = Self::validate_args(args, &mut id_validator)?;
                     id_validator
                         .move_all_resources()
                         .map_err(TransactionValidationError::IdValidatorError)?;
                     instructions.push(ValidatedInstruction::CallMethodWithAllResources {
                         component_address,
                         method,
+                        args,
+                    });
+                }
+                Instruction::CallResourceMethod {
+                    resource_address,
+                    method,
+                    args,
+                } => {
+                    instructions.push(ValidatedInstruction::CallResourceMethod {
+                        resource_address,
+                        method,
+                        args: Self::validate_args(args, &mut id_validator)?,
+                    });
+                }
+                Instruction::CreateLocker {
+                    component_address,
+                    bucket_id,
+                    claim_rule,
+                } => {
+                    id_validator
+                        .drop_bucket(bucket_id)
+                        .map_err(TransactionValidationError::IdValidatorError)?;
+                    instructions.push(ValidatedInstruction::CreateLocker {
+                        component_address,
+                        bucket_id,
+                        claim_rule,
+                    });
+                }
+                Instruction::ClaimLocker {
+                    component_address,
+                    locker_id,
+                } => {
+                    instructions.push(ValidatedInstruction::ClaimLocker {
+                        component_address,
+                        locker_id,
+                    });
+                }
+                Instruction::CreateAccount {
+                    withdraw_auth,
+                    bucket_id,
+                } => {
+                    if let Some(bucket_id) = bucket_id {
+                        id_validator
+                            .drop_bucket(bucket_id)
+                            .map_err(TransactionValidationError::IdValidatorError)?;
+                    }
+                    instructions.push(ValidatedInstruction::CreateAccount {
+                        withdraw_auth,
+                        bucket_id,
                     });
                 }
                 Instruction::PublishPackage { code } => {
                     instructions.push(ValidatedInstruction::PublishPackage { code });
                 }
+                Instruction::PublishPackageWithOwner { code, owner_badge } => {
+                    instructions
+                        .push(ValidatedInstruction::PublishPackageWithOwner { code, owner_badge });
+                }
                 Instruction::Nonce { .. } => {
                     // TODO: validate nonce
                 }
@@ -347,6 +596,8 @@ impl SignedTransaction {
 
         Ok(ValidatedTransaction {
             raw_hash: self.transaction.raw_hash(),
+            network_id: self.transaction.header.network_id,
+            hashes: Some(self.hashes()),
             instructions,
             signers,
         })
@@ -379,10 +630,84 @@ impl SignedTransaction {
     }
 }
 
+impl CompositeTransaction {
+    pub fn new(parts: Vec<SignedTransaction>) -> Self {
+        Self { parts }
+    }
+
+    /// Returns the hash of the composite intent, i.e. the unsigned parts in order, which each
+    /// part is signed over.
+    pub fn intent_hash_of(parts: &[&Transaction]) -> Hash {
+        let intent_hashes: Vec<Hash> = parts.iter().map(|part| part.intent_hash()).collect();
+        versioned_hash(
+            COMPOSITE_INTENT_HASH_KIND,
+            &[&scrypto_encode(&intent_hashes)],
+        )
+    }
+
+    /// Returns the hash of the composite intent, see `intent_hash_of`.
+    pub fn intent_hash(&self) -> Hash {
+        let parts: Vec<&Transaction> = self.parts.iter().map(|part| &part.transaction).collect();
+        Self::intent_hash_of(&parts)
+    }
+
+    /// Validates all parts, whose signatures must be over the composite intent hash.
+    ///
+    /// The composite transaction has no signers of its own; each part is only authorized by
+    /// its own signers, from its `StartPartialTransaction` instruction onwards.
+    pub fn validate(&self) -> Result<ValidatedTransaction, TransactionValidationError> {
+        if self.parts.is_empty() {
+            return Err(TransactionValidationError::EmptyCompositeTransaction);
+        }
+
+        let intent_hash = self.intent_hash();
+        let network_id = self.parts[0].transaction.header.network_id;
+        let mut instructions = vec![];
+        let mut part_hashes = Vec::new();
+        for part in &self.parts {
+            let validated = part.validate_signed(&part.transaction.part_message(&intent_hash))?;
+            if validated.network_id != network_id {
+                return Err(TransactionValidationError::InvalidNetwork {
+                    expected: network_id,
+                    actual: validated.network_id,
+                });
+            }
+            instructions.push(ValidatedInstruction::StartPartialTransaction {
+                signers: validated.signers.clone(),
+            });
+            instructions.extend(validated.instructions);
+            part_hashes.extend(validated.raw_hash.0);
+        }
+
+        Ok(ValidatedTransaction {
+            raw_hash: hash(part_hashes),
+            network_id,
+            hashes: None,
+            instructions,
+            signers: Vec::new(),
+        })
+    }
+}
+
+impl SystemTransaction {
+    pub fn new(transaction: Transaction) -> Self {
+        Self { transaction }
+    }
+
+    pub fn validate(&self) -> Result<ValidatedTransaction, TransactionValidationError> {
+        SignedTransaction {
+            transaction: self.transaction.clone(),
+            signatures: Vec::new(),
+        }
+        .validate()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
+    use scrypto::address::SIMULATOR_NETWORK_ID;
     use scrypto::engine::types::ComponentAddress;
     use scrypto::rust::borrow::ToOwned;
     use scrypto::rust::marker::PhantomData;
@@ -392,6 +717,9 @@ mod tests {
         assert_eq!(
             SignedTransaction {
                 transaction: Transaction {
+                    header: TransactionHeader {
+                        network_id: SIMULATOR_NETWORK_ID,
+                    },
                     instructions: vec![Instruction::CallMethod {
                         component_address: ComponentAddress([1u8; 26]),
                         method: "test".to_owned(),
@@ -411,11 +739,191 @@ mod tests {
         );
     }
 
+    #[test]
+    fn intent_hash_should_not_depend_on_signatures() {
+        let transaction = Transaction {
+            header: TransactionHeader {
+                network_id: SIMULATOR_NETWORK_ID,
+            },
+            instructions: vec![Instruction::Nonce { nonce: 5 }],
+        };
+        let sk = EcdsaPrivateKey::from_bytes(&[1u8; 32]).unwrap();
+        let unsigned = transaction.clone().sign([]);
+        let signed = transaction.clone().sign([&sk]);
+
+        assert_eq!(unsigned.intent_hash(), transaction.intent_hash());
+        assert_eq!(signed.intent_hash(), transaction.intent_hash());
+        assert_ne!(signed.signed_intent_hash(), unsigned.signed_intent_hash());
+        assert_ne!(signed.payload_hash(), unsigned.payload_hash());
+        assert_ne!(signed.payload_hash(), signed.signed_intent_hash());
+        assert_ne!(transaction.intent_hash(), transaction.raw_hash());
+    }
+
+    #[test]
+    fn should_reject_part_outside_of_its_composite_transaction() {
+        let sk = EcdsaPrivateKey::from_bytes(&[1u8; 32]).unwrap();
+        let new_part = |nonce| Transaction {
+            header: TransactionHeader {
+                network_id: SIMULATOR_NETWORK_ID,
+            },
+            instructions: vec![Instruction::Nonce { nonce }],
+        };
+        let (part, other_part) = (new_part(1), new_part(2));
+        let intent_hash = CompositeTransaction::intent_hash_of(&[&part, &other_part]);
+        let signed_part = part.sign_part(intent_hash, [&sk]);
+        let composite =
+            CompositeTransaction::new(vec![signed_part, other_part.sign_part(intent_hash, [])]);
+        assert!(composite.validate().is_ok());
+        let signed_part = composite.parts.into_iter().next().unwrap();
+
+        assert_eq!(
+            signed_part.validate().err(),
+            Some(TransactionValidationError::InvalidSignature)
+        );
+        let rewrapped = CompositeTransaction::new(vec![
+            new_part(3).sign([]),
+            SignedTransaction {
+                transaction: signed_part.transaction.clone(),
+                signatures: signed_part.signatures.clone(),
+            },
+        ]);
+        assert_eq!(
+            rewrapped.validate().err(),
+            Some(TransactionValidationError::InvalidSignature)
+        );
+        let alone = CompositeTransaction::new(vec![signed_part]);
+        assert_eq!(
+            alone.validate().err(),
+            Some(TransactionValidationError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn composite_transaction_should_only_have_signers_of_each_part() {
+        let sk = EcdsaPrivateKey::from_bytes(&[1u8; 32]).unwrap();
+        let part = Transaction {
+            header: TransactionHeader {
+                network_id: SIMULATOR_NETWORK_ID,
+            },
+            instructions: vec![Instruction::Nonce { nonce: 1 }],
+        };
+        let intent_hash = CompositeTransaction::intent_hash_of(&[&part]);
+
+        let validated = CompositeTransaction::new(vec![part.sign_part(intent_hash, [&sk])])
+            .validate()
+            .unwrap();
+
+        assert!(validated.signers.is_empty());
+        assert_eq!(
+            validated.instructions[0],
+            ValidatedInstruction::StartPartialTransaction {
+                signers: vec![sk.public_key()]
+            }
+        );
+    }
+
+    #[test]
+    fn instructions_should_keep_their_variant_index() {
+        let resource_address = ResourceAddress([3u8; 26]);
+        let component_address = ComponentAddress([2u8; 26]);
+        let ids = BTreeSet::from([NonFungibleId::from_u32(1)]);
+        let instructions = vec![
+            Instruction::TakeFromWorktop { resource_address },
+            Instruction::TakeFromWorktopByAmount {
+                amount: Decimal::one(),
+                resource_address,
+            },
+            Instruction::TakeFromWorktopByIds {
+                ids: ids.clone(),
+                resource_address,
+            },
+            Instruction::ReturnToWorktop { bucket_id: 512 },
+            Instruction::AssertWorktopContains { resource_address },
+            Instruction::AssertWorktopContainsByAmount {
+                amount: Decimal::one(),
+                resource_address,
+            },
+            Instruction::AssertWorktopContainsByIds {
+                ids: ids.clone(),
+                resource_address,
+            },
+            Instruction::PopFromAuthZone,
+            Instruction::PushToAuthZone { proof_id: 513 },
+            Instruction::ClearAuthZone,
+            Instruction::CreateProofFromAuthZone { resource_address },
+            Instruction::CreateProofFromAuthZoneByAmount {
+                amount: Decimal::one(),
+                resource_address,
+            },
+            Instruction::CreateProofFromAuthZoneByIds {
+                ids: ids.clone(),
+                resource_address,
+            },
+            Instruction::CreateProofFromBucket { bucket_id: 512 },
+            Instruction::CloneProof { proof_id: 513 },
+            Instruction::DropProof { proof_id: 513 },
+            Instruction::CallFunction {
+                package_address: PackageAddress([1u8; 26]),
+                blueprint_name: "Test".to_owned(),
+                function: "new".to_owned(),
+                args: vec![],
+            },
+            Instruction::CallMethod {
+                component_address,
+                method: "test".to_owned(),
+                args: vec![],
+            },
+            Instruction::CallMethodWithAllResources {
+                component_address,
+                method: "deposit_batch".to_owned(),
+                args: vec![],
+            },
+            Instruction::PublishPackage { code: vec![] },
+            Instruction::Nonce { nonce: 1 },
+            Instruction::CallResourceMethod {
+                resource_address,
+                method: "mint".to_owned(),
+                args: vec![],
+            },
+            Instruction::DropAllProofs,
+            Instruction::CreateLocker {
+                component_address,
+                bucket_id: 512,
+                claim_rule: AccessRule::AllowAll,
+            },
+            Instruction::ClaimLocker {
+                component_address,
+                locker_id: (Hash([0u8; 32]), 1024),
+            },
+            Instruction::PublishPackageWithOwner {
+                code: vec![],
+                owner_badge: resource_address,
+            },
+            Instruction::CreateAccount {
+                withdraw_auth: AccessRule::AllowAll,
+                bucket_id: None,
+            },
+        ];
+
+        // Signed transactions refer to instructions by index, so existing indices are pinned
+        for (index, instruction) in instructions.iter().enumerate() {
+            assert_eq!(
+                scrypto_encode(instruction)[..2],
+                [sbor::type_id::TYPE_INDEXED_ENUM, index as u8],
+                "{:?}",
+                instruction
+            );
+        }
+    }
+
     #[test]
     fn should_reject_transaction_passing_lazy_map() {
         assert_eq!(
             SignedTransaction {
                 transaction: Transaction {
+                    header: TransactionHeader {
+                        network_id: SIMULATOR_NETWORK_ID,
+                    },
                     instructions: vec![Instruction::CallMethod {
                         component_address: ComponentAddress([1u8; 26]),
                         method: "test".to_owned(),
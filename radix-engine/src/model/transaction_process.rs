@@ -1,7 +1,9 @@
+use scrypto::constants::SYSTEM_COMPONENT;
 use scrypto::core::SNodeRef;
 use scrypto::engine::types::*;
 use scrypto::prelude::ScryptoActor;
-use scrypto::rust::collections::{HashMap};
+use scrypto::rust::collections::BTreeMap;
+use scrypto::rust::mem;
 use scrypto::rust::string::ToString;
 use scrypto::rust::vec;
 use scrypto::rust::vec::Vec;
@@ -13,20 +15,53 @@ use crate::model::{ValidatedInstruction, ValidatedTransaction};
 
 pub struct TransactionProcess {
     transaction: ValidatedTransaction,
-    proof_id_mapping: HashMap<ProofId, ProofId>,
-    bucket_id_mapping: HashMap<BucketId, BucketId>,
+    proof_id_mapping: BTreeMap<ProofId, ProofId>,
+    bucket_id_mapping: BTreeMap<BucketId, BucketId>,
     outputs: Vec<ScryptoValue>,
     id_allocator: IdAllocator,
+    signature_proofs_cleared_after: Option<usize>,
+    holds_system_badge: bool,
 }
 
 impl TransactionProcess {
     pub fn new(transaction: ValidatedTransaction) -> Self {
         Self {
             transaction,
-            proof_id_mapping: HashMap::new(),
-            bucket_id_mapping: HashMap::new(),
+            proof_id_mapping: BTreeMap::new(),
+            bucket_id_mapping: BTreeMap::new(),
             outputs: Vec::new(),
             id_allocator: IdAllocator::new(IdSpace::Transaction),
+            signature_proofs_cleared_after: None,
+            holds_system_badge: false,
+        }
+    }
+
+    /// Drops the signature proofs from the auth zone once the instruction at the given index
+    /// has been executed, so that later instructions can't use them.
+    pub fn with_signature_proofs_cleared_after(mut self, instruction_index: Option<usize>) -> Self {
+        self.signature_proofs_cleared_after = instruction_index;
+        self
+    }
+
+    /// Marks the auth zone as holding the system badge, which is dropped before the first
+    /// instruction which calls into a blueprint or a component other than the system component.
+    pub fn with_system_badge(mut self, holds_system_badge: bool) -> Self {
+        self.holds_system_badge = holds_system_badge;
+        self
+    }
+
+    /// Returns whether the instruction runs code which the system badge mustn't reach.
+    fn calls_user_code(inst: &ValidatedInstruction) -> bool {
+        match inst {
+            ValidatedInstruction::CallFunction { .. }
+            | ValidatedInstruction::CreateAccount { .. } => true,
+            ValidatedInstruction::CallMethod {
+                component_address, ..
+            }
+            | ValidatedInstruction::CallMethodWithAllResources {
+                component_address, ..
+            } => *component_address != SYSTEM_COMPONENT,
+            _ => false,
         }
     }
 
@@ -49,7 +84,21 @@ impl TransactionProcess {
     }
 
     pub fn main<S: SystemApi>(&mut self, system_api: &mut S) -> Result<ScryptoValue, RuntimeError> {
-        for inst in &self.transaction.instructions.clone() {
+        let tx_base_fee = system_api.fee_table().tx_base_fee();
+        system_api
+            .consume_cost_units(tx_base_fee, "tx_base_fee")
+            .map_err(RuntimeError::CostingError)?;
+
+        for (index, inst) in self.transaction.instructions.clone().iter().enumerate() {
+            system_api.set_instruction_index(index);
+            if self.holds_system_badge && Self::calls_user_code(inst) {
+                system_api.invoke_snode(
+                    SNodeRef::AuthZoneRef,
+                    "clear_system_proofs".to_string(),
+                    vec![]
+                )?;
+                self.holds_system_badge = false;
+            }
             let result = match inst {
                 ValidatedInstruction::TakeFromWorktop { resource_address } => {
                     self.id_allocator.new_bucket_id()
@@ -293,6 +342,16 @@ impl TransactionProcess {
                         })
                         .unwrap_or(Err(ProofNotFound(*proof_id)))
                 },
+                ValidatedInstruction::DropAllProofs => {
+                    for (_, real_id) in mem::take(&mut self.proof_id_mapping) {
+                        system_api.invoke_snode(
+                            SNodeRef::Proof(real_id),
+                            "drop".to_string(),
+                            vec![]
+                        )?;
+                    }
+                    system_api.invoke_snode(SNodeRef::AuthZoneRef, "clear".to_string(), vec![])
+                },
                 ValidatedInstruction::CallFunction {
                     package_address,
                     blueprint_name,
@@ -360,13 +419,43 @@ impl TransactionProcess {
                             Ok(result)
                         })
                 },
+                ValidatedInstruction::CallResourceMethod {
+                    resource_address,
+                    method,
+                    args,
+                } => {
+                    self.replace_ids(args.clone())
+                        .and_then(|args|
+                            system_api.invoke_snode(
+                                SNodeRef::ResourceRef(*resource_address),
+                                method.to_string(),
+                                args
+                            )
+                        )
+                        .and_then(|result| {
+                            // Auto move into worktop
+                            for (bucket_id, _) in &result.bucket_ids {
+                                system_api.invoke_snode(
+                                    SNodeRef::WorktopRef,
+                                    "put".to_string(),
+                                    vec![ScryptoValue::from_value(&scrypto::resource::Bucket(*bucket_id))]
+                                ).unwrap(); // TODO: Remove unwrap
+                            }
+                            Ok(result)
+                        })
+                },
                 ValidatedInstruction::CallMethodWithAllResources {
                     component_address,
                     method,
+                    args,
                 } => {
-                    system_api.invoke_snode(SNodeRef::AuthZoneRef, "clear".to_string(), vec![])
-                        .and_then(|_| {
-                            for (_, real_id) in self.proof_id_mapping.drain() {
+                    self.replace_ids(args.clone())
+                        .and_then(|args|
+                            system_api.invoke_snode(SNodeRef::AuthZoneRef, "clear".to_string(), vec![])
+                                .map(|_| args)
+                        )
+                        .and_then(|args| {
+                            for (_, real_id) in mem::take(&mut self.proof_id_mapping) {
                                 system_api.invoke_snode(
                                     SNodeRef::Proof(real_id),
                                     "drop".to_string(),
@@ -374,22 +463,88 @@ impl TransactionProcess {
                                 ).unwrap();
                             }
                             system_api.invoke_snode(SNodeRef::WorktopRef, "drain".to_string(), vec![])
+                                .map(|result| (result, args))
                         })
-                        .and_then(|result| {
+                        .and_then(|(result, args)| {
                             let mut buckets = Vec::new();
                             for (bucket_id, _) in result.bucket_ids {
                                 buckets.push(scrypto::resource::Bucket(bucket_id));
                             }
-                            for (_, real_id) in self.bucket_id_mapping.drain() {
+                            for (_, real_id) in mem::take(&mut self.bucket_id_mapping) {
                                 buckets.push(scrypto::resource::Bucket(real_id));
                             }
+                            let mut call_args = vec![ScryptoValue::from_value(&buckets)];
+                            call_args.extend(args);
                             system_api.invoke_snode(
                                 SNodeRef::Scrypto(ScryptoActor::Component(*component_address)),
                                 method.to_string(),
-                                vec![ScryptoValue::from_value(&buckets)],
+                                call_args,
                             )
                         })
                 },
+                ValidatedInstruction::CreateLocker {
+                    component_address,
+                    bucket_id,
+                    claim_rule,
+                } => {
+                    self.bucket_id_mapping.remove(bucket_id)
+                        .map(|real_id| {
+                            system_api.invoke_snode(
+                                SNodeRef::LockerStatic,
+                                "create".to_string(),
+                                vec![
+                                    ScryptoValue::from_value(component_address),
+                                    ScryptoValue::from_value(&scrypto::resource::Bucket(real_id)),
+                                    ScryptoValue::from_value(claim_rule),
+                                ]
+                            )
+                        })
+                        .unwrap_or(Err(RuntimeError::BucketNotFound(*bucket_id)))
+                },
+                ValidatedInstruction::ClaimLocker {
+                    component_address,
+                    locker_id,
+                } => {
+                    system_api.invoke_snode(
+                        SNodeRef::LockerRef(*component_address, *locker_id),
+                        "claim".to_string(),
+                        vec![],
+                    )
+                    .and_then(|result| {
+                        // Auto move into worktop
+                        for (bucket_id, _) in &result.bucket_ids {
+                            system_api.invoke_snode(
+                                SNodeRef::WorktopRef,
+                                "put".to_string(),
+                                vec![ScryptoValue::from_value(&scrypto::resource::Bucket(*bucket_id))]
+                            ).unwrap(); // TODO: Remove unwrap
+                        }
+                        Ok(result)
+                    })
+                },
+                ValidatedInstruction::CreateAccount {
+                    withdraw_auth,
+                    bucket_id,
+                } => {
+                    let (function, args) = match bucket_id {
+                        Some(bucket_id) => match self.bucket_id_mapping.remove(bucket_id) {
+                            Some(real_id) => (
+                                "new_with_resource",
+                                vec![
+                                    ScryptoValue::from_value(withdraw_auth),
+                                    ScryptoValue::from_value(&scrypto::resource::Bucket(real_id)),
+                                ],
+                            ),
+                            None => return Err(RuntimeError::BucketNotFound(*bucket_id)),
+                        },
+                        None => ("new", vec![ScryptoValue::from_value(withdraw_auth)]),
+                    };
+                    system_api.invoke_snode(
+                        SNodeRef::Scrypto(ScryptoActor::Blueprint(ACCOUNT_PACKAGE, "Account".to_string())),
+                        function.to_string(),
+                        args,
+                    )
+                },
                 ValidatedInstruction::PublishPackage { code } => {
                     system_api.invoke_snode(
                         SNodeRef::PackageStatic,
@@ -397,8 +552,54 @@ impl TransactionProcess {
                         vec![ScryptoValue::from_value(code)],
                     )
                 },
+                ValidatedInstruction::PublishPackageWithOwner { code, owner_badge } => {
+                    system_api.invoke_snode(
+                        SNodeRef::PackageStatic,
+                        "publish_with_owner".to_string(),
+                        vec![
+                            ScryptoValue::from_value(code),
+                            ScryptoValue::from_value(owner_badge),
+                        ],
+                    )
+                },
+                ValidatedInstruction::StartPartialTransaction { signers } => {
+                    if !self.bucket_id_mapping.is_empty() {
+                        return Err(RuntimeError::BucketsCrossPartialTransaction);
+                    }
+                    system_api.invoke_snode(SNodeRef::AuthZoneRef, "clear".to_string(), vec![])
+                        .and_then(|_| {
+                            for (_, real_id) in mem::take(&mut self.proof_id_mapping) {
+                                system_api.invoke_snode(
+                                    SNodeRef::Proof(real_id),
+                                    "drop".to_string(),
+                                    vec![]
+                                )?;
+                            }
+                            // Each partial transaction allocates its IDs from scratch
+                            self.id_allocator = IdAllocator::new(IdSpace::Transaction);
+                            system_api.set_transaction_signers(signers.clone());
+
+                            if signers.is_empty() {
+                                return Ok(ScryptoValue::from_value(&()));
+                            }
+                            let proof_id = system_api.create_signature_proof(signers)?;
+                            system_api.invoke_snode(
+                                SNodeRef::AuthZoneRef,
+                                "push".to_string(),
+                                vec![ScryptoValue::from_value(&scrypto::resource::Proof(proof_id))]
+                            )
+                        })
+                },
             }?;
             self.outputs.push(result);
+
+            if self.signature_proofs_cleared_after == Some(index) {
+                system_api.invoke_snode(
+                    SNodeRef::AuthZoneRef,
+                    "clear_signature_proofs".to_string(),
+                    vec![]
+                )?;
+            }
         }
 
         Ok(ScryptoValue::from_value(&()))
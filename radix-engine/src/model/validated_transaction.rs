@@ -1,14 +1,20 @@
 use scrypto::crypto::*;
 use scrypto::engine::types::*;
+use scrypto::resource::AccessRule;
 use scrypto::rust::collections::{BTreeSet};
 use scrypto::rust::string::String;
 use scrypto::rust::vec::Vec;
 use scrypto::values::*;
 
+use crate::model::TransactionHashes;
+
 /// Represents a validated transaction
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ValidatedTransaction {
     pub raw_hash: Hash,
+    pub network_id: u8,
+    /// The hashes of the signed transaction, if it is a single one.
+    pub hashes: Option<TransactionHashes>,
     pub instructions: Vec<ValidatedInstruction>,
     pub signers: Vec<EcdsaPublicKey>,
 }
@@ -65,6 +71,7 @@ pub enum ValidatedInstruction {
     DropProof {
         proof_id: ProofId,
     },
+    DropAllProofs,
     CallFunction {
         package_address: PackageAddress,
         blueprint_name: String,
@@ -79,8 +86,36 @@ pub enum ValidatedInstruction {
     CallMethodWithAllResources {
         component_address: ComponentAddress,
         method: String,
+        args: Vec<ScryptoValue>,
+    },
+    CallResourceMethod {
+        resource_address: ResourceAddress,
+        method: String,
+        args: Vec<ScryptoValue>,
+    },
+    CreateLocker {
+        component_address: ComponentAddress,
+        bucket_id: BucketId,
+        claim_rule: AccessRule,
+    },
+    ClaimLocker {
+        component_address: ComponentAddress,
+        locker_id: LockerId,
+    },
+    CreateAccount {
+        withdraw_auth: AccessRule,
+        bucket_id: Option<BucketId>,
     },
     PublishPackage {
         code: Vec<u8>,
     },
+    PublishPackageWithOwner {
+        code: Vec<u8>,
+        owner_badge: ResourceAddress,
+    },
+    /// Marks the start of a partial transaction within a composite transaction, which is only
+    /// authorized by its own signers.
+    StartPartialTransaction {
+        signers: Vec<EcdsaPublicKey>,
+    },
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
 use sbor::*;
 use scrypto::buffer::scrypto_decode;
 use scrypto::engine::types::*;
+use scrypto::resource::AccessRule;
 use scrypto::rust::cell::{Ref, RefCell, RefMut};
 use scrypto::rust::collections::BTreeSet;
 use scrypto::rust::collections::HashMap;
@@ -12,7 +13,8 @@ use scrypto::values::ScryptoValue;
 use crate::engine::SystemApi;
 
 use crate::model::{
-    Bucket, Proof, ProofError, ResourceContainer, ResourceContainerError, ResourceContainerId,
+    Allowance, AllowanceError, Bucket, Proof, ProofError, ResourceContainer,
+    ResourceContainerError, ResourceContainerId,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -24,6 +26,10 @@ pub enum VaultError {
     CouldNotTakeBucket,
     ProofError(ProofError),
     CouldNotCreateProof,
+    NotOwnedByComponent,
+    AllowanceError(AllowanceError),
+    CouldNotRevokeAllowance,
+    AccessRuleSlotNotAllowed,
 }
 
 /// A persistent resource container.
@@ -43,7 +49,7 @@ impl Vault {
         self.borrow_container_mut().put(other.into_container()?)
     }
 
-    fn take(&mut self, amount: Decimal) -> Result<ResourceContainer, VaultError> {
+    pub fn take(&mut self, amount: Decimal) -> Result<ResourceContainer, VaultError> {
         let container = self
             .borrow_container_mut()
             .take_by_amount(amount)
@@ -76,21 +82,19 @@ impl Vault {
         container_id: ResourceContainerId,
     ) -> Result<Proof, ProofError> {
         // lock the specified amount
-        let locked_amount_or_ids = self
+        let lock = self
             .borrow_container_mut()
             .lock_by_amount(amount)
             .map_err(ProofError::ResourceContainerError)?;
 
         // produce proof
+        let total_locked = lock.locked().clone();
         let mut evidence = HashMap::new();
-        evidence.insert(
-            container_id,
-            (self.container.clone(), locked_amount_or_ids.clone()),
-        );
+        evidence.insert(container_id, (self.container.clone(), lock));
         Proof::new(
             self.resource_address(),
             self.resource_type(),
-            locked_amount_or_ids,
+            total_locked,
             evidence,
         )
     }
@@ -101,21 +105,19 @@ impl Vault {
         container_id: ResourceContainerId,
     ) -> Result<Proof, ProofError> {
         // lock the specified id set
-        let locked_amount_or_ids = self
+        let lock = self
             .borrow_container_mut()
             .lock_by_ids(ids)
             .map_err(ProofError::ResourceContainerError)?;
 
         // produce proof
+        let total_locked = lock.locked().clone();
         let mut evidence = HashMap::new();
-        evidence.insert(
-            container_id,
-            (self.container.clone(), locked_amount_or_ids.clone()),
-        );
+        evidence.insert(container_id, (self.container.clone(), lock));
         Proof::new(
             self.resource_address(),
             self.resource_type(),
-            locked_amount_or_ids,
+            total_locked,
             evidence,
         )
     }
@@ -136,6 +138,14 @@ impl Vault {
         self.borrow_container().total_ids()
     }
 
+    pub fn locked_amount(&self) -> Decimal {
+        self.borrow_container().max_locked_amount()
+    }
+
+    pub fn liquid_amount(&self) -> Decimal {
+        self.borrow_container().liquid_amount()
+    }
+
     pub fn is_locked(&self) -> bool {
         self.borrow_container().is_locked()
     }
@@ -155,6 +165,7 @@ impl Vault {
     pub fn main<S: SystemApi>(
         &mut self,
         vault_id: VaultId,
+        component_address: Option<ComponentAddress>,
         function: &str,
         args: Vec<ScryptoValue>,
         system_api: &mut S
@@ -185,6 +196,18 @@ impl Vault {
                 let amount = self.total_amount();
                 Ok(ScryptoValue::from_value(&amount))
             }
+            "get_vault_locked_amount" => {
+                let amount = self.locked_amount();
+                Ok(ScryptoValue::from_value(&amount))
+            }
+            "get_vault_liquid_amount" => {
+                let amount = self.liquid_amount();
+                Ok(ScryptoValue::from_value(&amount))
+            }
+            "is_vault_locked" => {
+                let locked = self.is_locked();
+                Ok(ScryptoValue::from_value(&locked))
+            }
             "get_vault_resource_address" => {
                 let resource_address = self.resource_address();
                 Ok(ScryptoValue::from_value(&resource_address))
@@ -211,7 +234,112 @@ impl Vault {
                 let proof_id = system_api.create_proof(proof).map_err(|_| VaultError::CouldNotCreateProof)?;
                 Ok(ScryptoValue::from_value(&scrypto::resource::Proof(proof_id)))
             }
+            "create_vault_allowance" => {
+                let max_amount: Decimal =
+                    scrypto_decode(&args[0].raw).map_err(|e| VaultError::InvalidRequestData(e))?;
+                let expiry_epoch: u64 =
+                    scrypto_decode(&args[1].raw).map_err(|e| VaultError::InvalidRequestData(e))?;
+                let redeem_rule: AccessRule =
+                    scrypto_decode(&args[2].raw).map_err(|e| VaultError::InvalidRequestData(e))?;
+                if redeem_rule.is_slot() {
+                    return Err(VaultError::AccessRuleSlotNotAllowed);
+                }
+                // Allowances draw from the vault after the call, so it must be kept by a component
+                let component_address = component_address.ok_or(VaultError::NotOwnedByComponent)?;
+                let allowance = Allowance::new(
+                    component_address,
+                    vault_id,
+                    self.resource_address(),
+                    max_amount,
+                    expiry_epoch,
+                    redeem_rule,
+                ).map_err(VaultError::AllowanceError)?;
+                let allowance_id = system_api.create_allowance(allowance);
+                Ok(ScryptoValue::from_value(&scrypto::resource::Allowance(allowance_id)))
+            }
+            "revoke_vault_allowance" => {
+                let allowance_id: AllowanceId =
+                    scrypto_decode(&args[0].raw).map_err(|e| VaultError::InvalidRequestData(e))?;
+                system_api.revoke_allowance(allowance_id, vault_id).map_err(|_| VaultError::CouldNotRevokeAllowance)?;
+                Ok(ScryptoValue::from_value(&()))
+            }
             _ => Err(VaultError::MethodNotFound(function.to_string())),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scrypto::buffer::scrypto_encode;
+    use scrypto::constants::{ECDSA_TOKEN, RADIX_TOKEN};
+    use scrypto::rust::collections::BTreeMap;
+
+    #[derive(TypeId, Encode)]
+    enum LegacyResourceContainer {
+        Fungible {
+            resource_address: ResourceAddress,
+            divisibility: u8,
+            locked_amounts: BTreeMap<Decimal, usize>,
+            liquid_amount: Decimal,
+        },
+        NonFungible {
+            resource_address: ResourceAddress,
+            locked_ids: HashMap<NonFungibleId, usize>,
+            liquid_ids: BTreeSet<NonFungibleId>,
+        },
+    }
+
+    #[derive(TypeId, Encode)]
+    struct LegacyVault {
+        container: LegacyResourceContainer,
+    }
+
+    #[test]
+    fn should_decode_legacy_fungible_vault() {
+        let bytes = scrypto_encode(&LegacyVault {
+            container: LegacyResourceContainer::Fungible {
+                resource_address: RADIX_TOKEN,
+                divisibility: 18,
+                locked_amounts: BTreeMap::new(),
+                liquid_amount: 100.into(),
+            },
+        });
+
+        let vault: Vault = scrypto_decode(&bytes).unwrap();
+
+        assert_eq!(vault.resource_address(), RADIX_TOKEN);
+        assert_eq!(vault.total_amount(), 100.into());
+        assert!(!vault.is_locked());
+    }
+
+    #[test]
+    fn should_decode_legacy_non_fungible_vault() {
+        let mut ids = BTreeSet::new();
+        ids.insert(NonFungibleId::from_u32(1));
+        ids.insert(NonFungibleId::from_u32(2));
+        let bytes = scrypto_encode(&LegacyVault {
+            container: LegacyResourceContainer::NonFungible {
+                resource_address: ECDSA_TOKEN,
+                locked_ids: HashMap::new(),
+                liquid_ids: ids.clone(),
+            },
+        });
+
+        let vault: Vault = scrypto_decode(&bytes).unwrap();
+
+        assert_eq!(vault.resource_address(), ECDSA_TOKEN);
+        assert_eq!(vault.total_ids(), Ok(ids));
+    }
+
+    #[test]
+    fn should_keep_vault_encoding_on_decode() {
+        let vault = Vault::new(ResourceContainer::new_fungible(RADIX_TOKEN, 18, 5.into()));
+        let bytes = scrypto_encode(&vault);
+
+        let decoded: Vault = scrypto_decode(&bytes).unwrap();
+
+        assert_eq!(decoded.total_amount(), 5.into());
+        assert_eq!(scrypto_encode(&decoded), bytes);
+    }
+}
@@ -2,8 +2,9 @@ use sbor::DecodeError;
 use scrypto::buffer::scrypto_decode;
 use scrypto::engine::types::*;
 use scrypto::rust::cell::{Ref, RefCell, RefMut};
+use scrypto::rust::collections::BTreeMap;
 use scrypto::rust::collections::BTreeSet;
-use scrypto::rust::collections::HashMap;
+use scrypto::rust::mem;
 use scrypto::rust::rc::Rc;
 use scrypto::rust::vec::Vec;
 use scrypto::rust::string::String;
@@ -16,7 +17,7 @@ use crate::model::{Bucket, ResourceContainer, ResourceContainerError, ResourceMa
 /// Worktop collects resources from function or method returns.
 #[derive(Debug)]
 pub struct Worktop {
-    containers: HashMap<ResourceAddress, Rc<RefCell<ResourceContainer>>>,
+    containers: BTreeMap<ResourceAddress, Rc<RefCell<ResourceContainer>>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -33,7 +34,7 @@ pub enum WorktopError {
 impl Worktop {
     pub fn new() -> Self {
         Self {
-            containers: HashMap::new(),
+            containers: BTreeMap::new(),
         }
     }
 
@@ -287,7 +288,7 @@ impl Worktop {
             }
             "drain" => {
                 let mut buckets = Vec::new();
-                for (_, container) in self.containers.drain() {
+                for (_, container) in mem::take(&mut self.containers) {
                     let container = container.borrow_mut().take_all_liquid().map_err(WorktopError::ResourceContainerError)?;
                     if !container.is_empty() {
                         let bucket_id = system_api.create_bucket(container).map_err(|_| WorktopError::CouldNotCreateBucket)?;
@@ -1,6 +1,4 @@
-use sbor::Type;
 use scrypto::abi;
-use scrypto::buffer::*;
 use scrypto::crypto::hash;
 use scrypto::engine::types::*;
 use scrypto::rust::borrow::ToOwned;
@@ -26,6 +24,9 @@ pub trait AbiProvider {
         &self,
         component_address: ComponentAddress,
     ) -> Result<abi::Blueprint, RuntimeError>;
+
+    /// Exports the structs and enums shared by a package, for other packages to import.
+    fn export_types(&self, package_address: PackageAddress) -> Result<abi::Types, RuntimeError>;
 }
 
 /// Provides ABIs for blueprints either installed during bootstrap or added manually.
@@ -51,7 +52,7 @@ impl BasicAbiProvider {
         let mut id_gen = SubstateIdGenerator::new(tx_hash);
 
         self.substate_store
-            .put_encoded_substate(package_address, &package, id_gen.next());
+            .put_package(package_address, &package, &mut id_gen);
         self
     }
 }
@@ -69,9 +70,17 @@ impl AbiProvider for BasicAbiProvider {
         // Start a process and run abi generator
         let mut track = Track::new(&mut ledger, transaction_hash, Vec::new());
         let mut proc = track.start_process(self.trace);
-        let output: (Type, Vec<abi::Function>, Vec<abi::Method>) = proc
+        let output = proc
             .call_abi(package_address, blueprint_name)
-            .and_then(|rtn| scrypto_decode(&rtn.raw).map_err(RuntimeError::AbiValidationError))?;
+            .and_then(|rtn| {
+                decode_blueprint_abi(&rtn.raw).map_err(RuntimeError::AbiValidationError)
+            })?;
+
+        let package: Package = self
+            .substate_store
+            .get_decoded_substate(&package_address)
+            .map(|(package, _)| package)
+            .ok_or(RuntimeError::PackageNotFound(package_address))?;
 
         // Return ABI
         Ok(abi::Blueprint {
@@ -79,6 +88,12 @@ impl AbiProvider for BasicAbiProvider {
             blueprint_name: blueprint_name.to_owned(),
             functions: output.1,
             methods: output.2,
+            constants: output.3,
+            error: output.4,
+            state: Some(output.0),
+            interfaces: output.5,
+            features: output.6,
+            imported_types: package.imported_types(),
         })
     }
 
@@ -93,4 +108,17 @@ impl AbiProvider for BasicAbiProvider {
             .ok_or(RuntimeError::ComponentNotFound(component_address))?;
         self.export_abi(component.package_address(), component.blueprint_name())
     }
+
+    fn export_types(&self, package_address: PackageAddress) -> Result<abi::Types, RuntimeError> {
+        let package: Package = self
+            .substate_store
+            .get_decoded_substate(&package_address)
+            .map(|(package, _)| package)
+            .ok_or(RuntimeError::PackageNotFound(package_address))?;
+
+        Ok(abi::Types {
+            package_address: package_address.to_string(),
+            types: package.types().to_vec(),
+        })
+    }
 }
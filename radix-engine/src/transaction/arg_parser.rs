@@ -0,0 +1,234 @@
+use scrypto::rust::borrow::ToOwned;
+use scrypto::rust::format;
+use scrypto::rust::string::String;
+use scrypto::rust::vec::Vec;
+
+/// Represents the syntax of an argument of composite type, before it's checked against the ABI.
+///
+/// Examples:
+/// - `[1, 2, 3]`
+/// - `Some("hello")`
+/// - `(1, Bucket("5,030000000000000000000000000000000000000000000000000004"))`
+/// - `{name: "Alice", age: 30}`
+/// - `Deposit { amount: 5 }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgExpr {
+    /// A bare token, such as a number, an address or an enum variant name.
+    Atom(String),
+    /// A double quoted string, with escapes resolved.
+    Quoted(String),
+    /// A list of elements, in square brackets.
+    List(Vec<ArgExpr>),
+    /// A list of elements in parentheses, optionally preceded by a name.
+    Tuple(Option<String>, Vec<ArgExpr>),
+    /// A list of entries in curly braces, optionally preceded by a name.
+    Map(Option<String>, Vec<(ArgExpr, ArgExpr)>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseArgExprError {
+    UnexpectedEndOfInput,
+    UnexpectedToken(String),
+    UnterminatedString,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Atom(String),
+    Quoted(String),
+    Punct(char),
+}
+
+const PUNCTUATIONS: [char; 8] = ['[', ']', '(', ')', '{', '}', ',', ':'];
+
+fn tokenize(input: &str) -> Result<Vec<Token>, ParseArgExprError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        } else if PUNCTUATIONS.contains(&c) {
+            tokens.push(Token::Punct(c));
+        } else if c == '"' {
+            let mut s = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some(e) => s.push(e),
+                        None => return Err(ParseArgExprError::UnterminatedString),
+                    },
+                    Some(e) => s.push(e),
+                    None => return Err(ParseArgExprError::UnterminatedString),
+                }
+            }
+            tokens.push(Token::Quoted(s));
+        } else {
+            let mut s = String::new();
+            s.push(c);
+            while let Some(&n) = chars.peek() {
+                if n.is_whitespace() || n == '"' || PUNCTUATIONS.contains(&n) {
+                    break;
+                }
+                s.push(n);
+                chars.next();
+            }
+            tokens.push(Token::Atom(s));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    current: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.current)
+    }
+
+    fn advance(&mut self) -> Result<Token, ParseArgExprError> {
+        let token = self
+            .tokens
+            .get(self.current)
+            .cloned()
+            .ok_or(ParseArgExprError::UnexpectedEndOfInput)?;
+        self.current += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseArgExprError> {
+        match self.advance()? {
+            Token::Punct(p) if p == c => Ok(()),
+            t => Err(ParseArgExprError::UnexpectedToken(format!("{:?}", t))),
+        }
+    }
+
+    /// Parses elements separated by commas, up to the closing punctuation.
+    fn parse_elements<T, F>(&mut self, close: char, mut f: F) -> Result<Vec<T>, ParseArgExprError>
+    where
+        F: FnMut(&mut Self) -> Result<T, ParseArgExprError>,
+    {
+        let mut elements = Vec::new();
+        loop {
+            if self.peek() == Some(&Token::Punct(close)) {
+                self.advance()?;
+                return Ok(elements);
+            }
+            elements.push(f(self)?);
+            match self.advance()? {
+                Token::Punct(',') => {}
+                Token::Punct(p) if p == close => return Ok(elements),
+                t => return Err(ParseArgExprError::UnexpectedToken(format!("{:?}", t))),
+            }
+        }
+    }
+
+    fn parse_entry(&mut self) -> Result<(ArgExpr, ArgExpr), ParseArgExprError> {
+        let key = self.parse_expr()?;
+        self.expect(':')?;
+        let value = self.parse_expr()?;
+        Ok((key, value))
+    }
+
+    fn parse_expr(&mut self) -> Result<ArgExpr, ParseArgExprError> {
+        match self.advance()? {
+            Token::Atom(name) => match self.peek() {
+                Some(Token::Punct('(')) => {
+                    self.advance()?;
+                    let elements = self.parse_elements(')', Self::parse_expr)?;
+                    Ok(ArgExpr::Tuple(Some(name), elements))
+                }
+                Some(Token::Punct('{')) => {
+                    self.advance()?;
+                    let entries = self.parse_elements('}', Self::parse_entry)?;
+                    Ok(ArgExpr::Map(Some(name), entries))
+                }
+                _ => Ok(ArgExpr::Atom(name)),
+            },
+            Token::Quoted(s) => Ok(ArgExpr::Quoted(s)),
+            Token::Punct('[') => Ok(ArgExpr::List(self.parse_elements(']', Self::parse_expr)?)),
+            Token::Punct('(') => Ok(ArgExpr::Tuple(
+                None,
+                self.parse_elements(')', Self::parse_expr)?,
+            )),
+            Token::Punct('{') => Ok(ArgExpr::Map(
+                None,
+                self.parse_elements('}', Self::parse_entry)?,
+            )),
+            t => Err(ParseArgExprError::UnexpectedToken(format!("{:?}", t))),
+        }
+    }
+}
+
+/// Parses the syntax of an argument.
+pub fn parse_arg_expr(input: &str) -> Result<ArgExpr, ParseArgExprError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        current: 0,
+    };
+    let expr = parser.parse_expr()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(t) => Err(ParseArgExprError::UnexpectedToken(format!("{:?}", t))),
+    }
+}
+
+impl ArgExpr {
+    /// Returns the text of an atom or a quoted string.
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            ArgExpr::Atom(s) | ArgExpr::Quoted(s) => Some(s.as_str()),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for ArgExpr {
+    fn from(s: &str) -> Self {
+        ArgExpr::Atom(s.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scrypto::rust::vec;
+
+    #[test]
+    fn test_parse_nested() {
+        let expr = parse_arg_expr(r#"[Some("a, b"), None, Foo { x: (1, 2) }]"#).unwrap();
+        assert_eq!(
+            expr,
+            ArgExpr::List(vec![
+                ArgExpr::Tuple(
+                    Some("Some".to_owned()),
+                    vec![ArgExpr::Quoted("a, b".to_owned())]
+                ),
+                "None".into(),
+                ArgExpr::Map(
+                    Some("Foo".to_owned()),
+                    vec![(
+                        "x".into(),
+                        ArgExpr::Tuple(None, vec!["1".into(), "2".into()])
+                    )]
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(
+            parse_arg_expr("[1, 2"),
+            Err(ParseArgExprError::UnexpectedEndOfInput)
+        );
+        assert_eq!(
+            parse_arg_expr("\"abc"),
+            Err(ParseArgExprError::UnterminatedString)
+        );
+        assert!(parse_arg_expr("1 2").is_err());
+    }
+}
@@ -0,0 +1,113 @@
+use scrypto::core::NetworkDefinition;
+use scrypto::rust::collections::*;
+use scrypto::rust::vec::Vec;
+
+use crate::engine::*;
+use crate::fee::*;
+use crate::ledger::*;
+use crate::model::*;
+use crate::transaction::*;
+
+/// An executor over an async substate store, which awaits I/O between executions rather than
+/// blocking on it.
+///
+/// A transaction is executed against the substates fetched so far. If it reads any substate
+/// which hasn't been fetched, it fails with `RuntimeError::SubstateNotFetched`, the missing
+/// substates are fetched in one batch, and the transaction is executed again. State changes are written in one batch when
+/// the transaction is committed.
+///
+/// Fetched substates are kept across transactions, assuming this executor is the only writer
+/// to the store. Call `clear_fetched` when the store is written to by someone else.
+pub struct AsyncTransactionExecutor<'s, A: AsyncWriteableSubstateStore> {
+    substate_store: &'s mut A,
+    trace: bool,
+    network: NetworkDefinition,
+    cost_unit_limit: u32,
+    fetched: HashMap<SubstateKey, Option<Substate>>,
+    round_trips: u64,
+}
+
+impl<'s, A: AsyncWriteableSubstateStore> AsyncTransactionExecutor<'s, A> {
+    pub fn new(substate_store: &'s mut A, trace: bool) -> Self {
+        Self {
+            substate_store,
+            trace,
+            network: NetworkDefinition::simulator(),
+            cost_unit_limit: DEFAULT_COST_UNIT_LIMIT,
+            fetched: HashMap::new(),
+            round_trips: 0,
+        }
+    }
+
+    /// Sets the network transactions are executed on.
+    ///
+    /// Transactions whose header has another network id are rejected.
+    pub fn with_network(mut self, network: NetworkDefinition) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Sets the maximum number of cost units a transaction can consume.
+    pub fn with_cost_unit_limit(mut self, cost_unit_limit: u32) -> Self {
+        self.cost_unit_limit = cost_unit_limit;
+        self
+    }
+
+    /// Returns the number of batched reads made so far.
+    pub fn round_trips(&self) -> u64 {
+        self.round_trips
+    }
+
+    /// Forgets the fetched substates, so that they are read from the store again.
+    pub fn clear_fetched(&mut self) {
+        self.fetched.clear();
+    }
+
+    pub async fn validate_and_execute(
+        &mut self,
+        signed: &SignedTransaction,
+    ) -> Result<Receipt, TransactionValidationError> {
+        let validated = signed.validate()?;
+        if validated.network_id != self.network.id {
+            return Err(TransactionValidationError::InvalidNetwork {
+                expected: self.network.id,
+                actual: validated.network_id,
+            });
+        }
+        Ok(self.execute(validated).await)
+    }
+
+    pub async fn execute(&mut self, validated: ValidatedTransaction) -> Receipt {
+        let epoch = self.substate_store.fetch_epoch().await;
+        let nonce = self.substate_store.fetch_nonce().await;
+        loop {
+            let (receipt, misses, batch) = {
+                let mut buffer = BufferedSubstateStore::new(&self.fetched, epoch, nonce);
+                let receipt = TransactionExecutor::new(&mut buffer, self.trace)
+                    .with_network(self.network.clone())
+                    .with_cost_unit_limit(self.cost_unit_limit)
+                    .execute(validated.clone());
+                let misses = buffer.misses();
+                (receipt, misses, buffer.into_write_batch())
+            };
+
+            if !misses.is_empty() {
+                self.fetch(misses).await;
+                continue;
+            }
+            if receipt.commit_receipt.is_some() {
+                for (key, substate) in &batch.substates {
+                    self.fetched.insert(key.clone(), Some(substate.clone()));
+                }
+                self.substate_store.write_batch(batch).await;
+            }
+            return receipt;
+        }
+    }
+
+    async fn fetch(&mut self, keys: Vec<SubstateKey>) {
+        let substates = self.substate_store.fetch_substates(keys.clone()).await;
+        self.round_trips += 1;
+        self.fetched.extend(keys.into_iter().zip(substates));
+    }
+}
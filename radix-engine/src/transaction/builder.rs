@@ -1,11 +1,15 @@
 use sbor::describe::*;
+use sbor::type_id::*;
 use sbor::*;
 use scrypto::buffer::*;
+use scrypto::core::NetworkDefinition;
 use scrypto::crypto::*;
 use scrypto::engine::types::*;
+use scrypto::math::{I256, U256};
 use scrypto::prelude::{AccessRuleNode, Burn, AccessRule, Mint, Withdraw};
 use scrypto::resource::{require, LOCKED};
 use scrypto::rust::borrow::ToOwned;
+use scrypto::rust::boxed::Box;
 use scrypto::rust::collections::BTreeSet;
 use scrypto::rust::collections::*;
 use scrypto::rust::fmt;
@@ -20,10 +24,13 @@ use scrypto::*;
 
 use crate::engine::*;
 use crate::model::*;
+use crate::transaction::arg_parser::*;
 use crate::transaction::*;
 
 /// Utility for building transaction.
 pub struct TransactionBuilder {
+    /// The network the transaction is built for
+    network_id: u8,
     /// ID validator for calculating transaction object id
     id_validator: IdValidator,
     /// Instructions generated.
@@ -31,9 +38,15 @@ pub struct TransactionBuilder {
 }
 
 impl TransactionBuilder {
-    /// Starts a new transaction builder.
+    /// Starts a new transaction builder, for the simulator network.
     pub fn new() -> Self {
+        Self::new_for_network(&NetworkDefinition::simulator())
+    }
+
+    /// Starts a new transaction builder, for the given network.
+    pub fn new_for_network(network: &NetworkDefinition) -> Self {
         Self {
+            network_id: network.id,
             id_validator: IdValidator::new(),
             instructions: Vec::new(),
         }
@@ -53,7 +66,8 @@ impl TransactionBuilder {
             | Instruction::TakeFromWorktopByIds { .. } => {
                 new_bucket_id = Some(self.id_validator.new_bucket().unwrap());
             }
-            Instruction::ReturnToWorktop { bucket_id } => {
+            Instruction::ReturnToWorktop { bucket_id }
+            | Instruction::CreateLocker { bucket_id, .. } => {
                 self.id_validator.drop_bucket(bucket_id).unwrap();
             }
             Instruction::AssertWorktopContains { .. }
@@ -69,6 +83,9 @@ impl TransactionBuilder {
             Instruction::PushToAuthZone { proof_id } => {
                 self.id_validator.drop_proof(proof_id).unwrap();
             }
+            Instruction::DropAllProofs => {
+                self.id_validator.drop_all_proofs().unwrap();
+            }
             Instruction::ClearAuthZone => {}
             Instruction::CreateProofFromAuthZone { .. }
             | Instruction::CreateProofFromAuthZoneByAmount { .. }
@@ -92,7 +109,9 @@ impl TransactionBuilder {
             Instruction::DropProof { proof_id } => {
                 self.id_validator.drop_proof(proof_id).unwrap();
             }
-            Instruction::CallFunction { args, .. } | Instruction::CallMethod { args, .. } => {
+            Instruction::CallFunction { args, .. }
+            | Instruction::CallMethod { args, .. }
+            | Instruction::CallResourceMethod { args, .. } => {
                 for arg in &args {
                     let validated_arg = ScryptoValue::from_slice(arg).unwrap();
                     self.id_validator.move_resources(&validated_arg).unwrap();
@@ -101,7 +120,15 @@ impl TransactionBuilder {
             Instruction::CallMethodWithAllResources { .. } => {
                 self.id_validator.move_all_resources().unwrap();
             }
-            Instruction::PublishPackage { .. } | Instruction::Nonce { .. } => {}
+            Instruction::CreateAccount { bucket_id, .. } => {
+                if let Some(bucket_id) = bucket_id {
+                    self.id_validator.drop_bucket(bucket_id).unwrap();
+                }
+            }
+            Instruction::ClaimLocker { .. }
+            | Instruction::PublishPackage { .. }
+            | Instruction::PublishPackageWithOwner { .. }
+            | Instruction::Nonce { .. } => {}
         }
 
         self.instructions.push(inst);
@@ -285,6 +312,11 @@ impl TransactionBuilder {
         self.add_instruction(Instruction::DropProof { proof_id }).0
     }
 
+    /// Drops all named proofs and all proofs in the auth zone.
+    pub fn drop_all_proofs(&mut self) -> &mut Self {
+        self.add_instruction(Instruction::DropAllProofs).0
+    }
+
     /// Calls a function where the arguments should be an array of encoded Scrypto value.
     pub fn call_function(
         &mut self,
@@ -401,6 +433,87 @@ impl TransactionBuilder {
         self.add_instruction(Instruction::CallMethodWithAllResources {
             component_address,
             method: method.into(),
+            args: vec![],
+        })
+        .0
+    }
+
+    /// Calls a method with all the resources on worktop, followed by the given arguments.
+    ///
+    /// The first parameter of the callee method must have type `Vec<Bucket>`, e.g.
+    /// `deposit_batch_safe` of an account, which also takes the allowed resources.
+    pub fn call_method_with_all_resources_and_args(
+        &mut self,
+        component_address: ComponentAddress,
+        method: &str,
+        args: Vec<Vec<u8>>,
+    ) -> &mut Self {
+        self.add_instruction(Instruction::CallMethodWithAllResources {
+            component_address,
+            method: method.into(),
+            args,
+        })
+        .0
+    }
+
+    /// Calls a resource manager method where the arguments should be an array of encoded Scrypto
+    /// value, such as `mint` or `update_metadata`.
+    pub fn call_resource_method(
+        &mut self,
+        resource_address: ResourceAddress,
+        method: &str,
+        args: Vec<Vec<u8>>,
+    ) -> &mut Self {
+        self.add_instruction(Instruction::CallResourceMethod {
+            resource_address,
+            method: method.to_owned(),
+            args,
+        });
+        self
+    }
+
+    /// Moves a bucket into a new locker owned by a component, which can be claimed by whoever
+    /// satisfies the claim rule.
+    ///
+    /// The auth zone must satisfy the component's rule for `create_locker`, e.g. the owner's
+    /// signature for accounts.
+    pub fn create_locker(
+        &mut self,
+        component_address: ComponentAddress,
+        bucket_id: BucketId,
+        claim_rule: AccessRule,
+    ) -> &mut Self {
+        self.add_instruction(Instruction::CreateLocker {
+            component_address,
+            bucket_id,
+            claim_rule,
+        })
+        .0
+    }
+
+    /// Claims all resources in a locker and puts them onto worktop.
+    pub fn claim_locker(
+        &mut self,
+        component_address: ComponentAddress,
+        locker_id: LockerId,
+    ) -> &mut Self {
+        self.add_instruction(Instruction::ClaimLocker {
+            component_address,
+            locker_id,
+        })
+        .0
+    }
+
+    /// Creates an account protected by the withdraw rule, and optionally deposits a bucket
+    /// into it.
+    pub fn create_account(
+        &mut self,
+        withdraw_auth: AccessRule,
+        bucket_id: Option<BucketId>,
+    ) -> &mut Self {
+        self.add_instruction(Instruction::CreateAccount {
+            withdraw_auth,
+            bucket_id,
         })
         .0
     }
@@ -413,12 +526,28 @@ impl TransactionBuilder {
         .0
     }
 
+    /// Publishes a package, which can be upgraded by whoever presents the owner badge.
+    pub fn publish_package_with_owner(
+        &mut self,
+        code: &[u8],
+        owner_badge: ResourceAddress,
+    ) -> &mut Self {
+        self.add_instruction(Instruction::PublishPackageWithOwner {
+            code: code.to_vec(),
+            owner_badge,
+        })
+        .0
+    }
+
     /// Builds a transaction with the given nonce.
     pub fn build(&self, nonce: u64) -> Transaction {
         let mut instructions = self.instructions.clone();
         instructions.push(Instruction::Nonce { nonce });
 
-        Transaction { instructions }
+        Transaction {
+            header: self.header(),
+            instructions,
+        }
     }
 
     /// Builds a transaction with no nonce
@@ -426,14 +555,21 @@ impl TransactionBuilder {
     /// Nonce can be later filled by a third party or wallet.
     pub fn build_with_no_nonce(&self) -> Transaction {
         Transaction {
+            header: self.header(),
             instructions: self.instructions.clone(),
         }
     }
 
+    fn header(&self) -> TransactionHeader {
+        TransactionHeader {
+            network_id: self.network_id,
+        }
+    }
+
     /// Creates a token resource with mutable supply.
     pub fn new_token_mutable(
         &mut self,
-        metadata: HashMap<String, String>,
+        metadata: HashMap<String, MetadataValue>,
         minter_resource_address: ResourceAddress,
     ) -> &mut Self {
         let mut resource_auth = HashMap::new();
@@ -464,7 +600,7 @@ impl TransactionBuilder {
     /// Creates a token resource with fixed supply.
     pub fn new_token_fixed(
         &mut self,
-        metadata: HashMap<String, String>,
+        metadata: HashMap<String, MetadataValue>,
         initial_supply: Decimal,
     ) -> &mut Self {
         let mut resource_auth = HashMap::new();
@@ -489,7 +625,7 @@ impl TransactionBuilder {
     /// Creates a badge resource with mutable supply.
     pub fn new_badge_mutable(
         &mut self,
-        metadata: HashMap<String, String>,
+        metadata: HashMap<String, MetadataValue>,
         minter_resource_address: ResourceAddress,
     ) -> &mut Self {
         let mut resource_auth = HashMap::new();
@@ -520,7 +656,7 @@ impl TransactionBuilder {
     /// Creates a badge resource with fixed supply.
     pub fn new_badge_fixed(
         &mut self,
-        metadata: HashMap<String, String>,
+        metadata: HashMap<String, MetadataValue>,
         initial_supply: Decimal,
     ) -> &mut Self {
         let mut resource_auth = HashMap::new();
@@ -719,28 +855,268 @@ impl TransactionBuilder {
             let arg = args
                 .get(i)
                 .ok_or_else(|| BuildArgsError::MissingArgument(i, t.clone()))?;
-            let res = match t {
-                Type::Bool => self.parse_basic_ty::<bool>(i, t, arg),
-                Type::I8 => self.parse_basic_ty::<i8>(i, t, arg),
-                Type::I16 => self.parse_basic_ty::<i16>(i, t, arg),
-                Type::I32 => self.parse_basic_ty::<i32>(i, t, arg),
-                Type::I64 => self.parse_basic_ty::<i64>(i, t, arg),
-                Type::I128 => self.parse_basic_ty::<i128>(i, t, arg),
-                Type::U8 => self.parse_basic_ty::<u8>(i, t, arg),
-                Type::U16 => self.parse_basic_ty::<u16>(i, t, arg),
-                Type::U32 => self.parse_basic_ty::<u32>(i, t, arg),
-                Type::U64 => self.parse_basic_ty::<u64>(i, t, arg),
-                Type::U128 => self.parse_basic_ty::<u128>(i, t, arg),
-                Type::String => self.parse_basic_ty::<String>(i, t, arg),
-                Type::Custom { name, .. } => self.parse_custom_ty(i, t, arg, name, account),
-                _ => Err(BuildArgsError::UnsupportedType(i, t.clone())),
-            };
-            encoded.push(res?);
+            encoded.push(self.parse_arg(i, t, arg, account)?);
         }
 
         Ok(encoded)
     }
 
+    fn parse_arg(
+        &mut self,
+        i: usize,
+        t: &Type,
+        arg: &str,
+        account: Option<ComponentAddress>,
+    ) -> Result<Vec<u8>, BuildArgsError> {
+        match t {
+            Type::Bool => self.parse_basic_ty::<bool>(i, t, arg),
+            Type::I8 => self.parse_basic_ty::<i8>(i, t, arg),
+            Type::I16 => self.parse_basic_ty::<i16>(i, t, arg),
+            Type::I32 => self.parse_basic_ty::<i32>(i, t, arg),
+            Type::I64 => self.parse_basic_ty::<i64>(i, t, arg),
+            Type::I128 => self.parse_basic_ty::<i128>(i, t, arg),
+            Type::U8 => self.parse_basic_ty::<u8>(i, t, arg),
+            Type::U16 => self.parse_basic_ty::<u16>(i, t, arg),
+            Type::U32 => self.parse_basic_ty::<u32>(i, t, arg),
+            Type::U64 => self.parse_basic_ty::<u64>(i, t, arg),
+            Type::U128 => self.parse_basic_ty::<u128>(i, t, arg),
+            Type::String => self.parse_basic_ty::<String>(i, t, arg),
+            Type::Custom { name, .. } => self.parse_custom_ty(i, t, arg, name, account),
+            _ => {
+                let expr = parse_arg_expr(arg)
+                    .map_err(|_| BuildArgsError::FailedToParse(i, t.clone(), arg.to_owned()))?;
+                let value = self.parse_composite_ty(i, t, &expr, arg, account)?;
+                let mut bytes = Vec::new();
+                encode_any(None, &value, &mut Encoder::with_type(&mut bytes));
+                Ok(bytes)
+            }
+        }
+    }
+
+    /// Converts the syntax of an argument into a value of the given type.
+    ///
+    /// Strings are quoted and buckets or proofs are written as `Bucket("<resource specifier>")`
+    /// or `Proof("<resource specifier>")`.
+    fn parse_composite_ty(
+        &mut self,
+        i: usize,
+        ty: &Type,
+        expr: &ArgExpr,
+        arg: &str,
+        account: Option<ComponentAddress>,
+    ) -> Result<Value, BuildArgsError> {
+        let failed = || BuildArgsError::FailedToParse(i, ty.clone(), arg.to_owned());
+        let element_type_id =
+            |t: &Type| type_id_of(t).ok_or_else(|| BuildArgsError::UnsupportedType(i, t.clone()));
+
+        match (ty, expr) {
+            (
+                Type::Bool
+                | Type::I8
+                | Type::I16
+                | Type::I32
+                | Type::I64
+                | Type::I128
+                | Type::U8
+                | Type::U16
+                | Type::U32
+                | Type::U64
+                | Type::U128
+                | Type::String
+                | Type::Custom { .. },
+                _,
+            ) => {
+                let text = match (ty, expr) {
+                    (Type::Custom { name, .. }, ArgExpr::Tuple(Some(n), elements))
+                        if n == name && elements.len() == 1 =>
+                    {
+                        elements[0].as_text()
+                    }
+                    _ => expr.as_text(),
+                }
+                .ok_or_else(failed)?;
+                let bytes = self.parse_arg(i, ty, text, account)?;
+                decode_any(&bytes).map_err(|_| failed())
+            }
+            (Type::Unit, ArgExpr::Tuple(None, elements)) if elements.is_empty() => Ok(Value::Unit),
+            (Type::Option { .. }, ArgExpr::Atom(name)) if name == "None" => Ok(Value::Option {
+                value: Box::new(None),
+            }),
+            (Type::Option { value }, ArgExpr::Tuple(Some(name), elements))
+                if name == "Some" && elements.len() == 1 =>
+            {
+                let v = self.parse_composite_ty(i, value, &elements[0], arg, account)?;
+                Ok(Value::Option {
+                    value: Box::new(Some(v)),
+                })
+            }
+            (Type::Result { okay, error }, ArgExpr::Tuple(Some(name), elements))
+                if elements.len() == 1 && (name == "Ok" || name == "Err") =>
+            {
+                let v = if name == "Ok" {
+                    Ok(self.parse_composite_ty(i, okay, &elements[0], arg, account)?)
+                } else {
+                    Err(self.parse_composite_ty(i, error, &elements[0], arg, account)?)
+                };
+                Ok(Value::Result { value: Box::new(v) })
+            }
+            (Type::Tuple { elements: types }, ArgExpr::Tuple(None, elements))
+                if types.len() == elements.len() =>
+            {
+                Ok(Value::Tuple {
+                    elements: self.parse_composite_tys(i, types, elements, arg, account)?,
+                })
+            }
+            (Type::Array { element, length }, ArgExpr::List(elements))
+                if *length as usize == elements.len() =>
+            {
+                Ok(Value::Array {
+                    element_type_id: element_type_id(element)?,
+                    elements: self.parse_composite_elements(i, element, elements, arg, account)?,
+                })
+            }
+            (Type::Vec { element }, ArgExpr::List(elements)) => Ok(Value::Vec {
+                element_type_id: element_type_id(element)?,
+                elements: self.parse_composite_elements(i, element, elements, arg, account)?,
+            }),
+            (Type::TreeSet { element }, ArgExpr::List(elements)) => Ok(Value::TreeSet {
+                element_type_id: element_type_id(element)?,
+                elements: self.parse_composite_elements(i, element, elements, arg, account)?,
+            }),
+            (Type::HashSet { element }, ArgExpr::List(elements)) => Ok(Value::HashSet {
+                element_type_id: element_type_id(element)?,
+                elements: self.parse_composite_elements(i, element, elements, arg, account)?,
+            }),
+            (Type::TreeMap { key, value }, ArgExpr::Map(None, entries)) => Ok(Value::TreeMap {
+                key_type_id: element_type_id(key)?,
+                value_type_id: element_type_id(value)?,
+                elements: self.parse_composite_entries(i, key, value, entries, arg, account)?,
+            }),
+            (Type::HashMap { key, value }, ArgExpr::Map(None, entries)) => Ok(Value::HashMap {
+                key_type_id: element_type_id(key)?,
+                value_type_id: element_type_id(value)?,
+                elements: self.parse_composite_entries(i, key, value, entries, arg, account)?,
+            }),
+            (Type::Struct { name, fields }, _) => {
+                let fields = match expr {
+                    ArgExpr::Atom(n) if n == name => {
+                        self.parse_composite_fields(i, ty, fields, None, arg, account)
+                    }
+                    ArgExpr::Tuple(n, _) | ArgExpr::Map(n, _)
+                        if n.as_ref().map(|n| n == name).unwrap_or(true) =>
+                    {
+                        self.parse_composite_fields(i, ty, fields, Some(expr), arg, account)
+                    }
+                    _ => Err(failed()),
+                }?;
+                Ok(Value::Struct { fields })
+            }
+            (Type::Enum { variants, .. }, _) => {
+                let (variant_name, inner) = match expr {
+                    ArgExpr::Atom(n) => (n, None),
+                    ArgExpr::Tuple(Some(n), _) | ArgExpr::Map(Some(n), _) => (n, Some(expr)),
+                    _ => return Err(failed()),
+                };
+                let variant = variants
+                    .iter()
+                    .find(|v| &v.name == variant_name)
+                    .ok_or_else(failed)?;
+                let fields =
+                    self.parse_composite_fields(i, ty, &variant.fields, inner, arg, account)?;
+                Ok(Value::Enum {
+                    name: variant.name.clone(),
+                    fields,
+                })
+            }
+            (Type::Ref { .. }, _) => Err(BuildArgsError::UnsupportedType(i, ty.clone())),
+            _ => Err(failed()),
+        }
+    }
+
+    fn parse_composite_tys(
+        &mut self,
+        i: usize,
+        types: &[Type],
+        elements: &[ArgExpr],
+        arg: &str,
+        account: Option<ComponentAddress>,
+    ) -> Result<Vec<Value>, BuildArgsError> {
+        types
+            .iter()
+            .zip(elements)
+            .map(|(t, e)| self.parse_composite_ty(i, t, e, arg, account))
+            .collect()
+    }
+
+    fn parse_composite_elements(
+        &mut self,
+        i: usize,
+        element: &Type,
+        elements: &[ArgExpr],
+        arg: &str,
+        account: Option<ComponentAddress>,
+    ) -> Result<Vec<Value>, BuildArgsError> {
+        elements
+            .iter()
+            .map(|e| self.parse_composite_ty(i, element, e, arg, account))
+            .collect()
+    }
+
+    fn parse_composite_entries(
+        &mut self,
+        i: usize,
+        key: &Type,
+        value: &Type,
+        entries: &[(ArgExpr, ArgExpr)],
+        arg: &str,
+        account: Option<ComponentAddress>,
+    ) -> Result<Vec<Value>, BuildArgsError> {
+        let mut elements = Vec::new();
+        for (k, v) in entries {
+            elements.push(self.parse_composite_ty(i, key, k, arg, account)?);
+            elements.push(self.parse_composite_ty(i, value, v, arg, account)?);
+        }
+        Ok(elements)
+    }
+
+    /// Parses the fields of a struct or an enum variant, from `(a, b)` or `{x: a, y: b}` syntax.
+    fn parse_composite_fields(
+        &mut self,
+        i: usize,
+        ty: &Type,
+        fields: &Fields,
+        expr: Option<&ArgExpr>,
+        arg: &str,
+        account: Option<ComponentAddress>,
+    ) -> Result<Vec<Value>, BuildArgsError> {
+        let failed = || BuildArgsError::FailedToParse(i, ty.clone(), arg.to_owned());
+        match (fields, expr) {
+            (Fields::Unit, None) => Ok(Vec::new()),
+            (Fields::Unit, Some(ArgExpr::Tuple(_, elements))) if elements.is_empty() => {
+                Ok(Vec::new())
+            }
+            (Fields::Unnamed { unnamed }, Some(ArgExpr::Tuple(_, elements)))
+                if unnamed.len() == elements.len() =>
+            {
+                self.parse_composite_tys(i, unnamed, elements, arg, account)
+            }
+            (Fields::Named { named }, Some(ArgExpr::Map(_, entries)))
+                if named.len() == entries.len() =>
+            {
+                let mut values = Vec::new();
+                for (field_name, field_type) in named {
+                    let (_, e) = entries
+                        .iter()
+                        .find(|(k, _)| k.as_text() == Some(field_name.as_str()))
+                        .ok_or_else(failed)?;
+                    values.push(self.parse_composite_ty(i, field_type, e, arg, account)?);
+                }
+                Ok(values)
+            }
+            _ => Err(failed()),
+        }
+    }
+
     fn parse_basic_ty<T>(
         &mut self,
         i: usize,
@@ -772,6 +1148,18 @@ impl TransactionBuilder {
                     .map_err(|_| BuildArgsError::FailedToParse(i, ty.clone(), arg.to_owned()))?;
                 Ok(scrypto_encode(&value))
             }
+            ScryptoType::I256 => {
+                let value = arg
+                    .parse::<I256>()
+                    .map_err(|_| BuildArgsError::FailedToParse(i, ty.clone(), arg.to_owned()))?;
+                Ok(scrypto_encode(&value))
+            }
+            ScryptoType::U256 => {
+                let value = arg
+                    .parse::<U256>()
+                    .map_err(|_| BuildArgsError::FailedToParse(i, ty.clone(), arg.to_owned()))?;
+                Ok(scrypto_encode(&value))
+            }
             ScryptoType::PackageAddress => {
                 let value = arg
                     .parse::<PackageAddress>()
@@ -867,6 +1255,38 @@ impl TransactionBuilder {
     }
 }
 
+/// Returns the SBOR type ID of the values of a type, if known.
+fn type_id_of(ty: &Type) -> Option<u8> {
+    match ty {
+        Type::Unit => Some(TYPE_UNIT),
+        Type::Bool => Some(TYPE_BOOL),
+        Type::I8 => Some(TYPE_I8),
+        Type::I16 => Some(TYPE_I16),
+        Type::I32 => Some(TYPE_I32),
+        Type::I64 => Some(TYPE_I64),
+        Type::I128 => Some(TYPE_I128),
+        Type::U8 => Some(TYPE_U8),
+        Type::U16 => Some(TYPE_U16),
+        Type::U32 => Some(TYPE_U32),
+        Type::U64 => Some(TYPE_U64),
+        Type::U128 => Some(TYPE_U128),
+        Type::String => Some(TYPE_STRING),
+        Type::Option { .. } => Some(TYPE_OPTION),
+        Type::Array { .. } => Some(TYPE_ARRAY),
+        Type::Tuple { .. } => Some(TYPE_TUPLE),
+        Type::Struct { .. } => Some(TYPE_STRUCT),
+        Type::Enum { .. } => Some(TYPE_ENUM),
+        Type::Result { .. } => Some(TYPE_RESULT),
+        Type::Vec { .. } => Some(TYPE_VEC),
+        Type::TreeSet { .. } => Some(TYPE_TREE_SET),
+        Type::TreeMap { .. } => Some(TYPE_TREE_MAP),
+        Type::HashSet { .. } => Some(TYPE_HASH_SET),
+        Type::HashMap { .. } => Some(TYPE_HASH_MAP),
+        Type::Custom { name, .. } => ScryptoType::from_name(name).map(|t| t.id()),
+        Type::Ref { .. } => None,
+    }
+}
+
 enum ResourceSpecifier {
     Amount(Decimal, ResourceAddress),
     Ids(BTreeSet<NonFungibleId>, ResourceAddress),
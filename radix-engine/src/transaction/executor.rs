@@ -1,39 +1,70 @@
+use scrypto::buffer::scrypto_encode;
+use scrypto::core::{NetworkDefinition, SNodeRef, ScryptoActor};
 use scrypto::crypto::hash;
 use scrypto::engine::types::*;
 use scrypto::resource::*;
+use scrypto::rust::boxed::Box;
+use scrypto::rust::collections::HashMap;
+use scrypto::rust::str::FromStr;
 use scrypto::rust::vec;
 use scrypto::rust::vec::Vec;
 use scrypto::rust::string::ToString;
+use scrypto::values::ScryptoValue;
 use scrypto::{abi, rule, access_rule_node};
 
 use crate::engine::*;
 use crate::errors::*;
+use crate::fee::*;
 use crate::ledger::*;
 use crate::model::*;
 use crate::transaction::*;
 
 /// An executor that runs transactions.
-pub struct TransactionExecutor<'l, L: SubstateStore> {
+pub struct TransactionExecutor<
+    'l,
+    L: SubstateStore,
+    M: EngineMetrics = NoOpMetrics,
+    H: EngineHooks = NoOpHooks,
+> {
     substate_store: &'l mut L,
     trace: bool,
+    network: NetworkDefinition,
+    substate_cache: SubstateCache,
+    wasm_instance_pool: WasmInstancePool,
+    cost_unit_limit: u32,
+    max_logs: usize,
+    max_log_bytes: usize,
+    cost_unit_price: Decimal,
+    fee_headroom_percentage: u32,
+    signature_proofs_cleared_after: Option<usize>,
+    resource_audit_mode: ResourceAuditMode,
+    metrics: M,
+    hooks: H,
+    clock: Box<dyn Clock>,
+    log_sink: Box<dyn LogSink>,
+    /// The number of read-only calls made, which tells the hashes of identical calls apart.
+    read_only_calls: u64,
 }
 
-impl<'l, L: SubstateStore> NonceProvider for TransactionExecutor<'l, L> {
+impl<'l, L: SubstateStore, M: EngineMetrics, H: EngineHooks> NonceProvider
+    for TransactionExecutor<'l, L, M, H>
+{
     fn get_nonce<PKS: AsRef<[EcdsaPublicKey]>>(&self, _intended_signers: PKS) -> u64 {
         self.substate_store.get_nonce()
     }
 }
 
-impl<'l, L: SubstateStore> AbiProvider for TransactionExecutor<'l, L> {
+impl<'l, L: SubstateStore, M: EngineMetrics, H: EngineHooks> AbiProvider
+    for TransactionExecutor<'l, L, M, H>
+{
     fn export_abi(
         &self,
         package_address: PackageAddress,
         blueprint_name: &str,
     ) -> Result<abi::Blueprint, RuntimeError> {
-        let package: Package = self
+        let package = self
             .substate_store
-            .get_decoded_substate(&package_address)
-            .map(|(package, _)| package)
+            .get_package(&package_address)
             .ok_or(RuntimeError::PackageNotFound(package_address))?;
 
         BasicAbiProvider::new(self.trace)
@@ -50,32 +81,238 @@ impl<'l, L: SubstateStore> AbiProvider for TransactionExecutor<'l, L> {
             .get_decoded_substate(&component_address)
             .map(|(component, _)| component)
             .ok_or(RuntimeError::ComponentNotFound(component_address))?;
-        let package: Package = self
+        let package = self
             .substate_store
-            .get_decoded_substate(&component.package_address())
-            .map(|(package, _)| package)
+            .get_package(&component.package_address())
             .unwrap();
         BasicAbiProvider::new(self.trace)
             .with_package(&component.package_address(), package)
             .export_abi(component.package_address(), component.blueprint_name())
     }
+
+    fn export_types(&self, package_address: PackageAddress) -> Result<abi::Types, RuntimeError> {
+        let package: Package = self
+            .substate_store
+            .get_decoded_substate(&package_address)
+            .map(|(package, _)| package)
+            .ok_or(RuntimeError::PackageNotFound(package_address))?;
+
+        Ok(abi::Types {
+            package_address: package_address.to_string(),
+            types: package.types().to_vec(),
+        })
+    }
 }
 
-impl<'l, L: SubstateStore> TransactionExecutor<'l, L> {
+impl<'l, L: SubstateStore> TransactionExecutor<'l, L, NoOpMetrics, NoOpHooks> {
     pub fn new(substate_store: &'l mut L, trace: bool) -> Self {
         Self {
             substate_store,
             trace,
+            network: NetworkDefinition::simulator(),
+            substate_cache: SubstateCache::default(),
+            wasm_instance_pool: WasmInstancePool::default(),
+            cost_unit_limit: DEFAULT_COST_UNIT_LIMIT,
+            max_logs: DEFAULT_MAX_LOGS,
+            max_log_bytes: DEFAULT_MAX_LOG_BYTES,
+            cost_unit_price: Decimal::from_str(DEFAULT_COST_UNIT_PRICE).unwrap(),
+            fee_headroom_percentage: DEFAULT_FEE_HEADROOM_PERCENTAGE,
+            signature_proofs_cleared_after: None,
+            resource_audit_mode: ResourceAuditMode::Off,
+            metrics: NoOpMetrics,
+            hooks: NoOpHooks,
+            clock: Box::new(DefaultClock::default()),
+            log_sink: Box::new(DefaultLogSink::default()),
+            read_only_calls: 0,
+        }
+    }
+}
+
+impl<'l, L: SubstateStore, M: EngineMetrics, H: EngineHooks> TransactionExecutor<'l, L, M, H> {
+    /// Sets the sink which the metrics of executed transactions are recorded to.
+    pub fn with_metrics<N: EngineMetrics>(self, metrics: N) -> TransactionExecutor<'l, L, N, H> {
+        TransactionExecutor {
+            substate_store: self.substate_store,
+            trace: self.trace,
+            network: self.network,
+            substate_cache: self.substate_cache,
+            wasm_instance_pool: self.wasm_instance_pool,
+            cost_unit_limit: self.cost_unit_limit,
+            max_logs: self.max_logs,
+            max_log_bytes: self.max_log_bytes,
+            cost_unit_price: self.cost_unit_price,
+            fee_headroom_percentage: self.fee_headroom_percentage,
+            signature_proofs_cleared_after: self.signature_proofs_cleared_after,
+            resource_audit_mode: self.resource_audit_mode,
+            metrics,
+            hooks: self.hooks,
+            clock: self.clock,
+            log_sink: self.log_sink,
+            read_only_calls: self.read_only_calls,
         }
     }
 
+    /// Returns the metrics sink.
+    pub fn metrics(&self) -> &M {
+        &self.metrics
+    }
+
+    /// Returns a mutable reference to the metrics sink.
+    pub fn metrics_mut(&mut self) -> &mut M {
+        &mut self.metrics
+    }
+
+    /// Sets the hooks which are called around the SNode invocations and substate accesses of
+    /// executed transactions.
+    ///
+    /// Several hooks can be layered by passing them as a tuple.
+    pub fn with_hooks<N: EngineHooks>(self, hooks: N) -> TransactionExecutor<'l, L, M, N> {
+        TransactionExecutor {
+            substate_store: self.substate_store,
+            trace: self.trace,
+            network: self.network,
+            substate_cache: self.substate_cache,
+            wasm_instance_pool: self.wasm_instance_pool,
+            cost_unit_limit: self.cost_unit_limit,
+            max_logs: self.max_logs,
+            max_log_bytes: self.max_log_bytes,
+            cost_unit_price: self.cost_unit_price,
+            fee_headroom_percentage: self.fee_headroom_percentage,
+            signature_proofs_cleared_after: self.signature_proofs_cleared_after,
+            resource_audit_mode: self.resource_audit_mode,
+            metrics: self.metrics,
+            hooks,
+            clock: self.clock,
+            log_sink: self.log_sink,
+            read_only_calls: self.read_only_calls,
+        }
+    }
+
+    /// Returns the hooks.
+    pub fn hooks(&self) -> &H {
+        &self.hooks
+    }
+
+    /// Returns a mutable reference to the hooks.
+    pub fn hooks_mut(&mut self) -> &mut H {
+        &mut self.hooks
+    }
+
+    /// Sets the clock which the execution time of transactions is measured with.
+    ///
+    /// With `NoClock`, execution time is left out of receipts and metrics.
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Sets the sink which trace messages are written to.
+    pub fn with_log_sink<T: LogSink + 'static>(mut self, log_sink: T) -> Self {
+        self.log_sink = Box::new(log_sink);
+        self
+    }
+
+    /// Sets the maximum number of cost units a transaction can consume.
+    pub fn with_cost_unit_limit(mut self, cost_unit_limit: u32) -> Self {
+        self.cost_unit_limit = cost_unit_limit;
+        self
+    }
+
+    /// Sets the maximum number of log messages, and their maximum total size in bytes, a
+    /// transaction can emit.
+    pub fn with_log_limits(mut self, max_logs: usize, max_log_bytes: usize) -> Self {
+        self.max_logs = max_logs;
+        self.max_log_bytes = max_log_bytes;
+        self
+    }
+
+    /// Sets the cost unit price and the headroom percentage used by cost estimation.
+    pub fn with_fee_estimation(
+        mut self,
+        cost_unit_price: Decimal,
+        headroom_percentage: u32,
+    ) -> Self {
+        self.cost_unit_price = cost_unit_price;
+        self.fee_headroom_percentage = headroom_percentage;
+        self
+    }
+
+    /// Drops the signature proofs from the auth zone once the instruction at the given index
+    /// has been executed.
+    ///
+    /// Instructions after it, and the components they call, can't act on behalf of the signers.
+    pub fn with_signature_proofs_cleared_after(mut self, instruction_index: usize) -> Self {
+        self.signature_proofs_cleared_after = Some(instruction_index);
+        self
+    }
+
+    /// Sets whether transactions are audited for resource conservation, i.e. whether the amount
+    /// of every resource held by vaults and lockers changed by as much as its total supply.
+    pub fn with_resource_audit(mut self, mode: ResourceAuditMode) -> Self {
+        self.resource_audit_mode = mode;
+        self
+    }
+
+    /// Sets the number of substates kept in the cache shared by transactions.
+    pub fn with_substate_cache_capacity(mut self, capacity: usize) -> Self {
+        self.substate_cache = SubstateCache::new(capacity);
+        self
+    }
+
+    /// Returns the statistics of the substate cache shared by transactions.
+    pub fn substate_cache_stats(&self) -> SubstateCacheStats {
+        self.substate_cache.stats()
+    }
+
+    /// Sets the number of distinct packages whose WASM instances are kept for reuse.
+    pub fn with_wasm_instance_pool_capacity(mut self, capacity: usize) -> Self {
+        self.wasm_instance_pool = WasmInstancePool::new(capacity);
+        self
+    }
+
+    /// Returns the statistics of the WASM instance pool shared by transactions.
+    pub fn wasm_instance_pool_stats(&self) -> WasmInstancePoolStats {
+        self.wasm_instance_pool.stats()
+    }
+
+    /// Sets the network transactions are executed on.
+    ///
+    /// Transactions whose header has another network id are rejected.
+    pub fn with_network(mut self, network: NetworkDefinition) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Returns the network transactions are executed on.
+    pub fn network(&self) -> &NetworkDefinition {
+        &self.network
+    }
+
+    /// Returns the engine version transactions are executed with, as recorded in the ledger.
+    pub fn engine_version(&self) -> EngineVersion {
+        self.substate_store.get_engine_version()
+    }
+
     /// Returns an immutable reference to the ledger.
     pub fn substate_store(&self) -> &L {
         self.substate_store
     }
 
+    /// Returns the read-only half of this executor, which can serve previews and read-only
+    /// calls from several threads while no transaction is being executed.
+    pub fn query_executor(&self) -> QueryExecutor<'_, L> {
+        QueryExecutor::new(&*self.substate_store, self.trace)
+            .with_network(self.network.clone())
+            .with_cost_unit_limit(self.cost_unit_limit)
+            .with_log_limits(self.max_logs, self.max_log_bytes)
+            .with_signature_proofs_cleared_after(self.signature_proofs_cleared_after)
+    }
+
     /// Returns a mutable reference to the ledger.
+    ///
+    /// The substate cache is cleared, as the ledger may be modified outside of a transaction.
     pub fn substate_store_mut(&mut self) -> &mut L {
+        self.substate_cache.clear();
         self.substate_store
     }
 
@@ -89,14 +326,23 @@ impl<'l, L: SubstateStore> TransactionExecutor<'l, L> {
         (public_key, private_key)
     }
 
+    /// Derives a key pair from a seed, so that the same seed always yields the same key pair.
+    pub fn new_key_pair_with_seed(&self, seed: u64) -> (EcdsaPublicKey, EcdsaPrivateKey) {
+        let mut bytes = b"seed".to_vec();
+        bytes.extend(seed.to_le_bytes());
+        let private_key = EcdsaPrivateKey::from_bytes(hash(bytes).as_ref()).unwrap();
+        let public_key = private_key.public_key();
+        (public_key, private_key)
+    }
+
     /// Creates an account with 1,000,000 XRD in balance.
     pub fn new_account_with_auth_rule(&mut self, withdraw_auth: &AccessRule) -> ComponentAddress {
         let receipt = self
             .validate_and_execute(
-                &TransactionBuilder::new()
+                &TransactionBuilder::new_for_network(&self.network)
                     .call_method(SYSTEM_COMPONENT, "free_xrd", vec![])
                     .take_from_worktop(RADIX_TOKEN, |builder, bucket_id| {
-                        builder.new_account_with_resource(withdraw_auth, bucket_id)
+                        builder.create_account(withdraw_auth.clone(), Some(bucket_id))
                     })
                     .build(self.get_nonce([]))
                     .sign([]),
@@ -110,11 +356,40 @@ impl<'l, L: SubstateStore> TransactionExecutor<'l, L> {
     /// Creates a new key and an account which can be accessed using the key.
     pub fn new_account(&mut self) -> (EcdsaPublicKey, EcdsaPrivateKey, ComponentAddress) {
         let (public_key, private_key) = self.new_key_pair();
+        let account = self.new_account_with_public_key(&public_key);
+        (public_key, private_key, account)
+    }
+
+    /// Creates an account which can be accessed using the key pair derived from a seed.
+    ///
+    /// The key pair only depends on the seed, and the account address on the ledger history.
+    pub fn new_account_with_seed(
+        &mut self,
+        seed: u64,
+    ) -> (EcdsaPublicKey, EcdsaPrivateKey, ComponentAddress) {
+        let (public_key, private_key) = self.new_key_pair_with_seed(seed);
+        let account = self.new_account_with_public_key(&public_key);
+        (public_key, private_key, account)
+    }
+
+    fn new_account_with_public_key(&mut self, public_key: &EcdsaPublicKey) -> ComponentAddress {
         let id = NonFungibleId::from_bytes(public_key.to_vec());
         let auth_address = NonFungibleAddress::new(ECDSA_TOKEN, id);
         let withdraw_auth = rule!(require(auth_address));
-        let account = self.new_account_with_auth_rule(&withdraw_auth);
-        (public_key, private_key, account)
+        self.new_account_with_auth_rule(&withdraw_auth)
+    }
+
+    /// Creates pre-funded test accounts, using seeds `0` to `count - 1`.
+    ///
+    /// When called on a freshly bootstrapped ledger, the same keys and addresses are returned
+    /// every time.
+    pub fn new_test_accounts(
+        &mut self,
+        count: u64,
+    ) -> Vec<(EcdsaPublicKey, EcdsaPrivateKey, ComponentAddress)> {
+        (0..count)
+            .map(|seed| self.new_account_with_seed(seed))
+            .collect()
     }
 
     /// Publishes a package.
@@ -124,7 +399,7 @@ impl<'l, L: SubstateStore> TransactionExecutor<'l, L> {
     ) -> Result<PackageAddress, RuntimeError> {
         let receipt = self
             .validate_and_execute(
-                &TransactionBuilder::new()
+                &TransactionBuilder::new_for_network(&self.network)
                     .publish_package(code.as_ref())
                     .build(self.get_nonce([]))
                     .sign([]),
@@ -134,22 +409,46 @@ impl<'l, L: SubstateStore> TransactionExecutor<'l, L> {
         if receipt.result.is_ok() {
             Ok(receipt.new_package_addresses[0])
         } else {
-            Err(receipt.result.err().unwrap())
+            Err(receipt.result.err().unwrap().cause)
         }
     }
 
     /// Overwrites a package.
+    ///
+    /// The package version is increased, so that the state of existing components is migrated
+    /// the first time they are called, or with `migrate_components`.
     pub fn overwrite_package(
         &mut self,
         package_address: PackageAddress,
         code: Vec<u8>,
     ) -> Result<(), WasmValidationError> {
+        let features = self.engine_version().features();
+        let package = match self
+            .substate_store
+            .get_decoded_substate::<_, Package>(&package_address)
+        {
+            Some((package, _)) => package.upgrade(code, &features)?,
+            None => Package::new_with_features(code, &features)?,
+        };
         let tx_hash = hash(self.substate_store.get_and_increase_nonce().to_le_bytes());
         let mut id_gen = SubstateIdGenerator::new(tx_hash);
 
-        let package = Package::new(code)?;
         self.substate_store
-            .put_encoded_substate(&package_address, &package, id_gen.next());
+            .put_package(&package_address, &package, &mut id_gen);
+        self.substate_cache.clear();
+        Ok(())
+    }
+
+    fn check_network(
+        &self,
+        validated: &ValidatedTransaction,
+    ) -> Result<(), TransactionValidationError> {
+        if validated.network_id != self.network.id {
+            return Err(TransactionValidationError::InvalidNetwork {
+                expected: self.network.id,
+                actual: validated.network_id,
+            });
+        }
         Ok(())
     }
 
@@ -158,26 +457,283 @@ impl<'l, L: SubstateStore> TransactionExecutor<'l, L> {
         signed: &SignedTransaction,
     ) -> Result<Receipt, TransactionValidationError> {
         let validated = signed.validate()?;
+        self.check_network(&validated)?;
+        let receipt = self.execute(validated);
+        Ok(receipt)
+    }
+
+    pub fn validate_and_execute_composite(
+        &mut self,
+        composite: &CompositeTransaction,
+    ) -> Result<Receipt, TransactionValidationError> {
+        let validated = composite.validate()?;
+        self.check_network(&validated)?;
         let receipt = self.execute(validated);
         Ok(receipt)
     }
 
+    /// Validates and executes a system transaction, which is authorized by the system badge.
+    pub fn validate_and_execute_system(
+        &mut self,
+        system: &SystemTransaction,
+    ) -> Result<Receipt, TransactionValidationError> {
+        let validated = system.validate()?;
+        self.check_network(&validated)?;
+        let receipt = self.execute_with_system_badge(validated, true);
+        Ok(receipt)
+    }
+
+    /// Executes a transaction without committing, and returns its receipt.
+    ///
+    /// As with `estimate_cost`, the intended signers are trusted instead of signatures.
+    pub fn preview<PKS: AsRef<[EcdsaPublicKey]>>(
+        &mut self,
+        transaction: &Transaction,
+        intended_signers: PKS,
+    ) -> Result<Receipt, TransactionValidationError> {
+        let mut validated = SignedTransaction {
+            transaction: transaction.clone(),
+            signatures: Vec::new(),
+        }
+        .validate()?;
+        self.check_network(&validated)?;
+        validated.signers = intended_signers.as_ref().to_vec();
+
+        Ok(self.execute_internal(validated, false, true))
+    }
+
+    /// Estimates the cost of a transaction, by executing it without committing.
+    ///
+    /// Signatures are not checked; the intended signers are trusted instead, so that the cost
+    /// can be estimated before signing. The cost unit limit still applies, so the estimate of a
+    /// transaction which exceeds it is a failed preview.
+    pub fn estimate_cost<PKS: AsRef<[EcdsaPublicKey]>>(
+        &mut self,
+        transaction: &Transaction,
+        intended_signers: PKS,
+    ) -> Result<CostEstimate, TransactionValidationError> {
+        let mut validated = SignedTransaction {
+            transaction: transaction.clone(),
+            signatures: Vec::new(),
+        }
+        .validate()?;
+        self.check_network(&validated)?;
+        validated.signers = intended_signers.as_ref().to_vec();
+
+        let receipt = self.execute_internal(validated, false, true);
+        Ok(CostEstimate::new(
+            receipt.cost_units_consumed,
+            self.cost_unit_price,
+            self.fee_headroom_percentage,
+            receipt.result,
+        ))
+    }
+
+    /// Predicts the packages, components and resources a transaction creates, in creation order,
+    /// by executing it without committing.
+    ///
+    /// As with `estimate_cost`, the intended signers are trusted instead of signatures. Addresses
+    /// only depend on the transaction hash, which doesn't cover signatures, so they can be
+    /// computed before signing. If the transaction fails, only the entities created before the
+    /// failure are returned.
+    pub fn predict_addresses<PKS: AsRef<[EcdsaPublicKey]>>(
+        &mut self,
+        transaction: &Transaction,
+        intended_signers: PKS,
+    ) -> Result<Vec<NewEntity>, TransactionValidationError> {
+        let mut validated = SignedTransaction {
+            transaction: transaction.clone(),
+            signatures: Vec::new(),
+        }
+        .validate()?;
+        self.check_network(&validated)?;
+        validated.signers = intended_signers.as_ref().to_vec();
+
+        let receipt = self.execute_internal(validated, false, true);
+        Ok(receipt
+            .new_global_entities
+            .into_iter()
+            .filter(|entity| {
+                matches!(
+                    entity.address,
+                    EntityAddress::Package(_)
+                        | EntityAddress::Component(_)
+                        | EntityAddress::Resource(_)
+                )
+            })
+            .collect())
+    }
+
+    /// Migrates the state of the given components to the latest version of their packages,
+    /// with the `migrate_state` functions of their blueprints.
+    ///
+    /// Returns the components whose state was behind their package. Nothing is committed if
+    /// any migration fails.
+    pub fn migrate_components(
+        &mut self,
+        component_addresses: &[ComponentAddress],
+    ) -> Result<Vec<ComponentAddress>, RuntimeError> {
+        let tx_hash = hash(self.substate_store.get_nonce().to_le_bytes());
+        let mut track = Track::new(self.substate_store, tx_hash, Vec::new())
+            .with_substate_cache(&mut self.substate_cache)
+            .with_wasm_instance_pool(&mut self.wasm_instance_pool)
+            .with_clock(self.clock.as_ref())
+            .with_log_sink(self.log_sink.as_ref());
+
+        let mut migrated = Vec::new();
+        let mut proc = track.start_process(self.trace);
+        for component_address in component_addresses {
+            if proc.migrate_component(*component_address)? {
+                migrated.push(*component_address);
+            }
+        }
+
+        track.commit();
+        self.substate_store.increase_nonce();
+        Ok(migrated)
+    }
+
+    /// Runs the `#[scrypto_test]` functions of a package against the current ledger state.
+    ///
+    /// Each test runs in its own transaction context and its state changes are discarded.
+    pub fn run_package_tests(
+        &mut self,
+        package_address: PackageAddress,
+    ) -> Result<Vec<PackageTestResult>, RuntimeError> {
+        let package: Package = self
+            .substate_store
+            .get_decoded_substate(&package_address)
+            .map(|(package, _)| package)
+            .ok_or(RuntimeError::PackageNotFound(package_address))?;
+
+        let mut results = Vec::new();
+        for test_name in package.tests() {
+            let mut track = Track::new(self.substate_store, hash(test_name.as_bytes()), Vec::new())
+                .with_clock(self.clock.as_ref())
+                .with_log_sink(self.log_sink.as_ref());
+            let result = track
+                .start_process(self.trace)
+                .call_test(package_address, test_name)
+                .map(|_| ());
+            results.push(PackageTestResult {
+                test_name: test_name.clone(),
+                result,
+                logs: track.logs().clone(),
+            });
+        }
+        Ok(results)
+    }
+
+    /// Calls a method of a component against the current ledger state, outside of a transaction.
+    ///
+    /// No signatures or nonce are involved and nothing is committed. The call fails if it
+    /// changes any state, which makes it suitable for serving "view" calls.
+    pub fn call_read_only(
+        &mut self,
+        component_address: ComponentAddress,
+        method: &str,
+        args: Vec<Vec<u8>>,
+    ) -> Result<ScryptoValue, RuntimeError> {
+        // The call stands in for a transaction, so it gets a hash of its own
+        let call_hash = hash(scrypto_encode(&(
+            component_address,
+            method,
+            args.clone(),
+            self.substate_store.get_nonce(),
+            self.read_only_calls,
+        )));
+        self.read_only_calls += 1;
+
+        let args = args
+            .iter()
+            .map(|arg| ScryptoValue::from_slice(arg))
+            .collect::<Result<Vec<ScryptoValue>, _>>()
+            .map_err(RuntimeError::ParseScryptoValueError)?;
+
+        let mut track = Track::new(self.substate_store, call_hash, Vec::new())
+            .with_substate_cache(&mut self.substate_cache)
+            .with_wasm_instance_pool(&mut self.wasm_instance_pool)
+            .with_hooks(&mut self.hooks)
+            .with_clock(self.clock.as_ref())
+            .with_log_sink(self.log_sink.as_ref())
+            .with_log_limits(self.max_logs, self.max_log_bytes)
+            .with_cost_unit_limit(self.cost_unit_limit);
+        let result = track.start_process(self.trace).invoke_snode(
+            SNodeRef::Scrypto(ScryptoActor::Component(component_address)),
+            method.to_string(),
+            args,
+        )?;
+
+        if track.has_state_updates() {
+            return Err(RuntimeError::StateChangedInReadOnlyCall);
+        }
+        Ok(result)
+    }
+
     pub fn execute(&mut self, validated: ValidatedTransaction) -> Receipt {
-        #[cfg(not(feature = "alloc"))]
-        let now = std::time::Instant::now();
+        self.execute_with_system_badge(validated, false)
+    }
+
+    fn execute_with_system_badge(
+        &mut self,
+        validated: ValidatedTransaction,
+        system_badge: bool,
+    ) -> Receipt {
+        self.execute_internal(validated, system_badge, false)
+    }
+
+    fn execute_internal(
+        &mut self,
+        validated: ValidatedTransaction,
+        system_badge: bool,
+        preview: bool,
+    ) -> Receipt {
+        let start = self.clock.now_millis();
 
         let mut track = Track::new(
             self.substate_store,
             validated.raw_hash.clone(),
             validated.signers.clone(),
-        );
-        let mut proc = track.start_process(self.trace);
+        )
+        .with_substate_cache(&mut self.substate_cache)
+        .with_wasm_instance_pool(&mut self.wasm_instance_pool)
+        .with_hooks(&mut self.hooks)
+        .with_clock(self.clock.as_ref())
+        .with_log_sink(self.log_sink.as_ref())
+        .with_log_limits(self.max_logs, self.max_log_bytes)
+        // Previews run under the same limit, so that a transaction which would run out of cost
+        // units fails its preview too
+        .with_cost_unit_limit(self.cost_unit_limit);
+        let mut proc = if system_badge {
+            track.start_system_process(self.trace)
+        } else {
+            track.start_process(self.trace)
+        };
 
-        let txn_process = TransactionProcess::new(validated.clone());
+        let txn_process = TransactionProcess::new(validated.clone())
+            .with_signature_proofs_cleared_after(self.signature_proofs_cleared_after)
+            .with_system_badge(system_badge);
         let mut txn_snode = SNodeState::Transaction(txn_process);
+        let mut resource_audit = None;
         let error = match proc.run(&mut txn_snode, "execute".to_string(), vec![]) {
-            Ok(_) => None,
-            Err(e) => Some(e),
+            Ok(_) => Self::audit_resources(self.resource_audit_mode, &track, &mut resource_audit)
+                .and_then(|()| track.run_substate_write_hooks())
+                .and_then(|()| {
+                    track
+                        .consume_substate_cost_units()
+                        .map_err(RuntimeError::CostingError)
+                })
+                .err()
+                .map(|e| TransactionError::new(e, Vec::new())),
+            Err(e) => Some(TransactionError::new(
+                e,
+                track.failed_call_stack().cloned().unwrap_or_default(),
+            )),
+        };
+        // The outcome of a transaction which read a substate not fetched yet isn't meaningful
+        let error = match track.check_fetched() {
+            Err(e) => Some(TransactionError::new(e, Vec::new())),
+            Ok(()) => error,
         };
         let outputs = if let SNodeState::Transaction(txn_process) = txn_snode {
             txn_process.outputs().to_vec()
@@ -189,10 +745,25 @@ impl<'l, L: SubstateStore> TransactionExecutor<'l, L> {
         let new_package_addresses = track.new_package_addresses();
         let new_component_addresses = track.new_component_addresses();
         let new_resource_addresses = track.new_resource_addresses();
+        // Entities are only reported once committed, or once they would be for a preview
+        let (new_global_entities, new_owned_entities): (Vec<NewEntity>, Vec<NewEntity>) =
+            if error.is_none() {
+                track
+                    .new_entities()
+                    .into_iter()
+                    .partition(|entity| entity.address.is_global())
+            } else {
+                (Vec::new(), Vec::new())
+            };
         let logs = track.logs().clone();
+        let events = track.events().clone();
+        let substate_read_stats = track.read_stats();
+        let cost_units_consumed = track.cost_unit_counter().consumed();
+        let cost_unit_breakdown = track.cost_unit_counter().breakdown().clone();
+        let invocation_counts = track.invocation_counts().clone();
 
         // commit state updates
-        let commit_receipt = if error.is_none() {
+        let commit_receipt = if error.is_none() && !preview {
             let receipt = track.commit();
             self.substate_store.increase_nonce();
             Some(receipt)
@@ -202,24 +773,73 @@ impl<'l, L: SubstateStore> TransactionExecutor<'l, L> {
 
 
 
-        #[cfg(feature = "alloc")]
-        let execution_time = None;
-        #[cfg(not(feature = "alloc"))]
-        let execution_time = Some(now.elapsed().as_millis());
+        let execution_time = start
+            .zip(self.clock.now_millis())
+            .map(|(start, end)| end - start);
+
+        // record metrics
+        if !preview {
+            for (snode_type, count) in invocation_counts {
+                self.metrics.record_invocations(snode_type, count);
+            }
+            self.metrics.record_substate_reads(substate_read_stats);
+            if let Some(receipt) = &commit_receipt {
+                self.metrics
+                    .record_substate_writes(receipt.up_substates.len() as u64);
+            }
+            self.metrics.record_cost_units(cost_units_consumed);
+            if let Some(millis) = execution_time {
+                self.metrics.record_execution_time(millis);
+            }
+        }
 
         Receipt {
             commit_receipt,
             validated_transaction: validated.clone(),
+            transaction_hashes: validated.hashes.clone(),
             result: match error {
                 Some(error) => Err(error),
                 None => Ok(()),
             },
             outputs,
             logs,
+            events,
             new_package_addresses,
             new_component_addresses,
             new_resource_addresses,
+            new_global_entities,
+            new_owned_entities,
             execution_time,
+            substate_read_stats,
+            cost_units_consumed,
+            cost_unit_breakdown,
+            resource_audit,
         }
     }
+
+    fn audit_resources<S: SubstateStore>(
+        mode: ResourceAuditMode,
+        track: &Track<S>,
+        resource_audit: &mut Option<ResourceAudit>,
+    ) -> Result<(), RuntimeError> {
+        if mode == ResourceAuditMode::Off {
+            return Ok(());
+        }
+        let audit = track.resource_audit();
+        let result = mode.check(&audit);
+        *resource_audit = Some(audit);
+        result
+    }
+}
+
+impl<'l, L: SubstateStore + QueryableSubstateStore, M: EngineMetrics, H: EngineHooks>
+    TransactionExecutor<'l, L, M, H>
+{
+    /// Returns the total amount of each resource held by a component.
+    pub fn get_component_resources(
+        &self,
+        component_address: ComponentAddress,
+    ) -> HashMap<ResourceAddress, Decimal> {
+        get_component_resources(&*self.substate_store, component_address)
+    }
 }
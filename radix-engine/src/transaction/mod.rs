@@ -1,11 +1,18 @@
 mod abi_provider;
+mod arg_parser;
+#[cfg(not(feature = "alloc"))]
+mod async_executor;
 mod builder;
 mod error;
 mod executor;
 mod nonce_provider;
+mod query_executor;
 
 pub use abi_provider::{AbiProvider, BasicAbiProvider};
+#[cfg(not(feature = "alloc"))]
+pub use async_executor::AsyncTransactionExecutor;
 pub use builder::TransactionBuilder;
 pub use error::{BuildArgsError, CallWithAbiError};
 pub use executor::TransactionExecutor;
 pub use nonce_provider::NonceProvider;
+pub use query_executor::QueryExecutor;
@@ -0,0 +1,119 @@
+use scrypto::core::NetworkDefinition;
+use scrypto::crypto::EcdsaPublicKey;
+use scrypto::engine::types::*;
+use scrypto::rust::vec::Vec;
+use scrypto::values::ScryptoValue;
+
+use crate::engine::*;
+use crate::errors::*;
+use crate::fee::*;
+use crate::ledger::*;
+use crate::model::*;
+use crate::transaction::*;
+
+/// The read-only half of an executor, which serves previews and read-only calls through a
+/// shared reference to the substate store.
+///
+/// Unlike `TransactionExecutor`, it's `Send + Sync` whenever the store is `Sync`, so gateways
+/// can serve queries concurrently from several threads, e.g. behind an `Arc`.
+///
+/// Each query runs without the substate cache and WASM instance pool of the executor, as those
+/// are mutated by every execution.
+pub struct QueryExecutor<'s, S: SubstateStore> {
+    substate_store: &'s S,
+    trace: bool,
+    network: NetworkDefinition,
+    cost_unit_limit: u32,
+    max_logs: usize,
+    max_log_bytes: usize,
+    signature_proofs_cleared_after: Option<usize>,
+}
+
+impl<'s, S: SubstateStore> QueryExecutor<'s, S> {
+    pub fn new(substate_store: &'s S, trace: bool) -> Self {
+        Self {
+            substate_store,
+            trace,
+            network: NetworkDefinition::simulator(),
+            cost_unit_limit: DEFAULT_COST_UNIT_LIMIT,
+            max_logs: DEFAULT_MAX_LOGS,
+            max_log_bytes: DEFAULT_MAX_LOG_BYTES,
+            signature_proofs_cleared_after: None,
+        }
+    }
+
+    /// Sets the network previewed transactions are bound to.
+    pub fn with_network(mut self, network: NetworkDefinition) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Sets the maximum number of cost units a read-only call can consume.
+    pub fn with_cost_unit_limit(mut self, cost_unit_limit: u32) -> Self {
+        self.cost_unit_limit = cost_unit_limit;
+        self
+    }
+
+    /// Sets the maximum number of log messages, and their maximum total size in bytes, a query
+    /// can emit.
+    pub fn with_log_limits(mut self, max_logs: usize, max_log_bytes: usize) -> Self {
+        self.max_logs = max_logs;
+        self.max_log_bytes = max_log_bytes;
+        self
+    }
+
+    /// Sets the instruction after which previewed transactions lose their signature proofs.
+    pub fn with_signature_proofs_cleared_after(mut self, instruction_index: Option<usize>) -> Self {
+        self.signature_proofs_cleared_after = instruction_index;
+        self
+    }
+
+    /// Returns an immutable reference to the ledger.
+    pub fn substate_store(&self) -> &S {
+        self.substate_store
+    }
+
+    /// Executes a transaction without committing, and returns its receipt.
+    ///
+    /// As with `TransactionExecutor::preview`, the intended signers are trusted instead of
+    /// signatures.
+    pub fn preview<PKS: AsRef<[EcdsaPublicKey]>>(
+        &self,
+        transaction: &Transaction,
+        intended_signers: PKS,
+    ) -> Result<Receipt, TransactionValidationError> {
+        let mut substate_store = ReadOnlySubstateStore::new(self.substate_store);
+        self.executor(&mut substate_store)
+            .preview(transaction, intended_signers)
+    }
+
+    /// Calls a method of a component against the current ledger state, outside of a transaction.
+    ///
+    /// See `TransactionExecutor::call_read_only`.
+    pub fn call_read_only(
+        &self,
+        component_address: ComponentAddress,
+        method: &str,
+        args: Vec<Vec<u8>>,
+    ) -> Result<ScryptoValue, RuntimeError> {
+        let mut substate_store = ReadOnlySubstateStore::new(self.substate_store);
+        self.executor(&mut substate_store)
+            .call_read_only(component_address, method, args)
+    }
+
+    fn executor<'r>(
+        &self,
+        substate_store: &'r mut ReadOnlySubstateStore<'s, S>,
+    ) -> TransactionExecutor<'r, ReadOnlySubstateStore<'s, S>> {
+        let executor = TransactionExecutor::new(substate_store, self.trace)
+            .with_network(self.network.clone())
+            .with_cost_unit_limit(self.cost_unit_limit)
+            .with_log_limits(self.max_logs, self.max_log_bytes);
+        match self.signature_proofs_cleared_after {
+            Some(instruction_index) => {
+                executor.with_signature_proofs_cleared_after(instruction_index)
+            }
+            None => executor,
+        }
+    }
+}
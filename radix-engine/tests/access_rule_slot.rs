@@ -0,0 +1,255 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::errors::RuntimeError;
+use radix_engine::ledger::InMemorySubstateStore;
+use scrypto::prelude::*;
+
+fn create_component(
+    test_runner: &mut TestRunner,
+    owner_badge: ResourceAddress,
+    admin_badge: ResourceAddress,
+) -> ComponentAddress {
+    let package_address = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package_address,
+            "RuleSlotComponent",
+            "create_component",
+            args![owner_badge, admin_badge],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    receipt.new_component_addresses[0]
+}
+
+#[test]
+fn method_protected_by_slot_requires_slot_rule() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (key, sk, account) = test_runner.new_account();
+    let owner_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let admin_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let component = create_component(&mut test_runner, owner_badge, admin_badge);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(component, "get_secret", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt_without_badge = test_runner.validate_and_execute(&transaction);
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(admin_badge, account)
+        .call_method(component, "get_secret", args![])
+        .build(test_runner.get_nonce([key]))
+        .sign([&sk]);
+    let receipt_with_badge = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt_without_badge
+        .result
+        .expect_err("Should be error")
+        .cause;
+    assert_auth_error!(error);
+    receipt_with_badge.result.expect("Should be okay");
+}
+
+#[test]
+fn owner_can_rotate_admin_badge() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (key, sk, account) = test_runner.new_account();
+    let owner_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let admin_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let new_admin_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let component = create_component(&mut test_runner, owner_badge, admin_badge);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(owner_badge, account)
+        .call_method(
+            component,
+            "set_rule",
+            args!["admin".to_string(), rule!(require(new_admin_badge))],
+        )
+        .build(test_runner.get_nonce([key]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(admin_badge, account)
+        .call_method(component, "get_secret", args![])
+        .build(test_runner.get_nonce([key]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert_auth_error!(error);
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(new_admin_badge, account)
+        .call_method(component, "get_secret", args![])
+        .build(test_runner.get_nonce([key]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+}
+
+#[test]
+fn cannot_update_slot_without_owner_badge() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (key, sk, account) = test_runner.new_account();
+    let owner_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let admin_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let component = create_component(&mut test_runner, owner_badge, admin_badge);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(admin_badge, account)
+        .call_method(
+            component,
+            "set_rule",
+            args!["admin".to_string(), rule!(allow_all)],
+        )
+        .build(test_runner.get_nonce([key]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert_auth_error!(error);
+}
+
+#[test]
+fn cannot_update_locked_slot() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (key, sk, account) = test_runner.new_account();
+    let owner_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let admin_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let component = create_component(&mut test_runner, owner_badge, admin_badge);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(owner_badge, account)
+        .call_method(
+            component,
+            "set_rule",
+            args!["locked".to_string(), rule!(deny_all)],
+        )
+        .build(test_runner.get_nonce([key]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert_auth_error!(error);
+}
+
+#[test]
+fn missing_slot_denies_access_and_cannot_be_updated() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (key, sk, account) = test_runner.new_account();
+    let owner_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let admin_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let component = create_component(&mut test_runner, owner_badge, admin_badge);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(component, "get_other_secret", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt_call = test_runner.validate_and_execute(&transaction);
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(owner_badge, account)
+        .call_method(
+            component,
+            "set_rule",
+            args!["missing".to_string(), rule!(allow_all)],
+        )
+        .build(test_runner.get_nonce([key]))
+        .sign([&sk]);
+    let receipt_update = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt_call.result.expect_err("Should be error").cause;
+    assert_auth_error!(error);
+    let error = receipt_update.result.expect_err("Should be error").cause;
+    assert_eq!(
+        error,
+        RuntimeError::AccessRuleSlotNotFound("missing".to_string())
+    );
+}
+
+#[test]
+fn cannot_create_slot_referring_to_slot() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package_address = test_runner.publish_package("component");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package_address,
+            "RuleSlotComponent",
+            "create_component_with_nested_slot",
+            args![],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert_eq!(error, RuntimeError::AccessRuleSlotNotAllowed);
+}
+
+#[test]
+fn cannot_update_slot_to_refer_to_slot() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (key, sk, account) = test_runner.new_account();
+    let owner_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let admin_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let component = create_component(&mut test_runner, owner_badge, admin_badge);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(owner_badge, account)
+        .call_method(
+            component,
+            "set_rule",
+            args!["admin".to_string(), AccessRule::slot("locked")],
+        )
+        .build(test_runner.get_nonce([key]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert_eq!(error, RuntimeError::AccessRuleSlotNotAllowed);
+}
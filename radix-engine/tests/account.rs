@@ -29,6 +29,32 @@ fn can_withdraw_from_my_account() {
     assert!(receipt.result.is_ok());
 }
 
+#[test]
+fn can_query_account_resources() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (_, _, other_account) = test_runner.new_account();
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account(RADIX_TOKEN, account)
+        .call_method_with_all_resources(other_account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+
+    // Assert
+    let resources = test_runner.get_component_resources(account);
+    assert_eq!(resources.get(&RADIX_TOKEN), Some(&Decimal::zero()));
+    let resources = test_runner.get_component_resources(other_account);
+    assert_eq!(resources.get(&RADIX_TOKEN), Some(&Decimal::from(2_000_000)));
+    assert_eq!(resources.len(), 1);
+}
+
 #[test]
 fn can_withdraw_non_fungible_from_my_account() {
     // Arrange
@@ -69,7 +95,7 @@ fn cannot_withdraw_from_other_account() {
     let receipt = test_runner.validate_and_execute(&transaction);
 
     // Assert
-    let error = receipt.result.expect_err("Should be runtime error");
+    let error = receipt.result.expect_err("Should be runtime error").cause;
     assert_auth_error!(error);
 }
 
@@ -122,3 +148,321 @@ fn test_account_balance() {
         ScryptoValue::from_value(&Decimal::from(1000000))
     );
 }
+
+#[test]
+fn account_state_should_not_grow_with_resources_held() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (_, _, account) = test_runner.new_account();
+    let state = test_runner.component(account).state().to_vec();
+
+    // Act
+    let resource_address = test_runner.create_fungible_resource(100.into(), 18, account);
+
+    // Assert
+    assert_eq!(test_runner.component(account).state(), &state[..]);
+    let resources = test_runner.get_component_resources(account);
+    assert_eq!(resources.get(&resource_address), Some(&Decimal::from(100)));
+    assert_eq!(resources.get(&RADIX_TOKEN), Some(&Decimal::from(1000000)));
+}
+
+#[test]
+fn withdrawing_unknown_resource_from_account_should_fail() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account(ECDSA_TOKEN, account)
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+
+    // Act
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be runtime error").cause;
+    assert_eq!(
+        error,
+        RuntimeError::AccountError(AccountError::ResourceNotFound(ECDSA_TOKEN))
+    );
+}
+
+#[test]
+fn test_accounts_are_deterministic() {
+    // Arrange
+    let mut substate_store1 = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner1 = TestRunner::new(&mut substate_store1);
+    let mut substate_store2 = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner2 = TestRunner::new(&mut substate_store2);
+
+    // Act
+    let accounts1 = test_runner1.new_test_accounts(3);
+    let accounts2 = test_runner2.new_test_accounts(3);
+
+    // Assert
+    assert_eq!(accounts1.len(), 3);
+    for ((pk1, _, account1), (pk2, _, account2)) in accounts1.iter().zip(accounts2.iter()) {
+        assert_eq!(pk1, pk2);
+        assert_eq!(account1, account2);
+        assert_eq!(
+            test_runner1.get_component_resources(*account1)[&RADIX_TOKEN],
+            dec!("1000000")
+        );
+    }
+    assert_ne!(accounts1[0].0, accounts1[1].0);
+}
+
+#[test]
+fn account_with_seed_can_be_withdrawn_from_with_seeded_key() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account_with_seed(42);
+    let (_, _, other_account) = test_runner.new_account();
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account(RADIX_TOKEN, account)
+        .call_method_with_all_resources(other_account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+}
+
+#[test]
+fn deposit_batch_safe_should_accept_allowed_resources() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (_, _, other_account) = test_runner.new_account();
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account(RADIX_TOKEN, account)
+        .call_method_with_all_resources_and_args(
+            other_account,
+            "deposit_batch_safe",
+            args![BTreeSet::from([RADIX_TOKEN])],
+        )
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    let resources = test_runner.get_component_resources(other_account);
+    assert_eq!(resources.get(&RADIX_TOKEN), Some(&Decimal::from(2_000_000)));
+}
+
+#[test]
+fn deposit_batch_safe_should_reject_unexpected_resources() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (_, _, other_account) = test_runner.new_account();
+    let junk_address = test_runner.create_fungible_resource(100.into(), 0, account);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account(RADIX_TOKEN, account)
+        .withdraw_from_account(junk_address, account)
+        .call_method_with_all_resources_and_args(
+            other_account,
+            "deposit_batch_safe",
+            args![BTreeSet::from([RADIX_TOKEN])],
+        )
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be runtime error").cause;
+    assert_eq!(
+        error,
+        RuntimeError::AccountError(AccountError::ResourceNotAllowed(junk_address))
+    );
+}
+
+fn new_account_with_spending_limit(
+    test_runner: &mut TestRunner,
+    limit: Decimal,
+    method: &str,
+) -> ComponentAddress {
+    let package = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package, "SpendingLimit", "new", args![limit])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    let delegate = receipt.new_component_addresses[0];
+
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            ACCOUNT_PACKAGE,
+            "Account",
+            "new_with_auth_delegate",
+            args![delegate, method.to_string()],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    let account = receipt.new_component_addresses[0];
+
+    let (pk, sk, funded_account) = test_runner.new_account();
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_amount(1000.into(), RADIX_TOKEN, funded_account)
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+
+    account
+}
+
+#[test]
+fn auth_delegate_can_authorize_withdrawal() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let account = new_account_with_spending_limit(&mut test_runner, 100.into(), "authorize");
+    let (_, _, other_account) = test_runner.new_account();
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_amount(60.into(), RADIX_TOKEN, account)
+        .call_method_with_all_resources(other_account, "deposit_batch")
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    let resources = test_runner.get_component_resources(account);
+    assert_eq!(resources.get(&RADIX_TOKEN), Some(&Decimal::from(940)));
+}
+
+#[test]
+fn auth_delegate_can_reject_withdrawal() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let account = new_account_with_spending_limit(&mut test_runner, 100.into(), "authorize");
+    let (_, _, other_account) = test_runner.new_account();
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_amount(60.into(), RADIX_TOKEN, account)
+        .call_method_with_all_resources(other_account, "deposit_batch")
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_amount(60.into(), RADIX_TOKEN, account)
+        .call_method_with_all_resources(other_account, "deposit_batch")
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be runtime error").cause;
+    assert_eq!(
+        error,
+        RuntimeError::AccountError(AccountError::NotAuthorizedByDelegate(
+            "withdraw_by_amount".to_string()
+        ))
+    );
+}
+
+#[test]
+fn auth_delegate_exceeding_cost_limit_should_fail() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let account =
+        new_account_with_spending_limit(&mut test_runner, 100.into(), "authorize_expensively");
+    let (_, _, other_account) = test_runner.new_account();
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_amount(60.into(), RADIX_TOKEN, account)
+        .call_method_with_all_resources(other_account, "deposit_batch")
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be runtime error").cause;
+    assert_eq!(
+        error,
+        RuntimeError::AccountError(AccountError::AuthDelegateCostLimitExceeded)
+    );
+}
+
+#[test]
+fn can_create_account_with_initial_deposit() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_amount(Decimal::from(100), RADIX_TOKEN, account)
+        .take_from_worktop(RADIX_TOKEN, |builder, bucket_id| {
+            builder.create_account(rule!(allow_all), Some(bucket_id))
+        })
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    let new_account = receipt.new_component_addresses[0];
+    let resources = test_runner.get_component_resources(new_account);
+    assert_eq!(resources.get(&RADIX_TOKEN), Some(&Decimal::from(100)));
+}
+
+#[test]
+fn can_create_empty_account() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_account(rule!(allow_all), None)
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    let new_account = receipt.new_component_addresses[0];
+    assert!(test_runner.get_component_resources(new_account).is_empty());
+}
@@ -0,0 +1,301 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::engine::EntityAddress;
+use radix_engine::errors::RuntimeError;
+use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::model::{AllowanceError, SystemTransaction};
+use scrypto::prelude::*;
+
+struct AllowanceSetup {
+    pk: EcdsaPublicKey,
+    sk: EcdsaPrivateKey,
+    account: ComponentAddress,
+    token: ResourceAddress,
+    badge: ResourceAddress,
+    grantor: ComponentAddress,
+    spender: ComponentAddress,
+}
+
+fn setup(test_runner: &mut TestRunner) -> AllowanceSetup {
+    let (pk, sk, account) = test_runner.new_account();
+    let token = test_runner.create_fungible_resource(1000.into(), 18, account);
+    setup_with_token(test_runner, pk, sk, account, token, 100.into())
+}
+
+fn setup_with_token(
+    test_runner: &mut TestRunner,
+    pk: EcdsaPublicKey,
+    sk: EcdsaPrivateKey,
+    account: ComponentAddress,
+    token: ResourceAddress,
+    amount: Decimal,
+) -> AllowanceSetup {
+    let badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let package = test_runner.publish_package("component");
+    let grantor = test_runner.instantiate_component(
+        package,
+        "AllowanceGrantor",
+        "new",
+        vec![format!("{},{}", amount, token)],
+        account,
+        pk,
+        &sk,
+    );
+    let spender = test_runner.instantiate_component(
+        package,
+        "AllowanceSpender",
+        "new",
+        vec![format!("1,{}", badge)],
+        account,
+        pk,
+        &sk,
+    );
+    AllowanceSetup {
+        pk,
+        sk,
+        account,
+        token,
+        badge,
+        grantor,
+        spender,
+    }
+}
+
+fn grant(
+    test_runner: &mut TestRunner,
+    setup: &AllowanceSetup,
+    max_amount: Decimal,
+    expiry_epoch: u64,
+) -> Allowance {
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(
+            setup.grantor,
+            "grant",
+            args![max_amount, expiry_epoch, setup.badge],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    scrypto_decode(&receipt.outputs[0].raw).unwrap()
+}
+
+fn redeem(
+    test_runner: &mut TestRunner,
+    setup: &AllowanceSetup,
+    method: &str,
+    allowance: Allowance,
+    amount: Decimal,
+) -> Result<(), RuntimeError> {
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(setup.spender, method, args![allowance, amount])
+        .call_method_with_all_resources(setup.account, "deposit_batch")
+        .build(test_runner.get_nonce([setup.pk]))
+        .sign([&setup.sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.map_err(|e| e.cause)
+}
+
+#[test]
+fn can_redeem_allowance() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let setup = setup(&mut test_runner);
+    let allowance = grant(&mut test_runner, &setup, 50.into(), 10);
+
+    // Act
+    let result = redeem(&mut test_runner, &setup, "redeem", allowance, 30.into());
+
+    // Assert
+    result.expect("Should be okay");
+    assert_eq!(
+        test_runner
+            .get_component_resources(setup.account)
+            .get(&setup.token),
+        Some(&Decimal::from(930))
+    );
+    assert_eq!(
+        test_runner
+            .get_component_resources(setup.grantor)
+            .get(&setup.token),
+        Some(&Decimal::from(70))
+    );
+}
+
+#[test]
+fn cannot_redeem_more_than_allowance() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let setup = setup(&mut test_runner);
+    let allowance = grant(&mut test_runner, &setup, 50.into(), 10);
+    redeem(&mut test_runner, &setup, "redeem", allowance, 30.into()).expect("Should be okay");
+
+    // Act
+    let result = redeem(&mut test_runner, &setup, "redeem", allowance, 30.into());
+
+    // Assert
+    assert_eq!(
+        result.expect_err("Should be runtime error"),
+        RuntimeError::AllowanceError(AllowanceError::AmountExceeded {
+            requested: 30.into(),
+            remaining: 20.into(),
+        })
+    );
+}
+
+#[test]
+fn cannot_redeem_allowance_without_satisfying_redeem_rule() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let setup = setup(&mut test_runner);
+    let allowance = grant(&mut test_runner, &setup, 50.into(), 10);
+
+    // Act
+    let result = redeem(
+        &mut test_runner,
+        &setup,
+        "redeem_without_badge",
+        allowance,
+        30.into(),
+    );
+
+    // Assert
+    let error = result.expect_err("Should be runtime error");
+    assert_auth_error!(error);
+}
+
+#[test]
+fn cannot_redeem_revoked_allowance() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let setup = setup(&mut test_runner);
+    let allowance = grant(&mut test_runner, &setup, 50.into(), 10);
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(setup.grantor, "revoke", args![allowance])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+
+    // Act
+    let result = redeem(&mut test_runner, &setup, "redeem", allowance, 30.into());
+
+    // Assert
+    assert_eq!(
+        result.expect_err("Should be runtime error"),
+        RuntimeError::AllowanceError(AllowanceError::Revoked)
+    );
+}
+
+#[test]
+fn cannot_redeem_expired_allowance() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let setup = setup(&mut test_runner);
+    let allowance = grant(&mut test_runner, &setup, 50.into(), 10);
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(SYSTEM_COMPONENT, "set_epoch", args![11u64])
+        .build_with_no_nonce();
+    let receipt = test_runner.validate_and_execute_system(&SystemTransaction::new(transaction));
+    receipt.result.expect("Should be okay");
+
+    // Act
+    let result = redeem(&mut test_runner, &setup, "redeem", allowance, 30.into());
+
+    // Assert
+    assert_eq!(
+        result.expect_err("Should be runtime error"),
+        RuntimeError::AllowanceError(AllowanceError::Expired {
+            expiry_epoch: 10,
+            current_epoch: 11,
+        })
+    );
+}
+
+#[test]
+fn new_allowance_is_recorded_in_receipt() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let setup = setup(&mut test_runner);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(
+            setup.grantor,
+            "grant",
+            args![Decimal::from(50), 10u64, setup.badge],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    let allowance: Allowance = scrypto_decode(&receipt.outputs[0].raw).unwrap();
+    assert!(receipt
+        .new_global_entities
+        .iter()
+        .any(
+            |entity| entity.address == EntityAddress::Allowance(allowance.0)
+                && entity.instruction_index == Some(0)
+        ));
+}
+
+#[test]
+fn cannot_redeem_allowance_after_withdraw_rule_is_tightened() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let admin_auth = test_runner.create_non_fungible_resource(account);
+    let withdraw_auth = test_runner.create_non_fungible_resource(account);
+    let package = test_runner.publish_package("resource_creator");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package,
+            "ResourceCreator",
+            "create_updateable_withdraw",
+            args![admin_auth],
+        )
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    let token = receipt.new_resource_addresses[0];
+    let setup = setup_with_token(&mut test_runner, pk, sk, account, token, 5.into());
+    let allowance = grant(&mut test_runner, &setup, 3.into(), 10);
+    test_runner.set_auth(
+        (&setup.pk, &setup.sk, setup.account),
+        "set_withdrawable",
+        admin_auth,
+        token,
+        withdraw_auth,
+    );
+
+    // Act
+    let result = redeem(&mut test_runner, &setup, "redeem", allowance, 3.into());
+
+    // Assert
+    let error = result.expect_err("Should be runtime error");
+    assert_auth_error!(error);
+    assert_eq!(
+        test_runner
+            .get_component_resources(setup.grantor)
+            .get(&setup.token),
+        Some(&Decimal::from(5))
+    );
+}
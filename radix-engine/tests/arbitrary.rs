@@ -0,0 +1,157 @@
+#![cfg(feature = "proptest")]
+
+use proptest::prelude::*;
+use radix_engine::arbitrary::amount;
+use radix_engine::model::{
+    Instruction, ResourceContainer, SignedTransaction, Transaction, TransactionHeader,
+};
+use scrypto::prelude::*;
+
+fn fungible_amounts() -> impl Strategy<Value = (u8, Decimal, Decimal)> {
+    (0u8..=18).prop_flat_map(|divisibility| {
+        (
+            Just(divisibility),
+            amount(divisibility),
+            amount(divisibility),
+        )
+    })
+}
+
+fn overlapping_ids(
+) -> impl Strategy<Value = (Vec<NonFungibleId>, Vec<NonFungibleId>, Vec<NonFungibleId>)> {
+    prop::collection::btree_set(any::<NonFungibleId>(), 1..8).prop_flat_map(|ids| {
+        let ids: Vec<NonFungibleId> = ids.into_iter().collect();
+        (
+            Just(ids.clone()),
+            prop::sample::subsequence(ids.clone(), 0..=ids.len()),
+            prop::sample::subsequence(ids.clone(), 0..=ids.len()),
+        )
+    })
+}
+
+proptest! {
+    #[test]
+    fn test_resource_container_encoding_roundtrip(container in any::<ResourceContainer>()) {
+        let bytes = scrypto_encode(&container);
+        let decoded = scrypto_decode::<ResourceContainer>(&bytes).unwrap();
+        prop_assert_eq!(scrypto_encode(&decoded), bytes);
+        prop_assert_eq!(decoded.total_amount(), container.total_amount());
+    }
+
+    #[test]
+    fn test_resource_container_amount_is_consistent(container in any::<ResourceContainer>()) {
+        prop_assert!(!container.total_amount().is_negative());
+        prop_assert_eq!(container.total_amount(), container.liquid_amount());
+        if let Ok(ids) = container.total_ids() {
+            prop_assert_eq!(container.total_amount(), Decimal::from(ids.len()));
+        }
+    }
+
+    #[test]
+    fn test_fungible_take_and_put((divisibility, a, b) in fungible_amounts()) {
+        let mut container = ResourceContainer::new_fungible(RADIX_TOKEN, divisibility, a + b);
+
+        let taken = container.take_by_amount(b).unwrap();
+        prop_assert_eq!(taken.total_amount(), b);
+        prop_assert_eq!(container.total_amount(), a);
+
+        container.put(taken).unwrap();
+        prop_assert_eq!(container.total_amount(), a + b);
+    }
+
+    #[test]
+    fn test_non_fungible_take_and_put(
+        ids in prop::collection::btree_set(any::<NonFungibleId>(), 0..8),
+        n in any::<prop::sample::Index>(),
+    ) {
+        let mut container = ResourceContainer::new_non_fungible(RADIX_TOKEN, ids.clone());
+        let n = n.index(ids.len() + 1);
+
+        let taken = container.take_by_amount(n.into()).unwrap();
+        prop_assert_eq!(taken.total_amount(), Decimal::from(n));
+        prop_assert_eq!(container.total_amount(), Decimal::from(ids.len() - n));
+
+        container.put(taken).unwrap();
+        prop_assert_eq!(container.total_ids().unwrap(), ids);
+    }
+
+    #[test]
+    fn test_fungible_lock_and_unlock(
+        (divisibility, a, b) in fungible_amounts(),
+        unlock_in_order in any::<bool>(),
+    ) {
+        let total = a + b;
+        let mut container = ResourceContainer::new_fungible(RADIX_TOKEN, divisibility, total);
+
+        let lock_a = container.lock_by_amount(a).unwrap();
+        let lock_b = container.lock_by_amount(b).unwrap();
+        prop_assert_eq!(container.total_amount(), total);
+        prop_assert_eq!(container.liquid_amount(), total - a.max(b));
+
+        let (first, second) = if unlock_in_order { (lock_a, lock_b) } else { (lock_b, lock_a) };
+        container.unlock(first);
+        container.unlock(second);
+        prop_assert!(!container.is_locked());
+        prop_assert_eq!(container.liquid_amount(), total);
+    }
+
+    #[test]
+    fn test_non_fungible_overlapping_locks(
+        (ids, a, b) in overlapping_ids(),
+        unlock_in_order in any::<bool>(),
+    ) {
+        let ids: BTreeSet<NonFungibleId> = ids.into_iter().collect();
+        let a: BTreeSet<NonFungibleId> = a.into_iter().collect();
+        let b: BTreeSet<NonFungibleId> = b.into_iter().collect();
+        let mut container = ResourceContainer::new_non_fungible(RADIX_TOKEN, ids.clone());
+
+        let lock_a = container.lock_by_ids(&a).unwrap();
+        let lock_b = container.lock_by_ids(&b).unwrap();
+        let locked: BTreeSet<NonFungibleId> = a.union(&b).cloned().collect();
+        prop_assert_eq!(container.total_ids().unwrap(), ids.clone());
+        prop_assert_eq!(container.max_locked_ids().unwrap(), locked.clone());
+        prop_assert_eq!(
+            container.liquid_ids().unwrap(),
+            ids.difference(&locked).cloned().collect::<BTreeSet<NonFungibleId>>()
+        );
+
+        let (first, second, remaining) = if unlock_in_order {
+            (lock_a, lock_b, b)
+        } else {
+            (lock_b, lock_a, a)
+        };
+        container.unlock(first);
+        prop_assert_eq!(container.max_locked_ids().unwrap(), remaining);
+        container.unlock(second);
+        prop_assert!(!container.is_locked());
+        prop_assert_eq!(container.liquid_ids().unwrap(), ids);
+    }
+
+    #[test]
+    fn test_transaction_encoding_roundtrip(
+        network_id in any::<u8>(),
+        instructions in prop::collection::vec(any::<Instruction>(), 0..8),
+    ) {
+        let transaction = Transaction {
+            header: TransactionHeader { network_id },
+            instructions,
+        };
+        let bytes = scrypto_encode(&transaction);
+        prop_assert_eq!(scrypto_decode::<Transaction>(&bytes), Ok(transaction));
+    }
+
+    #[test]
+    fn test_transaction_validation_does_not_panic(
+        network_id in any::<u8>(),
+        instructions in prop::collection::vec(any::<Instruction>(), 0..8),
+    ) {
+        let _ = SignedTransaction {
+            transaction: Transaction {
+                header: TransactionHeader { network_id },
+                instructions,
+            },
+            signatures: Vec::new(),
+        }
+        .validate();
+    }
+}
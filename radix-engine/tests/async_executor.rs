@@ -0,0 +1,102 @@
+use radix_engine::errors::RuntimeError;
+use radix_engine::ledger::*;
+use radix_engine::transaction::*;
+use scrypto::prelude::*;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+struct NoOpWaker;
+
+impl Wake for NoOpWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+/// Polls a future to completion, which is enough for stores whose futures are always ready.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(NoOpWaker));
+    let mut context = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        if let Poll::Ready(output) = Pin::as_mut(&mut future).poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn async_execution_should_match_sync_execution() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let (_, _, account) = TransactionExecutor::new(&mut substate_store, false).new_account();
+    let transaction = TransactionBuilder::new()
+        .call_method(SYSTEM_COMPONENT, "free_xrd", args![])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(SubstateStore::get_nonce(&substate_store))
+        .sign([]);
+    let mut expected_store = substate_store.clone();
+    let expected = TransactionExecutor::new(&mut expected_store, false)
+        .validate_and_execute(&transaction)
+        .unwrap();
+
+    // Act
+    let mut executor = AsyncTransactionExecutor::new(&mut substate_store, false);
+    let receipt = block_on(executor.validate_and_execute(&transaction)).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert!(executor.round_trips() > 0);
+    assert_eq!(receipt.outputs, expected.outputs);
+    assert_eq!(substate_store, expected_store);
+}
+
+#[test]
+fn fetched_substates_should_be_reused_across_transactions() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let (_, _, account) = TransactionExecutor::new(&mut substate_store, false).new_account();
+    let nonce = SubstateStore::get_nonce(&substate_store);
+    let mut executor = AsyncTransactionExecutor::new(&mut substate_store, false);
+    let mut execute = |nonce: u64| {
+        let transaction = TransactionBuilder::new()
+            .call_method(SYSTEM_COMPONENT, "free_xrd", args![])
+            .call_method_with_all_resources(account, "deposit_batch")
+            .build(nonce)
+            .sign([]);
+        let receipt = block_on(executor.validate_and_execute(&transaction)).unwrap();
+        receipt.result.expect("Should be okay");
+        executor.round_trips()
+    };
+    let round_trips = execute(nonce);
+
+    // Act
+    let total_round_trips = execute(nonce + 1);
+
+    // Assert
+    assert!(total_round_trips - round_trips < round_trips);
+}
+
+#[test]
+fn unfetched_substate_should_fail_transaction() {
+    // Arrange
+    let substate_store = InMemorySubstateStore::with_bootstrap();
+    let nonce = SubstateStore::get_nonce(&substate_store);
+    let transaction = TransactionBuilder::new()
+        .call_method(SYSTEM_COMPONENT, "free_xrd", args![])
+        .build(nonce)
+        .sign([]);
+    let fetched = HashMap::new();
+    let mut buffer = BufferedSubstateStore::new(&fetched, 0, nonce);
+
+    // Act
+    let receipt = TransactionExecutor::new(&mut buffer, false)
+        .validate_and_execute(&transaction)
+        .unwrap();
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert_eq!(error, RuntimeError::SubstateNotFetched);
+    assert!(receipt.commit_receipt.is_none());
+    assert!(!buffer.misses().is_empty());
+}
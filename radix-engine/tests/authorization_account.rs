@@ -6,6 +6,7 @@ pub mod test_runner;
 use crate::test_runner::TestRunner;
 use radix_engine::errors::RuntimeError;
 use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::transaction::*;
 use scrypto::prelude::*;
 
 fn test_auth_rule(
@@ -32,7 +33,7 @@ fn test_auth_rule(
     if should_succeed {
         receipt.result.expect("Should be okay");
     } else {
-        let error = receipt.result.expect_err("Should be an error");
+        let error = receipt.result.expect_err("Should be an error").cause;
         assert_auth_error!(error);
     }
 }
@@ -325,6 +326,70 @@ fn cannot_withdraw_from_my_any_xrd_auth_account_with_less_than_amount_of_proof()
     let receipt = test_runner.validate_and_execute(&transaction);
 
     // Assert
-    let error = receipt.result.expect_err("Should be an error");
+    let error = receipt.result.expect_err("Should be an error").cause;
     assert_auth_error!(error);
 }
+
+#[test]
+fn cannot_withdraw_from_my_account_after_dropping_all_proofs() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .drop_all_proofs()
+        .withdraw_from_account(RADIX_TOKEN, account)
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be an error").cause;
+    assert_auth_error!(error);
+}
+
+#[test]
+fn cannot_withdraw_from_my_account_after_signature_proofs_cleared() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let (pk, sk, account) = TransactionExecutor::new(&mut substate_store, true).new_account();
+    let mut executor =
+        TransactionExecutor::new(&mut substate_store, true).with_signature_proofs_cleared_after(0);
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_method(SYSTEM_COMPONENT, "free_xrd", vec![])
+        .withdraw_from_account(RADIX_TOKEN, account)
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(executor.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    let error = receipt.result.expect_err("Should be an error").cause;
+    assert_auth_error!(error);
+}
+
+#[test]
+fn can_withdraw_from_my_account_before_signature_proofs_cleared() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let (pk, sk, account) = TransactionExecutor::new(&mut substate_store, true).new_account();
+    let mut executor =
+        TransactionExecutor::new(&mut substate_store, true).with_signature_proofs_cleared_after(0);
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .withdraw_from_account(RADIX_TOKEN, account)
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(executor.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay");
+}
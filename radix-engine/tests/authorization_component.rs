@@ -4,6 +4,7 @@ pub mod test_runner;
 use crate::test_runner::TestRunner;
 use radix_engine::errors::RuntimeError;
 use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::model::{FailedRule, HardProofRule, HardResourceOrNonFungible, MissingProof};
 use scrypto::prelude::*;
 
 #[test]
@@ -60,7 +61,7 @@ fn cannot_make_cross_component_call_without_authorization() {
     let receipt = test_runner.validate_and_execute(&transaction);
 
     // Assert
-    let error = receipt.result.expect_err("Should be error");
+    let error = receipt.result.expect_err("Should be error").cause;
     assert_auth_error!(error);
 }
 
@@ -129,3 +130,176 @@ fn can_make_cross_component_call_with_authorization() {
     // Assert
     receipt.result.expect("Should be okay");
 }
+
+#[test]
+fn can_restrict_method_to_global_caller() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package_address = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package_address, "CrossComponent", "create_component", vec![])
+        .call_function(package_address, "CrossComponent", "create_component", vec![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    let my_component = receipt.new_component_addresses[0];
+    let other_component = receipt.new_component_addresses[1];
+
+    let authorization = AccessRules::new().method(
+        "get_component_state",
+        rule!(require(NonFungibleAddress::from_global_caller(my_component))),
+    );
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package_address,
+            "CrossComponent",
+            "create_component_with_auth",
+            vec![scrypto_encode(&authorization)],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    let secured_component = receipt.new_component_addresses[0];
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(
+            my_component,
+            "cross_component_call",
+            vec![scrypto_encode(&secured_component)],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    let other_transaction = test_runner
+        .new_transaction_builder()
+        .call_method(
+            other_component,
+            "cross_component_call",
+            vec![scrypto_encode(&secured_component)],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let other_receipt = test_runner.validate_and_execute(&other_transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    let error = other_receipt.result.expect_err("Should be error").cause;
+    assert_auth_error!(error);
+}
+
+#[test]
+fn can_restrict_method_to_caller_package() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package_address = test_runner.publish_package("component");
+    let authorization = AccessRules::new().method(
+        "get_component_state",
+        rule!(require(NonFungibleAddress::from_package(package_address))),
+    );
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package_address,
+            "CrossComponent",
+            "create_component_with_auth",
+            vec![scrypto_encode(&authorization)],
+        )
+        .call_function(package_address, "CrossComponent", "create_component", vec![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    let secured_component = receipt.new_component_addresses[0];
+    let my_component = receipt.new_component_addresses[1];
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(
+            my_component,
+            "cross_component_call",
+            vec![scrypto_encode(&secured_component)],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    let direct_transaction = test_runner
+        .new_transaction_builder()
+        .call_method(secured_component, "get_component_state", vec![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let direct_receipt = test_runner.validate_and_execute(&direct_transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    let error = direct_receipt.result.expect_err("Should be error").cause;
+    assert_auth_error!(error);
+}
+
+#[test]
+fn authorization_error_explains_missing_proof() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (key, sk, account) = test_runner.new_account();
+    let auth = test_runner.create_non_fungible_resource(account.clone());
+    let auth_address = NonFungibleAddress::new(auth, NonFungibleId::from_u32(1));
+    let other_auth_address = NonFungibleAddress::new(auth, NonFungibleId::from_u32(100));
+    let authorization = AccessRules::new().method(
+        "get_component_state",
+        rule!(require(auth_address.clone()) && require(other_auth_address.clone())),
+    );
+
+    let package_address = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package_address,
+            "CrossComponent",
+            "create_component_with_auth",
+            vec![scrypto_encode(&authorization)],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    let secured_component = receipt.new_component_addresses[0];
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(auth, account)
+        .call_method(secured_component, "get_component_state", vec![])
+        .build(test_runner.get_nonce([key]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert_auth_error!(error);
+    if let RuntimeError::AuthorizationError { explanation, .. } = &error {
+        assert_eq!(
+            explanation.failed_rules,
+            vec![FailedRule::ProofRule {
+                rule: HardProofRule::This(HardResourceOrNonFungible::NonFungible(
+                    other_auth_address.clone()
+                )),
+                missing: vec![MissingProof::NonFungible(other_auth_address.clone())],
+            }]
+        );
+        assert!(explanation
+            .auth_zones
+            .iter()
+            .flatten()
+            .any(|proof| proof.resource_address == auth));
+    }
+    assert!(error.to_string().contains(&other_auth_address.to_string()));
+}
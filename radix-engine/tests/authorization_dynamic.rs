@@ -76,7 +76,7 @@ fn test_dynamic_auth(
     if should_succeed {
         receipt2.result.expect("Should be okay.");
     } else {
-        let error = receipt2.result.expect_err("Should be an error.");
+        let error = receipt2.result.expect_err("Should be an error.").cause;
         assert_auth_error!(error);
     }
 }
@@ -135,7 +135,7 @@ fn test_dynamic_authlist(
     if should_succeed {
         receipt.result.expect("Should be okay.");
     } else {
-        let error = receipt.result.expect_err("Should be an error.");
+        let error = receipt.result.expect_err("Should be an error.").cause;
         assert_auth_error!(error);
     }
 }
@@ -260,7 +260,7 @@ fn chess_should_not_allow_second_player_to_move_if_first_player_didnt_move() {
     let receipt = test_runner.validate_and_execute(&transaction2);
 
     // Assert
-    let error = receipt.result.expect_err("Should be an error");
+    let error = receipt.result.expect_err("Should be an error").cause;
     assert_auth_error!(error);
 }
 
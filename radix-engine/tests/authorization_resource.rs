@@ -84,7 +84,7 @@ fn test_resource_auth(action: Action, update_auth: bool, use_other_auth: bool, e
 
     // Assert
     if expect_err {
-        let err = receipt.result.expect_err("Should be a runtime error");
+        let err = receipt.result.expect_err("Should be a runtime error").cause;
         assert_auth_error!(err);
     } else {
         receipt.result.expect("Should be okay.");
@@ -136,3 +136,165 @@ fn cannot_deposit_with_wrong_auth() {
 fn can_deposit_with_right_auth() {
     test_resource_auth(Action::Deposit, true, true, false);
 }
+
+#[test]
+fn cannot_mint_after_minting_is_renounced() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (token_address, mint_auth, _, _, admin_auth) =
+        test_runner.create_restricted_token(account);
+    let package = test_runner.publish_package("resource_creator");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(admin_auth, account)
+        .call_function(
+            package,
+            "ResourceCreator",
+            "renounce_mintable",
+            args![token_address],
+        )
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    test_runner
+        .validate_and_execute(&transaction)
+        .result
+        .expect("Should be okay");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(mint_auth, account)
+        .mint(Decimal::one(), token_address)
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let err = receipt.result.expect_err("Should be a runtime error").cause;
+    assert_auth_error!(err);
+}
+
+#[test]
+fn cannot_burn_after_burning_is_renounced() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (token_address, _, burn_auth, withdraw_auth, admin_auth) =
+        test_runner.create_restricted_token(account);
+    let package = test_runner.publish_package("resource_creator");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(admin_auth, account)
+        .call_function(
+            package,
+            "ResourceCreator",
+            "renounce_burnable",
+            args![token_address],
+        )
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    test_runner
+        .validate_and_execute(&transaction)
+        .result
+        .expect("Should be okay");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(burn_auth, account)
+        .create_proof_from_account(withdraw_auth, account)
+        .withdraw_from_account_by_amount(Decimal::one(), token_address, account)
+        .burn(Decimal::one(), token_address)
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let err = receipt.result.expect_err("Should be a runtime error").cause;
+    assert_auth_error!(err);
+}
+
+#[test]
+fn cannot_renounce_minting_twice() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (token_address, _, _, _, admin_auth) = test_runner.create_restricted_token(account);
+    let package = test_runner.publish_package("resource_creator");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(admin_auth, account)
+        .call_function(
+            package,
+            "ResourceCreator",
+            "renounce_mintable",
+            args![token_address],
+        )
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    test_runner
+        .validate_and_execute(&transaction)
+        .result
+        .expect("Should be okay");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(admin_auth, account)
+        .call_function(
+            package,
+            "ResourceCreator",
+            "renounce_mintable",
+            args![token_address],
+        )
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let err = receipt.result.expect_err("Should be a runtime error").cause;
+    assert_auth_error!(err);
+}
+
+fn mint_with_resource_method_call(use_other_auth: bool, expect_err: bool) {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (token_address, mint_auth, burn_auth, _, _) = test_runner.create_restricted_token(account);
+    let auth_to_use = if use_other_auth { burn_auth } else { mint_auth };
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(auth_to_use, account)
+        .call_resource_method(token_address, "mint", args![MintParams::fungible(1)])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    if expect_err {
+        let err = receipt.result.expect_err("Should be a runtime error").cause;
+        assert_auth_error!(err);
+    } else {
+        receipt.result.expect("Should be okay.");
+    }
+}
+
+#[test]
+fn can_mint_by_calling_resource_manager_with_right_auth() {
+    mint_with_resource_method_call(false, false);
+}
+
+#[test]
+fn cannot_mint_by_calling_resource_manager_with_wrong_auth() {
+    mint_with_resource_method_call(true, true);
+}
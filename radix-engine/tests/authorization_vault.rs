@@ -25,7 +25,7 @@ fn cannot_withdraw_restricted_transfer_from_my_account_with_no_auth() {
     let receipt = test_runner.validate_and_execute(&transaction);
 
     // Assert
-    let err = receipt.result.expect_err("Should be a runtime error");
+    let err = receipt.result.expect_err("Should be a runtime error").cause;
     assert_auth_error!(err);
 }
 
@@ -22,6 +22,7 @@ fn test_bucket() {
         .call_function(package, "BucketTest", "split", args![])
         .call_function(package, "BucketTest", "borrow", args![])
         .call_function(package, "BucketTest", "query", args![])
+        .call_function(package, "BucketTest", "typed_fungible", args![])
         .call_function(package, "BucketTest", "test_restricted_transfer", args![])
         .call_function(package, "BucketTest", "test_burn", args![])
         .call_function(package, "BucketTest", "test_burn_freely", args![])
@@ -83,7 +84,7 @@ fn test_take_with_invalid_granularity() {
 
     // Assert
     assert_eq!(
-        receipt.result,
+        receipt.result.map_err(|e| e.cause),
         Err(RuntimeError::BucketError(
             BucketError::ResourceContainerError(ResourceContainerError::InvalidAmount(
                 dec!("1.123"),
@@ -121,7 +122,7 @@ fn test_take_with_negative_amount() {
 
     // Assert
     assert_eq!(
-        receipt.result,
+        receipt.result.map_err(|e| e.cause),
         Err(RuntimeError::BucketError(
             BucketError::ResourceContainerError(ResourceContainerError::InvalidAmount(
                 dec!("-2"),
@@ -161,3 +162,119 @@ fn create_empty_bucket() {
     // Assert
     assert!(receipt.result.is_ok());
 }
+
+#[test]
+fn typed_buckets_can_be_passed_and_stored_in_typed_vaults() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let fungible = test_runner.create_fungible_resource(100.into(), 18, account);
+    let non_fungible = test_runner.create_non_fungible_resource(account);
+    let package_address = test_runner.publish_package("bucket");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function_with_abi(
+            package_address,
+            "TypedVaultTest",
+            "new",
+            vec![format!("10,{}", fungible), format!("2,{}", non_fungible)],
+            Some(account),
+            &test_runner.export_abi(package_address, "TypedVaultTest"),
+        )
+        .unwrap()
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    println!("{:?}", receipt);
+
+    // Assert
+    assert!(receipt.result.is_ok());
+    let resources = test_runner.get_component_resources(receipt.new_component_addresses[0]);
+    assert_eq!(resources.get(&fungible), Some(&Decimal::from(10)));
+    assert_eq!(resources.get(&non_fungible), Some(&Decimal::from(2)));
+}
+
+#[test]
+fn non_fungible_bucket_can_be_deposited_into_typed_vault() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let fungible = test_runner.create_fungible_resource(100.into(), 18, account);
+    let non_fungible = test_runner.create_non_fungible_resource(account);
+    let package_address = test_runner.publish_package("bucket");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function_with_abi(
+            package_address,
+            "TypedVaultTest",
+            "new",
+            vec![format!("10,{}", fungible), format!("1,{}", non_fungible)],
+            Some(account),
+            &test_runner.export_abi(package_address, "TypedVaultTest"),
+        )
+        .unwrap()
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    let component_address = receipt.new_component_addresses[0];
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method_with_abi(
+            component_address,
+            "deposit_non_fungible",
+            vec![format!("2,{}", non_fungible)],
+            Some(account),
+            &test_runner.export_abi_by_component(component_address),
+        )
+        .unwrap()
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    println!("{:?}", receipt);
+
+    // Assert
+    assert!(receipt.result.is_ok());
+    let resources = test_runner.get_component_resources(component_address);
+    assert_eq!(resources.get(&non_fungible), Some(&Decimal::from(3)));
+}
+
+#[test]
+fn typed_bucket_of_the_wrong_resource_type_is_rejected() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let fungible = test_runner.create_fungible_resource(100.into(), 18, account);
+    let non_fungible = test_runner.create_non_fungible_resource(account);
+    let package_address = test_runner.publish_package("bucket");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function_with_abi(
+            package_address,
+            "TypedVaultTest",
+            "new",
+            vec![format!("1,{}", non_fungible), format!("10,{}", fungible)],
+            Some(account),
+            &test_runner.export_abi(package_address, "TypedVaultTest"),
+        )
+        .unwrap()
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    println!("{:?}", receipt);
+
+    // Assert
+    assert!(receipt.result.is_err());
+}
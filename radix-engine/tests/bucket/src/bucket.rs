@@ -39,6 +39,23 @@ blueprint! {
             bucket
         }
 
+        pub fn typed_fungible() -> Bucket {
+            let mut bucket = Self::create_test_token(100).as_fungible();
+            let other = bucket.take(40);
+            let mut vault = FungibleVault::with_bucket(other);
+            bucket.put(vault.take(10));
+            assert_eq!(bucket.amount(), Decimal::from(70));
+            assert_eq!(vault.amount(), Decimal::from(30));
+            bucket.put(vault.take_all());
+
+            BucketTest {
+                vault: vault.into_vault(),
+            }
+            .instantiate()
+            .globalize();
+            bucket.into_bucket()
+        }
+
         pub fn query() -> (Decimal, ResourceAddress, Bucket) {
             let bucket = Self::create_test_token(100);
             (bucket.amount(), bucket.resource_address(), bucket)
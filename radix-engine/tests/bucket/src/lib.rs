@@ -1,2 +1,3 @@
 pub mod badge;
 pub mod bucket;
+pub mod typed;
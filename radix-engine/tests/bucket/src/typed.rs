@@ -0,0 +1,31 @@
+use scrypto::prelude::*;
+
+blueprint! {
+    struct TypedVaultTest {
+        fungible: FungibleVault,
+        non_fungible: NonFungibleVault,
+    }
+
+    impl TypedVaultTest {
+        pub fn new(fungible: FungibleBucket, non_fungible: NonFungibleBucket) -> ComponentAddress {
+            TypedVaultTest {
+                fungible: FungibleVault::with_bucket(fungible),
+                non_fungible: NonFungibleVault::with_bucket(non_fungible),
+            }
+            .instantiate()
+            .globalize()
+        }
+
+        pub fn deposit_non_fungible(&mut self, bucket: NonFungibleBucket) {
+            self.non_fungible.put(bucket);
+        }
+
+        pub fn take_non_fungible(&mut self, id: NonFungibleId) -> NonFungibleBucket {
+            self.non_fungible.take_non_fungible(&id)
+        }
+
+        pub fn amounts(&self) -> (Decimal, Decimal) {
+            (self.fungible.amount(), self.non_fungible.amount())
+        }
+    }
+}
@@ -2,9 +2,12 @@
 pub mod test_runner;
 
 use crate::test_runner::TestRunner;
+use radix_engine::engine::{EntityAddress, LockMode, SubstateId};
 use radix_engine::errors::RuntimeError;
 use radix_engine::ledger::InMemorySubstateStore;
+use sbor::Type;
 use scrypto::prelude::*;
+use scrypto::values::ScryptoValue;
 
 #[test]
 fn test_package() {
@@ -79,7 +82,7 @@ fn invalid_blueprint_name_should_cause_error() {
     let receipt = test_runner.validate_and_execute(&transaction);
 
     // Assert
-    let error = receipt.result.expect_err("Should be an error.");
+    let error = receipt.result.expect_err("Should be an error.").cause;
     assert_eq!(
         error,
         RuntimeError::BlueprintNotFound(package_address, "NonExistentBlueprint".to_string())
@@ -110,10 +113,93 @@ fn reentrancy_should_not_be_possible() {
     let receipt = test_runner.validate_and_execute(&transaction);
 
     // Assert
-    let error = receipt.result.expect_err("Should be an error.");
+    let error = receipt.result.expect_err("Should be an error.").cause;
     assert_eq!(error, RuntimeError::ComponentReentrancy(component_address))
 }
 
+#[test]
+fn reading_borrowed_component_should_fail() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package_address = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package_address,
+            "CrossComponent",
+            "create_component",
+            vec![],
+        )
+        .call_function(
+            package_address,
+            "CrossComponent",
+            "create_component",
+            vec![],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    let caller = receipt.new_component_addresses[0];
+    let callee = receipt.new_component_addresses[1];
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(caller, "cross_component_info_call", args![callee])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be an error.").cause;
+    assert_eq!(
+        error,
+        RuntimeError::SubstateLocked {
+            substate_id: SubstateId::Component(caller),
+            requested: LockMode::Read,
+            held: LockMode::Write,
+        }
+    );
+}
+
+#[test]
+fn component_should_read_its_own_info() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package_address = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package_address,
+            "CrossComponent",
+            "create_component",
+            vec![],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    let component_address = receipt.new_component_addresses[0];
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(component_address, "get_component_info", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert_eq!(
+        receipt.outputs[0],
+        ScryptoValue::from_value(&(package_address, "CrossComponent".to_string()))
+    );
+}
+
 #[test]
 fn missing_component_address_should_cause_error() {
     // Arrange
@@ -133,6 +219,275 @@ fn missing_component_address_should_cause_error() {
     let receipt = test_runner.validate_and_execute(&transaction);
 
     // Assert
-    let error = receipt.result.expect_err("Should be an error.");
+    let error = receipt.result.expect_err("Should be an error.").cause;
     assert_eq!(error, RuntimeError::ComponentNotFound(component_address));
 }
+
+#[test]
+fn imported_types_should_be_referenced_by_abi() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+
+    // Act
+    let abi = test_runner.export_abi(package, "ImportedTypesTest");
+
+    // Assert
+    assert_eq!(
+        abi.imported_types,
+        vec!["01a405d3129b61e86c51c3168d553d2ffd7a3f0bd2f66b5a3e9876".to_string()]
+    );
+}
+
+#[test]
+fn shared_types_should_be_exported() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+
+    // Act
+    let types = test_runner.export_types(package);
+
+    // Assert
+    assert_eq!(types.package_address, package.to_string());
+    let names: Vec<&str> = types
+        .types
+        .iter()
+        .map(|ty| match ty {
+            Type::Struct { name, .. } | Type::Enum { name, .. } => name.as_str(),
+            _ => panic!("Should be a struct or an enum"),
+        })
+        .collect();
+    assert_eq!(names, vec!["Position", "Shape"]);
+}
+
+#[test]
+fn package_without_shared_types_should_export_none() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("core");
+
+    // Act
+    let types = test_runner.export_types(package);
+
+    // Assert
+    assert!(types.types.is_empty());
+}
+
+#[test]
+fn read_only_call_should_return_result() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package, "ComponentTest", "create_component", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    let component = receipt.new_component_addresses[0];
+
+    // Act
+    let result = test_runner.call_read_only(component, "get_component_state", args![]);
+
+    // Assert
+    assert_eq!(result, Ok(ScryptoValue::from_value(&"Secret".to_owned())));
+}
+
+#[test]
+fn read_only_call_changing_state_should_fail() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package, "ComponentTest", "create_component", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    let component = receipt.new_component_addresses[0];
+
+    // Act
+    let result = test_runner.call_read_only(component, "put_component_state", args![]);
+
+    // Assert
+    assert_eq!(result, Err(RuntimeError::StateChangedInReadOnlyCall));
+    let result = test_runner.call_read_only(component, "get_component_state", args![]);
+    assert_eq!(result, Ok(ScryptoValue::from_value(&"Secret".to_owned())));
+}
+
+#[test]
+fn read_only_calls_should_have_distinct_transaction_hashes() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package, "ComponentTest", "create_component", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    let component = receipt.new_component_addresses[0];
+
+    // Act
+    let first = test_runner
+        .call_read_only(component, "get_transaction_hash", args![])
+        .unwrap();
+    let second = test_runner
+        .call_read_only(component, "get_transaction_hash", args![])
+        .unwrap();
+
+    // Assert
+    assert_ne!(first.raw, second.raw);
+}
+
+#[test]
+fn interfaces_should_be_exported_in_abi() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+
+    // Act
+    let abi = test_runner.export_abi(package, "StepCounter");
+
+    // Assert
+    assert_eq!(abi.interfaces.len(), 1);
+    assert_eq!(abi.interfaces[0].name, "Counter");
+    let methods: Vec<&str> = abi.interfaces[0]
+        .methods
+        .iter()
+        .map(|m| m.name.as_str())
+        .collect();
+    assert_eq!(methods, vec!["increment", "get"]);
+    assert!(test_runner
+        .export_abi(package, "CounterAggregator")
+        .interfaces
+        .is_empty());
+}
+
+#[test]
+fn can_call_components_of_different_blueprints_through_interface() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package, "SimpleCounter", "new", args![])
+        .call_function(package, "StepCounter", "new", args![5u32])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    let counters = receipt.new_component_addresses.clone();
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package,
+            "CounterAggregator",
+            "increment_all",
+            args![counters],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+    assert_eq!(receipt.outputs[0], ScryptoValue::from_value(&6u32));
+}
+
+#[test]
+fn receipt_should_record_new_entities_with_creating_instruction() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package, "SimpleCounter", "new", args![])
+        .call_function(package, "ComponentTest", "create_component", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+    let counter: ComponentAddress = scrypto_decode(&receipt.outputs[0].raw).unwrap();
+    let component: ComponentAddress = scrypto_decode(&receipt.outputs[1].raw).unwrap();
+    let components: Vec<(ComponentAddress, Option<usize>)> = receipt
+        .new_global_entities
+        .iter()
+        .filter_map(|entity| match entity.address {
+            EntityAddress::Component(address) => Some((address, entity.instruction_index)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(components, vec![(counter, Some(0)), (component, Some(1))]);
+    assert!(receipt.new_global_entities.iter().any(|entity| {
+        entity.address == EntityAddress::Resource(receipt.new_resource_addresses[0])
+            && entity.instruction_index == Some(1)
+    }));
+    assert!(receipt.new_owned_entities.iter().any(|entity| {
+        matches!(entity.address, EntityAddress::Vault(owner, _) if owner == component)
+            && entity.instruction_index == Some(1)
+    }));
+    assert!(receipt
+        .new_global_entities
+        .iter()
+        .all(|entity| entity.address.is_global()));
+}
+
+#[test]
+fn receipt_of_failed_transaction_should_record_no_new_entities() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package, "ComponentTest", "create_component", args![])
+        .call_function(package, "ComponentTest", "no_such_function", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    assert!(receipt.result.is_err());
+    assert!(receipt.new_global_entities.is_empty());
+    assert!(receipt.new_owned_entities.is_empty());
+}
+
+#[test]
+fn predicted_addresses_should_match_execution() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package, "SimpleCounter", "new", args![])
+        .call_function(package, "ComponentTest", "create_component", args![])
+        .build(test_runner.get_nonce([]));
+
+    // Act
+    let predicted = test_runner.predict_addresses(&transaction, []);
+    let receipt = test_runner.validate_and_execute(&transaction.sign([]));
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+    assert_eq!(predicted, receipt.new_global_entities);
+    assert_eq!(predicted.len(), 3);
+}
@@ -0,0 +1,35 @@
+use scrypto::prelude::*;
+
+blueprint! {
+    struct AllowanceGrantor {
+        tokens: Vault,
+    }
+
+    impl AllowanceGrantor {
+        pub fn new(tokens: Bucket) -> ComponentAddress {
+            Self {
+                tokens: Vault::with_bucket(tokens),
+            }
+            .instantiate()
+            .globalize()
+        }
+
+        pub fn grant(
+            &mut self,
+            max_amount: Decimal,
+            expiry_epoch: u64,
+            spender_badge: ResourceAddress,
+        ) -> Allowance {
+            self.tokens
+                .create_allowance(max_amount, expiry_epoch, rule!(require(spender_badge)))
+        }
+
+        pub fn revoke(&mut self, allowance: Allowance) {
+            self.tokens.revoke_allowance(&allowance);
+        }
+
+        pub fn balance(&self) -> Decimal {
+            self.tokens.amount()
+        }
+    }
+}
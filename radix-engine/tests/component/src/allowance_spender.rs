@@ -0,0 +1,25 @@
+use scrypto::prelude::*;
+
+blueprint! {
+    struct AllowanceSpender {
+        badge: Vault,
+    }
+
+    impl AllowanceSpender {
+        pub fn new(badge: Bucket) -> ComponentAddress {
+            Self {
+                badge: Vault::with_bucket(badge),
+            }
+            .instantiate()
+            .globalize()
+        }
+
+        pub fn redeem(&self, allowance: Allowance, amount: Decimal) -> Bucket {
+            self.badge.authorize(|| allowance.redeem(amount))
+        }
+
+        pub fn redeem_without_badge(&self, allowance: Allowance, amount: Decimal) -> Bucket {
+            allowance.redeem(amount)
+        }
+    }
+}
@@ -34,6 +34,10 @@ blueprint! {
             self.secret.clone()
         }
 
+        pub fn get_transaction_hash(&self) -> Hash {
+            Runtime::transaction_hash()
+        }
+
         pub fn put_component_state(&mut self) -> Bucket {
             // Take resource from vault
             let bucket = self.test_vault.take(1);
@@ -0,0 +1,24 @@
+use scrypto::prelude::*;
+
+interface! {
+    pub trait Counter {
+        fn increment(&mut self) -> u32;
+        fn get(&self) -> u32;
+    }
+}
+
+blueprint! {
+    struct CounterAggregator {}
+
+    impl CounterAggregator {
+        pub fn increment_all(counters: Vec<ComponentAddress>) -> u32 {
+            counters
+                .into_iter()
+                .map(|address| {
+                    let counter: Counter = address.into();
+                    counter.increment()
+                })
+                .sum()
+        }
+    }
+}
@@ -47,5 +47,23 @@ blueprint! {
         pub fn get_component_state(&self) -> String {
             self.secret.clone()
         }
+
+        pub fn get_component_info(&self) -> (PackageAddress, String) {
+            let component = borrow_component!(Runtime::actor().component_address().unwrap());
+            (component.package_address(), component.blueprint_name())
+        }
+
+        pub fn get_caller_component_info(&self) -> (PackageAddress, String) {
+            let caller = Runtime::caller().unwrap().component_address().unwrap();
+            let component = borrow_component!(caller);
+            (component.package_address(), component.blueprint_name())
+        }
+
+        pub fn cross_component_info_call(
+            &self,
+            component_address: ComponentAddress,
+        ) -> (PackageAddress, String) {
+            borrow_component!(component_address).call("get_caller_component_info", vec![])
+        }
     }
 }
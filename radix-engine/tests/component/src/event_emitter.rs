@@ -0,0 +1,31 @@
+use scrypto::prelude::*;
+
+blueprint! {
+    struct EventEmitter {}
+
+    impl EventEmitter {
+        pub fn new() -> ComponentAddress {
+            Self {}.instantiate().globalize()
+        }
+
+        pub fn allow_subscriptions(&self, events: Vec<String>) {
+            for event in events {
+                Runtime::allow_event_subscriptions(event);
+            }
+        }
+
+        pub fn disallow_subscriptions(&self, event: String) {
+            Runtime::disallow_event_subscriptions(event);
+        }
+
+        pub fn emit(&self, event: String, value: u32) {
+            Runtime::emit_event(event, &value);
+        }
+
+        pub fn emit_many(&self, event: String, count: u32) {
+            for value in 0..count {
+                Runtime::emit_event(&event, &value);
+            }
+        }
+    }
+}
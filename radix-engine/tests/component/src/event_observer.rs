@@ -0,0 +1,35 @@
+use scrypto::prelude::*;
+
+blueprint! {
+    struct EventObserver {}
+
+    impl EventObserver {
+        pub fn new() -> ComponentAddress {
+            Self {}.instantiate().globalize()
+        }
+
+        pub fn observe(&self, emitter: ComponentAddress, event: String) {
+            Runtime::subscribe_to_event(emitter, event);
+        }
+
+        pub fn observe_all(&self, emitter: ComponentAddress, events: Vec<String>) {
+            for event in events {
+                Runtime::subscribe_to_event(emitter, event);
+            }
+        }
+
+        pub fn stop_observing(&self, emitter: ComponentAddress, event: String) {
+            Runtime::unsubscribe_from_event(emitter, event);
+        }
+
+        pub fn take_values(&self) -> Vec<(ComponentAddress, String, u32)> {
+            Runtime::take_notifications()
+                .into_iter()
+                .map(|n| {
+                    let value = n.data::<u32>().unwrap();
+                    (n.emitter, n.event, value)
+                })
+                .collect()
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use scrypto::prelude::*;
+
+blueprint! {
+    #[features("fees", "whitelist")]
+    struct FeatureComponent {}
+
+    impl FeatureComponent {
+        pub fn create_component(features: Vec<String>) -> ComponentAddress {
+            let features: Vec<&str> = features.iter().map(String::as_str).collect();
+            Self {}.instantiate_with_features(&features).globalize()
+        }
+
+        pub fn is_enabled(&self, feature: String) -> bool {
+            Runtime::feature_enabled(&feature)
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use scrypto::prelude::*;
+
+import_types! {
+r#"
+{
+    "package_address": "01a405d3129b61e86c51c3168d553d2ffd7a3f0bd2f66b5a3e9876",
+    "types": [
+        {
+            "type": "Struct",
+            "name": "Point",
+            "fields": {
+                "type": "Named",
+                "named": [
+                    ["x", { "type": "U8" }],
+                    ["y", { "type": "U8" }]
+                ]
+            }
+        }
+    ]
+}
+"#
+}
+
+blueprint! {
+    struct ImportedTypesTest;
+
+    impl ImportedTypesTest {
+        pub fn sum(point: Point) -> u8 {
+            point.x + point.y
+        }
+    }
+}
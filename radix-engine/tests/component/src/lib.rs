@@ -1,8 +1,21 @@
+pub mod allowance_grantor;
+pub mod allowance_spender;
 pub mod auth_component;
 pub mod auth_list_component;
 pub mod chess;
 pub mod component;
+pub mod counter_interface;
 pub mod cross_component;
+pub mod event_emitter;
+pub mod event_observer;
+pub mod feature_component;
+pub mod imported_types;
 pub mod package;
+pub mod pausable_component;
 pub mod reentrant_component;
+pub mod rule_slot_component;
+pub mod shared_types;
+pub mod simple_counter;
+pub mod spending_limit;
+pub mod step_counter;
 pub mod typed_cross_component_call;
@@ -0,0 +1,26 @@
+use scrypto::prelude::*;
+
+blueprint! {
+    struct PausableComponent {
+        count: u32,
+    }
+
+    impl PausableComponent {
+        pub fn create_component(owner_badge: ResourceAddress) -> ComponentAddress {
+            Self { count: 0 }
+                .instantiate()
+                .method_toggle_auth(rule!(require(owner_badge)))
+                .globalize()
+        }
+
+        pub fn increment(&mut self) -> u32 {
+            self.count += 1;
+            self.count
+        }
+
+        pub fn set_enabled(&mut self, method: String, enabled: bool) {
+            let component_address = Runtime::actor().component_address().unwrap();
+            borrow_component!(component_address).set_method_enabled(&method, enabled);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use scrypto::prelude::*;
+
+blueprint! {
+    struct RuleSlotComponent {}
+
+    impl RuleSlotComponent {
+        pub fn create_component(
+            owner_badge: ResourceAddress,
+            admin_badge: ResourceAddress,
+        ) -> ComponentAddress {
+            Self {}
+                .instantiate()
+                .add_access_check(
+                    AccessRules::new()
+                        .method("get_secret", AccessRule::slot("admin"))
+                        .method("get_other_secret", AccessRule::slot("missing"))
+                        .default(rule!(allow_all)),
+                )
+                .add_access_rule_slot(
+                    "admin",
+                    rule!(require(admin_badge)),
+                    MUTABLE(rule!(require(owner_badge))),
+                )
+                .add_access_rule_slot("locked", rule!(allow_all), LOCKED)
+                .globalize()
+        }
+
+        pub fn create_component_with_nested_slot() -> ComponentAddress {
+            Self {}
+                .instantiate()
+                .add_access_check(
+                    AccessRules::new()
+                        .method("get_secret", AccessRule::slot("admin"))
+                        .default(rule!(allow_all)),
+                )
+                .add_access_rule_slot("admin", AccessRule::slot("other"), LOCKED)
+                .globalize()
+        }
+
+        pub fn get_secret(&self) -> String {
+            "Secret".to_owned()
+        }
+
+        pub fn get_other_secret(&self) -> String {
+            "Other secret".to_owned()
+        }
+
+        pub fn set_rule(&mut self, slot: String, rule: AccessRule) {
+            let component_address = Runtime::actor().component_address().unwrap();
+            borrow_component!(component_address).set_access_rule(&slot, rule);
+        }
+    }
+}
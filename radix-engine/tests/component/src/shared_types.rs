@@ -0,0 +1,15 @@
+use scrypto::prelude::*;
+
+#[derive(TypeId, Encode, Decode, Describe)]
+pub struct Position {
+    pub x: u8,
+    pub y: u8,
+}
+
+#[derive(TypeId, Encode, Decode, Describe)]
+pub enum Shape {
+    Point(Position),
+    Circle { center: Position, radius: u8 },
+}
+
+export_types!(Position, Shape);
@@ -0,0 +1,26 @@
+use scrypto::prelude::*;
+
+use crate::counter_interface::Counter;
+
+blueprint! {
+    struct SimpleCounter {
+        count: u32,
+    }
+
+    impl SimpleCounter {
+        pub fn new() -> ComponentAddress {
+            Self { count: 0 }.instantiate().globalize()
+        }
+
+        pub fn increment(&mut self) -> u32 {
+            self.count += 1;
+            self.count
+        }
+
+        pub fn get(&self) -> u32 {
+            self.count
+        }
+    }
+
+    impl Counter for SimpleCounter {}
+}
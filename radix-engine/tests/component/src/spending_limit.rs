@@ -0,0 +1,49 @@
+use scrypto::prelude::*;
+
+blueprint! {
+    struct SpendingLimit {
+        limit: Decimal,
+        spent: Decimal,
+    }
+
+    impl SpendingLimit {
+        pub fn new(limit: Decimal) -> ComponentAddress {
+            Self {
+                limit,
+                spent: Decimal::zero(),
+            }
+            .instantiate()
+            .globalize()
+        }
+
+        /// Authorizes account withdrawals by amount, up to the spending limit.
+        pub fn authorize(
+            &mut self,
+            _account: ComponentAddress,
+            method: String,
+            args: Vec<Vec<u8>>,
+        ) -> bool {
+            if method != "withdraw_by_amount" {
+                return false;
+            }
+            let amount: Decimal = scrypto_decode(&args[0]).unwrap();
+            if self.spent + amount > self.limit {
+                return false;
+            }
+            self.spent += amount;
+            true
+        }
+
+        pub fn authorize_expensively(
+            &mut self,
+            _account: ComponentAddress,
+            _method: String,
+            _args: Vec<Vec<u8>>,
+        ) -> bool {
+            for _ in 0..100_000 {
+                Runtime::generate_uuid();
+            }
+            true
+        }
+    }
+}
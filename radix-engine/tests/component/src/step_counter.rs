@@ -0,0 +1,27 @@
+use scrypto::prelude::*;
+
+use crate::counter_interface::Counter;
+
+blueprint! {
+    struct StepCounter {
+        count: u32,
+        step: u32,
+    }
+
+    impl StepCounter {
+        pub fn new(step: u32) -> ComponentAddress {
+            Self { count: 0, step }.instantiate().globalize()
+        }
+
+        pub fn increment(&mut self) -> u32 {
+            self.count += self.step;
+            self.count
+        }
+
+        pub fn get(&self) -> u32 {
+            self.count
+        }
+    }
+
+    impl Counter for StepCounter {}
+}
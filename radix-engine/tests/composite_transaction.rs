@@ -0,0 +1,139 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::errors::{RuntimeError, TransactionValidationError};
+use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::model::*;
+use scrypto::prelude::*;
+
+#[test]
+fn partial_transactions_can_exchange_resources_through_worktop() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (other_pk, other_sk, other_account) = test_runner.new_account();
+    let resource_address = test_runner.create_fungible_resource(100.into(), 0, other_account);
+
+    // Act
+    let other_part = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_amount(Decimal::from(1), resource_address, other_account)
+        .build(test_runner.get_nonce([other_pk]));
+    let part = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_amount(Decimal::from(10), RADIX_TOKEN, account)
+        .take_from_worktop(resource_address, |builder, bucket_id| {
+            builder
+                .add_instruction(Instruction::CallMethod {
+                    component_address: account,
+                    method: "deposit".to_owned(),
+                    args: vec![scrypto_encode(&scrypto::resource::Bucket(bucket_id))],
+                })
+                .0
+        })
+        .call_method_with_all_resources(other_account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]));
+    let intent_hash = CompositeTransaction::intent_hash_of(&[&other_part, &part]);
+    let composite = CompositeTransaction::new(vec![
+        other_part.sign_part(intent_hash, [&other_sk]),
+        part.sign_part(intent_hash, [&sk]),
+    ]);
+    let receipt = test_runner.validate_and_execute_composite(&composite);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+}
+
+#[test]
+fn partial_transaction_cannot_use_signatures_of_other_parts() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (_, _, other_account) = test_runner.new_account();
+
+    // Act
+    let part = test_runner
+        .new_transaction_builder()
+        .call_method(account, "balance", args![RADIX_TOKEN])
+        .build(test_runner.get_nonce([pk]));
+    let other_part = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account(RADIX_TOKEN, account)
+        .call_method_with_all_resources(other_account, "deposit_batch")
+        .build(test_runner.get_nonce([]));
+    let intent_hash = CompositeTransaction::intent_hash_of(&[&part, &other_part]);
+    let composite = CompositeTransaction::new(vec![
+        part.sign_part(intent_hash, [&sk]),
+        other_part.sign_part(intent_hash, []),
+    ]);
+    let receipt = test_runner.validate_and_execute_composite(&composite);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be runtime error").cause;
+    assert_auth_error!(error);
+}
+
+#[test]
+fn buckets_cannot_cross_partial_transactions() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+
+    // Act
+    let part = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account(RADIX_TOKEN, account)
+        .take_from_worktop(RADIX_TOKEN, |builder, _| builder)
+        .build(test_runner.get_nonce([pk]));
+    let other_part = test_runner
+        .new_transaction_builder()
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]));
+    let intent_hash = CompositeTransaction::intent_hash_of(&[&part, &other_part]);
+    let composite = CompositeTransaction::new(vec![
+        part.sign_part(intent_hash, [&sk]),
+        other_part.sign_part(intent_hash, [&sk]),
+    ]);
+    let receipt = test_runner.validate_and_execute_composite(&composite);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be runtime error").cause;
+    assert_eq!(error, RuntimeError::BucketsCrossPartialTransaction);
+}
+
+#[test]
+fn part_cannot_be_rewrapped_in_another_composite_transaction() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (_, _, other_account) = test_runner.new_account();
+    let part = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account(RADIX_TOKEN, account)
+        .build(test_runner.get_nonce([pk]));
+    let other_part = test_runner
+        .new_transaction_builder()
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([]));
+    let intent_hash = CompositeTransaction::intent_hash_of(&[&part, &other_part]);
+    let signed_part = part.sign_part(intent_hash, [&sk]);
+
+    // Act
+    let malicious_part = test_runner
+        .new_transaction_builder()
+        .call_method_with_all_resources(other_account, "deposit_batch")
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let result = CompositeTransaction::new(vec![signed_part, malicious_part]).validate();
+
+    // Assert
+    assert_eq!(
+        result.err(),
+        Some(TransactionValidationError::InvalidSignature)
+    );
+}
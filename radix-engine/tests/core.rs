@@ -1,6 +1,10 @@
+use radix_engine::errors::{CallFrame, RuntimeError};
 use radix_engine::ledger::*;
 use radix_engine::transaction::*;
+use sbor::Type;
+use scrypto::engine::api::BATCH;
 use scrypto::prelude::*;
+use scrypto::values::ScryptoValue;
 
 #[test]
 fn test_process_and_transaction() {
@@ -18,6 +22,25 @@ fn test_process_and_transaction() {
     receipt1.result.expect("Should be okay.");
 }
 
+#[test]
+fn test_transaction_signers() {
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let (pk, sk) = executor.new_key_pair();
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
+
+    let transaction = TransactionBuilder::new()
+        .call_function(package, "CoreTest", "transaction_signers", args![])
+        .build(executor.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+    receipt.result.expect("Should be okay.");
+    let signer = NonFungibleAddress::new(ECDSA_TOKEN, NonFungibleId::from_bytes(pk.to_vec()));
+    assert_eq!(receipt.outputs[0], ScryptoValue::from_value(&vec![signer]));
+}
+
 #[test]
 fn test_call() {
     let mut ledger = InMemorySubstateStore::with_bootstrap();
@@ -36,3 +59,318 @@ fn test_call() {
     let receipt = executor.validate_and_execute(&transaction).unwrap();
     receipt.result.expect("Should be okay.");
 }
+
+#[derive(TypeId, Encode)]
+enum ErrorTestError {
+    AmountTooLarge { amount: u32, max: u32 },
+}
+
+#[test]
+fn test_blueprint_error_is_reported_in_receipt() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(package, "ErrorTest", "check_amount", args![101u32])
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    let error = ErrorTestError::AmountTooLarge {
+        amount: 101,
+        max: 100,
+    };
+    assert_eq!(
+        receipt.result.map_err(|e| e.cause),
+        Err(RuntimeError::BlueprintError {
+            package_address: package,
+            blueprint_name: "ErrorTest".to_owned(),
+            error: ScryptoValue::from_value(&error),
+        })
+    );
+}
+
+#[test]
+fn test_transaction_error_has_code_and_actor_stack() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(package, "ErrorTest", "check_amount", args![101u32])
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error");
+    assert_eq!(error.code, error.cause.code());
+    assert_eq!(
+        error.actor_stack,
+        vec![
+            CallFrame {
+                snode: "Transaction".to_owned(),
+                function: "execute".to_owned(),
+            },
+            CallFrame {
+                snode: format!("{}/ErrorTest", package),
+                function: "check_amount".to_owned(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_blueprint_result_without_error_returns_output() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(package, "ErrorTest", "check_amount", args![5u32])
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+    assert_eq!(receipt.outputs[0], ScryptoValue::from_value(&5u32));
+}
+
+#[test]
+fn test_blueprint_constants_and_error_are_exported_in_abi() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
+
+    // Act
+    let abi = executor.export_abi(package, "ErrorTest").unwrap();
+
+    // Assert
+    assert_eq!(abi.constants.len(), 1);
+    assert_eq!(abi.constants[0].name, "MAX_AMOUNT");
+    assert!(matches!(abi.constants[0].ty, Type::U32));
+    assert_eq!(abi.constants[0].value, scrypto_encode(&100u32));
+    assert!(matches!(abi.error, Some(Type::Enum { .. })));
+    assert!(matches!(abi.functions[0].output, Type::U32));
+}
+
+#[test]
+fn test_panic_message_is_reported_in_receipt() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(package, "ErrorTest", "panic_with_message", args![])
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    match receipt.result.map_err(|e| e.cause) {
+        Err(RuntimeError::Panic { message, .. }) => assert_eq!(message, "Something went wrong"),
+        _ => panic!("Should be a panic"),
+    }
+}
+
+#[test]
+fn test_call_function_with_composite_args() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
+    let abi = executor.export_abi(package, "ArgsTest").unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function_with_abi(
+            package,
+            "ArgsTest",
+            "composite_args",
+            vec![
+                "[1, 2, 3]".to_owned(),
+                r#"Some("hello, world")"#.to_owned(),
+                "(7, 1.5)".to_owned(),
+                r#"{name: "apple", quantity: 10}"#.to_owned(),
+                "Sell { limit: 2.5 }".to_owned(),
+            ],
+            None,
+            &abi,
+        )
+        .unwrap()
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+    assert_eq!(
+        receipt.outputs[0],
+        ScryptoValue::from_value(
+            &r#"[1, 2, 3] Some("hello, world") 7 1.5 apple 10 sell at 2.5"#.to_owned()
+        )
+    );
+}
+
+#[test]
+fn test_call_function_with_bucket_vec_arg() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let (pk, sk, account) = executor.new_account();
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
+    let abi = executor.export_abi(package, "ArgsTest").unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function_with_abi(
+            package,
+            "ArgsTest",
+            "return_buckets",
+            vec![format!(
+                r#"[Bucket("5,{}"), Bucket("3,{}")]"#,
+                RADIX_TOKEN, RADIX_TOKEN
+            )],
+            Some(account),
+            &abi,
+        )
+        .unwrap()
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(executor.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+}
+
+#[test]
+fn test_call_function_with_invalid_composite_arg() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
+    let abi = executor.export_abi(package, "ArgsTest").unwrap();
+
+    // Act
+    let mut builder = TransactionBuilder::new();
+    let result = builder.call_function_with_abi(
+        package,
+        "ArgsTest",
+        "composite_args",
+        vec![
+            "[1, 2, 3]".to_owned(),
+            "None".to_owned(),
+            "(7, 1.5)".to_owned(),
+            r#"{name: "apple"}"#.to_owned(),
+            "Buy".to_owned(),
+        ],
+        None,
+        &abi,
+    );
+
+    // Assert
+    assert!(matches!(
+        result,
+        Err(CallWithAbiError::FailedToBuildArgs(
+            BuildArgsError::FailedToParse(3, ..)
+        ))
+    ));
+}
+
+#[test]
+fn nested_batch_should_fail() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(package, "CoreTest", "nested_batch", args![])
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
+    assert_eq!(runtime_error, RuntimeError::InvalidRequestCode(BATCH));
+}
+
+#[test]
+fn caller_should_be_none_when_called_by_transaction() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(package, "CoreTest", "caller", args![])
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+    let caller: Option<ScryptoActorInfo> = None;
+    assert_eq!(receipt.outputs[0], ScryptoValue::from_value(&caller));
+}
+
+#[test]
+fn caller_should_be_the_calling_blueprint() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(package, "CoreTest", "nested_caller", args![])
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+    let caller = Some(ScryptoActorInfo::blueprint(
+        package,
+        "CoreTest".to_owned(),
+        "CoreTest_main".to_owned(),
+    ));
+    assert_eq!(receipt.outputs[0], ScryptoValue::from_value(&caller));
+}
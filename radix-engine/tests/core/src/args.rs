@@ -0,0 +1,40 @@
+use scrypto::prelude::*;
+
+#[derive(TypeId, Encode, Decode, Describe)]
+pub struct Order {
+    pub name: String,
+    pub quantity: u32,
+}
+
+#[derive(TypeId, Encode, Decode, Describe)]
+pub enum Side {
+    Buy,
+    Sell { limit: Decimal },
+}
+
+blueprint! {
+    struct ArgsTest;
+
+    impl ArgsTest {
+        pub fn composite_args(
+            amounts: Vec<u32>,
+            memo: Option<String>,
+            pair: (u8, Decimal),
+            order: Order,
+            side: Side,
+        ) -> String {
+            let side = match side {
+                Side::Buy => "buy".to_owned(),
+                Side::Sell { limit } => format!("sell at {}", limit),
+            };
+            format!(
+                "{:?} {:?} {} {} {} {} {}",
+                amounts, memo, pair.0, pair.1, order.name, order.quantity, side
+            )
+        }
+
+        pub fn return_buckets(buckets: Vec<Bucket>) -> Vec<Bucket> {
+            buckets
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use scrypto::prelude::*;
+
+#[derive(NonFungibleData)]
+pub struct Ticket {
+    seat: u32,
+}
+
+blueprint! {
+    struct BenchTest {
+        next: Option<ComponentAddress>,
+    }
+
+    impl BenchTest {
+        pub fn new() -> ComponentAddress {
+            BenchTest { next: None }.instantiate().globalize()
+        }
+
+        /// Instantiates `depth` components, each of which calls the next one.
+        pub fn new_chain(depth: u32) -> ComponentAddress {
+            let mut next = None;
+            for _ in 0..depth {
+                next = Some(BenchTest { next }.instantiate().globalize());
+            }
+            next.unwrap()
+        }
+
+        pub fn call_chain(&self) -> u32 {
+            match self.next {
+                Some(next) => {
+                    let depth: u32 =
+                        scrypto_decode(&Runtime::call_method(next, "call_chain", args!())).unwrap();
+                    depth + 1
+                }
+                None => 1,
+            }
+        }
+
+        pub fn mint_non_fungibles(count: u32) -> Bucket {
+            ResourceBuilder::new_non_fungible()
+                .metadata("name", "Tickets")
+                .initial_supply(
+                    (0..count).map(|seat| (NonFungibleId::from_u32(seat), Ticket { seat })),
+                )
+        }
+    }
+}
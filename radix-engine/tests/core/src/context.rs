@@ -1,3 +1,5 @@
+use scrypto::engine::api::*;
+use scrypto::engine::EngineBatch;
 use scrypto::prelude::*;
 
 blueprint! {
@@ -12,5 +14,35 @@ blueprint! {
                 Runtime::generate_uuid(),
             )
         }
+
+        pub fn transaction_signers() -> Vec<NonFungibleAddress> {
+            Runtime::transaction_signers()
+        }
+
+        pub fn query_repeatedly(count: u32) {
+            for _ in 0..count {
+                Runtime::package_address();
+            }
+        }
+
+        pub fn echo(data: Vec<u8>) -> Vec<u8> {
+            data
+        }
+
+        pub fn caller() -> Option<ScryptoActorInfo> {
+            Runtime::caller()
+        }
+
+        pub fn nested_caller() -> Option<ScryptoActorInfo> {
+            let rtn =
+                Runtime::call_function(Runtime::package_address(), "CoreTest", "caller", args![]);
+            scrypto_decode(&rtn).unwrap()
+        }
+
+        pub fn nested_batch() {
+            let mut batch = EngineBatch::new();
+            batch.add(BATCH, BatchInput { calls: Vec::new() });
+            let _: Vec<BatchOutput> = batch.execute();
+        }
     }
 }
@@ -0,0 +1,28 @@
+use scrypto::prelude::*;
+
+blueprint! {
+    struct ErrorTest;
+
+    const MAX_AMOUNT: u32 = 100;
+
+    enum ErrorTestError {
+        AmountTooLarge { amount: u32, max: u32 },
+    }
+
+    impl ErrorTest {
+        pub fn check_amount(amount: u32) -> Result<u32, ErrorTestError> {
+            if amount > MAX_AMOUNT {
+                Err(ErrorTestError::AmountTooLarge {
+                    amount,
+                    max: MAX_AMOUNT,
+                })
+            } else {
+                Ok(amount)
+            }
+        }
+
+        pub fn panic_with_message() {
+            panic!("Something went wrong");
+        }
+    }
+}
@@ -1,2 +1,9 @@
+pub mod args;
+pub mod bench;
 pub mod call;
 pub mod context;
+pub mod error;
+pub mod logger;
+pub mod package_tests;
+pub mod state_size;
+pub mod static_state;
@@ -0,0 +1,22 @@
+use scrypto::prelude::*;
+
+blueprint! {
+    struct LoggerTest {}
+
+    impl LoggerTest {
+        pub fn emit_logs(count: u32, size: u32) {
+            let message = "x".repeat(size as usize);
+            for _ in 0..count {
+                info!("{}", message);
+            }
+        }
+
+        pub fn new() -> ComponentAddress {
+            Self {}.instantiate().globalize()
+        }
+
+        pub fn log(&self, count: u32, size: u32) {
+            Self::emit_logs(count, size);
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use scrypto::prelude::*;
+
+#[scrypto_test]
+fn test_decimal_addition() {
+    assert_eq!(dec!("1.5") + dec!("2.5"), dec!("4"));
+}
+
+#[scrypto_test]
+fn test_failing_assertion() {
+    assert!(Runtime::generate_uuid() == 0, "UUID should not be zero");
+}
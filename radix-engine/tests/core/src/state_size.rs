@@ -0,0 +1,64 @@
+use scrypto::prelude::*;
+
+#[derive(NonFungibleData)]
+pub struct Blob {
+    data: Vec<u8>,
+}
+
+blueprint! {
+    struct StateSize {
+        data: Vec<u8>,
+        map: LazyMap<u32, Vec<u8>>,
+        keys: LazyMap<Vec<u8>, u32>,
+    }
+
+    impl StateSize {
+        pub fn new() -> ComponentAddress {
+            StateSize {
+                data: Vec::new(),
+                map: LazyMap::new(),
+                keys: LazyMap::new(),
+            }
+            .instantiate()
+            .globalize()
+        }
+
+        pub fn set_data(&mut self, size: u32) {
+            self.data = vec![0u8; size as usize];
+        }
+
+        pub fn put_entry(&self, size: u32) {
+            self.map.insert(0u32, vec![0u8; size as usize]);
+        }
+
+        pub fn put_key(&self, size: u32) {
+            self.keys.insert(vec![0u8; size as usize], 0u32);
+        }
+
+        pub fn mint_blob(size: u32) -> Bucket {
+            ResourceBuilder::new_non_fungible()
+                .metadata("name", "Blobs")
+                .initial_supply([(
+                    NonFungibleId::from_u32(0),
+                    Blob {
+                        data: vec![0u8; size as usize],
+                    },
+                )])
+        }
+
+        /// Calls `take` with an argument of the given size.
+        pub fn forward(size: u32) -> u32 {
+            scrypto_decode(&Runtime::call_function(
+                Runtime::package_address(),
+                "StateSize",
+                "take",
+                args!(vec![0u8; size as usize]),
+            ))
+            .unwrap()
+        }
+
+        pub fn take(data: Vec<u8>) -> u32 {
+            data.len() as u32
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use scrypto::prelude::*;
+
+static mut COUNTER: u32 = 0;
+
+blueprint! {
+    struct StaticState;
+
+    impl StaticState {
+        pub fn increment() -> u32 {
+            unsafe {
+                COUNTER += 1;
+                COUNTER
+            }
+        }
+
+        pub fn increment_and_panic() {
+            unsafe {
+                COUNTER += 1;
+            }
+            panic!("Counter incremented");
+        }
+    }
+}
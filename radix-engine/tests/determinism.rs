@@ -0,0 +1,118 @@
+use radix_engine::engine::{WasmFeatures, DEFAULT_WASM_FEATURES};
+use radix_engine::errors::WasmValidationError;
+use radix_engine::ledger::*;
+use radix_engine::model::{Package, Receipt};
+use radix_engine::transaction::*;
+use scrypto::prelude::*;
+
+fn execute_transactions(ledger: &mut InMemorySubstateStore, codes: &[Vec<u8>]) -> Vec<Receipt> {
+    let mut executor = TransactionExecutor::new(ledger, false);
+    let mut receipts = Vec::new();
+
+    let mut packages = Vec::new();
+    for code in codes {
+        packages.push(executor.publish_package(code).unwrap());
+    }
+
+    let transaction = TransactionBuilder::new()
+        .call_function(packages[0], "SuperLazyMap", "new", args![])
+        .call_function(
+            packages[0],
+            "LazyMapTest",
+            "new_lazy_map_into_map_then_get",
+            args![],
+        )
+        .call_function(packages[1], "VaultTest", "new_vault_into_map", args![])
+        .call_function(
+            packages[1],
+            "VaultTest",
+            "new_vault_with_take_non_fungible",
+            args![],
+        )
+        .build(executor.get_nonce([]))
+        .sign([]);
+    receipts.push(executor.validate_and_execute(&transaction).unwrap());
+
+    let (_, _, account) = executor.new_account();
+    let transaction = TransactionBuilder::new()
+        .call_method(SYSTEM_COMPONENT, "free_xrd", args![])
+        .call_method(SYSTEM_COMPONENT, "free_xrd", args![])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(executor.get_nonce([]))
+        .sign([]);
+    receipts.push(executor.validate_and_execute(&transaction).unwrap());
+
+    receipts
+}
+
+#[test]
+fn executing_same_transactions_twice_gives_same_results() {
+    // Arrange
+    let codes = vec![
+        compile_package!(format!("./tests/{}", "lazy_map")),
+        compile_package!(format!("./tests/{}", "vault")),
+    ];
+    let mut ledger1 = InMemorySubstateStore::with_bootstrap();
+    let mut ledger2 = InMemorySubstateStore::with_bootstrap();
+
+    // Act
+    let receipts1 = execute_transactions(&mut ledger1, &codes);
+    let receipts2 = execute_transactions(&mut ledger2, &codes);
+
+    // Assert
+    for (receipt1, receipt2) in receipts1.iter().zip(receipts2.iter()) {
+        assert!(receipt1.result.is_ok());
+        assert_eq!(receipt1.result, receipt2.result);
+        assert_eq!(receipt1.outputs, receipt2.outputs);
+        assert_eq!(receipt1.logs, receipt2.logs);
+        assert_eq!(
+            receipt1.new_component_addresses,
+            receipt2.new_component_addresses
+        );
+        assert_eq!(
+            receipt1.new_resource_addresses,
+            receipt2.new_resource_addresses
+        );
+        let commit1 = receipt1.commit_receipt.as_ref().unwrap();
+        let commit2 = receipt2.commit_receipt.as_ref().unwrap();
+        assert_eq!(commit1.up_substates, commit2.up_substates);
+        assert_eq!(commit1.down_substates, commit2.down_substates);
+    }
+    assert_eq!(ledger1, ledger2);
+}
+
+#[test]
+fn floating_point_should_be_rejected_wherever_it_appears() {
+    // The interpreter's own check only looks at instructions and function signatures, so the
+    // other places are caught by the wasm feature scan.
+    for wat in [
+        "(module (func (param f32)))",
+        "(module (func (local f64)))",
+        "(module (global f32 (f32.const 0)))",
+        "(module (memory 1) (func (drop (f64.load (i32.const 0)))))",
+        "(module (func (drop (f32.reinterpret_i32 (i32.const 0)))))",
+        "(module (func (drop (block (result f64) (unreachable)))))",
+        "(module (type (func (param f64))))",
+        "(module (import \"env\" \"f\" (func (param f32))))",
+    ] {
+        // Arrange
+        let code = wabt::wat2wasm(wat).expect("failed to parse wat");
+
+        // Act
+        let result = Package::new(code.clone());
+
+        // Assert
+        assert!(
+            matches!(result, Err(WasmValidationError::FloatingPointNotAllowed)),
+            "{}",
+            wat
+        );
+        let features = WasmFeatures {
+            nan_canonicalization: true,
+            ..DEFAULT_WASM_FEATURES
+        };
+        assert_eq!(features.check(&code), Ok(()));
+        let canonicalized = features.canonicalize_nans(&code).unwrap();
+        assert!(wasmi::Module::from_buffer(canonicalized).is_ok(), "{}", wat);
+    }
+}
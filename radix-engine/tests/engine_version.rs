@@ -0,0 +1,353 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::engine::{
+    derive_component_address, derive_resource_address, EngineVersion, MAX_PACKAGE_CODE_SIZE,
+};
+use radix_engine::errors::{RuntimeError, WasmValidationError};
+use radix_engine::ledger::{InMemorySubstateStore, SubstateStore};
+use radix_engine::model::{PackageError, SystemError, SystemTransaction};
+use radix_engine::transaction::TransactionExecutor;
+use scrypto::prelude::*;
+
+/// Appends a custom section to a wasm module, growing it to at least the given size.
+fn pad_code(mut code: Vec<u8>, min_size: usize) -> Vec<u8> {
+    let name = b"padding";
+    let payload_size = min_size.saturating_sub(code.len());
+    let mut section_size = name.len() + 1 + payload_size;
+
+    code.push(0u8);
+    loop {
+        let byte = (section_size & 0x7f) as u8;
+        section_size >>= 7;
+        if section_size == 0 {
+            code.push(byte);
+            break;
+        }
+        code.push(byte | 0x80);
+    }
+    code.push(name.len() as u8);
+    code.extend_from_slice(name);
+    code.extend(vec![0u8; payload_size]);
+    code
+}
+
+#[test]
+fn caller_identity_proofs_depend_on_engine_version() {
+    for engine_version in EngineVersion::ALL {
+        // Arrange
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::with_engine_version(&mut substate_store, engine_version);
+        let package_address = test_runner.publish_package("component");
+        let transaction = test_runner
+            .new_transaction_builder()
+            .call_function(
+                package_address,
+                "CrossComponent",
+                "create_component",
+                vec![],
+            )
+            .build(test_runner.get_nonce([]))
+            .sign([]);
+        let receipt = test_runner.validate_and_execute(&transaction);
+        receipt.result.expect("Should be okay");
+        let my_component = receipt.new_component_addresses[0];
+
+        let authorization = AccessRules::new().method(
+            "get_component_state",
+            rule!(require(NonFungibleAddress::from_global_caller(
+                my_component
+            ))),
+        );
+        let transaction = test_runner
+            .new_transaction_builder()
+            .call_function(
+                package_address,
+                "CrossComponent",
+                "create_component_with_auth",
+                vec![scrypto_encode(&authorization)],
+            )
+            .build(test_runner.get_nonce([]))
+            .sign([]);
+        let receipt = test_runner.validate_and_execute(&transaction);
+        receipt.result.expect("Should be okay");
+        let secured_component = receipt.new_component_addresses[0];
+
+        // Act
+        let transaction = test_runner
+            .new_transaction_builder()
+            .call_method(
+                my_component,
+                "cross_component_call",
+                vec![scrypto_encode(&secured_component)],
+            )
+            .build(test_runner.get_nonce([]))
+            .sign([]);
+        let receipt = test_runner.validate_and_execute(&transaction);
+
+        // Assert
+        if engine_version.features().caller_identity_proofs {
+            receipt.result.expect("Should be okay");
+        } else {
+            let error = receipt.result.expect_err("Should be error").cause;
+            assert_auth_error!(error);
+        }
+    }
+}
+
+#[test]
+fn package_code_size_limit_depends_on_engine_version() {
+    for engine_version in EngineVersion::ALL {
+        // Arrange
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::with_engine_version(&mut substate_store, engine_version);
+        let code = pad_code(TestRunner::compile("package"), MAX_PACKAGE_CODE_SIZE + 1);
+
+        // Act
+        let transaction = test_runner
+            .new_transaction_builder()
+            .publish_package(&code)
+            .build(test_runner.get_nonce([]))
+            .sign([]);
+        let receipt = test_runner.validate_and_execute(&transaction);
+
+        // Assert
+        match engine_version.features().max_package_code_size {
+            Some(_) => assert_eq!(
+                receipt.result.map_err(|e| e.cause),
+                Err(RuntimeError::PackageError(
+                    PackageError::WasmValidationError(WasmValidationError::CodeTooLarge(
+                        code.len()
+                    ))
+                ))
+            ),
+            None => receipt.result.expect("Should be okay"),
+        }
+    }
+}
+
+#[test]
+fn wasm_limits_depend_on_engine_version() {
+    for engine_version in EngineVersion::ALL {
+        // Arrange
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::with_engine_version(&mut substate_store, engine_version);
+        let code: Vec<u8> = wabt::wat2wasm(
+            r#"
+                (module
+                    (memory (export "memory") 1)
+                    (func (export "Test-main"))
+                )
+                "#,
+        )
+        .expect("failed to parse wat");
+
+        // Act
+        let transaction = test_runner
+            .new_transaction_builder()
+            .publish_package(&code)
+            .build(test_runner.get_nonce([]))
+            .sign([]);
+        let receipt = test_runner.validate_and_execute(&transaction);
+
+        // Assert
+        match engine_version.features().wasm_limits {
+            Some(_) => assert_eq!(
+                receipt.result.map_err(|e| e.cause),
+                Err(RuntimeError::PackageError(
+                    PackageError::WasmValidationError(WasmValidationError::InvalidExportName(
+                        "Test-main".to_string()
+                    ))
+                ))
+            ),
+            None => receipt.result.expect("Should be okay"),
+        }
+    }
+}
+
+#[test]
+fn address_derivation_depends_on_engine_version() {
+    for engine_version in EngineVersion::ALL {
+        // Arrange
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::with_engine_version(&mut substate_store, engine_version);
+        let package_address = test_runner.publish_package("component");
+        let transaction = test_runner
+            .new_transaction_builder()
+            .call_function(
+                package_address,
+                "ComponentTest",
+                "create_component",
+                args![],
+            )
+            .build(test_runner.get_nonce([]));
+        let transaction_hash = transaction.raw_hash();
+
+        // Act
+        let receipt = test_runner.validate_and_execute(&transaction.sign([]));
+
+        // Assert
+        receipt.result.expect("Should be okay");
+        let typed_addresses = receipt.new_component_addresses
+            == vec![derive_component_address(transaction_hash, 0)]
+            && receipt.new_resource_addresses == vec![derive_resource_address(transaction_hash, 0)];
+        assert_eq!(typed_addresses, engine_version.features().typed_addresses);
+    }
+}
+
+#[test]
+fn substate_fees_depend_on_engine_version() {
+    for engine_version in EngineVersion::ALL {
+        // Arrange
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::with_engine_version(&mut substate_store, engine_version);
+        let package_address = test_runner.publish_package("component");
+
+        // Act
+        let transaction = test_runner
+            .new_transaction_builder()
+            .call_function(
+                package_address,
+                "ComponentTest",
+                "create_component",
+                args![],
+            )
+            .build(test_runner.get_nonce([]));
+        let receipt = test_runner.validate_and_execute(&transaction.sign([]));
+
+        // Assert
+        receipt.result.expect("Should be okay");
+        let substate_fees = engine_version.features().substate_fees;
+        assert_eq!(
+            receipt.cost_unit_breakdown.contains_key("substate_read"),
+            substate_fees
+        );
+        assert_eq!(
+            receipt.cost_unit_breakdown.contains_key("substate_write"),
+            substate_fees
+        );
+    }
+}
+
+#[test]
+fn lock_fees_depend_on_engine_version() {
+    for engine_version in EngineVersion::ALL {
+        // Arrange
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut test_runner = TestRunner::with_engine_version(&mut substate_store, engine_version);
+        let package_address = test_runner.publish_package("component");
+
+        // Act
+        let transaction = test_runner
+            .new_transaction_builder()
+            .call_function(
+                package_address,
+                "ComponentTest",
+                "create_component",
+                args![],
+            )
+            .build(test_runner.get_nonce([]));
+        let receipt = test_runner.validate_and_execute(&transaction.sign([]));
+
+        // Assert
+        receipt.result.expect("Should be okay");
+        assert_eq!(
+            receipt.cost_unit_breakdown.contains_key("lock_substate"),
+            engine_version.features().lock_fees
+        );
+    }
+}
+
+#[test]
+fn engine_version_should_be_read_from_ledger_state() {
+    // Arrange
+    let mut unversioned_store = InMemorySubstateStore::new();
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+
+    // Act
+    let unversioned = TransactionExecutor::new(&mut unversioned_store, false).engine_version();
+    let bootstrapped = TransactionExecutor::new(&mut substate_store, false).engine_version();
+    substate_store.set_engine_version(EngineVersion::V2);
+    let updated = TransactionExecutor::new(&mut substate_store, false).engine_version();
+
+    // Assert
+    assert_eq!(unversioned, EngineVersion::V1);
+    assert_eq!(bootstrapped, EngineVersion::LATEST);
+    assert_eq!(updated, EngineVersion::V2);
+}
+
+#[test]
+fn legacy_ledger_can_be_upgraded_by_system_transaction() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::with_engine_version(&mut substate_store, EngineVersion::V1);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(
+            SYSTEM_COMPONENT,
+            "set_engine_version",
+            args![EngineVersion::LATEST],
+        )
+        .build_with_no_nonce();
+    let receipt = test_runner.validate_and_execute_system(&SystemTransaction::new(transaction));
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert_eq!(test_runner.engine_version(), EngineVersion::LATEST);
+}
+
+#[test]
+fn user_transaction_cannot_upgrade_engine_version() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::with_engine_version(&mut substate_store, EngineVersion::V1);
+    let (pk, sk, _) = test_runner.new_account();
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(
+            SYSTEM_COMPONENT,
+            "set_engine_version",
+            args![EngineVersion::LATEST],
+        )
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert_auth_error!(error);
+    assert_eq!(test_runner.engine_version(), EngineVersion::V1);
+}
+
+#[test]
+fn engine_version_cannot_be_downgraded() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(
+            SYSTEM_COMPONENT,
+            "set_engine_version",
+            args![EngineVersion::V1],
+        )
+        .build_with_no_nonce();
+    let receipt = test_runner.validate_and_execute_system(&SystemTransaction::new(transaction));
+
+    // Assert
+    assert_eq!(
+        receipt.result.expect_err("Should be error").cause,
+        RuntimeError::SystemError(SystemError::EngineVersionDowngrade {
+            current: EngineVersion::LATEST,
+            requested: EngineVersion::V1,
+        })
+    );
+    assert_eq!(test_runner.engine_version(), EngineVersion::LATEST);
+}
@@ -0,0 +1,114 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::errors::RuntimeError;
+use radix_engine::ledger::InMemorySubstateStore;
+use scrypto::prelude::*;
+
+#[test]
+fn component_can_check_its_features() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package_address = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package_address,
+            "FeatureComponent",
+            "create_component",
+            args![vec!["fees".to_string()]],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    let component = receipt.new_component_addresses[0];
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(component, "is_enabled", args!["fees".to_string()])
+        .call_method(component, "is_enabled", args!["whitelist".to_string()])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    let fees: bool = scrypto_decode(&receipt.outputs[0].raw).unwrap();
+    let whitelist: bool = scrypto_decode(&receipt.outputs[1].raw).unwrap();
+    assert!(fees);
+    assert!(!whitelist);
+}
+
+#[test]
+fn cannot_instantiate_with_undeclared_feature() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package_address = test_runner.publish_package("component");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package_address,
+            "FeatureComponent",
+            "create_component",
+            args![vec!["unknown".to_string()]],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert_eq!(
+        error,
+        RuntimeError::FeatureNotDeclared {
+            blueprint_name: "FeatureComponent".to_string(),
+            feature: "unknown".to_string(),
+        }
+    );
+}
+
+#[test]
+fn cannot_check_undeclared_feature() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package_address = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package_address,
+            "FeatureComponent",
+            "create_component",
+            args![Vec::<String>::new()],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    let component = receipt.new_component_addresses[0];
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(component, "is_enabled", args!["unknown".to_string()])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert_eq!(
+        error,
+        RuntimeError::FeatureNotDeclared {
+            blueprint_name: "FeatureComponent".to_string(),
+            feature: "unknown".to_string(),
+        }
+    );
+}
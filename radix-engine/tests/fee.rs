@@ -0,0 +1,196 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::errors::RuntimeError;
+use radix_engine::fee::CostUnitCounterError;
+use radix_engine::ledger::*;
+use radix_engine::transaction::*;
+use scrypto::prelude::*;
+
+#[test]
+fn can_estimate_cost_without_committing() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, _, account) = test_runner.new_account();
+    let nonce = test_runner.get_nonce([pk]);
+    let resources = test_runner.get_component_resources(account);
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(SYSTEM_COMPONENT, "free_xrd", args![])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(nonce);
+
+    // Act
+    let estimate = test_runner.estimate_cost(&transaction, [pk]);
+
+    // Assert
+    estimate.preview_result.expect("Should be okay");
+    assert!(estimate.cost_units > 0);
+    assert_eq!(
+        estimate.suggested_cost_unit_limit,
+        estimate.cost_units + estimate.cost_units / 5
+    );
+    assert!(estimate.suggested_fee.is_positive());
+    assert_eq!(test_runner.get_nonce([pk]), nonce);
+    assert_eq!(test_runner.get_component_resources(account), resources);
+}
+
+#[test]
+fn cost_estimate_should_be_limited_by_cost_unit_limit() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let transaction = TransactionBuilder::new()
+        .call_method(SYSTEM_COMPONENT, "free_xrd", args![])
+        .build(SubstateStore::get_nonce(&substate_store));
+    let mut executor = TransactionExecutor::new(&mut substate_store, false).with_cost_unit_limit(1);
+
+    // Act
+    let estimate = executor.estimate_cost(&transaction, []).unwrap();
+
+    // Assert
+    assert!(matches!(
+        estimate.preview_result.unwrap_err().cause,
+        RuntimeError::CostingError(CostUnitCounterError::LimitExceeded { .. })
+    ));
+}
+
+#[test]
+fn cost_estimate_should_match_execution() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(SYSTEM_COMPONENT, "free_xrd", args![])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]));
+    let estimate = test_runner.estimate_cost(&transaction, [pk]);
+
+    // Act
+    let receipt = test_runner.validate_and_execute(&transaction.sign([&sk]));
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert_eq!(receipt.cost_units_consumed, estimate.cost_units);
+}
+
+#[test]
+fn can_execute_within_cost_budget() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(SYSTEM_COMPONENT, "free_xrd", args![])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+
+    // Act
+    let receipt = test_runner.execute_with_cost_budget(&transaction, 1_000_000);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert_eq!(
+        receipt.cost_unit_breakdown.values().sum::<u32>(),
+        receipt.cost_units_consumed
+    );
+}
+
+#[test]
+#[should_panic(expected = "exceeding the budget")]
+fn exceeding_cost_budget_should_fail() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(SYSTEM_COMPONENT, "free_xrd", args![])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+
+    // Act
+    test_runner.execute_with_cost_budget(&transaction, 1);
+}
+
+#[test]
+fn creating_substates_should_consume_write_cost_units() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package, "ComponentTest", "create_component", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+
+    // Act
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert!(receipt.cost_unit_breakdown["substate_read"] > 0);
+    assert!(receipt.cost_unit_breakdown["substate_write"] > 0);
+}
+
+#[test]
+fn reading_substates_should_not_consume_write_cost_units() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package, "ComponentTest", "create_component", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    let component = receipt.new_component_addresses[0];
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(component, "get_component_state", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+
+    // Act
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert!(receipt.cost_unit_breakdown["substate_read"] > 0);
+    assert!(!receipt.cost_unit_breakdown.contains_key("substate_write"));
+}
+
+#[test]
+fn borrowing_substates_should_consume_lock_cost_units() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package, "ComponentTest", "create_component", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    let component = receipt.new_component_addresses[0];
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(component, "get_component_state", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+
+    // Act
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert!(receipt.cost_unit_breakdown["lock_substate"] > 0);
+}
@@ -0,0 +1,224 @@
+use radix_engine::engine::EngineHooks;
+use radix_engine::errors::RuntimeError;
+use radix_engine::fee::CostUnitCounter;
+use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::model::SignedTransaction;
+use radix_engine::transaction::*;
+use scrypto::prelude::*;
+use scrypto::values::ScryptoValue;
+
+#[derive(Default)]
+struct CountingHooks {
+    pre_invocations: u64,
+    post_invocations: u64,
+    max_depth: usize,
+    substate_reads: u64,
+    substate_writes: u64,
+}
+
+impl EngineHooks for CountingHooks {
+    fn pre_invoke(
+        &mut self,
+        depth: usize,
+        _snode_ref: &SNodeRef,
+        _function: &str,
+        _args: &[ScryptoValue],
+        _cost_unit_counter: &mut CostUnitCounter,
+    ) -> Result<(), RuntimeError> {
+        self.pre_invocations += 1;
+        self.max_depth = self.max_depth.max(depth);
+        Ok(())
+    }
+
+    fn post_invoke(
+        &mut self,
+        _depth: usize,
+        _snode_ref: &SNodeRef,
+        _function: &str,
+        _result: &Result<ScryptoValue, RuntimeError>,
+        _cost_unit_counter: &mut CostUnitCounter,
+    ) -> Result<(), RuntimeError> {
+        self.post_invocations += 1;
+        Ok(())
+    }
+
+    fn on_substate_read(&mut self, _substate_id: &[u8], _value: Option<&[u8]>) {
+        self.substate_reads += 1;
+    }
+
+    fn on_substate_write(
+        &mut self,
+        _substate_id: &[u8],
+        _value: &[u8],
+    ) -> Result<(), RuntimeError> {
+        self.substate_writes += 1;
+        Ok(())
+    }
+}
+
+struct FunctionBlocker(&'static str);
+
+impl EngineHooks for FunctionBlocker {
+    fn pre_invoke(
+        &mut self,
+        _depth: usize,
+        _snode_ref: &SNodeRef,
+        function: &str,
+        _args: &[ScryptoValue],
+        _cost_unit_counter: &mut CostUnitCounter,
+    ) -> Result<(), RuntimeError> {
+        if function == self.0 {
+            return Err(RuntimeError::HookError(format!("{} is blocked", function)));
+        }
+        Ok(())
+    }
+}
+
+struct WriteBlocker;
+
+impl EngineHooks for WriteBlocker {
+    fn on_substate_write(
+        &mut self,
+        _substate_id: &[u8],
+        _value: &[u8],
+    ) -> Result<(), RuntimeError> {
+        Err(RuntimeError::HookError("writes are blocked".to_owned()))
+    }
+}
+
+struct InvocationFee(u32);
+
+impl EngineHooks for InvocationFee {
+    fn pre_invoke(
+        &mut self,
+        _depth: usize,
+        _snode_ref: &SNodeRef,
+        _function: &str,
+        _args: &[ScryptoValue],
+        cost_unit_counter: &mut CostUnitCounter,
+    ) -> Result<(), RuntimeError> {
+        cost_unit_counter
+            .consume(self.0, "invocation_fee")
+            .map_err(RuntimeError::CostingError)
+    }
+}
+
+fn free_xrd_transaction(nonce: u64, account: ComponentAddress) -> SignedTransaction {
+    TransactionBuilder::new()
+        .call_method(SYSTEM_COMPONENT, "free_xrd", args![])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(nonce)
+        .sign([])
+}
+
+#[test]
+fn hooks_should_observe_invocations_and_substate_accesses() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut executor =
+        TransactionExecutor::new(&mut substate_store, false).with_hooks(CountingHooks::default());
+    let (_, _, account) = executor.new_account();
+    *executor.hooks_mut() = CountingHooks::default();
+
+    // Act
+    let transaction = free_xrd_transaction(executor.get_nonce([]), account);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    let hooks = executor.hooks();
+    assert!(hooks.pre_invocations > 0);
+    assert_eq!(hooks.pre_invocations, hooks.post_invocations);
+    assert!(hooks.max_depth > 0);
+    assert!(hooks.substate_reads > 0);
+    assert!(hooks.substate_writes > 0);
+}
+
+#[test]
+fn hooks_should_observe_substate_reads_served_by_caches() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut executor =
+        TransactionExecutor::new(&mut substate_store, false).with_hooks(CountingHooks::default());
+    let (_, _, account) = executor.new_account();
+    *executor.hooks_mut() = CountingHooks::default();
+
+    // Act
+    let transaction = free_xrd_transaction(executor.get_nonce([]), account);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    let stats = receipt.substate_read_stats;
+    assert!(stats.transaction_cache_hits > 0);
+    assert_eq!(
+        executor.hooks().substate_reads,
+        stats.transaction_cache_hits + stats.substate_cache_hits + stats.store_reads
+    );
+}
+
+#[test]
+fn hooks_should_be_able_to_reject_invocations() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut substate_store, false);
+    let (_, _, account) = executor.new_account();
+    let mut executor = executor.with_hooks(FunctionBlocker("free_xrd"));
+
+    // Act
+    let transaction = free_xrd_transaction(executor.get_nonce([]), account);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    let error = receipt.result.expect_err("Should be an error");
+    assert_eq!(
+        error.cause,
+        RuntimeError::HookError("free_xrd is blocked".to_owned())
+    );
+}
+
+#[test]
+fn hooks_should_be_able_to_reject_substate_writes() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut substate_store, false);
+    let (_, _, account) = executor.new_account();
+    let mut executor = executor.with_hooks(WriteBlocker);
+
+    // Act
+    let transaction = free_xrd_transaction(executor.get_nonce([]), account);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    let error = receipt.result.expect_err("Should be an error");
+    assert_eq!(
+        error.cause,
+        RuntimeError::HookError("writes are blocked".to_owned())
+    );
+    assert!(receipt.commit_receipt.is_none());
+}
+
+#[test]
+fn hooks_should_be_able_to_charge_cost_units() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut substate_store, false);
+    let (_, _, account) = executor.new_account();
+    let transaction = free_xrd_transaction(executor.get_nonce([]), account);
+    let cost_units_without_fee = executor
+        .preview(&transaction.transaction, [])
+        .unwrap()
+        .cost_units_consumed;
+    let mut executor = executor.with_hooks((CountingHooks::default(), InvocationFee(1000)));
+
+    // Act
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    let invocations = executor.hooks().0.pre_invocations as u32;
+    assert_eq!(
+        receipt.cost_units_consumed,
+        cost_units_without_fee + invocations * 1000
+    );
+}
@@ -1,8 +1,12 @@
 use radix_engine::errors::ResourceFailure;
 use radix_engine::errors::RuntimeError;
+use radix_engine::errors::TransientValue;
 use radix_engine::ledger::*;
 use radix_engine::transaction::*;
+use sbor::describe::Fields;
+use sbor::Type;
 use scrypto::prelude::*;
+use scrypto::values::ScryptoValue;
 
 #[test]
 fn dangling_lazy_map_should_fail() {
@@ -21,7 +25,7 @@ fn dangling_lazy_map_should_fail() {
     let receipt = executor.validate_and_execute(&transaction).unwrap();
 
     // Assert
-    let runtime_error = receipt.result.expect_err("Should be runtime error");
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
     assert_eq!(runtime_error, RuntimeError::ResourceCheckFailure(ResourceFailure::UnclaimedLazyMap));
 }
 
@@ -87,7 +91,7 @@ fn cyclic_map_fails_execution() {
     let receipt = executor.validate_and_execute(&transaction).unwrap();
 
     // Assert
-    let runtime_error = receipt.result.expect_err("Should be runtime error");
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
     match runtime_error {
         RuntimeError::CyclicLazyMap(_) => {}
         _ => panic!(
@@ -114,7 +118,7 @@ fn self_cyclic_map_fails_execution() {
     let receipt = executor.validate_and_execute(&transaction).unwrap();
 
     // Assert
-    let runtime_error = receipt.result.expect_err("Should be runtime error");
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
     match runtime_error {
         RuntimeError::CyclicLazyMap(_) => {}
         _ => panic!(
@@ -147,7 +151,7 @@ fn cannot_remove_lazy_maps() {
     let receipt = executor.validate_and_execute(&transaction).unwrap();
 
     // Assert
-    let runtime_error = receipt.result.expect_err("Should be runtime error");
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
     match runtime_error {
         RuntimeError::LazyMapRemoved(_) => {}
         _ => panic!("Should be lazy map removed error but was {}", runtime_error),
@@ -182,7 +186,7 @@ fn cannot_overwrite_lazy_maps() {
     let receipt = executor.validate_and_execute(&transaction).unwrap();
 
     // Assert
-    let runtime_error = receipt.result.expect_err("Should be runtime error");
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
     match runtime_error {
         RuntimeError::LazyMapRemoved(_) => {}
         _ => panic!("Should be lazy map removed error but was {}", runtime_error),
@@ -228,3 +232,186 @@ fn create_lazy_map_and_put() {
     // Assert
     assert!(receipt.result.is_ok());
 }
+
+#[test]
+fn can_read_readable_lazy_map_of_another_component() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "lazy_map")))
+        .unwrap();
+    let transaction = TransactionBuilder::new()
+        .call_function(package, "ReadableLazyMap", "new", args![])
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+    let registry = receipt.new_component_addresses[0];
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(
+            package,
+            "ReadableLazyMap",
+            "read_entry",
+            args![registry, "hello".to_owned()],
+        )
+        .call_function(
+            package,
+            "ReadableLazyMap",
+            "read_entry",
+            args![registry, "missing".to_owned()],
+        )
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    assert!(receipt.result.is_ok());
+    assert_eq!(
+        receipt.outputs[0],
+        ScryptoValue::from_value(&Some("world".to_owned()))
+    );
+    assert_eq!(
+        receipt.outputs[1],
+        ScryptoValue::from_value(&Option::<String>::None)
+    );
+}
+
+#[test]
+fn cannot_read_private_lazy_map_of_another_component() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "lazy_map")))
+        .unwrap();
+    let transaction = TransactionBuilder::new()
+        .call_function(package, "ReadableLazyMap", "new", args![])
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+    let registry = receipt.new_component_addresses[0];
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(
+            package,
+            "ReadableLazyMap",
+            "read_private_entry",
+            args![registry, "hello".to_owned()],
+        )
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
+    match runtime_error {
+        RuntimeError::LazyMapNotReadable(_) => {}
+        _ => panic!(
+            "Should be lazy map not readable error but was {}",
+            runtime_error
+        ),
+    }
+}
+
+#[test]
+fn lazy_map_key_and_value_types_are_exported_in_abi() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "lazy_map")))
+        .unwrap();
+
+    // Act
+    let abi = executor.export_abi(package, "LazyMapTest").unwrap();
+
+    // Assert
+    let fields = match abi.state {
+        Some(Type::Struct {
+            fields: Fields::Named { named },
+            ..
+        }) => named,
+        _ => panic!("Should be a struct with named fields"),
+    };
+    assert_eq!(fields[0].0, "map");
+    assert_eq!(
+        lazy_map_entry_types(&fields[0].1),
+        Some((&Type::String, &Type::String))
+    );
+}
+
+#[test]
+fn bucket_in_lazy_map_entry_should_fail() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "lazy_map")))
+        .unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(package, "LazyMapTest", "put_bucket_into_lazy_map", args![])
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
+    assert!(matches!(
+        runtime_error,
+        RuntimeError::TransientValuePersisted(TransientValue::Bucket(..))
+    ));
+}
+
+#[test]
+fn proof_in_lazy_map_key_should_fail() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "lazy_map")))
+        .unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(package, "LazyMapTest", "put_proof_as_lazy_map_key", args![])
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
+    assert!(matches!(
+        runtime_error,
+        RuntimeError::TransientValuePersisted(TransientValue::Proof(..))
+    ));
+}
+
+#[test]
+fn batched_lazy_map_reads_should_return_entries_in_order() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "lazy_map")))
+        .unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(
+            package,
+            "LazyMapTest",
+            "new_lazy_map_with_batched_get",
+            args![],
+        )
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay");
+}
@@ -14,6 +14,24 @@ blueprint! {
             map.get(&"hello".to_owned())
         }
 
+        pub fn new_lazy_map_with_batched_get() -> ComponentAddress {
+            let map = LazyMap::new();
+            map.insert("hello".to_owned(), "world".to_owned());
+            map.insert("foo".to_owned(), "bar".to_owned());
+            let values = map.get_many(&["foo".to_owned(), "none".to_owned(), "hello".to_owned()]);
+            assert_eq!(
+                values,
+                vec![Some("bar".to_owned()), None, Some("world".to_owned())]
+            );
+            LazyMapTest {
+                map,
+                vector: Vec::new(),
+                lazy_maps: LazyMap::new(),
+            }
+            .instantiate()
+            .globalize()
+        }
+
         pub fn new_lazy_map_into_vector() -> ComponentAddress {
             let map = LazyMap::new();
             map.get(&"hello".to_owned());
@@ -91,5 +109,17 @@ blueprint! {
         pub fn clear_vector(&mut self) -> () {
             self.vector.clear()
         }
+
+        pub fn put_bucket_into_lazy_map() {
+            let bucket = ResourceBuilder::new_fungible().initial_supply(1);
+            let map = LazyMap::new();
+            map.insert("bucket".to_owned(), bucket);
+        }
+
+        pub fn put_proof_as_lazy_map_key() {
+            let bucket = ResourceBuilder::new_fungible().initial_supply(1);
+            let map = LazyMap::new();
+            map.insert(bucket.create_proof(), ());
+        }
     }
 }
@@ -1,3 +1,4 @@
 pub mod cyclic_map;
 pub mod lazy_map;
+pub mod readable_lazy_map;
 pub mod super_lazy_map;
@@ -0,0 +1,44 @@
+use scrypto::engine::types::LazyMapId;
+use scrypto::prelude::*;
+
+blueprint! {
+    struct ReadableLazyMap {
+        entries: LazyMap<String, String>,
+        private_entries: LazyMap<String, String>,
+    }
+
+    impl ReadableLazyMap {
+        pub fn new() -> ComponentAddress {
+            let entries = LazyMap::new_readable();
+            entries.insert("hello".to_owned(), "world".to_owned());
+            let private_entries = LazyMap::new();
+            private_entries.insert("hello".to_owned(), "secret".to_owned());
+            ReadableLazyMap {
+                entries,
+                private_entries,
+            }
+            .instantiate()
+            .globalize()
+        }
+
+        pub fn entries_id(&self) -> LazyMapId {
+            self.entries.id
+        }
+
+        pub fn private_entries_id(&self) -> LazyMapId {
+            self.private_entries.id
+        }
+
+        pub fn read_entry(registry: ComponentAddress, key: String) -> Option<String> {
+            let component = borrow_component!(registry);
+            let lazy_map_id: LazyMapId = component.call("entries_id", args![]);
+            component.get_lazy_map_entry(lazy_map_id, &key)
+        }
+
+        pub fn read_private_entry(registry: ComponentAddress, key: String) -> Option<String> {
+            let component = borrow_component!(registry);
+            let lazy_map_id: LazyMapId = component.call("private_entries_id", args![]);
+            component.get_lazy_map_entry(lazy_map_id, &key)
+        }
+    }
+}
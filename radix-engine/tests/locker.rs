@@ -0,0 +1,302 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::engine::EntityAddress;
+use radix_engine::errors::RuntimeError;
+use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::model::{LockerError, Receipt};
+use scrypto::engine::types::LockerId;
+use scrypto::prelude::*;
+
+fn create_locker(
+    test_runner: &mut TestRunner,
+    pk: EcdsaPublicKey,
+    sk: &EcdsaPrivateKey,
+    account: ComponentAddress,
+    token: ResourceAddress,
+    badge: ResourceAddress,
+) -> LockerId {
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_amount(Decimal::from(100), token, account)
+        .take_from_worktop(token, |builder, bucket_id| {
+            builder.create_locker(account, bucket_id, rule!(require(badge)))
+        })
+        .build(test_runner.get_nonce([pk]))
+        .sign([sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay.");
+    let locker_id = scrypto_decode(&receipt.outputs[2].raw).unwrap();
+    assert!(receipt.new_owned_entities.iter().any(|entity| {
+        entity.address == EntityAddress::Locker(account, locker_id)
+            && entity.instruction_index == Some(2)
+    }));
+    locker_id
+}
+
+fn claim_locker(
+    test_runner: &mut TestRunner,
+    pk: EcdsaPublicKey,
+    sk: &EcdsaPrivateKey,
+    account: ComponentAddress,
+    badge: Option<ResourceAddress>,
+    owner: ComponentAddress,
+    locker_id: LockerId,
+) -> Receipt {
+    let mut builder = test_runner.new_transaction_builder();
+    if let Some(badge) = badge {
+        builder.create_proof_from_account(badge, account);
+    }
+    let transaction = builder
+        .claim_locker(owner, locker_id)
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([sk]);
+    test_runner.validate_and_execute(&transaction)
+}
+
+#[test]
+fn can_claim_locker_with_badge() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (recipient_pk, recipient_sk, recipient) = test_runner.new_account();
+    let token = test_runner.create_fungible_resource(Decimal::from(1000), 18, account);
+    let badge = test_runner.create_fungible_resource(Decimal::one(), 0, recipient);
+    let locker_id = create_locker(&mut test_runner, pk, &sk, account, token, badge);
+
+    // Act
+    let receipt = claim_locker(
+        &mut test_runner,
+        recipient_pk,
+        &recipient_sk,
+        recipient,
+        Some(badge),
+        account,
+        locker_id,
+    );
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+    assert_eq!(
+        test_runner.get_component_resources(recipient).get(&token),
+        Some(&Decimal::from(100))
+    );
+    assert_eq!(
+        test_runner.get_component_resources(account).get(&token),
+        Some(&Decimal::from(900))
+    );
+}
+
+#[test]
+fn cannot_claim_locker_without_badge() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (_, _, recipient) = test_runner.new_account();
+    let (other_pk, other_sk, other_account) = test_runner.new_account();
+    let token = test_runner.create_fungible_resource(Decimal::from(1000), 18, account);
+    let badge = test_runner.create_fungible_resource(Decimal::one(), 0, recipient);
+    let locker_id = create_locker(&mut test_runner, pk, &sk, account, token, badge);
+
+    // Act
+    let receipt = claim_locker(
+        &mut test_runner,
+        other_pk,
+        &other_sk,
+        other_account,
+        None,
+        account,
+        locker_id,
+    );
+
+    // Assert
+    let err = receipt.result.expect_err("Should be a runtime error").cause;
+    assert_auth_error!(err);
+}
+
+#[test]
+fn locker_is_deleted_after_claim() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (recipient_pk, recipient_sk, recipient) = test_runner.new_account();
+    let token = test_runner.create_fungible_resource(Decimal::from(1000), 18, account);
+    let badge = test_runner.create_fungible_resource(Decimal::one(), 0, recipient);
+    let locker_id = create_locker(&mut test_runner, pk, &sk, account, token, badge);
+    claim_locker(
+        &mut test_runner,
+        recipient_pk,
+        &recipient_sk,
+        recipient,
+        Some(badge),
+        account,
+        locker_id,
+    )
+    .result
+    .expect("Should be okay.");
+
+    // Act
+    let receipt = claim_locker(
+        &mut test_runner,
+        recipient_pk,
+        &recipient_sk,
+        recipient,
+        Some(badge),
+        account,
+        locker_id,
+    );
+
+    // Assert
+    let err = receipt.result.expect_err("Should be a runtime error").cause;
+    assert_eq!(err, RuntimeError::LockerNotFound(locker_id));
+    assert_eq!(
+        test_runner.get_component_resources(recipient).get(&token),
+        Some(&Decimal::from(100))
+    );
+}
+
+#[test]
+fn cannot_claim_non_existent_locker() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let locker_id = (hash("locker"), 1024);
+
+    // Act
+    let receipt = claim_locker(&mut test_runner, pk, &sk, account, None, account, locker_id);
+
+    // Assert
+    let err = receipt.result.expect_err("Should be a runtime error").cause;
+    assert_eq!(err, RuntimeError::LockerNotFound(locker_id));
+}
+
+#[test]
+fn cannot_claim_locker_of_another_component() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (recipient_pk, recipient_sk, recipient) = test_runner.new_account();
+    let token = test_runner.create_fungible_resource(Decimal::from(1000), 18, account);
+    let badge = test_runner.create_fungible_resource(Decimal::one(), 0, recipient);
+    let locker_id = create_locker(&mut test_runner, pk, &sk, account, token, badge);
+
+    // Act
+    let receipt = claim_locker(
+        &mut test_runner,
+        recipient_pk,
+        &recipient_sk,
+        recipient,
+        Some(badge),
+        recipient,
+        locker_id,
+    );
+
+    // Assert
+    let err = receipt.result.expect_err("Should be a runtime error").cause;
+    assert_eq!(err, RuntimeError::LockerNotFound(locker_id));
+}
+
+#[test]
+fn cannot_create_locker_in_non_existent_component() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let token = test_runner.create_fungible_resource(Decimal::from(1000), 18, account);
+    let owner = ComponentAddress([0u8; 26]);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_amount(Decimal::from(100), token, account)
+        .take_from_worktop(token, |builder, bucket_id| {
+            builder.create_locker(owner, bucket_id, rule!(allow_all))
+        })
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let err = receipt.result.expect_err("Should be a runtime error").cause;
+    assert_eq!(
+        err,
+        RuntimeError::LockerError(LockerError::ComponentNotFound(owner))
+    );
+}
+
+#[test]
+fn cannot_create_locker_in_component_of_another_owner() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (_, _, other_account) = test_runner.new_account();
+    let token = test_runner.create_fungible_resource(Decimal::from(1000), 18, account);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_amount(Decimal::from(100), token, account)
+        .take_from_worktop(token, |builder, bucket_id| {
+            builder.create_locker(other_account, bucket_id, rule!(allow_all))
+        })
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let err = receipt.result.expect_err("Should be a runtime error").cause;
+    assert_auth_error!(err);
+}
+
+#[test]
+fn cannot_claim_restricted_transfer_locker_without_withdraw_auth() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (recipient_pk, recipient_sk, recipient) = test_runner.new_account();
+    let (auth, token) = test_runner.create_restricted_transfer_token(account);
+    let auth_ids = BTreeSet::from([NonFungibleId::from_u32(1)]);
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_ids(&auth_ids, auth, account)
+        .take_from_worktop_by_ids(&auth_ids, auth, |builder, bucket_id| {
+            builder.create_proof_from_bucket(bucket_id, |builder, proof_id| {
+                builder.push_to_auth_zone(proof_id)
+            })
+        })
+        .withdraw_from_account_by_amount(Decimal::one(), token, account)
+        .pop_from_auth_zone(|builder, proof_id| builder.drop_proof(proof_id))
+        .take_from_worktop(token, |builder, bucket_id| {
+            builder.create_locker(account, bucket_id, rule!(allow_all))
+        })
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay.");
+    let locker_id: LockerId = scrypto_decode(&receipt.outputs[8].raw).unwrap();
+
+    // Act
+    let receipt = claim_locker(
+        &mut test_runner,
+        recipient_pk,
+        &recipient_sk,
+        recipient,
+        None,
+        account,
+        locker_id,
+    );
+
+    // Assert
+    let err = receipt.result.expect_err("Should be a runtime error").cause;
+    assert_auth_error!(err);
+}
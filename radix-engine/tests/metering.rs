@@ -0,0 +1,116 @@
+use radix_engine::errors::RuntimeError;
+use radix_engine::ledger::*;
+use radix_engine::model::Receipt;
+use radix_engine::transaction::*;
+use scrypto::prelude::*;
+
+fn emit_logs(
+    executor: &mut TransactionExecutor<InMemorySubstateStore>,
+    count: u32,
+    size: u32,
+) -> Receipt {
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
+    let transaction = TransactionBuilder::new()
+        .call_function(package, "LoggerTest", "emit_logs", args![count, size])
+        .build(executor.get_nonce([]))
+        .sign([]);
+    executor.validate_and_execute(&transaction).unwrap()
+}
+
+#[test]
+fn emitting_logs_within_limits_should_succeed() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true).with_log_limits(10, 1000);
+
+    // Act
+    let receipt = emit_logs(&mut executor, 10, 100);
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+    assert_eq!(receipt.logs.len(), 10);
+}
+
+#[test]
+fn emitting_too_many_logs_should_fail() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true).with_log_limits(10, 1000);
+
+    // Act
+    let receipt = emit_logs(&mut executor, 11, 1);
+
+    // Assert
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
+    assert_eq!(
+        runtime_error,
+        RuntimeError::LogLimitExceeded {
+            max_logs: 10,
+            max_log_bytes: 1000
+        }
+    );
+}
+
+#[test]
+fn emitting_too_many_log_bytes_should_fail() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true).with_log_limits(10, 1000);
+
+    // Act
+    let receipt = emit_logs(&mut executor, 2, 501);
+
+    // Assert
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
+    assert_eq!(
+        runtime_error,
+        RuntimeError::LogLimitExceeded {
+            max_logs: 10,
+            max_log_bytes: 1000
+        }
+    );
+}
+
+#[test]
+fn emitting_too_many_logs_in_read_only_call_should_fail() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true).with_log_limits(10, 1000);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "core")))
+        .unwrap();
+    let transaction = TransactionBuilder::new()
+        .call_function(package, "LoggerTest", "new", args![])
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+    let component = receipt.new_component_addresses[0];
+
+    // Act
+    let result = executor.call_read_only(component, "log", args![11u32, 1u32]);
+
+    // Assert
+    assert_eq!(
+        result,
+        Err(RuntimeError::LogLimitExceeded {
+            max_logs: 10,
+            max_log_bytes: 1000
+        })
+    );
+}
+
+#[test]
+fn emitting_logs_should_consume_cost_units_per_byte() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+
+    // Act
+    let receipt = emit_logs(&mut executor, 3, 100);
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+    assert_eq!(receipt.cost_unit_breakdown.get("emit_log"), Some(&3000));
+}
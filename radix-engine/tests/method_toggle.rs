@@ -0,0 +1,214 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::errors::RuntimeError;
+use radix_engine::ledger::InMemorySubstateStore;
+use scrypto::prelude::*;
+
+fn create_component(
+    test_runner: &mut TestRunner,
+    owner_badge: ResourceAddress,
+) -> ComponentAddress {
+    let package_address = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package_address,
+            "PausableComponent",
+            "create_component",
+            args![owner_badge],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    receipt.new_component_addresses[0]
+}
+
+#[test]
+fn owner_can_disable_and_enable_method() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (key, sk, account) = test_runner.new_account();
+    let owner_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let component = create_component(&mut test_runner, owner_badge);
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(owner_badge, account)
+        .call_method(
+            component,
+            "set_enabled",
+            args!["increment".to_string(), false],
+        )
+        .build(test_runner.get_nonce([key]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(component, "increment", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt_disabled = test_runner.validate_and_execute(&transaction);
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(owner_badge, account)
+        .call_method(
+            component,
+            "set_enabled",
+            args!["increment".to_string(), true],
+        )
+        .call_method(component, "increment", args![])
+        .build(test_runner.get_nonce([key]))
+        .sign([&sk]);
+    let receipt_enabled = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt_disabled.result.expect_err("Should be error").cause;
+    assert_eq!(
+        error,
+        RuntimeError::MethodDisabled {
+            component_address: component,
+            method: "increment".to_string(),
+        }
+    );
+    receipt_enabled.result.expect("Should be okay");
+    let count: u32 = scrypto_decode(&receipt_enabled.outputs[2].raw).unwrap();
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn cannot_disable_method_without_owner_badge() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (_, _, account) = test_runner.new_account();
+    let owner_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let component = create_component(&mut test_runner, owner_badge);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(
+            component,
+            "set_enabled",
+            args!["increment".to_string(), false],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert_auth_error!(error);
+}
+
+#[test]
+fn cannot_disable_method_not_declared_by_blueprint() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (key, sk, account) = test_runner.new_account();
+    let owner_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let component = create_component(&mut test_runner, owner_badge);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(owner_badge, account)
+        .call_method(
+            component,
+            "set_enabled",
+            args!["incremnet".to_string(), false],
+        )
+        .build(test_runner.get_nonce([key]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert_eq!(
+        error,
+        RuntimeError::MethodNotDeclared {
+            blueprint_name: "PausableComponent".to_string(),
+            method: "incremnet".to_string(),
+        }
+    );
+}
+
+#[test]
+fn owner_can_disable_method_without_blueprint_code() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (key, sk, account) = test_runner.new_account();
+    let owner_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let component = create_component(&mut test_runner, owner_badge);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(owner_badge, account)
+        .call_function(
+            SYSTEM_PACKAGE,
+            "System",
+            "set_method_enabled",
+            args![component, "set_enabled".to_string(), false],
+        )
+        .build(test_runner.get_nonce([key]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(owner_badge, account)
+        .call_method(
+            component,
+            "set_enabled",
+            args!["increment".to_string(), false],
+        )
+        .build(test_runner.get_nonce([key]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert_eq!(
+        error,
+        RuntimeError::MethodDisabled {
+            component_address: component,
+            method: "set_enabled".to_string(),
+        }
+    );
+}
+
+#[test]
+fn cannot_disable_method_without_blueprint_code_and_owner_badge() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (_, _, account) = test_runner.new_account();
+    let owner_badge = test_runner.create_fungible_resource(1.into(), 0, account);
+    let component = create_component(&mut test_runner, owner_badge);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            SYSTEM_PACKAGE,
+            "System",
+            "set_method_enabled",
+            args![component, "increment".to_string(), false],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert_auth_error!(error);
+}
@@ -0,0 +1,29 @@
+use radix_engine::engine::InMemoryMetrics;
+use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::transaction::*;
+use scrypto::prelude::*;
+
+#[test]
+fn executed_transactions_should_be_recorded_in_metrics() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut substate_store, false)
+        .with_metrics(InMemoryMetrics::default());
+    let (_, _, account) = executor.new_account();
+    let transactions_before = executor.metrics().cost_units.count();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_method(SYSTEM_COMPONENT, "free_xrd", args![])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    let metrics = executor.metrics();
+    assert_eq!(metrics.cost_units.count(), transactions_before + 1);
+    assert!(metrics.invocations.get("Scrypto").copied().unwrap_or(0) > 0);
+    assert!(metrics.substate_writes > 0);
+}
@@ -0,0 +1,84 @@
+use radix_engine::errors::TransactionValidationError;
+use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::model::CompositeTransaction;
+use radix_engine::transaction::*;
+use scrypto::address::{LOCALNET_NETWORK_ID, MAINNET_NETWORK_ID, SIMULATOR_NETWORK_ID};
+use scrypto::core::NetworkDefinition;
+use scrypto::prelude::*;
+
+#[test]
+fn transaction_for_other_network_is_rejected() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut substate_store, true);
+    let transaction = TransactionBuilder::new_for_network(&NetworkDefinition::mainnet())
+        .call_method(SYSTEM_COMPONENT, "free_xrd", vec![])
+        .build(executor.get_nonce([]))
+        .sign([]);
+
+    // Act
+    let result = executor.validate_and_execute(&transaction);
+
+    // Assert
+    assert_eq!(
+        result.err(),
+        Some(TransactionValidationError::InvalidNetwork {
+            expected: SIMULATOR_NETWORK_ID,
+            actual: MAINNET_NETWORK_ID,
+        })
+    );
+}
+
+#[test]
+fn executor_accepts_transactions_for_its_network() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut substate_store, true)
+        .with_network(NetworkDefinition::local_network());
+
+    // Act
+    let account = executor.new_account_with_auth_rule(&rule!(allow_all));
+    let transaction = TransactionBuilder::new_for_network(&NetworkDefinition::local_network())
+        .call_method(SYSTEM_COMPONENT, "free_xrd", vec![])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction);
+    let preview = executor.preview(&TransactionBuilder::new().build(executor.get_nonce([])), []);
+
+    // Assert
+    receipt.unwrap().result.expect("Should be okay");
+    assert_eq!(
+        preview.err(),
+        Some(TransactionValidationError::InvalidNetwork {
+            expected: LOCALNET_NETWORK_ID,
+            actual: SIMULATOR_NETWORK_ID,
+        })
+    );
+}
+
+#[test]
+fn composite_transaction_parts_must_share_network() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut substate_store, true);
+    let part = TransactionBuilder::new().build(executor.get_nonce([]));
+    let other_part = TransactionBuilder::new_for_network(&NetworkDefinition::mainnet())
+        .build(executor.get_nonce([]));
+    let intent_hash = CompositeTransaction::intent_hash_of(&[&part, &other_part]);
+
+    // Act
+    let result = executor.validate_and_execute_composite(&CompositeTransaction::new(vec![
+        part.sign_part(intent_hash, []),
+        other_part.sign_part(intent_hash, []),
+    ]));
+
+    // Assert
+    assert_eq!(
+        result.err(),
+        Some(TransactionValidationError::InvalidNetwork {
+            expected: SIMULATOR_NETWORK_ID,
+            actual: MAINNET_NETWORK_ID,
+        })
+    );
+}
@@ -0,0 +1,37 @@
+use radix_engine::engine::{BufferLogSink, NoClock};
+use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::transaction::*;
+use scrypto::prelude::*;
+use scrypto::rust::rc::Rc;
+
+// These tests are also run with `--no-default-features --features alloc`, where the engine
+// has no clock or console.
+
+#[test]
+fn transfer_should_succeed_without_clock_or_console() {
+    // Arrange
+    let log_sink = Rc::new(BufferLogSink::new());
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut substate_store, true)
+        .with_clock(NoClock)
+        .with_log_sink(log_sink.clone());
+    let (pk, sk, account1) = executor.new_account();
+    let (_, _, account2) = executor.new_account();
+    log_sink.take();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .withdraw_from_account_by_amount(1.into(), RADIX_TOKEN, account1)
+        .call_method_with_all_resources(account2, "deposit_batch")
+        .build(executor.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert_eq!(receipt.execution_time, None);
+    let messages = log_sink.take();
+    assert!(messages
+        .iter()
+        .any(|(_, level, message)| *level == Level::Info && message.starts_with("Run started")));
+}
@@ -2,7 +2,9 @@
 pub mod test_runner;
 
 use crate::test_runner::TestRunner;
+use radix_engine::errors::RuntimeError;
 use radix_engine::ledger::*;
+use radix_engine::model::ResourceManagerError;
 use radix_engine::transaction::*;
 use scrypto::prelude::*;
 
@@ -32,6 +34,49 @@ fn create_non_fungible_mutable() {
     assert!(receipt.result.is_ok());
 }
 
+#[test]
+fn can_track_non_fungible_location() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap().with_non_fungible_index();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let (_, _, other_account) = test_runner.new_account();
+    let resource_address = test_runner.create_non_fungible_resource(account);
+    let non_fungible_address =
+        NonFungibleAddress::new(resource_address, NonFungibleId::from_u32(1));
+    let location = test_runner
+        .get_non_fungible_location(&non_fungible_address)
+        .expect("Should be indexed");
+    assert_eq!(location.component_address, account);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_ids(
+            &BTreeSet::from([NonFungibleId::from_u32(1)]),
+            resource_address,
+            account,
+        )
+        .call_method_with_all_resources(other_account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+    let location = test_runner
+        .get_non_fungible_location(&non_fungible_address)
+        .expect("Should be indexed");
+    assert_eq!(location.component_address, other_account);
+    let other_location = test_runner
+        .get_non_fungible_location(&NonFungibleAddress::new(
+            resource_address,
+            NonFungibleId::from_u32(2),
+        ))
+        .expect("Should be indexed");
+    assert_eq!(other_location.component_address, account);
+}
+
 #[test]
 fn can_burn_non_fungible() {
     // Arrange
@@ -141,3 +186,86 @@ fn test_singleton_non_fungible() {
     println!("{:?}", receipt);
     assert!(receipt.result.is_ok());
 }
+
+#[test]
+fn can_update_non_fungible_metadata() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (_, _, account) = test_runner.new_account();
+    let package = test_runner.publish_package("non_fungible");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package,
+            "NonFungibleTest",
+            "update_and_get_non_fungible_metadata",
+            args![],
+        )
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+}
+
+#[test]
+fn cannot_update_non_fungible_metadata_without_auth() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (_, _, account) = test_runner.new_account();
+    let package = test_runner.publish_package("non_fungible");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package,
+            "NonFungibleTest",
+            "update_non_fungible_metadata_without_auth",
+            args![],
+        )
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be runtime error").cause;
+    assert_auth_error!(error);
+}
+
+#[test]
+fn cannot_update_non_fungible_metadata_with_too_many_entries() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (_, _, account) = test_runner.new_account();
+    let package = test_runner.publish_package("non_fungible");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package,
+            "NonFungibleTest",
+            "update_non_fungible_metadata_with_too_many_entries",
+            args![],
+        )
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be runtime error").cause;
+    assert_eq!(
+        error,
+        RuntimeError::ResourceManagerError(ResourceManagerError::NonFungibleMetadataTooLarge)
+    );
+}
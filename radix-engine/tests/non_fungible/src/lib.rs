@@ -25,6 +25,10 @@ blueprint! {
                 .mintable(rule!(require(mint_badge.resource_address())), LOCKED)
                 .burnable(rule!(allow_all), LOCKED)
                 .updateable_non_fungible_data(rule!(require(mint_badge.resource_address())), LOCKED)
+                .updateable_non_fungible_metadata(
+                    rule!(require(mint_badge.resource_address())),
+                    LOCKED,
+                )
                 .no_initial_supply();
 
             // Mint a non-fungible
@@ -117,6 +121,50 @@ blueprint! {
             (mint_badge, bucket)
         }
 
+        pub fn update_and_get_non_fungible_metadata() -> (Bucket, Bucket) {
+            let (mint_badge, resource_address, bucket) = Self::create_non_fungible_mutable();
+            let non_fungible: NonFungible<Sandwich> = NonFungible::from(NonFungibleAddress::new(
+                resource_address,
+                NonFungibleId::from_u32(0),
+            ));
+            assert!(non_fungible.metadata().is_empty());
+
+            let metadata = BTreeMap::from([
+                ("name".to_owned(), MetadataValue::from("Reuben")),
+                (
+                    "image_url".to_owned(),
+                    MetadataValue::Url("https://example.com/reuben.png".to_owned()),
+                ),
+            ]);
+            mint_badge.authorize(|| non_fungible.update_metadata(metadata.clone()));
+
+            assert_eq!(non_fungible.metadata(), metadata);
+            let data: Sandwich = non_fungible.data();
+            assert_eq!(data.name, "Test");
+            (mint_badge, bucket)
+        }
+
+        pub fn update_non_fungible_metadata_without_auth() -> (Bucket, Bucket) {
+            let (mint_badge, resource_address, bucket) = Self::create_non_fungible_mutable();
+            borrow_resource_manager!(resource_address).update_non_fungible_metadata(
+                &NonFungibleId::from_u32(0),
+                BTreeMap::from([("name".to_owned(), MetadataValue::from("Reuben"))]),
+            );
+            (mint_badge, bucket)
+        }
+
+        pub fn update_non_fungible_metadata_with_too_many_entries() -> (Bucket, Bucket) {
+            let (mint_badge, resource_address, bucket) = Self::create_non_fungible_mutable();
+            let metadata = (0..17)
+                .map(|i| (i.to_string(), MetadataValue::from("value")))
+                .collect();
+            mint_badge.authorize(|| {
+                borrow_resource_manager!(resource_address)
+                    .update_non_fungible_metadata(&NonFungibleId::from_u32(0), metadata)
+            });
+            (mint_badge, bucket)
+        }
+
         pub fn non_fungible_exists() -> (Bucket, Bucket) {
             let (mint_badge, resource_address, bucket) = Self::create_non_fungible_mutable();
             assert_eq!(
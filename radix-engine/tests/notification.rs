@@ -0,0 +1,393 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::errors::RuntimeError;
+use radix_engine::fee::FeeTable;
+use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::model::{MAX_EVENT_OBSERVERS, MAX_PENDING_NOTIFICATIONS};
+use scrypto::prelude::*;
+use scrypto::values::ScryptoValue;
+
+fn instantiate(
+    test_runner: &mut TestRunner,
+    package: PackageAddress,
+    blueprint: &str,
+) -> ComponentAddress {
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package, blueprint, "new", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay.");
+    receipt.new_component_addresses[0]
+}
+
+fn call(
+    test_runner: &mut TestRunner,
+    component: ComponentAddress,
+    method: &str,
+    args: Vec<Vec<u8>>,
+) -> Result<ScryptoValue, RuntimeError> {
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(component, method, args)
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt
+        .result
+        .map(|_| receipt.outputs[0].clone())
+        .map_err(|e| e.cause)
+}
+
+fn allow_subscriptions(test_runner: &mut TestRunner, emitter: ComponentAddress, event: &str) {
+    call(
+        test_runner,
+        emitter,
+        "allow_subscriptions",
+        args![vec![event.to_string()]],
+    )
+    .unwrap();
+}
+
+fn take_values(
+    test_runner: &mut TestRunner,
+    observer: ComponentAddress,
+) -> Vec<(ComponentAddress, String, u32)> {
+    let output = call(test_runner, observer, "take_values", args![]).unwrap();
+    scrypto_decode(&output.raw).unwrap()
+}
+
+#[test]
+fn observer_is_notified_of_subscribed_events() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let emitter = instantiate(&mut test_runner, package, "EventEmitter");
+    let observer = instantiate(&mut test_runner, package, "EventObserver");
+    allow_subscriptions(&mut test_runner, emitter, "Ping");
+    call(
+        &mut test_runner,
+        observer,
+        "observe",
+        args![emitter, "Ping".to_string()],
+    )
+    .unwrap();
+
+    // Act
+    call(
+        &mut test_runner,
+        emitter,
+        "emit",
+        args!["Ping".to_string(), 1u32],
+    )
+    .unwrap();
+    call(
+        &mut test_runner,
+        emitter,
+        "emit",
+        args!["Pong".to_string(), 2u32],
+    )
+    .unwrap();
+    call(
+        &mut test_runner,
+        emitter,
+        "emit",
+        args!["Ping".to_string(), 3u32],
+    )
+    .unwrap();
+
+    // Assert
+    assert_eq!(
+        take_values(&mut test_runner, observer),
+        vec![
+            (emitter, "Ping".to_string(), 1u32),
+            (emitter, "Ping".to_string(), 3u32)
+        ]
+    );
+    assert_eq!(take_values(&mut test_runner, observer), vec![]);
+}
+
+#[test]
+fn observer_is_not_notified_after_unsubscribing() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let emitter = instantiate(&mut test_runner, package, "EventEmitter");
+    let observer = instantiate(&mut test_runner, package, "EventObserver");
+    allow_subscriptions(&mut test_runner, emitter, "Ping");
+    call(
+        &mut test_runner,
+        observer,
+        "observe",
+        args![emitter, "Ping".to_string()],
+    )
+    .unwrap();
+    call(
+        &mut test_runner,
+        observer,
+        "stop_observing",
+        args![emitter, "Ping".to_string()],
+    )
+    .unwrap();
+
+    // Act
+    call(
+        &mut test_runner,
+        emitter,
+        "emit",
+        args!["Ping".to_string(), 1u32],
+    )
+    .unwrap();
+
+    // Assert
+    assert_eq!(take_values(&mut test_runner, observer), vec![]);
+}
+
+#[test]
+fn emitted_events_are_recorded_in_receipt() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let emitter = instantiate(&mut test_runner, package, "EventEmitter");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(emitter, "emit", args!["Ping".to_string(), 1u32])
+        .call_method(emitter, "emit", args!["Pong".to_string(), 2u32])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+    assert_eq!(
+        receipt.events,
+        vec![
+            Notification {
+                emitter,
+                event: "Ping".to_string(),
+                data: scrypto_encode(&1u32),
+            },
+            Notification {
+                emitter,
+                event: "Pong".to_string(),
+                data: scrypto_encode(&2u32),
+            },
+        ]
+    );
+}
+
+#[test]
+fn notifications_are_discarded_when_emitting_transaction_fails() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let emitter = instantiate(&mut test_runner, package, "EventEmitter");
+    let observer = instantiate(&mut test_runner, package, "EventObserver");
+    allow_subscriptions(&mut test_runner, emitter, "Ping");
+    call(
+        &mut test_runner,
+        observer,
+        "observe",
+        args![emitter, "Ping".to_string()],
+    )
+    .unwrap();
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(emitter, "emit", args!["Ping".to_string(), 1u32])
+        .assert_worktop_contains_by_amount(Decimal::one(), RADIX_TOKEN)
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    assert!(receipt.result.is_err());
+    assert_eq!(take_values(&mut test_runner, observer), vec![]);
+}
+
+#[test]
+fn cannot_subscribe_to_non_existent_component() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let observer = instantiate(&mut test_runner, package, "EventObserver");
+    let emitter = ComponentAddress([2u8; 26]);
+
+    // Act
+    let result = call(
+        &mut test_runner,
+        observer,
+        "observe",
+        args![emitter, "Ping".to_string()],
+    );
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        RuntimeError::ComponentNotFound(emitter)
+    );
+}
+
+#[test]
+fn cannot_subscribe_without_emitter_consent() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let emitter = instantiate(&mut test_runner, package, "EventEmitter");
+    let observer = instantiate(&mut test_runner, package, "EventObserver");
+    allow_subscriptions(&mut test_runner, emitter, "Pong");
+
+    // Act
+    let result = call(
+        &mut test_runner,
+        observer,
+        "observe",
+        args![emitter, "Ping".to_string()],
+    );
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        RuntimeError::EventNotSubscribable {
+            emitter,
+            event: "Ping".to_string()
+        }
+    );
+}
+
+#[test]
+fn observer_is_not_notified_after_emitter_disallows_subscriptions() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let emitter = instantiate(&mut test_runner, package, "EventEmitter");
+    let observer = instantiate(&mut test_runner, package, "EventObserver");
+    allow_subscriptions(&mut test_runner, emitter, "Ping");
+    call(
+        &mut test_runner,
+        observer,
+        "observe",
+        args![emitter, "Ping".to_string()],
+    )
+    .unwrap();
+    call(
+        &mut test_runner,
+        emitter,
+        "disallow_subscriptions",
+        args!["Ping".to_string()],
+    )
+    .unwrap();
+
+    // Act
+    call(
+        &mut test_runner,
+        emitter,
+        "emit",
+        args!["Ping".to_string(), 1u32],
+    )
+    .unwrap();
+
+    // Assert
+    assert_eq!(take_values(&mut test_runner, observer), vec![]);
+}
+
+#[test]
+fn cannot_exceed_max_event_observers() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let emitter = instantiate(&mut test_runner, package, "EventEmitter");
+    let observer = instantiate(&mut test_runner, package, "EventObserver");
+    let events: Vec<String> = (0..=MAX_EVENT_OBSERVERS)
+        .map(|i| format!("Event{}", i))
+        .collect();
+    call(
+        &mut test_runner,
+        emitter,
+        "allow_subscriptions",
+        args![events.clone()],
+    )
+    .unwrap();
+    call(
+        &mut test_runner,
+        observer,
+        "observe_all",
+        args![emitter, events[..MAX_EVENT_OBSERVERS].to_vec()],
+    )
+    .unwrap();
+
+    // Act
+    let result = call(
+        &mut test_runner,
+        observer,
+        "observe",
+        args![emitter, events[MAX_EVENT_OBSERVERS].clone()],
+    );
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        RuntimeError::TooManyEventObservers(emitter)
+    );
+}
+
+#[test]
+fn notifications_beyond_max_pending_are_dropped_and_not_charged() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let emitter = instantiate(&mut test_runner, package, "EventEmitter");
+    let observer = instantiate(&mut test_runner, package, "EventObserver");
+    allow_subscriptions(&mut test_runner, emitter, "Ping");
+    call(
+        &mut test_runner,
+        observer,
+        "observe",
+        args![emitter, "Ping".to_string()],
+    )
+    .unwrap();
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(
+            emitter,
+            "emit_many",
+            args!["Ping".to_string(), MAX_PENDING_NOTIFICATIONS as u32 + 1],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert_eq!(
+        receipt.cost_unit_breakdown["deliver_notification"],
+        MAX_PENDING_NOTIFICATIONS as u32 * FeeTable::new().deliver_notification()
+    );
+    let values = take_values(&mut test_runner, observer);
+    assert_eq!(values.len(), MAX_PENDING_NOTIFICATIONS);
+    assert_eq!(
+        values.last(),
+        Some(&(
+            emitter,
+            "Ping".to_string(),
+            MAX_PENDING_NOTIFICATIONS as u32 - 1
+        ))
+    );
+}
@@ -3,8 +3,9 @@ pub mod test_runner;
 
 use crate::test_runner::TestRunner;
 use radix_engine::errors::RuntimeError;
+use radix_engine::errors::WasmValidationError;
 use radix_engine::errors::WasmValidationError::NoValidMemoryExport;
-use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::ledger::{InMemorySubstateStore, SubstateStore};
 use scrypto::prelude::*;
 use radix_engine::model::PackageError;
 
@@ -33,7 +34,7 @@ fn missing_memory_should_cause_error() {
     let receipt = test_runner.validate_and_execute(&transaction);
 
     // Assert
-    let error = receipt.result.expect_err("Should be error.");
+    let error = receipt.result.expect_err("Should be error.").cause;
     assert_eq!(
         error,
         RuntimeError::PackageError(PackageError::WasmValidationError(NoValidMemoryExport))
@@ -56,7 +57,7 @@ fn large_return_len_should_cause_memory_access_error() {
     let receipt = test_runner.validate_and_execute(&transaction);
 
     // Assert
-    let error = receipt.result.expect_err("Should be an error.");
+    let error = receipt.result.expect_err("Should be an error.").cause;
     assert_eq!(error, RuntimeError::MemoryAccessError);
 }
 
@@ -76,7 +77,7 @@ fn overflow_return_len_should_cause_memory_access_error() {
     let receipt = test_runner.validate_and_execute(&transaction);
 
     // Assert
-    let error = receipt.result.expect_err("Should be an error.");
+    let error = receipt.result.expect_err("Should be an error.").cause;
     assert_eq!(error, RuntimeError::MemoryAccessError);
 }
 
@@ -96,8 +97,174 @@ fn zero_return_len_should_cause_data_validation_error() {
     let receipt = test_runner.validate_and_execute(&transaction);
 
     // Assert
-    let error = receipt.result.expect_err("Should be an error.");
+    let error = receipt.result.expect_err("Should be an error.").cause;
     if !matches!(error, RuntimeError::ParseScryptoValueError(_)) {
         panic!("{} should be data validation error", error);
     }
 }
+
+#[test]
+fn scrypto_tests_should_be_run_by_engine() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("core");
+
+    // Act
+    let results = test_runner.run_package_tests(package);
+
+    // Assert
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].test_name, "test_decimal_addition");
+    assert_eq!(results[0].result, Ok(()));
+    assert_eq!(results[1].test_name, "test_failing_assertion");
+    assert!(matches!(
+        &results[1].result,
+        Err(RuntimeError::Panic { message, .. }) if message == "UUID should not be zero"
+    ));
+}
+
+/// Publishes a package from wat, returning the wasm validation error.
+fn publish_wat_should_fail(wat: &str) -> WasmValidationError {
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let code: Vec<u8> = wabt::wat2wasm(wat).expect("failed to parse wat");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .publish_package(&code)
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    match receipt.result.expect_err("Should be error.").cause {
+        RuntimeError::PackageError(PackageError::WasmValidationError(e)) => e,
+        e => panic!("{} should be wasm validation error", e),
+    }
+}
+
+#[test]
+fn import_other_than_engine_function_should_cause_error() {
+    // Act
+    let error = publish_wat_should_fail(
+        r#"
+            (module
+                (import "env" "abort" (func $abort))
+                (memory (export "memory") 1)
+            )
+            "#,
+    );
+
+    // Assert
+    assert_eq!(
+        error,
+        WasmValidationError::ImportNotAllowed {
+            module: "env".to_string(),
+            name: "abort".to_string()
+        }
+    );
+}
+
+#[test]
+fn export_name_which_is_not_identifier_should_cause_error() {
+    // Act
+    let error = publish_wat_should_fail(
+        r#"
+            (module
+                (memory (export "memory") 1)
+                (func (export "Test-main"))
+            )
+            "#,
+    );
+
+    // Assert
+    assert_eq!(
+        error,
+        WasmValidationError::InvalidExportName("Test-main".to_string())
+    );
+}
+
+#[test]
+fn too_many_functions_should_cause_error() {
+    // Act
+    let error = publish_wat_should_fail(&format!(
+        "(module (memory (export \"memory\") 1) {})",
+        "(func)".repeat(16 * 1024 + 1)
+    ));
+
+    // Assert
+    assert_eq!(error, WasmValidationError::TooManyFunctions(16 * 1024 + 1));
+}
+
+#[test]
+fn too_large_function_should_cause_error() {
+    // Act
+    let error = publish_wat_should_fail(&format!(
+        "(module (memory (export \"memory\") 1) (func {}))",
+        "nop ".repeat(128 * 1024)
+    ));
+
+    // Assert
+    assert!(matches!(error, WasmValidationError::FunctionTooLarge(size) if size > 128 * 1024));
+}
+
+#[test]
+fn too_many_globals_should_cause_error() {
+    // Act
+    let error = publish_wat_should_fail(&format!(
+        "(module (memory (export \"memory\") 1) {})",
+        "(global i32 (i32.const 0))".repeat(65)
+    ));
+
+    // Assert
+    assert_eq!(error, WasmValidationError::TooManyGlobals(65));
+}
+
+#[test]
+fn table_without_maximum_should_cause_error() {
+    // Act
+    let error = publish_wat_should_fail(
+        r#"
+            (module
+                (memory (export "memory") 1)
+                (table 1 funcref)
+            )
+            "#,
+    );
+
+    // Assert
+    assert_eq!(error, WasmValidationError::TableTooLarge(u32::MAX));
+}
+
+#[test]
+fn too_many_elements_should_cause_error() {
+    // Act
+    let error = publish_wat_should_fail(&format!(
+        "(module (memory (export \"memory\") 1) (table 4096 4096 funcref) (func $f) (elem (i32.const 0) {}))",
+        "$f ".repeat(4 * 1024 + 1)
+    ));
+
+    // Assert
+    assert_eq!(error, WasmValidationError::TooManyElements(4 * 1024 + 1));
+}
+
+#[test]
+fn identical_schemas_are_stored_once_in_schema_registry() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+
+    // Act
+    let package_address = test_runner.publish_package("component");
+    let other_package_address = test_runner.publish_package("component");
+
+    // Assert
+    let package = substate_store.get_package(&package_address).unwrap();
+    let other_package = substate_store.get_package(&other_package_address).unwrap();
+    let schema_hash = package.blueprint_schema_hash("ComponentTest").unwrap();
+    assert_eq!(
+        other_package.blueprint_schema_hash("ComponentTest"),
+        Some(schema_hash)
+    );
+    let schema = substate_store.get_schema(&schema_hash).unwrap();
+    assert_eq!(hash_schema(&schema), schema_hash);
+    assert_eq!(package.load_blueprint_schema("ComponentTest"), Ok(&schema));
+}
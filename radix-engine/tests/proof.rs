@@ -4,6 +4,7 @@ pub mod test_runner;
 use crate::test_runner::TestRunner;
 use radix_engine::errors::RuntimeError;
 use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::model::AuthZoneError;
 use scrypto::prelude::*;
 
 #[test]
@@ -286,8 +287,8 @@ fn cant_move_restricted_proof() {
 
     // Assert
     assert_eq!(
-        receipt.result,
-        Err(RuntimeError::CantMoveRestrictedProof(1025))
+        receipt.result.map_err(|e| e.cause),
+        Err(RuntimeError::CantMoveRestrictedProof(327680))
     );
 }
 
@@ -499,3 +500,147 @@ fn can_create_auth_zone_proof_by_amount_from_non_fungibles() {
     // Assert
     assert!(receipt.result.is_ok());
 }
+
+#[test]
+fn can_validate_proof_against_resource_and_amount() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let resource_address =
+        test_runner.create_fungible_resource(100.into(), DIVISIBILITY_MAXIMUM, account);
+    let other_resource_address =
+        test_runner.create_fungible_resource(100.into(), DIVISIBILITY_MAXIMUM, account);
+    let package_address = test_runner.publish_package("proof");
+    let abi = test_runner.export_abi(package_address, "Receiver");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function_with_abi(
+            package_address,
+            "Receiver",
+            "assert_valid",
+            vec![
+                format!("1,{}", resource_address),
+                resource_address.to_string(),
+                "1".to_owned(),
+            ],
+            Some(account),
+            &abi,
+        )
+        .unwrap()
+        .call_function_with_abi(
+            package_address,
+            "Receiver",
+            "assert_invalid",
+            vec![
+                format!("1,{}", resource_address),
+                resource_address.to_string(),
+                "2".to_owned(),
+            ],
+            Some(account),
+            &abi,
+        )
+        .unwrap()
+        .call_function_with_abi(
+            package_address,
+            "Receiver",
+            "assert_invalid",
+            vec![
+                format!("1,{}", other_resource_address),
+                resource_address.to_string(),
+                "1".to_owned(),
+            ],
+            Some(account),
+            &abi,
+        )
+        .unwrap()
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    println!("{:?}", receipt);
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+}
+
+#[test]
+fn can_create_proofs_of_all_resources_from_auth_zone() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let resource_address1 =
+        test_runner.create_fungible_resource(100.into(), DIVISIBILITY_MAXIMUM, account);
+    let resource_address2 = test_runner.create_non_fungible_resource(account);
+    let package_address = test_runner.publish_package("proof");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account(resource_address1, account)
+        .withdraw_from_account(resource_address2, account)
+        .take_from_worktop(resource_address1, |builder, bucket_id1| {
+            builder.take_from_worktop(resource_address2, |builder, bucket_id2| {
+                builder.call_function(
+                    package_address,
+                    "BucketProof",
+                    "check_proofs_of_all_from_auth_zone",
+                    args![
+                        vec![Bucket(bucket_id1), Bucket(bucket_id2)],
+                        vec![resource_address1, resource_address2]
+                    ],
+                )
+            })
+        })
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    println!("{:?}", receipt);
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+}
+
+#[test]
+fn cant_create_proofs_of_all_resources_if_one_is_missing_from_auth_zone() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let resource_address1 =
+        test_runner.create_fungible_resource(100.into(), DIVISIBILITY_MAXIMUM, account);
+    let resource_address2 =
+        test_runner.create_fungible_resource(100.into(), DIVISIBILITY_MAXIMUM, account);
+    let package_address = test_runner.publish_package("proof");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account(resource_address1, account)
+        .take_from_worktop(resource_address1, |builder, bucket_id| {
+            builder.call_function(
+                package_address,
+                "BucketProof",
+                "check_proofs_of_all_from_auth_zone",
+                args![
+                    vec![Bucket(bucket_id)],
+                    vec![resource_address1, resource_address2]
+                ],
+            )
+        })
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    println!("{:?}", receipt);
+
+    // Assert
+    let err = receipt.result.expect_err("Should be a runtime error").cause;
+    assert!(matches!(
+        err,
+        RuntimeError::AuthZoneError(AuthZoneError::ProofError(_))
+    ));
+}
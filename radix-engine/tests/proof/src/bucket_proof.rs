@@ -24,5 +24,23 @@ blueprint! {
 
             bucket
         }
+
+        pub fn check_proofs_of_all_from_auth_zone(
+            buckets: Vec<Bucket>,
+            resource_addresses: Vec<ResourceAddress>,
+        ) -> Vec<Bucket> {
+            for bucket in &buckets {
+                ComponentAuthZone::push(bucket.create_proof());
+            }
+
+            let proof = ComponentAuthZone::create_proofs(&resource_addresses);
+            assert_eq!(proof.resource_addresses(), resource_addresses);
+            assert!(proof.check(&rule!(require_all_of(resource_addresses))));
+            proof.drop();
+            for _ in &buckets {
+                ComponentAuthZone::pop().drop();
+            }
+            buckets
+        }
     }
 }
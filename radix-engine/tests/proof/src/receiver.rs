@@ -19,5 +19,21 @@ blueprint! {
             assert_eq!(proof.non_fungible_ids(), ids);
             assert_eq!(proof.resource_address(), resource_address);
         }
+
+        pub fn assert_valid(proof: Proof, resource_address: ResourceAddress, min_amount: Decimal) {
+            let proof = proof
+                .validate(resource_address, min_amount)
+                .expect("Proof should be valid");
+            assert_eq!(proof.resource_address(), resource_address);
+            proof.drop();
+        }
+
+        pub fn assert_invalid(
+            proof: Proof,
+            resource_address: ResourceAddress,
+            min_amount: Decimal,
+        ) {
+            assert!(proof.validate(resource_address, min_amount).is_err());
+        }
     }
 }
@@ -0,0 +1,178 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::ledger::*;
+use radix_engine::model::{Locker, LockerKey, NonFungible};
+use scrypto::engine::types::LockerId;
+use scrypto::prelude::*;
+
+fn burn_non_fungible(substate_store: &mut InMemorySubstateStore) -> NonFungibleAddress {
+    let mut test_runner = TestRunner::new(substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let package = test_runner.publish_package("non_fungible");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(
+            package,
+            "NonFungibleTest",
+            "create_burnable_non_fungible",
+            vec![],
+        )
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay.");
+    let resource_address = receipt.new_resource_addresses[0];
+    let non_fungible_address =
+        NonFungibleAddress::new(resource_address, NonFungibleId::from_u32(0));
+
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account(resource_address, account)
+        .burn_non_fungible(non_fungible_address.clone())
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay.");
+
+    non_fungible_address
+}
+
+fn get_non_fungible_substate(
+    substate_store: &InMemorySubstateStore,
+    non_fungible_address: &NonFungibleAddress,
+) -> Option<Option<NonFungible>> {
+    substate_store
+        .get_decoded_child_substate(
+            &non_fungible_address.resource_address(),
+            &non_fungible_address.non_fungible_id(),
+        )
+        .map(|(non_fungible, _)| non_fungible)
+}
+
+fn empty_locker(substate_store: &mut InMemorySubstateStore) -> (ComponentAddress, LockerId) {
+    let mut test_runner = TestRunner::new(substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let transaction = test_runner
+        .new_transaction_builder()
+        .withdraw_from_account_by_amount(Decimal::from(100), RADIX_TOKEN, account)
+        .take_from_worktop(RADIX_TOKEN, |builder, bucket_id| {
+            builder.create_locker(account, bucket_id, rule!(allow_all))
+        })
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay.");
+    let locker_id: LockerId = scrypto_decode(&receipt.outputs[2].raw).unwrap();
+
+    let transaction = test_runner
+        .new_transaction_builder()
+        .claim_locker(account, locker_id)
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay.");
+
+    (account, locker_id)
+}
+
+#[test]
+fn pruning_bootstrapped_ledger_should_not_remove_anything() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+
+    // Act
+    let stats = Pruner::new(PrunerConfig::default()).prune(&mut substate_store);
+
+    // Assert
+    assert!(stats.components_scanned > 0);
+    assert!(stats.resource_managers_scanned > 0);
+    assert_eq!(stats.substates_pruned, 0);
+    assert_eq!(stats.retained_non_empty_vaults, 0);
+}
+
+#[test]
+fn can_prune_burned_non_fungible() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let non_fungible_address = burn_non_fungible(&mut substate_store);
+    assert!(matches!(
+        get_non_fungible_substate(&substate_store, &non_fungible_address),
+        Some(None)
+    ));
+
+    // Act
+    let stats = Pruner::new(PrunerConfig::default()).prune(&mut substate_store);
+
+    // Assert
+    assert_eq!(stats.burned_non_fungibles, 1);
+    assert_eq!(stats.substates_pruned, 1);
+    assert!(stats.bytes_pruned > 0);
+    assert!(get_non_fungible_substate(&substate_store, &non_fungible_address).is_none());
+    let stats = Pruner::new(PrunerConfig::default()).prune(&mut substate_store);
+    assert_eq!(stats.substates_pruned, 0);
+}
+
+#[test]
+fn dry_run_should_not_remove_substates() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let non_fungible_address = burn_non_fungible(&mut substate_store);
+    let config = PrunerConfig {
+        dry_run: true,
+        ..PrunerConfig::default()
+    };
+
+    // Act
+    let stats = Pruner::new(config).prune(&mut substate_store);
+
+    // Assert
+    assert_eq!(stats.burned_non_fungibles, 1);
+    assert_eq!(stats.substates_pruned, 1);
+    assert!(matches!(
+        get_non_fungible_substate(&substate_store, &non_fungible_address),
+        Some(None)
+    ));
+}
+
+#[test]
+fn can_retain_burned_non_fungibles() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let non_fungible_address = burn_non_fungible(&mut substate_store);
+    let config = PrunerConfig {
+        prune_burned_non_fungibles: false,
+        ..PrunerConfig::default()
+    };
+
+    // Act
+    let stats = Pruner::new(config).prune(&mut substate_store);
+
+    // Assert
+    assert_eq!(stats.burned_non_fungibles, 1);
+    assert_eq!(stats.substates_pruned, 0);
+    assert!(matches!(
+        get_non_fungible_substate(&substate_store, &non_fungible_address),
+        Some(None)
+    ));
+}
+
+#[test]
+fn should_prune_emptied_lockers() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let (account, locker_id) = empty_locker(&mut substate_store);
+
+    // Act
+    let stats = Pruner::new(PrunerConfig::default()).prune(&mut substate_store);
+
+    // Assert
+    assert_eq!(stats.emptied_lockers, 1);
+    assert!(substate_store
+        .get_decoded_child_substate::<_, _, Option<Locker>>(&account, &LockerKey(locker_id))
+        .is_none());
+}
@@ -0,0 +1,79 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::transaction::*;
+use scrypto::prelude::*;
+use scrypto::values::ScryptoValue;
+use std::thread;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn query_executor_should_be_send_and_sync() {
+    assert_send_sync::<QueryExecutor<InMemorySubstateStore>>();
+}
+
+#[test]
+fn read_only_calls_should_be_served_concurrently() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package, "ComponentTest", "create_component", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    let component = receipt.new_component_addresses[0];
+
+    // Act
+    let query_executor = test_runner.query_executor();
+    let results: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                scope.spawn(|| {
+                    query_executor.call_read_only(component, "get_component_state", args![])
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    // Assert
+    for result in results {
+        assert_eq!(result, Ok(ScryptoValue::from_value(&"Secret".to_owned())));
+    }
+}
+
+#[test]
+fn previews_should_be_served_concurrently_without_committing() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, _, account) = test_runner.new_account();
+    let nonce = test_runner.get_nonce([pk]);
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(SYSTEM_COMPONENT, "free_xrd", args![])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(nonce);
+
+    // Act
+    let query_executor = test_runner.query_executor();
+    let receipts: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..4)
+            .map(|_| scope.spawn(|| query_executor.preview(&transaction, [pk]).unwrap()))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    // Assert
+    for receipt in receipts {
+        receipt.result.expect("Should be okay");
+        assert!(receipt.commit_receipt.is_none());
+    }
+    assert_eq!(test_runner.get_nonce([pk]), nonce);
+}
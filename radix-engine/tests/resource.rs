@@ -1,6 +1,6 @@
 use radix_engine::errors::RuntimeError;
 use radix_engine::ledger::*;
-use radix_engine::model::ResourceManagerError;
+use radix_engine::model::{Instruction, ResourceManagerError};
 use radix_engine::transaction::*;
 use scrypto::prelude::*;
 
@@ -18,6 +18,7 @@ fn test_resource_manager() {
     let transaction = TransactionBuilder::new()
         .call_function(package, "ResourceTest", "create_fungible", vec![])
         .call_function(package, "ResourceTest", "query", vec![])
+        .call_function(package, "ResourceTest", "query_standard_metadata", vec![])
         .call_function(package, "ResourceTest", "burn", vec![])
         .call_function(package, "ResourceTest", "update_resource_metadata", vec![])
         .call_method_with_all_resources(account, "deposit_batch")
@@ -30,6 +31,33 @@ fn test_resource_manager() {
     assert!(receipt.result.is_ok());
 }
 
+#[test]
+fn mintable_resource_with_initial_supply_can_mint_more() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let (pk, sk, account) = executor.new_account();
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "resource")))
+        .unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(
+            package,
+            "ResourceTest",
+            "create_mintable_with_initial_supply",
+            args![],
+        )
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(executor.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+}
+
 #[test]
 fn mint_with_bad_granularity_should_fail() {
     // Arrange
@@ -54,7 +82,99 @@ fn mint_with_bad_granularity_should_fail() {
     let receipt = executor.validate_and_execute(&transaction).unwrap();
 
     // Assert
-    let runtime_error = receipt.result.expect_err("Should be runtime error");
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
+    assert_eq!(
+        runtime_error,
+        RuntimeError::ResourceManagerError(ResourceManagerError::InvalidAmount(
+            Decimal::from("0.1"),
+            0
+        ))
+    );
+}
+
+#[test]
+fn divisibility_can_be_updated_before_supply_exists() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let (pk, sk, account) = executor.new_account();
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "resource")))
+        .unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(
+            package,
+            "ResourceTest",
+            "update_divisibility_and_mint",
+            args![2u8, dec!("0.5")],
+        )
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(executor.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay.");
+}
+
+#[test]
+fn divisibility_cannot_be_updated_after_supply_exists() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let (pk, sk, account) = executor.new_account();
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "resource")))
+        .unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(
+            package,
+            "ResourceTest",
+            "mint_and_update_divisibility",
+            args![2u8],
+        )
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(executor.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
+    assert_eq!(
+        runtime_error,
+        RuntimeError::ResourceManagerError(ResourceManagerError::TotalSupplyNotZero)
+    );
+}
+
+#[test]
+fn initial_supply_with_bad_granularity_should_fail() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let (pk, sk, account) = executor.new_account();
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "resource")))
+        .unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(
+            package,
+            "ResourceTest",
+            "create_fungible_with_initial_supply",
+            args![0u8, dec!("0.1")],
+        )
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(executor.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
     assert_eq!(
         runtime_error,
         RuntimeError::ResourceManagerError(ResourceManagerError::InvalidAmount(
@@ -64,6 +184,42 @@ fn mint_with_bad_granularity_should_fail() {
     );
 }
 
+#[test]
+fn fungible_initial_supply_of_non_fungible_resource_should_fail() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let (pk, sk, account) = executor.new_account();
+    let mut resource_auth = HashMap::new();
+    resource_auth.insert(Withdraw, (rule!(allow_all), LOCKED));
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .add_instruction(Instruction::CallFunction {
+            package_address: SYSTEM_PACKAGE,
+            blueprint_name: "System".to_owned(),
+            function: "new_resource".to_owned(),
+            args: vec![
+                scrypto_encode(&ResourceType::NonFungible),
+                scrypto_encode(&HashMap::<String, MetadataValue>::new()),
+                scrypto_encode(&resource_auth),
+                scrypto_encode(&Some(MintParams::fungible(1))),
+            ],
+        })
+        .0
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(executor.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
+    assert_eq!(
+        runtime_error,
+        RuntimeError::ResourceManagerError(ResourceManagerError::ResourceTypeDoesNotMatch)
+    );
+}
+
 #[test]
 fn mint_too_much_should_fail() {
     // Arrange
@@ -88,9 +244,37 @@ fn mint_too_much_should_fail() {
     let receipt = executor.validate_and_execute(&transaction).unwrap();
 
     // Assert
-    let runtime_error = receipt.result.expect_err("Should be runtime error");
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
     assert_eq!(
         runtime_error,
         RuntimeError::ResourceManagerError(ResourceManagerError::MaxMintAmountExceeded)
     );
 }
+
+#[test]
+fn create_resource_with_mistyped_standard_metadata_should_fail() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let (pk, sk, account) = executor.new_account();
+    let mut metadata = HashMap::new();
+    metadata.insert(METADATA_NAME.to_owned(), "TestToken".into());
+    metadata.insert(METADATA_ICON_URL.to_owned(), "not a url".into());
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .new_token_fixed(metadata, 100.into())
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(executor.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
+    assert_eq!(
+        runtime_error,
+        RuntimeError::ResourceManagerError(ResourceManagerError::InvalidMetadata(
+            METADATA_ICON_URL.to_owned()
+        ))
+    );
+}
@@ -34,6 +34,68 @@ blueprint! {
             (badge, tokens, token_address)
         }
 
+        pub fn update_divisibility_and_mint(
+            divisibility: u8,
+            amount: Decimal,
+        ) -> (Bucket, Bucket, Bucket) {
+            let badge = ResourceBuilder::new_fungible()
+                .divisibility(DIVISIBILITY_NONE)
+                .initial_supply(1);
+            let token_address = ResourceBuilder::new_fungible()
+                .divisibility(DIVISIBILITY_NONE)
+                .mintable(rule!(require(badge.resource_address())), LOCKED)
+                .updateable_divisibility(rule!(require(badge.resource_address())), LOCKED)
+                .no_initial_supply();
+            let mut tokens = Bucket::new(token_address);
+            badge.authorize(|| {
+                let resource_manager = borrow_resource_manager!(token_address);
+                resource_manager.update_divisibility(divisibility);
+                tokens.put(resource_manager.mint(amount));
+            });
+            let change = tokens.take(amount / 2);
+            (badge, tokens, change)
+        }
+
+        pub fn mint_and_update_divisibility(divisibility: u8) -> (Bucket, Bucket) {
+            let badge = ResourceBuilder::new_fungible()
+                .divisibility(DIVISIBILITY_NONE)
+                .initial_supply(1);
+            let token_address = ResourceBuilder::new_fungible()
+                .divisibility(DIVISIBILITY_NONE)
+                .mintable(rule!(require(badge.resource_address())), LOCKED)
+                .updateable_divisibility(rule!(require(badge.resource_address())), LOCKED)
+                .no_initial_supply();
+            let tokens = badge.authorize(|| {
+                let resource_manager = borrow_resource_manager!(token_address);
+                let tokens = resource_manager.mint(1);
+                resource_manager.update_divisibility(divisibility);
+                tokens
+            });
+            (badge, tokens)
+        }
+
+        pub fn create_fungible_with_initial_supply(divisibility: u8, amount: Decimal) -> Bucket {
+            ResourceBuilder::new_fungible()
+                .divisibility(divisibility)
+                .metadata("name", "TestToken")
+                .initial_supply(amount)
+        }
+
+        pub fn create_mintable_with_initial_supply() -> (Bucket, Bucket) {
+            let badge = ResourceBuilder::new_fungible()
+                .divisibility(DIVISIBILITY_NONE)
+                .initial_supply(1);
+            let mut tokens = ResourceBuilder::new_fungible()
+                .metadata("name", "TestToken")
+                .mintable(rule!(require(badge.resource_address())), LOCKED)
+                .restrict_withdraw(rule!(allow_all), LOCKED)
+                .mint_initial_supply(5);
+            let resource_manager = borrow_resource_manager!(tokens.resource_address());
+            tokens.put(badge.authorize(|| resource_manager.mint(3)));
+            assert_eq!(tokens.amount(), dec!("8"));
+            (badge, tokens)
+        }
+
         pub fn create_fungible_wrong_resource_flags_should_fail() -> ResourceAddress {
             let token_address = ResourceBuilder::new_fungible()
                 .divisibility(DIVISIBILITY_MAXIMUM)
@@ -64,7 +126,7 @@ blueprint! {
             (badge, token_address)
         }
 
-        pub fn query() -> (Bucket, HashMap<String, String>, Decimal) {
+        pub fn query() -> (Bucket, HashMap<String, MetadataValue>, Decimal) {
             let (badge, resource_address) = Self::create_fungible();
             let resource_manager = borrow_resource_manager!(resource_address);
             (
@@ -74,6 +136,35 @@ blueprint! {
             )
         }
 
+        pub fn query_standard_metadata() {
+            let resource_manager = borrow_resource_manager!(ResourceBuilder::new_fungible()
+                .metadata(METADATA_SYMBOL, "TT")
+                .metadata(METADATA_NAME, "TestToken")
+                .metadata(
+                    METADATA_ICON_URL,
+                    MetadataValue::Url("https://example.com/icon.png".to_owned()),
+                )
+                .metadata(METADATA_TAGS, vec!["test".to_owned(), "token".to_owned()])
+                .metadata("weight", dec!("1.5"))
+                .no_initial_supply());
+
+            assert_eq!(resource_manager.symbol(), Some("TT".to_owned()));
+            assert_eq!(resource_manager.name(), Some("TestToken".to_owned()));
+            assert_eq!(resource_manager.description(), None);
+            assert_eq!(
+                resource_manager.icon_url(),
+                Some("https://example.com/icon.png".to_owned())
+            );
+            assert_eq!(
+                resource_manager.tags(),
+                vec!["test".to_owned(), "token".to_owned()]
+            );
+            assert_eq!(
+                resource_manager.metadata().get("weight"),
+                Some(&MetadataValue::Decimal(dec!("1.5")))
+            );
+        }
+
         pub fn burn() -> Bucket {
             let (badge, resource_address) = Self::create_fungible();
             let resource_manager = borrow_resource_manager!(resource_address);
@@ -95,7 +186,7 @@ blueprint! {
                 .no_initial_supply());
 
             let mut new_metadata = HashMap::new();
-            new_metadata.insert("a".to_owned(), "b".to_owned());
+            new_metadata.insert("a".to_owned(), "b".into());
             badge.authorize(|| {
                 token_resource_manager.update_metadata(new_metadata.clone());
                 assert_eq!(token_resource_manager.metadata(), new_metadata);
@@ -0,0 +1,74 @@
+use radix_engine::engine::ResourceAuditMode;
+use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::transaction::*;
+use scrypto::prelude::*;
+
+#[test]
+fn resource_changes_should_be_reported() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut substate_store, false)
+        .with_resource_audit(ResourceAuditMode::Report);
+    let (_, _, account) = executor.new_account();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_method(SYSTEM_COMPONENT, "free_xrd", args![])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    let audit = receipt.resource_audit.expect("Should be audited");
+    let change = audit.changes()[&RADIX_TOKEN];
+    assert!(change.supply_change.is_positive());
+    assert_eq!(change.supply_change, change.held_change);
+    assert!(audit.violations().is_empty());
+}
+
+#[test]
+fn conserving_transaction_should_be_committed_when_audit_is_enforced() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut substate_store, false)
+        .with_resource_audit(ResourceAuditMode::Enforce);
+    let (_, _, account) = executor.new_account();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_method(SYSTEM_COMPONENT, "free_xrd", args![])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    let audit = receipt.resource_audit.expect("Should be audited");
+    assert!(audit.changes()[&RADIX_TOKEN].is_conserved());
+    assert!(audit.violations().is_empty());
+    let resources = executor.get_component_resources(account);
+    assert!(resources[&RADIX_TOKEN].is_positive());
+}
+
+#[test]
+fn resource_changes_should_not_be_reported_when_audit_is_off() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut substate_store, false);
+    let (_, _, account) = executor.new_account();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_method(SYSTEM_COMPONENT, "free_xrd", args![])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert!(receipt.resource_audit.is_none());
+}
@@ -45,6 +45,13 @@ blueprint! {
                 .initial_supply(5)
         }
 
+        pub fn create_updateable_withdraw(admin_auth: ResourceAddress) -> Bucket {
+            ResourceBuilder::new_fungible()
+                .divisibility(0)
+                .restrict_withdraw(rule!(allow_all), MUTABLE(rule!(require(admin_auth))))
+                .initial_supply(5)
+        }
+
         pub fn create_restricted_burn(badge_resource_address: ResourceAddress) -> Bucket {
             ResourceBuilder::new_fungible()
                 .divisibility(0)
@@ -74,6 +81,14 @@ blueprint! {
             borrow_resource_manager!(resource_address).lock_mintable();
         }
 
+        pub fn renounce_mintable(resource_address: ResourceAddress) {
+            borrow_resource_manager!(resource_address).renounce_mintable();
+        }
+
+        pub fn renounce_burnable(resource_address: ResourceAddress) {
+            borrow_resource_manager!(resource_address).renounce_burnable();
+        }
+
         pub fn create_non_fungible_fixed() -> Bucket {
             ResourceBuilder::new_non_fungible()
                 .metadata("name", "Katz's Sandwiches")
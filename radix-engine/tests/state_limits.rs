@@ -0,0 +1,199 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::engine::{EngineVersion, SizeLimitKind, DEFAULT_STATE_LIMITS};
+use radix_engine::errors::RuntimeError;
+use radix_engine::ledger::*;
+use scrypto::prelude::*;
+
+fn instantiate(test_runner: &mut TestRunner) -> (PackageAddress, ComponentAddress) {
+    let package_address = test_runner.publish_package("core");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package_address, "StateSize", "new", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    (package_address, receipt.new_component_addresses[0])
+}
+
+#[test]
+fn component_state_within_limit_is_accepted() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (_, component_address) = instantiate(&mut test_runner);
+    let size = DEFAULT_STATE_LIMITS.max_component_state_size as u32 / 2;
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(component_address, "set_data", args![size])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+}
+
+#[test]
+fn oversized_component_state_is_rejected() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (_, component_address) = instantiate(&mut test_runner);
+    let size = DEFAULT_STATE_LIMITS.max_component_state_size as u32;
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(component_address, "set_data", args![size])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be runtime error").cause;
+    assert!(matches!(
+        error,
+        RuntimeError::SizeLimitExceeded {
+            kind: SizeLimitKind::ComponentState,
+            limit,
+            ..
+        } if limit == DEFAULT_STATE_LIMITS.max_component_state_size
+    ));
+}
+
+#[test]
+fn oversized_component_state_is_accepted_before_v6() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::with_engine_version(&mut substate_store, EngineVersion::V5);
+    let (_, component_address) = instantiate(&mut test_runner);
+    let size = DEFAULT_STATE_LIMITS.max_component_state_size as u32;
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(component_address, "set_data", args![size])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+}
+
+#[test]
+fn oversized_lazy_map_value_is_rejected() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (_, component_address) = instantiate(&mut test_runner);
+    let size = DEFAULT_STATE_LIMITS.max_lazy_map_value_size as u32;
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(component_address, "put_entry", args![size])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be runtime error").cause;
+    assert!(matches!(
+        error,
+        RuntimeError::SizeLimitExceeded {
+            kind: SizeLimitKind::LazyMapValue,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn oversized_lazy_map_key_is_rejected() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (_, component_address) = instantiate(&mut test_runner);
+    let size = DEFAULT_STATE_LIMITS.max_lazy_map_key_size as u32;
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(component_address, "put_key", args![size])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be runtime error").cause;
+    assert!(matches!(
+        error,
+        RuntimeError::SizeLimitExceeded {
+            kind: SizeLimitKind::LazyMapKey,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn oversized_non_fungible_data_is_rejected() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (_, _, account) = test_runner.new_account();
+    let (package_address, _) = instantiate(&mut test_runner);
+    let size = DEFAULT_STATE_LIMITS.max_non_fungible_data_size as u32;
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package_address, "StateSize", "mint_blob", args![size])
+        .call_method_with_all_resources(account, "deposit_batch")
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be runtime error").cause;
+    assert!(matches!(
+        error,
+        RuntimeError::SizeLimitExceeded {
+            kind: SizeLimitKind::NonFungibleData,
+            limit,
+            ..
+        } if limit == DEFAULT_STATE_LIMITS.max_non_fungible_data_size
+    ));
+}
+
+#[test]
+fn oversized_call_data_is_rejected() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (package_address, _) = instantiate(&mut test_runner);
+    let size = DEFAULT_STATE_LIMITS.max_call_data_size as u32;
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package_address, "StateSize", "forward", args![size])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be runtime error").cause;
+    assert!(matches!(
+        error,
+        RuntimeError::SizeLimitExceeded {
+            kind: SizeLimitKind::CallData,
+            ..
+        }
+    ));
+}
@@ -0,0 +1,88 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::errors::RuntimeError;
+use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::model::*;
+use scrypto::prelude::*;
+
+#[test]
+fn system_transaction_can_change_epoch() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(SYSTEM_COMPONENT, "set_epoch", args![5u64])
+        .build_with_no_nonce();
+    let receipt = test_runner.validate_and_execute_system(&SystemTransaction::new(transaction));
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert_eq!(test_runner.current_epoch(), 5);
+}
+
+#[test]
+fn user_transaction_cannot_change_epoch() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, _) = test_runner.new_account();
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(SYSTEM_COMPONENT, "set_epoch", args![5u64])
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert_auth_error!(error);
+    assert_eq!(test_runner.current_epoch(), 0);
+}
+
+#[test]
+fn system_badge_is_dropped_before_user_code_is_called() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package, "ComponentTest", "create_component", args![])
+        .call_method(SYSTEM_COMPONENT, "set_epoch", args![5u64])
+        .build_with_no_nonce();
+    let receipt = test_runner.validate_and_execute_system(&SystemTransaction::new(transaction));
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert_auth_error!(error);
+    assert_eq!(test_runner.current_epoch(), 0);
+}
+
+#[test]
+fn system_instructions_can_precede_user_code() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("component");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(SYSTEM_COMPONENT, "set_epoch", args![5u64])
+        .call_function(package, "ComponentTest", "create_component", args![])
+        .build_with_no_nonce();
+    let receipt = test_runner.validate_and_execute_system(&SystemTransaction::new(transaction));
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert_eq!(test_runner.current_epoch(), 5);
+}
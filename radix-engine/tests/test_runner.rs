@@ -1,8 +1,17 @@
+use radix_engine::engine::{
+    EngineVersion, NewEntity, ResourceAuditMode, SubstateCacheStats, WasmInstancePoolStats,
+};
+use radix_engine::errors::RuntimeError;
+use radix_engine::fee::CostEstimate;
 use radix_engine::ledger::*;
-use radix_engine::model::{Component, Receipt, SignedTransaction};
+use radix_engine::model::{
+    Component, CompositeTransaction, PackageTestResult, Receipt, SignedTransaction,
+    SystemTransaction, Transaction,
+};
 use radix_engine::transaction::*;
 use scrypto::abi;
 use scrypto::prelude::*;
+use scrypto::values::ScryptoValue;
 
 pub struct TestRunner<'l> {
     executor: TransactionExecutor<'l, InMemorySubstateStore>,
@@ -10,11 +19,20 @@ pub struct TestRunner<'l> {
 
 impl<'l> TestRunner<'l> {
     pub fn new(ledger: &'l mut InMemorySubstateStore) -> Self {
-        let executor = TransactionExecutor::new(ledger, true);
+        let executor =
+            TransactionExecutor::new(ledger, true).with_resource_audit(ResourceAuditMode::Enforce);
 
         Self { executor }
     }
 
+    pub fn with_engine_version(
+        ledger: &'l mut InMemorySubstateStore,
+        engine_version: EngineVersion,
+    ) -> Self {
+        ledger.set_engine_version(engine_version);
+        Self::new(ledger)
+    }
+
     pub fn new_transaction_builder(&self) -> TransactionBuilder {
         TransactionBuilder::new()
     }
@@ -42,14 +60,113 @@ impl<'l> TestRunner<'l> {
         self.executor.new_account()
     }
 
+    pub fn new_account_with_seed(
+        &mut self,
+        seed: u64,
+    ) -> (EcdsaPublicKey, EcdsaPrivateKey, ComponentAddress) {
+        self.executor.new_account_with_seed(seed)
+    }
+
+    pub fn new_test_accounts(
+        &mut self,
+        count: u64,
+    ) -> Vec<(EcdsaPublicKey, EcdsaPrivateKey, ComponentAddress)> {
+        self.executor.new_test_accounts(count)
+    }
+
     pub fn validate_and_execute(&mut self, transaction: &SignedTransaction) -> Receipt {
         self.executor.validate_and_execute(transaction).unwrap()
     }
 
+    /// Executes a transaction, failing the test if it consumes more cost units than the budget.
+    pub fn execute_with_cost_budget(
+        &mut self,
+        transaction: &SignedTransaction,
+        max_cost_units: u32,
+    ) -> Receipt {
+        let receipt = self.validate_and_execute(transaction);
+        if receipt.cost_units_consumed > max_cost_units {
+            let breakdown: Vec<String> = receipt
+                .cost_unit_breakdown
+                .iter()
+                .map(|(reason, cost_units)| format!("  {}: {}", reason, cost_units))
+                .collect();
+            panic!(
+                "Transaction consumed {} cost units, exceeding the budget of {}\n{}",
+                receipt.cost_units_consumed,
+                max_cost_units,
+                breakdown.join("\n")
+            );
+        }
+        receipt
+    }
+
+    pub fn validate_and_execute_composite(&mut self, composite: &CompositeTransaction) -> Receipt {
+        self.executor.validate_and_execute_composite(composite).unwrap()
+    }
+
+    pub fn validate_and_execute_system(&mut self, system: &SystemTransaction) -> Receipt {
+        self.executor.validate_and_execute_system(system).unwrap()
+    }
+
+    pub fn estimate_cost<PKS: AsRef<[EcdsaPublicKey]>>(
+        &mut self,
+        transaction: &Transaction,
+        intended_signers: PKS,
+    ) -> CostEstimate {
+        self.executor
+            .estimate_cost(transaction, intended_signers)
+            .unwrap()
+    }
+
+    pub fn predict_addresses<PKS: AsRef<[EcdsaPublicKey]>>(
+        &mut self,
+        transaction: &Transaction,
+        intended_signers: PKS,
+    ) -> Vec<NewEntity> {
+        self.executor
+            .predict_addresses(transaction, intended_signers)
+            .unwrap()
+    }
+
+    pub fn substate_cache_stats(&self) -> SubstateCacheStats {
+        self.executor.substate_cache_stats()
+    }
+
+    pub fn wasm_instance_pool_stats(&self) -> WasmInstancePoolStats {
+        self.executor.wasm_instance_pool_stats()
+    }
+
+    pub fn current_epoch(&self) -> u64 {
+        self.executor.substate_store().get_epoch()
+    }
+
+    pub fn engine_version(&self) -> EngineVersion {
+        self.executor.engine_version()
+    }
+
     pub fn publish_package(&mut self, name: &str) -> PackageAddress {
         self.executor.publish_package(&Self::compile(name)).unwrap()
     }
 
+    pub fn overwrite_package(&mut self, package_address: PackageAddress, name: &str) {
+        self.executor
+            .overwrite_package(package_address, Self::compile(name))
+            .unwrap()
+    }
+
+    pub fn migrate_components(
+        &mut self,
+        component_addresses: &[ComponentAddress],
+    ) -> Vec<ComponentAddress> {
+        self.executor.migrate_components(component_addresses).unwrap()
+    }
+
+    pub fn publish_precompiled<P: AsRef<std::path::Path>>(&mut self, path: P) -> PackageAddress {
+        let code = std::fs::read(path).expect("Failed to read precompiled package");
+        self.executor.publish_package(&code).unwrap()
+    }
+
     pub fn compile(name: &str) -> Vec<u8> {
         compile_package!(format!("./tests/{}", name))
     }
@@ -62,6 +179,22 @@ impl<'l> TestRunner<'l> {
             .unwrap()
     }
 
+    pub fn get_component_resources(
+        &self,
+        component_address: ComponentAddress,
+    ) -> HashMap<ResourceAddress, Decimal> {
+        self.executor.get_component_resources(component_address)
+    }
+
+    pub fn get_non_fungible_location(
+        &self,
+        non_fungible_address: &NonFungibleAddress,
+    ) -> Option<NonFungibleLocation> {
+        self.executor
+            .substate_store()
+            .get_non_fungible_location(non_fungible_address)
+    }
+
     pub fn export_abi(
         &self,
         package_address: PackageAddress,
@@ -78,6 +211,27 @@ impl<'l> TestRunner<'l> {
             .unwrap()
     }
 
+    pub fn export_types(&self, package_address: PackageAddress) -> abi::Types {
+        self.executor.export_types(package_address).unwrap()
+    }
+
+    pub fn call_read_only(
+        &mut self,
+        component_address: ComponentAddress,
+        method: &str,
+        args: Vec<Vec<u8>>,
+    ) -> Result<ScryptoValue, RuntimeError> {
+        self.executor.call_read_only(component_address, method, args)
+    }
+
+    pub fn query_executor(&self) -> QueryExecutor<'_, InMemorySubstateStore> {
+        self.executor.query_executor()
+    }
+
+    pub fn run_package_tests(&mut self, package_address: PackageAddress) -> Vec<PackageTestResult> {
+        self.executor.run_package_tests(package_address).unwrap()
+    }
+
     pub fn get_nonce<PKS: AsRef<[EcdsaPublicKey]>>(&self, intended_signers: PKS) -> u64 {
         self.executor.get_nonce(intended_signers)
     }
@@ -269,7 +423,8 @@ macro_rules! assert_auth_error {
             RuntimeError::AuthorizationError {
                 authorization: _,
                 function: _,
-                error: ::radix_engine::model::MethodAuthorizationError::NotAuthorized
+                error: ::radix_engine::model::MethodAuthorizationError::NotAuthorized,
+                ..
             }
         ) {
             panic!("Expected auth error but got: {:?}", $error);
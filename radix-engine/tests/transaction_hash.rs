@@ -0,0 +1,49 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::ledger::InMemorySubstateStore;
+use radix_engine::model::CompositeTransaction;
+use scrypto::prelude::*;
+
+#[test]
+fn receipt_should_include_transaction_hashes() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(account, "balance", args![RADIX_TOKEN])
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+
+    // Act
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert_eq!(receipt.transaction_hashes, Some(transaction.hashes()));
+}
+
+#[test]
+fn composite_transaction_receipt_should_not_include_transaction_hashes() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let part = test_runner
+        .new_transaction_builder()
+        .call_method(account, "balance", args![RADIX_TOKEN])
+        .build(test_runner.get_nonce([pk]));
+    let intent_hash = CompositeTransaction::intent_hash_of(&[&part]);
+
+    // Act
+    let receipt = test_runner.validate_and_execute_composite(&CompositeTransaction::new(vec![
+        part.sign_part(intent_hash, [&sk]),
+    ]));
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert_eq!(receipt.transaction_hashes, None);
+}
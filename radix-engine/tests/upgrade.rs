@@ -0,0 +1,235 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::errors::RuntimeError;
+use radix_engine::ledger::*;
+use radix_engine::model::Receipt;
+use scrypto::prelude::*;
+use scrypto::values::ScryptoValue;
+
+fn create_counter(test_runner: &mut TestRunner, package: PackageAddress) -> ComponentAddress {
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package, "Counter", "new", args![5u32])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    receipt.new_component_addresses[0]
+}
+
+fn get_counter(test_runner: &mut TestRunner, component: ComponentAddress) -> ScryptoValue {
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_method(component, "get", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    receipt.outputs[0].clone()
+}
+
+fn publish_with_owner(
+    test_runner: &mut TestRunner,
+    name: &str,
+    owner_badge: ResourceAddress,
+) -> PackageAddress {
+    let transaction = test_runner
+        .new_transaction_builder()
+        .publish_package_with_owner(&TestRunner::compile(name), owner_badge)
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    receipt.new_package_addresses[0]
+}
+
+fn upgrade_as_owner(
+    test_runner: &mut TestRunner,
+    pk: EcdsaPublicKey,
+    sk: &EcdsaPrivateKey,
+    account: ComponentAddress,
+    owner_badge: Option<ResourceAddress>,
+    package: PackageAddress,
+    name: &str,
+) -> Receipt {
+    let mut builder = test_runner.new_transaction_builder();
+    if let Some(owner_badge) = owner_badge {
+        builder.create_proof_from_account(owner_badge, account);
+    }
+    let transaction = builder
+        .call_function(
+            SYSTEM_PACKAGE,
+            "System",
+            "upgrade_package",
+            args![package, TestRunner::compile(name)],
+        )
+        .build(test_runner.get_nonce([pk]))
+        .sign([sk]);
+    test_runner.validate_and_execute(&transaction)
+}
+
+#[test]
+fn component_state_should_be_migrated_when_first_called_after_upgrade() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("upgrade_v1");
+    let component = create_counter(&mut test_runner, package);
+
+    // Act
+    test_runner.overwrite_package(package, "upgrade_v2");
+    let output = get_counter(&mut test_runner, component);
+
+    // Assert
+    assert_eq!(
+        output,
+        ScryptoValue::from_value(&(5u64, "migrated".to_owned()))
+    );
+    assert_eq!(test_runner.migrate_components(&[component]), vec![]);
+}
+
+#[test]
+fn component_state_should_be_migrated_in_bulk() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("upgrade_v1");
+    let component1 = create_counter(&mut test_runner, package);
+    let component2 = create_counter(&mut test_runner, package);
+    test_runner.overwrite_package(package, "upgrade_v2");
+
+    // Act
+    let migrated = test_runner.migrate_components(&[component1, component2]);
+
+    // Assert
+    assert_eq!(migrated, vec![component1, component2]);
+    assert_eq!(
+        get_counter(&mut test_runner, component2),
+        ScryptoValue::from_value(&(5u64, "migrated".to_owned()))
+    );
+}
+
+#[test]
+fn component_state_should_be_kept_without_migrate_state() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package = test_runner.publish_package("upgrade_v1");
+    let component = create_counter(&mut test_runner, package);
+
+    // Act
+    test_runner.overwrite_package(package, "upgrade_v1");
+    let output = get_counter(&mut test_runner, component);
+
+    // Assert
+    assert_eq!(output, ScryptoValue::from_value(&5u32));
+}
+
+#[test]
+fn owner_can_upgrade_package() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let owner_badge = test_runner.create_fungible_resource(Decimal::one(), 0, account);
+    let package = publish_with_owner(&mut test_runner, "upgrade_v1", owner_badge);
+    let component = create_counter(&mut test_runner, package);
+
+    // Act
+    let receipt = upgrade_as_owner(
+        &mut test_runner,
+        pk,
+        &sk,
+        account,
+        Some(owner_badge),
+        package,
+        "upgrade_v2",
+    );
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert_eq!(receipt.outputs[1], ScryptoValue::from_value(&1u32));
+    assert_eq!(
+        get_counter(&mut test_runner, component),
+        ScryptoValue::from_value(&(5u64, "migrated".to_owned()))
+    );
+}
+
+#[test]
+fn upgrade_without_owner_badge_should_fail() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let owner_badge = test_runner.create_fungible_resource(Decimal::one(), 0, account);
+    let package = publish_with_owner(&mut test_runner, "upgrade_v1", owner_badge);
+
+    // Act
+    let receipt = upgrade_as_owner(
+        &mut test_runner,
+        pk,
+        &sk,
+        account,
+        None,
+        package,
+        "upgrade_v2",
+    );
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert!(matches!(error, RuntimeError::AuthorizationError { .. }));
+}
+
+#[test]
+fn package_without_owner_cannot_be_upgraded() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let badge = test_runner.create_fungible_resource(Decimal::one(), 0, account);
+    let package = test_runner.publish_package("upgrade_v1");
+
+    // Act
+    let receipt = upgrade_as_owner(
+        &mut test_runner,
+        pk,
+        &sk,
+        account,
+        Some(badge),
+        package,
+        "upgrade_v2",
+    );
+
+    // Assert
+    let error = receipt.result.expect_err("Should be error").cause;
+    assert!(matches!(error, RuntimeError::AuthorizationError { .. }));
+}
+
+#[test]
+fn owner_can_set_package_metadata() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let (pk, sk, account) = test_runner.new_account();
+    let owner_badge = test_runner.create_fungible_resource(Decimal::one(), 0, account);
+    let package = publish_with_owner(&mut test_runner, "upgrade_v1", owner_badge);
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .create_proof_from_account(owner_badge, account)
+        .call_function(
+            SYSTEM_PACKAGE,
+            "System",
+            "set_package_metadata",
+            args![package, "name".to_owned(), "Counter".to_owned()],
+        )
+        .build(test_runner.get_nonce([pk]))
+        .sign([&sk]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+}
@@ -0,0 +1,17 @@
+use scrypto::prelude::*;
+
+blueprint! {
+    struct Counter {
+        count: u32,
+    }
+
+    impl Counter {
+        pub fn new(count: u32) -> ComponentAddress {
+            Self { count }.instantiate().globalize()
+        }
+
+        pub fn get(&self) -> u32 {
+            self.count
+        }
+    }
+}
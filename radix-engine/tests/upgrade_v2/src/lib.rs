@@ -0,0 +1,37 @@
+use scrypto::prelude::*;
+
+/// The state of `Counter` in the previous version of the package.
+#[derive(Decode)]
+struct CounterV1 {
+    count: u32,
+}
+
+blueprint! {
+    struct Counter {
+        count: u64,
+        label: String,
+    }
+
+    impl Counter {
+        pub fn new(count: u64) -> ComponentAddress {
+            Self {
+                count,
+                label: "new".to_owned(),
+            }
+            .instantiate()
+            .globalize()
+        }
+
+        pub fn migrate_state(old_state: Vec<u8>) -> Vec<u8> {
+            let old: CounterV1 = scrypto_decode(&old_state).unwrap();
+            scrypto_encode(&Self {
+                count: old.count as u64,
+                label: "migrated".to_owned(),
+            })
+        }
+
+        pub fn get(&self) -> (u64, String) {
+            (self.count, self.label.clone())
+        }
+    }
+}
@@ -26,7 +26,7 @@ fn non_existent_vault_in_component_creation_should_fail() {
     let receipt = executor.validate_and_execute(&transaction).unwrap();
 
     // Assert
-    let runtime_error = receipt.result.expect_err("Should be runtime error");
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
     match runtime_error {
         RuntimeError::VaultNotFound(_) => {}
         _ => panic!("Should be vault not found error but was {}", runtime_error),
@@ -56,7 +56,7 @@ fn non_existent_vault_in_committed_component_should_fail() {
     let receipt = executor.validate_and_execute(&transaction).unwrap();
 
     // Assert
-    let runtime_error = receipt.result.expect_err("Should be runtime error");
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
     match runtime_error {
         RuntimeError::VaultNotFound(_) => {}
         _ => panic!("Should be vault not found error but was {}", runtime_error),
@@ -85,7 +85,7 @@ fn non_existent_vault_in_lazy_map_creation_should_fail() {
     let receipt = executor.validate_and_execute(&transaction).unwrap();
 
     // Assert
-    let runtime_error = receipt.result.expect_err("Should be runtime error");
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
     match runtime_error {
         RuntimeError::VaultNotFound(_) => {}
         _ => panic!("Should be vault not found error but was {}", runtime_error),
@@ -119,7 +119,7 @@ fn non_existent_vault_in_committed_lazy_map_should_fail() {
     let receipt = executor.validate_and_execute(&transaction).unwrap();
 
     // Assert
-    let runtime_error = receipt.result.expect_err("Should be runtime error");
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
     match runtime_error {
         RuntimeError::VaultNotFound(_) => {}
         _ => panic!("Should be vault not found error but was {}", runtime_error),
@@ -143,7 +143,7 @@ fn dangling_vault_should_fail() {
     let receipt = executor.validate_and_execute(&transaction).unwrap();
 
     // Assert
-    let runtime_error = receipt.result.expect_err("Should be runtime error");
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
     assert_eq!(runtime_error, RuntimeError::ResourceCheckFailure(ResourceFailure::Resource(receipt.new_resource_addresses[0])));
 }
 
@@ -189,7 +189,7 @@ fn invalid_double_ownership_of_vault() {
     let receipt = executor.validate_and_execute(&transaction).unwrap();
 
     // Assert
-    let runtime_error = receipt.result.expect_err("Should be runtime error");
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
     match runtime_error {
         RuntimeError::VaultNotFound(_) => {}
         _ => panic!("Should be vault not found error"),
@@ -239,7 +239,7 @@ fn cannot_overwrite_vault_in_map() {
     let receipt = executor.validate_and_execute(&transaction).unwrap();
 
     // Assert
-    let runtime_error = receipt.result.expect_err("Should be runtime error");
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
     match runtime_error {
         RuntimeError::VaultRemoved(_) => {}
         _ => panic!("Should be vault not found error"),
@@ -289,7 +289,7 @@ fn cannot_remove_vaults() {
     let receipt = executor.validate_and_execute(&transaction).unwrap();
 
     // Assert
-    let runtime_error = receipt.result.expect_err("Should be runtime error");
+    let runtime_error = receipt.result.expect_err("Should be runtime error").cause;
     match runtime_error {
         RuntimeError::VaultRemoved(_) => {}
         _ => panic!("Should be vault not found error"),
@@ -412,6 +412,26 @@ fn create_mutable_vault_with_get_amount() {
     receipt.result.expect("Should be okay");
 }
 
+#[test]
+fn locked_and_liquid_amounts_should_reflect_outstanding_proofs() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "vault")))
+        .unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(package, "VaultTest", "new_vault_with_locked_amount", args![])
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay");
+}
+
 #[test]
 fn create_mutable_vault_with_get_resource_manager() {
     // Arrange
@@ -436,3 +456,28 @@ fn create_mutable_vault_with_get_resource_manager() {
     // Assert
     receipt.result.expect("Should be okay");
 }
+
+#[test]
+fn batched_vault_amounts_should_be_returned_in_order() {
+    // Arrange
+    let mut ledger = InMemorySubstateStore::with_bootstrap();
+    let mut executor = TransactionExecutor::new(&mut ledger, true);
+    let package = executor
+        .publish_package(&compile_package!(format!("./tests/{}", "vault")))
+        .unwrap();
+
+    // Act
+    let transaction = TransactionBuilder::new()
+        .call_function(
+            package,
+            "VaultTest",
+            "new_vaults_with_batched_amounts",
+            vec![],
+        )
+        .build(executor.get_nonce([]))
+        .sign([]);
+    let receipt = executor.validate_and_execute(&transaction).unwrap();
+
+    // Assert
+    receipt.result.expect("Should be okay");
+}
@@ -174,6 +174,35 @@ blueprint! {
             .globalize()
         }
 
+        pub fn new_vault_with_locked_amount() -> ComponentAddress {
+            let bucket = Self::new_fungible();
+            let bucket_proof = bucket.create_proof();
+            assert!(bucket.is_locked());
+            assert_eq!(bucket.locked_amount(), dec!("1"));
+            assert_eq!(bucket.liquid_amount(), dec!("0"));
+            bucket_proof.drop();
+            assert!(!bucket.is_locked());
+
+            let vault = Vault::with_bucket(bucket);
+            let vault_proof = vault.create_proof_by_amount(dec!("0.4"));
+            assert!(vault.is_locked());
+            assert_eq!(vault.locked_amount(), dec!("0.4"));
+            assert_eq!(vault.liquid_amount(), dec!("0.6"));
+            vault_proof.drop();
+            assert!(!vault.is_locked());
+            assert_eq!(vault.liquid_amount(), dec!("1"));
+
+            let vaults = LazyMap::new();
+            let vault_vector = Vec::new();
+            VaultTest {
+                vault,
+                vaults,
+                vault_vector,
+            }
+            .instantiate()
+            .globalize()
+        }
+
         pub fn new_vault_with_get_resource_manager() -> ComponentAddress {
             let vault = Self::create_non_fungible_vault();
             let _resource_manager = vault.resource_address();
@@ -187,5 +216,23 @@ blueprint! {
             .instantiate()
             .globalize()
         }
+
+        pub fn new_vaults_with_batched_amounts() -> ComponentAddress {
+            let vault = Vault::with_bucket(Self::new_fungible());
+            let vault_vector = vec![
+                Vault::new(RADIX_TOKEN),
+                Vault::with_bucket(Self::new_fungible()),
+            ];
+            let amounts = Vault::amounts(&vault_vector);
+            assert_eq!(amounts, vec![Decimal::zero(), Decimal::one()]);
+            let vaults = LazyMap::new();
+            VaultTest {
+                vault,
+                vaults,
+                vault_vector,
+            }
+            .instantiate()
+            .globalize()
+        }
     }
 }
@@ -0,0 +1,184 @@
+#[rustfmt::skip]
+pub mod test_runner;
+
+use crate::test_runner::TestRunner;
+use radix_engine::ledger::*;
+use sbor::describe::{Fields, Type};
+use scrypto::abi::{Constant, Function, Interface, Method};
+use scrypto::prelude::*;
+use scrypto::values::ScryptoValue;
+
+/// Formats bytes as a wat string, prefixed with their length as a radix-style buffer.
+fn wat_buffer(bytes: &[u8]) -> String {
+    (bytes.len() as u32)
+        .to_le_bytes()
+        .iter()
+        .chain(bytes)
+        .map(|b| format!("\\{:02x}", b))
+        .collect()
+}
+
+/// Builds a package with a `Counter` blueprint, which increments a mutable global that isn't
+/// exported and returns its value as a `u32`.
+fn counter_package_code() -> Vec<u8> {
+    let abi = scrypto_encode(&(
+        Type::Struct {
+            name: "Counter".to_string(),
+            fields: Fields::Unit,
+        },
+        Vec::<Function>::new(),
+        Vec::<Method>::new(),
+        Vec::<Constant>::new(),
+        Option::<Type>::None,
+        Vec::<Interface>::new(),
+        Vec::<String>::new(),
+    ));
+    let output = scrypto_encode(&0u32);
+    let value_offset = 2048 + 4 + output.len() - 4;
+    wabt::wat2wasm(format!(
+        r#"
+            (module
+                (memory (export "memory") 1)
+                (global $counter (mut i32) (i32.const 0))
+                (data (i32.const 1024) "{}")
+                (data (i32.const 2048) "{}")
+                (func (export "Counter_abi") (result i32)
+                    i32.const 1024
+                )
+                (func (export "Counter_main") (result i32)
+                    global.get $counter
+                    i32.const 1
+                    i32.add
+                    global.set $counter
+                    i32.const {}
+                    global.get $counter
+                    i32.store
+                    i32.const 2048
+                )
+            )
+            "#,
+        wat_buffer(&abi),
+        wat_buffer(&output),
+        value_offset
+    ))
+    .expect("failed to parse wat")
+}
+
+#[test]
+fn static_state_should_not_leak_between_transactions() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package_address = test_runner.publish_package("core");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package_address, "StaticState", "increment", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    assert_eq!(receipt.outputs[0], ScryptoValue::from_value(&1u32));
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package_address, "StaticState", "increment", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert_eq!(receipt.outputs[0], ScryptoValue::from_value(&1u32));
+    assert!(test_runner.wasm_instance_pool_stats().hits > 0);
+}
+
+#[test]
+fn static_state_should_not_leak_between_calls() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package_address = test_runner.publish_package("core");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package_address, "StaticState", "increment", args![])
+        .call_function(package_address, "StaticState", "increment", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert_eq!(receipt.outputs[0], ScryptoValue::from_value(&1u32));
+    assert_eq!(receipt.outputs[1], ScryptoValue::from_value(&1u32));
+    assert_eq!(test_runner.wasm_instance_pool_stats().hits, 1);
+}
+
+#[test]
+fn static_state_should_not_leak_after_failed_call() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let package_address = test_runner.publish_package("core");
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package_address, "StaticState", "increment", args![])
+        .call_function(
+            package_address,
+            "StaticState",
+            "increment_and_panic",
+            args![],
+        )
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect_err("Should fail");
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package_address, "StaticState", "increment", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert_eq!(receipt.outputs[0], ScryptoValue::from_value(&1u32));
+}
+
+#[test]
+fn mutable_globals_should_not_leak_between_transactions() {
+    // Arrange
+    let mut substate_store = InMemorySubstateStore::with_bootstrap();
+    let mut test_runner = TestRunner::new(&mut substate_store);
+    let transaction = test_runner
+        .new_transaction_builder()
+        .publish_package(&counter_package_code())
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    receipt.result.expect("Should be okay");
+    let package_address = receipt.new_package_addresses[0];
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package_address, "Counter", "increment", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+    assert_eq!(receipt.outputs[0], ScryptoValue::from_value(&1u32));
+
+    // Act
+    let transaction = test_runner
+        .new_transaction_builder()
+        .call_function(package_address, "Counter", "increment", args![])
+        .build(test_runner.get_nonce([]))
+        .sign([]);
+    let receipt = test_runner.validate_and_execute(&transaction);
+
+    // Assert
+    receipt.result.expect("Should be okay");
+    assert_eq!(receipt.outputs[0], ScryptoValue::from_value(&1u32));
+    assert!(test_runner.wasm_instance_pool_stats().hits > 0);
+}
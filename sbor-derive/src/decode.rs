@@ -14,7 +14,9 @@ macro_rules! trace {
 pub fn handle_decode(input: TokenStream) -> Result<TokenStream> {
     trace!("handle_decode() starts");
 
-    let DeriveInput { ident, data, .. } = parse2(input)?;
+    let DeriveInput {
+        ident, attrs, data, ..
+    } = parse2(input)?;
     trace!("Decoding: {}", ident);
 
     let output = match data {
@@ -76,10 +78,16 @@ pub fn handle_decode(input: TokenStream) -> Result<TokenStream> {
             }
         },
         Data::Enum(DataEnum { variants, .. }) => {
-            let match_arms = variants.iter().map(| v| {
+            let indexed = is_indexed_enum(&attrs, &variants)?;
+            let match_arms = variants.iter().enumerate().map(|(i, v)| {
                 let v_id = &v.ident;
-                let name_string = v_id.to_string();
-                let name: Expr = parse_quote! { #name_string };
+                let name: Expr = if indexed {
+                    let index = i as u8;
+                    parse_quote! { #index }
+                } else {
+                    let name_string = v_id.to_string();
+                    parse_quote! { #name_string }
+                };
 
                 match &v.fields {
                     syn::Fields::Named(FieldsNamed { named, .. }) => {
@@ -131,16 +139,33 @@ pub fn handle_decode(input: TokenStream) -> Result<TokenStream> {
                 }
             });
 
-            quote! {
-                impl ::sbor::Decode for #ident {
-                    #[inline]
-                    fn decode_value(decoder: &mut ::sbor::Decoder) -> Result<Self, ::sbor::DecodeError> {
-                        use ::sbor::{self, Decode};
+            if indexed {
+                quote! {
+                    impl ::sbor::Decode for #ident {
+                        #[inline]
+                        fn decode_value(decoder: &mut ::sbor::Decoder) -> Result<Self, ::sbor::DecodeError> {
+                            use ::sbor::{self, Decode};
 
-                        let name = <String>::decode_value(decoder)?;
-                        match name.as_str() {
-                            #(#match_arms,)*
-                            _ => Err(::sbor::DecodeError::InvalidEnum(name))
+                            let index = decoder.read_u8()?;
+                            match index {
+                                #(#match_arms,)*
+                                _ => Err(::sbor::DecodeError::InvalidIndex(index))
+                            }
+                        }
+                    }
+                }
+            } else {
+                quote! {
+                    impl ::sbor::Decode for #ident {
+                        #[inline]
+                        fn decode_value(decoder: &mut ::sbor::Decoder) -> Result<Self, ::sbor::DecodeError> {
+                            use ::sbor::{self, Decode};
+
+                            let name = <String>::decode_value(decoder)?;
+                            match name.as_str() {
+                                #(#match_arms,)*
+                                _ => Err(::sbor::DecodeError::InvalidEnum(name))
+                            }
                         }
                     }
                 }
@@ -225,4 +250,34 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn test_decode_indexed_enum() {
+        let input = TokenStream::from_str("#[sbor(indexed)] enum Test {A, B (u32)}").unwrap();
+        let output = handle_decode(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                impl ::sbor::Decode for Test {
+                    #[inline]
+                    fn decode_value(decoder: &mut ::sbor::Decoder) -> Result<Self, ::sbor::DecodeError> {
+                        use ::sbor::{self, Decode};
+                        let index = decoder.read_u8()?;
+                        match index {
+                            0u8 => {
+                                decoder.check_len(0)?;
+                                Ok(Self::A)
+                            },
+                            1u8 => {
+                                decoder.check_len(1)?;
+                                Ok(Self::B(<u32>::decode(decoder)?))
+                            },
+                            _ => Err(::sbor::DecodeError::InvalidIndex(index))
+                        }
+                    }
+                }
+            },
+        );
+    }
 }
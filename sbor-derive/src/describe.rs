@@ -35,16 +35,30 @@ pub fn handle_describe(input: TokenStream) -> Result<TokenStream> {
                 quote! {
                     impl ::sbor::Describe for #ident {
                         fn describe() -> ::sbor::describe::Type {
+                            Self::describe_with(&mut ::sbor::describe::DescribeContext::new())
+                        }
+
+                        fn describe_with(
+                            context: &mut ::sbor::describe::DescribeContext,
+                        ) -> ::sbor::describe::Type {
                             use ::sbor::rust::borrow::ToOwned;
                             use ::sbor::rust::vec;
                             use ::sbor::Describe;
 
-                            ::sbor::describe::Type::Struct {
+                            let path = concat!(module_path!(), "::", #ident_str);
+                            if !context.enter(path) {
+                                return ::sbor::describe::Type::Ref {
+                                    name: path.to_owned(),
+                                };
+                            }
+                            let ty = ::sbor::describe::Type::Struct {
                                 name: #ident_str.to_owned(),
                                 fields: ::sbor::describe::Fields::Named {
-                                    named: vec![#((#names.to_owned(), <#types>::describe())),*]
+                                    named: vec![#((#names.to_owned(), <#types>::describe_with(context))),*]
                                 },
-                            }
+                            };
+                            context.exit();
+                            ty
                         }
                     }
                 }
@@ -57,16 +71,30 @@ pub fn handle_describe(input: TokenStream) -> Result<TokenStream> {
                 quote! {
                     impl ::sbor::Describe for #ident {
                         fn describe() -> ::sbor::describe::Type {
+                            Self::describe_with(&mut ::sbor::describe::DescribeContext::new())
+                        }
+
+                        fn describe_with(
+                            context: &mut ::sbor::describe::DescribeContext,
+                        ) -> ::sbor::describe::Type {
                             use ::sbor::rust::borrow::ToOwned;
                             use ::sbor::rust::vec;
                             use ::sbor::Describe;
 
-                            ::sbor::describe::Type::Struct {
+                            let path = concat!(module_path!(), "::", #ident_str);
+                            if !context.enter(path) {
+                                return ::sbor::describe::Type::Ref {
+                                    name: path.to_owned(),
+                                };
+                            }
+                            let ty = ::sbor::describe::Type::Struct {
                                 name: #ident_str.to_owned(),
                                 fields: ::sbor::describe::Fields::Unnamed {
-                                    unnamed: vec![#(<#types>::describe()),*]
+                                    unnamed: vec![#(<#types>::describe_with(context)),*]
                                 },
-                            }
+                            };
+                            context.exit();
+                            ty
                         }
                     }
                 }
@@ -106,7 +134,7 @@ pub fn handle_describe(input: TokenStream) -> Result<TokenStream> {
                         quote! {
                             {
                                 ::sbor::describe::Fields::Named {
-                                    named: vec![#((#names.to_owned(), <#types>::describe())),*]
+                                    named: vec![#((#names.to_owned(), <#types>::describe_with(context))),*]
                                 }
                             }
                         }
@@ -119,7 +147,7 @@ pub fn handle_describe(input: TokenStream) -> Result<TokenStream> {
                         quote! {
                             {
                                 ::sbor::describe::Fields::Unnamed {
-                                    unnamed: vec![#(<#types>::describe()),*]
+                                    unnamed: vec![#(<#types>::describe_with(context)),*]
                                 }
                             }
                         }
@@ -137,11 +165,23 @@ pub fn handle_describe(input: TokenStream) -> Result<TokenStream> {
             quote! {
                 impl ::sbor::Describe for #ident {
                     fn describe() -> ::sbor::describe::Type {
+                        Self::describe_with(&mut ::sbor::describe::DescribeContext::new())
+                    }
+
+                    fn describe_with(
+                        context: &mut ::sbor::describe::DescribeContext,
+                    ) -> ::sbor::describe::Type {
                         use ::sbor::rust::borrow::ToOwned;
                         use ::sbor::rust::vec;
                         use ::sbor::Describe;
 
-                        ::sbor::describe::Type::Enum {
+                        let path = concat!(module_path!(), "::", #ident_str);
+                        if !context.enter(path) {
+                            return ::sbor::describe::Type::Ref {
+                                name: path.to_owned(),
+                            };
+                        }
+                        let ty = ::sbor::describe::Type::Enum {
                             name: #ident_str.to_owned(),
                             variants: vec![
                                 #(::sbor::describe::Variant {
@@ -149,7 +189,9 @@ pub fn handle_describe(input: TokenStream) -> Result<TokenStream> {
                                     fields: #fields
                                 }),*
                             ]
-                        }
+                        };
+                        context.exit();
+                        ty
                     }
                 }
             }
@@ -187,16 +229,30 @@ mod tests {
             quote! {
                 impl ::sbor::Describe for Test {
                     fn describe() -> ::sbor::describe::Type {
+                        Self::describe_with(&mut ::sbor::describe::DescribeContext::new())
+                    }
+
+                    fn describe_with(
+                        context: &mut ::sbor::describe::DescribeContext,
+                    ) -> ::sbor::describe::Type {
                         use ::sbor::rust::borrow::ToOwned;
                         use ::sbor::rust::vec;
                         use ::sbor::Describe;
 
-                        ::sbor::describe::Type::Struct {
+                        let path = concat!(module_path!(), "::", "Test");
+                        if !context.enter(path) {
+                            return ::sbor::describe::Type::Ref {
+                                name: path.to_owned(),
+                            };
+                        }
+                        let ty = ::sbor::describe::Type::Struct {
                             name: "Test".to_owned(),
                             fields: ::sbor::describe::Fields::Named {
-                                named: vec![("a".to_owned(), <u32>::describe())]
+                                named: vec![("a".to_owned(), <u32>::describe_with(context))]
                             },
-                        }
+                        };
+                        context.exit();
+                        ty
                     }
                 }
             },
@@ -213,11 +269,23 @@ mod tests {
             quote! {
                 impl ::sbor::Describe for Test {
                     fn describe() -> ::sbor::describe::Type {
+                        Self::describe_with(&mut ::sbor::describe::DescribeContext::new())
+                    }
+
+                    fn describe_with(
+                        context: &mut ::sbor::describe::DescribeContext,
+                    ) -> ::sbor::describe::Type {
                         use ::sbor::rust::borrow::ToOwned;
                         use ::sbor::rust::vec;
                         use ::sbor::Describe;
 
-                        ::sbor::describe::Type::Enum {
+                        let path = concat!(module_path!(), "::", "Test");
+                        if !context.enter(path) {
+                            return ::sbor::describe::Type::Ref {
+                                name: path.to_owned(),
+                            };
+                        }
+                        let ty = ::sbor::describe::Type::Enum {
                             name: "Test".to_owned(),
                             variants: vec![
                                 ::sbor::describe::Variant {
@@ -227,17 +295,19 @@ mod tests {
                                 ::sbor::describe::Variant {
                                     name: "B".to_owned(),
                                     fields: {
-                                        ::sbor::describe::Fields::Unnamed { unnamed: vec![<u32>::describe()] }
+                                        ::sbor::describe::Fields::Unnamed { unnamed: vec![<u32>::describe_with(context)] }
                                     }
                                 },
                                 ::sbor::describe::Variant {
                                     name: "C".to_owned(),
                                     fields: {
-                                        ::sbor::describe::Fields::Named { named: vec![("x".to_owned(), <u8>::describe())] }
+                                        ::sbor::describe::Fields::Named { named: vec![("x".to_owned(), <u8>::describe_with(context))] }
                                     }
                                 }
                             ]
-                        }
+                        };
+                        context.exit();
+                        ty
                     }
                 }
             },
@@ -254,14 +324,28 @@ mod tests {
             quote! {
                 impl ::sbor::Describe for Test {
                     fn describe() -> ::sbor::describe::Type {
+                        Self::describe_with(&mut ::sbor::describe::DescribeContext::new())
+                    }
+
+                    fn describe_with(
+                        context: &mut ::sbor::describe::DescribeContext,
+                    ) -> ::sbor::describe::Type {
                         use ::sbor::rust::borrow::ToOwned;
                         use ::sbor::rust::vec;
                         use ::sbor::Describe;
 
-                        ::sbor::describe::Type::Struct {
+                        let path = concat!(module_path!(), "::", "Test");
+                        if !context.enter(path) {
+                            return ::sbor::describe::Type::Ref {
+                                name: path.to_owned(),
+                            };
+                        }
+                        let ty = ::sbor::describe::Type::Struct {
                             name: "Test".to_owned(),
                             fields: ::sbor::describe::Fields::Named { named: vec![] },
-                        }
+                        };
+                        context.exit();
+                        ty
                     }
                 }
             },
@@ -280,11 +364,23 @@ mod tests {
             quote! {
                 impl ::sbor::Describe for Test {
                     fn describe() -> ::sbor::describe::Type {
+                        Self::describe_with(&mut ::sbor::describe::DescribeContext::new())
+                    }
+
+                    fn describe_with(
+                        context: &mut ::sbor::describe::DescribeContext,
+                    ) -> ::sbor::describe::Type {
                         use ::sbor::rust::borrow::ToOwned;
                         use ::sbor::rust::vec;
                         use ::sbor::Describe;
 
-                        ::sbor::describe::Type::Enum {
+                        let path = concat!(module_path!(), "::", "Test");
+                        if !context.enter(path) {
+                            return ::sbor::describe::Type::Ref {
+                                name: path.to_owned(),
+                            };
+                        }
+                        let ty = ::sbor::describe::Type::Enum {
                             name: "Test".to_owned(),
                             variants: vec![
                                 ::sbor::describe::Variant {
@@ -304,7 +400,9 @@ mod tests {
                                     }
                                 }
                             ]
-                        }
+                        };
+                        context.exit();
+                        ty
                     }
                 }
             },
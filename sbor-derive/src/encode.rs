@@ -14,7 +14,9 @@ macro_rules! trace {
 pub fn handle_encode(input: TokenStream) -> Result<TokenStream> {
     trace!("handle_encode() starts");
 
-    let DeriveInput { ident, data, .. } = parse2(input)?;
+    let DeriveInput {
+        ident, attrs, data, ..
+    } = parse2(input)?;
     trace!("Encoding: {}", ident);
 
     let output = match data {
@@ -63,10 +65,16 @@ pub fn handle_encode(input: TokenStream) -> Result<TokenStream> {
             }
         },
         Data::Enum(DataEnum { variants, .. }) => {
-            let match_arms = variants.iter().map(| v| {
+            let indexed = is_indexed_enum(&attrs, &variants)?;
+            let match_arms = variants.iter().enumerate().map(|(i, v)| {
                 let v_id = &v.ident;
-                let name_string = v_id.to_string();
-                let name: Expr = parse_quote! { #name_string };
+                let discriminator = if indexed {
+                    let index = i as u8;
+                    quote! { encoder.write_u8(#index); }
+                } else {
+                    let name_string = v_id.to_string();
+                    quote! { #name_string.to_string().encode_value(encoder); }
+                };
 
                 match &v.fields {
                     syn::Fields::Named(FieldsNamed { named, .. }) => {
@@ -76,7 +84,7 @@ pub fn handle_encode(input: TokenStream) -> Result<TokenStream> {
                         let ns_len = Index::from(ns.len());
                         quote! {
                             Self::#v_id {#(#ns_ids,)* ..} => {
-                                #name.to_string().encode_value(encoder);
+                                #discriminator
                                 encoder.write_len(#ns_len);
                                 #(#ns_ids2.encode(encoder);)*
                             }
@@ -93,7 +101,7 @@ pub fn handle_encode(input: TokenStream) -> Result<TokenStream> {
                         let ns_len = Index::from(ns_args.len());
                         quote! {
                             Self::#v_id (#(#args),*) => {
-                                #name.to_string().encode_value(encoder);
+                                #discriminator
                                 encoder.write_len(#ns_len);
                                 #(#ns_args.encode(encoder);)*
                             }
@@ -102,7 +110,7 @@ pub fn handle_encode(input: TokenStream) -> Result<TokenStream> {
                     syn::Fields::Unit => {
                         quote! {
                             Self::#v_id => {
-                                #name.to_string().encode_value(encoder);
+                                #discriminator
                                 encoder.write_len(0);
                             }
                         }
@@ -196,4 +204,32 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn test_encode_indexed_enum() {
+        let input = TokenStream::from_str("#[sbor(indexed)] enum Test {A, B (u32)}").unwrap();
+        let output = handle_encode(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                impl ::sbor::Encode for Test {
+                    fn encode_value(&self, encoder: &mut ::sbor::Encoder) {
+                        use ::sbor::{self, Encode};
+                        match self {
+                            Self::A => {
+                                encoder.write_u8(0u8);
+                                encoder.write_len(0);
+                            }
+                            Self::B(a0) => {
+                                encoder.write_u8(1u8);
+                                encoder.write_len(1);
+                                a0.encode(encoder);
+                            }
+                        }
+                    }
+                }
+            },
+        );
+    }
 }
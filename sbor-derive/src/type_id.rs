@@ -2,6 +2,8 @@ use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::*;
 
+use crate::utils::*;
+
 macro_rules! trace {
     ($($arg:expr),*) => {{
         #[cfg(feature = "trace")]
@@ -12,7 +14,9 @@ macro_rules! trace {
 pub fn handle_type_id(input: TokenStream) -> Result<TokenStream> {
     trace!("handle_type_id() starts");
 
-    let DeriveInput { ident, data, .. } = parse2(input).expect("Unable to parse input");
+    let DeriveInput {
+        ident, attrs, data, ..
+    } = parse2(input).expect("Unable to parse input");
     trace!("Encoding: {}", ident);
 
     let output = match data {
@@ -24,14 +28,27 @@ pub fn handle_type_id(input: TokenStream) -> Result<TokenStream> {
                 }
             }
         },
-        Data::Enum(_) => quote! {
-            impl ::sbor::TypeId for #ident {
-                #[inline]
-                fn type_id() -> u8 {
-                    ::sbor::type_id::TYPE_ENUM
+        Data::Enum(DataEnum { variants, .. }) => {
+            if is_indexed_enum(&attrs, &variants)? {
+                quote! {
+                    impl ::sbor::TypeId for #ident {
+                        #[inline]
+                        fn type_id() -> u8 {
+                            ::sbor::type_id::TYPE_INDEXED_ENUM
+                        }
+                    }
+                }
+            } else {
+                quote! {
+                    impl ::sbor::TypeId for #ident {
+                        #[inline]
+                        fn type_id() -> u8 {
+                            ::sbor::type_id::TYPE_ENUM
+                        }
+                    }
                 }
             }
-        },
+        }
         Data::Union(_) => {
             return Err(Error::new(Span::call_site(), "Union is not supported!"));
         }
@@ -90,4 +107,22 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn test_type_id_indexed_enum() {
+        let input = TokenStream::from_str("#[sbor(indexed)] enum Test {A, B (u32)}").unwrap();
+        let output = handle_type_id(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                impl ::sbor::TypeId for Test {
+                    #[inline]
+                    fn type_id() -> u8 {
+                        ::sbor::type_id::TYPE_INDEXED_ENUM
+                    }
+                }
+            },
+        );
+    }
 }
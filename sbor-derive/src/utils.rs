@@ -30,6 +30,37 @@ pub fn print_generated_code<S: ToString>(kind: &str, code: S) {
     }
 }
 
+pub fn is_indexed(attrs: &[syn::Attribute]) -> bool {
+    let mut indexed = false;
+    for att in attrs {
+        if att.path.is_ident("sbor")
+            && att
+                .parse_args::<syn::Path>()
+                .map(|p| p.is_ident("indexed"))
+                .unwrap_or(false)
+        {
+            indexed = true;
+        }
+    }
+    indexed
+}
+
+/// Returns whether an enum is annotated with `#[sbor(indexed)]`, in which case variants are
+/// encoded by their position instead of their name.
+pub fn is_indexed_enum(
+    attrs: &[syn::Attribute],
+    variants: &syn::punctuated::Punctuated<syn::Variant, syn::token::Comma>,
+) -> syn::Result<bool> {
+    let indexed = is_indexed(attrs);
+    if indexed && variants.len() > u8::MAX as usize + 1 {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "Indexed enum can't have more than 256 variants!",
+        ));
+    }
+    Ok(indexed)
+}
+
 pub fn is_skipped(f: &syn::Field) -> bool {
     let mut skipped = false;
     for att in &f.attrs {
@@ -1,68 +1,55 @@
-#[macro_use]
-extern crate bencher;
-use bencher::Bencher;
+use criterion::{criterion_group, criterion_main, Criterion};
 
 mod adapter;
 mod data;
 
 const SIMPLE_REAPT: usize = 32;
-
-fn encode_simple_json(b: &mut Bencher) {
-    let t = data::get_simple_dataset(SIMPLE_REAPT);
-    b.iter(|| adapter::json_encode(&t));
-}
-
-fn encode_simple_bincode(b: &mut Bencher) {
-    let t = data::get_simple_dataset(SIMPLE_REAPT);
-    b.iter(|| adapter::bincode_encode(&t));
-}
-
-fn encode_simple_sbor(b: &mut Bencher) {
-    let t = data::get_simple_dataset(SIMPLE_REAPT);
-    b.iter(|| sbor::encode_with_type(&t));
-}
-
-fn encode_simple_sbor_no_type(b: &mut Bencher) {
-    let t = data::get_simple_dataset(SIMPLE_REAPT);
-    b.iter(|| sbor::encode_no_type(&t));
-}
-
-fn decode_simple_json(b: &mut Bencher) {
-    let t = data::get_simple_dataset(SIMPLE_REAPT);
-    let bytes = adapter::json_encode(&t);
-    b.iter(|| adapter::json_decode::<data::simple::SimpleStruct>(&bytes));
-}
-
-fn decode_simple_bincode(b: &mut Bencher) {
-    let t = data::get_simple_dataset(SIMPLE_REAPT);
-    let bytes = adapter::bincode_encode(&t);
-    b.iter(|| adapter::bincode_decode::<data::simple::SimpleStruct>(&bytes));
-}
-
-fn decode_simple_sbor(b: &mut Bencher) {
-    let t = data::get_simple_dataset(SIMPLE_REAPT);
+const LARGE_COUNT: usize = 1000;
+const LARGE_REAPT: usize = 1024;
+
+fn encode_simple(c: &mut Criterion) {
+    let t = data::get_simple_dataset(SIMPLE_REAPT);
+    let mut group = c.benchmark_group("encode_simple");
+    group.bench_function("json", |b| b.iter(|| adapter::json_encode(&t)));
+    group.bench_function("bincode", |b| b.iter(|| adapter::bincode_encode(&t)));
+    group.bench_function("sbor", |b| b.iter(|| sbor::encode_with_type(&t)));
+    group.bench_function("sbor_no_type", |b| b.iter(|| sbor::encode_no_type(&t)));
+    group.finish();
+}
+
+fn decode_simple(c: &mut Criterion) {
+    let t = data::get_simple_dataset(SIMPLE_REAPT);
+    let json_bytes = adapter::json_encode(&t);
+    let bincode_bytes = adapter::bincode_encode(&t);
+    let sbor_bytes = sbor::encode_with_type(&t);
+    let sbor_no_type_bytes = sbor::encode_no_type(&t);
+    let mut group = c.benchmark_group("decode_simple");
+    group.bench_function("json", |b| {
+        b.iter(|| adapter::json_decode::<data::simple::SimpleStruct>(&json_bytes))
+    });
+    group.bench_function("bincode", |b| {
+        b.iter(|| adapter::bincode_decode::<data::simple::SimpleStruct>(&bincode_bytes))
+    });
+    group.bench_function("sbor", |b| {
+        b.iter(|| sbor::decode_with_type::<data::simple::SimpleStruct>(&sbor_bytes))
+    });
+    group.bench_function("sbor_no_type", |b| {
+        b.iter(|| sbor::decode_no_type::<data::simple::SimpleStruct>(&sbor_no_type_bytes))
+    });
+    group.finish();
+}
+
+fn encode_decode_large(c: &mut Criterion) {
+    let t = data::get_large_dataset(LARGE_COUNT, LARGE_REAPT);
     let bytes = sbor::encode_with_type(&t);
-    b.iter(|| sbor::decode_with_type::<data::simple::SimpleStruct>(&bytes));
-}
-
-fn decode_simple_sbor_no_type(b: &mut Bencher) {
-    let t = data::get_simple_dataset(SIMPLE_REAPT);
-    let bytes = sbor::encode_no_type(&t);
-    b.iter(|| sbor::decode_no_type::<data::simple::SimpleStruct>(&bytes));
+    let mut group = c.benchmark_group("large");
+    group.bench_function("encode_sbor", |b| b.iter(|| sbor::encode_with_type(&t)));
+    group.bench_function("decode_sbor", |b| {
+        b.iter(|| sbor::decode_with_type::<data::large::LargeStruct>(&bytes))
+    });
+    group.finish();
 }
 
-benchmark_group!(
-    encode_simple,
-    encode_simple_json,
-    encode_simple_bincode,
-    encode_simple_sbor,
-    encode_simple_sbor_no_type
-);
-benchmark_group!(
-    decode_simple,
-    decode_simple_json,
-    decode_simple_bincode,
-    decode_simple_sbor,
-    decode_simple_sbor_no_type
-);
-benchmark_main!(encode_simple, decode_simple);
+criterion_group!(simple, encode_simple, decode_simple);
+criterion_group!(large, encode_decode_large);
+criterion_main!(simple, large);
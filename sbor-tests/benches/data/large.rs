@@ -0,0 +1,12 @@
+use sbor::{Decode, Encode, TypeId};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::simple::SimpleStruct;
+
+#[derive(TypeId, Encode, Decode, Serialize, Deserialize)]
+pub struct LargeStruct {
+    pub items: Vec<SimpleStruct>,
+    pub index: BTreeMap<u32, String>,
+    pub blob: Vec<u8>,
+}
@@ -1,3 +1,4 @@
+pub mod large;
 pub mod simple;
 
 pub fn get_simple_dataset(repeat: usize) -> simple::SimpleStruct {
@@ -12,3 +13,11 @@ pub fn get_simple_dataset(repeat: usize) -> simple::SimpleStruct {
         },
     }
 }
+
+pub fn get_large_dataset(count: usize, repeat: usize) -> large::LargeStruct {
+    large::LargeStruct {
+        items: (0..count).map(|_| get_simple_dataset(repeat)).collect(),
+        index: (0..count as u32).map(|i| (i, i.to_string())).collect(),
+        blob: vec![7u8; count * repeat],
+    }
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "std")]
+
+use proptest::prelude::*;
+use sbor::*;
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, TypeId, Encode, Decode)]
+pub struct Sample {
+    pub a: u32,
+    pub b: String,
+    pub c: Vec<i64>,
+    pub d: Option<bool>,
+    pub e: BTreeMap<u8, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, TypeId, Encode, Decode)]
+#[sbor(indexed)]
+pub enum IndexedSample {
+    A { x: u32 },
+    B(String),
+    C,
+}
+
+fn indexed_sample() -> impl Strategy<Value = IndexedSample> {
+    prop_oneof![
+        any::<u32>().prop_map(|x| IndexedSample::A { x }),
+        ".{0,8}".prop_map(IndexedSample::B),
+        Just(IndexedSample::C),
+    ]
+}
+
+fn encode_value(value: &Value) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    encode_any(None, value, &mut Encoder::with_type(&mut bytes));
+    bytes
+}
+
+proptest! {
+    #[test]
+    fn test_value_roundtrip(value in any::<Value>()) {
+        let bytes = encode_value(&value);
+        prop_assert_eq!(decode_any(&bytes), Ok(value));
+    }
+
+    #[test]
+    fn test_truncated_value_is_rejected(
+        value in any::<Value>(),
+        cut in any::<prop::sample::Index>(),
+    ) {
+        let bytes = encode_value(&value);
+        let cut = cut.index(bytes.len());
+        prop_assert!(decode_any(&bytes[..cut]).is_err());
+    }
+
+    #[test]
+    fn test_arbitrary_bytes_do_not_panic(bytes in prop::collection::vec(any::<u8>(), 0..64)) {
+        let _ = decode_any(&bytes);
+        let _ = decode_with_type::<Sample>(&bytes);
+    }
+
+    #[test]
+    fn test_struct_roundtrip(
+        a in any::<u32>(),
+        b in ".{0,16}",
+        c in prop::collection::vec(any::<i64>(), 0..8),
+        d in any::<Option<bool>>(),
+        e in prop::collection::btree_map(any::<u8>(), ".{0,8}", 0..8),
+    ) {
+        let sample = Sample { a, b, c, d, e };
+        let bytes = encode_with_type(&sample);
+        prop_assert_eq!(decode_with_type::<Sample>(&bytes), Ok(sample));
+    }
+
+    #[test]
+    fn test_indexed_enum_roundtrip(sample in indexed_sample()) {
+        let bytes = encode_with_type(&sample);
+        let is_indexed = matches!(decode_any(&bytes), Ok(Value::IndexedEnum { .. }));
+        prop_assert!(is_indexed);
+        prop_assert_eq!(decode_with_type::<IndexedSample>(&bytes), Ok(sample));
+    }
+}
@@ -36,6 +36,17 @@ pub enum TestEnum {
     C,
 }
 
+#[derive(Debug, PartialEq, TypeId, Encode, Decode)]
+#[sbor(indexed)]
+pub enum TestIndexedEnum {
+    A {
+        #[sbor(skip)]
+        x: u32,
+        y: u32,
+    },
+    B(#[sbor(skip)] u32, u32),
+}
+
 #[test]
 fn test_struct_with_skip() {
     let a = TestStructNamed { x: 1, y: 2 };
@@ -204,3 +215,37 @@ fn test_enum_with_skip() {
         }),
     );
 }
+
+#[test]
+fn test_indexed_enum_with_skip() {
+    let a = TestIndexedEnum::A { x: 1, y: 2 };
+    let b = TestIndexedEnum::B(3, 4);
+
+    let mut bytes = Vec::with_capacity(512);
+    let mut encoder = Encoder::with_type(&mut bytes);
+    a.encode(&mut encoder);
+    b.encode(&mut encoder);
+
+    #[rustfmt::skip]
+    assert_eq!(
+        vec![
+            18, // indexed enum type
+            0, // variant index
+            1, 0, 0, 0,  // number of fields
+            9, 2, 0, 0, 0, // field value
+
+            18, // indexed enum type
+            1, // variant index
+            1, 0, 0, 0, // number of fields
+            9, 4, 0, 0, 0, // field value
+        ],
+        bytes
+    );
+
+    let mut decoder = Decoder::with_type(&bytes);
+    let a = TestIndexedEnum::decode(&mut decoder).unwrap();
+    let b = TestIndexedEnum::decode(&mut decoder).unwrap();
+
+    assert_eq!(TestIndexedEnum::A { x: 0, y: 2 }, a);
+    assert_eq!(TestIndexedEnum::B(0, 4), b);
+}
@@ -60,6 +60,10 @@ pub enum Value {
         name: String,
         fields: Vec<Value>,
     },
+    IndexedEnum {
+        index: u8,
+        fields: Vec<Value>,
+    },
 
     Option {
         value: Box<Option<Value>>,
@@ -141,6 +145,16 @@ pub fn encode_any(ty_ctx: Option<u8>, value: &Value, enc: &mut Encoder) {
                 encode_any(None, field, enc);
             }
         }
+        Value::IndexedEnum { index, fields } => {
+            if ty_ctx.is_none() {
+                enc.write_type(TYPE_INDEXED_ENUM);
+            }
+            enc.write_u8(*index);
+            enc.write_len(fields.len());
+            for field in fields {
+                encode_any(None, field, enc);
+            }
+        }
         // composite types
         Value::Option { value } => {
             if ty_ctx.is_none() {
@@ -285,13 +299,25 @@ fn encode_basic<T: Encode>(ty_ctx: Option<u8>, t: u8, v: &T, enc: &mut Encoder)
 
 /// Decode any SBOR data.
 pub fn decode_any(data: &[u8]) -> Result<Value, DecodeError> {
-    let mut decoder = Decoder::with_type(data);
-    let result = decode_next(None, &mut decoder);
+    decode_any_with_limits(data, DecodeLimits::default())
+}
+
+/// Decode any SBOR data, within the given limits.
+pub fn decode_any_with_limits(data: &[u8], limits: DecodeLimits) -> Result<Value, DecodeError> {
+    let mut decoder = Decoder::with_limits(data, true, limits);
+    let result = decode_next(None, &mut decoder)?;
     decoder.check_end()?;
-    result
+    Ok(result)
 }
 
 fn decode_next(ty_ctx: Option<u8>, dec: &mut Decoder) -> Result<Value, DecodeError> {
+    dec.increase_depth()?;
+    let result = decode_next_value(ty_ctx, dec);
+    dec.decrease_depth();
+    result
+}
+
+fn decode_next_value(ty_ctx: Option<u8>, dec: &mut Decoder) -> Result<Value, DecodeError> {
     let ty = match ty_ctx {
         Some(t) => t,
         None => dec.read_type()?,
@@ -359,6 +385,18 @@ fn decode_next(ty_ctx: Option<u8>, dec: &mut Decoder) -> Result<Value, DecodeErr
             }
             Ok(Value::Enum { name, fields })
         }
+        TYPE_INDEXED_ENUM => {
+            // index
+            let index = dec.read_u8()?;
+            // number of fields
+            let len = dec.read_len()?;
+            // fields
+            let mut fields = Vec::new();
+            for _ in 0..len {
+                fields.push(decode_next(None, dec)?);
+            }
+            Ok(Value::IndexedEnum { index, fields })
+        }
         // composite types
         TYPE_OPTION => {
             // index
@@ -517,7 +555,9 @@ where
         | Value::U128 { .. }
         | Value::String { .. } => {}
         // struct & enum
-        Value::Struct { fields } | Value::Enum { fields, .. } => {
+        Value::Struct { fields }
+        | Value::Enum { fields, .. }
+        | Value::IndexedEnum { fields, .. } => {
             for (i, field) in fields.iter().enumerate() {
                 path.push(i);
                 traverse_any(path, field, visitor)?;
@@ -575,6 +615,176 @@ where
     Ok(())
 }
 
+/// Traverses raw SBOR data and visits all custom values, without building a `Value`.
+///
+/// The data is validated the same way as `decode_any_with_limits`, and custom values are
+/// visited with the same paths as `traverse_any`.
+pub fn traverse_raw<V, E>(data: &[u8], limits: DecodeLimits, visitor: &mut V) -> Result<(), E>
+where
+    V: CustomValueVisitor<Err = E>,
+    E: From<DecodeError>,
+{
+    let mut decoder = Decoder::with_limits(data, true, limits);
+    traverse_next(None, &mut decoder, &mut MutableSborPath::new(), visitor)?;
+    decoder.check_end()?;
+    Ok(())
+}
+
+fn traverse_next<V, E>(
+    ty_ctx: Option<u8>,
+    dec: &mut Decoder,
+    path: &mut MutableSborPath,
+    visitor: &mut V,
+) -> Result<(), E>
+where
+    V: CustomValueVisitor<Err = E>,
+    E: From<DecodeError>,
+{
+    dec.increase_depth()?;
+    let result = traverse_next_value(ty_ctx, dec, path, visitor);
+    dec.decrease_depth();
+    result
+}
+
+fn traverse_elements<V, E>(
+    ty_ctx: Option<u8>,
+    len: usize,
+    dec: &mut Decoder,
+    path: &mut MutableSborPath,
+    visitor: &mut V,
+) -> Result<(), E>
+where
+    V: CustomValueVisitor<Err = E>,
+    E: From<DecodeError>,
+{
+    for i in 0..len {
+        path.push(i);
+        traverse_next(ty_ctx, dec, path, visitor)?;
+        path.pop();
+    }
+    Ok(())
+}
+
+fn traverse_next_value<V, E>(
+    ty_ctx: Option<u8>,
+    dec: &mut Decoder,
+    path: &mut MutableSborPath,
+    visitor: &mut V,
+) -> Result<(), E>
+where
+    V: CustomValueVisitor<Err = E>,
+    E: From<DecodeError>,
+{
+    let ty = match ty_ctx {
+        Some(t) => t,
+        None => dec.read_type()?,
+    };
+
+    match ty {
+        // primitive types
+        TYPE_UNIT => {}
+        TYPE_BOOL => {
+            <bool>::decode_value(dec)?;
+        }
+        TYPE_I8 | TYPE_U8 => {
+            dec.read_bytes(1)?;
+        }
+        TYPE_I16 | TYPE_U16 => {
+            dec.read_bytes(2)?;
+        }
+        TYPE_I32 | TYPE_U32 => {
+            dec.read_bytes(4)?;
+        }
+        TYPE_I64 | TYPE_U64 => {
+            dec.read_bytes(8)?;
+        }
+        TYPE_I128 | TYPE_U128 => {
+            dec.read_bytes(16)?;
+        }
+        TYPE_STRING => {
+            let len = dec.read_len()?;
+            let slice = dec.read_bytes(len)?;
+            core::str::from_utf8(slice).map_err(|_| DecodeError::InvalidUtf8)?;
+        }
+        // struct & enum
+        TYPE_STRUCT => {
+            let len = dec.read_len()?;
+            traverse_elements(None, len, dec, path, visitor)?;
+        }
+        TYPE_ENUM => {
+            // name
+            let len = dec.read_len()?;
+            let slice = dec.read_bytes(len)?;
+            core::str::from_utf8(slice).map_err(|_| DecodeError::InvalidUtf8)?;
+            // fields
+            let len = dec.read_len()?;
+            traverse_elements(None, len, dec, path, visitor)?;
+        }
+        TYPE_INDEXED_ENUM => {
+            dec.read_u8()?;
+            let len = dec.read_len()?;
+            traverse_elements(None, len, dec, path, visitor)?;
+        }
+        // composite types
+        TYPE_OPTION => match dec.read_u8()? {
+            OPTION_TYPE_NONE => {}
+            OPTION_TYPE_SOME => {
+                path.push(0);
+                traverse_next(None, dec, path, visitor)?;
+                path.pop();
+            }
+            index => return Err(DecodeError::InvalidIndex(index).into()),
+        },
+        TYPE_TUPLE => {
+            let len = dec.read_len()?;
+            traverse_elements(None, len, dec, path, visitor)?;
+        }
+        TYPE_RESULT => match dec.read_u8()? {
+            RESULT_TYPE_OK | RESULT_TYPE_ERR => {
+                path.push(0);
+                traverse_next(None, dec, path, visitor)?;
+                path.pop();
+            }
+            index => return Err(DecodeError::InvalidIndex(index).into()),
+        },
+        // collections
+        TYPE_ARRAY | TYPE_VEC | TYPE_TREE_SET | TYPE_HASH_SET => {
+            let element_type_id = dec.read_type()?;
+            let len = dec.read_len()?;
+            traverse_elements(Some(element_type_id), len, dec, path, visitor)?;
+        }
+        TYPE_TREE_MAP | TYPE_HASH_MAP => {
+            let key_type_id = dec.read_type()?;
+            let value_type_id = dec.read_type()?;
+            let len = dec.read_len()?;
+            // keys and values are flattened into a single list of elements
+            for i in 0..len {
+                path.push(2 * i);
+                traverse_next(Some(key_type_id), dec, path, visitor)?;
+                path.pop();
+                path.push(2 * i + 1);
+                traverse_next(Some(value_type_id), dec, path, visitor)?;
+                path.pop();
+            }
+        }
+        _ => {
+            if ty >= TYPE_CUSTOM_START {
+                let len = dec.read_len()?;
+                let slice = dec.read_bytes(len)?;
+                visitor.visit(path, ty, slice)?;
+            } else {
+                return Err(DecodeError::InvalidType {
+                    expected: None,
+                    actual: ty,
+                }
+                .into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
 pub trait CustomValueVisitor {
     type Err;
 
@@ -592,6 +802,7 @@ mod tests {
     use crate::*;
 
     use super::*;
+    use crate::path::SborPath;
 
     #[derive(TypeId, Encode)]
     struct TestStruct {
@@ -605,6 +816,14 @@ mod tests {
         C,
     }
 
+    #[derive(TypeId, Encode, Decode, Debug, PartialEq)]
+    #[sbor(indexed)]
+    enum TestIndexedEnum {
+        A { x: u32 },
+        B(u32),
+        C,
+    }
+
     #[derive(TypeId, Encode)]
     struct TestData {
         a: (),
@@ -768,4 +987,165 @@ mod tests {
             value
         );
     }
+
+    #[test]
+    pub fn test_parse_indexed_enum() {
+        let data = vec![
+            TestIndexedEnum::A { x: 1 },
+            TestIndexedEnum::B(2),
+            TestIndexedEnum::C,
+        ];
+        let bytes = encode_with_type(&data);
+        let value = decode_any(&bytes).unwrap();
+
+        assert_eq!(
+            Value::Vec {
+                element_type_id: TYPE_INDEXED_ENUM,
+                elements: vec![
+                    Value::IndexedEnum {
+                        index: 0,
+                        fields: vec![Value::U32 { value: 1 }]
+                    },
+                    Value::IndexedEnum {
+                        index: 1,
+                        fields: vec![Value::U32 { value: 2 }]
+                    },
+                    Value::IndexedEnum {
+                        index: 2,
+                        fields: vec![]
+                    },
+                ]
+            },
+            value
+        );
+
+        let mut bytes2 = Vec::new();
+        let mut enc = Encoder::with_type(&mut bytes2);
+        encode_any(None, &value, &mut enc);
+        assert_eq!(bytes2, bytes);
+        assert_eq!(decode_with_type::<Vec<TestIndexedEnum>>(&bytes), Ok(data));
+    }
+
+    #[test]
+    pub fn test_decode_invalid_enum_index() {
+        // indexed enum type, index, number of fields
+        let bytes: Vec<u8> = vec![TYPE_INDEXED_ENUM, 0x03, 0x00, 0x00, 0x00, 0x00];
+        assert_eq!(
+            decode_with_type::<TestIndexedEnum>(&bytes),
+            Err(DecodeError::InvalidIndex(3))
+        );
+    }
+
+    #[test]
+    pub fn test_parse_deeply_nested_value() {
+        // 100 nested `Some`s, followed by a unit
+        let mut bytes = Vec::new();
+        for _ in 0..100 {
+            bytes.extend([TYPE_OPTION, OPTION_TYPE_SOME]);
+        }
+        bytes.push(TYPE_UNIT);
+
+        assert_eq!(
+            decode_any(&bytes),
+            Err(DecodeError::MaxDepthExceeded(DEFAULT_MAX_DEPTH))
+        );
+        let limits = DecodeLimits {
+            max_depth: 101,
+            ..DecodeLimits::default()
+        };
+        assert!(decode_any_with_limits(&bytes, limits).is_ok());
+    }
+
+    struct CustomValueCollector {
+        values: Vec<(SborPath, u8, Vec<u8>)>,
+    }
+
+    impl CustomValueVisitor for CustomValueCollector {
+        type Err = DecodeError;
+
+        fn visit(
+            &mut self,
+            path: &mut MutableSborPath,
+            type_id: u8,
+            data: &[u8],
+        ) -> Result<(), Self::Err> {
+            self.values
+                .push((path.clone().into(), type_id, data.to_vec()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    pub fn test_traverse_raw_matches_traverse_any() {
+        let custom = |type_id: u8, byte: u8| Value::Custom {
+            type_id,
+            bytes: vec![byte; 4],
+        };
+        let value = Value::Struct {
+            fields: vec![
+                Value::String {
+                    value: String::from("abc"),
+                },
+                custom(0x80, 1),
+                Value::Option {
+                    value: Box::new(Some(custom(0x81, 2))),
+                },
+                Value::Result {
+                    value: Box::new(Err(custom(0x82, 3))),
+                },
+                Value::Vec {
+                    element_type_id: 0x80,
+                    elements: vec![custom(0x80, 4), custom(0x80, 5)],
+                },
+                Value::TreeMap {
+                    key_type_id: TYPE_U32,
+                    value_type_id: 0x83,
+                    elements: vec![Value::U32 { value: 1 }, custom(0x83, 6)],
+                },
+                Value::IndexedEnum {
+                    index: 1,
+                    fields: vec![Value::U8 { value: 7 }, custom(0x84, 8)],
+                },
+            ],
+        };
+        let mut bytes = Vec::new();
+        let mut enc = Encoder::with_type(&mut bytes);
+        encode_any(None, &value, &mut enc);
+
+        let mut expected = CustomValueCollector { values: Vec::new() };
+        traverse_any(&mut MutableSborPath::new(), &value, &mut expected).unwrap();
+        let mut actual = CustomValueCollector { values: Vec::new() };
+        traverse_raw(&bytes, DecodeLimits::default(), &mut actual).unwrap();
+
+        assert_eq!(expected.values.len(), 7);
+        assert_eq!(expected.values, actual.values);
+    }
+
+    #[test]
+    pub fn test_traverse_raw_rejects_invalid_data() {
+        let mut visitor = CustomValueCollector { values: Vec::new() };
+        let mut bytes = encode_with_type(&Some(1u32));
+        assert_eq!(
+            traverse_raw(&bytes, DecodeLimits::default(), &mut visitor),
+            Ok(())
+        );
+
+        bytes.push(0);
+        assert_eq!(
+            traverse_raw(&bytes, DecodeLimits::default(), &mut visitor),
+            Err(DecodeError::NotAllBytesUsed(1))
+        );
+        assert_eq!(
+            traverse_raw(&[TYPE_OPTION, 0x02], DecodeLimits::default(), &mut visitor),
+            Err(DecodeError::InvalidIndex(2))
+        );
+        assert_eq!(
+            traverse_raw(
+                &[TYPE_STRING, 0x01, 0x00, 0x00, 0x00, 0xff],
+                DecodeLimits::default(),
+                &mut visitor
+            ),
+            Err(DecodeError::InvalidUtf8)
+        );
+    }
 }
@@ -0,0 +1,108 @@
+use proptest::collection::{btree_set, vec};
+use proptest::prelude::*;
+
+use crate::any::Value;
+use crate::rust::boxed::Box;
+use crate::type_id::*;
+
+/// The maximum nesting depth of generated values.
+const MAX_DEPTH: u32 = 4;
+
+/// The maximum number of fields or elements of generated composite values.
+const MAX_LEN: usize = 8;
+
+/// A strategy for primitive values.
+pub fn primitive_value() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        Just(Value::Unit),
+        any::<bool>().prop_map(|value| Value::Bool { value }),
+        any::<i8>().prop_map(|value| Value::I8 { value }),
+        any::<i16>().prop_map(|value| Value::I16 { value }),
+        any::<i32>().prop_map(|value| Value::I32 { value }),
+        any::<i64>().prop_map(|value| Value::I64 { value }),
+        any::<i128>().prop_map(|value| Value::I128 { value }),
+        any::<u8>().prop_map(|value| Value::U8 { value }),
+        any::<u16>().prop_map(|value| Value::U16 { value }),
+        any::<u32>().prop_map(|value| Value::U32 { value }),
+        any::<u64>().prop_map(|value| Value::U64 { value }),
+        any::<u128>().prop_map(|value| Value::U128 { value }),
+        ".{0,16}".prop_map(|value| Value::String { value }),
+    ]
+}
+
+/// A strategy for homogeneous collections of primitive values.
+fn collection_value() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        vec(any::<u8>(), 0..MAX_LEN).prop_map(|elements| Value::Vec {
+            element_type_id: TYPE_U8,
+            elements: elements
+                .into_iter()
+                .map(|value| Value::U8 { value })
+                .collect(),
+        }),
+        vec(".{0,8}", 0..MAX_LEN).prop_map(|elements| Value::Vec {
+            element_type_id: TYPE_STRING,
+            elements: elements
+                .into_iter()
+                .map(|value| Value::String { value })
+                .collect(),
+        }),
+        vec(any::<i64>(), 0..MAX_LEN).prop_map(|elements| Value::Array {
+            element_type_id: TYPE_I64,
+            elements: elements
+                .into_iter()
+                .map(|value| Value::I64 { value })
+                .collect(),
+        }),
+        btree_set(any::<u32>(), 0..MAX_LEN).prop_map(|elements| Value::TreeSet {
+            element_type_id: TYPE_U32,
+            elements: elements
+                .into_iter()
+                .map(|value| Value::U32 { value })
+                .collect(),
+        }),
+        vec((any::<u16>(), any::<bool>()), 0..MAX_LEN).prop_map(|entries| Value::TreeMap {
+            key_type_id: TYPE_U16,
+            value_type_id: TYPE_BOOL,
+            elements: entries
+                .into_iter()
+                .flat_map(|(key, value)| [Value::U16 { value: key }, Value::Bool { value }])
+                .collect(),
+        }),
+    ]
+}
+
+/// A strategy for values whose leaves are drawn from the given strategy.
+///
+/// Used by crates with custom types to mix their custom values into the generated values.
+pub fn value_with_leaves<S: Strategy<Value = Value> + 'static>(leaf: S) -> BoxedStrategy<Value> {
+    prop_oneof![leaf.boxed(), collection_value()]
+        .prop_recursive(MAX_DEPTH, 64, MAX_LEN as u32, |inner| {
+            prop_oneof![
+                vec(inner.clone(), 0..MAX_LEN).prop_map(|fields| Value::Struct { fields }),
+                (".{0,8}", vec(inner.clone(), 0..MAX_LEN))
+                    .prop_map(|(name, fields)| Value::Enum { name, fields }),
+                (any::<u8>(), vec(inner.clone(), 0..MAX_LEN))
+                    .prop_map(|(index, fields)| Value::IndexedEnum { index, fields }),
+                proptest::option::of(inner.clone()).prop_map(|value| Value::Option {
+                    value: Box::new(value)
+                }),
+                vec(inner.clone(), 0..MAX_LEN).prop_map(|elements| Value::Tuple { elements }),
+                proptest::result::maybe_ok(inner.clone(), inner).prop_map(|value| {
+                    Value::Result {
+                        value: Box::new(value),
+                    }
+                }),
+            ]
+        })
+        .boxed()
+}
+
+impl Arbitrary for Value {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Value>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        value_with_leaves(primitive_value())
+    }
+}
@@ -33,6 +33,33 @@ pub enum DecodeError {
     InvalidCustomData(u8),
 
     DuplicateEntry,
+
+    MaxDepthExceeded(usize),
+
+    PayloadTooLarge { max: usize, actual: usize },
+
+    InvalidValue(&'static str),
+}
+
+/// The default maximum nesting depth of decoded values.
+pub const DEFAULT_MAX_DEPTH: usize = 64;
+
+/// Limits applied when decoding, to protect against malicious payloads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// The maximum nesting depth of values.
+    pub max_depth: usize,
+    /// The maximum length of the payload, in bytes.
+    pub max_payload_length: usize,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_payload_length: usize::MAX,
+        }
+    }
 }
 
 /// A data structure that can be decoded from a byte array using SBOR.
@@ -40,7 +67,10 @@ pub trait Decode: Sized + TypeId {
     #[inline]
     fn decode(decoder: &mut Decoder) -> Result<Self, DecodeError> {
         decoder.check_type(Self::type_id())?;
-        Self::decode_value(decoder)
+        decoder.increase_depth()?;
+        let result = Self::decode_value(decoder);
+        decoder.decrease_depth();
+        result
     }
 
     fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError>;
@@ -51,14 +81,22 @@ pub struct Decoder<'de> {
     input: &'de [u8],
     offset: usize,
     with_type: bool,
+    depth: usize,
+    limits: DecodeLimits,
 }
 
 impl<'de> Decoder<'de> {
     pub fn new(input: &'de [u8], with_type: bool) -> Self {
+        Self::with_limits(input, with_type, DecodeLimits::default())
+    }
+
+    pub fn with_limits(input: &'de [u8], with_type: bool, limits: DecodeLimits) -> Self {
         Self {
             input,
             offset: 0,
             with_type,
+            depth: 0,
+            limits,
         }
     }
 
@@ -86,6 +124,29 @@ impl<'de> Decoder<'de> {
         }
     }
 
+    /// Enters a nested value, failing if the maximum depth is exceeded.
+    ///
+    /// Entering the outermost value also checks the payload length, so that it's checked once
+    /// per decoded payload.
+    pub fn increase_depth(&mut self) -> Result<(), DecodeError> {
+        if self.depth == 0 && self.input.len() > self.limits.max_payload_length {
+            return Err(DecodeError::PayloadTooLarge {
+                max: self.limits.max_payload_length,
+                actual: self.input.len(),
+            });
+        }
+        if self.depth >= self.limits.max_depth {
+            return Err(DecodeError::MaxDepthExceeded(self.limits.max_depth));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Leaves a nested value.
+    pub fn decrease_depth(&mut self) {
+        self.depth -= 1;
+    }
+
     pub fn read_type(&mut self) -> Result<u8, DecodeError> {
         self.read_u8()
     }
@@ -559,4 +620,42 @@ mod tests {
         let value2 = <[NFA; 2]>::decode(&mut dec).unwrap();
         assert_eq!(value1, value2);
     }
+
+    #[test]
+    pub fn test_decode_max_depth() {
+        let value: Option<Option<Option<u8>>> = Some(Some(Some(1)));
+        let bytes = crate::encode_with_type(&value);
+        let limits = DecodeLimits {
+            max_depth: 3,
+            ..DecodeLimits::default()
+        };
+
+        let mut dec = Decoder::with_limits(&bytes, true, limits);
+        assert_eq!(
+            <Option<Option<Option<u8>>>>::decode(&mut dec),
+            Err(DecodeError::MaxDepthExceeded(3))
+        );
+
+        let limits = DecodeLimits {
+            max_depth: 4,
+            ..DecodeLimits::default()
+        };
+        let mut dec = Decoder::with_limits(&bytes, true, limits);
+        assert_eq!(<Option<Option<Option<u8>>>>::decode(&mut dec), Ok(value));
+    }
+
+    #[test]
+    pub fn test_decode_max_payload_length() {
+        let bytes = crate::encode_with_type(&1u32);
+        let limits = DecodeLimits {
+            max_payload_length: 4,
+            ..DecodeLimits::default()
+        };
+
+        let mut dec = Decoder::with_limits(&bytes, true, limits);
+        assert_eq!(
+            <u32>::decode(&mut dec),
+            Err(DecodeError::PayloadTooLarge { max: 4, actual: 5 })
+        );
+    }
 }
@@ -1,7 +1,9 @@
 use crate::sbor::{Decode, Encode, TypeId};
 
 use crate::rust::boxed::Box;
+use crate::rust::cell::RefCell;
 use crate::rust::collections::*;
+use crate::rust::rc::Rc;
 use crate::rust::string::String;
 use crate::rust::string::ToString;
 use crate::rust::vec;
@@ -83,6 +85,25 @@ pub enum Type {
         name: String,
         generics: Vec<Type>,
     },
+
+    /// A reference to an enclosing struct or enum, which describes a recursive type.
+    ///
+    /// The name is the module-qualified path of the referenced type, e.g. `my_crate::tree::Tree`,
+    /// so that types sharing a name in different modules are not confused.
+    Ref {
+        name: String,
+    },
+}
+
+impl Type {
+    /// Returns whether this struct or enum is the one referenced by the given `Type::Ref` path.
+    pub fn is_referenced_by(&self, path: &str) -> bool {
+        let ident = path.rsplit("::").next().unwrap_or(path);
+        match self {
+            Type::Struct { name, .. } | Type::Enum { name, .. } => name == ident,
+            _ => false,
+        }
+    }
 }
 
 /// Represents the type info of an enum variant.
@@ -111,6 +132,41 @@ pub enum Fields {
 /// A data structure that can be described using SBOR types.
 pub trait Describe {
     fn describe() -> Type;
+
+    /// Describes this type as part of an enclosing type.
+    ///
+    /// Types which contain other types must pass the context on, so that recursive structs and
+    /// enums are described with a `Type::Ref` rather than expanded forever.
+    fn describe_with(_context: &mut DescribeContext) -> Type {
+        Self::describe()
+    }
+}
+
+/// Keeps track of the structs and enums being described.
+#[derive(Debug, Clone, Default)]
+pub struct DescribeContext {
+    names: Vec<&'static str>,
+}
+
+impl DescribeContext {
+    pub fn new() -> Self {
+        Self { names: Vec::new() }
+    }
+
+    /// Starts describing the named type, returning `false` if it's already being described.
+    pub fn enter(&mut self, name: &'static str) -> bool {
+        if self.names.contains(&name) {
+            false
+        } else {
+            self.names.push(name);
+            true
+        }
+    }
+
+    /// Finishes describing the last entered type.
+    pub fn exit(&mut self) {
+        self.names.pop();
+    }
 }
 
 impl Describe for () {
@@ -149,16 +205,42 @@ describe_basic_type!(String, Type::String);
 
 impl<T: Describe> Describe for Option<T> {
     fn describe() -> Type {
-        let ty = T::describe();
+        Self::describe_with(&mut DescribeContext::new())
+    }
+
+    fn describe_with(context: &mut DescribeContext) -> Type {
+        let ty = T::describe_with(context);
         Type::Option {
             value: Box::new(ty),
         }
     }
 }
 
+macro_rules! describe_wrapper_type {
+    ($type:ident) => {
+        impl<T: Describe> Describe for $type<T> {
+            fn describe() -> Type {
+                T::describe()
+            }
+
+            fn describe_with(context: &mut DescribeContext) -> Type {
+                T::describe_with(context)
+            }
+        }
+    };
+}
+
+describe_wrapper_type!(Box);
+describe_wrapper_type!(Rc);
+describe_wrapper_type!(RefCell);
+
 impl<T: Describe, const N: usize> Describe for [T; N] {
     fn describe() -> Type {
-        let ty = T::describe();
+        Self::describe_with(&mut DescribeContext::new())
+    }
+
+    fn describe_with(context: &mut DescribeContext) -> Type {
+        let ty = T::describe_with(context);
         Type::Array {
             element: Box::new(ty),
             length: N as u16,
@@ -170,7 +252,11 @@ macro_rules! describe_tuple {
     ($($name:ident)+) => {
         impl<$($name: Describe),+> Describe for ($($name,)+) {
             fn describe() -> Type {
-                Type::Tuple { elements: vec![ $($name::describe(),)* ] }
+                Self::describe_with(&mut DescribeContext::new())
+            }
+
+            fn describe_with(context: &mut DescribeContext) -> Type {
+                Type::Tuple { elements: vec![ $($name::describe_with(context),)* ] }
             }
         }
     };
@@ -188,8 +274,12 @@ describe_tuple! { A B C D E F G H I J }
 
 impl<T: Describe, E: Describe> Describe for Result<T, E> {
     fn describe() -> Type {
-        let t = T::describe();
-        let e = E::describe();
+        Self::describe_with(&mut DescribeContext::new())
+    }
+
+    fn describe_with(context: &mut DescribeContext) -> Type {
+        let t = T::describe_with(context);
+        let e = E::describe_with(context);
         Type::Result {
             okay: Box::new(t),
             error: Box::new(e),
@@ -199,7 +289,11 @@ impl<T: Describe, E: Describe> Describe for Result<T, E> {
 
 impl<T: Describe> Describe for Vec<T> {
     fn describe() -> Type {
-        let ty = T::describe();
+        Self::describe_with(&mut DescribeContext::new())
+    }
+
+    fn describe_with(context: &mut DescribeContext) -> Type {
+        let ty = T::describe_with(context);
         Type::Vec {
             element: Box::new(ty),
         }
@@ -208,7 +302,11 @@ impl<T: Describe> Describe for Vec<T> {
 
 impl<T: Describe> Describe for BTreeSet<T> {
     fn describe() -> Type {
-        let ty = T::describe();
+        Self::describe_with(&mut DescribeContext::new())
+    }
+
+    fn describe_with(context: &mut DescribeContext) -> Type {
+        let ty = T::describe_with(context);
         Type::TreeSet {
             element: Box::new(ty),
         }
@@ -217,8 +315,12 @@ impl<T: Describe> Describe for BTreeSet<T> {
 
 impl<K: Describe, V: Describe> Describe for BTreeMap<K, V> {
     fn describe() -> Type {
-        let k = K::describe();
-        let v = V::describe();
+        Self::describe_with(&mut DescribeContext::new())
+    }
+
+    fn describe_with(context: &mut DescribeContext) -> Type {
+        let k = K::describe_with(context);
+        let v = V::describe_with(context);
         Type::TreeMap {
             key: Box::new(k),
             value: Box::new(v),
@@ -228,7 +330,11 @@ impl<K: Describe, V: Describe> Describe for BTreeMap<K, V> {
 
 impl<T: Describe> Describe for HashSet<T> {
     fn describe() -> Type {
-        let ty = T::describe();
+        Self::describe_with(&mut DescribeContext::new())
+    }
+
+    fn describe_with(context: &mut DescribeContext) -> Type {
+        let ty = T::describe_with(context);
         Type::HashSet {
             element: Box::new(ty),
         }
@@ -237,8 +343,12 @@ impl<T: Describe> Describe for HashSet<T> {
 
 impl<K: Describe, V: Describe> Describe for HashMap<K, V> {
     fn describe() -> Type {
-        let k = K::describe();
-        let v = V::describe();
+        Self::describe_with(&mut DescribeContext::new())
+    }
+
+    fn describe_with(context: &mut DescribeContext) -> Type {
+        let k = K::describe_with(context);
+        let v = V::describe_with(context);
         Type::HashMap {
             key: Box::new(k),
             value: Box::new(v),
@@ -252,6 +362,29 @@ mod tests {
     use crate::rust::boxed::Box;
     use crate::rust::string::String;
     use crate::rust::vec;
+    use crate::Describe;
+
+    #[allow(dead_code)]
+    #[derive(Describe)]
+    enum Tree {
+        Leaf(u32),
+        Node(Box<Tree>, Box<Tree>),
+    }
+
+    #[allow(dead_code)]
+    #[derive(Describe)]
+    struct Forest {
+        first: Tree,
+        rest: Vec<Tree>,
+    }
+
+    mod other {
+        #[allow(dead_code)]
+        #[derive(crate::Describe)]
+        pub struct Tree {
+            pub inner: super::Tree,
+        }
+    }
 
     #[test]
     pub fn test_basic_types() {
@@ -299,4 +432,65 @@ mod tests {
             <(u8, u128)>::describe(),
         );
     }
+
+    #[test]
+    pub fn test_recursive_type() {
+        let tree = Type::Enum {
+            name: String::from("Tree"),
+            variants: vec![
+                Variant {
+                    name: String::from("Leaf"),
+                    fields: Fields::Unnamed {
+                        unnamed: vec![Type::U32],
+                    },
+                },
+                Variant {
+                    name: String::from("Node"),
+                    fields: Fields::Unnamed {
+                        unnamed: vec![
+                            Type::Ref {
+                                name: String::from("sbor::describe::tests::Tree"),
+                            },
+                            Type::Ref {
+                                name: String::from("sbor::describe::tests::Tree"),
+                            },
+                        ],
+                    },
+                },
+            ],
+        };
+        assert_eq!(tree, Tree::describe());
+
+        // Sibling fields of the same type are described in full
+        assert_eq!(
+            Type::Struct {
+                name: String::from("Forest"),
+                fields: Fields::Named {
+                    named: vec![
+                        (String::from("first"), tree.clone()),
+                        (
+                            String::from("rest"),
+                            Type::Vec {
+                                element: Box::new(tree.clone())
+                            }
+                        ),
+                    ]
+                },
+            },
+            Forest::describe()
+        );
+
+        // A type sharing the name of an enclosing type in another module is described in full
+        assert_eq!(
+            Type::Struct {
+                name: String::from("Tree"),
+                fields: Fields::Named {
+                    named: vec![(String::from("inner"), tree.clone())]
+                },
+            },
+            other::Tree::describe()
+        );
+        assert!(tree.is_referenced_by("sbor::describe::tests::Tree"));
+        assert!(!tree.is_referenced_by("sbor::describe::tests::Forest"));
+    }
 }
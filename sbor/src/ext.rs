@@ -0,0 +1,161 @@
+#[cfg(feature = "uuid")]
+mod uuid_impls {
+    use uuid::Uuid;
+
+    use crate::decode::*;
+    use crate::describe::*;
+    use crate::encode::*;
+    use crate::rust::boxed::Box;
+    use crate::type_id::*;
+
+    /// A UUID is encoded as its 16 bytes, in big-endian order.
+    impl TypeId for Uuid {
+        #[inline]
+        fn type_id() -> u8 {
+            TYPE_ARRAY
+        }
+    }
+
+    impl Encode for Uuid {
+        fn encode_value(&self, encoder: &mut Encoder) {
+            self.as_bytes().encode_value(encoder);
+        }
+    }
+
+    impl Decode for Uuid {
+        fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+            <[u8; 16]>::decode_value(decoder).map(Uuid::from_bytes)
+        }
+    }
+
+    impl Describe for Uuid {
+        fn describe() -> Type {
+            Type::Array {
+                element: Box::new(Type::U8),
+                length: 16,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impls {
+    use chrono::{DateTime, Utc};
+
+    use crate::decode::*;
+    use crate::describe::*;
+    use crate::encode::*;
+    use crate::type_id::*;
+
+    /// A date time is encoded as a tuple of the seconds since Unix epoch and the nanoseconds
+    /// within that second.
+    impl TypeId for DateTime<Utc> {
+        #[inline]
+        fn type_id() -> u8 {
+            TYPE_TUPLE
+        }
+    }
+
+    impl Encode for DateTime<Utc> {
+        fn encode_value(&self, encoder: &mut Encoder) {
+            (self.timestamp(), self.timestamp_subsec_nanos()).encode_value(encoder);
+        }
+    }
+
+    impl Decode for DateTime<Utc> {
+        fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+            let (secs, nanos) = <(i64, u32)>::decode_value(decoder)?;
+            DateTime::from_timestamp(secs, nanos).ok_or(DecodeError::InvalidValue("DateTime"))
+        }
+    }
+
+    impl Describe for DateTime<Utc> {
+        fn describe() -> Type {
+            <(i64, u32)>::describe()
+        }
+    }
+}
+
+#[cfg(feature = "url")]
+mod url_impls {
+    use url::Url;
+
+    use crate::decode::*;
+    use crate::describe::*;
+    use crate::encode::*;
+    use crate::rust::string::String;
+    use crate::type_id::*;
+
+    /// A URL is encoded as its serialization string.
+    impl TypeId for Url {
+        #[inline]
+        fn type_id() -> u8 {
+            TYPE_STRING
+        }
+    }
+
+    impl Encode for Url {
+        fn encode_value(&self, encoder: &mut Encoder) {
+            self.as_str().encode_value(encoder);
+        }
+    }
+
+    impl Decode for Url {
+        fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+            let s = <String>::decode_value(decoder)?;
+            Url::parse(&s).map_err(|_| DecodeError::InvalidValue("Url"))
+        }
+    }
+
+    impl Describe for Url {
+        fn describe() -> Type {
+            Type::String
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use crate::*;
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    pub fn test_uuid() {
+        let uuid = uuid::Uuid::from_u128(0x67e5504410b1426f9247bb680e5fe0c8);
+        let bytes = encode_with_type(&uuid);
+        assert_eq!(decode_with_type::<[u8; 16]>(&bytes), Ok(*uuid.as_bytes()));
+        assert_eq!(decode_with_type::<uuid::Uuid>(&bytes), Ok(uuid));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    pub fn test_date_time() {
+        let date_time = chrono::DateTime::from_timestamp(1_660_000_000, 123_456_789).unwrap();
+        let bytes = encode_with_type(&date_time);
+        assert_eq!(
+            decode_with_type::<chrono::DateTime<chrono::Utc>>(&bytes),
+            Ok(date_time)
+        );
+
+        let bytes = encode_with_type(&(i64::MAX, 0u32));
+        assert_eq!(
+            decode_with_type::<chrono::DateTime<chrono::Utc>>(&bytes),
+            Err(DecodeError::InvalidValue("DateTime"))
+        );
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    pub fn test_url() {
+        let url = url::Url::parse("https://www.radixdlt.com/").unwrap();
+        let bytes = encode_with_type(&url);
+        assert_eq!(decode_with_type::<url::Url>(&bytes), Ok(url));
+
+        let bytes = encode_with_type(&"not a url".to_string());
+        assert_eq!(
+            decode_with_type::<url::Url>(&bytes),
+            Err(DecodeError::InvalidValue("Url"))
+        );
+    }
+}
@@ -7,12 +7,17 @@ compile_error!("Feature `std` and `alloc` can't be enabled at the same time.");
 
 /// SBOR any data encoding and decoding.
 pub mod any;
+/// Proptest strategies for SBOR values.
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
 /// SBOR decoding.
 pub mod decode;
 /// SBOR describing.
 pub mod describe;
 /// SBOR encoding.
 pub mod encode;
+/// SBOR implementations for third-party types.
+mod ext;
 /// SBOR paths.
 pub mod path;
 /// A facade of Rust types.
@@ -20,9 +25,9 @@ pub mod rust;
 /// SBOR type ids.
 pub mod type_id;
 
-pub use any::{decode_any, encode_any, Value};
-pub use decode::{Decode, DecodeError, Decoder};
-pub use describe::{Describe, Type};
+pub use any::{decode_any, decode_any_with_limits, encode_any, Value};
+pub use decode::{Decode, DecodeError, DecodeLimits, Decoder};
+pub use describe::{Describe, DescribeContext, Type};
 pub use encode::{Encode, Encoder};
 pub use type_id::TypeId;
 pub use crate::rust::string::String;
@@ -54,6 +59,17 @@ pub fn decode_with_type<T: Decode>(buf: &[u8]) -> Result<T, DecodeError> {
     Ok(v)
 }
 
+/// Decode an instance of `T` from a slice, with type info included, within the given limits.
+pub fn decode_with_type_and_limits<T: Decode>(
+    buf: &[u8],
+    limits: DecodeLimits,
+) -> Result<T, DecodeError> {
+    let mut dec = Decoder::with_limits(buf, true, limits);
+    let v = T::decode(&mut dec)?;
+    dec.check_end()?;
+    Ok(v)
+}
+
 /// Decode an instance of `T` from a slice, with no type info.
 pub fn decode_no_type<T: Decode>(buf: &[u8]) -> Result<T, DecodeError> {
     let mut dec = Decoder::no_type(buf);
@@ -3,7 +3,7 @@ use crate::rust::vec::Vec;
 use crate::rust::vec;
 use sbor::*;
 
-#[derive(Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub struct MutableSborPath(Vec<usize>);
 
 impl MutableSborPath {
@@ -27,7 +27,7 @@ impl From<MutableSborPath> for SborPath {
 }
 
 /// A series of indexes which describes some value in the sbor tree
-#[derive(Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub struct SborPath(Vec<usize>);
 
 impl SborPath {
@@ -73,7 +73,9 @@ impl<'a> SborValueRetriever<'a> {
         }
 
         match value {
-            Value::Struct { fields } | Value::Enum { fields, .. } => self.get_from_vector(fields),
+            Value::Struct { fields }
+            | Value::Enum { fields, .. }
+            | Value::IndexedEnum { fields, .. } => self.get_from_vector(fields),
             Value::Array { elements, .. } | Value::Vec { elements, .. } => {
                 self.get_from_vector(elements)
             }
@@ -94,7 +96,9 @@ impl<'a> SborValueRetriever<'a> {
         }
 
         match value {
-            Value::Struct { fields } | Value::Enum { fields, .. } => self.get_from_vector_mut(fields),
+            Value::Struct { fields }
+            | Value::Enum { fields, .. }
+            | Value::IndexedEnum { fields, .. } => self.get_from_vector_mut(fields),
             Value::Array { elements, .. } | Value::Vec { elements, .. } => {
                 self.get_from_vector_mut(elements)
             }
@@ -24,6 +24,7 @@ pub const TYPE_STRING: u8 = 0x0c;
 // enum and struct
 pub const TYPE_STRUCT: u8 = 0x10;
 pub const TYPE_ENUM: u8 = 0x11;
+pub const TYPE_INDEXED_ENUM: u8 = 0x12;
 // composite types
 pub const TYPE_OPTION: u8 = 0x20;
 pub const TYPE_ARRAY: u8 = 0x22;
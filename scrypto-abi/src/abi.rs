@@ -18,6 +18,31 @@ pub struct Blueprint {
     pub blueprint_name: String,
     pub functions: Vec<Function>,
     pub methods: Vec<Method>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub constants: Vec<Constant>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub error: Option<Type>,
+    /// The schema of the component state, including the key and value types of lazy maps.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub state: Option<Type>,
+    /// The interfaces which the blueprint declares to implement.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub interfaces: Vec<Interface>,
+    /// The feature flags which components of the blueprint can be instantiated with.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub features: Vec<String>,
+    /// The addresses of the packages whose types are imported by the package.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub imported_types: Vec<String>,
+}
+
+/// Represents the types exported by a package, which other packages can import so that they
+/// share the same definitions.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct Types {
+    pub package_address: String,
+    pub types: Vec<Type>,
 }
 
 /// Represents a function.
@@ -39,6 +64,30 @@ pub struct Method {
     pub output: Type,
 }
 
+/// Represents an interface, a set of methods which components of different blueprints can
+/// implement and be called through.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct Interface {
+    pub name: String,
+    pub methods: Vec<Method>,
+}
+
+/// Describes the interface generated by `interface!`.
+pub trait DescribeInterface {
+    fn describe_interface() -> Interface;
+}
+
+/// Represents a blueprint constant.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, TypeId, Encode, Decode)]
+pub struct Constant {
+    pub name: String,
+    pub ty: Type,
+    /// The SBOR-encoded value.
+    pub value: Vec<u8>,
+}
+
 /// Whether a method is going to change the component state.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, TypeId, Encode, Decode)]
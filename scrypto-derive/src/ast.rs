@@ -1,17 +1,71 @@
 use syn::parse::{Parse, ParseStream};
-use syn::{ItemImpl, ItemStruct, Result};
+use syn::spanned::Spanned;
+use syn::{Error, Item, ItemConst, ItemEnum, ItemImpl, ItemStruct, ItemTrait, Result};
 
 /// Represents the AST of blueprint.
 pub struct Blueprint {
     pub structure: ItemStruct,
+    pub constants: Vec<ItemConst>,
+    pub error: Option<ItemEnum>,
     pub implementation: ItemImpl,
+    pub interfaces: Vec<ItemImpl>,
 }
 
 impl Parse for Blueprint {
     fn parse(input: ParseStream) -> Result<Self> {
+        let structure = input.parse()?;
+        let mut constants = Vec::new();
+        let mut error: Option<ItemEnum> = None;
+        let implementation = loop {
+            match input.parse::<Item>()? {
+                Item::Const(c) => constants.push(c),
+                Item::Enum(e) => {
+                    if error.is_some() {
+                        return Err(Error::new(e.span(), "At most one error `enum` is allowed"));
+                    }
+                    error = Some(e);
+                }
+                Item::Impl(i) => break i,
+                item => {
+                    return Err(Error::new(
+                        item.span(),
+                        "Only `const` items, an error `enum` and `impl` are allowed here",
+                    ));
+                }
+            }
+        };
+
+        let mut interfaces = Vec::new();
+        while !input.is_empty() {
+            match input.parse::<Item>()? {
+                Item::Impl(i) if i.trait_.is_some() && i.items.is_empty() => interfaces.push(i),
+                item => {
+                    return Err(Error::new(
+                        item.span(),
+                        "Only empty `impl Interface for Blueprint {}` items are allowed here",
+                    ));
+                }
+            }
+        }
+
         Ok(Self {
-            structure: input.parse()?,
-            implementation: input.parse()?,
+            structure,
+            constants,
+            error,
+            implementation,
+            interfaces,
         })
     }
 }
+
+/// Represents the AST of interface.
+pub struct Interface {
+    pub definition: ItemTrait,
+}
+
+impl Parse for Interface {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let definition = input.parse()?;
+        Ok(Self { definition })
+    }
+}
@@ -1,5 +1,6 @@
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{format_ident, quote};
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::Brace;
 use syn::*;
@@ -27,25 +28,60 @@ pub fn handle_blueprint(input: TokenStream) -> Result<TokenStream> {
     let bp_name = bp_ident.to_string();
     trace!("Blueprint name: {}", bp_name);
 
-    let impl_ident_matches = match &*bp_impl.self_ty {
-        Type::Path(p) => p
-            .path
-            .get_ident()
-            .filter(|i| i.to_string() == bp_name)
-            .is_some(),
-        _ => false,
-    };
-    if !impl_ident_matches {
+    // constants and the error type are exported in the ABI, thus always public
+    let bp_constants: Vec<ItemConst> = bp
+        .constants
+        .iter()
+        .map(|c| ItemConst {
+            vis: parse_quote! { pub },
+            ..c.clone()
+        })
+        .collect();
+    let bp_error = bp.error.as_ref().map(|e| {
+        let mut attrs = e.attrs.clone();
+        attrs.push(parse_quote! {
+            #[derive(::sbor::TypeId, ::sbor::Encode, ::sbor::Decode, ::sbor::Describe)]
+        });
+        ItemEnum {
+            attrs,
+            vis: parse_quote! { pub },
+            ..e.clone()
+        }
+    });
+    let bp_error_ident = bp.error.as_ref().map(|e| &e.ident);
+
+    // feature flags are declared with `#[features(..)]` on the blueprint struct
+    let bp_features = parse_features(&bp_strut.attrs)?;
+
+    if !type_is_ident(&bp_impl.self_ty, &bp_name) {
         return Err(Error::new(
             bp_impl.span(),
             format!("Only `impl {}` is allowed here", bp_name),
         ));
     }
 
+    // interfaces are declared with empty trait impls, as the methods live in the blueprint impl
+    let mut bp_interfaces = Vec::<&Path>::new();
+    for i in &bp.interfaces {
+        if !type_is_ident(&i.self_ty, &bp_name) {
+            return Err(Error::new(
+                i.self_ty.span(),
+                format!("Only `impl Interface for {}` is allowed here", bp_name),
+            ));
+        }
+        if let Some((_, path, _)) = &i.trait_ {
+            bp_interfaces.push(path);
+        }
+    }
+
     let output_mod = quote! {
         pub mod blueprint {
             use super::*;
 
+            #(#bp_constants)*
+
+            #bp_error
+
             #[derive(::sbor::TypeId, ::sbor::Encode, ::sbor::Decode, ::sbor::Describe)]
             pub struct #bp_ident #bp_fields #bp_semi_token
 
@@ -66,7 +102,7 @@ pub fn handle_blueprint(input: TokenStream) -> Result<TokenStream> {
     trace!("Generated mod: \n{}", quote! { #output_mod });
 
     let dispatcher_ident = format_ident!("{}_main", bp_ident);
-    let (arm_guards, arm_bodies) = generate_dispatcher(bp_ident, bp_items)?;
+    let (arm_guards, arm_bodies) = generate_dispatcher(bp_ident, bp_error_ident, bp_items)?;
     let output_dispatcher = quote! {
         #[no_mangle]
         pub extern "C" fn #dispatcher_ident() -> *mut u8 {
@@ -99,20 +135,31 @@ pub fn handle_blueprint(input: TokenStream) -> Result<TokenStream> {
     trace!("Generated dispatcher: \n{}", quote! { #output_dispatcher });
 
     let abi_ident = format_ident!("{}_abi", bp_ident);
-    let (abi_functions, abi_methods) = generate_abi(bp_ident, bp_items)?;
+    let (abi_functions, abi_methods) = generate_abi(bp_ident, bp_error_ident, bp_items)?;
+    let abi_constants = generate_abi_constants(&bp_constants);
+    let abi_error: Expr = match bp_error_ident {
+        Some(ident) => parse_quote! { Some(blueprint::#ident::describe()) },
+        None => parse_quote! { None },
+    };
     let output_abi = quote! {
         #[no_mangle]
         pub extern "C" fn #abi_ident() -> *mut u8 {
             use ::sbor::{Describe, Type};
-            use ::scrypto::abi::{Function, Method};
+            use ::scrypto::abi::{Constant, Function, Interface, Method};
             use ::scrypto::rust::borrow::ToOwned;
             use ::scrypto::rust::vec;
             use ::scrypto::rust::vec::Vec;
 
             let functions: Vec<Function> = vec![ #(#abi_functions),* ];
             let methods: Vec<Method> = vec![ #(#abi_methods),* ];
+            let constants: Vec<Constant> = vec![ #(#abi_constants),* ];
+            let error: Option<Type> = #abi_error;
+            let interfaces: Vec<Interface> = vec![
+                #(<#bp_interfaces as ::scrypto::abi::DescribeInterface>::describe_interface()),*
+            ];
+            let features: Vec<::scrypto::rust::string::String> = vec![ #(#bp_features.to_owned()),* ];
             let schema: Type = blueprint::#bp_ident::describe();
-            let output = (schema, functions, methods);
+            let output = (schema, functions, methods, constants, error, interfaces, features);
 
             // serialize the output
             let output_bytes = ::scrypto::buffer::scrypto_encode_for_radix_engine(&output);
@@ -126,7 +173,7 @@ pub fn handle_blueprint(input: TokenStream) -> Result<TokenStream> {
         quote! { #output_dispatcher }
     );
 
-    let output_stubs = generate_stubs(bp_ident, bp_items)?;
+    let output_stubs = generate_stubs(bp_ident, bp_error_ident, bp_items)?;
 
     let output = quote! {
         #output_mod
@@ -147,7 +194,11 @@ pub fn handle_blueprint(input: TokenStream) -> Result<TokenStream> {
 
 // Parses function items in an `Impl` and returns the arm guards and bodies
 // used for call matching.
-fn generate_dispatcher(bp_ident: &Ident, items: &[ImplItem]) -> Result<(Vec<Expr>, Vec<Expr>)> {
+fn generate_dispatcher(
+    bp_ident: &Ident,
+    bp_error_ident: Option<&Ident>,
+    items: &[ImplItem],
+) -> Result<(Vec<Expr>, Vec<Expr>)> {
     let mut arm_guards = Vec::<Expr>::new();
     let mut arm_bodies = Vec::<Expr>::new();
 
@@ -215,11 +266,24 @@ fn generate_dispatcher(bp_ident: &Ident, items: &[ImplItem]) -> Result<(Vec<Expr
                     trace!("Generated stmt: {}", quote! { #stmt });
                     stmts.push(stmt);
                 }
-                // call the function
-                let stmt: Stmt = parse_quote! {
-                    rtn = ::scrypto::buffer::scrypto_encode_for_radix_engine(
-                        &blueprint::#bp_ident::#fn_ident(#(#args),*)
-                    );
+                // call the function, raising blueprint errors to the engine
+                let raises_error = match &m.sig.output {
+                    ReturnType::Default => false,
+                    ReturnType::Type(_, t) => unwrap_result_type(t, bp_error_ident).is_some(),
+                };
+                let stmt: Stmt = if raises_error {
+                    parse_quote! {
+                        rtn = match blueprint::#bp_ident::#fn_ident(#(#args),*) {
+                            Ok(output) => ::scrypto::buffer::scrypto_encode_for_radix_engine(&output),
+                            Err(error) => ::scrypto::core::Runtime::raise_error(error),
+                        };
+                    }
+                } else {
+                    parse_quote! {
+                        rtn = ::scrypto::buffer::scrypto_encode_for_radix_engine(
+                            &blueprint::#bp_ident::#fn_ident(#(#args),*)
+                        );
+                    }
                 };
                 trace!("Generated stmt: {}", quote! { #stmt });
                 stmts.push(stmt);
@@ -248,7 +312,11 @@ fn generate_dispatcher(bp_ident: &Ident, items: &[ImplItem]) -> Result<(Vec<Expr
 }
 
 // Parses function items of an `Impl` and returns ABI of functions.
-fn generate_abi(bp_ident: &Ident, items: &[ImplItem]) -> Result<(Vec<Expr>, Vec<Expr>)> {
+fn generate_abi(
+    bp_ident: &Ident,
+    bp_error_ident: Option<&Ident>,
+    items: &[ImplItem],
+) -> Result<(Vec<Expr>, Vec<Expr>)> {
     let mut functions = Vec::<Expr>::new();
     let mut methods = Vec::<Expr>::new();
 
@@ -290,6 +358,7 @@ fn generate_abi(bp_ident: &Ident, items: &[ImplItem]) -> Result<(Vec<Expr>, Vec<
                             ::sbor::describe::Type::Unit
                         },
                         ReturnType::Type(_, t) => {
+                            let t = unwrap_result_type(t, bp_error_ident).unwrap_or(t);
                             let ty = replace_self_with(t, &bp_ident.to_string());
                             quote! {
                                 <#ty>::describe()
@@ -329,8 +398,31 @@ fn generate_abi(bp_ident: &Ident, items: &[ImplItem]) -> Result<(Vec<Expr>, Vec<
     Ok((functions, methods))
 }
 
+// Returns ABI of blueprint constants.
+fn generate_abi_constants(constants: &[ItemConst]) -> Vec<Expr> {
+    constants
+        .iter()
+        .map(|c| {
+            let ident = &c.ident;
+            let name = ident.to_string();
+            let ty = &c.ty;
+            parse_quote! {
+                ::scrypto::abi::Constant {
+                    name: #name.to_owned(),
+                    ty: <#ty>::describe(),
+                    value: ::scrypto::buffer::scrypto_encode(&blueprint::#ident),
+                }
+            }
+        })
+        .collect()
+}
+
 // Parses function items of an `Impl` and returns ABI of functions.
-fn generate_stubs(bp_ident: &Ident, items: &[ImplItem]) -> Result<TokenStream> {
+fn generate_stubs(
+    bp_ident: &Ident,
+    bp_error_ident: Option<&Ident>,
+    items: &[ImplItem],
+) -> Result<TokenStream> {
     let bp_name = bp_ident.to_string();
     let mut functions = Vec::<ImplItem>::new();
     let mut methods = Vec::<ImplItem>::new();
@@ -374,7 +466,10 @@ fn generate_stubs(bp_ident: &Ident, items: &[ImplItem]) -> Result<TokenStream> {
 
                     let output = match &m.sig.output {
                         ReturnType::Default => parse_quote! { () },
-                        ReturnType::Type(_, t) => replace_self_with(t, &bp_ident.to_string()),
+                        ReturnType::Type(_, t) => replace_self_with(
+                            unwrap_result_type(t, bp_error_ident).unwrap_or(t),
+                            &bp_ident.to_string(),
+                        ),
                     };
 
                     if mutable.is_none() {
@@ -442,6 +537,55 @@ fn generate_stubs(bp_ident: &Ident, items: &[ImplItem]) -> Result<TokenStream> {
     Ok(output)
 }
 
+// Returns `T` if the given type is `Result<T, E>` where `E` is the blueprint error type.
+fn unwrap_result_type<'a>(t: &'a Type, bp_error_ident: Option<&Ident>) -> Option<&'a Type> {
+    let bp_error_ident = bp_error_ident?;
+    let segment = match t {
+        Type::Path(tp) => tp.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Result" {
+        return None;
+    }
+    let args = match &segment.arguments {
+        PathArguments::AngleBracketed(a) => &a.args,
+        _ => return None,
+    };
+    match (args.first(), args.last(), args.len()) {
+        (Some(GenericArgument::Type(ok)), Some(GenericArgument::Type(Type::Path(err))), 2)
+            if err.path.is_ident(bp_error_ident) =>
+        {
+            Some(ok)
+        }
+        _ => None,
+    }
+}
+
+// Returns the feature flags declared with `#[features("a", "b")]`.
+fn parse_features(attrs: &[Attribute]) -> Result<Vec<LitStr>> {
+    let mut features = Vec::<LitStr>::new();
+    for attr in attrs.iter().filter(|a| a.path.is_ident("features")) {
+        let list = attr.parse_args_with(Punctuated::<LitStr, Token![,]>::parse_terminated)?;
+        for feature in list {
+            if features.iter().any(|f| f.value() == feature.value()) {
+                return Err(Error::new(
+                    feature.span(),
+                    format!("Duplicate feature `{}`", feature.value()),
+                ));
+            }
+            features.push(feature);
+        }
+    }
+    Ok(features)
+}
+
+fn type_is_ident(t: &Type, name: &str) -> bool {
+    match t {
+        Type::Path(p) => p.path.get_ident().filter(|i| *i == name).is_some(),
+        _ => false,
+    }
+}
+
 fn replace_self_with(t: &Type, name: &str) -> Type {
     match t {
         Type::Path(tp) => {
@@ -538,7 +682,7 @@ mod tests {
                 #[no_mangle]
                 pub extern "C" fn Test_abi() -> *mut u8 {
                     use ::sbor::{Describe, Type};
-                    use ::scrypto::abi::{Function, Method};
+                    use ::scrypto::abi::{Constant, Function, Interface, Method};
                     use ::scrypto::rust::borrow::ToOwned;
                     use ::scrypto::rust::vec;
                     use ::scrypto::rust::vec::Vec;
@@ -549,8 +693,12 @@ mod tests {
                         inputs: vec![],
                         output: <u32>::describe(),
                     }];
+                    let constants: Vec<Constant> = vec![];
+                    let error: Option<Type> = None;
+                    let interfaces: Vec<Interface> = vec![];
+                    let features: Vec<::scrypto::rust::string::String> = vec![];
                     let schema: Type = blueprint::Test::describe();
-                    let output = (schema, functions, methods);
+                    let output = (schema, functions, methods, constants, error, interfaces, features);
                     let output_bytes = ::scrypto::buffer::scrypto_encode_for_radix_engine(&output);
                     ::scrypto::buffer::scrypto_wrap(output_bytes)
                 }
@@ -577,4 +725,251 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn test_blueprint_with_constants_and_error() {
+        let input = TokenStream::from_str(
+            "struct Test {} const MAX: u32 = 10; enum TestError { Failed } impl Test { pub fn x() -> Result<u32, TestError> { Ok(MAX) } }",
+        )
+        .unwrap();
+        let output = handle_blueprint(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                pub mod blueprint {
+                    use super::*;
+
+                    pub const MAX: u32 = 10;
+
+                    #[derive(::sbor::TypeId, ::sbor::Encode, ::sbor::Decode, ::sbor::Describe)]
+                    pub enum TestError {
+                        Failed
+                    }
+
+                    #[derive(::sbor::TypeId, ::sbor::Encode, ::sbor::Decode, ::sbor::Describe)]
+                    pub struct Test {}
+
+                    impl Test {
+                        pub fn x() -> Result<u32, TestError> {
+                            Ok(MAX)
+                        }
+                    }
+
+                    impl ::scrypto::component::ComponentState for Test {
+                        fn instantiate(self) -> ::scrypto::component::LocalComponent {
+                            ::scrypto::component::component_system().to_component_state_with_auth(
+                                "Test",
+                                self
+                            )
+                        }
+                    }
+                }
+                #[no_mangle]
+                pub extern "C" fn Test_main() -> *mut u8 {
+                    ::scrypto::misc::set_up_panic_hook();
+                    ::scrypto::component::init_component_system(::scrypto::component::ComponentSystem::new());
+                    ::scrypto::resource::init_resource_system(::scrypto::resource::ResourceSystem::new());
+                    let calldata: ::scrypto::engine::api::GetCallDataOutput = ::scrypto::engine::call_engine(
+                        ::scrypto::engine::api::GET_CALL_DATA,
+                        ::scrypto::engine::api::GetCallDataInput {},
+                    );
+                    let rtn;
+                    match calldata.function.as_str() {
+                        "x" => {
+                            rtn = match blueprint::Test::x() {
+                                Ok(output) => ::scrypto::buffer::scrypto_encode_for_radix_engine(&output),
+                                Err(error) => ::scrypto::core::Runtime::raise_error(error),
+                            };
+                        }
+                        _ => {
+                            panic!("Function/method not found")
+                        }
+                    }
+                    ::scrypto::buffer::scrypto_wrap(rtn)
+                }
+                #[no_mangle]
+                pub extern "C" fn Test_abi() -> *mut u8 {
+                    use ::sbor::{Describe, Type};
+                    use ::scrypto::abi::{Constant, Function, Interface, Method};
+                    use ::scrypto::rust::borrow::ToOwned;
+                    use ::scrypto::rust::vec;
+                    use ::scrypto::rust::vec::Vec;
+                    let functions: Vec<Function> = vec![::scrypto::abi::Function {
+                        name: "x".to_owned(),
+                        inputs: vec![],
+                        output: <u32>::describe(),
+                    }];
+                    let methods: Vec<Method> = vec![];
+                    let constants: Vec<Constant> = vec![::scrypto::abi::Constant {
+                        name: "MAX".to_owned(),
+                        ty: <u32>::describe(),
+                        value: ::scrypto::buffer::scrypto_encode(&blueprint::MAX),
+                    }];
+                    let error: Option<Type> = Some(blueprint::TestError::describe());
+                    let interfaces: Vec<Interface> = vec![];
+                    let features: Vec<::scrypto::rust::string::String> = vec![];
+                    let schema: Type = blueprint::Test::describe();
+                    let output = (schema, functions, methods, constants, error, interfaces, features);
+                    let output_bytes = ::scrypto::buffer::scrypto_encode_for_radix_engine(&output);
+                    ::scrypto::buffer::scrypto_wrap(output_bytes)
+                }
+                #[derive(::sbor::TypeId, ::sbor::Encode, ::sbor::Decode, ::sbor::Describe)]
+                pub struct Test {
+                    component_address: ::scrypto::component::ComponentAddress,
+                }
+                impl Test {
+                    pub fn x() -> u32 {
+                        let rtn = ::scrypto::core::Runtime::call_function(
+                            ::scrypto::core::Runtime::package_address(),
+                            "Test",
+                            "x",
+                            ::scrypto::args!()
+                        );
+                        ::scrypto::buffer::scrypto_decode(&rtn).unwrap()
+                    }
+                }
+                impl From<::scrypto::component::ComponentAddress> for Test {
+                    fn from(component_address: ::scrypto::component::ComponentAddress) -> Self {
+                        Self { component_address }
+                    }
+                }
+                impl From<Test> for ::scrypto::component::ComponentAddress {
+                    fn from(a: Test) -> ::scrypto::component::ComponentAddress {
+                        a.component_address
+                    }
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_blueprint_with_interface() {
+        let input =
+            TokenStream::from_str("struct Test {} impl Test { } impl Pool for Test {}").unwrap();
+        let output = handle_blueprint(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                pub mod blueprint {
+                    use super::*;
+
+                    #[derive(::sbor::TypeId, ::sbor::Encode, ::sbor::Decode, ::sbor::Describe)]
+                    pub struct Test {}
+
+                    impl Test {}
+
+                    impl ::scrypto::component::ComponentState for Test {
+                        fn instantiate(self) -> ::scrypto::component::LocalComponent {
+                            ::scrypto::component::component_system().to_component_state_with_auth(
+                                "Test",
+                                self
+                            )
+                        }
+                    }
+                }
+                #[no_mangle]
+                pub extern "C" fn Test_main() -> *mut u8 {
+                    ::scrypto::misc::set_up_panic_hook();
+                    ::scrypto::component::init_component_system(::scrypto::component::ComponentSystem::new());
+                    ::scrypto::resource::init_resource_system(::scrypto::resource::ResourceSystem::new());
+                    let calldata: ::scrypto::engine::api::GetCallDataOutput = ::scrypto::engine::call_engine(
+                        ::scrypto::engine::api::GET_CALL_DATA,
+                        ::scrypto::engine::api::GetCallDataInput {},
+                    );
+                    let rtn;
+                    match calldata.function.as_str() {
+                        _ => {
+                            panic!("Function/method not found")
+                        }
+                    }
+                    ::scrypto::buffer::scrypto_wrap(rtn)
+                }
+                #[no_mangle]
+                pub extern "C" fn Test_abi() -> *mut u8 {
+                    use ::sbor::{Describe, Type};
+                    use ::scrypto::abi::{Constant, Function, Interface, Method};
+                    use ::scrypto::rust::borrow::ToOwned;
+                    use ::scrypto::rust::vec;
+                    use ::scrypto::rust::vec::Vec;
+                    let functions: Vec<Function> = vec![];
+                    let methods: Vec<Method> = vec![];
+                    let constants: Vec<Constant> = vec![];
+                    let error: Option<Type> = None;
+                    let interfaces: Vec<Interface> = vec![
+                        <Pool as ::scrypto::abi::DescribeInterface>::describe_interface()
+                    ];
+                    let features: Vec<::scrypto::rust::string::String> = vec![];
+                    let schema: Type = blueprint::Test::describe();
+                    let output = (schema, functions, methods, constants, error, interfaces, features);
+                    let output_bytes = ::scrypto::buffer::scrypto_encode_for_radix_engine(&output);
+                    ::scrypto::buffer::scrypto_wrap(output_bytes)
+                }
+                #[derive(::sbor::TypeId, ::sbor::Encode, ::sbor::Decode, ::sbor::Describe)]
+                pub struct Test {
+                    component_address: ::scrypto::component::ComponentAddress,
+                }
+                impl Test {}
+                impl From<::scrypto::component::ComponentAddress> for Test {
+                    fn from(component_address: ::scrypto::component::ComponentAddress) -> Self {
+                        Self { component_address }
+                    }
+                }
+                impl From<Test> for ::scrypto::component::ComponentAddress {
+                    fn from(a: Test) -> ::scrypto::component::ComponentAddress {
+                        a.component_address
+                    }
+                }
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_interface_for_other_blueprint_should_fail() {
+        let input = TokenStream::from_str("struct A {} impl A { } impl Pool for B {}").unwrap();
+        handle_blueprint(input).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_non_empty_interface_impl_should_fail() {
+        let input =
+            TokenStream::from_str("struct A {} impl A { } impl Pool for A { fn x(&self) {} }")
+                .unwrap();
+        handle_blueprint(input).unwrap();
+    }
+
+    #[test]
+    fn test_blueprint_features() {
+        let input =
+            TokenStream::from_str("#[features(\"fees\", \"whitelist\")] struct A {} impl A { }")
+                .unwrap();
+        let output = handle_blueprint(input).unwrap();
+
+        assert!(output.to_string().contains(
+            &quote! {
+                let features: Vec<::scrypto::rust::string::String> =
+                    vec![ "fees".to_owned(), "whitelist".to_owned() ];
+            }
+            .to_string()
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_duplicate_features_should_fail() {
+        let input = TokenStream::from_str("#[features(\"fees\", \"fees\")] struct A {} impl A { }")
+            .unwrap();
+        handle_blueprint(input).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_multiple_error_enums_should_fail() {
+        let input =
+            TokenStream::from_str("struct A {} enum E1 { X } enum E2 { Y } impl A { }").unwrap();
+        handle_blueprint(input).unwrap();
+    }
 }
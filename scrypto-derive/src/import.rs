@@ -131,6 +131,74 @@ pub fn handle_import(input: TokenStream) -> Result<TokenStream> {
     Ok(output)
 }
 
+pub fn handle_import_types(input: TokenStream) -> Result<TokenStream> {
+    trace!("Started processing import_types macro");
+
+    let content = parse2::<LitStr>(input)?;
+    let types: abi::Types = match serde_json::from_str(content.value().as_str()) {
+        Ok(o) => o,
+        Err(e) => {
+            return Err(Error::new(content.span(), e));
+        }
+    };
+    trace!("Parsed types: {:?}", types);
+
+    // Types nested in one another are generated once.
+    let mut names = Vec::<Ident>::new();
+    let mut structs: Vec<Item> = vec![];
+    for ty in &types.types {
+        match ty {
+            des::Type::Struct { .. } | des::Type::Enum { .. } => {
+                let (_, new_structs) = get_native_type(ty)?;
+                for item in new_structs {
+                    let name = match &item {
+                        Item::Struct(s) => Some(s.ident.clone()),
+                        Item::Enum(e) => Some(e.ident.clone()),
+                        _ => None,
+                    };
+                    if let Some(name) = name {
+                        if names.contains(&name) {
+                            continue;
+                        }
+                        names.push(name);
+                    }
+                    structs.push(item);
+                }
+            }
+            _ => {
+                return Err(Error::new(
+                    content.span(),
+                    format!("Only structs and enums can be imported: {:?}", ty),
+                ));
+            }
+        }
+    }
+
+    // The export tells the engine which package the types come from, for the package ABI
+    let marker_ident =
+        parse_str::<Ident>(&format!("scrypto_imported_types_{}", types.package_address)).map_err(
+            |_| {
+                Error::new(
+                    content.span(),
+                    format!("Invalid package address: {}", types.package_address),
+                )
+            },
+        )?;
+
+    let output = quote! {
+        #(#structs)*
+
+        #[no_mangle]
+        pub extern "C" fn #marker_ident() {}
+    };
+    trace!("Finished processing import_types macro");
+
+    #[cfg(feature = "trace")]
+    crate::utils::print_generated_code("import_types!", &output);
+
+    Ok(output)
+}
+
 fn get_native_type(ty: &des::Type) -> Result<(Type, Vec<Item>)> {
     let mut structs = Vec::<Item>::new();
 
@@ -324,6 +392,8 @@ fn get_native_type(ty: &des::Type) -> Result<(Type, Vec<Item>)> {
                 "EcdsaPublicKey" => "::scrypto::crypto::EcdsaPublicKey",
                 "EcdsaSignature" => "::scrypto::crypto::EcdsaSignature",
                 "Decimal" => "::scrypto::math::Decimal",
+                "I256" => "::scrypto::math::I256",
+                "U256" => "::scrypto::math::U256",
                 "Bucket" => "::scrypto::resource::Bucket",
                 "Proof" => "::scrypto::resource::Proof",
                 "Vault" => "::scrypto::resource::Vault",
@@ -353,6 +423,13 @@ fn get_native_type(ty: &des::Type) -> Result<(Type, Vec<Item>)> {
                 parse_quote! { #ty<#(#types),*> }
             }
         }
+        des::Type::Ref { name } => {
+            // The referenced struct or enum is generated by the enclosing type, under its
+            // unqualified name.
+            let ident = format_ident!("{}", name.rsplit("::").next().unwrap_or(name));
+
+            parse_quote! { Box<#ident> }
+        }
     };
 
     Ok((t, structs))
@@ -449,4 +526,121 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn test_import_types() {
+        let input = TokenStream::from_str(
+            r###"
+                r#"
+                {
+                    "package_address": "056967d3d49213394892980af59be76e9b3e7cc4cb78237460d0c7",
+                    "types": [
+                        {
+                            "type": "Struct",
+                            "name": "Order",
+                            "fields": {
+                                "type": "Named",
+                                "named": [
+                                    ["amount", { "type": "Custom", "name": "Decimal", "generics": [] }],
+                                    ["side", {
+                                        "type": "Enum",
+                                        "name": "Side",
+                                        "variants": [
+                                            { "name": "Buy", "fields": { "type": "Unit" } },
+                                            { "name": "Sell", "fields": { "type": "Unit" } }
+                                        ]
+                                    }]
+                                ]
+                            }
+                        },
+                        {
+                            "type": "Enum",
+                            "name": "Side",
+                            "variants": [
+                                { "name": "Buy", "fields": { "type": "Unit" } },
+                                { "name": "Sell", "fields": { "type": "Unit" } }
+                            ]
+                        }
+                    ]
+                }
+                "#
+            "###,
+        )
+        .unwrap();
+        let output = handle_import_types(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                #[derive(Debug, ::sbor::TypeId, ::sbor::Encode, ::sbor::Decode, ::sbor::Describe)]
+                pub enum Side {
+                    Buy,
+                    Sell
+                }
+                #[derive(Debug, ::sbor::TypeId, ::sbor::Encode, ::sbor::Decode, ::sbor::Describe)]
+                pub struct Order {
+                    pub amount: ::scrypto::math::Decimal,
+                    pub side: Side,
+                }
+
+                #[no_mangle]
+                pub extern "C" fn scrypto_imported_types_056967d3d49213394892980af59be76e9b3e7cc4cb78237460d0c7() {}
+            },
+        );
+    }
+
+    #[test]
+    fn test_import_types_rejects_non_struct_types() {
+        let input = TokenStream::from_str(
+            r###"
+                r#"
+                {
+                    "package_address": "056967d3d49213394892980af59be76e9b3e7cc4cb78237460d0c7",
+                    "types": [
+                        { "type": "U32" }
+                    ]
+                }
+                "#
+            "###,
+        )
+        .unwrap();
+
+        assert!(handle_import_types(input).is_err());
+    }
+
+    #[test]
+    fn test_import_recursive_type() {
+        let ty = des::Type::Enum {
+            name: "Tree".to_owned(),
+            variants: vec![
+                des::Variant {
+                    name: "Leaf".to_owned(),
+                    fields: des::Fields::Unit,
+                },
+                des::Variant {
+                    name: "Node".to_owned(),
+                    fields: des::Fields::Unnamed {
+                        unnamed: vec![des::Type::Vec {
+                            element: Box::new(des::Type::Ref {
+                                name: "my_blueprint::Tree".to_owned(),
+                            }),
+                        }],
+                    },
+                },
+            ],
+        };
+        let (native_type, structs) = get_native_type(&ty).unwrap();
+
+        assert_code_eq(quote! { #native_type }, quote! { Tree });
+        assert_code_eq(
+            quote! { #(#structs)* },
+            quote! {
+                #[derive(Debug, ::sbor::TypeId, ::sbor::Encode, ::sbor::Decode, ::sbor::Describe)]
+                pub enum Tree {
+                    Leaf,
+                    Node(Vec<Box<Tree> >)
+                }
+            },
+        );
+    }
 }
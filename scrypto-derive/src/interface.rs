@@ -0,0 +1,256 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::*;
+
+use crate::ast;
+
+macro_rules! trace {
+    ($($arg:expr),*) => {{
+        #[cfg(feature = "trace")]
+        println!($($arg),*);
+    }};
+}
+
+pub fn handle_interface(input: TokenStream) -> Result<TokenStream> {
+    trace!("Started processing interface macro");
+
+    let interface = parse2::<ast::Interface>(input)?;
+    let definition = &interface.definition;
+    let if_attrs = &definition.attrs;
+    let if_vis = &definition.vis;
+    let if_ident = &definition.ident;
+    let if_name = if_ident.to_string();
+    trace!("Interface name: {}", if_name);
+
+    if !definition.generics.params.is_empty() || !definition.supertraits.is_empty() {
+        return Err(Error::new(
+            definition.span(),
+            "An interface can't have generics or supertraits",
+        ));
+    }
+
+    let mut stubs = Vec::<ImplItem>::new();
+    let mut abi_methods = Vec::<Expr>::new();
+    for item in &definition.items {
+        trace!("Processing item: {}", quote! { #item });
+        let m = match item {
+            TraitItem::Method(m) if m.default.is_none() => m,
+            _ => {
+                return Err(Error::new(
+                    item.span(),
+                    "Only methods without a body are allowed in an interface",
+                ));
+            }
+        };
+        if !m.sig.generics.params.is_empty() {
+            return Err(Error::new(
+                m.sig.generics.span(),
+                "Interface methods can't be generic",
+            ));
+        }
+
+        let attrs = &m.attrs;
+        let ident = &m.sig.ident;
+        let name = ident.to_string();
+        let mut mutability = None;
+        let mut input_types = vec![];
+        let mut input_args = vec![];
+        for input in &m.sig.inputs {
+            match input {
+                FnArg::Receiver(ref r) => {
+                    // Check receiver type and mutability
+                    if r.reference.is_none() {
+                        return Err(Error::new(
+                            r.span(),
+                            "Function input `self` is not supported. Try replacing it with &self.",
+                        ));
+                    }
+
+                    if r.mutability.is_some() {
+                        mutability = Some(quote! { ::scrypto::abi::Mutability::Mutable });
+                    } else {
+                        mutability = Some(quote! { ::scrypto::abi::Mutability::Immutable });
+                    }
+                }
+                FnArg::Typed(ref t) => {
+                    input_args.push(format_ident!("arg{}", input_args.len()));
+                    input_types.push(&t.ty);
+                }
+            }
+        }
+        let mutability = mutability.ok_or_else(|| {
+            Error::new(
+                m.sig.span(),
+                "Interface methods must take `&self` or `&mut self`",
+            )
+        })?;
+
+        let (output, output_type): (Type, TokenStream) = match &m.sig.output {
+            ReturnType::Default => (parse_quote! { () }, quote! { ::sbor::describe::Type::Unit }),
+            ReturnType::Type(_, t) => (
+                (**t).clone(),
+                quote! { <#t as ::sbor::Describe>::describe() },
+            ),
+        };
+
+        stubs.push(parse_quote! {
+            #(#attrs)*
+            pub fn #ident(&self #(, #input_args: #input_types)*) -> #output {
+                let rtn = ::scrypto::core::Runtime::call_method(
+                    self.component_address,
+                    #name,
+                    ::scrypto::args!(#(#input_args),*)
+                );
+                ::scrypto::buffer::scrypto_decode(&rtn).unwrap()
+            }
+        });
+        abi_methods.push(parse_quote! {
+            ::scrypto::abi::Method {
+                name: #name.to_owned(),
+                mutability: #mutability,
+                inputs: vec![#(<#input_types as ::sbor::Describe>::describe()),*],
+                output: #output_type,
+            }
+        });
+    }
+
+    let output = quote! {
+        #(#if_attrs)*
+        #[derive(::sbor::TypeId, ::sbor::Encode, ::sbor::Decode, ::sbor::Describe)]
+        #if_vis struct #if_ident {
+            component_address: ::scrypto::component::ComponentAddress,
+        }
+
+        impl #if_ident {
+            #(#stubs)*
+        }
+
+        impl ::scrypto::abi::DescribeInterface for #if_ident {
+            fn describe_interface() -> ::scrypto::abi::Interface {
+                use ::scrypto::rust::borrow::ToOwned;
+                use ::scrypto::rust::vec;
+
+                ::scrypto::abi::Interface {
+                    name: #if_name.to_owned(),
+                    methods: vec![ #(#abi_methods),* ],
+                }
+            }
+        }
+
+        impl From<::scrypto::component::ComponentAddress> for #if_ident {
+            fn from(component_address: ::scrypto::component::ComponentAddress) -> Self {
+                Self {
+                    component_address
+                }
+            }
+        }
+
+        impl From<#if_ident> for ::scrypto::component::ComponentAddress {
+            fn from(a: #if_ident) -> ::scrypto::component::ComponentAddress {
+                a.component_address
+            }
+        }
+    };
+    trace!("Finished processing interface macro");
+
+    #[cfg(feature = "trace")]
+    crate::utils::print_generated_code("interface!", &output);
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn assert_code_eq(a: TokenStream, b: TokenStream) {
+        assert_eq!(a.to_string(), b.to_string());
+    }
+
+    #[test]
+    fn test_interface() {
+        let input = TokenStream::from_str(
+            "pub trait Pool { fn swap(&mut self, input: Bucket) -> Bucket; fn price(&self) -> Decimal; }",
+        )
+        .unwrap();
+        let output = handle_interface(input).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                #[derive(::sbor::TypeId, ::sbor::Encode, ::sbor::Decode, ::sbor::Describe)]
+                pub struct Pool {
+                    component_address: ::scrypto::component::ComponentAddress,
+                }
+                impl Pool {
+                    pub fn swap(&self, arg0: Bucket) -> Bucket {
+                        let rtn = ::scrypto::core::Runtime::call_method(
+                            self.component_address,
+                            "swap",
+                            ::scrypto::args!(arg0)
+                        );
+                        ::scrypto::buffer::scrypto_decode(&rtn).unwrap()
+                    }
+                    pub fn price(&self) -> Decimal {
+                        let rtn = ::scrypto::core::Runtime::call_method(
+                            self.component_address,
+                            "price",
+                            ::scrypto::args!()
+                        );
+                        ::scrypto::buffer::scrypto_decode(&rtn).unwrap()
+                    }
+                }
+                impl ::scrypto::abi::DescribeInterface for Pool {
+                    fn describe_interface() -> ::scrypto::abi::Interface {
+                                use ::scrypto::rust::borrow::ToOwned;
+                        use ::scrypto::rust::vec;
+                        ::scrypto::abi::Interface {
+                            name: "Pool".to_owned(),
+                            methods: vec![
+                                ::scrypto::abi::Method {
+                                    name: "swap".to_owned(),
+                                    mutability: ::scrypto::abi::Mutability::Mutable,
+                                    inputs: vec![<Bucket as ::sbor::Describe>::describe()],
+                                    output: <Bucket as ::sbor::Describe>::describe(),
+                                },
+                                ::scrypto::abi::Method {
+                                    name: "price".to_owned(),
+                                    mutability: ::scrypto::abi::Mutability::Immutable,
+                                    inputs: vec![],
+                                    output: <Decimal as ::sbor::Describe>::describe(),
+                                }
+                            ],
+                        }
+                    }
+                }
+                impl From<::scrypto::component::ComponentAddress> for Pool {
+                    fn from(component_address: ::scrypto::component::ComponentAddress) -> Self {
+                        Self { component_address }
+                    }
+                }
+                impl From<Pool> for ::scrypto::component::ComponentAddress {
+                    fn from(a: Pool) -> ::scrypto::component::ComponentAddress {
+                        a.component_address
+                    }
+                }
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_function_in_interface_should_fail() {
+        let input = TokenStream::from_str("trait Pool { fn new() -> Pool; }").unwrap();
+        handle_interface(input).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_method_with_body_should_fail() {
+        let input = TokenStream::from_str("trait Pool { fn price(&self) -> u32 { 1 } }").unwrap();
+        handle_interface(input).unwrap();
+    }
+}
@@ -1,7 +1,9 @@
 mod ast;
 mod blueprint;
 mod import;
+mod interface;
 mod non_fungible_data;
+mod scrypto_test;
 mod utils;
 
 use proc_macro::TokenStream;
@@ -13,6 +15,18 @@ use proc_macro::TokenStream;
 /// - A `struct` which defines the structure
 /// - A `impl` which defines the implementation.
 ///
+/// In between, a blueprint may declare `const` items and at most one `enum` as its error
+/// type, both of which are exported in the ABI. A function or method returning
+/// `Result<T, E>`, where `E` is the error type, aborts the transaction on `Err` and the
+/// decoded error is reported in the receipt.
+///
+/// After the `impl`, a blueprint may declare the interfaces it implements with empty
+/// `impl Interface for Blueprint {}` items. The methods themselves are defined in the
+/// blueprint `impl`, and the interfaces are exported in the ABI.
+///
+/// The `struct` may declare feature flags with `#[features("a", "b")]`, which components are
+/// instantiated with using `instantiate_with_features` and check with `Runtime::feature_enabled`.
+///
 /// This macro will derive the dispatcher method responsible for handling invocation
 /// according to Scrypto ABI.
 ///
@@ -98,6 +112,89 @@ pub fn import(input: TokenStream) -> TokenStream {
         .into()
 }
 
+/// Declares an interface, a set of methods which components of different blueprints can
+/// implement.
+///
+/// The input is a `trait` whose items are methods taking `&self` or `&mut self`, without a
+/// body. This macro will generate a stub, which can be created from the address of any
+/// component implementing the interface and calls its methods.
+///
+/// # Example
+/// ```ignore
+/// use scrypto::prelude::*;
+///
+/// interface! {
+///     pub trait Pool {
+///         fn swap(&mut self, input: Bucket) -> Bucket;
+///         fn price(&self) -> Decimal;
+///     }
+/// }
+///
+/// blueprint! {
+///     struct ConstantProductPool {
+///         // ...
+///     }
+///
+///     impl ConstantProductPool {
+///         pub fn swap(&mut self, input: Bucket) -> Bucket {
+///             // ...
+///         }
+///
+///         pub fn price(&self) -> Decimal {
+///             // ...
+///         }
+///     }
+///
+///     impl Pool for ConstantProductPool {}
+/// }
+///
+/// let pool: Pool = component_address.into();
+/// let output = pool.swap(input);
+/// ```
+#[proc_macro]
+pub fn interface(input: TokenStream) -> TokenStream {
+    interface::handle_interface(proc_macro2::TokenStream::from(input))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Imports the structs and enums exported by a types-only package.
+///
+/// The input is the output of `resim export-types`, which lists the schemas exported
+/// with `export_types!`. The package address is recorded in the ABIs of the importing
+/// package, so the types of a package can only be imported once per crate.
+///
+/// # Example
+/// ```ignore
+/// use scrypto::prelude::*;
+///
+/// import_types! {
+/// r#"
+/// {
+///     "package_address": "01a405d3129b61e86c51c3168d553d2ffd7a3f0bd2f66b5a3e9876",
+///     "types": [
+///         {
+///             "type": "Struct",
+///             "name": "Order",
+///             "fields": {
+///                 "type": "Named",
+///                 "named": [
+///                     ["amount", { "type": "Custom", "name": "Decimal", "generics": [] }]
+///                 ]
+///             }
+///         }
+///     ]
+/// }
+/// "#
+/// }
+/// ```
+#[proc_macro]
+pub fn import_types(input: TokenStream) -> TokenStream {
+    import::handle_import_types(proc_macro2::TokenStream::from(input))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 /// Derive code that describe a non-fungible data structure.
 ///
 /// # Example
@@ -118,3 +215,27 @@ pub fn non_fungible_data(input: TokenStream) -> TokenStream {
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
+
+/// Declares a test function, which is compiled into the package and run by the engine.
+///
+/// Test functions must take no arguments and return nothing; a test fails if it panics.
+/// They are exported from the package and should not be placed behind `#[cfg(test)]`.
+///
+/// # Example
+/// ```ignore
+/// use scrypto::prelude::*;
+///
+/// #[scrypto_test]
+/// fn test_decimal_addition() {
+///     assert_eq!(dec!("1") + dec!("2"), dec!("3"));
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn scrypto_test(attr: TokenStream, item: TokenStream) -> TokenStream {
+    scrypto_test::handle_scrypto_test(
+        proc_macro2::TokenStream::from(attr),
+        proc_macro2::TokenStream::from(item),
+    )
+    .unwrap_or_else(|err| err.to_compile_error())
+    .into()
+}
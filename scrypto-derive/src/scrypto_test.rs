@@ -0,0 +1,105 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::*;
+
+macro_rules! trace {
+    ($($arg:expr),*) => {{
+        #[cfg(feature = "trace")]
+        println!($($arg),*);
+    }};
+}
+
+pub fn handle_scrypto_test(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    trace!("Started processing scrypto_test macro");
+
+    if !attr.is_empty() {
+        return Err(Error::new(attr.span(), "No arguments are allowed here"));
+    }
+
+    let f = parse2::<ItemFn>(item)?;
+    let sig = &f.sig;
+    if !sig.inputs.is_empty()
+        || !sig.generics.params.is_empty()
+        || sig.asyncness.is_some()
+        || !matches!(sig.output, ReturnType::Default)
+    {
+        return Err(Error::new(
+            sig.span(),
+            "A test function must take no arguments and return nothing",
+        ));
+    }
+
+    let fn_ident = &sig.ident;
+    let export_ident = format_ident!("scrypto_test_{}", fn_ident);
+    trace!("Test name: {}", fn_ident);
+
+    let output = quote! {
+        #f
+
+        #[no_mangle]
+        pub extern "C" fn #export_ident() -> *mut u8 {
+            // Set up panic hook
+            ::scrypto::misc::set_up_panic_hook();
+
+            // Set up component and resource subsystems;
+            ::scrypto::component::init_component_system(::scrypto::component::ComponentSystem::new());
+            ::scrypto::resource::init_resource_system(::scrypto::resource::ResourceSystem::new());
+
+            // Run the test
+            #fn_ident();
+
+            // Return
+            ::scrypto::buffer::scrypto_wrap(::scrypto::buffer::scrypto_encode_for_radix_engine(&()))
+        }
+    };
+    trace!("Finished processing scrypto_test macro");
+
+    #[cfg(feature = "trace")]
+    crate::utils::print_generated_code("scrypto_test", &output);
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn assert_code_eq(a: TokenStream, b: TokenStream) {
+        assert_eq!(a.to_string(), b.to_string());
+    }
+
+    #[test]
+    fn test_scrypto_test() {
+        let attr = TokenStream::new();
+        let item = TokenStream::from_str("fn test_x() { assert!(true); }").unwrap();
+        let output = handle_scrypto_test(attr, item).unwrap();
+
+        assert_code_eq(
+            output,
+            quote! {
+                fn test_x() {
+                    assert!(true);
+                }
+                #[no_mangle]
+                pub extern "C" fn scrypto_test_test_x() -> *mut u8 {
+                    ::scrypto::misc::set_up_panic_hook();
+                    ::scrypto::component::init_component_system(::scrypto::component::ComponentSystem::new());
+                    ::scrypto::resource::init_resource_system(::scrypto::resource::ResourceSystem::new());
+                    test_x();
+                    ::scrypto::buffer::scrypto_wrap(::scrypto::buffer::scrypto_encode_for_radix_engine(&()))
+                }
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_scrypto_test_with_arguments_should_fail() {
+        let attr = TokenStream::new();
+        let item = TokenStream::from_str("fn test_x(a: u32) { }").unwrap();
+        handle_scrypto_test(attr, item).unwrap();
+    }
+}
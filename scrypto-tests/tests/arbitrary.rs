@@ -0,0 +1,69 @@
+#![cfg(feature = "std")]
+
+use proptest::prelude::*;
+use scrypto::prelude::*;
+use scrypto::values::ScryptoValue;
+
+proptest! {
+    #[test]
+    fn test_decimal_encoding_roundtrip(a in any::<Decimal>()) {
+        prop_assert_eq!(scrypto_decode::<Decimal>(&scrypto_encode(&a)), Ok(a));
+    }
+
+    #[test]
+    fn test_decimal_string_roundtrip(a in any::<Decimal>()) {
+        prop_assert_eq!(Decimal::from_str(&a.to_string()), Ok(a));
+    }
+
+    #[test]
+    fn test_decimal_add_sub(a in any::<i64>(), b in any::<i64>()) {
+        let a = Decimal::from(a);
+        let b = Decimal::from(b);
+        prop_assert_eq!(a + b - b, a);
+        prop_assert_eq!(a - b + b, a);
+        prop_assert_eq!(a + b, b + a);
+    }
+
+    #[test]
+    fn test_decimal_mul_div(a in any::<i32>(), b in any::<i32>()) {
+        let product = Decimal::from(a) * Decimal::from(b);
+        prop_assert_eq!(product, Decimal::from(a as i128 * b as i128));
+        if b != 0 {
+            prop_assert_eq!(product / Decimal::from(b), Decimal::from(a));
+        }
+    }
+
+    #[test]
+    fn test_decimal_identities(a in any::<Decimal>()) {
+        prop_assert_eq!(a * Decimal::one(), a);
+        prop_assert_eq!(a / Decimal::one(), a);
+        prop_assert_eq!(a + Decimal::zero(), a);
+    }
+
+    #[test]
+    fn test_decimal_order(a in any::<i64>(), b in any::<i64>()) {
+        prop_assert_eq!(Decimal::from(a).cmp(&Decimal::from(b)), a.cmp(&b));
+    }
+
+    #[test]
+    fn test_non_fungible_id_roundtrip(id in any::<NonFungibleId>()) {
+        prop_assert_eq!(scrypto_decode::<NonFungibleId>(&scrypto_encode(&id)), Ok(id.clone()));
+        prop_assert_eq!(NonFungibleId::from_str(&id.to_string()), Ok(id));
+    }
+
+    #[test]
+    fn test_resource_address_roundtrip(address in any::<ResourceAddress>()) {
+        prop_assert_eq!(
+            scrypto_decode::<ResourceAddress>(&scrypto_encode(&address)),
+            Ok(address)
+        );
+    }
+
+    #[test]
+    fn test_scrypto_value_roundtrip(value in any::<ScryptoValue>()) {
+        prop_assert_eq!(ScryptoValue::from_slice(&value.raw), Ok(value.clone()));
+        prop_assert!(value.bucket_ids.is_empty());
+        prop_assert!(value.proof_ids.is_empty());
+        let _ = value.to_string();
+    }
+}
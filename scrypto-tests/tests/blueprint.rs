@@ -42,8 +42,13 @@ fn assert_json_eq<T: Serialize>(actual: T, expected: Value) {
 #[test]
 fn test_simple_abi() {
     let ptr = Simple_abi();
-    let abi: (Type, Vec<abi::Function>, Vec<abi::Method>) =
-        unsafe { scrypto_consume(ptr, |slice| scrypto_decode(slice).unwrap()) };
+    let abi: (
+        Type,
+        Vec<abi::Function>,
+        Vec<abi::Method>,
+        Vec<abi::Constant>,
+        Option<Type>,
+    ) = unsafe { scrypto_consume(ptr, |slice| scrypto_decode(slice).unwrap()) };
 
     assert_json_eq(
         abi,
@@ -144,7 +149,9 @@ fn test_simple_abi() {
                         "type": "Unit"
                     }
                 }
-            ]
+            ],
+            [],
+            null
         ]),
     );
 }
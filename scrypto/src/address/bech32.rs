@@ -0,0 +1,211 @@
+use crate::rust::string::String;
+use crate::rust::vec::Vec;
+
+/// The alphabet of the data part of a Bech32 string.
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// The generator of the BCH code used for the checksum.
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+const BECH32_CONST: u32 = 1;
+const BECH32M_CONST: u32 = 0x2bc830a3;
+
+const SEPARATOR: char = '1';
+const CHECKSUM_LENGTH: usize = 6;
+
+/// The checksum variant of a Bech32 string, see BIP-173 and BIP-350.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Variant {
+    fn constant(&self) -> u32 {
+        match self {
+            Variant::Bech32 => BECH32_CONST,
+            Variant::Bech32m => BECH32M_CONST,
+        }
+    }
+}
+
+/// Represents an error when decoding a Bech32 string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Bech32Error {
+    MissingSeparator,
+    InvalidHrp,
+    InvalidCharacter(char),
+    MixedCase,
+    InvalidChecksum,
+    InvalidPadding,
+}
+
+fn polymod<I: Iterator<Item = u8>>(values: I) -> u32 {
+    let mut checksum = 1u32;
+    for value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x1ffffff) << 5) ^ value as u32;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+fn hrp_expand(hrp: &[u8]) -> Vec<u8> {
+    let mut expanded = Vec::with_capacity(hrp.len() * 2 + 1);
+    expanded.extend(hrp.iter().map(|c| c >> 5));
+    expanded.push(0);
+    expanded.extend(hrp.iter().map(|c| c & 0x1f));
+    expanded
+}
+
+fn create_checksum(hrp: &[u8], data: &[u8], variant: Variant) -> Vec<u8> {
+    let values = hrp_expand(hrp)
+        .into_iter()
+        .chain(data.iter().copied())
+        .chain([0u8; CHECKSUM_LENGTH]);
+    let checksum = polymod(values) ^ variant.constant();
+    (0..CHECKSUM_LENGTH)
+        .map(|i| ((checksum >> (5 * (5 - i))) & 0x1f) as u8)
+        .collect()
+}
+
+fn verify_checksum(hrp: &[u8], data: &[u8]) -> Option<Variant> {
+    let values = hrp_expand(hrp).into_iter().chain(data.iter().copied());
+    match polymod(values) {
+        BECH32_CONST => Some(Variant::Bech32),
+        BECH32M_CONST => Some(Variant::Bech32m),
+        _ => None,
+    }
+}
+
+/// Regroups bits, e.g. from 8-bit bytes into 5-bit groups and back.
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, Bech32Error> {
+    let mut acc = 0u32;
+    let mut bits = 0u32;
+    let max_value = (1u32 << to) - 1;
+    let mut result = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    for value in data {
+        acc = (acc << from) | *value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            result.push(((acc >> bits) & max_value) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            result.push(((acc << (to - bits)) & max_value) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & max_value) != 0 {
+        return Err(Bech32Error::InvalidPadding);
+    }
+    Ok(result)
+}
+
+/// Encodes bytes into a Bech32 string with the given human-readable part.
+///
+/// The human-readable part is expected to be lowercase ASCII.
+pub fn encode(hrp: &str, data: &[u8], variant: Variant) -> String {
+    let data = convert_bits(data, 8, 5, true).expect("Padding never fails");
+    let checksum = create_checksum(hrp.as_bytes(), &data, variant);
+
+    let mut encoded = String::with_capacity(hrp.len() + 1 + data.len() + CHECKSUM_LENGTH);
+    encoded.push_str(hrp);
+    encoded.push(SEPARATOR);
+    for value in data.iter().chain(checksum.iter()) {
+        encoded.push(CHARSET[*value as usize] as char);
+    }
+    encoded
+}
+
+/// Decodes a Bech32 string into its human-readable part, bytes and checksum variant.
+///
+/// The human-readable part is returned in lowercase.
+pub fn decode(s: &str) -> Result<(String, Vec<u8>, Variant), Bech32Error> {
+    let has_lower = s.chars().any(|c| c.is_ascii_lowercase());
+    let has_upper = s.chars().any(|c| c.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return Err(Bech32Error::MixedCase);
+    }
+    let s = s.to_ascii_lowercase();
+
+    let position = s.rfind(SEPARATOR).ok_or(Bech32Error::MissingSeparator)?;
+    let (hrp, data) = (&s[..position], &s[position + 1..]);
+    if hrp.is_empty() || hrp.bytes().any(|c| !(33..=126).contains(&c)) {
+        return Err(Bech32Error::InvalidHrp);
+    }
+    if data.len() < CHECKSUM_LENGTH {
+        return Err(Bech32Error::InvalidChecksum);
+    }
+
+    let values = data
+        .chars()
+        .map(|c| {
+            CHARSET
+                .iter()
+                .position(|x| *x as char == c)
+                .map(|i| i as u8)
+                .ok_or(Bech32Error::InvalidCharacter(c))
+        })
+        .collect::<Result<Vec<u8>, Bech32Error>>()?;
+    let variant = verify_checksum(hrp.as_bytes(), &values).ok_or(Bech32Error::InvalidChecksum)?;
+    let bytes = convert_bits(&values[..values.len() - CHECKSUM_LENGTH], 5, 8, false)?;
+
+    Ok((hrp.into(), bytes, variant))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_strings() {
+        for s in [
+            "A1LQFN3A",
+            "a1lqfn3a",
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+            "split1checkupstagehandshakeupstreamerranterredcaperredlc445v",
+            "?1v759aa",
+        ] {
+            let (_, _, variant) = decode(s).unwrap();
+            assert_eq!(variant, Variant::Bech32m);
+        }
+        for s in [
+            "A12UEL5L",
+            "a12uel5l",
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+        ] {
+            let (_, _, variant) = decode(s).unwrap();
+            assert_eq!(variant, Variant::Bech32);
+        }
+    }
+
+    #[test]
+    fn test_invalid_strings() {
+        assert_eq!(decode("a1lqfn3A"), Err(Bech32Error::MixedCase));
+        assert_eq!(decode("pzry9x0s0muk"), Err(Bech32Error::MissingSeparator));
+        assert_eq!(decode("1qyrz8wqd2c9m"), Err(Bech32Error::InvalidHrp));
+        assert_eq!(decode("16plkw9"), Err(Bech32Error::InvalidHrp));
+        assert_eq!(decode("a1lqfn3q"), Err(Bech32Error::InvalidChecksum));
+        assert_eq!(
+            decode("xy1qbqqqqqqqb"),
+            Err(Bech32Error::InvalidCharacter('b'))
+        );
+    }
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let data = [2u8, 0, 1, 255, 128, 77];
+        for variant in [Variant::Bech32, Variant::Bech32m] {
+            let encoded = encode("test_", &data, variant);
+            assert_eq!(
+                decode(&encoded),
+                Ok(("test_".into(), data.to_vec(), variant))
+            );
+        }
+    }
+}
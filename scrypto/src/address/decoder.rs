@@ -0,0 +1,172 @@
+use crate::address::bech32::{self, Variant};
+use crate::address::{AddressError, EntityType, HrpSet, SIMULATOR_NETWORK_ID};
+use crate::component::{ComponentAddress, PackageAddress};
+use crate::misc::copy_u8_array;
+use crate::resource::ResourceAddress;
+
+/// Decodes addresses from their Bech32m text form, rejecting addresses of other networks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bech32Decoder {
+    hrp_set: HrpSet,
+}
+
+impl Bech32Decoder {
+    /// Creates a decoder for the given network.
+    pub fn new(network_id: u8) -> Self {
+        Self {
+            hrp_set: HrpSet::of(network_id),
+        }
+    }
+
+    /// Creates a decoder for the simulator network.
+    pub fn for_simulator() -> Self {
+        Self::new(SIMULATOR_NETWORK_ID)
+    }
+
+    /// Creates a decoder for the network named by the human-readable part of the given address.
+    pub fn for_address(s: &str) -> Result<Self, AddressError> {
+        let (hrp, _, _) = bech32::decode(s).map_err(AddressError::DecodingError)?;
+        HrpSet::network_id(&hrp)
+            .map(Self::new)
+            .ok_or(AddressError::UnknownHrp(hrp))
+    }
+
+    pub fn validate_and_decode_package_address(
+        &self,
+        s: &str,
+    ) -> Result<PackageAddress, AddressError> {
+        Self::decode(s, &self.hrp_set.package, EntityType::Package).map(PackageAddress)
+    }
+
+    pub fn validate_and_decode_component_address(
+        &self,
+        s: &str,
+    ) -> Result<ComponentAddress, AddressError> {
+        Self::decode(s, &self.hrp_set.component, EntityType::Component).map(ComponentAddress)
+    }
+
+    pub fn validate_and_decode_resource_address(
+        &self,
+        s: &str,
+    ) -> Result<ResourceAddress, AddressError> {
+        Self::decode(s, &self.hrp_set.resource, EntityType::Resource).map(ResourceAddress)
+    }
+
+    fn decode(s: &str, hrp: &str, entity_type: EntityType) -> Result<[u8; 26], AddressError> {
+        let (actual_hrp, bytes, variant) =
+            bech32::decode(s).map_err(AddressError::DecodingError)?;
+        if variant != Variant::Bech32m {
+            return Err(AddressError::InvalidVariant);
+        }
+        if actual_hrp != hrp {
+            return Err(AddressError::InvalidHrp {
+                expected: hrp.into(),
+                actual: actual_hrp,
+            });
+        }
+        if bytes.first() != Some(&entity_type.id()) {
+            return Err(AddressError::InvalidEntityType {
+                expected: entity_type,
+                actual: bytes.first().copied(),
+            });
+        }
+        match bytes.len() {
+            27 => Ok(copy_u8_array(&bytes[1..])),
+            _ => Err(AddressError::InvalidLength(bytes.len())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::{Bech32Encoder, MAINNET_NETWORK_ID};
+    use crate::constants::{RADIX_TOKEN, SYSTEM_COMPONENT, SYSTEM_PACKAGE};
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        for network_id in [MAINNET_NETWORK_ID, SIMULATOR_NETWORK_ID, 0x0a] {
+            let encoder = Bech32Encoder::new(network_id);
+            let decoder = Bech32Decoder::new(network_id);
+            assert_eq!(
+                decoder.validate_and_decode_package_address(
+                    &encoder.encode_package_address(&SYSTEM_PACKAGE)
+                ),
+                Ok(SYSTEM_PACKAGE)
+            );
+            assert_eq!(
+                decoder.validate_and_decode_component_address(
+                    &encoder.encode_component_address(&SYSTEM_COMPONENT)
+                ),
+                Ok(SYSTEM_COMPONENT)
+            );
+            assert_eq!(
+                decoder.validate_and_decode_resource_address(
+                    &encoder.encode_resource_address(&RADIX_TOKEN)
+                ),
+                Ok(RADIX_TOKEN)
+            );
+        }
+    }
+
+    #[test]
+    fn test_encoding_is_stable() {
+        assert_eq!(
+            Bech32Encoder::for_simulator().encode_component_address(&SYSTEM_COMPONENT),
+            "component_sim1qgqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqpqgyhcl2"
+        );
+        assert_eq!(
+            Bech32Encoder::new(MAINNET_NETWORK_ID).encode_component_address(&SYSTEM_COMPONENT),
+            "component_rdx1qgqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqpqwxjp54"
+        );
+    }
+
+    #[test]
+    fn test_address_of_other_network_is_rejected() {
+        let address = Bech32Encoder::new(MAINNET_NETWORK_ID).encode_resource_address(&RADIX_TOKEN);
+        assert_eq!(
+            Bech32Decoder::for_simulator().validate_and_decode_resource_address(&address),
+            Err(AddressError::InvalidHrp {
+                expected: "resource_sim".into(),
+                actual: "resource_rdx".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_address_of_other_entity_type_is_rejected() {
+        let address = bech32::encode("component_sim", &[3u8; 27], Variant::Bech32m);
+        assert_eq!(
+            Bech32Decoder::for_simulator().validate_and_decode_component_address(&address),
+            Err(AddressError::InvalidEntityType {
+                expected: EntityType::Component,
+                actual: Some(3),
+            })
+        );
+    }
+
+    #[test]
+    fn test_decoder_for_address_uses_network_of_address() {
+        for network_id in [MAINNET_NETWORK_ID, SIMULATOR_NETWORK_ID, 0x0a] {
+            let address = Bech32Encoder::new(network_id).encode_resource_address(&RADIX_TOKEN);
+            assert_eq!(
+                Bech32Decoder::for_address(&address),
+                Ok(Bech32Decoder::new(network_id))
+            );
+        }
+        let address = bech32::encode("resource_xyz", &[3u8; 27], Variant::Bech32m);
+        assert_eq!(
+            Bech32Decoder::for_address(&address),
+            Err(AddressError::UnknownHrp("resource_xyz".into()))
+        );
+    }
+
+    #[test]
+    fn test_bech32_address_is_rejected() {
+        let address = bech32::encode("component_sim", &[2u8; 27], Variant::Bech32);
+        assert_eq!(
+            Bech32Decoder::for_simulator().validate_and_decode_component_address(&address),
+            Err(AddressError::InvalidVariant)
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use crate::address::bech32::{self, Variant};
+use crate::address::{EntityType, HrpSet, SIMULATOR_NETWORK_ID};
+use crate::component::{ComponentAddress, PackageAddress};
+use crate::misc::combine;
+use crate::resource::ResourceAddress;
+use crate::rust::string::String;
+
+/// Encodes addresses into their Bech32m text form for a network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bech32Encoder {
+    hrp_set: HrpSet,
+}
+
+impl Bech32Encoder {
+    /// Creates an encoder for the given network.
+    pub fn new(network_id: u8) -> Self {
+        Self {
+            hrp_set: HrpSet::of(network_id),
+        }
+    }
+
+    /// Creates an encoder for the simulator network.
+    pub fn for_simulator() -> Self {
+        Self::new(SIMULATOR_NETWORK_ID)
+    }
+
+    pub fn encode_package_address(&self, address: &PackageAddress) -> String {
+        Self::encode(&self.hrp_set.package, EntityType::Package, &address.0)
+    }
+
+    pub fn encode_component_address(&self, address: &ComponentAddress) -> String {
+        Self::encode(&self.hrp_set.component, EntityType::Component, &address.0)
+    }
+
+    pub fn encode_resource_address(&self, address: &ResourceAddress) -> String {
+        Self::encode(&self.hrp_set.resource, EntityType::Resource, &address.0)
+    }
+
+    fn encode(hrp: &str, entity_type: EntityType, bytes: &[u8]) -> String {
+        bech32::encode(hrp, &combine(entity_type.id(), bytes), Variant::Bech32m)
+    }
+}
@@ -0,0 +1,19 @@
+/// The type of an addressable entity.
+///
+/// The type id is the leading byte of the encoded address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityType {
+    Package,
+    Component,
+    Resource,
+}
+
+impl EntityType {
+    pub fn id(&self) -> u8 {
+        match self {
+            EntityType::Package => 1,
+            EntityType::Component => 2,
+            EntityType::Resource => 3,
+        }
+    }
+}
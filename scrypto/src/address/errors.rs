@@ -0,0 +1,30 @@
+use crate::address::{Bech32Error, EntityType};
+use crate::rust::fmt;
+use crate::rust::string::String;
+
+/// Represents an error when decoding an address from its Bech32m text form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressError {
+    DecodingError(Bech32Error),
+    InvalidVariant,
+    InvalidHrp {
+        expected: String,
+        actual: String,
+    },
+    InvalidEntityType {
+        expected: EntityType,
+        actual: Option<u8>,
+    },
+    InvalidLength(usize),
+    UnknownHrp(String),
+}
+
+#[cfg(not(feature = "alloc"))]
+impl std::error::Error for AddressError {}
+
+#[cfg(not(feature = "alloc"))]
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
@@ -0,0 +1,51 @@
+use crate::rust::format;
+use crate::rust::string::String;
+
+/// The network id of the Radix mainnet.
+pub const MAINNET_NETWORK_ID: u8 = 0x01;
+/// The network id of a local development network.
+pub const LOCALNET_NETWORK_ID: u8 = 0xf0;
+/// The network id of the simulator.
+pub const SIMULATOR_NETWORK_ID: u8 = 0xf2;
+
+/// The human-readable parts used to encode the addresses of a network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HrpSet {
+    pub package: String,
+    pub component: String,
+    pub resource: String,
+}
+
+impl HrpSet {
+    /// Returns the human-readable parts of the given network.
+    ///
+    /// Well-known networks have a short suffix, e.g. `component_rdx` on mainnet, while
+    /// test networks are identified by their id, e.g. `component_tdx_a_`.
+    pub fn of(network_id: u8) -> Self {
+        let suffix = match network_id {
+            MAINNET_NETWORK_ID => "rdx".into(),
+            LOCALNET_NETWORK_ID => "loc".into(),
+            SIMULATOR_NETWORK_ID => "sim".into(),
+            _ => format!("tdx_{:x}_", network_id),
+        };
+        Self {
+            package: format!("package_{}", suffix),
+            component: format!("component_{}", suffix),
+            resource: format!("resource_{}", suffix),
+        }
+    }
+
+    /// Returns the network of the given human-readable part, e.g. `component_rdx`.
+    pub fn network_id(hrp: &str) -> Option<u8> {
+        let (_, suffix) = hrp.split_once('_')?;
+        match suffix {
+            "rdx" => Some(MAINNET_NETWORK_ID),
+            "loc" => Some(LOCALNET_NETWORK_ID),
+            "sim" => Some(SIMULATOR_NETWORK_ID),
+            _ => suffix
+                .strip_prefix("tdx_")
+                .and_then(|suffix| suffix.strip_suffix('_'))
+                .and_then(|id| u8::from_str_radix(id, 16).ok()),
+        }
+    }
+}
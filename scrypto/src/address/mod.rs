@@ -0,0 +1,13 @@
+mod bech32;
+mod decoder;
+mod encoder;
+mod entity;
+mod errors;
+mod hrpset;
+
+pub use self::bech32::{Bech32Error, Variant};
+pub use decoder::Bech32Decoder;
+pub use encoder::Bech32Encoder;
+pub use entity::EntityType;
+pub use errors::AddressError;
+pub use hrpset::*;
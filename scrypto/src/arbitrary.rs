@@ -0,0 +1,119 @@
+use proptest::collection::vec;
+use proptest::prelude::*;
+use sbor::arbitrary::{primitive_value, value_with_leaves};
+use sbor::{decode_any, encode_any, Encode, Encoder, Value};
+
+use crate::buffer::scrypto_encode;
+use crate::component::{ComponentAddress, PackageAddress};
+use crate::math::Decimal;
+use crate::resource::{
+    AccessRule, AccessRuleNode, NonFungibleId, ProofRule, ResourceAddress, SoftDecimal,
+    SoftResource, SoftResourceOrNonFungible,
+};
+use crate::rust::vec::Vec;
+use crate::values::ScryptoValue;
+
+impl Arbitrary for Decimal {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Decimal>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        any::<i128>().prop_map(Decimal).boxed()
+    }
+}
+
+impl Arbitrary for NonFungibleId {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<NonFungibleId>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        vec(any::<u8>(), 0..32)
+            .prop_map(NonFungibleId::from_bytes)
+            .boxed()
+    }
+}
+
+impl Arbitrary for PackageAddress {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<PackageAddress>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        any::<[u8; 26]>().prop_map(PackageAddress).boxed()
+    }
+}
+
+impl Arbitrary for ComponentAddress {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<ComponentAddress>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        any::<[u8; 26]>().prop_map(ComponentAddress).boxed()
+    }
+}
+
+impl Arbitrary for ResourceAddress {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<ResourceAddress>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        any::<[u8; 26]>().prop_map(ResourceAddress).boxed()
+    }
+}
+
+impl Arbitrary for AccessRule {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<AccessRule>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        let proof_rule = prop_oneof![
+            any::<ResourceAddress>().prop_map(|resource_address| {
+                ProofRule::Require(SoftResourceOrNonFungible::StaticResource(resource_address))
+            }),
+            (any::<Decimal>(), any::<ResourceAddress>()).prop_map(|(amount, resource_address)| {
+                ProofRule::AmountOf(
+                    SoftDecimal::Static(amount),
+                    SoftResource::Static(resource_address),
+                )
+            }),
+        ];
+        prop_oneof![
+            Just(AccessRule::AllowAll),
+            Just(AccessRule::DenyAll),
+            proof_rule.prop_map(|rule| AccessRule::Protected(AccessRuleNode::ProofRule(rule))),
+            "[a-z_]{1,16}".prop_map(AccessRule::Slot),
+        ]
+        .boxed()
+    }
+}
+
+/// Converts a value of a Scrypto type into its SBOR representation.
+fn to_sbor_value<T: Encode>(value: &T) -> Value {
+    decode_any(&scrypto_encode(value)).unwrap()
+}
+
+/// A strategy for values of the Scrypto custom types which carry no ownership, i.e. anything
+/// but buckets, proofs, vaults and lazy maps.
+pub fn custom_value() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        any::<Decimal>().prop_map(|v| to_sbor_value(&v)),
+        any::<NonFungibleId>().prop_map(|v| to_sbor_value(&v)),
+        any::<ResourceAddress>().prop_map(|v| to_sbor_value(&v)),
+        any::<ComponentAddress>().prop_map(|v| to_sbor_value(&v)),
+        any::<PackageAddress>().prop_map(|v| to_sbor_value(&v)),
+    ]
+}
+
+impl Arbitrary for ScryptoValue {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<ScryptoValue>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        value_with_leaves(prop_oneof![primitive_value(), custom_value()])
+            .prop_map(|value| {
+                let mut bytes = Vec::new();
+                encode_any(None, &value, &mut Encoder::with_type(&mut bytes));
+                ScryptoValue::from_slice(&bytes).unwrap()
+            })
+            .boxed()
+    }
+}
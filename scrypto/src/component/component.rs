@@ -1,15 +1,19 @@
 use sbor::*;
 
+use crate::address::{AddressError, Bech32Decoder};
+use crate::args;
 use crate::buffer::*;
 use crate::component::*;
 use crate::core::*;
-use crate::engine::{api::*, call_engine};
+use crate::engine::{api::*, call_engine, types::LazyMapId};
 use crate::misc::*;
-use crate::resource::AccessRules;
+use crate::resource::{AccessRule, AccessRules, Mutability};
 use crate::rust::borrow::ToOwned;
+use crate::rust::collections::{BTreeMap, BTreeSet};
 use crate::rust::fmt;
 use crate::rust::str::FromStr;
 use crate::rust::string::String;
+use crate::rust::string::ToString;
 use crate::rust::vec::Vec;
 use crate::types::*;
 
@@ -17,6 +21,9 @@ pub struct LocalComponent {
     blueprint_name: String,
     state: Vec<u8>,
     access_rules_list: Vec<AccessRules>,
+    access_rule_slots: BTreeMap<String, (AccessRule, Mutability)>,
+    method_toggle_auth: AccessRule,
+    features: BTreeSet<String>,
 }
 
 impl LocalComponent {
@@ -25,6 +32,9 @@ impl LocalComponent {
             blueprint_name,
             state,
             access_rules_list: Vec::new(),
+            access_rule_slots: BTreeMap::new(),
+            method_toggle_auth: AccessRule::DenyAll,
+            features: BTreeSet::new(),
         }
     }
 
@@ -33,11 +43,43 @@ impl LocalComponent {
         self
     }
 
+    /// Adds a rule slot, which access rules can refer to with `AccessRule::Slot`.
+    ///
+    /// The rule of the slot can be updated with `Component::set_access_rule` by whoever
+    /// satisfies the `mutability` rule.
+    pub fn add_access_rule_slot(
+        mut self,
+        name: &str,
+        rule: AccessRule,
+        mutability: Mutability,
+    ) -> Self {
+        self.access_rule_slots
+            .insert(name.to_owned(), (rule, mutability));
+        self
+    }
+
+    /// Allows whoever satisfies the given rule to disable and enable methods of the component,
+    /// with `Component::set_method_enabled`.
+    pub fn method_toggle_auth(mut self, rule: AccessRule) -> Self {
+        self.method_toggle_auth = rule;
+        self
+    }
+
+    /// Enables the given feature flags, which must be declared by the blueprint.
+    pub fn with_features(mut self, features: &[&str]) -> Self {
+        self.features
+            .extend(features.iter().map(|feature| feature.to_string()));
+        self
+    }
+
     pub fn globalize(self) -> ComponentAddress {
         let input = CreateComponentInput {
             blueprint_name: self.blueprint_name,
             state: self.state,
             access_rules_list: self.access_rules_list,
+            access_rule_slots: self.access_rule_slots,
+            method_toggle_auth: self.method_toggle_auth,
+            features: self.features,
         };
         let output: CreateComponentOutput = call_engine(CREATE_COMPONENT, input);
         output.component_address
@@ -48,6 +90,16 @@ impl LocalComponent {
 pub trait ComponentState: Encode + Decode {
     /// Instantiates a component from this data structure.
     fn instantiate(self) -> LocalComponent;
+
+    /// Instantiates a component from this data structure, with the given feature flags enabled.
+    ///
+    /// The features must be declared with `#[features(..)]` on the blueprint struct.
+    fn instantiate_with_features(self, features: &[&str]) -> LocalComponent
+    where
+        Self: Sized,
+    {
+        self.instantiate().with_features(features)
+    }
 }
 
 /// An instance of a blueprint, which lives in the ledger state.
@@ -84,6 +136,39 @@ impl Component {
         let _: PutComponentStateOutput = call_engine(PUT_COMPONENT_STATE, input);
     }
 
+    /// Updates the rule of a rule slot of this component.
+    ///
+    /// The caller must satisfy the mutability rule the slot was created with.
+    pub fn set_access_rule(&self, slot: &str, rule: AccessRule) {
+        let input = SetAccessRuleInput {
+            slot: slot.to_owned(),
+            rule,
+        };
+        let _: SetAccessRuleOutput = call_engine(SET_ACCESS_RULE, input);
+    }
+
+    /// Disables or enables a method of this component.
+    ///
+    /// Calls to a disabled method fail until it's enabled again. The caller must satisfy the
+    /// method toggle rule the component was created with, and the method must be declared by
+    /// the blueprint.
+    pub fn set_method_enabled(&self, method: &str, enabled: bool) {
+        if Runtime::actor().component_address() == Some(self.0) {
+            let input = SetMethodEnabledInput {
+                method: method.to_owned(),
+                enabled,
+            };
+            let _: SetMethodEnabledOutput = call_engine(SET_METHOD_ENABLED, input);
+        } else {
+            let input = InvokeSNodeInput {
+                snode_ref: SNodeRef::ComponentRef(self.0),
+                function: "set_method_enabled".to_string(),
+                args: args![method.to_owned(), enabled],
+            };
+            let _: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        }
+    }
+
     /// Returns the package ID of this component.
     pub fn package_address(&self) -> PackageAddress {
         let input = GetComponentInfoInput {
@@ -101,6 +186,25 @@ impl Component {
         let output: GetComponentInfoOutput = call_engine(GET_COMPONENT_INFO, input);
         output.blueprint_name
     }
+
+    /// Returns the value that is associated with the given key, in a lazy map owned by this
+    /// component.
+    ///
+    /// The lazy map must have been created with `LazyMap::new_readable`.
+    pub fn get_lazy_map_entry<K: Encode, V: Decode>(
+        &self,
+        lazy_map_id: LazyMapId,
+        key: &K,
+    ) -> Option<V> {
+        let input = GetReadableLazyMapEntryInput {
+            component_address: self.0,
+            lazy_map_id,
+            key: scrypto_encode(key),
+        };
+        let output: GetReadableLazyMapEntryOutput = call_engine(GET_READABLE_LAZY_MAP_ENTRY, input);
+
+        output.value.map(|v| scrypto_decode(&v).unwrap())
+    }
 }
 
 //========
@@ -110,9 +214,9 @@ impl Component {
 /// Represents an error when decoding component address.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ParseComponentAddressError {
-    InvalidHex(String),
     InvalidLength(usize),
     InvalidPrefix,
+    InvalidAddress(AddressError),
 }
 
 #[cfg(not(feature = "alloc"))]
@@ -152,18 +256,21 @@ scrypto_type!(ComponentAddress, ScryptoType::ComponentAddress, Vec::new());
 // text
 //======
 
-// Before Bech32, we use a fixed prefix for text representation.
+// See `crate::address` for the text forms of addresses.
 
 impl FromStr for ComponentAddress {
     type Err = ParseComponentAddressError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let bytes =
-            hex::decode(s).map_err(|_| ParseComponentAddressError::InvalidHex(s.to_owned()))?;
-        if bytes.get(0) != Some(&2u8) {
-            return Err(ParseComponentAddressError::InvalidPrefix);
+        if let Ok(bytes) = hex::decode(s) {
+            if bytes.get(0) != Some(&2u8) {
+                return Err(ParseComponentAddressError::InvalidPrefix);
+            }
+            return Self::try_from(&bytes[1..]);
         }
-        Self::try_from(&bytes[1..])
+        Bech32Decoder::for_address(s)
+            .and_then(|decoder| decoder.validate_and_decode_component_address(s))
+            .map_err(ParseComponentAddressError::InvalidAddress)
     }
 }
 
@@ -1,10 +1,12 @@
+use sbor::describe::Fields;
 use sbor::*;
 
 use crate::buffer::*;
 use crate::crypto::*;
-use crate::engine::{api::*, call_engine, types::LazyMapId};
+use crate::engine::{api::*, call_engine, types::LazyMapId, EngineBatch};
 use crate::misc::*;
 use crate::rust::borrow::ToOwned;
+use crate::rust::collections::HashMap;
 use crate::rust::fmt;
 use crate::rust::marker::PhantomData;
 use crate::rust::str::FromStr;
@@ -34,6 +36,21 @@ impl<K: Encode + Decode, V: Encode + Decode> LazyMap<K, V> {
         }
     }
 
+    /// Creates a new lazy map, whose entries can be read by other components once it's owned
+    /// by a component.
+    ///
+    /// Writes are still restricted to the owning component.
+    pub fn new_readable() -> Self {
+        let input = CreateReadableLazyMapInput {};
+        let output: CreateReadableLazyMapOutput = call_engine(CREATE_READABLE_LAZY_MAP, input);
+
+        Self {
+            id: output.lazy_map_id,
+            key: PhantomData,
+            value: PhantomData,
+        }
+    }
+
     /// Returns the value that is associated with the given key.
     pub fn get(&self, key: &K) -> Option<V> {
         let input = GetLazyMapEntryInput {
@@ -45,6 +62,26 @@ impl<K: Encode + Decode, V: Encode + Decode> LazyMap<K, V> {
         output.value.map(|v| scrypto_decode(&v).unwrap())
     }
 
+    /// Returns the values that are associated with the given keys, reading all entries with a
+    /// single engine call.
+    pub fn get_many(&self, keys: &[K]) -> Vec<Option<V>> {
+        let mut batch = EngineBatch::new();
+        for key in keys {
+            batch.add(
+                GET_LAZY_MAP_ENTRY,
+                GetLazyMapEntryInput {
+                    lazy_map_id: self.id,
+                    key: scrypto_encode(key),
+                },
+            );
+        }
+        batch
+            .execute::<GetLazyMapEntryOutput>()
+            .into_iter()
+            .map(|o| o.value.map(|v| scrypto_decode(&v).unwrap()))
+            .collect()
+    }
+
     /// Inserts a new key-value pair into this map.
     pub fn insert(&self, key: K, value: V) {
         let input = PutLazyMapEntryInput {
@@ -132,13 +169,138 @@ impl<K: Encode + Decode, V: Encode + Decode> Decode for LazyMap<K, V> {
 
 impl<K: Encode + Decode + Describe, V: Encode + Decode + Describe> Describe for LazyMap<K, V> {
     fn describe() -> Type {
+        Self::describe_with(&mut DescribeContext::new())
+    }
+
+    fn describe_with(context: &mut DescribeContext) -> Type {
         Type::Custom {
             name: ScryptoType::LazyMap.name(),
-            generics: vec![K::describe(), V::describe()],
+            generics: vec![K::describe_with(context), V::describe_with(context)],
         }
     }
 }
 
+//========
+// schema
+//========
+
+/// Returns the key and value types of a lazy map type, as described in a blueprint schema.
+pub fn lazy_map_entry_types(ty: &Type) -> Option<(&Type, &Type)> {
+    match ty {
+        Type::Custom { name, generics }
+            if *name == ScryptoType::LazyMap.name() && generics.len() == 2 =>
+        {
+            Some((&generics[0], &generics[1]))
+        }
+        _ => None,
+    }
+}
+
+/// Finds the lazy maps referenced by a value, along with their key and value types, by walking
+/// the value alongside its schema.
+pub fn find_lazy_map_entry_types(ty: &Type, value: &Value) -> HashMap<LazyMapId, (Type, Type)> {
+    let mut found = HashMap::new();
+    collect_lazy_map_entry_types(ty, value, &mut Vec::new(), &mut found);
+    found
+}
+
+fn collect_lazy_map_entry_types<'a>(
+    ty: &'a Type,
+    value: &Value,
+    enclosing: &mut Vec<&'a Type>,
+    found: &mut HashMap<LazyMapId, (Type, Type)>,
+) {
+    match (ty, value) {
+        (Type::Ref { name }, _) => {
+            let resolved = enclosing
+                .iter()
+                .rev()
+                .copied()
+                .find(|t| t.is_referenced_by(name));
+            if let Some(t) = resolved {
+                collect_lazy_map_entry_types(t, value, enclosing, found);
+            }
+        }
+        (Type::Struct { fields, .. }, Value::Struct { fields: values }) => {
+            enclosing.push(ty);
+            collect_fields_entry_types(fields, values, enclosing, found);
+            enclosing.pop();
+        }
+        (Type::Enum { variants, .. }, Value::Enum { name, fields }) => {
+            enclosing.push(ty);
+            if let Some(variant) = variants.iter().find(|v| &v.name == name) {
+                collect_fields_entry_types(&variant.fields, fields, enclosing, found);
+            }
+            enclosing.pop();
+        }
+        (Type::Enum { variants, .. }, Value::IndexedEnum { index, fields }) => {
+            enclosing.push(ty);
+            if let Some(variant) = variants.get(*index as usize) {
+                collect_fields_entry_types(&variant.fields, fields, enclosing, found);
+            }
+            enclosing.pop();
+        }
+        (Type::Option { value: t }, Value::Option { value: v }) => {
+            if let Some(v) = v.as_ref() {
+                collect_lazy_map_entry_types(t, v, enclosing, found);
+            }
+        }
+        (Type::Result { okay, error }, Value::Result { value: v }) => match v.as_ref() {
+            Ok(v) => collect_lazy_map_entry_types(okay, v, enclosing, found),
+            Err(v) => collect_lazy_map_entry_types(error, v, enclosing, found),
+        },
+        (Type::Tuple { elements: types }, Value::Tuple { elements }) => {
+            for (t, v) in types.iter().zip(elements) {
+                collect_lazy_map_entry_types(t, v, enclosing, found);
+            }
+        }
+        (Type::Array { element, .. }, Value::Array { elements, .. })
+        | (Type::Vec { element }, Value::Vec { elements, .. })
+        | (Type::TreeSet { element }, Value::TreeSet { elements, .. })
+        | (Type::HashSet { element }, Value::HashSet { elements, .. }) => {
+            for v in elements {
+                collect_lazy_map_entry_types(element, v, enclosing, found);
+            }
+        }
+        (Type::TreeMap { key, value: t }, Value::TreeMap { elements, .. })
+        | (Type::HashMap { key, value: t }, Value::HashMap { elements, .. }) => {
+            for entry in elements.chunks(2) {
+                collect_lazy_map_entry_types(key, &entry[0], enclosing, found);
+                if let Some(v) = entry.get(1) {
+                    collect_lazy_map_entry_types(t, v, enclosing, found);
+                }
+            }
+        }
+        (Type::Custom { .. }, Value::Custom { type_id, bytes })
+            if *type_id == ScryptoType::LazyMap.id() =>
+        {
+            if let (Some((k, v)), Ok(map)) = (
+                lazy_map_entry_types(ty),
+                LazyMap::<(), ()>::try_from(bytes.as_slice()),
+            ) {
+                found.insert(map.id, (k.clone(), v.clone()));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_fields_entry_types<'a>(
+    fields: &'a Fields,
+    values: &[Value],
+    enclosing: &mut Vec<&'a Type>,
+    found: &mut HashMap<LazyMapId, (Type, Type)>,
+) {
+    let types: Vec<&Type> = match fields {
+        Fields::Named { named } => named.iter().map(|(_, t)| t).collect(),
+        Fields::Unnamed { unnamed } => unnamed.iter().collect(),
+        Fields::Unit => Vec::new(),
+    };
+    for (t, v) in types.into_iter().zip(values) {
+        collect_lazy_map_entry_types(t, v, enclosing, found);
+    }
+}
+
 //======
 // text
 //======
@@ -163,3 +325,39 @@ impl<K: Encode + Decode, V: Encode + Decode> fmt::Debug for LazyMap<K, V> {
         write!(f, "{}", self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rust::boxed::Box;
+
+    #[test]
+    fn test_find_lazy_map_entry_types() {
+        let map_type = LazyMap::<String, u32>::describe();
+        let ty = Type::Struct {
+            name: "Test".to_owned(),
+            fields: Fields::Named {
+                named: vec![(
+                    "maps".to_owned(),
+                    Type::Vec {
+                        element: Box::new(map_type),
+                    },
+                )],
+            },
+        };
+        let value = Value::Struct {
+            fields: vec![Value::Vec {
+                element_type_id: ScryptoType::LazyMap.id(),
+                elements: vec![Value::Custom {
+                    type_id: ScryptoType::LazyMap.id(),
+                    bytes: vec![1u8; 36],
+                }],
+            }],
+        };
+
+        let found = find_lazy_map_entry_types(&ty, &value);
+
+        let id = (Hash([1u8; 32]), u32::from_le_bytes([1u8; 4]));
+        assert_eq!(found.get(&id), Some(&(Type::String, Type::U32)));
+    }
+}
@@ -6,6 +6,6 @@ mod system;
 pub use component::{
     Component, ComponentAddress, ComponentState, LocalComponent, ParseComponentAddressError,
 };
-pub use lazy_map::{LazyMap, ParseLazyMapError};
+pub use lazy_map::{find_lazy_map_entry_types, lazy_map_entry_types, LazyMap, ParseLazyMapError};
 pub use package::{Package, PackageAddress, ParsePackageAddressError};
 pub use system::{component_system, init_component_system, ComponentSystem};
@@ -1,12 +1,18 @@
 use sbor::*;
 
+use crate::address::{AddressError, Bech32Decoder};
 use crate::buffer::*;
 use crate::core::*;
+use crate::engine::{api::*, call_engine};
 use crate::misc::*;
+use crate::resource::ResourceAddress;
 use crate::rust::borrow::ToOwned;
+use crate::rust::collections::BTreeMap;
 use crate::rust::fmt;
 use crate::rust::str::FromStr;
 use crate::rust::string::String;
+use crate::rust::string::ToString;
+use crate::rust::vec;
 use crate::rust::vec::Vec;
 use crate::types::*;
 
@@ -27,6 +33,56 @@ impl Package {
 
         scrypto_decode(&output).unwrap()
     }
+
+    /// Replaces the code of this package, returning the new package version.
+    ///
+    /// The owner badge must be present in the auth zone.
+    pub fn upgrade(&self, code: &[u8]) -> u32 {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::PackageRef(self.0),
+            function: "upgrade".to_string(),
+            args: vec![scrypto_encode(&code.to_vec())],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    /// Updates a metadata entry of this package.
+    ///
+    /// The owner badge must be present in the auth zone.
+    pub fn set_metadata(&self, name: &str, value: &str) {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::PackageRef(self.0),
+            function: "set_metadata".to_string(),
+            args: vec![
+                scrypto_encode(&name.to_owned()),
+                scrypto_encode(&value.to_owned()),
+            ],
+        };
+        let _: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+    }
+
+    /// Returns the metadata of this package.
+    pub fn metadata(&self) -> BTreeMap<String, String> {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::PackageRef(self.0),
+            function: "get_metadata".to_string(),
+            args: vec![],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    /// Returns the badge of the package owner, if published with one.
+    pub fn owner_badge(&self) -> Option<ResourceAddress> {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::PackageRef(self.0),
+            function: "get_owner_badge".to_string(),
+            args: vec![],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
 }
 
 //========
@@ -36,9 +92,9 @@ impl Package {
 /// Represents an error when decoding package address.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ParsePackageAddressError {
-    InvalidHex(String),
     InvalidLength(usize),
     InvalidPrefix,
+    InvalidAddress(AddressError),
 }
 
 #[cfg(not(feature = "alloc"))]
@@ -78,18 +134,21 @@ scrypto_type!(PackageAddress, ScryptoType::PackageAddress, Vec::new());
 // text
 //======
 
-// Before Bech32, we use a fixed prefix for text representation.
+// See `crate::address` for the text forms of addresses.
 
 impl FromStr for PackageAddress {
     type Err = ParsePackageAddressError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let bytes =
-            hex::decode(s).map_err(|_| ParsePackageAddressError::InvalidHex(s.to_owned()))?;
-        if bytes.get(0) != Some(&1u8) {
-            return Err(ParsePackageAddressError::InvalidPrefix);
+        if let Ok(bytes) = hex::decode(s) {
+            if bytes.get(0) != Some(&1u8) {
+                return Err(ParsePackageAddressError::InvalidPrefix);
+            }
+            return Self::try_from(&bytes[1..]);
         }
-        Self::try_from(&bytes[1..])
+        Bech32Decoder::for_address(s)
+            .and_then(|decoder| decoder.validate_and_decode_package_address(s))
+            .map_err(ParsePackageAddressError::InvalidAddress)
     }
 }
 
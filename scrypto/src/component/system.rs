@@ -3,7 +3,8 @@ use crate::buffer::*;
 use crate::component::*;
 use crate::core::SNodeRef;
 use crate::engine::{api::*, call_engine};
-use crate::prelude::AccessRules;
+use crate::prelude::{AccessRule, AccessRules};
+use crate::resource::ResourceAddress;
 use crate::rust::borrow::ToOwned;
 use crate::rust::collections::*;
 use crate::rust::string::ToString;
@@ -56,6 +57,21 @@ impl ComponentSystem {
         scrypto_decode(&output.rtn).unwrap()
     }
 
+    /// Publishes a package, which can be upgraded by whoever presents the owner badge.
+    pub fn publish_package_with_owner(
+        &mut self,
+        code: &[u8],
+        owner_badge: ResourceAddress,
+    ) -> PackageAddress {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::PackageStatic,
+            function: "publish_with_owner".to_string(),
+            args: args![code.to_vec(), owner_badge],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
     /// Instantiates a component.
     pub fn instantiate_component<T: ComponentState>(
         &mut self,
@@ -67,6 +83,9 @@ impl ComponentSystem {
             blueprint_name: blueprint_name.to_owned(),
             state: scrypto_encode(&state),
             access_rules_list: authorization,
+            access_rule_slots: BTreeMap::new(),
+            method_toggle_auth: AccessRule::DenyAll,
+            features: BTreeSet::new(),
         };
         let output: CreateComponentOutput = call_engine(CREATE_COMPONENT, input);
 
@@ -25,3 +25,18 @@ pub const RADIX_TOKEN: ResourceAddress = ResourceAddress([
 pub const ECDSA_TOKEN: ResourceAddress = ResourceAddress([
     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 5,
 ]);
+
+/// The virtual resource address of the badges identifying a caller's package.
+pub const PACKAGE_TOKEN: ResourceAddress = ResourceAddress([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 6,
+]);
+
+/// The virtual resource address of the badges identifying a calling global component.
+pub const GLOBAL_CALLER_TOKEN: ResourceAddress = ResourceAddress([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 7,
+]);
+
+/// The virtual resource address of the system badge, held by system transactions only.
+pub const SYSTEM_TOKEN: ResourceAddress = ResourceAddress([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8,
+]);
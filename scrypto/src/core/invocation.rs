@@ -1,5 +1,6 @@
+use crate::component::{ComponentAddress, PackageAddress};
 use crate::core::ScryptoActor;
-use crate::engine::types::{BucketId, ProofId, VaultId};
+use crate::engine::types::{AllowanceId, BucketId, LockerId, ProofId, VaultId};
 use crate::resource::ResourceAddress;
 use crate::rust::string::ToString;
 use sbor::*;
@@ -7,9 +8,11 @@ use sbor::*;
 #[derive(Debug, Clone, TypeId, Encode, Decode)]
 pub enum SNodeRef {
     PackageStatic,
+    PackageRef(PackageAddress),
     AuthZoneRef,
     WorktopRef,
     Scrypto(ScryptoActor),
+    ComponentRef(ComponentAddress),
     ResourceStatic,
     ResourceRef(ResourceAddress),
     Bucket(BucketId),
@@ -17,4 +20,7 @@ pub enum SNodeRef {
     ProofRef(ProofId),
     Proof(ProofId),
     VaultRef(VaultId),
+    LockerStatic,
+    LockerRef(ComponentAddress, LockerId),
+    AllowanceRef(AllowanceId),
 }
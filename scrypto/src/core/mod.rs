@@ -2,10 +2,14 @@ mod actor;
 mod invocation;
 mod level;
 mod logger;
+mod network;
+mod notification;
 mod runtime;
 
 pub use actor::{ScryptoActor, ScryptoActorInfo};
 pub use invocation::SNodeRef;
 pub use level::Level;
 pub use logger::Logger;
+pub use network::NetworkDefinition;
+pub use notification::Notification;
 pub use runtime::Runtime;
@@ -0,0 +1,35 @@
+use crate::address::{HrpSet, LOCALNET_NETWORK_ID, MAINNET_NETWORK_ID, SIMULATOR_NETWORK_ID};
+use crate::rust::string::String;
+
+/// Describes a network, which transactions are bound to with the network id in their header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkDefinition {
+    pub id: u8,
+    pub logical_name: String,
+}
+
+impl NetworkDefinition {
+    pub fn new(id: u8, logical_name: &str) -> Self {
+        Self {
+            id,
+            logical_name: logical_name.into(),
+        }
+    }
+
+    pub fn mainnet() -> Self {
+        Self::new(MAINNET_NETWORK_ID, "mainnet")
+    }
+
+    pub fn local_network() -> Self {
+        Self::new(LOCALNET_NETWORK_ID, "localnet")
+    }
+
+    pub fn simulator() -> Self {
+        Self::new(SIMULATOR_NETWORK_ID, "simulator")
+    }
+
+    /// Returns the human-readable parts of the addresses on this network.
+    pub fn hrp_set(&self) -> HrpSet {
+        HrpSet::of(self.id)
+    }
+}
@@ -0,0 +1,24 @@
+use sbor::*;
+
+use crate::buffer::scrypto_decode;
+use crate::component::ComponentAddress;
+use crate::rust::string::String;
+use crate::rust::vec::Vec;
+
+/// A notification of an event emitted by a component, pending for a component observing it.
+#[derive(Debug, Clone, PartialEq, Eq, TypeId, Encode, Decode, Describe)]
+pub struct Notification {
+    /// The component which emitted the event.
+    pub emitter: ComponentAddress,
+    /// The name of the event.
+    pub event: String,
+    /// The encoded data of the event.
+    pub data: Vec<u8>,
+}
+
+impl Notification {
+    /// Decodes the data of the event.
+    pub fn data<T: Decode>(&self) -> Result<T, DecodeError> {
+        scrypto_decode(&self.data)
+    }
+}
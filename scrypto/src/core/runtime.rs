@@ -1,7 +1,11 @@
+use sbor::Encode;
+
+use crate::buffer::scrypto_encode;
 use crate::component::*;
 use crate::core::*;
 use crate::crypto::*;
 use crate::engine::{api::*, call_engine};
+use crate::resource::NonFungibleAddress;
 use crate::rust::borrow::ToOwned;
 use crate::rust::vec::Vec;
 
@@ -18,6 +22,14 @@ impl Runtime {
         output.actor
     }
 
+    /// Returns the entity which called the running entity, or `None` if it was called by the
+    /// transaction.
+    pub fn caller() -> Option<ScryptoActorInfo> {
+        let input = GetCallerInput {};
+        let output: GetCallerOutput = call_engine(GET_CALLER, input);
+        output.caller
+    }
+
     /// Returns the package ID.
     pub fn package_address() -> PackageAddress {
         let input = GetActorInput {};
@@ -25,6 +37,17 @@ impl Runtime {
         output.actor.to_package_address()
     }
 
+    /// Checks whether the given feature flag is enabled for the running component.
+    ///
+    /// The feature must be declared with `#[features(..)]` on the blueprint struct.
+    pub fn feature_enabled(feature: &str) -> bool {
+        let input = IsFeatureEnabledInput {
+            feature: feature.to_owned(),
+        };
+        let output: IsFeatureEnabledOutput = call_engine(IS_FEATURE_ENABLED, input);
+        output.enabled
+    }
+
     /// Generates a UUID.
     pub fn generate_uuid() -> u128 {
         let input = GenerateUuidInput {};
@@ -76,10 +99,89 @@ impl Runtime {
         output.transaction_hash
     }
 
+    /// Returns the transaction signers, as the addresses of their virtual signature badges.
+    pub fn transaction_signers() -> Vec<NonFungibleAddress> {
+        let input = GetTransactionSignersInput {};
+        let output: GetTransactionSignersOutput = call_engine(GET_TRANSACTION_SIGNERS, input);
+        output.transaction_signers
+    }
+
     /// Returns the current epoch number.
     pub fn current_epoch() -> u64 {
         let input = GetCurrentEpochInput {};
         let output: GetCurrentEpochOutput = call_engine(GET_CURRENT_EPOCH, input);
         output.current_epoch
     }
+
+    /// Emits an event of the running component, which notifies the components observing the
+    /// event.
+    pub fn emit_event<S: AsRef<str>, T: Encode>(event: S, data: &T) {
+        let input = EmitEventInput {
+            event: event.as_ref().to_owned(),
+            data: scrypto_encode(data),
+        };
+        let _: EmitEventOutput = call_engine(EMIT_EVENT, input);
+    }
+
+    /// Allows other components to observe the events of the given name emitted by the running
+    /// component.
+    pub fn allow_event_subscriptions<S: AsRef<str>>(event: S) {
+        let input = SetEventSubscribableInput {
+            event: event.as_ref().to_owned(),
+            subscribable: true,
+        };
+        let _: SetEventSubscribableOutput = call_engine(SET_EVENT_SUBSCRIBABLE, input);
+    }
+
+    /// Disallows other components to observe the events of the given name emitted by the running
+    /// component, and removes the current observers of these events.
+    pub fn disallow_event_subscriptions<S: AsRef<str>>(event: S) {
+        let input = SetEventSubscribableInput {
+            event: event.as_ref().to_owned(),
+            subscribable: false,
+        };
+        let _: SetEventSubscribableOutput = call_engine(SET_EVENT_SUBSCRIBABLE, input);
+    }
+
+    /// Makes the running component observe the events of the given name emitted by a component.
+    ///
+    /// The emitter must allow subscriptions to the event with `allow_event_subscriptions`, unless
+    /// it's the running component itself.
+    ///
+    /// Notifications of these events are kept on ledger until the running component takes them
+    /// with `take_notifications`. Notifications beyond the pending notification limit are dropped.
+    pub fn subscribe_to_event<S: AsRef<str>>(emitter: ComponentAddress, event: S) {
+        let input = SubscribeToEventInput {
+            emitter,
+            event: event.as_ref().to_owned(),
+        };
+        let _: SubscribeToEventOutput = call_engine(SUBSCRIBE_TO_EVENT, input);
+    }
+
+    /// Makes the running component stop observing the events of the given name emitted by a
+    /// component.
+    pub fn unsubscribe_from_event<S: AsRef<str>>(emitter: ComponentAddress, event: S) {
+        let input = UnsubscribeFromEventInput {
+            emitter,
+            event: event.as_ref().to_owned(),
+        };
+        let _: UnsubscribeFromEventOutput = call_engine(UNSUBSCRIBE_FROM_EVENT, input);
+    }
+
+    /// Returns the notifications pending for the running component, oldest first, and clears
+    /// them.
+    pub fn take_notifications() -> Vec<Notification> {
+        let input = TakeNotificationsInput {};
+        let output: TakeNotificationsOutput = call_engine(TAKE_NOTIFICATIONS, input);
+        output.notifications
+    }
+
+    /// Aborts the transaction with a blueprint error, which is reported in the receipt.
+    pub fn raise_error<E: Encode>(error: E) -> ! {
+        let input = RaiseErrorInput {
+            error: scrypto_encode(&error),
+        };
+        let _: RaiseErrorOutput = call_engine(RAISE_ERROR, input);
+        panic!("Blueprint error was not raised")
+    }
 }
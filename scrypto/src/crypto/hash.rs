@@ -1,5 +1,6 @@
 use sbor::*;
 
+use crate::buffer::scrypto_encode;
 use crate::misc::*;
 use crate::rust::borrow::ToOwned;
 use crate::rust::convert::TryFrom;
@@ -10,7 +11,7 @@ use crate::rust::vec::Vec;
 use crate::types::*;
 
 /// Represents a 32-byte hash digest.
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Hash(pub [u8; Self::LENGTH]);
 
 impl Hash {
@@ -45,6 +46,14 @@ pub fn hash<T: AsRef<[u8]>>(data: T) -> Hash {
     crate::crypto::sha256(data)
 }
 
+/// Computes the hash of a type schema, from its SBOR encoding.
+///
+/// The encoding of a schema is canonical, so identical schemas always have the same hash, which
+/// can be used to look the schema up in the schema registry.
+pub fn hash_schema(schema: &Type) -> Hash {
+    hash(scrypto_encode(schema))
+}
+
 //========
 // error
 //========
@@ -125,4 +134,22 @@ mod tests {
         let h = Hash::from_str(s).unwrap();
         assert_eq!(h.to_string(), s);
     }
+
+    #[test]
+    fn test_hash_schema() {
+        let schema = Type::Struct {
+            name: "Test".to_string(),
+            fields: describe::Fields::Named {
+                named: vec![("a".to_string(), Type::U32)],
+            },
+        };
+        let other = Type::Struct {
+            name: "Test".to_string(),
+            fields: describe::Fields::Named {
+                named: vec![("a".to_string(), Type::U64)],
+            },
+        };
+        assert_eq!(hash_schema(&schema), hash_schema(&schema.clone()));
+        assert_ne!(hash_schema(&schema), hash_schema(&other));
+    }
 }
@@ -1,8 +1,9 @@
-use crate::core::SNodeRef;
+use crate::core::{Notification, SNodeRef};
 use sbor::*;
-use scrypto::prelude::{AccessRule, AccessRules};
+use scrypto::prelude::{AccessRule, AccessRules, Mutability};
 
 use crate::engine::types::*;
+use crate::rust::collections::{BTreeMap, BTreeSet};
 use crate::rust::string::String;
 use crate::rust::vec::Vec;
 
@@ -20,6 +21,12 @@ pub const GET_COMPONENT_INFO: u32 = 0x11;
 pub const GET_COMPONENT_STATE: u32 = 0x12;
 /// Update component state
 pub const PUT_COMPONENT_STATE: u32 = 0x13;
+/// Update a rule slot of a component
+pub const SET_ACCESS_RULE: u32 = 0x14;
+/// Enable or disable a method of a component
+pub const SET_METHOD_ENABLED: u32 = 0x15;
+/// Check whether a feature flag of the component is enabled
+pub const IS_FEATURE_ENABLED: u32 = 0x16;
 
 /// Create a lazy map
 pub const CREATE_LAZY_MAP: u32 = 0x20;
@@ -27,11 +34,17 @@ pub const CREATE_LAZY_MAP: u32 = 0x20;
 pub const GET_LAZY_MAP_ENTRY: u32 = 0x21;
 /// Insert a key-value pair into a lazy map
 pub const PUT_LAZY_MAP_ENTRY: u32 = 0x22;
+/// Create a lazy map whose entries can be read by other components
+pub const CREATE_READABLE_LAZY_MAP: u32 = 0x23;
+/// Retrieve an entry from a readable lazy map of another component
+pub const GET_READABLE_LAZY_MAP_ENTRY: u32 = 0x24;
 
 /// Create an empty vault
 pub const CREATE_EMPTY_VAULT: u32 = 0x40;
 
 pub const INVOKE_SNODE: u32 = 0x70;
+/// Make multiple engine calls at once
+pub const BATCH: u32 = 0x71;
 
 /// Log a message
 pub const EMIT_LOG: u32 = 0xf0;
@@ -48,6 +61,24 @@ pub const GET_ACTOR: u32 = 0xf5;
 
 /// Check that an access rule is satisfied
 pub const CHECK_ACCESS_RULE: u32 = 0xf6;
+/// Retrieve transaction signers
+pub const GET_TRANSACTION_SIGNERS: u32 = 0xf7;
+/// Abort the transaction with a blueprint error
+pub const RAISE_ERROR: u32 = 0xf8;
+/// Abort the transaction with a panic message
+pub const RAISE_PANIC: u32 = 0xf9;
+/// Retrieve the entity which called the running entity
+pub const GET_CALLER: u32 = 0xfa;
+/// Emit an event of the running component
+pub const EMIT_EVENT: u32 = 0xfb;
+/// Observe the events of a component
+pub const SUBSCRIBE_TO_EVENT: u32 = 0xfc;
+/// Stop observing the events of a component
+pub const UNSUBSCRIBE_FROM_EVENT: u32 = 0xfd;
+/// Retrieve and clear the notifications pending for the running component
+pub const TAKE_NOTIFICATIONS: u32 = 0xfe;
+/// Allow or disallow other components to observe an event of the running component
+pub const SET_EVENT_SUBSCRIBABLE: u32 = 0xff;
 
 #[derive(Debug, TypeId, Encode, Decode)]
 pub struct InvokeSNodeInput {
@@ -61,6 +92,22 @@ pub struct InvokeSNodeOutput {
     pub rtn: Vec<u8>,
 }
 
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct BatchCall {
+    pub op: u32,
+    pub input: Vec<u8>,
+}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct BatchInput {
+    pub calls: Vec<BatchCall>,
+}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct BatchOutput {
+    pub outputs: Vec<Vec<u8>>,
+}
+
 //==========
 // component
 //==========
@@ -70,6 +117,9 @@ pub struct CreateComponentInput {
     pub blueprint_name: String,
     pub state: Vec<u8>,
     pub access_rules_list: Vec<AccessRules>,
+    pub access_rule_slots: BTreeMap<String, (AccessRule, Mutability)>,
+    pub method_toggle_auth: AccessRule,
+    pub features: BTreeSet<String>,
 }
 
 #[derive(Debug, TypeId, Encode, Decode)]
@@ -104,6 +154,34 @@ pub struct PutComponentStateInput {
 #[derive(Debug, TypeId, Encode, Decode)]
 pub struct PutComponentStateOutput {}
 
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct SetAccessRuleInput {
+    pub slot: String,
+    pub rule: AccessRule,
+}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct SetAccessRuleOutput {}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct SetMethodEnabledInput {
+    pub method: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct SetMethodEnabledOutput {}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct IsFeatureEnabledInput {
+    pub feature: String,
+}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct IsFeatureEnabledOutput {
+    pub enabled: bool,
+}
+
 //==========
 // LazyMap
 //==========
@@ -137,6 +215,26 @@ pub struct PutLazyMapEntryInput {
 #[derive(Debug, TypeId, Encode, Decode)]
 pub struct PutLazyMapEntryOutput {}
 
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct CreateReadableLazyMapInput {}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct CreateReadableLazyMapOutput {
+    pub lazy_map_id: LazyMapId,
+}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct GetReadableLazyMapEntryInput {
+    pub component_address: ComponentAddress,
+    pub lazy_map_id: LazyMapId,
+    pub key: Vec<u8>,
+}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct GetReadableLazyMapEntryOutput {
+    pub value: Option<Vec<u8>>,
+}
+
 //==========
 // vault
 //==========
@@ -192,6 +290,28 @@ pub struct GetTransactionHashOutput {
 #[derive(Debug, TypeId, Encode, Decode)]
 pub struct GetTransactionSignersInput {}
 
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct GetTransactionSignersOutput {
+    pub transaction_signers: Vec<NonFungibleAddress>,
+}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct RaiseErrorInput {
+    pub error: Vec<u8>,
+}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct RaiseErrorOutput {}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct RaisePanicInput {
+    pub message: String,
+    pub location: Option<String>,
+}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct RaisePanicOutput {}
+
 #[derive(Debug, TypeId, Encode, Decode)]
 pub struct GenerateUuidInput {}
 
@@ -208,6 +328,58 @@ pub struct GetActorOutput {
     pub actor: ScryptoActorInfo,
 }
 
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct GetCallerInput {}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct GetCallerOutput {
+    pub caller: Option<ScryptoActorInfo>,
+}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct EmitEventInput {
+    pub event: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct EmitEventOutput {}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct SubscribeToEventInput {
+    pub emitter: ComponentAddress,
+    pub event: String,
+}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct SubscribeToEventOutput {}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct UnsubscribeFromEventInput {
+    pub emitter: ComponentAddress,
+    pub event: String,
+}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct UnsubscribeFromEventOutput {}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct SetEventSubscribableInput {
+    pub event: String,
+    pub subscribable: bool,
+}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct SetEventSubscribableOutput {}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct TakeNotificationsInput {}
+
+#[derive(Debug, TypeId, Encode, Decode)]
+pub struct TakeNotificationsOutput {
+    pub notifications: Vec<Notification>,
+}
+
 #[derive(Debug, TypeId, Encode, Decode)]
 pub struct CheckAccessRuleInput {
     pub access_rule: AccessRule,
@@ -4,6 +4,7 @@ pub mod api;
 pub mod types;
 
 use crate::buffer::*;
+use crate::rust::vec::Vec;
 use api::*;
 use sbor::*;
 
@@ -37,3 +38,44 @@ pub fn call_engine<T: Encode, V: Decode>(op: u32, input: T) -> V {
         todo!()
     }
 }
+
+/// A list of radix engine calls, which are made with a single call to the engine.
+///
+/// Batching saves the overhead of crossing the host boundary, e.g. when reading many lazy map
+/// entries or vault balances in a loop.
+#[derive(Debug, Default)]
+pub struct EngineBatch {
+    calls: Vec<BatchCall>,
+}
+
+impl EngineBatch {
+    pub fn new() -> Self {
+        Self { calls: Vec::new() }
+    }
+
+    /// Adds a call to the batch.
+    pub fn add<T: Encode>(&mut self, op: u32, input: T) {
+        self.calls.push(BatchCall {
+            op,
+            input: scrypto_encode(&input),
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Makes the calls in order and returns the decoded output of each call.
+    pub fn execute<V: Decode>(self) -> Vec<V> {
+        let output: BatchOutput = call_engine(BATCH, BatchInput { calls: self.calls });
+        output
+            .outputs
+            .iter()
+            .map(|o| scrypto_decode(o).unwrap())
+            .collect()
+    }
+}
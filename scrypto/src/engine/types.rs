@@ -10,6 +10,7 @@ pub use crate::crypto::EcdsaPublicKey;
 pub use crate::crypto::EcdsaSignature;
 pub use crate::crypto::Hash;
 pub use crate::math::Decimal;
+pub use crate::resource::MetadataValue;
 pub use crate::resource::MintParams;
 pub use crate::resource::NonFungibleAddress;
 pub use crate::resource::NonFungibleId;
@@ -20,5 +21,7 @@ pub type LazyMapId = (Hash, u32);
 pub type BucketId = u32;
 pub type ProofId = u32;
 pub type VaultId = (Hash, u32);
+pub type LockerId = (Hash, u32);
+pub type AllowanceId = (Hash, u32);
 
 pub use crate::constants::*;
@@ -20,6 +20,18 @@ compile_error!("Feature `std` and `alloc` can't be enabled at the same time.");
 pub mod abi {
     pub use scrypto_abi::*;
 }
+/// Scrypto address encoding.
+///
+/// Package, component and resource addresses have two text forms:
+/// - Bech32m, whose human-readable part names the entity type and the network, e.g.
+///   `component_rdx1..` on mainnet. Use `Bech32Encoder` and `Bech32Decoder` with the id of the
+///   network the address belongs to.
+/// - Hex, with a leading entity type byte, which doesn't depend on the network. This is what
+///   `Display` produces.
+///
+/// `FromStr` accepts both, and takes the network of a Bech32m address from its human-readable
+/// part.
+pub mod address;
 /// Scrypto data encoding, decoding and exchange.
 pub mod buffer;
 /// Scrypto component library.
@@ -46,6 +58,9 @@ pub mod rust;
 pub mod types;
 /// Scrypto values.
 pub mod values;
+/// Proptest strategies for Scrypto types.
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
 
 // Export macros
 mod macros;
@@ -57,7 +72,9 @@ pub use sbor::{Decode, Describe, Encode, TypeId};
 
 // Re-export Scrypto derive.
 extern crate scrypto_derive;
-pub use scrypto_derive::{blueprint, import, NonFungibleData};
+pub use scrypto_derive::{
+    blueprint, import, import_types, interface, scrypto_test, NonFungibleData,
+};
 
 // This is to make derives work within this crate.
 // See: https://users.rust-lang.org/t/how-can-i-use-my-derive-macro-from-the-crate-that-declares-the-trait/60502
@@ -99,6 +99,10 @@ macro_rules! trace {
 ///
 /// Notes:
 /// * This macro only works when `std` is linked;
+/// * Packages within a cargo workspace are built into the workspace target directory;
+/// * Features, the profile, the target directory and the workspace member can be set with
+///   `BuildOptions`;
+/// * Use `scrypto::misc::try_compile_package` to handle build errors rather than panicking.
 ///
 /// # Example
 /// ```ignore
@@ -109,6 +113,12 @@ macro_rules! trace {
 ///
 /// // Another package
 /// let wasm2 = compile_package!("/path/to/package");
+///
+/// // A workspace member, with features, in debug
+/// let wasm3 = compile_package!(
+///     "/path/to/workspace",
+///     BuildOptions::new().package("my-package").feature("foo").release(false)
+/// );
 /// ```
 #[macro_export]
 macro_rules! compile_package {
@@ -118,13 +128,18 @@ macro_rules! compile_package {
     ($package_dir: expr) => {
         ::scrypto::misc::compile_package($package_dir)
     };
+    ($package_dir: expr, $options: expr) => {
+        ::scrypto::misc::compile_package_with_options($package_dir, &$options)
+    };
 }
 
 /// Includes the WASM file of a Scrypto package.
 ///
 /// Notes:
 /// * This macro will NOT compile the package;
-/// * The WASM file name is normally the package name with `-` replaced with `_`.
+/// * The WASM file name is normally the package name with `-` replaced with `_`;
+/// * For packages within a workspace, or built with a custom target dir, pass the target
+///   directory, and optionally the profile, which defaults to `release`.
 ///
 /// # Example
 /// ```ignore
@@ -135,9 +150,34 @@ macro_rules! compile_package {
 ///
 /// // Another package
 /// let wasm2 = include_package!("/path/to/package", "wasm_name");
+///
+/// // A workspace member, built in debug
+/// let wasm3 = include_package!(
+///     target_dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../target"),
+///     profile = "debug",
+///     "wasm_name"
+/// );
 /// ```
 #[macro_export]
 macro_rules! include_package {
+    (target_dir = $target_dir: expr, profile = $profile: expr, $wasm_name: expr) => {
+        include_bytes!(concat!(
+            $target_dir,
+            "/wasm32-unknown-unknown/",
+            $profile,
+            "/",
+            $wasm_name,
+            ".wasm"
+        ))
+    };
+    (target_dir = $target_dir: expr, $wasm_name: expr) => {
+        include_bytes!(concat!(
+            $target_dir,
+            "/wasm32-unknown-unknown/release/",
+            $wasm_name,
+            ".wasm"
+        ))
+    };
     ($wasm_name: expr) => {
         include_bytes!(concat!(
             env!("CARGO_MANIFEST_DIR"),
@@ -155,3 +195,37 @@ macro_rules! include_package {
         ))
     };
 }
+
+/// Exports the schemas of the given structs and enums, so that other packages can import them
+/// with `import_types!` rather than copying their definitions.
+///
+/// A package can only export types once; it may or may not also define blueprints.
+///
+/// # Example
+/// ```ignore
+/// use scrypto::prelude::*;
+///
+/// #[derive(TypeId, Encode, Decode, Describe)]
+/// pub struct Order {
+///     pub amount: Decimal,
+///     pub price: Decimal,
+/// }
+///
+/// export_types!(Order);
+/// ```
+#[macro_export]
+macro_rules! export_types {
+    ($($type: ty),* $(,)?) => {
+        #[no_mangle]
+        pub extern "C" fn scrypto_types() -> *mut u8 {
+            let types: ::scrypto::rust::vec::Vec<::sbor::Type> =
+                ::scrypto::rust::vec![$(<$type as ::sbor::Describe>::describe()),*];
+
+            // serialize the output
+            let output_bytes = ::scrypto::buffer::scrypto_encode_for_radix_engine(&types);
+
+            // return the output wrapped in a radix-style buffer
+            ::scrypto::buffer::scrypto_wrap(output_bytes)
+        }
+    };
+}
@@ -1,6 +1,6 @@
 use core::ops::*;
 use num_bigint::BigInt;
-use num_traits::Signed;
+use num_traits::{Signed, Zero};
 use sbor::rust::iter;
 use sbor::*;
 
@@ -37,6 +37,8 @@ pub enum RoundingMode {
     TowardsNearestAndHalfTowardsZero,
     /// Rounds to the nearest and when a number is halfway between two others, it's rounded away zero, e.g. `3.5 -> 4`, `-3.5 -> -4`.
     TowardsNearestAndHalfAwayFromZero,
+    /// Rounds to the nearest and when a number is halfway between two others, it's rounded towards the even one, e.g. `3.5 -> 4`, `2.5 -> 2`.
+    TowardsNearestAndHalfToEven,
 }
 
 impl Default for Decimal {
@@ -107,78 +109,189 @@ impl Decimal {
         self.round(0, RoundingMode::TowardsPositiveInfinity)
     }
 
+    /// Rounds this number to the given number of decimal places.
     pub fn round(&self, decimal_places: u8, mode: RoundingMode) -> Self {
         assert!(decimal_places <= 18);
 
-        let divisor = 10i128.pow(18 - decimal_places as u32);
-        match mode {
-            RoundingMode::TowardsPositiveInfinity => {
-                if self.0 % divisor == 0 {
-                    self.clone()
-                } else if self.is_negative() {
-                    Self(self.0 / divisor * divisor)
-                } else {
-                    Self((self.0 / divisor + 1) * divisor)
-                }
-            }
-            RoundingMode::TowardsNegativeInfinity => {
-                if self.0 % divisor == 0 {
-                    self.clone()
-                } else if self.is_negative() {
-                    Self((self.0 / divisor - 1) * divisor)
-                } else {
-                    Self(self.0 / divisor * divisor)
-                }
-            }
-            RoundingMode::TowardsZero => {
-                if self.0 % divisor == 0 {
-                    self.clone()
-                } else {
-                    Self(self.0 / divisor * divisor)
-                }
-            }
-            RoundingMode::AwayFromZero => {
-                if self.0 % divisor == 0 {
-                    self.clone()
-                } else if self.is_negative() {
-                    Self((self.0 / divisor - 1) * divisor)
-                } else {
-                    Self((self.0 / divisor + 1) * divisor)
+        let divisor = BigInt::from(10i128.pow(18 - decimal_places as u32));
+        let quotient = round_quotient(BigInt::from(self.0), &divisor, mode);
+        big_int_to_decimal(quotient * divisor)
+    }
+
+    /// Divides this number by another, rounding the result to 18 decimal places with the
+    /// given mode. The division operator always rounds towards zero.
+    ///
+    /// Panics if the divisor is zero, or if the result overflows.
+    pub fn div_with_rounding<T: Into<Decimal>>(&self, other: T, mode: RoundingMode) -> Self {
+        let a = BigInt::from(self.0) * Self::ONE.0;
+        let b = BigInt::from(other.into().0);
+        big_int_to_decimal(round_quotient(a, &b, mode))
+    }
+}
+
+/// Divides `a` by `b`, rounding the quotient to an integer with the given mode.
+fn round_quotient(a: BigInt, b: &BigInt, mode: RoundingMode) -> BigInt {
+    let quotient = &a / b;
+    let remainder = &a % b;
+    if remainder.is_zero() {
+        return quotient;
+    }
+
+    // The step which moves the truncated quotient away from zero.
+    let away = if a.is_negative() == b.is_negative() {
+        BigInt::from(1)
+    } else {
+        BigInt::from(-1)
+    };
+    let rounds_away = match mode {
+        RoundingMode::TowardsPositiveInfinity => away.is_positive(),
+        RoundingMode::TowardsNegativeInfinity => away.is_negative(),
+        RoundingMode::TowardsZero => false,
+        RoundingMode::AwayFromZero => true,
+        RoundingMode::TowardsNearestAndHalfTowardsZero
+        | RoundingMode::TowardsNearestAndHalfAwayFromZero
+        | RoundingMode::TowardsNearestAndHalfToEven => {
+            let twice_remainder = remainder.abs() * 2;
+            let divisor = b.abs();
+            if twice_remainder != divisor {
+                twice_remainder > divisor
+            } else {
+                match mode {
+                    RoundingMode::TowardsNearestAndHalfTowardsZero => false,
+                    RoundingMode::TowardsNearestAndHalfAwayFromZero => true,
+                    _ => !(&quotient % BigInt::from(2)).is_zero(),
                 }
             }
-            RoundingMode::TowardsNearestAndHalfTowardsZero => {
-                if self.0 % divisor == 0 {
-                    self.clone()
-                } else {
-                    let digit = (self.0 / (divisor / 10) % 10).abs();
-                    if digit > 5 {
-                        if self.is_negative() {
-                            Self((self.0 / divisor - 1) * divisor)
-                        } else {
-                            Self((self.0 / divisor + 1) * divisor)
-                        }
-                    } else {
-                        Self(self.0 / divisor * divisor)
-                    }
-                }
+        }
+    };
+
+    if rounds_away {
+        quotient + away
+    } else {
+        quotient
+    }
+}
+
+impl Decimal {
+    /// Raises this number to an integer power.
+    ///
+    /// The result is truncated towards zero, with an error of less than `10^-18`.
+    /// Panics if the result overflows, or if zero is raised to a negative power.
+    pub fn powi(&self, exp: i64) -> Self {
+        let one = precise_one();
+        let max = to_precise(Self::MAX).abs();
+        let mut base = to_precise(*self);
+        if exp < 0 {
+            base = precise_div(&one, &base);
+        }
+
+        let mut result = one;
+        let mut n = exp.unsigned_abs();
+        while n > 0 {
+            if n & 1 == 1 {
+                result = precise_mul(&result, &base);
             }
-            RoundingMode::TowardsNearestAndHalfAwayFromZero => {
-                if self.0 % divisor == 0 {
-                    self.clone()
-                } else {
-                    let digit = (self.0 / (divisor / 10) % 10).abs();
-                    if digit < 5 {
-                        Self(self.0 / divisor * divisor)
-                    } else {
-                        if self.is_negative() {
-                            Self((self.0 / divisor - 1) * divisor)
-                        } else {
-                            Self((self.0 / divisor + 1) * divisor)
-                        }
-                    }
+            n >>= 1;
+            if n > 0 {
+                base = precise_mul(&base, &base);
+                if base.abs() > max {
+                    panic!("Overflow");
                 }
             }
         }
+        from_precise(result)
+    }
+
+    /// Returns the square root, or `None` if this number is negative.
+    ///
+    /// The result is truncated towards zero, with an error of less than `10^-18`.
+    pub fn sqrt(&self) -> Option<Self> {
+        if self.is_negative() {
+            return None;
+        }
+        let n = BigInt::from(self.0) * Self::ONE.0;
+        Some(big_int_to_decimal(n.sqrt()))
+    }
+
+    /// Returns the cube root.
+    ///
+    /// The result is truncated towards zero, with an error of less than `10^-18`.
+    pub fn cbrt(&self) -> Self {
+        let n = BigInt::from(self.0) * Self::ONE.0 * Self::ONE.0;
+        big_int_to_decimal(n.cbrt())
+    }
+
+    /// Returns the natural logarithm, or `None` if this number isn't positive.
+    ///
+    /// The result is truncated towards zero, with an error of less than `10^-18`.
+    pub fn ln(&self) -> Option<Self> {
+        if !self.is_positive() {
+            return None;
+        }
+
+        // Reduce to `x = y * 2^k`, where `1 <= y < 2`
+        let one = precise_one();
+        let two: BigInt = &one * 2;
+        let mut y = to_precise(*self);
+        let mut k = y.bits() as i64 - one.bits() as i64;
+        y = if k >= 0 { y >> k } else { y << -k };
+        while y >= two {
+            y >>= 1;
+            k += 1;
+        }
+        while y < one {
+            y <<= 1;
+            k -= 1;
+        }
+
+        // ln(y) = 2 * atanh(z), where z = (y - 1) / (y + 1) and 0 <= z < 1/3
+        let z = precise_div(&(&y - &one), &(&y + &one));
+        let z2 = precise_mul(&z, &z);
+        let mut term = z;
+        let mut sum = BigInt::from(0);
+        let mut i = 1u32;
+        while !term.is_zero() {
+            sum += &term / i;
+            term = precise_mul(&term, &z2);
+            i += 2;
+        }
+
+        Some(from_precise(sum * 2 + precise_ln2() * k))
+    }
+
+    /// Returns `e` raised to the power of this number, the inverse of `ln`.
+    ///
+    /// The result is truncated towards zero, with an error of less than `10^-18`, so it's zero
+    /// for numbers below about `-41.45`, where it's less than `10^-18`.
+    /// Panics if the result overflows, i.e. for numbers above about `46.58`.
+    pub fn exp(&self) -> Self {
+        // e^47 is already beyond the max value, and e^-42 is below the smallest positive value
+        if *self > Self::from(50) {
+            panic!("Overflow");
+        }
+        if *self < Self::from(-50) {
+            return Self::zero();
+        }
+
+        // Reduce to `e^x = e^r * 2^k`, where `|r| < ln(2)`
+        let one = precise_one();
+        let ln2 = precise_ln2();
+        let x = to_precise(*self);
+        let k = &x / &ln2;
+        let r = &x - &k * &ln2;
+
+        let mut term = one.clone();
+        let mut sum = one;
+        let mut i = 1u32;
+        while !term.is_zero() {
+            term = precise_mul(&term, &r) / i;
+            sum += &term;
+            i += 1;
+        }
+
+        let k = i64::try_from(k).unwrap();
+        let result = if k >= 0 { sum << k } else { sum >> -k };
+        from_precise(result)
     }
 }
 
@@ -291,6 +404,36 @@ fn big_int_to_decimal(v: BigInt) -> Decimal {
     }
 }
 
+/// The number of decimal places used internally by `powi`, `ln` and `exp`.
+const PRECISE_SCALE: u32 = 48;
+
+/// ln(2), with `PRECISE_SCALE` decimal places.
+const LN2_DIGITS: &str = "693147180559945309417232121458176568075500134360";
+
+fn precise_one() -> BigInt {
+    BigInt::from(10).pow(PRECISE_SCALE)
+}
+
+fn precise_ln2() -> BigInt {
+    BigInt::parse_bytes(LN2_DIGITS.as_bytes(), 10).unwrap()
+}
+
+fn to_precise(d: Decimal) -> BigInt {
+    BigInt::from(d.0) * BigInt::from(10).pow(PRECISE_SCALE - Decimal::SCALE)
+}
+
+fn from_precise(v: BigInt) -> Decimal {
+    big_int_to_decimal(v / BigInt::from(10).pow(PRECISE_SCALE - Decimal::SCALE))
+}
+
+fn precise_mul(a: &BigInt, b: &BigInt) -> BigInt {
+    a * b / precise_one()
+}
+
+fn precise_div(a: &BigInt, b: &BigInt) -> BigInt {
+    a * precise_one() / b
+}
+
 impl<T: Into<Decimal>> Mul<T> for Decimal {
     type Output = Decimal;
 
@@ -791,6 +934,85 @@ mod tests {
         assert_eq!(dec!("-5.5").round(0, mode).to_string(), "-6");
     }
 
+    #[test]
+    fn test_round_towards_nearest_and_half_to_even() {
+        let mode = RoundingMode::TowardsNearestAndHalfToEven;
+        assert_eq!(dec!("5.5").round(0, mode).to_string(), "6");
+        assert_eq!(dec!("2.5").round(0, mode).to_string(), "2");
+        assert_eq!(dec!("1.6").round(0, mode).to_string(), "2");
+        assert_eq!(dec!("1.1").round(0, mode).to_string(), "1");
+        assert_eq!(dec!("1.0").round(0, mode).to_string(), "1");
+        assert_eq!(dec!("-1.0").round(0, mode).to_string(), "-1");
+        assert_eq!(dec!("-1.1").round(0, mode).to_string(), "-1");
+        assert_eq!(dec!("-1.6").round(0, mode).to_string(), "-2");
+        assert_eq!(dec!("-2.5").round(0, mode).to_string(), "-2");
+        assert_eq!(dec!("-5.5").round(0, mode).to_string(), "-6");
+    }
+
+    #[test]
+    fn test_round_considers_all_remaining_digits() {
+        let mode = RoundingMode::TowardsNearestAndHalfTowardsZero;
+        assert_eq!(dec!("2.51").round(0, mode).to_string(), "3");
+        assert_eq!(
+            dec!("-2.500000000000000001").round(0, mode).to_string(),
+            "-3"
+        );
+        let mode = RoundingMode::TowardsNearestAndHalfToEven;
+        assert_eq!(dec!("2.500000000000000001").round(0, mode).to_string(), "3");
+        assert_eq!(dec!("0.125").round(2, mode).to_string(), "0.12");
+        assert_eq!(dec!("0.135").round(2, mode).to_string(), "0.14");
+    }
+
+    #[test]
+    fn test_div_with_rounding() {
+        let a = Decimal::from(2u32);
+        let b = Decimal::from(3u32);
+        assert_eq!(
+            a.div_with_rounding(b, RoundingMode::TowardsZero)
+                .to_string(),
+            "0.666666666666666666"
+        );
+        assert_eq!(
+            a.div_with_rounding(b, RoundingMode::TowardsNearestAndHalfAwayFromZero)
+                .to_string(),
+            "0.666666666666666667"
+        );
+        assert_eq!(
+            (-a).div_with_rounding(b, RoundingMode::TowardsPositiveInfinity)
+                .to_string(),
+            "-0.666666666666666666"
+        );
+        assert_eq!(
+            (-a).div_with_rounding(b, RoundingMode::TowardsNegativeInfinity)
+                .to_string(),
+            "-0.666666666666666667"
+        );
+        assert_eq!(
+            a.div_with_rounding(-b, RoundingMode::AwayFromZero)
+                .to_string(),
+            "-0.666666666666666667"
+        );
+        assert_eq!(
+            Decimal(5).div_with_rounding(2, RoundingMode::TowardsNearestAndHalfToEven),
+            Decimal(2)
+        );
+        assert_eq!(
+            Decimal(7).div_with_rounding(2, RoundingMode::TowardsNearestAndHalfToEven),
+            Decimal(4)
+        );
+        assert_eq!(
+            Decimal(5).div_with_rounding(2, RoundingMode::TowardsNearestAndHalfTowardsZero),
+            Decimal(2)
+        );
+        assert_eq!(a.div_with_rounding(b, RoundingMode::TowardsZero), a / b);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_div_with_rounding_by_zero() {
+        Decimal::one().div_with_rounding(0, RoundingMode::TowardsZero);
+    }
+
     #[test]
     fn test_various_decimal_places() {
         let mode = RoundingMode::TowardsNearestAndHalfAwayFromZero;
@@ -811,4 +1033,68 @@ mod tests {
         assert_eq!(sum1, dec!("6"));
         assert_eq!(sum2, dec!("6"));
     }
+
+    #[test]
+    fn test_powi() {
+        assert_eq!(dec!("1.1").powi(10), dec!("2.5937424601"));
+        assert_eq!(dec!("2").powi(-3), dec!("0.125"));
+        assert_eq!(dec!("-1.5").powi(3), dec!("-3.375"));
+        assert_eq!(dec!("7").powi(0), dec!("1"));
+        assert_eq!(
+            dec!("1.000000000000000001").powi(1000000),
+            dec!("1.000000000001")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Overflow")]
+    fn test_powi_overflow_should_panic() {
+        let _ = dec!("10000").powi(6);
+    }
+
+    #[test]
+    fn test_sqrt() {
+        assert_eq!(dec!("2").sqrt(), Some(dec!("1.414213562373095048")));
+        assert_eq!(dec!("0.01").sqrt(), Some(dec!("0.1")));
+        assert_eq!(dec!("0").sqrt(), Some(dec!("0")));
+        assert_eq!(dec!("-1").sqrt(), None);
+    }
+
+    #[test]
+    fn test_cbrt() {
+        assert_eq!(dec!("2").cbrt(), dec!("1.259921049894873164"));
+        assert_eq!(dec!("-27").cbrt(), dec!("-3"));
+        assert_eq!(dec!("0.001").cbrt(), dec!("0.1"));
+    }
+
+    #[test]
+    fn test_ln() {
+        assert_eq!(dec!("2").ln(), Some(dec!("0.693147180559945309")));
+        assert_eq!(dec!("10").ln(), Some(dec!("2.302585092994045684")));
+        assert_eq!(dec!("1").ln(), Some(dec!("0")));
+        assert_eq!(Decimal(1).ln(), Some(dec!("-41.446531673892822312")));
+        assert_eq!(dec!("0").ln(), None);
+        assert_eq!(dec!("-1").ln(), None);
+    }
+
+    #[test]
+    fn test_exp() {
+        assert_eq!(dec!("1").exp(), dec!("2.718281828459045235"));
+        assert_eq!(dec!("-1").exp(), dec!("0.367879441171442321"));
+        assert_eq!(dec!("0").exp(), dec!("1"));
+        assert_eq!(
+            dec!("45").exp(),
+            dec!("34934271057485095348.034797233406099533")
+        );
+        assert_eq!(dec!("-100").exp(), dec!("0"));
+        assert_eq!(dec!("-41.5").exp(), dec!("0"));
+        assert_eq!(dec!("-41.4").exp(), dec!("0.000000000000000001"));
+        assert!(dec!("46.5").exp().is_positive());
+    }
+
+    #[test]
+    #[should_panic(expected = "Overflow")]
+    fn test_exp_overflow_should_panic() {
+        let _ = dec!("47").exp();
+    }
 }
@@ -0,0 +1,480 @@
+use core::cmp::Ordering;
+use core::ops::*;
+use num_bigint::{BigInt, Sign};
+use num_traits::Signed;
+use sbor::*;
+
+use crate::math::Decimal;
+use crate::misc::*;
+use crate::rust::convert::TryFrom;
+use crate::rust::fmt;
+use crate::rust::str::FromStr;
+use crate::rust::string::ToString;
+use crate::rust::vec::Vec;
+use crate::types::*;
+
+/// `I256` represents a 256-bit signed integer.
+///
+/// Unless otherwise specified, all operations will panic if underflow/overflow.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct I256([u8; 32]);
+
+/// `U256` represents a 256-bit unsigned integer.
+///
+/// Unless otherwise specified, all operations will panic if underflow/overflow.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct U256([u8; 32]);
+
+/// Represents an error when parsing I256.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseI256Error {
+    InvalidDigit,
+    InvalidLength(usize),
+    Overflow,
+}
+
+/// Represents an error when parsing U256.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseU256Error {
+    InvalidDigit,
+    InvalidLength(usize),
+    Overflow,
+}
+
+impl I256 {
+    /// The min value of `I256`.
+    pub const MIN: Self = Self(min_signed_bytes());
+
+    /// The max value of `I256`.
+    pub const MAX: Self = Self(max_signed_bytes());
+
+    /// Whether this number is positive.
+    pub fn is_positive(&self) -> bool {
+        !self.is_negative() && !self.is_zero()
+    }
+
+    /// Whether this number is negative.
+    pub fn is_negative(&self) -> bool {
+        self.0[31] & 0x80 != 0
+    }
+
+    /// Returns the absolute value.
+    pub fn abs(&self) -> Self {
+        Self::from_big_int(self.to_big_int().abs())
+    }
+
+    fn to_big_int(&self) -> BigInt {
+        BigInt::from_signed_bytes_le(&self.0)
+    }
+
+    fn try_from_big_int(v: BigInt) -> Option<Self> {
+        let bytes = v.to_signed_bytes_le();
+        if bytes.len() > 32 {
+            None
+        } else {
+            let mut buf = if v.is_negative() {
+                [255u8; 32]
+            } else {
+                [0u8; 32]
+            };
+            buf[..bytes.len()].copy_from_slice(&bytes);
+            Some(Self(buf))
+        }
+    }
+}
+
+impl U256 {
+    /// The min value of `U256`.
+    pub const MIN: Self = Self([0u8; 32]);
+
+    /// The max value of `U256`.
+    pub const MAX: Self = Self([255u8; 32]);
+
+    fn to_big_int(&self) -> BigInt {
+        BigInt::from_bytes_le(Sign::Plus, &self.0)
+    }
+
+    fn try_from_big_int(v: BigInt) -> Option<Self> {
+        let (sign, bytes) = v.to_bytes_le();
+        if sign == Sign::Minus || bytes.len() > 32 {
+            None
+        } else {
+            let mut buf = [0u8; 32];
+            buf[..bytes.len()].copy_from_slice(&bytes);
+            Some(Self(buf))
+        }
+    }
+}
+
+const fn min_signed_bytes() -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes[31] = 0x80;
+    bytes
+}
+
+const fn max_signed_bytes() -> [u8; 32] {
+    let mut bytes = [255u8; 32];
+    bytes[31] = 0x7f;
+    bytes
+}
+
+const fn one_bytes() -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes[0] = 1;
+    bytes
+}
+
+macro_rules! big_integer {
+    ($t:ident, $err:ident, $scrypto_type:expr) => {
+        impl $t {
+            pub const ZERO: Self = Self([0u8; 32]);
+
+            pub const ONE: Self = Self(one_bytes());
+
+            /// Returns `0`.
+            pub fn zero() -> Self {
+                Self::ZERO
+            }
+
+            /// Returns `1`.
+            pub fn one() -> Self {
+                Self::ONE
+            }
+
+            /// Whether this number is zero.
+            pub fn is_zero(&self) -> bool {
+                self.0 == [0u8; 32]
+            }
+
+            /// Raises this number to a power.
+            pub fn pow(&self, exp: u32) -> Self {
+                let mut base = self.to_big_int();
+                let mut result = BigInt::from(1);
+                let mut n = exp;
+                while n > 0 {
+                    if n & 1 == 1 {
+                        result = Self::from_big_int(result * &base).to_big_int();
+                    }
+                    n >>= 1;
+                    if n > 0 {
+                        base = Self::from_big_int(&base * &base).to_big_int();
+                    }
+                }
+                Self::from_big_int(result)
+            }
+
+            /// Creates from little-endian bytes.
+            pub fn from_le_bytes(bytes: [u8; 32]) -> Self {
+                Self(bytes)
+            }
+
+            /// Returns the little-endian bytes.
+            pub fn to_le_bytes(&self) -> [u8; 32] {
+                self.0
+            }
+
+            fn from_big_int(v: BigInt) -> Self {
+                Self::try_from_big_int(v).expect("Overflow")
+            }
+        }
+
+        impl Default for $t {
+            fn default() -> Self {
+                Self::zero()
+            }
+        }
+
+        impl PartialOrd for $t {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $t {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.to_big_int().cmp(&other.to_big_int())
+            }
+        }
+
+        impl<T: Into<$t>> Add<T> for $t {
+            type Output = $t;
+
+            fn add(self, other: T) -> Self::Output {
+                Self::from_big_int(self.to_big_int() + other.into().to_big_int())
+            }
+        }
+
+        impl<T: Into<$t>> Sub<T> for $t {
+            type Output = $t;
+
+            fn sub(self, other: T) -> Self::Output {
+                Self::from_big_int(self.to_big_int() - other.into().to_big_int())
+            }
+        }
+
+        impl<T: Into<$t>> Mul<T> for $t {
+            type Output = $t;
+
+            fn mul(self, other: T) -> Self::Output {
+                Self::from_big_int(self.to_big_int() * other.into().to_big_int())
+            }
+        }
+
+        impl<T: Into<$t>> Div<T> for $t {
+            type Output = $t;
+
+            fn div(self, other: T) -> Self::Output {
+                Self::from_big_int(self.to_big_int() / other.into().to_big_int())
+            }
+        }
+
+        impl<T: Into<$t>> Rem<T> for $t {
+            type Output = $t;
+
+            fn rem(self, other: T) -> Self::Output {
+                Self::from_big_int(self.to_big_int() % other.into().to_big_int())
+            }
+        }
+
+        impl<T: Into<$t>> AddAssign<T> for $t {
+            fn add_assign(&mut self, other: T) {
+                *self = *self + other;
+            }
+        }
+
+        impl<T: Into<$t>> SubAssign<T> for $t {
+            fn sub_assign(&mut self, other: T) {
+                *self = *self - other;
+            }
+        }
+
+        impl<T: Into<$t>> MulAssign<T> for $t {
+            fn mul_assign(&mut self, other: T) {
+                *self = *self * other;
+            }
+        }
+
+        impl<T: Into<$t>> DivAssign<T> for $t {
+            fn div_assign(&mut self, other: T) {
+                *self = *self / other;
+            }
+        }
+
+        impl<T: Into<$t>> RemAssign<T> for $t {
+            fn rem_assign(&mut self, other: T) {
+                *self = *self % other;
+            }
+        }
+
+        /// Converts the integral part of a decimal, rounding towards zero.
+        impl TryFrom<Decimal> for $t {
+            type Error = $err;
+
+            fn try_from(val: Decimal) -> Result<Self, Self::Error> {
+                Self::try_from_big_int(BigInt::from(val.0) / Decimal::ONE.0).ok_or($err::Overflow)
+            }
+        }
+
+        impl TryFrom<$t> for Decimal {
+            type Error = $err;
+
+            fn try_from(val: $t) -> Result<Self, Self::Error> {
+                let v = val.to_big_int() * Decimal::ONE.0;
+                i128::try_from(v).map(Decimal).map_err(|_| $err::Overflow)
+            }
+        }
+
+        #[cfg(not(feature = "alloc"))]
+        impl std::error::Error for $err {}
+
+        #[cfg(not(feature = "alloc"))]
+        impl fmt::Display for $err {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{:?}", self)
+            }
+        }
+
+        //========
+        // binary
+        //========
+
+        impl TryFrom<&[u8]> for $t {
+            type Error = $err;
+
+            fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+                if slice.len() == 32 {
+                    Ok(Self(copy_u8_array(slice)))
+                } else {
+                    Err($err::InvalidLength(slice.len()))
+                }
+            }
+        }
+
+        impl $t {
+            pub fn to_vec(&self) -> Vec<u8> {
+                self.0.to_vec()
+            }
+        }
+
+        scrypto_type!($t, $scrypto_type, Vec::new());
+
+        //======
+        // text
+        //======
+
+        impl FromStr for $t {
+            type Err = $err;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let v = BigInt::from_str(s).map_err(|_| $err::InvalidDigit)?;
+                Self::try_from_big_int(v).ok_or($err::Overflow)
+            }
+        }
+
+        impl fmt::Display for $t {
+            fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+                write!(f, "{}", self.to_big_int().to_string())
+            }
+        }
+
+        impl fmt::Debug for $t {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.to_string())
+            }
+        }
+    };
+}
+
+big_integer!(I256, ParseI256Error, ScryptoType::I256);
+big_integer!(U256, ParseU256Error, ScryptoType::U256);
+
+macro_rules! from_int {
+    ($t:ident, $($type:ident),*) => {
+        $(
+            impl From<$type> for $t {
+                fn from(val: $type) -> Self {
+                    Self::from_big_int(BigInt::from(val))
+                }
+            }
+        )*
+    };
+}
+from_int!(I256, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+from_int!(U256, u8, u16, u32, u64, u128, usize);
+
+impl Neg for I256 {
+    type Output = I256;
+
+    fn neg(self) -> Self::Output {
+        Self::from_big_int(-self.to_big_int())
+    }
+}
+
+impl TryFrom<I256> for U256 {
+    type Error = ParseU256Error;
+
+    fn try_from(val: I256) -> Result<Self, Self::Error> {
+        Self::try_from_big_int(val.to_big_int()).ok_or(ParseU256Error::Overflow)
+    }
+}
+
+impl TryFrom<U256> for I256 {
+    type Error = ParseI256Error;
+
+    fn try_from(val: U256) -> Result<Self, Self::Error> {
+        Self::try_from_big_int(val.to_big_int()).ok_or(ParseI256Error::Overflow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dec;
+
+    #[test]
+    fn test_format_and_parse() {
+        assert_eq!(
+            I256::MAX.to_string(),
+            "57896044618658097711785492504343953926634992332820282019728792003956564819967"
+        );
+        assert_eq!(
+            I256::MIN.to_string(),
+            "-57896044618658097711785492504343953926634992332820282019728792003956564819968"
+        );
+        assert_eq!(
+            U256::MAX.to_string(),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+        assert_eq!(I256::from_str(&I256::MIN.to_string()), Ok(I256::MIN));
+        assert_eq!(U256::from_str(&U256::MAX.to_string()), Ok(U256::MAX));
+        assert_eq!(I256::from_str("-123"), Ok(I256::from(-123)));
+        assert_eq!(U256::from_str("-1"), Err(ParseU256Error::Overflow));
+        assert_eq!(U256::from_str("1.5"), Err(ParseU256Error::InvalidDigit));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = I256::from(i128::MAX);
+        let b = a * a;
+        assert_eq!(b / a, a);
+        assert_eq!(b % a, I256::zero());
+        assert_eq!(I256::from(-7) / 2, I256::from(-3));
+        assert_eq!(I256::from(-7) % 2, I256::from(-1));
+        assert_eq!(-I256::from(5) + 3, I256::from(-2));
+        assert_eq!(
+            U256::from(10u32).pow(77) - 1u32,
+            U256::from_str(&"9".repeat(77)).unwrap()
+        );
+        assert!(I256::from(-1) < I256::from(1));
+        assert!(I256::MIN < I256::MAX);
+        assert!(U256::from(256u32) > U256::from(255u32));
+    }
+
+    #[test]
+    #[should_panic(expected = "Overflow")]
+    fn test_add_overflow() {
+        let _ = I256::MAX + 1;
+    }
+
+    #[test]
+    #[should_panic(expected = "Overflow")]
+    fn test_sub_underflow() {
+        let _ = U256::zero() - 1u32;
+    }
+
+    #[test]
+    #[should_panic(expected = "Overflow")]
+    fn test_pow_overflow() {
+        let _ = U256::from(2u32).pow(256);
+    }
+
+    #[test]
+    fn test_decimal_conversion() {
+        assert_eq!(I256::try_from(dec!("-1.9")), Ok(I256::from(-1)));
+        assert_eq!(U256::try_from(dec!("-1")), Err(ParseU256Error::Overflow));
+        assert_eq!(Decimal::try_from(I256::from(-5)), Ok(dec!("-5")));
+        assert_eq!(
+            Decimal::try_from(I256::from(i128::MAX)),
+            Err(ParseI256Error::Overflow)
+        );
+
+        // x * y = k, with intermediate products beyond the range of Decimal
+        let x = I256::from(dec!("1000000000").0);
+        let y = I256::from(dec!("2000000000").0);
+        let k = x * y / I256::from(Decimal::ONE.0);
+        assert_eq!(
+            Decimal::try_from(k / I256::from(Decimal::ONE.0)),
+            Ok(dec!("2000000000000000000"))
+        );
+    }
+
+    #[test]
+    fn test_binary() {
+        let v = I256::from(-2);
+        assert_eq!(I256::try_from(v.to_vec().as_slice()), Ok(v));
+        assert_eq!(
+            U256::try_from([0u8; 31].as_slice()),
+            Err(ParseU256Error::InvalidLength(31))
+        );
+    }
+}
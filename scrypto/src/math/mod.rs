@@ -1,3 +1,5 @@
 mod decimal;
+mod integer;
 
 pub use decimal::*;
+pub use integer::*;
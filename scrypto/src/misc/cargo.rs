@@ -1,50 +1,674 @@
 use cargo_toml::{Manifest, Product};
+use std::env;
+use std::fmt;
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::SystemTime;
+
+use crate::crypto::{hash, Hash};
+
+/// The directory, relative to the package target directory, where compiled WASM is cached.
+const BUILD_CACHE_DIR: &str = "scrypto-cache";
+
+/// The maximum total size of the cached WASM files of a target directory, beyond which the
+/// least recently used ones are evicted.
+const BUILD_CACHE_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+/// The target which Scrypto packages are compiled to.
+const WASM_TARGET: &str = "wasm32-unknown-unknown";
+
+/// Options for building a Scrypto package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildOptions {
+    /// The workspace member to build, if the package directory is a workspace root.
+    pub package: Option<String>,
+    /// The features to enable.
+    pub features: Vec<String>,
+    /// Whether to build with the release profile, rather than the debug profile.
+    pub release: bool,
+    /// The target directory, overriding `CARGO_TARGET_DIR` and the workspace target directory.
+    pub target_dir: Option<PathBuf>,
+    /// Whether cargo output is shown, rather than captured into the build error.
+    pub verbose: bool,
+}
+
+impl Default for BuildOptions {
+    fn default() -> Self {
+        Self {
+            package: None,
+            features: Vec::new(),
+            release: true,
+            target_dir: None,
+            verbose: false,
+        }
+    }
+}
+
+impl BuildOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn package<S: Into<String>>(mut self, package: S) -> Self {
+        self.package = Some(package.into());
+        self
+    }
+
+    pub fn feature<S: Into<String>>(mut self, feature: S) -> Self {
+        self.features.push(feature.into());
+        self
+    }
+
+    pub fn features<I: IntoIterator<Item = S>, S: Into<String>>(mut self, features: I) -> Self {
+        self.features.extend(features.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn release(mut self, release: bool) -> Self {
+        self.release = release;
+        self
+    }
+
+    pub fn target_dir<P: Into<PathBuf>>(mut self, target_dir: P) -> Self {
+        self.target_dir = Some(target_dir.into());
+        self
+    }
+
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// The name of the cargo profile directory.
+    pub fn profile(&self) -> &'static str {
+        if self.release {
+            "release"
+        } else {
+            "debug"
+        }
+    }
+}
+
+/// Represents an error when building a Scrypto package.
+#[derive(Debug)]
+pub enum BuildError {
+    /// The directory does not contain a `Cargo.toml` file.
+    NotCargoPackage(PathBuf),
+    /// The manifest can't be parsed.
+    InvalidManifest(PathBuf),
+    /// The manifest is a virtual workspace manifest, and no member was selected.
+    PackageNotSpecified(PathBuf),
+    /// Cargo can't be started.
+    FailedToRunCargo(io::Error),
+    /// Cargo failed; `stderr` holds its output, unless the build was verbose.
+    FailedToBuild { status: ExitStatus, stderr: String },
+    /// The build succeeded but the WASM file can't be read.
+    WasmNotFound(PathBuf, io::Error),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::NotCargoPackage(path) => {
+                write!(f, "Not a cargo package: {}", path.display())
+            }
+            BuildError::InvalidManifest(path) => {
+                write!(f, "Invalid manifest file: {}", path.display())
+            }
+            BuildError::PackageNotSpecified(path) => {
+                write!(f, "No package selected in workspace: {}", path.display())
+            }
+            BuildError::FailedToRunCargo(e) => write!(f, "Failed to run cargo: {}", e),
+            BuildError::FailedToBuild { status, stderr } => {
+                write!(f, "Failed to build package ({})", status)?;
+                if !stderr.is_empty() {
+                    write!(f, "\n{}", stderr)?;
+                }
+                Ok(())
+            }
+            BuildError::WasmNotFound(path, e) => {
+                write!(f, "Failed to read {}: {}", path.display(), e)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
 
 /// Compiles a Scrypto package.
+///
+/// The output is cached under `target/scrypto-cache`, keyed by a hash of the package sources
+/// and build environment, so that repeated compilations of an unchanged package skip invoking
+/// cargo. The least recently used outputs are evicted once the cache exceeds 256 MiB.
 pub fn compile_package<P: AsRef<Path>>(package_dir: P) -> Vec<u8> {
+    compile_package_with_options(package_dir, &BuildOptions::default())
+}
+
+/// Compiles a Scrypto package with the given options, panicking on failure.
+pub fn compile_package_with_options<P: AsRef<Path>>(
+    package_dir: P,
+    options: &BuildOptions,
+) -> Vec<u8> {
+    match try_compile_package(package_dir.as_ref(), options) {
+        Ok(code) => code,
+        Err(e) => panic!(
+            "Failed to compile package {:?}: {}",
+            package_dir.as_ref(),
+            e
+        ),
+    }
+}
+
+/// Compiles a Scrypto package with the given options, and returns the WASM code.
+///
+/// For packages within a workspace, the sources of the whole workspace are hashed for caching,
+/// along with those of the path dependencies outside of it.
+pub fn try_compile_package<P: AsRef<Path>>(
+    package_dir: P,
+    options: &BuildOptions,
+) -> Result<Vec<u8>, BuildError> {
+    let package_dir = package_dir.as_ref();
+    let target_dir = resolve_target_dir(package_dir, options);
+
+    // check cache
+    let source_hash = source_hash(package_dir, &target_dir, options);
+    let mut cache_path = target_dir.clone();
+    cache_path.push(BUILD_CACHE_DIR);
+    cache_path.push(source_hash.to_string());
+    cache_path.set_extension("wasm");
+    if let Ok(code) = fs::read(&cache_path) {
+        // rewritten to mark it as recently used, which spares it from eviction
+        fs::write(&cache_path, &code).ok();
+        return Ok(code);
+    }
+
     // build
-    let status = Command::new("cargo")
-        .current_dir(package_dir.as_ref())
-        .args(["build", "--target", "wasm32-unknown-unknown", "--release"])
-        .status()
-        .unwrap();
+    let path = build_package(package_dir, options)?;
+    let code = fs::read(&path).map_err(|e| BuildError::WasmNotFound(path, e))?;
+
+    // update cache; failing to do so only costs a rebuild next time
+    if let Some(dir) = cache_path.parent() {
+        if fs::create_dir_all(dir).is_ok() {
+            fs::write(&cache_path, &code).ok();
+            evict_cache_entries(dir, BUILD_CACHE_MAX_BYTES);
+        }
+    }
+
+    Ok(code)
+}
+
+/// Removes the least recently written files of a cache directory until the total size of the
+/// remaining ones is within the given limit.
+fn evict_cache_entries(cache_dir: &Path, max_bytes: u64) {
+    let mut entries: Vec<(SystemTime, u64, PathBuf)> = match fs::read_dir(cache_dir) {
+        Ok(entries) => entries
+            .flatten()
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                if !metadata.is_file() {
+                    return None;
+                }
+                Some((metadata.modified().ok()?, metadata.len(), entry.path()))
+            })
+            .collect(),
+        Err(_) => return,
+    };
+    entries.sort();
+
+    let mut total_bytes: u64 = entries.iter().map(|(_, len, _)| len).sum();
+    for (_, len, path) in entries {
+        if total_bytes <= max_bytes {
+            break;
+        }
+        if fs::remove_file(&path).is_ok() {
+            total_bytes -= len;
+        }
+    }
+}
+
+/// Builds a Scrypto package with the given options, and returns the path of the WASM file.
+pub fn build_package<P: AsRef<Path>>(
+    package_dir: P,
+    options: &BuildOptions,
+) -> Result<PathBuf, BuildError> {
+    let package_dir = package_dir.as_ref();
+    let manifest_path = package_dir.join("Cargo.toml");
+    if !manifest_path.exists() {
+        return Err(BuildError::NotCargoPackage(package_dir.to_owned()));
+    }
+    let wasm_name = wasm_name(package_dir, &manifest_path, options)?;
+
+    let mut command = Command::new("cargo");
+    command
+        .current_dir(package_dir)
+        .args(["build", "--target", WASM_TARGET]);
+    if options.release {
+        command.arg("--release");
+    }
+    if let Some(package) = &options.package {
+        command.arg("--package").arg(package);
+    }
+    if !options.features.is_empty() {
+        command.arg("--features").arg(options.features.join(","));
+    }
+    if let Some(target_dir) = &options.target_dir {
+        command.arg("--target-dir").arg(target_dir);
+    }
+
+    let (status, stderr) = if options.verbose {
+        let status = command.status().map_err(BuildError::FailedToRunCargo)?;
+        (status, String::new())
+    } else {
+        let output = command
+            .stdout(Stdio::null())
+            .output()
+            .map_err(BuildError::FailedToRunCargo)?;
+        (
+            output.status,
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        )
+    };
     if !status.success() {
-        panic!("Failed to compile package: {:?}", package_dir.as_ref());
+        return Err(BuildError::FailedToBuild { status, stderr });
     }
 
-    // resolve wasm name
-    let mut cargo = package_dir.as_ref().to_owned();
-    cargo.push("Cargo.toml");
-    let manifest = Manifest::from_path(&cargo).unwrap();
-    let wasm_name = if let Some(Product { name: Some(x), .. }) = manifest.lib {
+    // path of the wasm executable
+    let mut path = resolve_target_dir(package_dir, options);
+    path.push(WASM_TARGET);
+    path.push(options.profile());
+    path.push(wasm_name);
+    path.set_extension("wasm");
+    Ok(path)
+}
+
+/// Returns the target directory cargo builds the package into.
+fn resolve_target_dir(package_dir: &Path, options: &BuildOptions) -> PathBuf {
+    if let Some(target_dir) = &options.target_dir {
+        return package_dir.join(target_dir);
+    }
+    if let Some(target_dir) = env::var_os("CARGO_TARGET_DIR") {
+        return package_dir.join(target_dir);
+    }
+    let mut path = workspace_root(package_dir).unwrap_or_else(|| package_dir.to_owned());
+    path.push("target");
+    path
+}
+
+/// Returns the root of the workspace the package belongs to, if any.
+///
+/// Like cargo, this is the closest directory, starting from the package itself, whose manifest
+/// has a `[workspace]` section.
+fn workspace_root(package_dir: &Path) -> Option<PathBuf> {
+    let package_dir = package_dir.canonicalize().ok()?;
+    package_dir
+        .ancestors()
+        .find(|dir| {
+            fs::read(dir.join("Cargo.toml"))
+                .ok()
+                .and_then(|content| Manifest::from_slice(&content).ok())
+                .map(|manifest| manifest.workspace.is_some())
+                .unwrap_or(false)
+        })
+        .map(Path::to_owned)
+}
+
+/// Resolves the name of the WASM file from the manifest.
+fn wasm_name(
+    package_dir: &Path,
+    manifest_path: &Path,
+    options: &BuildOptions,
+) -> Result<String, BuildError> {
+    if let Some(package) = &options.package {
+        // workspace member
+        return Ok(package.replace("-", "_"));
+    }
+
+    let manifest = Manifest::from_path(manifest_path)
+        .map_err(|_| BuildError::InvalidManifest(manifest_path.to_owned()))?;
+    if let Some(Product { name: Some(x), .. }) = manifest.lib {
         // lib name
-        x
+        Ok(x)
     } else if let Some(pkg) = manifest.package {
         // package name
-        pkg.name.replace("-", "_")
+        Ok(pkg.name.replace("-", "_"))
+    } else if manifest.workspace.is_some() {
+        Err(BuildError::PackageNotSpecified(manifest_path.to_owned()))
     } else {
         // file name
         package_dir
-            .as_ref()
             .file_name()
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_owned()
-            .replace("-", "_")
+            .and_then(|name| name.to_str())
+            .map(|name| name.replace("-", "_"))
+            .ok_or_else(|| BuildError::InvalidManifest(manifest_path.to_owned()))
+    }
+}
+
+/// The environment variables which change the output of cargo for the same sources.
+const BUILD_ENV_VARS: [&str; 4] = [
+    "RUSTFLAGS",
+    "CARGO_ENCODED_RUSTFLAGS",
+    "CARGO_BUILD_RUSTFLAGS",
+    "CARGO_TARGET_WASM32_UNKNOWN_UNKNOWN_RUSTFLAGS",
+];
+
+/// Computes the cache key of a build, which is a hash over:
+/// - the manifests and sources under the package or workspace root, and under the roots of the
+///   path dependencies outside of it;
+/// - the build options and target directory;
+/// - the rustc flags and the toolchain.
+fn source_hash(package_dir: &Path, target_dir: &Path, options: &BuildOptions) -> Hash {
+    let root = workspace_root(package_dir)
+        .or_else(|| package_dir.canonicalize().ok())
+        .unwrap_or_else(|| package_dir.to_owned());
+
+    let mut data = Vec::new();
+    let mut roots = vec![root.clone()];
+    let mut i = 0;
+    while i < roots.len() {
+        let mut files = Vec::new();
+        collect_files(&roots[i], &mut files);
+        files.sort();
+
+        for file in files {
+            if let Ok(content) = fs::read(&file) {
+                // Files outside the root are identified by their full path
+                let name = file.strip_prefix(&root).unwrap_or(&file);
+                data.extend(name.to_string_lossy().as_bytes());
+                data.extend(hash(&content).0);
+
+                if file
+                    .file_name()
+                    .map(|name| name == "Cargo.toml")
+                    .unwrap_or(false)
+                {
+                    for dependency in path_dependencies(&file, &content) {
+                        if !roots.iter().any(|root| dependency.starts_with(root)) {
+                            roots.push(dependency);
+                        }
+                    }
+                }
+            }
+        }
+        i += 1;
+    }
+
+    data.extend(
+        format!(
+            "{:?}",
+            (&options.package, &options.features, options.release)
+        )
+        .as_bytes(),
+    );
+    data.extend(target_dir.to_string_lossy().as_bytes());
+    for name in BUILD_ENV_VARS {
+        data.extend(format!("{}={:?}", name, env::var_os(name)).as_bytes());
+    }
+    data.extend(toolchain_version(package_dir));
+    hash(data)
+}
+
+/// Returns the directories of the path dependencies of a manifest.
+fn path_dependencies(manifest_path: &Path, content: &[u8]) -> Vec<PathBuf> {
+    let manifest = match Manifest::from_slice(content) {
+        Ok(manifest) => manifest,
+        Err(_) => return Vec::new(),
     };
+    let manifest_dir = manifest_path.parent().unwrap_or_else(|| Path::new("."));
 
-    // path of the wasm executable
-    let mut path = PathBuf::from(package_dir.as_ref());
-    path.push("target");
-    path.push("wasm32-unknown-unknown");
-    path.push("release");
-    path.push(wasm_name);
-    path.set_extension("wasm");
+    let mut deps_sets = vec![
+        &manifest.dependencies,
+        &manifest.dev_dependencies,
+        &manifest.build_dependencies,
+    ];
+    for target in manifest.target.values() {
+        deps_sets.push(&target.dependencies);
+        deps_sets.push(&target.dev_dependencies);
+        deps_sets.push(&target.build_dependencies);
+    }
+    deps_sets
+        .into_iter()
+        .flat_map(|deps| deps.values())
+        .filter_map(|dependency| dependency.detail())
+        .filter_map(|detail| detail.path.as_ref())
+        .filter_map(|path| manifest_dir.join(path).canonicalize().ok())
+        .collect()
+}
+
+/// Returns the version of the compiler which cargo builds the package with, which depends on
+/// the `RUSTC` variable and rustup overrides.
+fn toolchain_version(package_dir: &Path) -> Vec<u8> {
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    Command::new(rustc)
+        .current_dir(package_dir)
+        .arg("-vV")
+        .output()
+        .map(|output| output.stdout)
+        .unwrap_or_default()
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if path.is_dir() {
+                // skip build output and hidden directories, such as `.git`
+                if name != "target" && !name.starts_with('.') {
+                    collect_files(&path, files);
+                }
+            } else if name == "Cargo.toml"
+                || name == "Cargo.lock"
+                || path.extension().map(|ext| ext == "rs").unwrap_or(false)
+            {
+                files.push(path);
+            }
+        }
+    }
+}
 
-    // return
-    fs::read(path).unwrap()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    /// A directory under the system temp directory, unique to each test and test process, which
+    /// is removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+            let path = env::temp_dir().join(format!(
+                "scrypto-cargo-test-{}-{}-{}",
+                name,
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::SeqCst)
+            ));
+            fs::remove_dir_all(&path).ok();
+            fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        fn path(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            fs::remove_dir_all(&self.0).ok();
+        }
+    }
+
+    fn write_workspace(root: &Path) -> PathBuf {
+        let member = root.join("blueprints").join("my-package");
+        fs::create_dir_all(member.join("src")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"blueprints/my-package\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            member.join("Cargo.toml"),
+            "[package]\nname = \"my-package\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(member.join("src").join("lib.rs"), "").unwrap();
+        member
+    }
+
+    #[test]
+    fn test_workspace_member_builds_into_workspace_target_dir() {
+        let temp_dir = TempDir::new("workspace");
+        let root = temp_dir.path();
+        let member = write_workspace(&root);
+        let root = root.canonicalize().unwrap();
+
+        assert_eq!(workspace_root(&member), Some(root.clone()));
+        if env::var_os("CARGO_TARGET_DIR").is_none() {
+            assert_eq!(
+                resolve_target_dir(&member, &BuildOptions::new()),
+                root.join("target")
+            );
+        }
+        assert_eq!(
+            resolve_target_dir(&member, &BuildOptions::new().target_dir("out")),
+            member.join("out")
+        );
+        assert_eq!(
+            wasm_name(&member, &member.join("Cargo.toml"), &BuildOptions::new()).unwrap(),
+            "my_package"
+        );
+        assert!(matches!(
+            wasm_name(&root, &root.join("Cargo.toml"), &BuildOptions::new()),
+            Err(BuildError::PackageNotSpecified(..))
+        ));
+        assert_eq!(
+            wasm_name(
+                &root,
+                &root.join("Cargo.toml"),
+                &BuildOptions::new().package("my-package")
+            )
+            .unwrap(),
+            "my_package"
+        );
+    }
+
+    #[test]
+    fn test_source_hash_depends_on_options() {
+        let temp_dir = TempDir::new("hash");
+        let root = temp_dir.path();
+        let member = write_workspace(&root);
+        let target_dir = root.join("target");
+
+        let release = source_hash(&member, &target_dir, &BuildOptions::new());
+        assert_ne!(
+            release,
+            source_hash(&member, &target_dir, &BuildOptions::new().release(false))
+        );
+        assert_ne!(
+            release,
+            source_hash(&member, &target_dir, &BuildOptions::new().feature("foo"))
+        );
+        assert_eq!(
+            release,
+            source_hash(&member, &target_dir, &BuildOptions::new().verbose(true))
+        );
+
+        fs::write(member.join("src").join("lib.rs"), "pub fn f() {}").unwrap();
+        assert_ne!(
+            release,
+            source_hash(&member, &target_dir, &BuildOptions::new())
+        );
+    }
+
+    #[test]
+    fn test_source_hash_depends_on_target_dir_and_path_dependencies() {
+        let temp_dir = TempDir::new("hash-deps");
+        let root = temp_dir.path();
+        let workspace = root.join("workspace");
+        let member = write_workspace(&workspace);
+        let dependency = root.join("my-dependency");
+        fs::create_dir_all(dependency.join("src")).unwrap();
+        fs::write(
+            dependency.join("Cargo.toml"),
+            "[package]\nname = \"my-dependency\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(dependency.join("src").join("lib.rs"), "").unwrap();
+        fs::write(
+            member.join("Cargo.toml"),
+            "[package]\nname = \"my-package\"\nversion = \"0.1.0\"\n\n\
+             [dependencies]\nmy-dependency = { path = \"../../../my-dependency\" }\n",
+        )
+        .unwrap();
+        let target_dir = workspace.join("target");
+
+        let original = source_hash(&member, &target_dir, &BuildOptions::new());
+        assert_eq!(
+            original,
+            source_hash(&member, &target_dir, &BuildOptions::new())
+        );
+        assert_ne!(
+            original,
+            source_hash(&member, &member.join("out"), &BuildOptions::new())
+        );
+
+        fs::write(dependency.join("src").join("lib.rs"), "pub fn f() {}").unwrap();
+        assert_ne!(
+            original,
+            source_hash(&member, &target_dir, &BuildOptions::new())
+        );
+    }
+
+    #[test]
+    fn test_compile_package_reuses_cached_wasm_until_sources_change() {
+        let temp_dir = TempDir::new("cache");
+        let root = temp_dir.path();
+        let member = write_workspace(&root);
+        let options = BuildOptions::new().target_dir("out");
+        let target_dir = resolve_target_dir(&member, &options);
+
+        // Arrange a cached build of the current sources
+        let cache_dir = target_dir.join(BUILD_CACHE_DIR);
+        fs::create_dir_all(&cache_dir).unwrap();
+        let cache_path = cache_dir
+            .join(source_hash(&member, &target_dir, &options).to_string())
+            .with_extension("wasm");
+        fs::write(&cache_path, b"cached").unwrap();
+
+        // Cache hit, cargo is not invoked
+        assert_eq!(try_compile_package(&member, &options).unwrap(), b"cached");
+
+        // Cache miss, the package is rebuilt
+        fs::write(member.join("src").join("lib.rs"), "pub fn f() {}").unwrap();
+        assert!(!matches!(
+            try_compile_package(&member, &options),
+            Ok(code) if code == b"cached"
+        ));
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_written_entries_beyond_limit() {
+        let temp_dir = TempDir::new("eviction");
+        let cache_dir = temp_dir.path();
+        for name in ["a", "b", "c"] {
+            fs::write(cache_dir.join(name).with_extension("wasm"), [0u8; 10]).unwrap();
+            // keeps the modification times apart
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        evict_cache_entries(cache_dir, 30);
+        assert!(cache_dir.join("a.wasm").exists());
+
+        evict_cache_entries(cache_dir, 25);
+        assert!(!cache_dir.join("a.wasm").exists());
+        assert!(cache_dir.join("b.wasm").exists());
+        assert!(cache_dir.join("c.wasm").exists());
+    }
 }
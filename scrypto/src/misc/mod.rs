@@ -4,6 +4,9 @@ mod panic;
 mod slice;
 
 #[cfg(not(feature = "alloc"))]
-pub use cargo::compile_package;
+pub use cargo::{
+    build_package, compile_package, compile_package_with_options, try_compile_package, BuildError,
+    BuildOptions,
+};
 pub use panic::set_up_panic_hook;
 pub use slice::{combine, copy_u8_array};
@@ -25,5 +25,17 @@ pub fn set_up_panic_hook() {
             payload,
             location
         ));
+
+        // report the panic, with source location in debug builds only
+        let input = crate::engine::api::RaisePanicInput {
+            message: payload,
+            location: if cfg!(debug_assertions) {
+                Some(location)
+            } else {
+                None
+            },
+        };
+        let _: crate::engine::api::RaisePanicOutput =
+            crate::engine::call_engine(crate::engine::api::RAISE_PANIC, input);
     }));
 }
@@ -8,8 +8,9 @@ pub use crate::misc::*;
 pub use crate::resource::*;
 pub use crate::{
     args, rule, access_and_or, access_rule_node, blueprint, borrow_component, borrow_package,
-    borrow_resource_manager, compile_package, debug, dec, error, import, include_package, info,
-    resource_list, trace, warn, Decode, Describe, Encode, NonFungibleData, TypeId,
+    borrow_resource_manager, compile_package, debug, dec, error, export_types, import,
+    import_types, include_package, info, interface, resource_list, scrypto_test, trace, warn,
+    Decode, Describe, Encode, NonFungibleData, TypeId,
 };
 
 pub use crate::rust::borrow::ToOwned;
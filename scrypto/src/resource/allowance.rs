@@ -0,0 +1,64 @@
+use sbor::*;
+
+use crate::buffer::{scrypto_decode, scrypto_encode};
+use crate::core::SNodeRef;
+use crate::engine::{api::*, call_engine, types::AllowanceId};
+use crate::math::*;
+use crate::resource::*;
+use crate::rust::string::ToString;
+use crate::rust::vec;
+
+/// Represents the right to withdraw up to some amount of resource from a vault of another
+/// component, until an expiry epoch.
+///
+/// Allowances are created with [`Vault::create_allowance`] by the component which owns the vault,
+/// and redeemed by whoever satisfies their redeem rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, TypeId, Encode, Decode, Describe)]
+pub struct Allowance(pub AllowanceId);
+
+impl Allowance {
+    /// Withdraws some amount of resource from the vault of this allowance.
+    pub fn redeem<A: Into<Decimal>>(&self, amount: A) -> Bucket {
+        let amount: Decimal = amount.into();
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::AllowanceRef(self.0),
+            function: "redeem_allowance".to_string(),
+            args: vec![scrypto_encode(&amount)],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    /// Returns the amount of resource which can still be withdrawn.
+    pub fn remaining_amount(&self) -> Decimal {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::AllowanceRef(self.0),
+            function: "get_allowance_remaining_amount".to_string(),
+            args: vec![],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    /// Returns the last epoch in which this allowance can be redeemed.
+    pub fn expiry_epoch(&self) -> u64 {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::AllowanceRef(self.0),
+            function: "get_allowance_expiry_epoch".to_string(),
+            args: vec![],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    /// Returns the resource address.
+    pub fn resource_address(&self) -> ResourceAddress {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::AllowanceRef(self.0),
+            function: "get_allowance_resource_address".to_string(),
+            args: vec![],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+}
@@ -47,6 +47,20 @@ impl ComponentAuthZone {
         scrypto_decode(&output.rtn).unwrap()
     }
 
+    /// Creates a compound proof of all the given resources from the auth zone, which can be
+    /// checked against a rule requiring all of them.
+    ///
+    /// No proof is created if any of the resources can't be proven.
+    pub fn create_proofs(resource_addresses: &[ResourceAddress]) -> CompoundProof {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::AuthZoneRef,
+            function: "create_proofs".to_string(),
+            args: args![resource_addresses.to_vec()],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        CompoundProof::new(scrypto_decode(&output.rtn).unwrap())
+    }
+
     pub fn create_proof_by_amount(amount: Decimal, resource_address: ResourceAddress) -> Proof {
         let input = InvokeSNodeInput {
             snode_ref: SNodeRef::AuthZoneRef,
@@ -126,6 +126,63 @@ impl Bucket {
         self.amount() == 0.into()
     }
 
+    /// Returns the amount of resources locked by outstanding proofs.
+    pub fn locked_amount(&self) -> Decimal {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::BucketRef(self.0),
+            function: "get_bucket_locked_amount".to_string(),
+            args: args![],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    /// Returns the amount of resources which are not locked and can be taken.
+    pub fn liquid_amount(&self) -> Decimal {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::BucketRef(self.0),
+            function: "get_bucket_liquid_amount".to_string(),
+            args: args![],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    /// Checks if this bucket has outstanding proofs.
+    pub fn is_locked(&self) -> bool {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::BucketRef(self.0),
+            function: "is_bucket_locked".to_string(),
+            args: args![],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    /// Converts this bucket into a [`FungibleBucket`].
+    ///
+    /// # Panics
+    /// Panics if this is not a fungible bucket.
+    pub fn as_fungible(self) -> FungibleBucket {
+        let resource_type = borrow_resource_manager!(self.resource_address()).resource_type();
+        if !matches!(resource_type, ResourceType::Fungible { .. }) {
+            panic!("Expecting fungible bucket");
+        }
+        FungibleBucket(self)
+    }
+
+    /// Converts this bucket into a [`NonFungibleBucket`].
+    ///
+    /// # Panics
+    /// Panics if this is not a non-fungible bucket.
+    pub fn as_non_fungible(self) -> NonFungibleBucket {
+        let resource_type = borrow_resource_manager!(self.resource_address()).resource_type();
+        if !matches!(resource_type, ResourceType::NonFungible) {
+            panic!("Expecting non-fungible bucket");
+        }
+        NonFungibleBucket(self)
+    }
+
     /// Returns all the non-fungible ids contained.
     ///
     /// # Panics
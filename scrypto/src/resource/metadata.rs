@@ -0,0 +1,266 @@
+use sbor::*;
+
+use crate::component::ComponentAddress;
+use crate::math::*;
+use crate::rust::borrow::ToOwned;
+use crate::rust::fmt;
+use crate::rust::string::String;
+use crate::rust::vec::Vec;
+
+/// The metadata key of the resource symbol, e.g. `XRD`.
+pub const METADATA_SYMBOL: &str = "symbol";
+/// The metadata key of the resource name.
+pub const METADATA_NAME: &str = "name";
+/// The metadata key of the resource description.
+pub const METADATA_DESCRIPTION: &str = "description";
+/// The metadata key of the project website.
+pub const METADATA_URL: &str = "url";
+/// The metadata key of the resource icon.
+pub const METADATA_ICON_URL: &str = "icon_url";
+/// The metadata key of the resource tags.
+pub const METADATA_TAGS: &str = "tags";
+
+/// Represents a typed resource metadata value.
+#[derive(Debug, Clone, PartialEq, Eq, TypeId, Encode, Decode, Describe)]
+pub enum MetadataValue {
+    /// A plain string
+    String(String),
+
+    /// An `http://` or `https://` URL
+    Url(String),
+
+    /// A decimal number
+    Decimal(Decimal),
+
+    /// A list of strings
+    StringList(Vec<String>),
+
+    /// A list of component addresses
+    AddressList(Vec<ComponentAddress>),
+}
+
+impl MetadataValue {
+    /// Checks whether this value has the type expected for the given key.
+    ///
+    /// Standardized keys require a specific type; any value is accepted for other keys.
+    pub fn is_valid_for(&self, key: &str) -> bool {
+        match key {
+            METADATA_SYMBOL | METADATA_NAME | METADATA_DESCRIPTION => {
+                matches!(self, MetadataValue::String(_))
+            }
+            METADATA_URL | METADATA_ICON_URL => self.is_valid_url(),
+            METADATA_TAGS => matches!(self, MetadataValue::StringList(_)),
+            _ => match self {
+                MetadataValue::Url(_) => self.is_valid_url(),
+                _ => true,
+            },
+        }
+    }
+
+    fn is_valid_url(&self) -> bool {
+        match self {
+            MetadataValue::Url(url) => is_valid_http_url(url),
+            _ => false,
+        }
+    }
+
+    /// Returns the string if this is a `String` or `Url` value.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            MetadataValue::String(s) | MetadataValue::Url(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the decimal if this is a `Decimal` value.
+    pub fn as_decimal(&self) -> Option<Decimal> {
+        match self {
+            MetadataValue::Decimal(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    /// Returns the strings if this is a `StringList` value.
+    pub fn as_string_list(&self) -> Option<&[String]> {
+        match self {
+            MetadataValue::StringList(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    /// Returns the addresses if this is an `AddressList` value.
+    pub fn as_address_list(&self) -> Option<&[ComponentAddress]> {
+        match self {
+            MetadataValue::AddressList(list) => Some(list),
+            _ => None,
+        }
+    }
+}
+
+/// Checks that a string is an absolute `http://` or `https://` URL, made of a host name or IPv4
+/// address, an optional port, and an optional path, query and fragment.
+///
+/// Characters outside printable ASCII, including whitespace, must be percent-encoded.
+fn is_valid_http_url(url: &str) -> bool {
+    let rest = match url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+    {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let authority_end = rest
+        .find(|c| c == '/' || c == '?' || c == '#')
+        .unwrap_or(rest.len());
+    let (authority, path) = rest.split_at(authority_end);
+    let (host, port) = match authority.split_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (authority, None),
+    };
+    is_valid_host(host) && port.map_or(true, is_valid_port) && is_valid_url_path(path)
+}
+
+fn is_valid_host(host: &str) -> bool {
+    !host.is_empty()
+        && host.len() <= 253
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        })
+}
+
+fn is_valid_port(port: &str) -> bool {
+    !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) && port.parse::<u16>().is_ok()
+}
+
+fn is_valid_url_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                if !bytes.get(i + 1).map_or(false, u8::is_ascii_hexdigit)
+                    || !bytes.get(i + 2).map_or(false, u8::is_ascii_hexdigit)
+                {
+                    return false;
+                }
+                i += 3;
+            }
+            // Printable ASCII, which excludes whitespace and control characters
+            b'!'..=b'~' => i += 1,
+            _ => return false,
+        }
+    }
+    true
+}
+
+impl From<&str> for MetadataValue {
+    fn from(s: &str) -> Self {
+        MetadataValue::String(s.to_owned())
+    }
+}
+
+impl From<String> for MetadataValue {
+    fn from(s: String) -> Self {
+        MetadataValue::String(s)
+    }
+}
+
+impl From<Decimal> for MetadataValue {
+    fn from(d: Decimal) -> Self {
+        MetadataValue::Decimal(d)
+    }
+}
+
+impl From<Vec<String>> for MetadataValue {
+    fn from(list: Vec<String>) -> Self {
+        MetadataValue::StringList(list)
+    }
+}
+
+impl From<Vec<ComponentAddress>> for MetadataValue {
+    fn from(list: Vec<ComponentAddress>) -> Self {
+        MetadataValue::AddressList(list)
+    }
+}
+
+impl fmt::Display for MetadataValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MetadataValue::String(s) | MetadataValue::Url(s) => write!(f, "{}", s),
+            MetadataValue::Decimal(d) => write!(f, "{}", d),
+            MetadataValue::StringList(list) => write!(f, "{:?}", list),
+            MetadataValue::AddressList(list) => {
+                write!(f, "[")?;
+                for (i, address) in list.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", address)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rust::string::ToString;
+
+    fn url(s: &str) -> MetadataValue {
+        MetadataValue::Url(s.to_string())
+    }
+
+    #[test]
+    fn test_valid_urls() {
+        for s in [
+            "https://radixdlt.com",
+            "http://radixdlt.com/",
+            "https://www.radixdlt.com:8080/path/to/icon.png",
+            "https://radixdlt.com/a%20b?query=1&x=y#fragment",
+            "http://127.0.0.1",
+        ] {
+            assert!(url(s).is_valid_for(METADATA_ICON_URL), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_invalid_urls() {
+        for s in [
+            "radixdlt.com",
+            "ftp://radixdlt.com",
+            "https://",
+            "https:///path",
+            "https://radix dlt.com",
+            "https://radixdlt.com/a b",
+            "https://radixdlt.com/\tab",
+            "https://radixdlt.com/\n",
+            "https://radixdlt.com/\u{7f}",
+            "https://radixdlt.com/caf\u{e9}",
+            "https://radixdlt.com/%2",
+            "https://radixdlt.com/%zz",
+            "https://radixdlt..com",
+            "https://-radixdlt.com",
+            "https://user@radixdlt.com",
+            "https://radixdlt.com:",
+            "https://radixdlt.com:65536",
+            "https://radixdlt.com:80a",
+        ] {
+            assert!(!url(s).is_valid_for(METADATA_ICON_URL), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_url_is_required_for_url_keys() {
+        assert!(
+            !MetadataValue::String("https://radixdlt.com".to_string()).is_valid_for(METADATA_URL)
+        );
+    }
+}
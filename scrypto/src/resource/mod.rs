@@ -1,6 +1,8 @@
 mod access_rules;
+mod allowance;
 mod auth_zone;
 mod bucket;
+mod metadata;
 mod mint_params;
 mod non_fungible;
 mod non_fungible_address;
@@ -13,23 +15,33 @@ mod resource_manager;
 mod resource_type;
 mod schema_path;
 mod system;
+mod typed_bucket;
+mod typed_vault;
 mod vault;
 
 pub use access_rules::AccessRules;
+pub use allowance::Allowance;
 pub use auth_zone::ComponentAuthZone;
 pub use bucket::{Bucket, ParseBucketError};
+pub use metadata::{
+    MetadataValue, METADATA_DESCRIPTION, METADATA_ICON_URL, METADATA_NAME, METADATA_SYMBOL,
+    METADATA_TAGS, METADATA_URL,
+};
 pub use mint_params::MintParams;
 pub use non_fungible::NonFungible;
 pub use non_fungible_address::{NonFungibleAddress, ParseNonFungibleAddressError};
 pub use non_fungible_data::NonFungibleData;
 pub use non_fungible_id::{NonFungibleId, ParseNonFungibleIdError};
-pub use proof::{ParseProofError, Proof};
+pub use proof::{CompoundProof, ParseProofError, Proof, ProofValidationError, ValidatedProof};
 pub use proof_rule::{
     require, require_all_of, require_amount, require_any_of, require_n_of, AccessRuleNode,
     AccessRule, ProofRule, SoftCount, SoftDecimal, SoftResource, SoftResourceOrNonFungible,
     SoftResourceOrNonFungibleList,
 };
-pub use resource_builder::{ResourceBuilder, DIVISIBILITY_MAXIMUM, DIVISIBILITY_NONE};
+pub use resource_builder::{
+    FungibleResourceBuilder, Mintable, NonFungibleResourceBuilder, NotMintable, ResourceBuilder,
+    DIVISIBILITY_MAXIMUM, DIVISIBILITY_NONE,
+};
 pub use resource_manager::Mutability::*;
 pub use resource_manager::ResourceMethod::*;
 pub use resource_manager::{
@@ -38,4 +50,6 @@ pub use resource_manager::{
 pub use resource_type::ResourceType;
 pub use schema_path::SchemaPath;
 pub use system::{init_resource_system, resource_system, ResourceSystem};
+pub use typed_bucket::{FungibleBucket, NonFungibleBucket};
+pub use typed_vault::{FungibleVault, NonFungibleVault};
 pub use vault::{ParseVaultError, Vault};
@@ -1,6 +1,8 @@
 use crate::borrow_resource_manager;
 use crate::resource::*;
+use crate::rust::collections::BTreeMap;
 use crate::rust::marker::PhantomData;
+use crate::rust::string::String;
 
 /// Represents a non-fungible unit.
 #[derive(Debug)]
@@ -44,4 +46,15 @@ impl<T: NonFungibleData> NonFungible<T> {
         borrow_resource_manager!(self.resource_address())
             .update_non_fungible_data(&self.id(), new_data);
     }
+
+    /// Returns the metadata of this unit.
+    pub fn metadata(&self) -> BTreeMap<String, MetadataValue> {
+        borrow_resource_manager!(self.resource_address()).get_non_fungible_metadata(&self.id())
+    }
+
+    /// Replaces the metadata of this unit.
+    pub fn update_metadata(&self, new_metadata: BTreeMap<String, MetadataValue>) {
+        borrow_resource_manager!(self.resource_address())
+            .update_non_fungible_metadata(&self.id(), new_metadata);
+    }
 }
@@ -1,5 +1,7 @@
 use sbor::*;
 
+use crate::component::*;
+use crate::constants::*;
 use crate::misc::*;
 use crate::resource::*;
 use crate::rust::borrow::ToOwned;
@@ -24,6 +26,22 @@ impl NonFungibleAddress {
         }
     }
 
+    /// Returns the virtual badge held by callers running a blueprint of the given package.
+    pub fn from_package(package_address: PackageAddress) -> Self {
+        Self::new(
+            PACKAGE_TOKEN,
+            NonFungibleId::from_bytes(package_address.to_vec()),
+        )
+    }
+
+    /// Returns the virtual badge held by calls made from the given global component.
+    pub fn from_global_caller(component_address: ComponentAddress) -> Self {
+        Self::new(
+            GLOBAL_CALLER_TOKEN,
+            NonFungibleId::from_bytes(component_address.to_vec()),
+        )
+    }
+
     /// Returns the resource address.
     pub fn resource_address(&self) -> ResourceAddress {
         self.resource_address
@@ -52,6 +52,39 @@ impl Proof {
             .any(|k| k.eq(&non_fungible_address.non_fungible_id()))
     }
 
+    /// Validates that this proof is of the expected resource and covers at least `min_amount`.
+    ///
+    /// The proof is dropped if validation fails.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let proof = proof.validate(self.admin_badge, 1).expect("Invalid admin badge");
+    /// ```
+    pub fn validate<A: Into<Decimal>>(
+        self,
+        expected_resource_address: ResourceAddress,
+        min_amount: A,
+    ) -> Result<ValidatedProof, ProofValidationError> {
+        let min_amount: Decimal = min_amount.into();
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::ProofRef(self.0),
+            function: "validate".to_string(),
+            args: args![expected_resource_address, min_amount],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        let result: Result<(), ProofValidationError> = scrypto_decode(&output.rtn).unwrap();
+        match result {
+            Ok(()) => Ok(ValidatedProof {
+                proof: self,
+                resource_address: expected_resource_address,
+            }),
+            Err(e) => {
+                self.drop();
+                Err(e)
+            }
+        }
+    }
+
     /// Returns the resource amount within the bucket.
     pub fn amount(&self) -> Decimal {
         let input = InvokeSNodeInput {
@@ -129,10 +162,109 @@ impl Proof {
     }
 }
 
+/// A proof which has been checked against an expected resource and amount.
+///
+/// It can only be obtained through [`Proof::validate`], so holding one is enough to trust
+/// its resource address.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ValidatedProof {
+    proof: Proof,
+    resource_address: ResourceAddress,
+}
+
+impl ValidatedProof {
+    /// Returns the validated resource address.
+    pub fn resource_address(&self) -> ResourceAddress {
+        self.resource_address
+    }
+
+    /// Returns the resource amount within the proof.
+    pub fn amount(&self) -> Decimal {
+        self.proof.amount()
+    }
+
+    /// Returns the ids of all non-fungibles in this proof.
+    ///
+    /// # Panics
+    /// If the proof is not a non-fungible proof.
+    pub fn non_fungible_ids(&self) -> BTreeSet<NonFungibleId> {
+        self.proof.non_fungible_ids()
+    }
+
+    /// Returns all the non-fungible units contained.
+    ///
+    /// # Panics
+    /// Panics if this is not a non-fungible proof.
+    pub fn non_fungibles<T: NonFungibleData>(&self) -> Vec<NonFungible<T>> {
+        self.proof.non_fungibles()
+    }
+
+    /// Returns a singleton non-fungible.
+    ///
+    /// # Panics
+    /// Panics if this is not a singleton proof
+    pub fn non_fungible<T: NonFungibleData>(&self) -> NonFungible<T> {
+        self.proof.non_fungible()
+    }
+
+    /// Destroys this proof.
+    pub fn drop(self) {
+        self.proof.drop()
+    }
+}
+
+/// A proof of each of several resources, as created by [`ComponentAuthZone::create_proofs`].
+///
+/// It can be checked as a whole against a rule requiring all of the resources.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct CompoundProof {
+    proofs: Vec<Proof>,
+}
+
+impl CompoundProof {
+    pub(crate) fn new(proofs: Vec<Proof>) -> Self {
+        Self { proofs }
+    }
+
+    /// Returns the proof of each resource.
+    pub fn proofs(&self) -> &[Proof] {
+        &self.proofs
+    }
+
+    /// Returns the addresses of the proven resources.
+    pub fn resource_addresses(&self) -> Vec<ResourceAddress> {
+        self.proofs
+            .iter()
+            .map(|proof| proof.resource_address())
+            .collect()
+    }
+
+    /// Checks this proof against an access rule.
+    pub fn check(&self, access_rule: &AccessRule) -> bool {
+        access_rule.check(&self.proofs)
+    }
+
+    /// Destroys this proof.
+    pub fn drop(self) {
+        for proof in self.proofs {
+            proof.drop();
+        }
+    }
+}
+
 //========
 // error
 //========
 
+/// Represents an error when validating a proof.
+#[derive(Debug, Clone, PartialEq, Eq, TypeId, Encode, Decode)]
+pub enum ProofValidationError {
+    /// The proof is of another resource, carried here.
+    ResourceAddressDoesNotMatch(ResourceAddress),
+    /// The proof amount, carried here, is below the required minimum.
+    InsufficientAmount(Decimal),
+}
+
 /// Represents an error when decoding proof.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ParseProofError {
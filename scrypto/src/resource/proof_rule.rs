@@ -5,6 +5,7 @@ use crate::resource::*;
 use crate::rust::borrow::ToOwned;
 use crate::rust::vec;
 use crate::rust::vec::Vec;
+use crate::rust::string::String;
 use crate::rust::string::ToString;
 use sbor::*;
 use scrypto::math::Decimal;
@@ -189,23 +190,13 @@ macro_rules! resource_list {
   });
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, TypeId, Encode, Decode)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Describe, TypeId, Encode, Decode)]
 pub enum AccessRuleNode {
     ProofRule(ProofRule),
     AnyOf(Vec<AccessRuleNode>),
     AllOf(Vec<AccessRuleNode>),
 }
 
-// FIXME: describe types with cycles
-impl Describe for AccessRuleNode {
-    fn describe() -> sbor::describe::Type {
-        sbor::describe::Type::Custom {
-            name: "AccessRuleNode".to_owned(),
-            generics: vec![],
-        }
-    }
-}
-
 impl AccessRuleNode {
     pub fn or(self, other: AccessRuleNode) -> Self {
         match self {
@@ -351,9 +342,22 @@ pub enum AccessRule {
     AllowAll,
     DenyAll,
     Protected(AccessRuleNode),
+    /// Refers to a rule slot of the component, which can be updated at runtime.
+    Slot(String),
 }
 
 impl AccessRule {
+    /// Creates a rule which refers to the given rule slot of the component.
+    pub fn slot(name: &str) -> Self {
+        AccessRule::Slot(name.to_string())
+    }
+
+    /// Returns whether this rule refers to a rule slot, which is only allowed in the access rules
+    /// of a component.
+    pub fn is_slot(&self) -> bool {
+        matches!(self, AccessRule::Slot(_))
+    }
+
     pub fn check(&self, proofs: &[Proof]) -> bool {
         let input = CheckAccessRuleInput {
             access_rule: self.clone(),
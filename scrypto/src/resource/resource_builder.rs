@@ -3,6 +3,7 @@ use crate::math::*;
 use crate::resource::*;
 use crate::rust::borrow::ToOwned;
 use crate::rust::collections::HashMap;
+use crate::rust::marker::PhantomData;
 use crate::rust::string::String;
 
 /// Not divisible.
@@ -13,15 +14,31 @@ pub const DIVISIBILITY_MAXIMUM: u8 = 18;
 /// Utility for setting up a new resource.
 pub struct ResourceBuilder;
 
-pub struct FungibleResourceBuilder {
+/// Marks a resource builder on which no mint rule has been set.
+pub struct NotMintable;
+
+/// Marks a resource builder on which a mint rule has been set.
+pub struct Mintable;
+
+/// Builder for fungible resources.
+///
+/// The type parameter tracks whether a mint rule has been set, so that it can't be set twice
+/// and `mint_initial_supply` is only available on mintable resources.
+pub struct FungibleResourceBuilder<M = NotMintable> {
     divisibility: u8,
-    metadata: HashMap<String, String>,
+    metadata: HashMap<String, MetadataValue>,
     authorization: HashMap<ResourceMethod, (AccessRule, Mutability)>,
+    mintable: PhantomData<M>,
 }
 
-pub struct NonFungibleResourceBuilder {
-    metadata: HashMap<String, String>,
+/// Builder for non-fungible resources.
+///
+/// The type parameter tracks whether a mint rule has been set, so that it can't be set twice
+/// and `mint_initial_supply` is only available on mintable resources.
+pub struct NonFungibleResourceBuilder<M = NotMintable> {
+    metadata: HashMap<String, MetadataValue>,
     authorization: HashMap<ResourceMethod, (AccessRule, Mutability)>,
+    mintable: PhantomData<M>,
 }
 
 impl ResourceBuilder {
@@ -42,13 +59,44 @@ impl FungibleResourceBuilder {
             divisibility: DIVISIBILITY_MAXIMUM,
             metadata: HashMap::new(),
             authorization: HashMap::new(),
+            mintable: PhantomData,
         }
     }
 
+    pub fn mintable(
+        mut self,
+        method_auth: AccessRule,
+        mutability: Mutability,
+    ) -> FungibleResourceBuilder<Mintable> {
+        self.authorization.insert(Mint, (method_auth, mutability));
+        FungibleResourceBuilder {
+            divisibility: self.divisibility,
+            metadata: self.metadata,
+            authorization: self.authorization,
+            mintable: PhantomData,
+        }
+    }
+}
+
+impl FungibleResourceBuilder<Mintable> {
+    /// Creates a mintable resource with the given initial supply.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let bucket = ResourceBuilder::new_fungible()
+    ///     .mintable(rule!(require(badge)), LOCKED)
+    ///     .mint_initial_supply(5);
+    /// ```
+    pub fn mint_initial_supply<T: Into<Decimal>>(self, amount: T) -> Bucket {
+        self.initial_supply(amount)
+    }
+}
+
+impl<M> FungibleResourceBuilder<M> {
     /// Set the divisibility.
     ///
     /// `0` means the resource is not divisible; `18` is the max divisibility.
-    pub fn divisibility(&mut self, divisibility: u8) -> &mut Self {
+    pub fn divisibility(mut self, divisibility: u8) -> Self {
         assert!(divisibility <= 18);
         self.divisibility = divisibility;
         self
@@ -57,49 +105,41 @@ impl FungibleResourceBuilder {
     /// Adds a resource metadata.
     ///
     /// If a previous attribute with the same name has been set, it will be overwritten.
-    pub fn metadata<K: AsRef<str>, V: AsRef<str>>(&mut self, name: K, value: V) -> &mut Self {
-        self.metadata
-            .insert(name.as_ref().to_owned(), value.as_ref().to_owned());
+    pub fn metadata<K: AsRef<str>, V: Into<MetadataValue>>(mut self, name: K, value: V) -> Self {
+        self.metadata.insert(name.as_ref().to_owned(), value.into());
         self
     }
 
-    pub fn mintable(&mut self, method_auth: AccessRule, mutability: Mutability) -> &mut Self {
-        self.authorization.insert(Mint, (method_auth, mutability));
-        self
-    }
-
-    pub fn burnable(&mut self, method_auth: AccessRule, mutability: Mutability) -> &mut Self {
+    pub fn burnable(mut self, method_auth: AccessRule, mutability: Mutability) -> Self {
         self.authorization.insert(Burn, (method_auth, mutability));
         self
     }
 
-    pub fn restrict_withdraw(
-        &mut self,
-        method_auth: AccessRule,
-        mutability: Mutability,
-    ) -> &mut Self {
+    pub fn restrict_withdraw(mut self, method_auth: AccessRule, mutability: Mutability) -> Self {
         self.authorization
             .insert(Withdraw, (method_auth, mutability));
         self
     }
 
-    pub fn restrict_deposit(
-        &mut self,
-        method_auth: AccessRule,
-        mutability: Mutability,
-    ) -> &mut Self {
+    pub fn restrict_deposit(mut self, method_auth: AccessRule, mutability: Mutability) -> Self {
         self.authorization
             .insert(Deposit, (method_auth, mutability));
         self
     }
 
-    pub fn updateable_metadata(
-        &mut self,
+    pub fn updateable_metadata(mut self, method_auth: AccessRule, mutability: Mutability) -> Self {
+        self.authorization
+            .insert(UpdateMetadata, (method_auth, mutability));
+        self
+    }
+
+    pub fn updateable_divisibility(
+        mut self,
         method_auth: AccessRule,
         mutability: Mutability,
-    ) -> &mut Self {
+    ) -> Self {
         self.authorization
-            .insert(UpdateMetadata, (method_auth, mutability));
+            .insert(UpdateDivisibility, (method_auth, mutability));
         self
     }
 
@@ -111,17 +151,17 @@ impl FungibleResourceBuilder {
     ///     .metadata("name", "TestToken")
     ///     .initial_supply(5);
     /// ```
-    pub fn initial_supply<T: Into<Decimal>>(&self, amount: T) -> Bucket {
+    pub fn initial_supply<T: Into<Decimal>>(self, amount: T) -> Bucket {
         self.build(Some(MintParams::fungible(amount))).1.unwrap()
     }
 
     /// Creates resource with no initial supply.
-    pub fn no_initial_supply(&self) -> ResourceAddress {
+    pub fn no_initial_supply(self) -> ResourceAddress {
         self.build(None).0
     }
 
-    fn build(&self, mint_params: Option<MintParams>) -> (ResourceAddress, Option<Bucket>) {
-        let mut authorization = self.authorization.clone();
+    fn build(self, mint_params: Option<MintParams>) -> (ResourceAddress, Option<Bucket>) {
+        let mut authorization = self.authorization;
         if !authorization.contains_key(&Withdraw) {
             authorization.insert(Withdraw, (rule!(allow_all), LOCKED));
         }
@@ -130,7 +170,7 @@ impl FungibleResourceBuilder {
             ResourceType::Fungible {
                 divisibility: self.divisibility,
             },
-            self.metadata.clone(),
+            self.metadata,
             authorization,
             mint_params,
         )
@@ -142,68 +182,94 @@ impl NonFungibleResourceBuilder {
         Self {
             metadata: HashMap::new(),
             authorization: HashMap::new(),
+            mintable: PhantomData,
         }
     }
 
-    /// Adds a resource metadata.
+    pub fn mintable(
+        mut self,
+        method_auth: AccessRule,
+        mutability: Mutability,
+    ) -> NonFungibleResourceBuilder<Mintable> {
+        self.authorization.insert(Mint, (method_auth, mutability));
+        NonFungibleResourceBuilder {
+            metadata: self.metadata,
+            authorization: self.authorization,
+            mintable: PhantomData,
+        }
+    }
+}
+
+impl NonFungibleResourceBuilder<Mintable> {
+    /// Creates a mintable resource with the given initial supply.
     ///
-    /// If a previous attribute with the same name has been set, it will be overwritten.
-    pub fn metadata<K: AsRef<str>, V: AsRef<str>>(&mut self, name: K, value: V) -> &mut Self {
-        self.metadata
-            .insert(name.as_ref().to_owned(), value.as_ref().to_owned());
-        self
+    /// # Example
+    /// ```ignore
+    /// let bucket = ResourceBuilder::new_non_fungible()
+    ///     .mintable(rule!(require(badge)), LOCKED)
+    ///     .mint_initial_supply([(NonFungibleId::from_u32(1), MyData {})]);
+    /// ```
+    pub fn mint_initial_supply<T, V>(self, entries: T) -> Bucket
+    where
+        T: IntoIterator<Item = (NonFungibleId, V)>,
+        V: NonFungibleData,
+    {
+        self.initial_supply(entries)
     }
+}
 
-    pub fn mintable(&mut self, method_auth: AccessRule, mutability: Mutability) -> &mut Self {
-        self.authorization.insert(Mint, (method_auth, mutability));
+impl<M> NonFungibleResourceBuilder<M> {
+    /// Adds a resource metadata.
+    ///
+    /// If a previous attribute with the same name has been set, it will be overwritten.
+    pub fn metadata<K: AsRef<str>, V: Into<MetadataValue>>(mut self, name: K, value: V) -> Self {
+        self.metadata.insert(name.as_ref().to_owned(), value.into());
         self
     }
 
-    pub fn burnable(&mut self, method_auth: AccessRule, mutability: Mutability) -> &mut Self {
+    pub fn burnable(mut self, method_auth: AccessRule, mutability: Mutability) -> Self {
         self.authorization.insert(Burn, (method_auth, mutability));
         self
     }
 
-    pub fn restrict_withdraw(
-        &mut self,
-        method_auth: AccessRule,
-        mutability: Mutability,
-    ) -> &mut Self {
+    pub fn restrict_withdraw(mut self, method_auth: AccessRule, mutability: Mutability) -> Self {
         self.authorization
             .insert(Withdraw, (method_auth, mutability));
         self
     }
 
-    pub fn restrict_deposit(
-        &mut self,
-        method_auth: AccessRule,
-        mutability: Mutability,
-    ) -> &mut Self {
+    pub fn restrict_deposit(mut self, method_auth: AccessRule, mutability: Mutability) -> Self {
         self.authorization
             .insert(Deposit, (method_auth, mutability));
         self
     }
 
-    pub fn updateable_metadata(
-        &mut self,
-        method_auth: AccessRule,
-        mutability: Mutability,
-    ) -> &mut Self {
+    pub fn updateable_metadata(mut self, method_auth: AccessRule, mutability: Mutability) -> Self {
         self.authorization
             .insert(UpdateMetadata, (method_auth, mutability));
         self
     }
 
     pub fn updateable_non_fungible_data(
-        &mut self,
+        mut self,
         method_auth: AccessRule,
         mutability: Mutability,
-    ) -> &mut Self {
+    ) -> Self {
         self.authorization
             .insert(UpdateNonFungibleData, (method_auth, mutability));
         self
     }
 
+    pub fn updateable_non_fungible_metadata(
+        mut self,
+        method_auth: AccessRule,
+        mutability: Mutability,
+    ) -> Self {
+        self.authorization
+            .insert(UpdateNonFungibleMetadata, (method_auth, mutability));
+        self
+    }
+
     /// Creates resource with the given initial supply.
     ///
     /// # Example
@@ -215,7 +281,7 @@ impl NonFungibleResourceBuilder {
     ///         (NftKey::from(2u128), "another_immutable_part", "another_mutable_part"),
     ///     ]);
     /// ```
-    pub fn initial_supply<T, V>(&self, entries: T) -> Bucket
+    pub fn initial_supply<T, V>(self, entries: T) -> Bucket
     where
         T: IntoIterator<Item = (NonFungibleId, V)>,
         V: NonFungibleData,
@@ -226,19 +292,19 @@ impl NonFungibleResourceBuilder {
     }
 
     /// Creates resource with no initial supply.
-    pub fn no_initial_supply(&self) -> ResourceAddress {
+    pub fn no_initial_supply(self) -> ResourceAddress {
         self.build(None).0
     }
 
-    fn build(&self, mint_params: Option<MintParams>) -> (ResourceAddress, Option<Bucket>) {
-        let mut authorization = self.authorization.clone();
+    fn build(self, mint_params: Option<MintParams>) -> (ResourceAddress, Option<Bucket>) {
+        let mut authorization = self.authorization;
         if !authorization.contains_key(&Withdraw) {
             authorization.insert(Withdraw, (rule!(allow_all), LOCKED));
         }
 
         resource_system().new_resource(
             ResourceType::NonFungible,
-            self.metadata.clone(),
+            self.metadata,
             authorization,
             mint_params,
         )
@@ -1,5 +1,6 @@
 use sbor::*;
 
+use crate::address::{AddressError, Bech32Decoder};
 use crate::args;
 use crate::buffer::scrypto_decode;
 use crate::core::SNodeRef;
@@ -8,7 +9,7 @@ use crate::math::*;
 use crate::misc::*;
 use crate::resource::*;
 use crate::rust::borrow::ToOwned;
-use crate::rust::collections::HashMap;
+use crate::rust::collections::{BTreeMap, HashMap};
 use crate::rust::fmt;
 use crate::rust::str::FromStr;
 use crate::rust::string::String;
@@ -24,6 +25,8 @@ pub enum ResourceMethod {
     Deposit,
     UpdateMetadata,
     UpdateNonFungibleData,
+    UpdateNonFungibleMetadata,
+    UpdateDivisibility,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, TypeId, Encode, Decode, Describe)]
@@ -33,7 +36,7 @@ pub enum Mutability {
 }
 
 /// Represents a resource address.
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct ResourceAddress(pub [u8; 26]);
 
 impl ResourceAddress {}
@@ -76,6 +79,19 @@ impl ResourceManager {
         scrypto_decode(&output.rtn).unwrap()
     }
 
+    /// Permanently disables minting, which can't be undone.
+    ///
+    /// Requires the same authorization as `set_mintable`.
+    pub fn renounce_mintable(&self) -> () {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::ResourceRef(self.0),
+            function: "method_auth".to_string(),
+            args: args![Mint, "renounce"],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
     /// Mints non-fungible resources
     pub fn mint_non_fungible<T: NonFungibleData>(&self, id: &NonFungibleId, data: T) -> Bucket {
         let mut entries = HashMap::new();
@@ -120,6 +136,19 @@ impl ResourceManager {
         scrypto_decode(&output.rtn).unwrap()
     }
 
+    /// Permanently disables burning, which can't be undone.
+    ///
+    /// Requires the same authorization as `set_burnable`.
+    pub fn renounce_burnable(&self) -> () {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::ResourceRef(self.0),
+            function: "method_auth".to_string(),
+            args: args![Burn, "renounce"],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
     /// Returns the resource type.
     pub fn resource_type(&self) -> ResourceType {
         let input = InvokeSNodeInput {
@@ -151,6 +180,19 @@ impl ResourceManager {
         scrypto_decode(&output.rtn).unwrap()
     }
 
+    /// Permanently disables withdrawing, which can't be undone.
+    ///
+    /// Requires the same authorization as `set_withdrawable`.
+    pub fn renounce_withdrawable(&self) -> () {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::ResourceRef(self.0),
+            function: "method_auth".to_string(),
+            args: args![Withdraw, "renounce"],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
     pub fn set_depositable(&self, deposit_auth: AccessRule) -> () {
         let input = InvokeSNodeInput {
             snode_ref: SNodeRef::ResourceRef(self.0),
@@ -171,6 +213,19 @@ impl ResourceManager {
         scrypto_decode(&output.rtn).unwrap()
     }
 
+    /// Permanently disables depositing, which can't be undone.
+    ///
+    /// Requires the same authorization as `set_depositable`.
+    pub fn renounce_depositable(&self) -> () {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::ResourceRef(self.0),
+            function: "method_auth".to_string(),
+            args: args![Deposit, "renounce"],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
     pub fn set_updateable_metadata(&self, update_metadata_auth: AccessRule) -> () {
         let input = InvokeSNodeInput {
             snode_ref: SNodeRef::ResourceRef(self.0),
@@ -191,6 +246,19 @@ impl ResourceManager {
         scrypto_decode(&output.rtn).unwrap()
     }
 
+    /// Permanently freezes the resource metadata, which can't be undone.
+    ///
+    /// Requires the same authorization as `set_updateable_metadata`.
+    pub fn renounce_updateable_metadata(&self) -> () {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::ResourceRef(self.0),
+            function: "method_auth".to_string(),
+            args: args![UpdateMetadata, "renounce"],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
     pub fn set_updateable_non_fungible_data(&self, update_metadata_auth: AccessRule) -> () {
         let input = InvokeSNodeInput {
             snode_ref: SNodeRef::ResourceRef(self.0),
@@ -211,8 +279,87 @@ impl ResourceManager {
         scrypto_decode(&output.rtn).unwrap()
     }
 
+    /// Permanently freezes the mutable data of every non-fungible, which can't be undone.
+    ///
+    /// Requires the same authorization as `set_updateable_non_fungible_data`.
+    pub fn renounce_updateable_non_fungible_data(&self) -> () {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::ResourceRef(self.0),
+            function: "method_auth".to_string(),
+            args: args![UpdateNonFungibleData, "renounce"],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    pub fn set_updateable_non_fungible_metadata(&self, update_auth: AccessRule) -> () {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::ResourceRef(self.0),
+            function: "method_auth".to_string(),
+            args: args![UpdateNonFungibleMetadata, "update", update_auth],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    pub fn lock_updateable_non_fungible_metadata(&self) -> () {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::ResourceRef(self.0),
+            function: "method_auth".to_string(),
+            args: args![UpdateNonFungibleMetadata, "lock"],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    /// Permanently freezes the metadata of every non-fungible, which can't be undone.
+    ///
+    /// Requires the same authorization as `set_updateable_non_fungible_metadata`.
+    pub fn renounce_updateable_non_fungible_metadata(&self) -> () {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::ResourceRef(self.0),
+            function: "method_auth".to_string(),
+            args: args![UpdateNonFungibleMetadata, "renounce"],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    pub fn set_updateable_divisibility(&self, update_auth: AccessRule) -> () {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::ResourceRef(self.0),
+            function: "method_auth".to_string(),
+            args: args![UpdateDivisibility, "update", update_auth],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    pub fn lock_updateable_divisibility(&self) -> () {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::ResourceRef(self.0),
+            function: "method_auth".to_string(),
+            args: args![UpdateDivisibility, "lock"],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    /// Permanently freezes the divisibility, which can't be undone.
+    ///
+    /// Requires the same authorization as `set_updateable_divisibility`.
+    pub fn renounce_updateable_divisibility(&self) -> () {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::ResourceRef(self.0),
+            function: "method_auth".to_string(),
+            args: args![UpdateDivisibility, "renounce"],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
     /// Returns the metadata associated with this resource.
-    pub fn metadata(&self) -> HashMap<String, String> {
+    pub fn metadata(&self) -> HashMap<String, MetadataValue> {
         let input = InvokeSNodeInput {
             snode_ref: SNodeRef::ResourceRef(self.0),
             function: "get_metadata".to_string(),
@@ -222,6 +369,42 @@ impl ResourceManager {
         scrypto_decode(&output.rtn).unwrap()
     }
 
+    /// Returns the `symbol` metadata of this resource, if any.
+    pub fn symbol(&self) -> Option<String> {
+        self.metadata_string(METADATA_SYMBOL)
+    }
+
+    /// Returns the `name` metadata of this resource, if any.
+    pub fn name(&self) -> Option<String> {
+        self.metadata_string(METADATA_NAME)
+    }
+
+    /// Returns the `description` metadata of this resource, if any.
+    pub fn description(&self) -> Option<String> {
+        self.metadata_string(METADATA_DESCRIPTION)
+    }
+
+    /// Returns the `icon_url` metadata of this resource, if any.
+    pub fn icon_url(&self) -> Option<String> {
+        self.metadata_string(METADATA_ICON_URL)
+    }
+
+    /// Returns the `tags` metadata of this resource, or an empty list if not set.
+    pub fn tags(&self) -> Vec<String> {
+        self.metadata()
+            .get(METADATA_TAGS)
+            .and_then(MetadataValue::as_string_list)
+            .map(|tags| tags.to_vec())
+            .unwrap_or_default()
+    }
+
+    fn metadata_string(&self, key: &str) -> Option<String> {
+        self.metadata()
+            .get(key)
+            .and_then(MetadataValue::as_str)
+            .map(ToOwned::to_owned)
+    }
+
     /// Returns the current supply of this resource.
     pub fn total_supply(&self) -> Decimal {
         let input = InvokeSNodeInput {
@@ -261,6 +444,37 @@ impl ResourceManager {
         let _: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
     }
 
+    /// Returns the metadata of a non-fungible unit, which is separate from its data.
+    ///
+    /// # Panics
+    /// Panics if this is not a non-fungible resource or the specified non-fungible is not found.
+    pub fn get_non_fungible_metadata(&self, id: &NonFungibleId) -> BTreeMap<String, MetadataValue> {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::ResourceRef(self.0),
+            function: "get_non_fungible_metadata".to_string(),
+            args: args![id.clone()],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    /// Replaces the metadata of a non-fungible unit.
+    ///
+    /// # Panics
+    /// Panics if this is not a non-fungible resource or the specified non-fungible is not found.
+    pub fn update_non_fungible_metadata(
+        &self,
+        id: &NonFungibleId,
+        new_metadata: BTreeMap<String, MetadataValue>,
+    ) {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::ResourceRef(self.0),
+            function: "update_non_fungible_metadata".to_string(),
+            args: args![id.clone(), new_metadata],
+        };
+        let _: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+    }
+
     /// Checks if non-fungible unit, with certain key exists or not.
     ///
     pub fn non_fungible_exists(&self, id: &NonFungibleId) -> bool {
@@ -274,7 +488,7 @@ impl ResourceManager {
     }
 
     /// Updates the resource metadata
-    pub fn update_metadata(&self, new_metadata: HashMap<String, String>) {
+    pub fn update_metadata(&self, new_metadata: HashMap<String, MetadataValue>) {
         let input = InvokeSNodeInput {
             snode_ref: SNodeRef::ResourceRef(self.0),
             function: "update_metadata".to_string(),
@@ -282,6 +496,18 @@ impl ResourceManager {
         };
         let _: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
     }
+
+    /// Updates the divisibility of a fungible resource.
+    ///
+    /// Only allowed while the total supply is zero, so that no existing amount becomes invalid.
+    pub fn update_divisibility(&self, divisibility: u8) {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::ResourceRef(self.0),
+            function: "update_divisibility".to_string(),
+            args: args![divisibility],
+        };
+        let _: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+    }
 }
 
 //========
@@ -291,9 +517,9 @@ impl ResourceManager {
 /// Represents an error when decoding resource address.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ParseResourceAddressError {
-    InvalidHex(String),
     InvalidLength(usize),
     InvalidPrefix,
+    InvalidAddress(AddressError),
 }
 
 #[cfg(not(feature = "alloc"))]
@@ -333,18 +559,21 @@ scrypto_type!(ResourceAddress, ScryptoType::ResourceAddress, Vec::new());
 // text
 //======
 
-// Before Bech32, we use a fixed prefix for text representation.
+// See `crate::address` for the text forms of addresses.
 
 impl FromStr for ResourceAddress {
     type Err = ParseResourceAddressError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let bytes =
-            hex::decode(s).map_err(|_| ParseResourceAddressError::InvalidHex(s.to_owned()))?;
-        if bytes.get(0) != Some(&3u8) {
-            return Err(ParseResourceAddressError::InvalidPrefix);
+        if let Ok(bytes) = hex::decode(s) {
+            if bytes.get(0) != Some(&3u8) {
+                return Err(ParseResourceAddressError::InvalidPrefix);
+            }
+            return Self::try_from(&bytes[1..]);
         }
-        Self::try_from(&bytes[1..])
+        Bech32Decoder::for_address(s)
+            .and_then(|decoder| decoder.validate_and_decode_resource_address(s))
+            .map_err(ParseResourceAddressError::InvalidAddress)
     }
 }
 
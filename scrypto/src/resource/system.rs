@@ -41,7 +41,7 @@ impl ResourceSystem {
     pub fn new_resource(
         &mut self,
         resource_type: ResourceType,
-        metadata: HashMap<String, String>,
+        metadata: HashMap<String, MetadataValue>,
         authorization: HashMap<ResourceMethod, (AccessRule, Mutability)>,
         mint_params: Option<MintParams>,
     ) -> (ResourceAddress, Option<Bucket>) {
@@ -0,0 +1,206 @@
+use sbor::*;
+
+use crate::borrow_resource_manager;
+use crate::math::*;
+use crate::resource::*;
+use crate::rust::collections::BTreeSet;
+use crate::rust::vec::Vec;
+
+/// Implements the SBOR traits of a typed bucket or vault.
+///
+/// It's encoded as the container it wraps, so that it can be used as a method argument or in
+/// component state wherever the untyped container is accepted. Decoding fails if the resource
+/// manager reports a resource type other than the expected one.
+macro_rules! typed_container_sbor {
+    ($t:ty, $container:ty, $resource_type:pat) => {
+        impl TypeId for $t {
+            #[inline]
+            fn type_id() -> u8 {
+                <$container>::type_id()
+            }
+        }
+
+        impl Encode for $t {
+            fn encode_value(&self, encoder: &mut Encoder) {
+                self.0.encode_value(encoder);
+            }
+        }
+
+        impl Decode for $t {
+            fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+                let container = <$container>::decode_value(decoder)?;
+                let resource_type =
+                    borrow_resource_manager!(container.resource_address()).resource_type();
+                if matches!(resource_type, $resource_type) {
+                    Ok(Self(container))
+                } else {
+                    Err(DecodeError::InvalidCustomData(<$container>::type_id()))
+                }
+            }
+        }
+
+        impl Describe for $t {
+            fn describe() -> sbor::describe::Type {
+                <$container>::describe()
+            }
+        }
+    };
+}
+
+pub(crate) use typed_container_sbor;
+
+typed_container_sbor!(FungibleBucket, Bucket, ResourceType::Fungible { .. });
+typed_container_sbor!(NonFungibleBucket, Bucket, ResourceType::NonFungible);
+
+/// A bucket which is statically known to hold a fungible resource.
+///
+/// Obtained through [`Bucket::as_fungible`], or by decoding a bucket of a fungible resource.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct FungibleBucket(pub(crate) Bucket);
+
+impl FungibleBucket {
+    /// Puts resources from another bucket into this bucket.
+    pub fn put(&mut self, other: Self) {
+        self.0.put(other.0)
+    }
+
+    /// Takes some amount of resources from this bucket.
+    pub fn take<A: Into<Decimal>>(&mut self, amount: A) -> Self {
+        Self(self.0.take(amount))
+    }
+
+    /// Burns resource within this bucket.
+    pub fn burn(self) {
+        self.0.burn()
+    }
+
+    /// Creates an ownership proof of this bucket.
+    pub fn create_proof(&self) -> Proof {
+        self.0.create_proof()
+    }
+
+    /// Uses resources in this bucket as authorization for an operation.
+    pub fn authorize<F: FnOnce() -> O, O>(&self, f: F) -> O {
+        self.0.authorize(f)
+    }
+
+    /// Returns the amount of resources in this bucket.
+    pub fn amount(&self) -> Decimal {
+        self.0.amount()
+    }
+
+    /// Returns the resource address.
+    pub fn resource_address(&self) -> ResourceAddress {
+        self.0.resource_address()
+    }
+
+    /// Checks if this bucket is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the underlying untyped bucket.
+    pub fn into_bucket(self) -> Bucket {
+        self.0
+    }
+}
+
+impl From<FungibleBucket> for Bucket {
+    fn from(bucket: FungibleBucket) -> Self {
+        bucket.0
+    }
+}
+
+/// A bucket which is statically known to hold a non-fungible resource.
+///
+/// Obtained through [`Bucket::as_non_fungible`], or by decoding a bucket of a non-fungible
+/// resource.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct NonFungibleBucket(pub(crate) Bucket);
+
+impl NonFungibleBucket {
+    /// Puts resources from another bucket into this bucket.
+    pub fn put(&mut self, other: Self) {
+        self.0.put(other.0)
+    }
+
+    /// Takes some amount of non-fungibles from this bucket.
+    pub fn take<A: Into<Decimal>>(&mut self, amount: A) -> Self {
+        Self(self.0.take(amount))
+    }
+
+    /// Takes a specific non-fungible from this bucket.
+    ///
+    /// # Panics
+    /// Panics if the specified non-fungible is not found.
+    pub fn take_non_fungible(&mut self, non_fungible_id: &NonFungibleId) -> Self {
+        Self(self.0.take_non_fungible(non_fungible_id))
+    }
+
+    /// Takes non-fungibles from this bucket.
+    ///
+    /// # Panics
+    /// Panics if any of the specified non-fungibles is not found.
+    pub fn take_non_fungibles(&mut self, non_fungible_ids: &BTreeSet<NonFungibleId>) -> Self {
+        Self(self.0.take_non_fungibles(non_fungible_ids))
+    }
+
+    /// Burns resource within this bucket.
+    pub fn burn(self) {
+        self.0.burn()
+    }
+
+    /// Creates an ownership proof of this bucket.
+    pub fn create_proof(&self) -> Proof {
+        self.0.create_proof()
+    }
+
+    /// Uses resources in this bucket as authorization for an operation.
+    pub fn authorize<F: FnOnce() -> O, O>(&self, f: F) -> O {
+        self.0.authorize(f)
+    }
+
+    /// Returns the number of non-fungibles in this bucket.
+    pub fn amount(&self) -> Decimal {
+        self.0.amount()
+    }
+
+    /// Returns the resource address.
+    pub fn resource_address(&self) -> ResourceAddress {
+        self.0.resource_address()
+    }
+
+    /// Checks if this bucket is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns all the non-fungible ids contained.
+    pub fn non_fungible_ids(&self) -> BTreeSet<NonFungibleId> {
+        self.0.non_fungible_ids()
+    }
+
+    /// Returns all the non-fungible units contained.
+    pub fn non_fungibles<T: NonFungibleData>(&self) -> Vec<NonFungible<T>> {
+        self.0.non_fungibles()
+    }
+
+    /// Returns a singleton non-fungible.
+    ///
+    /// # Panics
+    /// Panics if this is not a singleton bucket
+    pub fn non_fungible<T: NonFungibleData>(&self) -> NonFungible<T> {
+        self.0.non_fungible()
+    }
+
+    /// Returns the underlying untyped bucket.
+    pub fn into_bucket(self) -> Bucket {
+        self.0
+    }
+}
+
+impl From<NonFungibleBucket> for Bucket {
+    fn from(bucket: NonFungibleBucket) -> Self {
+        bucket.0
+    }
+}
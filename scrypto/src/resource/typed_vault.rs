@@ -0,0 +1,180 @@
+use sbor::*;
+
+use crate::borrow_resource_manager;
+use crate::math::*;
+use crate::resource::typed_bucket::typed_container_sbor;
+use crate::resource::*;
+use crate::rust::collections::BTreeSet;
+use crate::rust::vec::Vec;
+
+typed_container_sbor!(FungibleVault, Vault, ResourceType::Fungible { .. });
+typed_container_sbor!(NonFungibleVault, Vault, ResourceType::NonFungible);
+
+/// A vault which is statically known to hold a fungible resource.
+///
+/// Obtained through [`Vault::as_fungible`], or by decoding a vault of a fungible resource.
+#[derive(PartialEq, Eq, Hash)]
+pub struct FungibleVault(pub(crate) Vault);
+
+impl FungibleVault {
+    /// Creates an empty vault and fills it with an initial bucket of resource.
+    pub fn with_bucket(bucket: FungibleBucket) -> Self {
+        Self(Vault::with_bucket(bucket.0))
+    }
+
+    /// Puts a bucket of resources into this vault.
+    pub fn put(&mut self, bucket: FungibleBucket) {
+        self.0.put(bucket.0)
+    }
+
+    /// Takes some amount of resource from this vault into a bucket.
+    pub fn take<A: Into<Decimal>>(&mut self, amount: A) -> FungibleBucket {
+        FungibleBucket(self.0.take(amount))
+    }
+
+    /// Takes all resource stored in this vault.
+    pub fn take_all(&mut self) -> FungibleBucket {
+        FungibleBucket(self.0.take_all())
+    }
+
+    /// Creates an ownership proof of this vault.
+    pub fn create_proof(&self) -> Proof {
+        self.0.create_proof()
+    }
+
+    /// Creates an ownership proof of this vault, by amount.
+    pub fn create_proof_by_amount(&self, amount: Decimal) -> Proof {
+        self.0.create_proof_by_amount(amount)
+    }
+
+    /// Uses resources in this vault as authorization for an operation.
+    pub fn authorize<F: FnOnce() -> O, O>(&self, f: F) -> O {
+        self.0.authorize(f)
+    }
+
+    /// Returns the amount of resources within this vault.
+    pub fn amount(&self) -> Decimal {
+        self.0.amount()
+    }
+
+    /// Returns the resource address.
+    pub fn resource_address(&self) -> ResourceAddress {
+        self.0.resource_address()
+    }
+
+    /// Checks if this vault is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the underlying untyped vault.
+    pub fn into_vault(self) -> Vault {
+        self.0
+    }
+}
+
+/// A vault which is statically known to hold a non-fungible resource.
+///
+/// Obtained through [`Vault::as_non_fungible`], or by decoding a vault of a non-fungible
+/// resource.
+#[derive(PartialEq, Eq, Hash)]
+pub struct NonFungibleVault(pub(crate) Vault);
+
+impl NonFungibleVault {
+    /// Creates an empty vault and fills it with an initial bucket of resource.
+    pub fn with_bucket(bucket: NonFungibleBucket) -> Self {
+        Self(Vault::with_bucket(bucket.0))
+    }
+
+    /// Puts a bucket of resources into this vault.
+    pub fn put(&mut self, bucket: NonFungibleBucket) {
+        self.0.put(bucket.0)
+    }
+
+    /// Takes some amount of non-fungibles from this vault into a bucket.
+    pub fn take<A: Into<Decimal>>(&mut self, amount: A) -> NonFungibleBucket {
+        NonFungibleBucket(self.0.take(amount))
+    }
+
+    /// Takes all resource stored in this vault.
+    pub fn take_all(&mut self) -> NonFungibleBucket {
+        NonFungibleBucket(self.0.take_all())
+    }
+
+    /// Takes a specific non-fungible from this vault.
+    ///
+    /// # Panics
+    /// Panics if the specified non-fungible is not found.
+    pub fn take_non_fungible(&mut self, non_fungible_id: &NonFungibleId) -> NonFungibleBucket {
+        NonFungibleBucket(self.0.take_non_fungible(non_fungible_id))
+    }
+
+    /// Takes non-fungibles from this vault.
+    ///
+    /// # Panics
+    /// Panics if any of the specified non-fungibles is not found.
+    pub fn take_non_fungibles(
+        &mut self,
+        non_fungible_ids: &BTreeSet<NonFungibleId>,
+    ) -> NonFungibleBucket {
+        NonFungibleBucket(self.0.take_non_fungibles(non_fungible_ids))
+    }
+
+    /// Creates an ownership proof of this vault.
+    pub fn create_proof(&self) -> Proof {
+        self.0.create_proof()
+    }
+
+    /// Creates an ownership proof of this vault, by amount.
+    pub fn create_proof_by_amount(&self, amount: Decimal) -> Proof {
+        self.0.create_proof_by_amount(amount)
+    }
+
+    /// Creates an ownership proof of this vault, by non-fungible ID set.
+    pub fn create_proof_by_ids(&self, ids: &BTreeSet<NonFungibleId>) -> Proof {
+        self.0.create_proof_by_ids(ids)
+    }
+
+    /// Uses resources in this vault as authorization for an operation.
+    pub fn authorize<F: FnOnce() -> O, O>(&self, f: F) -> O {
+        self.0.authorize(f)
+    }
+
+    /// Returns the number of non-fungibles within this vault.
+    pub fn amount(&self) -> Decimal {
+        self.0.amount()
+    }
+
+    /// Returns the resource address.
+    pub fn resource_address(&self) -> ResourceAddress {
+        self.0.resource_address()
+    }
+
+    /// Checks if this vault is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns all the non-fungible ids contained.
+    pub fn non_fungible_ids(&self) -> BTreeSet<NonFungibleId> {
+        self.0.non_fungible_ids()
+    }
+
+    /// Returns all the non-fungible units contained.
+    pub fn non_fungibles<T: NonFungibleData>(&self) -> Vec<NonFungible<T>> {
+        self.0.non_fungibles()
+    }
+
+    /// Returns a singleton non-fungible.
+    ///
+    /// # Panics
+    /// Panics if this is not a singleton vault
+    pub fn non_fungible<T: NonFungibleData>(&self) -> NonFungible<T> {
+        self.0.non_fungible()
+    }
+
+    /// Returns the underlying untyped vault.
+    pub fn into_vault(self) -> Vault {
+        self.0
+    }
+}
@@ -1,10 +1,10 @@
-use crate::args;
+use crate::{args, borrow_resource_manager};
 use crate::buffer::{scrypto_decode, scrypto_encode};
 use crate::core::SNodeRef;
 use sbor::*;
 
 use crate::crypto::*;
-use crate::engine::{api::*, call_engine, types::VaultId};
+use crate::engine::{api::*, call_engine, types::VaultId, EngineBatch};
 use crate::math::*;
 use crate::misc::*;
 use crate::resource::*;
@@ -124,6 +124,37 @@ impl Vault {
         scrypto_decode(&output.rtn).unwrap()
     }
 
+    /// Grants an allowance to withdraw up to `max_amount` of resource from this vault, which can
+    /// be redeemed by whoever satisfies `redeem_rule` until `expiry_epoch`.
+    ///
+    /// The vault must belong to a component.
+    pub fn create_allowance<A: Into<Decimal>>(
+        &mut self,
+        max_amount: A,
+        expiry_epoch: u64,
+        redeem_rule: AccessRule,
+    ) -> Allowance {
+        let max_amount: Decimal = max_amount.into();
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::VaultRef(self.0),
+            function: "create_vault_allowance".to_string(),
+            args: args![max_amount, expiry_epoch, redeem_rule],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    /// Revokes an allowance granted on this vault.
+    pub fn revoke_allowance(&mut self, allowance: &Allowance) {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::VaultRef(self.0),
+            function: "revoke_vault_allowance".to_string(),
+            args: args![allowance.0],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
     /// Uses resources in this vault as authorization for an operation.
     pub fn authorize<F: FnOnce() -> O, O>(&self, f: F) -> O {
         ComponentAuthZone::push(self.create_proof());
@@ -143,6 +174,27 @@ impl Vault {
         scrypto_decode(&output.rtn).unwrap()
     }
 
+    /// Returns the amounts of resources within the given vaults, reading all amounts with a
+    /// single engine call.
+    pub fn amounts(vaults: &[Vault]) -> Vec<Decimal> {
+        let mut batch = EngineBatch::new();
+        for vault in vaults {
+            batch.add(
+                INVOKE_SNODE,
+                InvokeSNodeInput {
+                    snode_ref: SNodeRef::VaultRef(vault.0),
+                    function: "get_vault_amount".to_string(),
+                    args: vec![],
+                },
+            );
+        }
+        batch
+            .execute::<InvokeSNodeOutput>()
+            .into_iter()
+            .map(|o| scrypto_decode(&o.rtn).unwrap())
+            .collect()
+    }
+
     /// Returns the resource address.
     pub fn resource_address(&self) -> ResourceAddress {
         let input = InvokeSNodeInput {
@@ -159,6 +211,63 @@ impl Vault {
         self.amount() == 0.into()
     }
 
+    /// Returns the amount of resources locked by outstanding proofs.
+    pub fn locked_amount(&self) -> Decimal {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::VaultRef(self.0),
+            function: "get_vault_locked_amount".to_string(),
+            args: vec![],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    /// Returns the amount of resources which are not locked and can be taken.
+    pub fn liquid_amount(&self) -> Decimal {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::VaultRef(self.0),
+            function: "get_vault_liquid_amount".to_string(),
+            args: vec![],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    /// Checks if this vault has outstanding proofs.
+    pub fn is_locked(&self) -> bool {
+        let input = InvokeSNodeInput {
+            snode_ref: SNodeRef::VaultRef(self.0),
+            function: "is_vault_locked".to_string(),
+            args: vec![],
+        };
+        let output: InvokeSNodeOutput = call_engine(INVOKE_SNODE, input);
+        scrypto_decode(&output.rtn).unwrap()
+    }
+
+    /// Converts this vault into a [`FungibleVault`].
+    ///
+    /// # Panics
+    /// Panics if this is not a fungible vault.
+    pub fn as_fungible(self) -> FungibleVault {
+        let resource_type = borrow_resource_manager!(self.resource_address()).resource_type();
+        if !matches!(resource_type, ResourceType::Fungible { .. }) {
+            panic!("Expecting fungible vault");
+        }
+        FungibleVault(self)
+    }
+
+    /// Converts this vault into a [`NonFungibleVault`].
+    ///
+    /// # Panics
+    /// Panics if this is not a non-fungible vault.
+    pub fn as_non_fungible(self) -> NonFungibleVault {
+        let resource_type = borrow_resource_manager!(self.resource_address()).resource_type();
+        if !matches!(resource_type, ResourceType::NonFungible) {
+            panic!("Expecting non-fungible vault");
+        }
+        NonFungibleVault(self)
+    }
+
     /// Returns all the non-fungible ids contained.
     ///
     /// # Panics
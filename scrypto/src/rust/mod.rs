@@ -1,6 +1,8 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 #[cfg(feature = "alloc")]
+pub use alloc::boxed;
+#[cfg(feature = "alloc")]
 pub use alloc::borrow;
 #[cfg(feature = "alloc")]
 pub use alloc::fmt;
@@ -27,6 +29,8 @@ pub use core::ops;
 #[cfg(feature = "alloc")]
 pub use core::ptr;
 
+#[cfg(not(feature = "alloc"))]
+pub use std::boxed;
 #[cfg(not(feature = "alloc"))]
 pub use std::borrow;
 #[cfg(not(feature = "alloc"))]
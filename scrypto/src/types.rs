@@ -57,6 +57,8 @@ pub enum ScryptoType {
 
     // math
     Decimal,
+    I256,
+    U256,
 
     // resource,
     Bucket,
@@ -68,7 +70,7 @@ pub enum ScryptoType {
 }
 
 // Need to update `scrypto-derive/src/import.rs` after changing the table below
-const MAPPING: [(ScryptoType, u8, &str); 13] = [
+const MAPPING: [(ScryptoType, u8, &str); 15] = [
     (ScryptoType::PackageAddress, 0x80, "PackageAddress"),
     (ScryptoType::ComponentAddress, 0x81, "ComponentAddress"),
     (ScryptoType::LazyMap, 0x82, "LazyMap"),
@@ -76,6 +78,8 @@ const MAPPING: [(ScryptoType, u8, &str); 13] = [
     (ScryptoType::EcdsaPublicKey, 0x91, "EcdsaPublicKey"),
     (ScryptoType::EcdsaSignature, 0x93, "EcdsaSignature"),
     (ScryptoType::Decimal, 0xa1, "Decimal"),
+    (ScryptoType::I256, 0xa2, "I256"),
+    (ScryptoType::U256, 0xa3, "U256"),
     (ScryptoType::Bucket, 0xb1, "Bucket"),
     (ScryptoType::Proof, 0xb2, "Proof"),
     (ScryptoType::Vault, 0xb3, "Vault"),
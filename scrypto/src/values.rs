@@ -2,6 +2,7 @@ use sbor::type_id::*;
 use sbor::{any::*, *};
 use sbor::path::{MutableSborPath, SborPath};
 
+use crate::address::Bech32Encoder;
 use crate::buffer::*;
 use crate::component::*;
 use crate::crypto::*;
@@ -9,8 +10,11 @@ use crate::engine::types::*;
 use crate::math::*;
 use crate::resource::*;
 use crate::rust::borrow::Borrow;
+use crate::rust::collections::BTreeMap;
+use crate::rust::collections::BTreeSet;
 use crate::rust::collections::HashMap;
 use crate::rust::collections::HashSet;
+use crate::rust::mem;
 use crate::rust::fmt;
 use crate::rust::format;
 use crate::rust::string::String;
@@ -35,16 +39,25 @@ pub enum ScryptoValueReplaceError {
 pub struct ScryptoValue {
     pub raw: Vec<u8>,
     pub dom: Value,
-    pub bucket_ids: HashMap<BucketId, SborPath>,
-    pub proof_ids: HashMap<ProofId, SborPath>,
-    pub vault_ids: HashSet<VaultId>,
-    pub lazy_map_ids: HashSet<LazyMapId>,
+    pub bucket_ids: BTreeMap<BucketId, SborPath>,
+    pub proof_ids: BTreeMap<ProofId, SborPath>,
+    pub vault_ids: BTreeSet<VaultId>,
+    pub lazy_map_ids: BTreeSet<LazyMapId>,
 }
 
 impl ScryptoValue {
     pub fn from_slice(slice: &[u8]) -> Result<Self, ParseScryptoValueError> {
+        Self::from_slice_with_limits(slice, DecodeLimits::default())
+    }
+
+    /// Parses a value, rejecting values which exceed the nesting depth or payload length limits.
+    pub fn from_slice_with_limits(
+        slice: &[u8],
+        limits: DecodeLimits,
+    ) -> Result<Self, ParseScryptoValueError> {
         // Decode with SBOR
-        let value = decode_any(slice).map_err(ParseScryptoValueError::DecodeError)?;
+        let value =
+            decode_any_with_limits(slice, limits).map_err(ParseScryptoValueError::DecodeError)?;
 
         // Scrypto specific types checking
         let mut checker = ScryptoCustomValueChecker::new();
@@ -63,11 +76,11 @@ impl ScryptoValue {
 
     pub fn replace_ids(
         &mut self,
-        proof_replacements: &mut HashMap<ProofId, ProofId>,
-        bucket_replacements: &mut HashMap<BucketId, BucketId>
+        proof_replacements: &mut BTreeMap<ProofId, ProofId>,
+        bucket_replacements: &mut BTreeMap<BucketId, BucketId>
     ) -> Result<(), ScryptoValueReplaceError>{
-        let mut new_proof_ids = HashMap::new();
-        for (proof_id, path) in self.proof_ids.drain() {
+        let mut new_proof_ids = BTreeMap::new();
+        for (proof_id, path) in mem::take(&mut self.proof_ids) {
             let next_id = proof_replacements.remove(&proof_id)
                 .ok_or(ScryptoValueReplaceError::ProofIdNotFound(proof_id))?;
             let value = path.get_from_value_mut(&mut self.dom).unwrap();
@@ -81,8 +94,8 @@ impl ScryptoValue {
         }
         self.proof_ids = new_proof_ids;
 
-        let mut new_bucket_ids = HashMap::new();
-        for (bucket_id, path) in self.bucket_ids.drain() {
+        let mut new_bucket_ids = BTreeMap::new();
+        for (bucket_id, path) in mem::take(&mut self.bucket_ids) {
             let next_id = bucket_replacements.remove(&bucket_id)
                 .ok_or(ScryptoValueReplaceError::BucketIdNotFound(bucket_id))?;
             let value = path.get_from_value_mut(&mut self.dom).unwrap();
@@ -109,15 +122,55 @@ impl ScryptoValue {
     }
 
     pub fn to_string(&self) -> String {
-        ScryptoValueFormatter::format_value(&self.dom, &HashMap::new(), &HashMap::new())
+        ScryptoValueFormatter::format_value(&self.dom, &HashMap::new(), &HashMap::new(), None)
     }
 
+    /// Formats this value, naming buckets and proofs with the given names and encoding
+    /// addresses as Bech32m with the given encoder, if any.
     pub fn to_string_with_context(
         &self,
         bucket_ids: &HashMap<BucketId, String>,
         proof_ids: &HashMap<ProofId, String>,
+        encoder: Option<&Bech32Encoder>,
     ) -> String {
-        ScryptoValueFormatter::format_value(&self.dom, bucket_ids, proof_ids)
+        ScryptoValueFormatter::format_value(&self.dom, bucket_ids, proof_ids, encoder)
+    }
+}
+
+/// The ids referenced by a Scrypto value.
+///
+/// The ids are collected by streaming over the raw SBOR payload, which is cheaper than parsing
+/// a `ScryptoValue` when the value itself isn't needed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScryptoValueIds {
+    pub bucket_ids: BTreeMap<BucketId, SborPath>,
+    pub proof_ids: BTreeMap<ProofId, SborPath>,
+    pub vault_ids: BTreeSet<VaultId>,
+    pub lazy_map_ids: BTreeSet<LazyMapId>,
+}
+
+impl ScryptoValueIds {
+    pub fn from_slice(slice: &[u8]) -> Result<Self, ParseScryptoValueError> {
+        Self::from_slice_with_limits(slice, DecodeLimits::default())
+    }
+
+    /// Collects the ids, rejecting values which exceed the nesting depth or payload length limits.
+    pub fn from_slice_with_limits(
+        slice: &[u8],
+        limits: DecodeLimits,
+    ) -> Result<Self, ParseScryptoValueError> {
+        let mut checker = ScryptoCustomValueChecker::new();
+        traverse_raw(slice, limits, &mut checker).map_err(|e| match e {
+            ScryptoCustomValueCheckError::DecodeError(e) => ParseScryptoValueError::DecodeError(e),
+            e => ParseScryptoValueError::CustomValueCheckError(e),
+        })?;
+
+        Ok(ScryptoValueIds {
+            bucket_ids: checker.buckets.drain().map(|(e, path)| (e.0, path)).collect(),
+            proof_ids: checker.proofs.drain().map(|(e, path)| (e.0, path)).collect(),
+            vault_ids: checker.vaults.iter().map(|e| e.0).collect(),
+            lazy_map_ids: checker.lazy_maps.iter().map(|e| e.id).collect(),
+        })
     }
 }
 
@@ -147,6 +200,8 @@ pub enum ScryptoCustomValueCheckError {
     DecodeError(DecodeError),
     InvalidTypeId(u8),
     InvalidDecimal(ParseDecimalError),
+    InvalidI256(ParseI256Error),
+    InvalidU256(ParseU256Error),
     InvalidPackageAddress(ParsePackageAddressError),
     InvalidComponentAddress(ParseComponentAddressError),
     InvalidResourceAddress(ParseResourceAddressError),
@@ -162,6 +217,12 @@ pub enum ScryptoCustomValueCheckError {
     DuplicateIds,
 }
 
+impl From<DecodeError> for ScryptoCustomValueCheckError {
+    fn from(error: DecodeError) -> Self {
+        ScryptoCustomValueCheckError::DecodeError(error)
+    }
+}
+
 impl ScryptoCustomValueChecker {
     pub fn new() -> Self {
         Self {
@@ -207,6 +268,12 @@ impl CustomValueVisitor for ScryptoCustomValueChecker {
             ScryptoType::Decimal => {
                 Decimal::try_from(data).map_err(ScryptoCustomValueCheckError::InvalidDecimal)?;
             }
+            ScryptoType::I256 => {
+                I256::try_from(data).map_err(ScryptoCustomValueCheckError::InvalidI256)?;
+            }
+            ScryptoType::U256 => {
+                U256::try_from(data).map_err(ScryptoCustomValueCheckError::InvalidU256)?;
+            }
             ScryptoType::Bucket => {
                 let bucket = Bucket::try_from(data).map_err(ScryptoCustomValueCheckError::InvalidBucket)?;
                 if self.buckets.insert(bucket, path.clone().into()).is_some() {
@@ -251,6 +318,7 @@ impl ScryptoValueFormatter {
         value: &Value,
         bucket_ids: &HashMap<BucketId, String>,
         proof_ids: &HashMap<ProofId, String>,
+        encoder: Option<&Bech32Encoder>,
     ) -> String {
         match value {
             // primitive types
@@ -271,7 +339,7 @@ impl ScryptoValueFormatter {
             Value::Struct { fields } => {
                 format!(
                     "Struct({})",
-                    Self::format_elements(fields, bucket_ids, proof_ids)
+                    Self::format_elements(fields, bucket_ids, proof_ids, encoder)
                 )
             }
             Value::Enum { name, fields } => {
@@ -279,12 +347,23 @@ impl ScryptoValueFormatter {
                     "Enum(\"{}\"{}{})",
                     name,
                     if fields.is_empty() { "" } else { ", " },
-                    Self::format_elements(fields, bucket_ids, proof_ids)
+                    Self::format_elements(fields, bucket_ids, proof_ids, encoder)
+                )
+            }
+            Value::IndexedEnum { index, fields } => {
+                format!(
+                    "IndexedEnum({}u8{}{})",
+                    index,
+                    if fields.is_empty() { "" } else { ", " },
+                    Self::format_elements(fields, bucket_ids, proof_ids, encoder)
                 )
             }
             // rust types
             Value::Option { value } => match value.borrow() {
-                Some(x) => format!("Some({})", Self::format_value(x, bucket_ids, proof_ids)),
+                Some(x) => format!(
+                    "Some({})",
+                    Self::format_value(x, bucket_ids, proof_ids, encoder)
+                ),
                 None => "None".to_string(),
             },
             Value::Array {
@@ -293,15 +372,21 @@ impl ScryptoValueFormatter {
             } => format!(
                 "Array<{}>({})",
                 Self::format_type_id(*element_type_id),
-                Self::format_elements(elements, bucket_ids, proof_ids)
+                Self::format_elements(elements, bucket_ids, proof_ids, encoder)
             ),
             Value::Tuple { elements } => format!(
                 "Tuple({})",
-                Self::format_elements(elements, bucket_ids, proof_ids)
+                Self::format_elements(elements, bucket_ids, proof_ids, encoder)
             ),
             Value::Result { value } => match value.borrow() {
-                Ok(x) => format!("Ok({})", Self::format_value(x, bucket_ids, proof_ids)),
-                Err(x) => format!("Err({})", Self::format_value(x, bucket_ids, proof_ids)),
+                Ok(x) => format!(
+                    "Ok({})",
+                    Self::format_value(x, bucket_ids, proof_ids, encoder)
+                ),
+                Err(x) => format!(
+                    "Err({})",
+                    Self::format_value(x, bucket_ids, proof_ids, encoder)
+                ),
             },
             // collections
             Value::Vec {
@@ -321,7 +406,7 @@ impl ScryptoValueFormatter {
                     format!(
                         "Vec<{}>({})",
                         Self::format_type_id(*element_type_id),
-                        Self::format_elements(elements, bucket_ids, proof_ids)
+                        Self::format_elements(elements, bucket_ids, proof_ids, encoder)
                     )
                 }
             }
@@ -331,7 +416,7 @@ impl ScryptoValueFormatter {
             } => format!(
                 "TreeSet<{}>({})",
                 Self::format_type_id(*element_type_id),
-                Self::format_elements(elements, bucket_ids, proof_ids)
+                Self::format_elements(elements, bucket_ids, proof_ids, encoder)
             ),
             Value::HashSet {
                 element_type_id,
@@ -339,7 +424,7 @@ impl ScryptoValueFormatter {
             } => format!(
                 "HashSet<{}>({})",
                 Self::format_type_id(*element_type_id),
-                Self::format_elements(elements, bucket_ids, proof_ids)
+                Self::format_elements(elements, bucket_ids, proof_ids, encoder)
             ),
             Value::TreeMap {
                 key_type_id,
@@ -349,7 +434,7 @@ impl ScryptoValueFormatter {
                 "TreeMap<{}, {}>({})",
                 Self::format_type_id(*key_type_id),
                 Self::format_type_id(*value_type_id),
-                Self::format_elements(elements, bucket_ids, proof_ids)
+                Self::format_elements(elements, bucket_ids, proof_ids, encoder)
             ),
             Value::HashMap {
                 key_type_id,
@@ -359,11 +444,11 @@ impl ScryptoValueFormatter {
                 "HashMap<{}, {}>({})",
                 Self::format_type_id(*key_type_id),
                 Self::format_type_id(*value_type_id),
-                Self::format_elements(elements, bucket_ids, proof_ids)
+                Self::format_elements(elements, bucket_ids, proof_ids, encoder)
             ),
             // custom types
             Value::Custom { type_id, bytes } => {
-                Self::from_custom_value(*type_id, bytes, bucket_ids, proof_ids)
+                Self::from_custom_value(*type_id, bytes, bucket_ids, proof_ids, encoder)
             }
         }
     }
@@ -391,6 +476,7 @@ impl ScryptoValueFormatter {
             // struct & enum
             TYPE_STRUCT => "Struct",
             TYPE_ENUM => "Enum",
+            TYPE_INDEXED_ENUM => "IndexedEnum",
             TYPE_OPTION => "Option",
             TYPE_ARRAY => "Array",
             TYPE_TUPLE => "Tuple",
@@ -411,13 +497,14 @@ impl ScryptoValueFormatter {
         values: &[Value],
         bucket_ids: &HashMap<BucketId, String>,
         proof_ids: &HashMap<ProofId, String>,
+        encoder: Option<&Bech32Encoder>,
     ) -> String {
         let mut buf = String::new();
         for (i, x) in values.iter().enumerate() {
             if i != 0 {
                 buf.push_str(", ");
             }
-            buf.push_str(Self::format_value(x, bucket_ids, proof_ids).as_str());
+            buf.push_str(Self::format_value(x, bucket_ids, proof_ids, encoder).as_str());
         }
         buf
     }
@@ -426,20 +513,31 @@ impl ScryptoValueFormatter {
         data: &[u8],
         bucket_ids: &HashMap<BucketId, String>,
         proof_ids: &HashMap<ProofId, String>,
+        encoder: Option<&Bech32Encoder>,
     ) -> String {
         match ScryptoType::from_id(type_id).unwrap() {
             ScryptoType::Decimal => format!("Decimal(\"{}\")", Decimal::try_from(data).unwrap()),
+            ScryptoType::I256 => format!("I256(\"{}\")", I256::try_from(data).unwrap()),
+            ScryptoType::U256 => format!("U256(\"{}\")", U256::try_from(data).unwrap()),
             ScryptoType::PackageAddress => {
-                format!(
-                    "PackageAddress(\"{}\")",
-                    PackageAddress::try_from(data).unwrap()
-                )
+                let address = PackageAddress::try_from(data).unwrap();
+                match encoder {
+                    Some(encoder) => format!(
+                        "PackageAddress(\"{}\")",
+                        encoder.encode_package_address(&address)
+                    ),
+                    None => format!("PackageAddress(\"{}\")", address),
+                }
             }
             ScryptoType::ComponentAddress => {
-                format!(
-                    "ComponentAddress(\"{}\")",
-                    ComponentAddress::try_from(data).unwrap()
-                )
+                let address = ComponentAddress::try_from(data).unwrap();
+                match encoder {
+                    Some(encoder) => format!(
+                        "ComponentAddress(\"{}\")",
+                        encoder.encode_component_address(&address)
+                    ),
+                    None => format!("ComponentAddress(\"{}\")", address),
+                }
             }
             ScryptoType::LazyMap => format!(
                 "LazyMap(\"{}\")",
@@ -483,10 +581,16 @@ impl ScryptoValueFormatter {
                 "NonFungibleAddress(\"{}\")",
                 NonFungibleAddress::try_from(data).unwrap()
             ),
-            ScryptoType::ResourceAddress => format!(
-                "ResourceAddress(\"{}\")",
-                ResourceAddress::try_from(data).unwrap()
-            ),
+            ScryptoType::ResourceAddress => {
+                let address = ResourceAddress::try_from(data).unwrap();
+                match encoder {
+                    Some(encoder) => format!(
+                        "ResourceAddress(\"{}\")",
+                        encoder.encode_resource_address(&address)
+                    ),
+                    None => format!("ResourceAddress(\"{}\")", address),
+                }
+            }
         }
     }
 }
@@ -511,4 +615,84 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn should_format_addresses_with_encoder() {
+        let value = ScryptoValue::from_value(&crate::constants::RADIX_TOKEN);
+        let encoder = Bech32Encoder::new(crate::address::MAINNET_NETWORK_ID);
+
+        let formatted =
+            value.to_string_with_context(&HashMap::new(), &HashMap::new(), Some(&encoder));
+
+        assert_eq!(
+            formatted,
+            format!(
+                "ResourceAddress(\"{}\")",
+                encoder.encode_resource_address(&crate::constants::RADIX_TOKEN)
+            )
+        );
+        assert_eq!(
+            value.to_string(),
+            format!("ResourceAddress(\"{}\")", crate::constants::RADIX_TOKEN)
+        );
+    }
+
+    #[test]
+    fn should_reject_value_exceeding_limits() {
+        let value = scrypto_encode(&Some(Some(vec![1u8, 2u8])));
+
+        let limits = DecodeLimits {
+            max_depth: 2,
+            ..DecodeLimits::default()
+        };
+        let error = ScryptoValue::from_slice_with_limits(&value, limits)
+            .expect_err("Should be an error");
+        assert_eq!(
+            error,
+            ParseScryptoValueError::DecodeError(DecodeError::MaxDepthExceeded(2))
+        );
+
+        let limits = DecodeLimits {
+            max_payload_length: 4,
+            ..DecodeLimits::default()
+        };
+        let error = ScryptoValue::from_slice_with_limits(&value, limits)
+            .expect_err("Should be an error");
+        assert_eq!(
+            error,
+            ParseScryptoValueError::DecodeError(DecodeError::PayloadTooLarge {
+                max: 4,
+                actual: value.len()
+            })
+        );
+    }
+
+    #[test]
+    fn should_collect_same_ids_as_parsed_value() {
+        let value = scrypto_encode(&(
+            vec![scrypto::resource::Bucket(1), scrypto::resource::Bucket(2)],
+            Some(scrypto::resource::Proof(3)),
+            String::from("abc"),
+        ));
+
+        let parsed = ScryptoValue::from_slice(&value).unwrap();
+        let ids = ScryptoValueIds::from_slice(&value).unwrap();
+        assert_eq!(ids.bucket_ids.len(), 2);
+        assert_eq!(ids.proof_ids.len(), 1);
+        assert_eq!(ids.bucket_ids, parsed.bucket_ids);
+        assert_eq!(ids.proof_ids, parsed.proof_ids);
+        assert_eq!(ids.vault_ids, parsed.vault_ids);
+        assert_eq!(ids.lazy_map_ids, parsed.lazy_map_ids);
+
+        let buckets = scrypto_encode(&vec![
+            scrypto::resource::Bucket(0),
+            scrypto::resource::Bucket(0),
+        ]);
+        assert_eq!(
+            ScryptoValueIds::from_slice(&buckets),
+            Err(ParseScryptoValueError::CustomValueCheckError(
+                ScryptoCustomValueCheckError::DuplicateIds
+            ))
+        );
+    }
 }
@@ -0,0 +1,82 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use radix_engine::ledger::*;
+use scrypto::buffer::scrypto_encode;
+use scrypto::crypto::Hash;
+use simulator::ledger::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SUBSTATE_COUNT: u32 = 1000;
+
+const COMPRESSIONS: [(&str, LedgerCompression); 3] = [
+    ("none", LedgerCompression::None),
+    ("lz4", LedgerCompression::Lz4),
+    ("zstd", LedgerCompression::Zstd),
+];
+
+/// A substate resembling non-fungible data, which is mostly repetitive text.
+fn substate(index: u32) -> Substate {
+    let entries: Vec<(String, u32, bool)> = (0..200)
+        .map(|i| (format!("Sandwich #{}", index * 200 + i), i, i % 2 == 0))
+        .collect();
+    Substate {
+        value: scrypto_encode(&entries),
+        phys_id: (Hash([0u8; 32]), index),
+    }
+}
+
+fn open_ledger(name: &str, compression: LedgerCompression) -> (PathBuf, RadixEngineDB) {
+    let path = std::env::temp_dir().join(format!("radix-ledger-bench-{}", name));
+    if path.exists() {
+        fs::remove_dir_all(&path).unwrap();
+    }
+    let ledger = RadixEngineDB::with_compression(path.clone(), compression);
+    (path, ledger)
+}
+
+fn dir_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .unwrap()
+        .map(|entry| entry.unwrap().metadata().unwrap().len())
+        .sum()
+}
+
+fn bench_write(c: &mut Criterion) {
+    let substates: Vec<Substate> = (0..SUBSTATE_COUNT).map(substate).collect();
+    let mut group = c.benchmark_group("ledger_write");
+    for (name, compression) in COMPRESSIONS {
+        let (path, mut ledger) = open_ledger(name, compression);
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| {
+                for (i, substate) in substates.iter().enumerate() {
+                    ledger.put_substate(&(i as u32), substate.clone());
+                }
+                ledger.flush();
+            })
+        });
+        println!("{}: {} bytes on disk", name, dir_size(&path));
+    }
+    group.finish();
+}
+
+fn bench_read(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ledger_read");
+    for (name, compression) in COMPRESSIONS {
+        let (_, mut ledger) = open_ledger(name, compression);
+        for i in 0..SUBSTATE_COUNT {
+            ledger.put_substate(&i, substate(i));
+        }
+        ledger.flush();
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| {
+                for i in 0..SUBSTATE_COUNT {
+                    ledger.get_substate(&i).unwrap();
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(ledger, bench_write, bench_read);
+criterion_main!(ledger);
@@ -1,9 +1,13 @@
 #![allow(unused_must_use)]
 use colored::*;
 use radix_engine::ledger::*;
+use radix_engine::model::fmt::format_vault;
 use radix_engine::model::*;
+use sbor::Type;
+use scrypto::address::Bech32Encoder;
+use scrypto::component::find_lazy_map_entry_types;
 use scrypto::engine::types::*;
-use scrypto::rust::collections::HashSet;
+use scrypto::rust::collections::HashMap;
 use scrypto::values::*;
 use std::collections::VecDeque;
 
@@ -21,6 +25,7 @@ pub enum DisplayError {
 pub fn dump_package<T: SubstateStore, O: std::io::Write>(
     package_address: PackageAddress,
     substate_store: &T,
+    encoder: &Bech32Encoder,
     output: &mut O,
 ) -> Result<(), DisplayError> {
     let package: Option<Package> = substate_store
@@ -32,7 +37,7 @@ pub fn dump_package<T: SubstateStore, O: std::io::Write>(
                 output,
                 "{}: {}",
                 "Package".green().bold(),
-                package_address.to_string()
+                encoder.encode_package_address(&package_address)
             );
             writeln!(
                 output,
@@ -50,6 +55,7 @@ pub fn dump_package<T: SubstateStore, O: std::io::Write>(
 pub fn dump_component<T: SubstateStore + QueryableSubstateStore, O: std::io::Write>(
     component_address: ComponentAddress,
     substate_store: &T,
+    encoder: &Bech32Encoder,
     output: &mut O,
 ) -> Result<(), DisplayError> {
     let component: Option<Component> = substate_store
@@ -61,14 +67,14 @@ pub fn dump_component<T: SubstateStore + QueryableSubstateStore, O: std::io::Wri
                 output,
                 "{}: {}",
                 "Component".green().bold(),
-                component_address.to_string()
+                encoder.encode_component_address(&component_address)
             );
 
             writeln!(
                 output,
                 "{}: {{ package_address: {}, blueprint_name: \"{}\" }}",
                 "Blueprint".green().bold(),
-                c.package_address(),
+                encoder.encode_package_address(&c.package_address()),
                 c.blueprint_name()
             );
 
@@ -81,21 +87,41 @@ pub fn dump_component<T: SubstateStore + QueryableSubstateStore, O: std::io::Wri
 
             let state = c.state();
             let state_data = ScryptoValue::from_slice(state).unwrap();
-            writeln!(output, "{}: {}", "State".green().bold(), state_data);
+            writeln!(
+                output,
+                "{}: {}",
+                "State".green().bold(),
+                format_value(&state_data, encoder)
+            );
+
+            // Find the key and value types of lazy maps from the blueprint schema, if available.
+            let mut entry_types = substate_store
+                .get_package(&c.package_address())
+                .and_then(|package| {
+                    package
+                        .load_blueprint_schema(c.blueprint_name())
+                        .ok()
+                        .map(|schema| find_lazy_map_entry_types(schema, &state_data.dom))
+                })
+                .unwrap_or_default();
 
-            // Find all vaults owned by the component, assuming a tree structure.
-            let mut vaults_found: HashSet<VaultId> = state_data.vault_ids.iter().cloned().collect();
+            // Dump lazy maps owned by the component, assuming a tree structure.
             let mut queue: VecDeque<LazyMapId> = state_data.lazy_map_ids.iter().cloned().collect();
             while !queue.is_empty() {
                 let lazy_map_id = queue.pop_front().unwrap();
-                let (maps, vaults) =
-                    dump_lazy_map(component_address, &lazy_map_id, substate_store, output)?;
+                let maps = dump_lazy_map(
+                    component_address,
+                    &lazy_map_id,
+                    &mut entry_types,
+                    substate_store,
+                    encoder,
+                    output,
+                )?;
                 queue.extend(maps);
-                vaults_found.extend(vaults);
             }
 
             // Dump resources
-            dump_resources(component_address, &vaults_found, substate_store, output)
+            dump_resources(component_address, substate_store, encoder, output)
         }
         None => Err(DisplayError::ComponentNotFound),
     }
@@ -104,19 +130,32 @@ pub fn dump_component<T: SubstateStore + QueryableSubstateStore, O: std::io::Wri
 fn dump_lazy_map<T: SubstateStore + QueryableSubstateStore, O: std::io::Write>(
     component_address: ComponentAddress,
     lazy_map_id: &LazyMapId,
+    entry_types: &mut HashMap<LazyMapId, (Type, Type)>,
     substate_store: &T,
+    encoder: &Bech32Encoder,
     output: &mut O,
-) -> Result<(Vec<LazyMapId>, Vec<VaultId>), DisplayError> {
+) -> Result<Vec<LazyMapId>, DisplayError> {
     let mut referenced_maps = Vec::new();
-    let mut referenced_vaults = Vec::new();
     let map = substate_store.get_lazy_map_entries(component_address, lazy_map_id);
-    writeln!(
-        output,
-        "{}: {:?}{:?}",
-        "Lazy Map".green().bold(),
-        component_address,
-        lazy_map_id
-    );
+    let types = entry_types.get(lazy_map_id).cloned();
+    match &types {
+        Some((key_type, value_type)) => writeln!(
+            output,
+            "{}: {:?}{:?} ({:?} => {:?})",
+            "Lazy Map".green().bold(),
+            component_address,
+            lazy_map_id,
+            key_type,
+            value_type
+        ),
+        None => writeln!(
+            output,
+            "{}: {:?}{:?}",
+            "Lazy Map".green().bold(),
+            component_address,
+            lazy_map_id
+        ),
+    };
     for (last, (k, v)) in map.iter().identify_last() {
         let k_validated = ScryptoValue::from_slice(k).unwrap();
         let v_validated = ScryptoValue::from_slice(v).unwrap();
@@ -124,29 +163,45 @@ fn dump_lazy_map<T: SubstateStore + QueryableSubstateStore, O: std::io::Write>(
             output,
             "{} {} => {}",
             list_item_prefix(last),
-            k_validated,
-            v_validated
+            format_value(&k_validated, encoder),
+            format_value(&v_validated, encoder)
         );
+        if let Some((_, value_type)) = &types {
+            entry_types.extend(find_lazy_map_entry_types(value_type, &v_validated.dom));
+        }
         referenced_maps.extend(v_validated.lazy_map_ids);
-        referenced_vaults.extend(v_validated.vault_ids);
     }
-    Ok((referenced_maps, referenced_vaults))
+    Ok(referenced_maps)
 }
 
-fn dump_resources<T: SubstateStore, O: std::io::Write>(
+fn dump_resources<T: SubstateStore + QueryableSubstateStore, O: std::io::Write>(
     component_address: ComponentAddress,
-    vaults: &HashSet<VaultId>,
     substate_store: &T,
+    encoder: &Bech32Encoder,
     output: &mut O,
 ) -> Result<(), DisplayError> {
     writeln!(output, "{}:", "Resources".green().bold());
-    for (last, vault_id) in vaults.iter().identify_last() {
-        let vault: Vault = substate_store
-            .get_decoded_child_substate(&component_address, vault_id)
-            .unwrap()
-            .0;
-
-        let amount = vault.total_amount();
+    let vaults: Vec<Vault> = get_component_vaults(substate_store, component_address)
+        .unwrap_or_default()
+        .iter()
+        .map(|vault_id| {
+            substate_store
+                .get_decoded_child_substate(&component_address, vault_id)
+                .unwrap()
+                .0
+        })
+        .collect();
+    let symbols = |resource_address: ResourceAddress| {
+        let resource_manager: Option<ResourceManager> = substate_store
+            .get_decoded_substate(&resource_address)
+            .map(|(resource, _)| resource);
+        Some(
+            resource_manager
+                .and_then(|resource_manager| resource_manager.metadata().get("symbol").cloned())
+                .unwrap_or_else(|| encoder.encode_resource_address(&resource_address)),
+        )
+    };
+    for (last, vault) in vaults.iter().identify_last() {
         let resource_address = vault.resource_address();
         let resource_manager: ResourceManager = substate_store
             .get_decoded_substate(&resource_address)
@@ -154,20 +209,15 @@ fn dump_resources<T: SubstateStore, O: std::io::Write>(
             .unwrap();
         writeln!(
             output,
-            "{} {{ amount: {}, resource address: {}{}{} }}",
+            "{} {}, resource address: {}{}",
             list_item_prefix(last),
-            amount,
-            resource_address,
+            format_vault(vault, &symbols),
+            encoder.encode_resource_address(&resource_address),
             resource_manager
                 .metadata()
                 .get("name")
                 .map(|name| format!(", name: \"{}\"", name))
                 .unwrap_or(String::new()),
-            resource_manager
-                .metadata()
-                .get("symbol")
-                .map(|symbol| format!(", symbol: \"{}\"", symbol))
-                .unwrap_or(String::new()),
         );
         if matches!(resource_manager.resource_type(), ResourceType::NonFungible) {
             let ids = vault.total_ids().unwrap();
@@ -182,14 +232,24 @@ fn dump_resources<T: SubstateStore, O: std::io::Write>(
                         ScryptoValue::from_slice(&non_fungible.immutable_data()).unwrap();
                     let mutable_data =
                         ScryptoValue::from_slice(&non_fungible.mutable_data()).unwrap();
+                    let metadata = non_fungible
+                        .metadata()
+                        .iter()
+                        .map(|(key, value)| format!("{}: \"{}\"", key, value))
+                        .collect::<Vec<String>>();
                     writeln!(
                         output,
-                        "{}  {} NonFungible {{ id: {}, immutable_data: {}, mutable_data: {} }}",
+                        "{}  {} NonFungible {{ id: {}, immutable_data: {}, mutable_data: {}{} }}",
                         if last { " " } else { "│" },
                         list_item_prefix(inner_last),
                         id,
-                        immutable_data,
-                        mutable_data
+                        format_value(&immutable_data, encoder),
+                        format_value(&mutable_data, encoder),
+                        if metadata.is_empty() {
+                            String::new()
+                        } else {
+                            format!(", metadata: {{ {} }}", metadata.join(", "))
+                        }
                     );
                 }
             }
@@ -198,6 +258,11 @@ fn dump_resources<T: SubstateStore, O: std::io::Write>(
     Ok(())
 }
 
+/// Formats a value, with addresses encoded for the network of the encoder.
+fn format_value(value: &ScryptoValue, encoder: &Bech32Encoder) -> String {
+    value.to_string_with_context(&HashMap::new(), &HashMap::new(), Some(encoder))
+}
+
 /// Dump a resource into console.
 pub fn dump_resource_manager<T: SubstateStore, O: std::io::Write>(
     resource_address: ResourceAddress,
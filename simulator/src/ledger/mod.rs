@@ -1,5 +1,7 @@
 mod dumper;
 mod radix_engine_db;
+mod transaction_log;
 
 pub use dumper::*;
-pub use radix_engine_db::RadixEngineDB;
+pub use radix_engine_db::{LedgerCompression, RadixEngineDB};
+pub use transaction_log::*;
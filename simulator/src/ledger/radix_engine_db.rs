@@ -1,20 +1,95 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 use radix_engine::ledger::*;
-use rocksdb::{DBWithThreadMode, Direction, IteratorMode, SingleThreaded, DB};
+use rocksdb::{
+    DBCompressionType, DBWithThreadMode, Direction, IteratorMode, Options, SingleThreaded, DB,
+};
 use sbor::{Decode, Encode};
 use scrypto::buffer::*;
 use scrypto::engine::types::*;
 
+/// How the substates of a ledger are compressed on disk.
+///
+/// Blocks are compressed by RocksDB, which records the compression of each, so a ledger can be
+/// reopened with a different setting and only new writes are affected. In particular, ledgers
+/// written with RocksDB's default, Snappy, stay readable now that the default is Lz4.
+///
+/// `resim` reads the setting from the `LEDGER_COMPRESSION` environment variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LedgerCompression {
+    None,
+    /// Fast compression, suited to ledgers which are written often.
+    #[default]
+    Lz4,
+    /// Slower compression with a better ratio, suited to large ledgers.
+    Zstd,
+}
+
+impl LedgerCompression {
+    fn options(&self) -> Options {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.set_compression_type(match self {
+            LedgerCompression::None => DBCompressionType::None,
+            LedgerCompression::Lz4 => DBCompressionType::Lz4,
+            LedgerCompression::Zstd => DBCompressionType::Zstd,
+        });
+        options
+    }
+}
+
+impl FromStr for LedgerCompression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(LedgerCompression::None),
+            "lz4" => Ok(LedgerCompression::Lz4),
+            "zstd" => Ok(LedgerCompression::Zstd),
+            _ => Err(format!("Unknown ledger compression: {}", s)),
+        }
+    }
+}
+
 pub struct RadixEngineDB {
     db: DBWithThreadMode<SingleThreaded>,
+    non_fungible_index: bool,
 }
 
+const NON_FUNGIBLE_INDEX_KEY: &str = "non_fungible_index";
+const NON_FUNGIBLE_LOCATION_KEY: &str = "non_fungible_location";
+
 impl RadixEngineDB {
     pub fn new(root: PathBuf) -> Self {
-        let db = DB::open_default(root.as_path()).unwrap();
-        Self { db }
+        Self::with_compression(root, LedgerCompression::default())
+    }
+
+    /// Opens the ledger, compressing the substates written from now on as specified.
+    pub fn with_compression(root: PathBuf, compression: LedgerCompression) -> Self {
+        let db = DB::open(&compression.options(), root.as_path()).unwrap();
+        Self::from_db(db)
+    }
+
+    /// Opens the ledger without locking it, e.g. to observe the state while it's updated by
+    /// another process.
+    ///
+    /// Fails if the ledger doesn't exist yet, as it can't be created without being written to.
+    pub fn new_read_only(root: PathBuf) -> Result<Self, rocksdb::Error> {
+        let db = DB::open_for_read_only(&Options::default(), root.as_path(), false)?;
+        Ok(Self::from_db(db))
+    }
+
+    fn from_db(db: DBWithThreadMode<SingleThreaded>) -> Self {
+        let non_fungible_index = db
+            .get(scrypto_encode(&NON_FUNGIBLE_INDEX_KEY))
+            .unwrap()
+            .is_some();
+        Self {
+            db,
+            non_fungible_index,
+        }
     }
 
     pub fn with_bootstrap(root: PathBuf) -> Self {
@@ -23,6 +98,19 @@ impl RadixEngineDB {
         ledger
     }
 
+    /// Enables the non-fungible location index, for non-fungibles committed from now on.
+    ///
+    /// The setting is stored in the ledger, so the index stays up to date when the ledger is
+    /// reopened.
+    pub fn with_non_fungible_index(mut self) -> Self {
+        self.write(
+            &scrypto_encode(&NON_FUNGIBLE_INDEX_KEY),
+            &scrypto_encode(&true),
+        );
+        self.non_fungible_index = true;
+        self
+    }
+
     pub fn list_packages(&self) -> Vec<PackageAddress> {
         let start = &scrypto_encode(&PackageAddress([0; 26]));
         let end = &scrypto_encode(&PackageAddress([255; 26]));
@@ -41,6 +129,36 @@ impl RadixEngineDB {
         self.list_items(start, end)
     }
 
+    /// Returns the raw keys and values of all entries whose key starts with the given prefix.
+    pub fn list_entries(&self, prefix: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let mut iter = self
+            .db
+            .iterator(IteratorMode::From(prefix, Direction::Forward));
+        let mut items = Vec::new();
+        while let Some((key, value)) = iter.next() {
+            if !key.starts_with(prefix) {
+                break;
+            }
+            items.push((key.to_vec(), value.to_vec()));
+        }
+        items
+    }
+
+    /// Writes the buffered changes to disk, e.g. before measuring the size of the ledger.
+    pub fn flush(&self) {
+        self.db.flush().unwrap();
+    }
+
+    /// Replaces all entries of the ledger with the given raw keys and values.
+    pub fn replace_entries(&mut self, entries: Vec<(Vec<u8>, Vec<u8>)>) {
+        for (key, _) in self.list_entries(&[]) {
+            self.db.delete(&key).unwrap();
+        }
+        for (key, value) in entries {
+            self.write(&key, &value);
+        }
+    }
+
     fn list_items<T: Decode>(&self, start: &[u8], inclusive_end: &[u8]) -> Vec<T> {
         let mut iter = self
             .db
@@ -94,6 +212,44 @@ impl QueryableSubstateStore for RadixEngineDB {
     }
 }
 
+impl PrunableSubstateStore for RadixEngineDB {
+    fn get_component_addresses(&self) -> Vec<ComponentAddress> {
+        self.list_components()
+    }
+
+    fn get_resource_addresses(&self) -> Vec<ResourceAddress> {
+        self.list_resource_managers()
+    }
+
+    fn get_child_substates<T: Encode>(&self, address: &T) -> Vec<(Vec<u8>, Substate)> {
+        let id = scrypto_encode(address);
+
+        let mut iter = self
+            .db
+            .iterator(IteratorMode::From(&id, Direction::Forward));
+        let mut items = Vec::new();
+        while let Some((key, value)) = iter.next() {
+            if !key.starts_with(&id) {
+                break;
+            }
+            if key.len() == id.len() {
+                continue;
+            }
+
+            let local_key = key.split_at(id.len()).1.to_vec();
+            let substate: Substate = scrypto_decode(&value.to_vec()).unwrap();
+            items.push((local_key, substate));
+        }
+        items
+    }
+
+    fn delete_child_substate<T: Encode>(&mut self, address: &T, key: &[u8]) {
+        let mut id = scrypto_encode(address);
+        id.extend(key.to_vec());
+        self.db.delete(&id).unwrap();
+    }
+}
+
 impl SubstateStore for RadixEngineDB {
     fn get_substate<T: Encode>(&self, address: &T) -> Option<Substate> {
         self.read(&scrypto_encode(address))
@@ -141,4 +297,33 @@ impl SubstateStore for RadixEngineDB {
         let value = scrypto_encode(&(self.get_nonce() + 1));
         self.write(&id, &value)
     }
+
+    fn is_non_fungible_index_enabled(&self) -> bool {
+        self.non_fungible_index
+    }
+
+    fn get_non_fungible_location(
+        &self,
+        non_fungible_address: &NonFungibleAddress,
+    ) -> Option<NonFungibleLocation> {
+        let mut id = scrypto_encode(&NON_FUNGIBLE_LOCATION_KEY);
+        id.extend(scrypto_encode(non_fungible_address));
+        self.read(&id).map(|v| scrypto_decode(&v).unwrap())
+    }
+
+    fn put_non_fungible_location(
+        &mut self,
+        non_fungible_address: NonFungibleAddress,
+        location: Option<NonFungibleLocation>,
+    ) {
+        if !self.non_fungible_index {
+            return;
+        }
+        let mut id = scrypto_encode(&NON_FUNGIBLE_LOCATION_KEY);
+        id.extend(scrypto_encode(&non_fungible_address));
+        match location {
+            Some(location) => self.write(&id, &scrypto_encode(&location)),
+            None => self.db.delete(&id).unwrap(),
+        }
+    }
 }
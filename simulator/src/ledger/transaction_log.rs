@@ -0,0 +1,361 @@
+use radix_engine::engine::CommitReceipt;
+use radix_engine::ledger::*;
+use radix_engine::model::*;
+use sbor::type_id::TYPE_STRUCT;
+use sbor::*;
+use scrypto::buffer::*;
+use scrypto::core::NetworkDefinition;
+use scrypto::crypto::*;
+use scrypto::engine::types::*;
+
+/// The ledger key under which the transaction log is stored.
+pub const TRANSACTION_LOG_KEY: &str = "transaction_log";
+
+/// An entry of the transaction log, which records everything that changed the ledger state, so
+/// that the state can be rebuilt from genesis with `resim replay`.
+#[derive(Debug, Clone, TypeId, Encode)]
+pub enum TransactionLogEntry {
+    /// A transaction, with a summary of its receipt.
+    Transaction {
+        transaction: Transaction,
+        signatures: Vec<(EcdsaPublicKey, EcdsaSignature)>,
+        receipt: ReceiptSummary,
+    },
+    /// An account created with a key pair derived from the ledger nonce.
+    NewAccount { account: ComponentAddress },
+    /// A package published without a transaction manifest.
+    PublishPackage {
+        code: Vec<u8>,
+        package_address: PackageAddress,
+    },
+    /// A package overwritten in place.
+    OverwritePackage {
+        package_address: PackageAddress,
+        code: Vec<u8>,
+    },
+    /// The current epoch set by `resim set-current-epoch`.
+    SetEpoch { epoch: u64 },
+}
+
+impl TransactionLogEntry {
+    pub fn transaction(signed: &SignedTransaction, receipt: &Receipt) -> Self {
+        Self::Transaction {
+            transaction: signed.transaction.clone(),
+            signatures: signed.signatures.clone(),
+            receipt: ReceiptSummary::new(receipt),
+        }
+    }
+}
+
+impl Decode for TransactionLogEntry {
+    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+        let name = String::decode_value(decoder)?;
+        match name.as_str() {
+            "Transaction" => {
+                decoder.check_len(3)?;
+                Ok(Self::Transaction {
+                    transaction: decode_logged_transaction(decoder)?,
+                    signatures: Vec::<(EcdsaPublicKey, EcdsaSignature)>::decode(decoder)?,
+                    receipt: ReceiptSummary::decode(decoder)?,
+                })
+            }
+            "NewAccount" => {
+                decoder.check_len(1)?;
+                Ok(Self::NewAccount {
+                    account: ComponentAddress::decode(decoder)?,
+                })
+            }
+            "PublishPackage" => {
+                decoder.check_len(2)?;
+                Ok(Self::PublishPackage {
+                    code: Vec::<u8>::decode(decoder)?,
+                    package_address: PackageAddress::decode(decoder)?,
+                })
+            }
+            "OverwritePackage" => {
+                decoder.check_len(2)?;
+                Ok(Self::OverwritePackage {
+                    package_address: PackageAddress::decode(decoder)?,
+                    code: Vec::<u8>::decode(decoder)?,
+                })
+            }
+            "SetEpoch" => {
+                decoder.check_len(1)?;
+                Ok(Self::SetEpoch {
+                    epoch: u64::decode(decoder)?,
+                })
+            }
+            _ => Err(DecodeError::InvalidEnum(name)),
+        }
+    }
+}
+
+/// Decodes a logged transaction, including one logged before transactions had a header.
+fn decode_logged_transaction(decoder: &mut Decoder) -> Result<Transaction, DecodeError> {
+    decoder.check_type(TYPE_STRUCT)?;
+    decoder.increase_depth()?;
+    let transaction = match decoder.read_len()? {
+        2 => Transaction {
+            header: TransactionHeader::decode(decoder)?,
+            instructions: Vec::<Instruction>::decode(decoder)?,
+        },
+        // resim has only ever run on the simulator network.
+        1 => Transaction {
+            header: TransactionHeader {
+                network_id: NetworkDefinition::simulator().id,
+            },
+            instructions: Vec::<Instruction>::decode(decoder)?,
+        },
+        actual => {
+            return Err(DecodeError::InvalidLength {
+                expected: 2,
+                actual,
+            })
+        }
+    };
+    decoder.decrease_depth();
+    Ok(transaction)
+}
+
+/// The part of a receipt which must be reproduced when the transaction is replayed.
+///
+/// Execution time and read statistics are left out, as they depend on the host and the cache.
+#[derive(Debug, Clone, PartialEq, Eq, TypeId, Encode)]
+pub struct ReceiptSummary {
+    pub success: bool,
+    pub outputs: Vec<Vec<u8>>,
+    pub new_package_addresses: Vec<PackageAddress>,
+    pub new_component_addresses: Vec<ComponentAddress>,
+    pub new_resource_addresses: Vec<ResourceAddress>,
+    pub cost_units_consumed: u32,
+    /// The hash of the substates brought up and down by the commit, or `None` for entries
+    /// logged before it was recorded.
+    pub state_updates_hash: Option<Hash>,
+}
+
+impl ReceiptSummary {
+    pub fn new(receipt: &Receipt) -> Self {
+        Self {
+            success: receipt.result.is_ok(),
+            outputs: receipt.outputs.iter().map(|o| o.raw.clone()).collect(),
+            new_package_addresses: receipt.new_package_addresses.clone(),
+            new_component_addresses: receipt.new_component_addresses.clone(),
+            new_resource_addresses: receipt.new_resource_addresses.clone(),
+            cost_units_consumed: receipt.cost_units_consumed,
+            state_updates_hash: Some(Self::hash_state_updates(receipt.commit_receipt.as_ref())),
+        }
+    }
+
+    /// Hashes the substates brought up and down by a commit, in commit order.
+    fn hash_state_updates(commit_receipt: Option<&CommitReceipt>) -> Hash {
+        let (up_substates, down_substates): (Vec<(Hash, u32)>, Vec<(Hash, u32)>) =
+            match commit_receipt {
+                Some(commit_receipt) => (
+                    commit_receipt.up_substates.clone(),
+                    commit_receipt.down_substates.iter().cloned().collect(),
+                ),
+                None => (Vec::new(), Vec::new()),
+            };
+        hash(scrypto_encode(&(up_substates, down_substates)))
+    }
+
+    /// Returns whether the receipt of a replayed transaction matches this recorded one.
+    ///
+    /// State updates are only compared if they were recorded.
+    pub fn matches(&self, replayed: &ReceiptSummary) -> bool {
+        match self.state_updates_hash {
+            Some(_) => self == replayed,
+            None => {
+                *self
+                    == Self {
+                        state_updates_hash: None,
+                        ..replayed.clone()
+                    }
+            }
+        }
+    }
+}
+
+impl Decode for ReceiptSummary {
+    fn decode_value(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+        let len = decoder.read_len()?;
+        if len != 6 && len != 7 {
+            return Err(DecodeError::InvalidLength {
+                expected: 7,
+                actual: len,
+            });
+        }
+        Ok(Self {
+            success: bool::decode(decoder)?,
+            outputs: Vec::<Vec<u8>>::decode(decoder)?,
+            new_package_addresses: Vec::<PackageAddress>::decode(decoder)?,
+            new_component_addresses: Vec::<ComponentAddress>::decode(decoder)?,
+            new_resource_addresses: Vec::<ResourceAddress>::decode(decoder)?,
+            cost_units_consumed: u32::decode(decoder)?,
+            // Entries logged before state updates were recorded have six fields
+            state_updates_hash: if len == 7 {
+                Option::<Hash>::decode(decoder)?
+            } else {
+                None
+            },
+        })
+    }
+}
+
+/// Returns the number of entries in the transaction log.
+pub fn transaction_log_length<L: SubstateStore>(substate_store: &L) -> u64 {
+    substate_store
+        .get_decoded_substate(&TRANSACTION_LOG_KEY)
+        .map(|(length, _)| length)
+        .unwrap_or(0)
+}
+
+/// Appends an entry to the transaction log.
+pub fn append_transaction_log<L: SubstateStore>(
+    substate_store: &mut L,
+    entry: &TransactionLogEntry,
+) {
+    let index = transaction_log_length(substate_store);
+    let phys_id = (hash(scrypto_encode(entry)), 0);
+    substate_store.put_encoded_child_substate(&TRANSACTION_LOG_KEY, &index, entry, phys_id);
+    substate_store.put_encoded_substate(&TRANSACTION_LOG_KEY, &(index + 1), phys_id);
+}
+
+/// Reads all entries of the transaction log, in execution order.
+pub fn read_transaction_log<L: SubstateStore>(substate_store: &L) -> Vec<TransactionLogEntry> {
+    (0..transaction_log_length(substate_store))
+        .map(|index| {
+            substate_store
+                .get_decoded_child_substate(&TRANSACTION_LOG_KEY, &index)
+                .map(|(entry, _)| entry)
+                .expect("Transaction log entry is missing")
+        })
+        .collect()
+}
+
+/// Whether a raw ledger key belongs to the transaction log, rather than to the ledger state.
+pub fn is_transaction_log_key(key: &[u8]) -> bool {
+    key.starts_with(&scrypto_encode(&TRANSACTION_LOG_KEY))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(TypeId, Encode)]
+    struct LegacyReceiptSummary {
+        success: bool,
+        outputs: Vec<Vec<u8>>,
+        new_package_addresses: Vec<PackageAddress>,
+        new_component_addresses: Vec<ComponentAddress>,
+        new_resource_addresses: Vec<ResourceAddress>,
+        cost_units_consumed: u32,
+    }
+
+    #[derive(TypeId, Encode)]
+    struct LegacyTransaction {
+        instructions: Vec<Instruction>,
+    }
+
+    #[derive(TypeId, Encode)]
+    enum LegacyTransactionLogEntry {
+        Transaction {
+            transaction: LegacyTransaction,
+            signatures: Vec<(EcdsaPublicKey, EcdsaSignature)>,
+            receipt: ReceiptSummary,
+        },
+    }
+
+    fn receipt_summary() -> ReceiptSummary {
+        ReceiptSummary {
+            success: true,
+            outputs: Vec::new(),
+            new_package_addresses: Vec::new(),
+            new_component_addresses: Vec::new(),
+            new_resource_addresses: Vec::new(),
+            cost_units_consumed: 1000,
+            state_updates_hash: Some(ReceiptSummary::hash_state_updates(None)),
+        }
+    }
+
+    #[test]
+    fn should_decode_entry_logged_without_transaction_header() {
+        let entry = LegacyTransactionLogEntry::Transaction {
+            transaction: LegacyTransaction {
+                instructions: vec![Instruction::ClearAuthZone],
+            },
+            signatures: Vec::new(),
+            receipt: receipt_summary(),
+        };
+
+        let decoded: TransactionLogEntry = scrypto_decode(&scrypto_encode(&entry)).unwrap();
+
+        match decoded {
+            TransactionLogEntry::Transaction {
+                transaction,
+                receipt,
+                ..
+            } => {
+                assert_eq!(
+                    transaction,
+                    Transaction {
+                        header: TransactionHeader {
+                            network_id: NetworkDefinition::simulator().id,
+                        },
+                        instructions: vec![Instruction::ClearAuthZone],
+                    }
+                );
+                assert_eq!(receipt, receipt_summary());
+            }
+            _ => panic!("Expected a transaction entry"),
+        }
+    }
+
+    #[test]
+    fn should_keep_transaction_header_on_decode() {
+        let transaction = Transaction {
+            header: TransactionHeader { network_id: 0xf2 },
+            instructions: vec![Instruction::ClearAuthZone],
+        };
+        let entry = TransactionLogEntry::Transaction {
+            transaction: transaction.clone(),
+            signatures: Vec::new(),
+            receipt: receipt_summary(),
+        };
+
+        let decoded: TransactionLogEntry = scrypto_decode(&scrypto_encode(&entry)).unwrap();
+
+        assert!(
+            matches!(decoded, TransactionLogEntry::Transaction { transaction: t, .. } if t == transaction)
+        );
+    }
+
+    #[test]
+    fn should_decode_receipt_summary_logged_without_state_updates() {
+        let legacy = LegacyReceiptSummary {
+            success: true,
+            outputs: Vec::new(),
+            new_package_addresses: Vec::new(),
+            new_component_addresses: Vec::new(),
+            new_resource_addresses: Vec::new(),
+            cost_units_consumed: 1000,
+        };
+
+        let decoded: ReceiptSummary = scrypto_decode(&scrypto_encode(&legacy)).unwrap();
+
+        assert_eq!(decoded.state_updates_hash, None);
+        assert!(decoded.matches(&receipt_summary()));
+    }
+
+    #[test]
+    fn should_not_match_different_state_updates() {
+        let recorded = receipt_summary();
+        let replayed = ReceiptSummary {
+            state_updates_hash: Some(Hash([1u8; 32])),
+            ..receipt_summary()
+        };
+
+        assert!(recorded.matches(&receipt_summary()));
+        assert!(!recorded.matches(&replayed));
+    }
+}
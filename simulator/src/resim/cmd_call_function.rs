@@ -16,7 +16,7 @@ pub struct CallFunction {
     /// The function name
     function_name: String,
 
-    /// The call arguments, e.g. \"5\", \"hello\", \"amount,resource_address\" for Bucket, or \"#id1,#id2,..,resource_address\" for non-fungible Bucket
+    /// The call arguments, e.g. \"5\", \"hello\", \"amount,resource_address\" for Bucket, or \"#id1,#id2,..,resource_address\" for non-fungible Bucket; composite types are written like Rust values, e.g. \"[1, 2]\", \"Some(5)\" or \"{amount: 5}\", with quoted strings and buckets as Bucket(\"amount,resource_address\")
     arguments: Vec<String>,
 
     /// Output a transaction manifest without execution
@@ -34,7 +34,7 @@ pub struct CallFunction {
 
 impl CallFunction {
     pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
-        let mut ledger = RadixEngineDB::with_bootstrap(get_data_dir()?);
+        let mut ledger = open_ledger()?;
         let mut executor = TransactionExecutor::new(&mut ledger, self.trace);
         let default_account = get_default_account()?;
 
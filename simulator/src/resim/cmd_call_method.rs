@@ -15,7 +15,7 @@ pub struct CallMethod {
     /// The method name
     method_name: String,
 
-    /// The call arguments
+    /// The call arguments, in the same syntax as for `call-function`
     arguments: Vec<String>,
 
     /// Output a transaction manifest without execution
@@ -33,7 +33,7 @@ pub struct CallMethod {
 
 impl CallMethod {
     pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
-        let mut ledger = RadixEngineDB::with_bootstrap(get_data_dir()?);
+        let mut ledger = open_ledger()?;
         let mut executor = TransactionExecutor::new(&mut ledger, self.trace);
         let default_account = get_default_account()?;
 
@@ -0,0 +1,84 @@
+use clap::Parser;
+use colored::*;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::resim::*;
+
+/// Show the differences between two snapshots produced by `dump-state`
+///
+/// Values are compared in full, but long values are truncated in the output.
+#[derive(Parser, Debug)]
+pub struct Diff {
+    /// The path to the older snapshot
+    snapshot_a: PathBuf,
+
+    /// The path to the newer snapshot
+    snapshot_b: PathBuf,
+}
+
+impl Diff {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
+        let a = read_snapshot(&self.snapshot_a)?;
+        let b = read_snapshot(&self.snapshot_b)?;
+
+        let mut changes = 0;
+        for (key, value_a) in &a {
+            match b.get(key) {
+                Some(value_b) if value_b == value_a => {}
+                Some(value_b) => {
+                    writeln!(
+                        out,
+                        "{}",
+                        format!("- {}{}{}", key, SNAPSHOT_SEPARATOR, truncate_value(value_a)).red()
+                    )
+                    .map_err(Error::IOError)?;
+                    writeln!(
+                        out,
+                        "{}",
+                        format!("+ {}{}{}", key, SNAPSHOT_SEPARATOR, truncate_value(value_b))
+                            .green()
+                    )
+                    .map_err(Error::IOError)?;
+                    changes += 1;
+                }
+                None => {
+                    writeln!(
+                        out,
+                        "{}",
+                        format!("- {}{}{}", key, SNAPSHOT_SEPARATOR, truncate_value(value_a)).red()
+                    )
+                    .map_err(Error::IOError)?;
+                    changes += 1;
+                }
+            }
+        }
+        for (key, value_b) in &b {
+            if !a.contains_key(key) {
+                writeln!(
+                    out,
+                    "{}",
+                    format!("+ {}{}{}", key, SNAPSHOT_SEPARATOR, truncate_value(value_b)).green()
+                )
+                .map_err(Error::IOError)?;
+                changes += 1;
+            }
+        }
+
+        writeln!(out, "{} substate(s) changed", changes).map_err(Error::IOError)?;
+        Ok(())
+    }
+}
+
+/// Reads a snapshot produced by `dump-state`, as a map from substate key to value.
+fn read_snapshot(path: &PathBuf) -> Result<BTreeMap<String, String>, Error> {
+    let content = std::fs::read_to_string(path).map_err(Error::IOError)?;
+    let mut entries = BTreeMap::new();
+    for line in content.lines().filter(|line| !line.is_empty()) {
+        let (key, value) = line
+            .split_once(SNAPSHOT_SEPARATOR)
+            .ok_or_else(|| Error::InvalidSnapshot(line.to_owned()))?;
+        entries.insert(key.to_owned(), value.to_owned());
+    }
+    Ok(entries)
+}
@@ -0,0 +1,107 @@
+use clap::Parser;
+use radix_engine::ledger::*;
+use scrypto::buffer::*;
+use scrypto::engine::types::*;
+use scrypto::values::*;
+use std::str::FromStr;
+
+use crate::ledger::*;
+use crate::resim::*;
+
+/// The maximum number of characters of a value shown by `diff`.
+const MAX_VALUE_LENGTH: usize = 1024;
+
+/// Dump the raw substates in the ledger state, one per line
+#[derive(Parser, Debug)]
+pub struct DumpState {
+    /// Only dump the substates of a package, component or resource manager
+    #[clap(short, long)]
+    address: Option<String>,
+}
+
+impl DumpState {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
+        let ledger = open_ledger()?;
+
+        let prefix = match &self.address {
+            None => Vec::new(),
+            Some(address) => {
+                if let Ok(package_address) = PackageAddress::from_str(address) {
+                    scrypto_encode(&package_address)
+                } else if let Ok(component_address) = ComponentAddress::from_str(address) {
+                    scrypto_encode(&component_address)
+                } else if let Ok(resource_address) = ResourceAddress::from_str(address) {
+                    scrypto_encode(&resource_address)
+                } else {
+                    return Err(Error::InvalidId(address.clone()));
+                }
+            }
+        };
+
+        // The transaction log is history rather than state, and would show up in every diff
+        for (key, value) in ledger
+            .list_entries(&prefix)
+            .into_iter()
+            .filter(|(key, _)| !is_transaction_log_key(key))
+        {
+            writeln!(
+                out,
+                "{}{}{}",
+                format_substate_key(&key),
+                SNAPSHOT_SEPARATOR,
+                format_substate_value(&value)
+            )
+            .map_err(Error::IOError)?;
+        }
+        Ok(())
+    }
+}
+
+/// The separator between the key and the value of a line of `dump-state` output.
+pub const SNAPSHOT_SEPARATOR: &str = " => ";
+
+/// Formats the key of a ledger entry, which is an encoded address optionally followed by the
+/// key of a child substate.
+pub fn format_substate_key(key: &[u8]) -> String {
+    if let Ok(value) = ScryptoValue::from_slice(key) {
+        return value.to_string();
+    }
+    let address_length = scrypto_encode(&ComponentAddress([0; 26])).len();
+    if key.len() > address_length {
+        let (address, child_key) = key.split_at(address_length);
+        if let Ok(value) = ScryptoValue::from_slice(address) {
+            return format!("{}/{}", value.to_string(), hex::encode(child_key));
+        }
+    }
+    hex::encode(key)
+}
+
+/// Formats the value of a ledger entry, which is either a substate or a raw encoded value.
+///
+/// Values are never truncated, so that snapshots can be diffed however large they are.
+pub fn format_substate_value(value: &[u8]) -> String {
+    match scrypto_decode::<Substate>(value) {
+        Ok(substate) => match ScryptoValue::from_slice(&substate.value) {
+            Ok(v) => v.to_string(),
+            Err(_) => hex::encode(&substate.value),
+        },
+        Err(_) => match ScryptoValue::from_slice(value) {
+            Ok(v) => v.to_string(),
+            Err(_) => hex::encode(value),
+        },
+    }
+}
+
+/// Truncates a formatted value for display, keeping its first `MAX_VALUE_LENGTH` characters.
+pub fn truncate_value(value: &str) -> String {
+    let length = value.chars().count();
+    if length > MAX_VALUE_LENGTH {
+        format!(
+            "{}... ({} characters)",
+            value.chars().take(MAX_VALUE_LENGTH).collect::<String>(),
+            length
+        )
+    } else {
+        value.to_owned()
+    }
+}
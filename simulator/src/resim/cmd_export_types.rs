@@ -0,0 +1,35 @@
+use clap::Parser;
+use radix_engine::transaction::*;
+use scrypto::engine::types::*;
+
+use crate::resim::*;
+
+/// Export the structs and enums shared by a package
+#[derive(Parser, Debug)]
+pub struct ExportTypes {
+    /// The package ID
+    package_address: PackageAddress,
+
+    /// Turn on tracing.
+    #[clap(short, long)]
+    trace: bool,
+}
+
+impl ExportTypes {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
+        let mut ledger = open_ledger()?;
+        let executor = TransactionExecutor::new(&mut ledger, self.trace);
+        match executor.export_types(self.package_address) {
+            Ok(t) => {
+                writeln!(
+                    out,
+                    "{}",
+                    serde_json::to_string_pretty(&t).map_err(Error::JSONError)?
+                )
+                .map_err(Error::IOError)?;
+                Ok(())
+            }
+            Err(e) => Err(Error::AbiExportError(e)),
+        }
+    }
+}
@@ -19,7 +19,7 @@ pub struct NewAccount {
 
 impl NewAccount {
     pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
-        let mut ledger = RadixEngineDB::with_bootstrap(get_data_dir()?);
+        let mut ledger = open_ledger()?;
         let mut executor = TransactionExecutor::new(&mut ledger, self.trace);
 
         if let Some(path) = &self.manifest {
@@ -49,11 +49,17 @@ impl NewAccount {
             .map_err(Error::IOError)?;
         } else {
             let (public_key, private_key, account) = executor.new_account();
+            append_transaction_log(
+                executor.substate_store_mut(),
+                &TransactionLogEntry::NewAccount { account },
+            );
             writeln!(out, "A new account has been created!").map_err(Error::IOError)?;
             writeln!(
                 out,
                 "Account component address: {}",
-                account.to_string().green()
+                get_address_encoder()
+                    .encode_component_address(&account)
+                    .green()
             )
             .map_err(Error::IOError)?;
             writeln!(out, "Public key: {}", public_key.to_string().green())
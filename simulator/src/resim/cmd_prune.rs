@@ -0,0 +1,102 @@
+use clap::Parser;
+use colored::*;
+use radix_engine::ledger::*;
+
+use crate::resim::*;
+
+/// Remove unreachable substates from the ledger state
+#[derive(Parser, Debug)]
+pub struct Prune {
+    /// Keep the data of burned non-fungibles
+    #[clap(long)]
+    keep_burned_non_fungibles: bool,
+
+    /// Keep unreachable lazy map entries
+    #[clap(long)]
+    keep_lazy_map_entries: bool,
+
+    /// Keep unreachable empty vaults
+    #[clap(long)]
+    keep_vaults: bool,
+
+    /// Keep emptied lockers
+    #[clap(long)]
+    keep_lockers: bool,
+
+    /// Only report what would be removed
+    #[clap(long)]
+    dry_run: bool,
+}
+
+impl Prune {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
+        let mut ledger = open_ledger()?;
+        let pruner = Pruner::new(PrunerConfig {
+            prune_burned_non_fungibles: !self.keep_burned_non_fungibles,
+            prune_unreachable_lazy_map_entries: !self.keep_lazy_map_entries,
+            prune_unreachable_vaults: !self.keep_vaults,
+            prune_emptied_lockers: !self.keep_lockers,
+            dry_run: self.dry_run,
+        });
+        let stats = pruner.prune(&mut ledger);
+
+        writeln!(
+            out,
+            "{}: {}",
+            "Components scanned".green().bold(),
+            stats.components_scanned
+        )
+        .map_err(Error::IOError)?;
+        writeln!(
+            out,
+            "{}: {}",
+            "Resource managers scanned".green().bold(),
+            stats.resource_managers_scanned
+        )
+        .map_err(Error::IOError)?;
+        writeln!(
+            out,
+            "{}: {}",
+            "Burned non-fungibles".green().bold(),
+            stats.burned_non_fungibles
+        )
+        .map_err(Error::IOError)?;
+        writeln!(
+            out,
+            "{}: {}",
+            "Unreachable lazy map entries".green().bold(),
+            stats.unreachable_lazy_map_entries
+        )
+        .map_err(Error::IOError)?;
+        writeln!(
+            out,
+            "{}: {} ({} non-empty retained)",
+            "Unreachable vaults".green().bold(),
+            stats.unreachable_vaults,
+            stats.retained_non_empty_vaults
+        )
+        .map_err(Error::IOError)?;
+        writeln!(
+            out,
+            "{}: {}",
+            "Emptied lockers".green().bold(),
+            stats.emptied_lockers
+        )
+        .map_err(Error::IOError)?;
+        writeln!(
+            out,
+            "{}: {} ({} bytes)",
+            if self.dry_run {
+                "Substates to prune"
+            } else {
+                "Substates pruned"
+            }
+            .green()
+            .bold(),
+            stats.substates_pruned,
+            stats.bytes_pruned
+        )
+        .map_err(Error::IOError)?;
+        Ok(())
+    }
+}
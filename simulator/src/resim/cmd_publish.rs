@@ -40,7 +40,7 @@ impl Publish {
         .map_err(Error::IOError)?;
 
         if let Some(path) = &self.manifest {
-            let mut ledger = RadixEngineDB::with_bootstrap(get_data_dir()?);
+            let mut ledger = open_ledger()?;
             let mut executor = TransactionExecutor::new(&mut ledger, self.trace);
             let transaction = TransactionBuilder::new()
                 .publish_package(code.as_ref())
@@ -65,22 +65,38 @@ impl Publish {
     }
 
     pub fn store_package<O: std::io::Write>(&self, out: &mut O, code: &[u8]) -> Result<(), Error> {
-        let mut ledger = RadixEngineDB::with_bootstrap(get_data_dir()?);
+        let mut ledger = open_ledger()?;
         let mut executor = TransactionExecutor::new(&mut ledger, self.trace);
         if let Some(package_address) = self.package_address.clone() {
             // Overwrite package
             executor
                 .overwrite_package(package_address, code.to_vec())
                 .map_err(|e| Error::PackageValidationError(e))?;
+            append_transaction_log(
+                executor.substate_store_mut(),
+                &TransactionLogEntry::OverwritePackage {
+                    package_address,
+                    code: code.to_vec(),
+                },
+            );
             writeln!(out, "Package updated!").map_err(Error::IOError)?;
             Ok(())
         } else {
             match executor.publish_package(code) {
                 Ok(package_address) => {
+                    append_transaction_log(
+                        executor.substate_store_mut(),
+                        &TransactionLogEntry::PublishPackage {
+                            code: code.to_vec(),
+                            package_address,
+                        },
+                    );
                     writeln!(
                         out,
                         "Success! New Package: {}",
-                        package_address.to_string().green()
+                        get_address_encoder()
+                            .encode_package_address(&package_address)
+                            .green()
                     )
                     .map_err(Error::IOError)?;
                     Ok(())
@@ -0,0 +1,237 @@
+use clap::Parser;
+use colored::*;
+use radix_engine::ledger::*;
+use radix_engine::model::*;
+use radix_engine::transaction::*;
+use scrypto::crypto::*;
+use std::fs::remove_dir_all;
+use std::str::FromStr;
+
+use crate::ledger::*;
+use crate::resim::*;
+
+/// Rebuild the ledger state from genesis by replaying the transaction log, verifying that every receipt matches
+#[derive(Parser, Debug)]
+pub struct Replay {
+    /// Stop after the log entry with this index, or the transaction with this hash
+    #[clap(long)]
+    to: Option<String>,
+
+    /// Only verify the transaction log, leaving the ledger state unchanged
+    #[clap(long)]
+    dry_run: bool,
+
+    /// Turn on tracing
+    #[clap(short, long)]
+    trace: bool,
+}
+
+impl Replay {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
+        let data_dir = get_data_dir()?;
+        let mut ledger = open_ledger()?;
+        let entries = read_transaction_log(&ledger);
+        let count = match &self.to {
+            Some(to) => find_log_entry(&entries, to)? + 1,
+            None => entries.len(),
+        };
+
+        // The state is rebuilt in a separate ledger, so that the current state is kept if the
+        // replay fails.
+        let replay_dir = data_dir.with_extension("replay");
+        if replay_dir.exists() {
+            remove_dir_all(&replay_dir).map_err(Error::IOError)?;
+        }
+        let result = self.replay(&entries[..count], &replay_dir, out);
+        if result.is_ok() && !self.dry_run {
+            let replayed = RadixEngineDB::new(replay_dir.clone()).list_entries(&[]);
+            ledger.replace_entries(replayed);
+        }
+        remove_dir_all(&replay_dir).map_err(Error::IOError)?;
+        result?;
+
+        if self.dry_run {
+            writeln!(out, "{} log entries verified.", count).map_err(Error::IOError)?;
+        } else {
+            writeln!(out, "Ledger state rebuilt from {} log entries.", count)
+                .map_err(Error::IOError)?;
+        }
+        Ok(())
+    }
+
+    fn replay<O: std::io::Write>(
+        &self,
+        entries: &[TransactionLogEntry],
+        replay_dir: &PathBuf,
+        out: &mut O,
+    ) -> Result<(), Error> {
+        let mut ledger = RadixEngineDB::with_bootstrap(replay_dir.clone());
+        for (index, entry) in entries.iter().enumerate() {
+            // Each entry was executed by a separate `resim` command, with a fresh executor
+            let mut executor = TransactionExecutor::new(&mut ledger, self.trace);
+            let matched = replay_log_entry(&mut executor, entry)?;
+            writeln!(
+                out,
+                "{} {} {}",
+                format!("[{}]", index).bold(),
+                describe_log_entry(entry),
+                if matched {
+                    "OK".green()
+                } else {
+                    "MISMATCH".red()
+                }
+            )
+            .map_err(Error::IOError)?;
+            if !matched {
+                return Err(Error::ReplayMismatch(index));
+            }
+            append_transaction_log(executor.substate_store_mut(), entry);
+        }
+        Ok(())
+    }
+}
+
+/// Replays a log entry, returning whether the outcome matches the recorded one.
+pub fn replay_log_entry<L: SubstateStore>(
+    executor: &mut TransactionExecutor<L>,
+    entry: &TransactionLogEntry,
+) -> Result<bool, Error> {
+    match entry {
+        TransactionLogEntry::Transaction {
+            transaction,
+            signatures,
+            receipt,
+        } => {
+            let signed = SignedTransaction {
+                transaction: transaction.clone(),
+                signatures: signatures.clone(),
+            };
+            let replayed = executor
+                .validate_and_execute(&signed)
+                .map_err(Error::TransactionValidationError)?;
+            Ok(receipt.matches(&ReceiptSummary::new(&replayed)))
+        }
+        TransactionLogEntry::NewAccount { account } => {
+            let (_, _, replayed) = executor.new_account();
+            Ok(replayed == *account)
+        }
+        TransactionLogEntry::PublishPackage {
+            code,
+            package_address,
+        } => Ok(executor.publish_package(code).ok() == Some(*package_address)),
+        TransactionLogEntry::OverwritePackage {
+            package_address,
+            code,
+        } => {
+            executor
+                .overwrite_package(*package_address, code.clone())
+                .map_err(Error::PackageValidationError)?;
+            Ok(true)
+        }
+        TransactionLogEntry::SetEpoch { epoch } => {
+            executor.substate_store_mut().set_epoch(*epoch);
+            Ok(true)
+        }
+    }
+}
+
+/// Finds a log entry by index, or a transaction by hash.
+fn find_log_entry(entries: &[TransactionLogEntry], to: &str) -> Result<usize, Error> {
+    let position = if let Ok(index) = to.parse::<usize>() {
+        Some(index).filter(|index| *index < entries.len())
+    } else if let Ok(hash) = Hash::from_str(to) {
+        entries.iter().position(|entry| match entry {
+            TransactionLogEntry::Transaction { transaction, .. } => transaction.raw_hash() == hash,
+            _ => false,
+        })
+    } else {
+        None
+    };
+    position.ok_or_else(|| Error::InvalidLogEntry(to.to_owned()))
+}
+
+fn describe_log_entry(entry: &TransactionLogEntry) -> String {
+    match entry {
+        TransactionLogEntry::Transaction { transaction, .. } => {
+            format!("Transaction {}", transaction.raw_hash())
+        }
+        TransactionLogEntry::NewAccount { account } => format!("NewAccount {}", account),
+        TransactionLogEntry::PublishPackage {
+            package_address, ..
+        } => format!("PublishPackage {}", package_address),
+        TransactionLogEntry::OverwritePackage {
+            package_address, ..
+        } => format!("OverwritePackage {}", package_address),
+        TransactionLogEntry::SetEpoch { epoch } => format!("SetEpoch {}", epoch),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scrypto::constants::*;
+    use scrypto::engine::types::*;
+
+    fn log_transaction<L: SubstateStore>(
+        executor: &mut TransactionExecutor<L>,
+        account: ComponentAddress,
+    ) {
+        let transaction = TransactionBuilder::new()
+            .call_method(SYSTEM_COMPONENT, "free_xrd", vec![])
+            .call_method_with_all_resources(account, "deposit_batch")
+            .build(executor.get_nonce([]))
+            .sign([]);
+        let receipt = executor.validate_and_execute(&transaction).unwrap();
+        append_transaction_log(
+            executor.substate_store_mut(),
+            &TransactionLogEntry::transaction(&transaction, &receipt),
+        );
+    }
+
+    #[test]
+    fn test_replay_reproduces_receipts() {
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut executor = TransactionExecutor::new(&mut substate_store, false);
+        let (_, _, account) = executor.new_account();
+        append_transaction_log(
+            executor.substate_store_mut(),
+            &TransactionLogEntry::NewAccount { account },
+        );
+        log_transaction(&mut executor, account);
+        let entries = read_transaction_log(&substate_store);
+
+        let mut replayed_store = InMemorySubstateStore::with_bootstrap();
+        let mut executor = TransactionExecutor::new(&mut replayed_store, false);
+        for entry in &entries {
+            assert!(replay_log_entry(&mut executor, entry).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_replay_detects_mismatch() {
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut executor = TransactionExecutor::new(&mut substate_store, false);
+        let (_, _, account) = executor.new_account();
+        log_transaction(&mut executor, account);
+        let entries = read_transaction_log(&substate_store);
+
+        // The account created before the transaction is missing from the log
+        let mut replayed_store = InMemorySubstateStore::with_bootstrap();
+        let mut executor = TransactionExecutor::new(&mut replayed_store, false);
+        assert!(!replay_log_entry(&mut executor, &entries[0]).unwrap());
+    }
+
+    #[test]
+    fn test_find_log_entry() {
+        let entries = vec![
+            TransactionLogEntry::SetEpoch { epoch: 1 },
+            TransactionLogEntry::SetEpoch { epoch: 2 },
+        ];
+
+        assert_eq!(find_log_entry(&entries, "1").unwrap(), 1);
+        assert!(matches!(
+            find_log_entry(&entries, "2"),
+            Err(Error::InvalidLogEntry(_))
+        ));
+    }
+}
@@ -30,12 +30,12 @@ impl Run {
     }
 
     pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
-        let mut ledger = RadixEngineDB::with_bootstrap(get_data_dir()?);
+        let mut ledger = open_ledger()?;
         let mut executor = TransactionExecutor::new(&mut ledger, self.trace);
         let manifest = std::fs::read_to_string(&self.path).map_err(Error::IOError)?;
         let pre_processed_manifest = Self::pre_process_manifest(&manifest);
-        let transaction =
-            transaction_manifest::compile(&pre_processed_manifest).map_err(Error::CompileError)?;
+        let transaction = transaction_manifest::compile(&pre_processed_manifest, &get_network())
+            .map_err(Error::CompileError)?;
         process_transaction(&mut executor, transaction, &self.signing_keys, &None, out)
     }
 }
@@ -0,0 +1,1092 @@
+use clap::Parser;
+use radix_engine::engine::{EntityAddress, NewEntity};
+use radix_engine::ledger::*;
+use radix_engine::model::*;
+use radix_engine::transaction::*;
+use scrypto::address::Bech32Encoder;
+use scrypto::crypto::*;
+use scrypto::engine::types::*;
+use scrypto::values::*;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::ledger::*;
+use crate::resim::*;
+
+/// The default port of `resim serve`.
+pub const DEFAULT_SERVE_PORT: u16 = 8080;
+
+/// The maximum size of a request body accepted by `resim serve`.
+const MAX_REQUEST_BODY_SIZE: usize = 1024 * 1024;
+
+/// The number of most recent receipts kept by `resim serve`.
+const MAX_RETAINED_RECEIPTS: usize = 1024;
+
+/// How long `resim serve` waits for a client to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The maximum size of the events queued for a subscriber which doesn't read them, beyond
+/// which it is disconnected.
+const MAX_PENDING_EVENT_BYTES: usize = 1024 * 1024;
+
+/// Serves a JSON API over the ledger state, so that front ends can integrate with the simulator
+#[derive(Parser, Debug)]
+pub struct Serve {
+    /// The port to listen on
+    #[clap(short, long, default_value_t = DEFAULT_SERVE_PORT)]
+    port: u16,
+
+    /// The origin allowed to make cross-origin requests, such as http://localhost:3000
+    #[clap(long)]
+    allowed_origin: Option<String>,
+
+    /// Sign transactions submitted without signing keys with the default account key
+    #[clap(long)]
+    sign_with_default_key: bool,
+
+    /// Turn on tracing
+    #[clap(short, long)]
+    trace: bool,
+}
+
+impl Serve {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
+        let mut ledger = open_ledger()?;
+        let config = ServerConfig {
+            allowed_origin: self.allowed_origin.clone(),
+            sign_with_default_key: self.sign_with_default_key,
+        };
+        let mut server = Server::new(TransactionExecutor::new(&mut ledger, self.trace), config);
+        let listener = TcpListener::bind(("127.0.0.1", self.port)).map_err(Error::IOError)?;
+        writeln!(out, "Listening on http://127.0.0.1:{}", self.port).map_err(Error::IOError)?;
+
+        for stream in listener.incoming() {
+            let result = stream.and_then(|stream| server.serve_connection(stream));
+            if let Err(e) = result {
+                writeln!(out, "Connection error: {}", e).map_err(Error::IOError)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An HTTP request, as far as `resim serve` understands it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+/// An error returned by an endpoint, with its HTTP status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    pub status: u16,
+    pub message: String,
+}
+
+impl ApiError {
+    fn bad_request<T: ToString>(message: T) -> Self {
+        Self {
+            status: 400,
+            message: message.to_string(),
+        }
+    }
+
+    fn not_found<T: ToString>(message: T) -> Self {
+        Self {
+            status: 404,
+            message: message.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SubmitRequest {
+    manifest: String,
+    #[serde(default)]
+    signing_keys: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PreviewRequest {
+    manifest: String,
+    #[serde(default)]
+    signers: Vec<String>,
+}
+
+/// The settings of `resim serve`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerConfig {
+    /// The origin allowed to make cross-origin requests, if any.
+    pub allowed_origin: Option<String>,
+    /// Whether transactions without signing keys are signed with the default account key, and
+    /// previewed with its public key.
+    ///
+    /// Off by default, as anyone who can reach the server could otherwise act as the default
+    /// account.
+    pub sign_with_default_key: bool,
+}
+
+/// Handles the requests of `resim serve`.
+///
+/// Endpoints:
+/// - `POST /transaction`, which signs, executes and commits a manifest;
+/// - `POST /preview`, which executes a manifest without committing;
+/// - `GET /components/<address>`, which returns the state of a component;
+/// - `GET /components/<address>/balances`, which returns the resources held by a component;
+/// - `GET /non_fungibles/<address>`, which returns the data and metadata of a non-fungible;
+/// - `GET /receipts?from=<index>`, which returns the receipts of the transactions committed
+///   through the server, starting from the given index;
+/// - `GET /subscribe?event=<names>&component=<addresses>`, which streams the matching events of
+///   the transactions committed through the server, as server-sent events, including the
+///   events emitted by components.
+pub struct Server<'l, L: SubstateStore + QueryableSubstateStore> {
+    executor: TransactionExecutor<'l, L>,
+    config: ServerConfig,
+    receipts: VecDeque<Value>,
+    first_receipt_index: usize,
+    subscribers: Vec<Subscriber>,
+}
+
+/// An event published to the subscribers of `resim serve`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerEvent {
+    /// The name of the event, which is the name given by the component for emitted events.
+    pub name: String,
+    /// The components the event relates to.
+    pub components: Vec<ComponentAddress>,
+    pub data: Value,
+}
+
+/// Selects the events sent to a subscriber. Empty lists match any event.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    pub names: Vec<String>,
+    pub components: Vec<ComponentAddress>,
+}
+
+impl EventFilter {
+    /// Parses a filter from the comma-separated `event` and `component` query parameters.
+    pub fn from_query(query: &HashMap<String, String>) -> Result<Self, ApiError> {
+        let split = |key: &str| -> Vec<String> {
+            query
+                .get(key)
+                .map(|value| {
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(str::to_owned)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let components = split("component")
+            .iter()
+            .map(|c| ComponentAddress::from_str(c).map_err(|_| ApiError::bad_request(c)))
+            .collect::<Result<Vec<ComponentAddress>, ApiError>>()?;
+
+        Ok(Self {
+            names: split("event"),
+            components,
+        })
+    }
+
+    pub fn matches(&self, event: &ServerEvent) -> bool {
+        (self.names.is_empty() || self.names.iter().any(|n| *n == event.name))
+            && (self.components.is_empty()
+                || self.components.iter().any(|c| event.components.contains(c)))
+    }
+}
+
+struct Subscriber {
+    writer: Box<dyn Write>,
+    filter: EventFilter,
+    /// The bytes not yet accepted by the connection.
+    pending: Vec<u8>,
+}
+
+impl Subscriber {
+    /// Writes as much of the pending bytes as the connection accepts without blocking.
+    ///
+    /// Returns whether the subscriber should be kept, which it isn't once disconnected or too
+    /// far behind.
+    fn flush(&mut self) -> bool {
+        while !self.pending.is_empty() {
+            match self.writer.write(&self.pending) {
+                Ok(0) => return false,
+                Ok(n) => {
+                    self.pending.drain(..n);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(_) => return false,
+            }
+        }
+        self.pending.len() <= MAX_PENDING_EVENT_BYTES
+    }
+}
+
+impl<'l, L: SubstateStore + QueryableSubstateStore> Server<'l, L> {
+    pub fn new(executor: TransactionExecutor<'l, L>, config: ServerConfig) -> Self {
+        Self {
+            executor,
+            config,
+            receipts: VecDeque::new(),
+            first_receipt_index: 0,
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Reads a single request from a connection and writes the response.
+    pub fn serve_connection(&mut self, stream: TcpStream) -> std::io::Result<()> {
+        // A client which never completes its request would otherwise block the server
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = stream;
+        let origin = self.config.allowed_origin.clone();
+        let origin = origin.as_deref();
+        match read_request(&mut reader) {
+            Ok(request) if request.method == "OPTIONS" => {
+                write_response(&mut writer, origin, 204, None)
+            }
+            Ok(request) if request.method == "GET" && request.path == "/subscribe" => {
+                match EventFilter::from_query(&request.query) {
+                    Ok(filter) => {
+                        // A subscriber which doesn't read its events would otherwise block the
+                        // server
+                        writer.set_nonblocking(true)?;
+                        self.subscribe(Box::new(writer), filter);
+                        Ok(())
+                    }
+                    Err(e) => write_response(
+                        &mut writer,
+                        origin,
+                        e.status,
+                        Some(&json!({ "error": e.message })),
+                    ),
+                }
+            }
+            Ok(request) => match self.handle(&request) {
+                Ok(body) => write_response(&mut writer, origin, 200, Some(&body)),
+                Err(e) => write_response(
+                    &mut writer,
+                    origin,
+                    e.status,
+                    Some(&json!({ "error": e.message })),
+                ),
+            },
+            Err(e) => write_response(&mut writer, origin, 400, Some(&json!({ "error": e }))),
+        }
+    }
+
+    /// Starts an event stream on a connection, which is kept open until writing to it fails.
+    ///
+    /// Writes never block: the bytes the connection doesn't accept are queued, and the
+    /// subscriber is dropped if it falls too far behind.
+    pub fn subscribe(&mut self, writer: Box<dyn Write>, filter: EventFilter) {
+        let header = format!(
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: text/event-stream\r\n\
+             Cache-Control: no-cache\r\n\
+             {}\
+             Connection: keep-alive\r\n\r\n",
+            cors_headers(self.config.allowed_origin.as_deref())
+        );
+        let mut subscriber = Subscriber {
+            writer,
+            filter,
+            pending: header.into_bytes(),
+        };
+        if subscriber.flush() {
+            self.subscribers.push(subscriber);
+        }
+    }
+
+    /// Queues events for the subscribers whose filter matches, and drops the subscribers which
+    /// are disconnected or too far behind.
+    pub fn publish(&mut self, events: &[ServerEvent]) {
+        self.subscribers.retain_mut(|subscriber| {
+            for event in events
+                .iter()
+                .filter(|event| subscriber.filter.matches(event))
+            {
+                write_event(&mut subscriber.pending, event);
+            }
+            subscriber.flush()
+        });
+    }
+
+    /// Routes a request to its endpoint.
+    pub fn handle(&mut self, request: &HttpRequest) -> Result<Value, ApiError> {
+        let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+        match (request.method.as_str(), segments.as_slice()) {
+            ("POST", ["transaction"]) => self.submit(&request.body),
+            ("POST", ["preview"]) => self.preview(&request.body),
+            ("GET", ["components", address]) => self.component(address),
+            ("GET", ["components", address, "balances"]) => self.balances(address),
+            ("GET", ["non_fungibles", address]) => self.non_fungible(address),
+            ("GET", ["receipts"]) => self.receipts(&request.query),
+            _ => Err(ApiError::not_found(format!(
+                "No endpoint for {} {}",
+                request.method, request.path
+            ))),
+        }
+    }
+
+    fn submit(&mut self, body: &[u8]) -> Result<Value, ApiError> {
+        let request: SubmitRequest = serde_json::from_slice(body).map_err(ApiError::bad_request)?;
+        let signing_keys = if !request.signing_keys.is_empty() {
+            Some(request.signing_keys.join(","))
+        } else if self.config.sign_with_default_key {
+            None
+        } else {
+            return Err(ApiError::bad_request(
+                "No signing keys, and signing with the default key is disabled",
+            ));
+        };
+        let sks = parse_signing_keys(&signing_keys)
+            .map_err(|e| ApiError::bad_request(format!("{:?}", e)))?;
+        let pks = sks
+            .iter()
+            .map(|e| e.public_key())
+            .collect::<Vec<EcdsaPublicKey>>();
+
+        let mut transaction = compile_manifest(&request.manifest)?;
+        let nonce = self.executor.get_nonce(&pks);
+        transaction.add_nonce(nonce);
+        let signed = transaction.sign(sks.iter().collect::<Vec<&EcdsaPrivateKey>>());
+        let receipt = self
+            .executor
+            .validate_and_execute(&signed)
+            .map_err(|e| ApiError::bad_request(format!("{:?}", e)))?;
+        append_transaction_log(
+            self.executor.substate_store_mut(),
+            &TransactionLogEntry::transaction(&signed, &receipt),
+        );
+
+        let receipt_json = receipt_to_json(&receipt, &self.encoder());
+        self.receipts.push_back(receipt_json.clone());
+        if self.receipts.len() > MAX_RETAINED_RECEIPTS {
+            self.receipts.pop_front();
+            self.first_receipt_index += 1;
+        }
+        self.publish(&receipt_events(&receipt, &receipt_json, &self.encoder()));
+        Ok(receipt_json)
+    }
+
+    fn preview(&mut self, body: &[u8]) -> Result<Value, ApiError> {
+        let request: PreviewRequest =
+            serde_json::from_slice(body).map_err(ApiError::bad_request)?;
+        let pks = if request.signers.is_empty() && self.config.sign_with_default_key {
+            vec![get_default_private_key()
+                .map_err(|e| ApiError::bad_request(format!("{:?}", e)))?
+                .public_key()]
+        } else {
+            request
+                .signers
+                .iter()
+                .map(|s| EcdsaPublicKey::from_str(s).map_err(ApiError::bad_request))
+                .collect::<Result<Vec<EcdsaPublicKey>, ApiError>>()?
+        };
+
+        let mut transaction = compile_manifest(&request.manifest)?;
+        let nonce = self.executor.get_nonce(&pks);
+        transaction.add_nonce(nonce);
+        let receipt = self
+            .executor
+            .preview(&transaction, pks)
+            .map_err(|e| ApiError::bad_request(format!("{:?}", e)))?;
+        Ok(receipt_to_json(&receipt, &self.encoder()))
+    }
+
+    fn component(&self, address: &str) -> Result<Value, ApiError> {
+        let component_address =
+            ComponentAddress::from_str(address).map_err(|_| ApiError::bad_request(address))?;
+        let component: Component = self
+            .executor
+            .substate_store()
+            .get_decoded_substate(&component_address)
+            .map(|(component, _)| component)
+            .ok_or_else(|| ApiError::not_found(format!("Component not found: {}", address)))?;
+
+        let encoder = self.encoder();
+        Ok(json!({
+            "address": encoder.encode_component_address(&component_address),
+            "package_address": encoder.encode_package_address(&component.package_address()),
+            "blueprint_name": component.blueprint_name(),
+            "state": data_to_string(component.state(), &encoder),
+        }))
+    }
+
+    fn balances(&self, address: &str) -> Result<Value, ApiError> {
+        let component_address =
+            ComponentAddress::from_str(address).map_err(|_| ApiError::bad_request(address))?;
+        let encoder = self.encoder();
+        let balances = self
+            .executor
+            .get_component_resources(component_address)
+            .into_iter()
+            .map(|(resource_address, amount)| {
+                (
+                    encoder.encode_resource_address(&resource_address),
+                    json!(amount.to_string()),
+                )
+            })
+            .collect::<serde_json::Map<String, Value>>();
+        Ok(Value::Object(balances))
+    }
+
+    fn non_fungible(&self, address: &str) -> Result<Value, ApiError> {
+        let non_fungible_address =
+            NonFungibleAddress::from_str(address).map_err(|_| ApiError::bad_request(address))?;
+        let non_fungible: NonFungible = self
+            .executor
+            .substate_store()
+            .get_decoded_child_substate::<_, _, Option<NonFungible>>(
+                &non_fungible_address.resource_address(),
+                &non_fungible_address.non_fungible_id(),
+            )
+            .and_then(|(non_fungible, _)| non_fungible)
+            .ok_or_else(|| ApiError::not_found(format!("Non-fungible not found: {}", address)))?;
+        let metadata = non_fungible
+            .metadata()
+            .iter()
+            .map(|(key, value)| (key.clone(), json!(value.to_string())))
+            .collect::<serde_json::Map<String, Value>>();
+
+        Ok(json!({
+            "address": non_fungible_address.to_string(),
+            "immutable_data": data_to_string(&non_fungible.immutable_data(), &self.encoder()),
+            "mutable_data": data_to_string(&non_fungible.mutable_data(), &self.encoder()),
+            "metadata": metadata,
+        }))
+    }
+
+    /// Returns the encoder of the addresses of the network the server executes transactions on.
+    fn encoder(&self) -> Bech32Encoder {
+        Bech32Encoder::new(self.executor.network().id)
+    }
+
+    fn receipts(&self, query: &HashMap<String, String>) -> Result<Value, ApiError> {
+        let from = match query.get("from") {
+            Some(from) => usize::from_str(from).map_err(ApiError::bad_request)?,
+            None => self.first_receipt_index,
+        };
+        let skip = from.saturating_sub(self.first_receipt_index);
+        let receipts = self
+            .receipts
+            .iter()
+            .skip(skip)
+            .cloned()
+            .collect::<Vec<Value>>();
+
+        Ok(json!({
+            "receipts": receipts,
+            "next": self.first_receipt_index + self.receipts.len(),
+        }))
+    }
+}
+
+fn compile_manifest(manifest: &str) -> Result<Transaction, ApiError> {
+    transaction_manifest::compile(&Run::pre_process_manifest(manifest), &get_network())
+        .map_err(|e| ApiError::bad_request(format!("{:?}", e)))
+}
+
+fn data_to_string(data: &[u8], encoder: &Bech32Encoder) -> String {
+    ScryptoValue::from_slice(data)
+        .map(|v| v.to_string_with_context(&HashMap::new(), &HashMap::new(), Some(encoder)))
+        .unwrap_or_else(|_| hex::encode(data))
+}
+
+fn entity_to_json(entity: &NewEntity, encoder: &Bech32Encoder) -> Value {
+    let (entity_type, address, owner) = match &entity.address {
+        EntityAddress::Package(address) => {
+            ("Package", encoder.encode_package_address(address), None)
+        }
+        EntityAddress::Component(address) => {
+            ("Component", encoder.encode_component_address(address), None)
+        }
+        EntityAddress::Resource(address) => {
+            ("Resource", encoder.encode_resource_address(address), None)
+        }
+        EntityAddress::Allowance(id) => ("Allowance", format!("{}{}", id.0, id.1), None),
+        EntityAddress::Vault(owner, id) => ("Vault", format!("{}{}", id.0, id.1), Some(owner)),
+        EntityAddress::Locker(owner, id) => ("Locker", format!("{}{}", id.0, id.1), Some(owner)),
+    };
+    json!({
+        "type": entity_type,
+        "address": address,
+        "owner": owner.map(|owner| encoder.encode_component_address(owner)),
+        "instruction_index": entity.instruction_index,
+    })
+}
+
+/// Converts a receipt into its JSON representation, with addresses encoded by the given encoder.
+pub fn receipt_to_json(receipt: &Receipt, encoder: &Bech32Encoder) -> Value {
+    let new_entities = receipt
+        .new_global_entities
+        .iter()
+        .chain(receipt.new_owned_entities.iter())
+        .map(|entity| entity_to_json(entity, encoder))
+        .collect::<Vec<Value>>();
+    let logs = receipt
+        .logs
+        .iter()
+        .map(|(level, message)| json!({ "level": format!("{:?}", level), "message": message }))
+        .collect::<Vec<Value>>();
+
+    json!({
+        "transaction_hash": receipt.validated_transaction.raw_hash.to_string(),
+        "intent_hash": receipt.transaction_hashes.as_ref().map(|h| h.intent_hash.to_string()),
+        "signed_intent_hash": receipt
+            .transaction_hashes
+            .as_ref()
+            .map(|h| h.signed_intent_hash.to_string()),
+        "payload_hash": receipt.transaction_hashes.as_ref().map(|h| h.payload_hash.to_string()),
+        "status": if receipt.result.is_ok() { "Success" } else { "Failure" },
+        "error": receipt.result.as_ref().err().map(|e| e.to_string()),
+        "outputs": receipt
+            .outputs
+            .iter()
+            .map(|o| o.to_string_with_context(&HashMap::new(), &HashMap::new(), Some(encoder)))
+            .collect::<Vec<String>>(),
+        "logs": logs,
+        "new_entities": new_entities,
+        "cost_units_consumed": receipt.cost_units_consumed,
+    })
+}
+
+/// Derives the events of a transaction: a `TransactionCommitted` event with the receipt, then a
+/// `MethodCalled` event per component method call of the manifest, an `EntityCreated` event per
+/// new entity, and the events emitted by components, under their own names.
+///
+/// Transactions which weren't committed have no events.
+pub fn receipt_events(
+    receipt: &Receipt,
+    receipt_json: &Value,
+    encoder: &Bech32Encoder,
+) -> Vec<ServerEvent> {
+    if receipt.commit_receipt.is_none() {
+        return Vec::new();
+    }
+    let transaction_hash = receipt.validated_transaction.raw_hash.to_string();
+
+    let mut events = Vec::new();
+    for (i, instruction) in receipt
+        .validated_transaction
+        .instructions
+        .iter()
+        .enumerate()
+    {
+        match instruction {
+            ValidatedInstruction::CallMethod {
+                component_address,
+                method,
+                ..
+            }
+            | ValidatedInstruction::CallMethodWithAllResources {
+                component_address,
+                method,
+                ..
+            } => events.push(ServerEvent {
+                name: "MethodCalled".to_string(),
+                components: vec![*component_address],
+                data: json!({
+                    "transaction_hash": transaction_hash,
+                    "component": encoder.encode_component_address(component_address),
+                    "method": method,
+                    "instruction_index": i,
+                }),
+            }),
+            _ => {}
+        }
+    }
+    for entity in receipt
+        .new_global_entities
+        .iter()
+        .chain(receipt.new_owned_entities.iter())
+    {
+        let mut data = entity_to_json(entity, encoder);
+        data["transaction_hash"] = json!(transaction_hash);
+        events.push(ServerEvent {
+            name: "EntityCreated".to_string(),
+            components: match entity.address {
+                EntityAddress::Component(component_address)
+                | EntityAddress::Vault(component_address, _)
+                | EntityAddress::Locker(component_address, _) => vec![component_address],
+                _ => Vec::new(),
+            },
+            data,
+        });
+    }
+    for event in &receipt.events {
+        events.push(ServerEvent {
+            name: event.event.clone(),
+            components: vec![event.emitter],
+            data: json!({
+                "transaction_hash": transaction_hash,
+                "component": encoder.encode_component_address(&event.emitter),
+                "event": event.event,
+                "data": data_to_string(&event.data, encoder),
+            }),
+        });
+    }
+
+    let mut components = Vec::new();
+    for component_address in events.iter().flat_map(|e| e.components.iter()) {
+        if !components.contains(component_address) {
+            components.push(*component_address);
+        }
+    }
+    events.insert(
+        0,
+        ServerEvent {
+            name: "TransactionCommitted".to_string(),
+            components,
+            data: receipt_json.clone(),
+        },
+    );
+    events
+}
+
+/// Appends an event in the server-sent events format.
+pub fn write_event(buffer: &mut Vec<u8>, event: &ServerEvent) {
+    // Line breaks would end the event name early
+    let name = event.name.replace(['\r', '\n'], " ");
+    buffer.extend(format!("event: {}\ndata: {}\n\n", name, event.data).into_bytes());
+}
+
+/// Reads an HTTP/1.1 request.
+pub fn read_request<R: BufRead>(reader: &mut R) -> Result<HttpRequest, String> {
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(|e| e.to_string())?;
+    let mut parts = line.split_whitespace();
+    let (method, target) = match (parts.next(), parts.next()) {
+        (Some(method), Some(target)) => (method.to_string(), target.to_string()),
+        _ => return Err(format!("Invalid request line: {}", line.trim())),
+    };
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).map_err(|e| e.to_string())? == 0 {
+            break;
+        }
+        let header = header.trim();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = usize::from_str(value.trim()).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    if content_length > MAX_REQUEST_BODY_SIZE {
+        return Err(format!("Request body too large: {} bytes", content_length));
+    }
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).map_err(|e| e.to_string())?;
+
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), parse_query(query)),
+        None => (target, HashMap::new()),
+    };
+    Ok(HttpRequest {
+        method,
+        path,
+        query,
+        body,
+    })
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => (key.to_string(), value.to_string()),
+            None => (pair.to_string(), String::new()),
+        })
+        .collect()
+}
+
+/// Returns the headers which allow cross-origin requests from the given origin, so that the API
+/// can be called from a front end served elsewhere.
+fn cors_headers(allowed_origin: Option<&str>) -> String {
+    match allowed_origin {
+        Some(origin) => format!(
+            "Access-Control-Allow-Origin: {}\r\n\
+             Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+             Access-Control-Allow-Headers: Content-Type\r\n\
+             Vary: Origin\r\n",
+            origin
+        ),
+        None => String::new(),
+    }
+}
+
+/// Writes an HTTP/1.1 response with an optional JSON body, allowing cross-origin requests from
+/// the given origin only.
+pub fn write_response<W: Write>(
+    writer: &mut W,
+    allowed_origin: Option<&str>,
+    status: u16,
+    body: Option<&Value>,
+) -> std::io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        _ => "Internal Server Error",
+    };
+    let body = body.map(|b| b.to_string()).unwrap_or_default();
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         {}\
+         Connection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        cors_headers(allowed_origin),
+        body
+    )?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scrypto::constants::{RADIX_TOKEN, SYSTEM_COMPONENT};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// A writer which accepts a limited number of bytes, then would block.
+    #[derive(Clone, Default)]
+    struct ThrottledBuffer(Rc<RefCell<(Vec<u8>, usize)>>);
+
+    impl Write for ThrottledBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let mut inner = self.0.borrow_mut();
+            let (written, capacity) = &mut *inner;
+            if *capacity == 0 {
+                return Err(ErrorKind::WouldBlock.into());
+            }
+            let n = buf.len().min(*capacity);
+            written.extend_from_slice(&buf[..n]);
+            *capacity -= n;
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct DisconnectedWriter;
+
+    impl Write for DisconnectedWriter {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn get(path: &str) -> HttpRequest {
+        read_request(&mut format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).as_bytes())
+            .unwrap()
+    }
+
+    fn post(path: &str, body: Value) -> HttpRequest {
+        let body = body.to_string();
+        read_request(
+            &mut format!(
+                "POST {} HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+                path,
+                body.len(),
+                body
+            )
+            .as_bytes(),
+        )
+        .unwrap()
+    }
+
+    fn free_xrd_manifest(account: ComponentAddress) -> String {
+        format!(
+            "CALL_METHOD ComponentAddress(\"{}\") \"free_xrd\";\n\
+             CALL_METHOD_WITH_ALL_RESOURCES ComponentAddress(\"{}\") \"deposit_batch\";",
+            SYSTEM_COMPONENT, account
+        )
+    }
+
+    #[test]
+    fn test_read_request() {
+        let raw = "POST /preview?x=1&y HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}";
+
+        let request = read_request(&mut raw.as_bytes()).unwrap();
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/preview");
+        assert_eq!(request.query.get("x"), Some(&"1".to_string()));
+        assert_eq!(request.query.get("y"), Some(&"".to_string()));
+        assert_eq!(request.body, b"{}".to_vec());
+    }
+
+    #[test]
+    fn test_unknown_endpoint() {
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut server = Server::new(
+            TransactionExecutor::new(&mut substate_store, false),
+            ServerConfig::default(),
+        );
+
+        let result = server.handle(&get("/unknown"));
+
+        assert_eq!(result.unwrap_err().status, 404);
+    }
+
+    #[test]
+    fn test_balances() {
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut executor = TransactionExecutor::new(&mut substate_store, false);
+        let (_, _, account) = executor.new_account();
+        let mut server = Server::new(executor, ServerConfig::default());
+
+        let balances = server
+            .handle(&get(&format!("/components/{}/balances", account)))
+            .unwrap();
+
+        assert!(balances
+            .get(Bech32Encoder::for_simulator().encode_resource_address(&RADIX_TOKEN))
+            .is_some());
+    }
+
+    #[test]
+    fn test_unknown_non_fungible() {
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut server = Server::new(
+            TransactionExecutor::new(&mut substate_store, false),
+            ServerConfig::default(),
+        );
+        let address = NonFungibleAddress::new(ECDSA_TOKEN, NonFungibleId::from_u32(1));
+
+        let result = server.handle(&get(&format!("/non_fungibles/{}", address)));
+
+        assert_eq!(result.unwrap_err().status, 404);
+    }
+
+    #[test]
+    fn test_receipts_are_empty_without_transactions() {
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut server = Server::new(
+            TransactionExecutor::new(&mut substate_store, false),
+            ServerConfig::default(),
+        );
+
+        let receipts = server.handle(&get("/receipts?from=0")).unwrap();
+
+        assert_eq!(receipts, json!({ "receipts": [], "next": 0 }));
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let component_address = ComponentAddress([1; 26]);
+        let query = HashMap::from([
+            (
+                "event".to_string(),
+                "MethodCalled, EntityCreated".to_string(),
+            ),
+            ("component".to_string(), component_address.to_string()),
+        ]);
+        let event = |name: &str, components| ServerEvent {
+            name: name.to_string(),
+            components,
+            data: Value::Null,
+        };
+
+        let filter = EventFilter::from_query(&query).unwrap();
+
+        assert!(filter.matches(&event("MethodCalled", vec![component_address])));
+        assert!(!filter.matches(&event("TransactionCommitted", vec![component_address])));
+        assert!(!filter.matches(&event("MethodCalled", vec![ComponentAddress([2; 26])])));
+        assert!(EventFilter::default().matches(&event("TransactionCommitted", Vec::new())));
+    }
+
+    #[test]
+    fn test_publish_to_matching_subscribers() {
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut server = Server::new(
+            TransactionExecutor::new(&mut substate_store, false),
+            ServerConfig::default(),
+        );
+        let all = SharedBuffer::default();
+        let created = SharedBuffer::default();
+        server.subscribe(Box::new(all.clone()), EventFilter::default());
+        server.subscribe(
+            Box::new(created.clone()),
+            EventFilter {
+                names: vec!["EntityCreated".to_string()],
+                components: Vec::new(),
+            },
+        );
+        all.0.borrow_mut().clear();
+        created.0.borrow_mut().clear();
+
+        server.publish(&[ServerEvent {
+            name: "MethodCalled".to_string(),
+            components: Vec::new(),
+            data: json!({ "method": "deposit" }),
+        }]);
+
+        assert_eq!(
+            String::from_utf8(all.0.borrow().clone()).unwrap(),
+            "event: MethodCalled\ndata: {\"method\":\"deposit\"}\n\n"
+        );
+        assert!(created.0.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_publish_to_slow_subscriber_does_not_block() {
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut server = Server::new(
+            TransactionExecutor::new(&mut substate_store, false),
+            ServerConfig::default(),
+        );
+        let slow = ThrottledBuffer::default();
+        server.subscribe(Box::new(slow.clone()), EventFilter::default());
+        let event = ServerEvent {
+            name: "Ping".to_string(),
+            components: Vec::new(),
+            data: json!(1),
+        };
+
+        server.publish(&[event.clone(), event.clone()]);
+        slow.0.borrow_mut().1 = usize::MAX;
+        server.publish(&[]);
+
+        let written = String::from_utf8(slow.0.borrow().0.clone()).unwrap();
+        assert!(written.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(written.ends_with("\r\n\r\nevent: Ping\ndata: 1\n\nevent: Ping\ndata: 1\n\n"));
+        assert_eq!(server.subscribers.len(), 1);
+    }
+
+    #[test]
+    fn test_disconnected_and_lagging_subscribers_are_dropped() {
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut server = Server::new(
+            TransactionExecutor::new(&mut substate_store, false),
+            ServerConfig::default(),
+        );
+        let slow = ThrottledBuffer::default();
+        server.subscribe(Box::new(slow.clone()), EventFilter::default());
+        server.subscribe(Box::new(DisconnectedWriter), EventFilter::default());
+        let event = ServerEvent {
+            name: "Ping".to_string(),
+            components: Vec::new(),
+            data: json!("x".repeat(MAX_PENDING_EVENT_BYTES)),
+        };
+
+        server.publish(&[event]);
+
+        assert!(server.subscribers.is_empty());
+    }
+
+    #[test]
+    fn test_submit() {
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut executor = TransactionExecutor::new(&mut substate_store, false);
+        let (_, sk, account) = executor.new_account();
+        let mut server = Server::new(executor, ServerConfig::default());
+
+        let receipt = server
+            .handle(&post(
+                "/transaction",
+                json!({
+                    "manifest": free_xrd_manifest(account),
+                    "signing_keys": [hex::encode(sk.to_bytes())],
+                }),
+            ))
+            .unwrap();
+
+        assert_eq!(receipt["status"], "Success");
+        let receipts = server.handle(&get("/receipts")).unwrap();
+        assert_eq!(receipts["receipts"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_submit_without_signing_keys_is_rejected() {
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut executor = TransactionExecutor::new(&mut substate_store, false);
+        let (_, _, account) = executor.new_account();
+        let mut server = Server::new(executor, ServerConfig::default());
+
+        let result = server.handle(&post(
+            "/transaction",
+            json!({ "manifest": free_xrd_manifest(account) }),
+        ));
+
+        assert_eq!(result.unwrap_err().status, 400);
+    }
+
+    #[test]
+    fn test_preview() {
+        let mut substate_store = InMemorySubstateStore::with_bootstrap();
+        let mut executor = TransactionExecutor::new(&mut substate_store, false);
+        let (_, _, account) = executor.new_account();
+        let mut server = Server::new(executor, ServerConfig::default());
+
+        let receipt = server
+            .handle(&post(
+                "/preview",
+                json!({ "manifest": free_xrd_manifest(account) }),
+            ))
+            .unwrap();
+
+        assert_eq!(receipt["status"], "Success");
+        let receipts = server.handle(&get("/receipts")).unwrap();
+        assert_eq!(receipts, json!({ "receipts": [], "next": 0 }));
+    }
+
+    #[test]
+    fn test_cors_is_restricted_to_allowed_origin() {
+        let mut response = Vec::new();
+        write_response(&mut response, None, 200, None).unwrap();
+        assert!(!String::from_utf8(response)
+            .unwrap()
+            .contains("Access-Control-Allow-Origin"));
+
+        let mut response = Vec::new();
+        write_response(&mut response, Some("http://localhost:3000"), 200, None).unwrap();
+        assert!(String::from_utf8(response)
+            .unwrap()
+            .contains("Access-Control-Allow-Origin: http://localhost:3000\r\n"));
+    }
+}
@@ -1,6 +1,7 @@
 use clap::Parser;
 use radix_engine::ledger::SubstateStore;
 
+use crate::ledger::*;
 use crate::resim::*;
 
 /// Set the current epoch
@@ -12,8 +13,12 @@ pub struct SetCurrentEpoch {
 
 impl SetCurrentEpoch {
     pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
-        let mut ledger = RadixEngineDB::with_bootstrap(get_data_dir()?);
+        let mut ledger = open_ledger()?;
         ledger.set_epoch(self.epoch);
+        append_transaction_log(
+            &mut ledger,
+            &TransactionLogEntry::SetEpoch { epoch: self.epoch },
+        );
 
         writeln!(out, "Current epoch set!").map_err(Error::IOError)?;
         Ok(())
@@ -14,12 +14,14 @@ pub struct Show {
 
 impl Show {
     pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
-        let ledger = RadixEngineDB::with_bootstrap(get_data_dir()?);
+        let ledger = open_ledger()?;
+        let encoder = get_address_encoder();
 
         if let Ok(package_address) = PackageAddress::from_str(&self.address) {
-            dump_package(package_address, &ledger, out).map_err(Error::LedgerDumpError)
+            dump_package(package_address, &ledger, &encoder, out).map_err(Error::LedgerDumpError)
         } else if let Ok(component_address) = ComponentAddress::from_str(&self.address) {
-            dump_component(component_address, &ledger, out).map_err(Error::LedgerDumpError)
+            dump_component(component_address, &ledger, &encoder, out)
+                .map_err(Error::LedgerDumpError)
         } else if let Ok(resource_address) = ResourceAddress::from_str(&self.address) {
             dump_resource_manager(resource_address, &ledger, out).map_err(Error::LedgerDumpError)
         } else {
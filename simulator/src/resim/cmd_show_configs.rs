@@ -1,5 +1,6 @@
 use clap::Parser;
 use colored::*;
+use radix_engine::ledger::SubstateStore;
 
 use crate::resim::*;
 
@@ -36,6 +37,17 @@ impl ShowConfigs {
         } else {
             writeln!(out, "No configuration found").map_err(Error::IOError)?;
         }
+
+        let ledger = open_ledger()?;
+        writeln!(
+            out,
+            "{}: {}",
+            "Current Epoch".green().bold(),
+            ledger.get_epoch()
+        )
+        .map_err(Error::IOError)?;
+        writeln!(out, "{}: {}", "Nonce".green().bold(), ledger.get_nonce())
+            .map_err(Error::IOError)?;
         Ok(())
     }
 }
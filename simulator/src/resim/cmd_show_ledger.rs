@@ -1,7 +1,6 @@
 use clap::Parser;
 use colored::*;
 
-use crate::ledger::*;
 use crate::resim::*;
 use crate::utils::*;
 
@@ -11,24 +10,40 @@ pub struct ShowLedger {}
 
 impl ShowLedger {
     pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
-        let ledger = RadixEngineDB::with_bootstrap(get_data_dir()?);
+        let ledger = open_ledger()?;
+        let encoder = get_address_encoder();
 
         writeln!(out, "{}:", "Packages".green().bold()).map_err(Error::IOError)?;
         for (last, package_address) in ledger.list_packages().iter().identify_last() {
-            writeln!(out, "{} {}", list_item_prefix(last), package_address)
-                .map_err(Error::IOError)?;
+            writeln!(
+                out,
+                "{} {}",
+                list_item_prefix(last),
+                encoder.encode_package_address(package_address)
+            )
+            .map_err(Error::IOError)?;
         }
 
         writeln!(out, "{}:", "Components".green().bold()).map_err(Error::IOError)?;
         for (last, component_address) in ledger.list_components().iter().identify_last() {
-            writeln!(out, "{} {}", list_item_prefix(last), component_address)
-                .map_err(Error::IOError)?;
+            writeln!(
+                out,
+                "{} {}",
+                list_item_prefix(last),
+                encoder.encode_component_address(component_address)
+            )
+            .map_err(Error::IOError)?;
         }
 
         writeln!(out, "{}:", "Resource Managers".green().bold()).map_err(Error::IOError)?;
         for (last, resource_address) in ledger.list_resource_managers().iter().identify_last() {
-            writeln!(out, "{} {}", list_item_prefix(last), resource_address)
-                .map_err(Error::IOError)?;
+            writeln!(
+                out,
+                "{} {}",
+                list_item_prefix(last),
+                encoder.encode_resource_address(resource_address)
+            )
+            .map_err(Error::IOError)?;
         }
 
         writeln!(out, "{}: {}", "Nonce".green().bold(), ledger.get_nonce())
@@ -0,0 +1,119 @@
+use clap::Parser;
+use colored::*;
+use radix_engine::model::*;
+use radix_engine::transaction::*;
+use scrypto::crypto::*;
+use std::path::PathBuf;
+
+use crate::resim::*;
+
+/// Sign a transaction manifest or a hex-encoded payload, producing detached signatures
+#[derive(Parser, Debug)]
+pub struct Sign {
+    /// The path to a transaction manifest file, or a hex-encoded payload
+    input: String,
+
+    /// The private keys used for signing, separated by comma
+    #[clap(short, long)]
+    signing_keys: Option<String>,
+
+    /// The transaction nonce; if not provided, the nonce of the local ledger is used
+    #[clap(short, long)]
+    nonce: Option<u64>,
+}
+
+impl Sign {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
+        let sks = parse_signing_keys(&self.signing_keys)?;
+        let pks = sks
+            .iter()
+            .map(|sk| sk.public_key())
+            .collect::<Vec<EcdsaPublicKey>>();
+
+        let path = PathBuf::from(&self.input);
+        let payload = if path.is_file() {
+            let manifest = std::fs::read_to_string(&path).map_err(Error::IOError)?;
+            let mut transaction = transaction_manifest::compile(
+                &Run::pre_process_manifest(&manifest),
+                &get_network(),
+            )
+            .map_err(Error::CompileError)?;
+            let nonce = match self.nonce {
+                Some(nonce) => nonce,
+                None => {
+                    let mut ledger = open_ledger()?;
+                    TransactionExecutor::new(&mut ledger, false).get_nonce(&pks)
+                }
+            };
+            transaction.add_nonce(nonce);
+            writeln!(
+                out,
+                "{} {}",
+                "Transaction Hash:".bold().green(),
+                transaction.raw_hash()
+            )
+            .map_err(Error::IOError)?;
+            transaction.to_vec()
+        } else {
+            hex::decode(&self.input).map_err(|_| Error::InvalidPayload(self.input.clone()))?
+        };
+
+        writeln!(
+            out,
+            "{} {}",
+            "Payload:".bold().green(),
+            hex::encode(&payload)
+        )
+        .map_err(Error::IOError)?;
+        for (pk, sig) in sign_payload(&payload, &sks) {
+            writeln!(
+                out,
+                "{} {} {}",
+                "Signature:".bold().green(),
+                pk,
+                hex::encode(sig.to_vec())
+            )
+            .map_err(Error::IOError)?;
+        }
+        Ok(())
+    }
+}
+
+/// Signs a payload with each of the given keys.
+///
+/// For a transaction, the payload is `Transaction::to_vec()` and the signatures can be used as
+/// `SignedTransaction::signatures`.
+pub fn sign_payload(
+    payload: &[u8],
+    sks: &[EcdsaPrivateKey],
+) -> Vec<(EcdsaPublicKey, EcdsaSignature)> {
+    sks.iter()
+        .map(|sk| (sk.public_key(), sk.sign(payload)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detached_signatures_are_valid_for_signed_transaction() {
+        let sk = EcdsaPrivateKey::from_bytes(&[1u8; 32]).unwrap();
+        let pk = sk.public_key();
+        let mut transaction = transaction_manifest::compile(
+            r#"CALL_METHOD ComponentAddress("020000000000000000000000000000000000000000000000000002") "free_xrd";"#,
+            &get_network(),
+        )
+        .unwrap();
+        transaction.add_nonce(5);
+
+        let signatures = sign_payload(&transaction.to_vec(), &[sk]);
+        let signed = SignedTransaction {
+            transaction,
+            signatures,
+        };
+
+        let validated = signed.validate().unwrap();
+        assert_eq!(validated.signers, vec![pk]);
+    }
+}
@@ -0,0 +1,71 @@
+use clap::Parser;
+use colored::*;
+use radix_engine::ledger::*;
+use scrypto::engine::types::*;
+use std::thread;
+use std::time::Duration;
+
+use crate::ledger::*;
+use crate::resim::*;
+
+/// The default polling interval of `resim watch`, in milliseconds.
+pub const DEFAULT_WATCH_INTERVAL_MS: u64 = 500;
+
+/// Watch the state of a component, printing it whenever the ledger changes
+#[derive(Parser, Debug)]
+pub struct Watch {
+    /// The address of the component to watch
+    component_address: ComponentAddress,
+
+    /// The polling interval, in milliseconds
+    #[clap(short, long, default_value_t = DEFAULT_WATCH_INTERVAL_MS)]
+    interval: u64,
+
+    /// Print the state on every interval, even if the ledger has not changed
+    #[clap(short, long)]
+    always: bool,
+
+    /// Exit after printing the state this many times
+    #[clap(short, long)]
+    count: Option<usize>,
+}
+
+impl Watch {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), Error> {
+        let data_dir = get_data_dir()?;
+        let encoder = get_address_encoder();
+        let mut last_nonce = None;
+        let mut updates = 0;
+
+        loop {
+            // The ledger is reopened on every poll, as a read-only instance only sees the
+            // state at the time it was opened. It doesn't lock the ledger, so that other `resim`
+            // commands can run at the same time.
+            let ledger =
+                RadixEngineDB::new_read_only(data_dir.clone()).map_err(Error::LedgerOpenError)?;
+            let nonce = ledger.get_nonce();
+            if self.always || last_nonce != Some(nonce) {
+                writeln!(
+                    out,
+                    "{} {}",
+                    "Ledger Nonce:".bold().green(),
+                    nonce.to_string()
+                )
+                .map_err(Error::IOError)?;
+                dump_component(self.component_address, &ledger, &encoder, out)
+                    .map_err(Error::LedgerDumpError)?;
+                writeln!(out).map_err(Error::IOError)?;
+                out.flush().map_err(Error::IOError)?;
+
+                last_nonce = Some(nonce);
+                updates += 1;
+                if self.count.map_or(false, |count| updates >= count) {
+                    return Ok(());
+                }
+            }
+            drop(ledger);
+
+            thread::sleep(Duration::from_millis(self.interval));
+        }
+    }
+}
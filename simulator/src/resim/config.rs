@@ -1,10 +1,15 @@
 use std::fs;
 use std::path::PathBuf;
+use std::str::FromStr;
 
+use radix_engine::ledger::SubstateStore;
 use sbor::*;
+use scrypto::address::Bech32Encoder;
 use scrypto::buffer::*;
+use scrypto::core::NetworkDefinition;
 use scrypto::engine::types::*;
 
+use crate::ledger::*;
 use crate::resim::*;
 use std::env;
 
@@ -15,6 +20,17 @@ pub struct Configs {
     pub default_private_key: Vec<u8>,
 }
 
+/// Returns the network of the simulator ledger, which transactions are bound to and addresses
+/// are encoded for.
+pub fn get_network() -> NetworkDefinition {
+    NetworkDefinition::simulator()
+}
+
+/// Returns the encoder of the addresses printed by the simulator.
+pub fn get_address_encoder() -> Bech32Encoder {
+    Bech32Encoder::new(get_network().id)
+}
+
 /// Returns the data directory.
 pub fn get_data_dir() -> Result<PathBuf, Error> {
     let path = match env::var(ENV_DATA_DIR) {
@@ -31,6 +47,22 @@ pub fn get_data_dir() -> Result<PathBuf, Error> {
     Ok(path)
 }
 
+/// Returns the compression of the ledger, which is set with the `LEDGER_COMPRESSION`
+/// environment variable to `none`, `lz4` or `zstd`.
+pub fn get_ledger_compression() -> Result<LedgerCompression, Error> {
+    match env::var(ENV_LEDGER_COMPRESSION) {
+        Ok(value) => LedgerCompression::from_str(&value).map_err(Error::InvalidLedgerCompression),
+        Err(..) => Ok(LedgerCompression::default()),
+    }
+}
+
+/// Opens the ledger in the data directory, bootstrapping it if it's new.
+pub fn open_ledger() -> Result<RadixEngineDB, Error> {
+    let mut ledger = RadixEngineDB::with_compression(get_data_dir()?, get_ledger_compression()?);
+    ledger.bootstrap();
+    Ok(ledger)
+}
+
 /// Returns the config file.
 pub fn get_config_file() -> Result<PathBuf, Error> {
     let mut path = get_data_dir()?;
@@ -36,6 +36,8 @@ pub enum Error {
 
     LedgerDumpError(DisplayError),
 
+    LedgerOpenError(rocksdb::Error),
+
     CompileError(transaction_manifest::CompileError),
 
     DecompileError(transaction_manifest::DecompileError),
@@ -43,4 +45,14 @@ pub enum Error {
     InvalidId(String),
 
     InvalidPrivateKey,
+
+    InvalidPayload(String),
+
+    InvalidSnapshot(String),
+
+    InvalidLogEntry(String),
+
+    InvalidLedgerCompression(String),
+
+    ReplayMismatch(usize),
 }
@@ -1,6 +1,9 @@
 mod cmd_call_function;
 mod cmd_call_method;
+mod cmd_diff;
+mod cmd_dump_state;
 mod cmd_export_abi;
+mod cmd_export_types;
 mod cmd_generate_key_pair;
 mod cmd_mint;
 mod cmd_new_account;
@@ -8,21 +11,29 @@ mod cmd_new_badge_fixed;
 mod cmd_new_badge_mutable;
 mod cmd_new_token_fixed;
 mod cmd_new_token_mutable;
+mod cmd_prune;
 mod cmd_publish;
+mod cmd_replay;
 mod cmd_reset;
 mod cmd_run;
+mod cmd_serve;
 mod cmd_set_current_epoch;
 mod cmd_set_default_account;
 mod cmd_show;
+mod cmd_sign;
 mod cmd_show_configs;
 mod cmd_show_ledger;
 mod cmd_transfer;
+mod cmd_watch;
 mod config;
 mod error;
 
 pub use cmd_call_function::*;
 pub use cmd_call_method::*;
+pub use cmd_diff::*;
+pub use cmd_dump_state::*;
 pub use cmd_export_abi::*;
+pub use cmd_export_types::*;
 pub use cmd_generate_key_pair::*;
 pub use cmd_mint::*;
 pub use cmd_new_account::*;
@@ -30,26 +41,33 @@ pub use cmd_new_badge_fixed::*;
 pub use cmd_new_badge_mutable::*;
 pub use cmd_new_token_fixed::*;
 pub use cmd_new_token_mutable::*;
+pub use cmd_prune::*;
 pub use cmd_publish::*;
+pub use cmd_replay::*;
 pub use cmd_reset::*;
 pub use cmd_run::*;
+pub use cmd_serve::*;
 pub use cmd_set_current_epoch::*;
 pub use cmd_set_default_account::*;
 pub use cmd_show::*;
+pub use cmd_sign::*;
 pub use cmd_show_configs::*;
 pub use cmd_show_ledger::*;
 pub use cmd_transfer::*;
+pub use cmd_watch::*;
 pub use config::*;
 pub use error::*;
 
 pub const DEFAULT_SCRYPTO_DIR_UNDER_HOME: &'static str = ".scrypto";
 pub const ENV_DATA_DIR: &'static str = "DATA_DIR";
 pub const ENV_DISABLE_MANIFEST_OUTPUT: &'static str = "DISABLE_MANIFEST_OUTPUT";
+pub const ENV_LEDGER_COMPRESSION: &'static str = "LEDGER_COMPRESSION";
 
 use clap::{Parser, Subcommand};
 use radix_engine::ledger::*;
 use radix_engine::model::*;
 use radix_engine::transaction::*;
+use scrypto::address::Bech32Encoder;
 use scrypto::crypto::*;
 use std::env;
 use std::fs;
@@ -76,7 +94,11 @@ impl ResimCli {
 pub enum Command {
     CallFunction(CallFunction),
     CallMethod(CallMethod),
+    Diff(Diff),
+    DumpState(DumpState),
     ExportAbi(ExportAbi),
+    ExportTypes(ExportTypes),
+    #[clap(alias = "keygen")]
     GenerateKeyPair(GenerateKeyPair),
     Mint(Mint),
     NewAccount(NewAccount),
@@ -84,15 +106,20 @@ pub enum Command {
     NewBadgeMutable(NewBadgeMutable),
     NewTokenFixed(NewTokenFixed),
     NewTokenMutable(NewTokenMutable),
+    Prune(Prune),
     Publish(Publish),
+    Replay(Replay),
     Reset(Reset),
     Run(Run),
+    Serve(Serve),
     SetCurrentEpoch(SetCurrentEpoch),
     SetDefaultAccount(SetDefaultAccount),
     ShowConfigs(ShowConfigs),
     ShowLedger(ShowLedger),
     Show(Show),
+    Sign(Sign),
     Transfer(Transfer),
+    Watch(Watch),
 }
 
 pub fn run() -> Result<(), Error> {
@@ -103,7 +130,10 @@ pub fn run() -> Result<(), Error> {
     match cli.command {
         Command::CallFunction(cmd) => cmd.run(&mut out),
         Command::CallMethod(cmd) => cmd.run(&mut out),
+        Command::Diff(cmd) => cmd.run(&mut out),
+        Command::DumpState(cmd) => cmd.run(&mut out),
         Command::ExportAbi(cmd) => cmd.run(&mut out),
+        Command::ExportTypes(cmd) => cmd.run(&mut out),
         Command::GenerateKeyPair(cmd) => cmd.run(&mut out),
         Command::Mint(cmd) => cmd.run(&mut out),
         Command::NewAccount(cmd) => cmd.run(&mut out),
@@ -111,15 +141,20 @@ pub fn run() -> Result<(), Error> {
         Command::NewBadgeMutable(cmd) => cmd.run(&mut out),
         Command::NewTokenFixed(cmd) => cmd.run(&mut out),
         Command::NewTokenMutable(cmd) => cmd.run(&mut out),
+        Command::Prune(cmd) => cmd.run(&mut out),
         Command::Publish(cmd) => cmd.run(&mut out),
+        Command::Replay(cmd) => cmd.run(&mut out),
         Command::Reset(cmd) => cmd.run(&mut out),
         Command::Run(cmd) => cmd.run(&mut out),
+        Command::Serve(cmd) => cmd.run(&mut out),
         Command::SetCurrentEpoch(cmd) => cmd.run(&mut out),
         Command::SetDefaultAccount(cmd) => cmd.run(&mut out),
         Command::ShowConfigs(cmd) => cmd.run(&mut out),
         Command::ShowLedger(cmd) => cmd.run(&mut out),
         Command::Show(cmd) => cmd.run(&mut out),
+        Command::Sign(cmd) => cmd.run(&mut out),
         Command::Transfer(cmd) => cmd.run(&mut out),
+        Command::Watch(cmd) => cmd.run(&mut out),
     }
 }
 
@@ -135,7 +170,7 @@ pub fn process_transaction<L: SubstateStore, O: std::io::Write>(
             if env::var(ENV_DISABLE_MANIFEST_OUTPUT).is_ok() {
                 Ok(())
             } else {
-                let manifest = decompile(&transaction).map_err(Error::DecompileError)?;
+                let manifest = decompile(&transaction, executor.network()).map_err(Error::DecompileError)?;
                 fs::write(path, manifest).map_err(Error::IOError)
             }
         }
@@ -151,8 +186,15 @@ pub fn process_transaction<L: SubstateStore, O: std::io::Write>(
             let receipt = executor
                 .validate_and_execute(&signed)
                 .map_err(Error::TransactionValidationError)?;
-            writeln!(out, "{:?}", receipt).map_err(Error::IOError)?;
-            receipt.result.map_err(Error::TransactionExecutionError)
+            append_transaction_log(
+                executor.substate_store_mut(),
+                &TransactionLogEntry::transaction(&signed, &receipt),
+            );
+            let encoder = Bech32Encoder::new(executor.network().id);
+            writeln!(out, "{}", receipt.display(&encoder)).map_err(Error::IOError)?;
+            receipt
+                .result
+                .map_err(|e| Error::TransactionExecutionError(e.cause))
         }
     }
 }
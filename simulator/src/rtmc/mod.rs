@@ -1,5 +1,6 @@
 use clap::Parser;
 use scrypto::buffer::scrypto_encode;
+use scrypto::core::NetworkDefinition;
 use std::path::PathBuf;
 use transaction_manifest::compile;
 
@@ -11,6 +12,10 @@ pub struct Args {
     #[clap(short, long)]
     output: PathBuf,
 
+    /// Network of the transaction, one of `simulator`, `localnet` and `mainnet`
+    #[clap(short, long, default_value = "simulator")]
+    network: String,
+
     /// Input file
     #[clap(required = true)]
     input: PathBuf,
@@ -20,13 +25,21 @@ pub struct Args {
 pub enum Error {
     IoError(std::io::Error),
     CompileError(transaction_manifest::CompileError),
+    InvalidNetwork(String),
 }
 
 pub fn run() -> Result<(), Error> {
     let args = Args::parse();
 
+    let network = match args.network.as_str() {
+        "simulator" => NetworkDefinition::simulator(),
+        "localnet" => NetworkDefinition::local_network(),
+        "mainnet" => NetworkDefinition::mainnet(),
+        _ => return Err(Error::InvalidNetwork(args.network)),
+    };
+
     let content = std::fs::read_to_string(args.input).map_err(Error::IoError)?;
-    let transaction = compile(&content).map_err(Error::CompileError)?;
+    let transaction = compile(&content, &network).map_err(Error::CompileError)?;
     std::fs::write(args.output, scrypto_encode(&transaction)).map_err(Error::IoError)?;
 
     Ok(())
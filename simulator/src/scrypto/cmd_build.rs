@@ -1,4 +1,5 @@
 use clap::Parser;
+use scrypto::misc::BuildOptions;
 use std::env::current_dir;
 use std::path::PathBuf;
 
@@ -12,6 +13,22 @@ pub struct Build {
     #[clap(long)]
     path: Option<PathBuf>,
 
+    /// The workspace member to build
+    #[clap(short, long)]
+    package: Option<String>,
+
+    /// The features to enable, comma separated
+    #[clap(long, use_value_delimiter = true)]
+    features: Vec<String>,
+
+    /// Build in debug mode, rather than release
+    #[clap(long)]
+    debug: bool,
+
+    /// The target directory
+    #[clap(long)]
+    target_dir: Option<PathBuf>,
+
     /// Turn on tracing
     #[clap(short, long)]
     trace: bool,
@@ -19,11 +36,20 @@ pub struct Build {
 
 impl Build {
     pub fn run(&self) -> Result<(), Error> {
-        build_package(
-            self.path.clone().unwrap_or(current_dir().unwrap()),
-            self.trace,
-        )
-        .map(|_| ())
-        .map_err(Error::CargoError)
+        let mut options = BuildOptions::new()
+            .features(self.features.clone())
+            .release(!self.debug);
+        if let Some(package) = &self.package {
+            options = options.package(package);
+        }
+        if let Some(target_dir) = &self.target_dir {
+            options = options.target_dir(target_dir);
+        }
+        if self.trace {
+            options = options.feature("scrypto/trace");
+        }
+        build_package_with_options(self.path.clone().unwrap_or(current_dir().unwrap()), options)
+            .map(|_| ())
+            .map_err(Error::CargoError)
     }
 }
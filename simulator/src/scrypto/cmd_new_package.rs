@@ -1,15 +1,86 @@
 use clap::Parser;
 use std::fs;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 use crate::scrypto::*;
 
+/// A package template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    /// A blueprint which gives away tokens.
+    Hello,
+    /// A token with an admin-controlled supply.
+    Token,
+    /// A collection of non-fungibles with mutable data.
+    Nft,
+    /// A constant product pool of two tokens.
+    Dex,
+    /// A treasury paying out proposals voted on by members.
+    Dao,
+}
+
+impl Template {
+    /// Returns the blueprint source, the test source, and whether the tests use `TestRunner`.
+    fn sources(&self) -> (&'static str, &'static str, bool) {
+        match self {
+            Template::Hello => (
+                include_str!("../../../assets/template/src/lib.rs"),
+                include_str!("../../../assets/template/tests/lib.rs"),
+                false,
+            ),
+            Template::Token => (
+                include_str!("../../../assets/templates/token/src/lib.rs"),
+                include_str!("../../../assets/templates/token/tests/lib.rs"),
+                true,
+            ),
+            Template::Nft => (
+                include_str!("../../../assets/templates/nft/src/lib.rs"),
+                include_str!("../../../assets/templates/nft/tests/lib.rs"),
+                true,
+            ),
+            Template::Dex => (
+                include_str!("../../../assets/templates/dex/src/lib.rs"),
+                include_str!("../../../assets/templates/dex/tests/lib.rs"),
+                true,
+            ),
+            Template::Dao => (
+                include_str!("../../../assets/templates/dao/src/lib.rs"),
+                include_str!("../../../assets/templates/dao/tests/lib.rs"),
+                true,
+            ),
+        }
+    }
+}
+
+impl FromStr for Template {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hello" => Ok(Template::Hello),
+            "token" => Ok(Template::Token),
+            "nft" => Ok(Template::Nft),
+            "dex" => Ok(Template::Dex),
+            "dao" => Ok(Template::Dao),
+            _ => Err(format!(
+                "Unknown template: {} (expected hello, token, nft, dex or dao)",
+                s
+            )),
+        }
+    }
+}
+
 /// Create a Scrypto package
 #[derive(Parser, Debug)]
 pub struct NewPackage {
     /// The package name
     package_name: String,
 
+    /// The template: hello, token, nft, dex or dao
+    #[clap(short, long, default_value = "hello")]
+    template: Template,
+
     /// The package directory
     #[clap(long)]
     path: Option<PathBuf>,
@@ -62,19 +133,24 @@ impl NewPackage {
             )
             .map_err(Error::IOError)?;
 
-            fs::write(
-                child_of(&child_of(&path, "src"), "lib.rs"),
-                include_str!("../../../assets/template/src/lib.rs"),
-            )
-            .map_err(Error::IOError)?;
+            let (blueprint, tests, uses_test_runner) = self.template.sources();
+            fs::write(child_of(&child_of(&path, "src"), "lib.rs"), blueprint)
+                .map_err(Error::IOError)?;
 
             fs::write(
                 child_of(&child_of(&path, "tests"), "lib.rs"),
-                include_str!("../../../assets/template/tests/lib.rs")
-                    .replace("${wasm_name}", &wasm_name),
+                tests.replace("${wasm_name}", &wasm_name),
             )
             .map_err(Error::IOError)?;
 
+            if uses_test_runner {
+                fs::write(
+                    child_of(&child_of(&path, "tests"), "test_runner.rs"),
+                    include_str!("../../../assets/templates/test_runner.rs"),
+                )
+                .map_err(Error::IOError)?;
+            }
+
             Ok(())
         }
     }
@@ -6,7 +6,7 @@ use std::path::PathBuf;
 use std::process::Command;
 use std::process::ExitStatus;
 
-use cargo_toml::Manifest;
+use scrypto::misc::{BuildError, BuildOptions};
 
 /// Represents an error when running a cargo command.
 #[derive(Debug)]
@@ -19,7 +19,7 @@ pub enum CargoExecutionError {
 
     FailedToRunCargo(io::Error),
 
-    FailedToBuild(ExitStatus),
+    FailedToBuild(BuildError),
 
     FailedToTest(ExitStatus),
 
@@ -30,50 +30,24 @@ pub enum CargoExecutionError {
 
 /// Builds a package.
 pub fn build_package<P: AsRef<Path>>(path: P, trace: bool) -> Result<PathBuf, CargoExecutionError> {
-    let mut cargo = path.as_ref().to_owned();
-    cargo.push("Cargo.toml");
-    if cargo.exists() {
-        let status = Command::new("cargo")
-            .arg("build")
-            .arg("--target")
-            .arg("wasm32-unknown-unknown")
-            .arg("--release")
-            .arg("--manifest-path")
-            .arg(cargo.to_str().unwrap())
-            .args(if trace {
-                vec!["--features", "scrypto/trace"]
-            } else {
-                vec![]
-            })
-            .status()
-            .map_err(CargoExecutionError::FailedToRunCargo)?;
-        if !status.success() {
-            return Err(CargoExecutionError::FailedToBuild(status));
-        }
-
-        let manifest =
-            Manifest::from_path(&cargo).map_err(|_| CargoExecutionError::InvalidManifestFile)?;
-
-        // resolve lib name from manifest
-        let mut wasm_name = None;
-        if let Some(lib) = manifest.lib {
-            wasm_name = lib.name.clone();
-        }
-        if wasm_name == None {
-            if let Some(pkg) = manifest.package {
-                wasm_name = Some(pkg.name.replace("-", "_"));
-            }
-        }
-
-        let mut bin = path.as_ref().to_owned();
-        bin.push("target");
-        bin.push("wasm32-unknown-unknown");
-        bin.push("release");
-        bin.push(wasm_name.ok_or(CargoExecutionError::InvalidManifestFile)?);
-        Ok(bin.with_extension("wasm"))
-    } else {
-        Err(CargoExecutionError::NotCargoPackage)
+    let mut options = BuildOptions::new();
+    if trace {
+        options = options.feature("scrypto/trace");
     }
+    build_package_with_options(path, options)
+}
+
+/// Builds a package with the given options, showing cargo output.
+pub fn build_package_with_options<P: AsRef<Path>>(
+    path: P,
+    options: BuildOptions,
+) -> Result<PathBuf, CargoExecutionError> {
+    scrypto::misc::build_package(path, &options.verbose(true)).map_err(|e| match e {
+        BuildError::NotCargoPackage(..) => CargoExecutionError::NotCargoPackage,
+        BuildError::InvalidManifest(..) => CargoExecutionError::InvalidManifestFile,
+        BuildError::FailedToRunCargo(e) => CargoExecutionError::FailedToRunCargo(e),
+        e => CargoExecutionError::FailedToBuild(e),
+    })
 }
 
 /// Runs tests within a package.
@@ -2,6 +2,8 @@ mod cargo;
 mod display;
 mod iter;
 
-pub use cargo::{build_package, fmt_package, test_package, CargoExecutionError};
+pub use cargo::{
+    build_package, build_package_with_options, fmt_package, test_package, CargoExecutionError,
+};
 pub use display::list_item_prefix;
 pub use iter::{IdentifyLast, Iter};
@@ -30,6 +30,7 @@ $resim call-method $component free_token
 
 # Test - export abi
 $resim export-abi $package Hello
+$resim export-types $package
 
 # Test - dump component state
 $resim show $package
@@ -38,6 +39,9 @@ $resim show $account
 $resim show $account2
 $resim show $token_address
 
+# Test - watch component state
+$resim watch $component --count 1
+
 # Test - output manifest
 $resim new-badge-fixed 1 --name 'MintBadge' --manifest ./target/temp.rtm
 cat ./target/temp.rtm
@@ -48,6 +52,8 @@ $resim run ./target/temp3.rtm
 
 # Test - run manifest with a given set of signing keys
 $resim generate-key-pair
+$resim keygen
+$resim sign ./target/temp2.rtm --nonce 1
 $resim run ./target/temp2.rtm --signing-keys 4fc0db017bf9b80743b7151fee3f04bad817f2d8d7e34ae96c022fe7451b0ea3,329a27258d7e9496c42a110571e6ba0d47f2bda8bd610e9777d16853ab145b0c
 
 # Test - nft
@@ -55,3 +61,21 @@ package=`$resim publish ./tests/nft | awk '/Package:/ {print $NF}'`
 $resim call-function $package Foo nfts
 $resim show $account
 
+# Test - set current epoch and show configs
+$resim set-current-epoch 100
+$resim show-configs
+
+# Test - dump state and diff
+$resim dump-state > ./target/state1.txt
+$resim dump-state --address $account
+$resim transfer 1 030000000000000000000000000000000000000000000000000004 $account2
+$resim dump-state > ./target/state2.txt
+$resim diff ./target/state1.txt ./target/state2.txt
+
+# Test - replay transaction log
+$resim replay --dry-run
+$resim replay
+
+# Test - prune
+$resim prune --dry-run
+$resim prune
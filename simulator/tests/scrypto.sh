@@ -19,3 +19,12 @@ $scrypto build --path $test_pkg
 $scrypto test --path $test_pkg
 $scrypto test --path $test_pkg -- test_hello --nocapture
 $scrypto test --path $test_pkg -- --nocapture
+
+# Templates
+for template in token nft dex dao; do
+    template_pkg="./target/temp/$template"
+    rm -fr $template_pkg
+    $scrypto new-package $template --template $template --path $template_pkg --local
+    $scrypto build --path $template_pkg
+    $scrypto test --path $template_pkg
+done
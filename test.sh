@@ -12,7 +12,7 @@ echo "Testing with std..."
 (cd scrypto; cargo test)
 (cd scrypto-derive; cargo test)
 (cd scrypto-tests; cargo test)
-(cd radix-engine; cargo test)
+(cd radix-engine; cargo test --features proptest)
 (cd transaction-manifest; cargo test)
 
 echo "Testing with no_std..."
@@ -21,6 +21,7 @@ echo "Testing with no_std..."
 (cd scrypto; cargo test --no-default-features --features alloc)
 (cd scrypto-abi; cargo test --no-default-features --features alloc)
 (cd scrypto-tests; cargo test --no-default-features --features alloc)
+(cd radix-engine; cargo test --no-default-features --features alloc --test no_std)
 
 echo "Building assets and examples..."
 (cd assets/account; cargo build --target wasm32-unknown-unknown --release; cargo test --release)
@@ -81,6 +81,8 @@ pub enum Instruction {
         proof: Value,
     },
 
+    DropAllProofs,
+
     CallFunction {
         package_address: Value,
         blueprint_name: Value,
@@ -97,11 +99,39 @@ pub enum Instruction {
     CallMethodWithAllResources {
         component_address: Value,
         method: Value,
+        args: Vec<Value>,
+    },
+
+    CallResourceMethod {
+        resource_address: Value,
+        method: Value,
+        args: Vec<Value>,
+    },
+
+    CreateLocker {
+        component_address: Value,
+        bucket: Value,
+        claim_rule: Value,
+    },
+
+    ClaimLocker {
+        component_address: Value,
+        locker_id: Value,
+    },
+
+    CreateAccount {
+        withdraw_auth: Value,
+        bucket: Value,
     },
 
     PublishPackage {
         code: Value,
     },
+
+    PublishPackageWithOwner {
+        code: Value,
+        owner_badge: Value,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -122,6 +152,7 @@ pub enum Type {
     String,
     Struct,
     Enum,
+    IndexedEnum,
     Option,
     Array,
     Tuple,
@@ -136,6 +167,8 @@ pub enum Type {
 
     /* Custom types */
     Decimal,
+    I256,
+    U256,
     PackageAddress,
     ComponentAddress,
     ResourceAddress,
@@ -166,6 +199,7 @@ pub enum Value {
     String(String),
     Struct(Vec<Value>),
     Enum(String, Vec<Value>),
+    IndexedEnum(u8, Vec<Value>),
     Option(Box<Option<Value>>),
     Array(Type, Vec<Value>),
     Tuple(Vec<Value>),
@@ -178,6 +212,8 @@ pub enum Value {
     HashMap(Type, Type, Vec<Value>),
 
     Decimal(Box<Value>),
+    I256(Box<Value>),
+    U256(Box<Value>),
     PackageAddress(Box<Value>),
     ComponentAddress(Box<Value>),
     ResourceAddress(Box<Value>),
@@ -208,6 +244,7 @@ impl Value {
             Value::String(_) => Type::String,
             Value::Struct(_) => Type::Struct,
             Value::Enum(_, _) => Type::Enum,
+            Value::IndexedEnum(_, _) => Type::IndexedEnum,
             Value::Option(_) => Type::Option,
             Value::Array(_, _) => Type::Array,
             Value::Tuple(_) => Type::Tuple,
@@ -218,6 +255,8 @@ impl Value {
             Value::HashSet(_, _) => Type::HashSet,
             Value::HashMap(_, _, _) => Type::HashMap,
             Value::Decimal(_) => Type::Decimal,
+            Value::I256(_) => Type::I256,
+            Value::U256(_) => Type::U256,
             Value::PackageAddress(_) => Type::PackageAddress,
             Value::ComponentAddress(_) => Type::ComponentAddress,
             Value::ResourceAddress(_) => Type::ResourceAddress,
@@ -1,5 +1,7 @@
 use radix_engine::engine::*;
 use radix_engine::model::*;
+use scrypto::address::Bech32Encoder;
+use scrypto::core::NetworkDefinition;
 use scrypto::engine::types::*;
 use scrypto::rust::collections::*;
 use scrypto::values::*;
@@ -10,7 +12,9 @@ pub enum DecompileError {
     ParseScryptoValueError(ParseScryptoValueError),
 }
 
-pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
+/// Decompiles a transaction into a manifest, with addresses encoded for the given network.
+pub fn decompile(tx: &Transaction, network: &NetworkDefinition) -> Result<String, DecompileError> {
+    let bech32_encoder = Bech32Encoder::new(network.id);
     let mut buf = String::new();
     let mut id_validator = IdValidator::new();
     let mut buckets = HashMap::<BucketId, String>::new();
@@ -25,7 +29,8 @@ pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
                 buckets.insert(bucket_id, name.clone());
                 buf.push_str(&format!(
                     "TAKE_FROM_WORKTOP ResourceAddress(\"{}\") Bucket(\"{}\");\n",
-                    resource_address, name
+                    bech32_encoder.encode_resource_address(&resource_address),
+                    name
                 ));
             }
             Instruction::TakeFromWorktopByAmount {
@@ -39,7 +44,7 @@ pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
                 buckets.insert(bucket_id, name.clone());
                 buf.push_str(&format!(
                     "TAKE_FROM_WORKTOP_BY_AMOUNT Decimal(\"{}\") ResourceAddress(\"{}\") Bucket(\"{}\");\n",
-                    amount, resource_address, name
+                    amount, bech32_encoder.encode_resource_address(&resource_address), name
                 ));
             }
             Instruction::TakeFromWorktopByIds {
@@ -57,7 +62,7 @@ pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
                     .map(|k| format!("NonFungibleId(\"{}\")", k))
                     .collect::<Vec<String>>()
                     .join(", "),
-                    resource_address, name
+                    bech32_encoder.encode_resource_address(&resource_address), name
                 ));
             }
             Instruction::ReturnToWorktop { bucket_id } => {
@@ -75,7 +80,7 @@ pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
             Instruction::AssertWorktopContains { resource_address } => {
                 buf.push_str(&format!(
                     "ASSERT_WORKTOP_CONTAINS ResourceAddress(\"{}\");\n",
-                    resource_address
+                    bech32_encoder.encode_resource_address(&resource_address)
                 ));
             }
             Instruction::AssertWorktopContainsByAmount {
@@ -84,7 +89,8 @@ pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
             } => {
                 buf.push_str(&format!(
                     "ASSERT_WORKTOP_CONTAINS_BY_AMOUNT Decimal(\"{}\") ResourceAddress(\"{}\");\n",
-                    amount, resource_address
+                    amount,
+                    bech32_encoder.encode_resource_address(&resource_address)
                 ));
             }
             Instruction::AssertWorktopContainsByIds {
@@ -97,7 +103,7 @@ pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
                         .map(|k| format!("NonFungibleId(\"{}\")", k))
                         .collect::<Vec<String>>()
                         .join(", "),
-                    resource_address
+                    bech32_encoder.encode_resource_address(&resource_address)
                 ));
             }
             Instruction::PopFromAuthZone => {
@@ -131,7 +137,8 @@ pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
                 proofs.insert(proof_id, name.clone());
                 buf.push_str(&format!(
                     "CREATE_PROOF_FROM_AUTH_ZONE ResourceAddress(\"{}\") Proof(\"{}\");\n",
-                    resource_address, name
+                    bech32_encoder.encode_resource_address(&resource_address),
+                    name
                 ));
             }
             Instruction::CreateProofFromAuthZoneByAmount {
@@ -146,7 +153,7 @@ pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
                 buf.push_str(&format!(
                     "CREATE_PROOF_FROM_AUTH_ZONE_BY_AMOUNT Decimal(\"{}\") ResourceAddress(\"{}\") Proof(\"{}\");\n",
                     amount,
-                    resource_address, name
+                    bech32_encoder.encode_resource_address(&resource_address), name
                 ));
             }
             Instruction::CreateProofFromAuthZoneByIds {
@@ -163,7 +170,7 @@ pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
                     .map(|k| format!("NonFungibleId(\"{}\")", k))
                     .collect::<Vec<String>>()
                     .join(", "),
-                    resource_address, name
+                    bech32_encoder.encode_resource_address(&resource_address), name
                 ));
             }
             Instruction::CreateProofFromBucket { bucket_id } => {
@@ -208,6 +215,12 @@ pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
                         .unwrap_or(format!("{}u32", proof_id)),
                 ));
             }
+            Instruction::DropAllProofs => {
+                id_validator
+                    .drop_all_proofs()
+                    .map_err(DecompileError::IdValidatorError)?;
+                buf.push_str("DROP_ALL_PROOFS;\n");
+            }
             Instruction::CallFunction {
                 package_address,
                 blueprint_name,
@@ -216,7 +229,9 @@ pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
             } => {
                 buf.push_str(&format!(
                     "CALL_FUNCTION PackageAddress(\"{}\") \"{}\" \"{}\"",
-                    package_address, blueprint_name, function
+                    bech32_encoder.encode_package_address(&package_address),
+                    blueprint_name,
+                    function
                 ));
                 for arg in args {
                     let validated_arg = ScryptoValue::from_slice(&arg)
@@ -225,7 +240,11 @@ pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
                         .move_resources(&validated_arg)
                         .map_err(DecompileError::IdValidatorError)?;
                     buf.push(' ');
-                    buf.push_str(&validated_arg.to_string_with_context(&buckets, &proofs));
+                    buf.push_str(&validated_arg.to_string_with_context(
+                        &buckets,
+                        &proofs,
+                        Some(&bech32_encoder),
+                    ));
                 }
                 buf.push_str(";\n");
             }
@@ -236,7 +255,8 @@ pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
             } => {
                 buf.push_str(&format!(
                     "CALL_METHOD ComponentAddress(\"{}\") \"{}\"",
-                    component_address, method
+                    bech32_encoder.encode_component_address(&component_address),
+                    method
                 ));
                 for arg in args {
                     let validated_arg = ScryptoValue::from_slice(&arg)
@@ -245,20 +265,127 @@ pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
                         .move_resources(&validated_arg)
                         .map_err(DecompileError::IdValidatorError)?;
                     buf.push(' ');
-                    buf.push_str(&validated_arg.to_string_with_context(&buckets, &proofs));
+                    buf.push_str(&validated_arg.to_string_with_context(
+                        &buckets,
+                        &proofs,
+                        Some(&bech32_encoder),
+                    ));
                 }
                 buf.push_str(";\n");
             }
             Instruction::CallMethodWithAllResources {
                 component_address,
                 method,
+                args,
             } => {
+                buf.push_str(&format!(
+                    "CALL_METHOD_WITH_ALL_RESOURCES ComponentAddress(\"{}\") \"{}\"",
+                    bech32_encoder.encode_component_address(&component_address),
+                    method
+                ));
+                for arg in args {
+                    let validated_arg = ScryptoValue::from_slice(&arg)
+                        .map_err(DecompileError::ParseScryptoValueError)?;
+                    buf.push(' ');
+                    buf.push_str(&validated_arg.to_string_with_context(
+                        &buckets,
+                        &proofs,
+                        Some(&bech32_encoder),
+                    ));
+                }
                 id_validator
                     .move_all_resources()
                     .map_err(DecompileError::IdValidatorError)?;
+                buf.push_str(";\n");
+            }
+            Instruction::CallResourceMethod {
+                resource_address,
+                method,
+                args,
+            } => {
                 buf.push_str(&format!(
-                    "CALL_METHOD_WITH_ALL_RESOURCES ComponentAddress(\"{}\") \"{}\";\n",
-                    component_address, method
+                    "CALL_RESOURCE_METHOD ResourceAddress(\"{}\") \"{}\"",
+                    bech32_encoder.encode_resource_address(&resource_address),
+                    method
+                ));
+                for arg in args {
+                    let validated_arg = ScryptoValue::from_slice(&arg)
+                        .map_err(DecompileError::ParseScryptoValueError)?;
+                    id_validator
+                        .move_resources(&validated_arg)
+                        .map_err(DecompileError::IdValidatorError)?;
+                    buf.push(' ');
+                    buf.push_str(&validated_arg.to_string_with_context(
+                        &buckets,
+                        &proofs,
+                        Some(&bech32_encoder),
+                    ));
+                }
+                buf.push_str(";\n");
+            }
+            Instruction::CreateLocker {
+                component_address,
+                bucket_id,
+                claim_rule,
+            } => {
+                id_validator
+                    .drop_bucket(bucket_id)
+                    .map_err(DecompileError::IdValidatorError)?;
+                buf.push_str(&format!(
+                    "CREATE_LOCKER ComponentAddress(\"{}\") Bucket({}) {};\n",
+                    bech32_encoder.encode_component_address(&component_address),
+                    buckets
+                        .get(&bucket_id)
+                        .map(|name| format!("\"{}\"", name))
+                        .unwrap_or(format!("{}u32", bucket_id)),
+                    ScryptoValue::from_value(&claim_rule).to_string_with_context(
+                        &buckets,
+                        &proofs,
+                        Some(&bech32_encoder),
+                    )
+                ));
+            }
+            Instruction::ClaimLocker {
+                component_address,
+                locker_id,
+            } => {
+                buf.push_str(&format!(
+                    "CLAIM_LOCKER ComponentAddress(\"{}\") {};\n",
+                    bech32_encoder.encode_component_address(&component_address),
+                    ScryptoValue::from_value(&locker_id).to_string_with_context(
+                        &buckets,
+                        &proofs,
+                        Some(&bech32_encoder),
+                    )
+                ));
+            }
+            Instruction::CreateAccount {
+                withdraw_auth,
+                bucket_id,
+            } => {
+                let bucket = match bucket_id {
+                    Some(bucket_id) => {
+                        id_validator
+                            .drop_bucket(bucket_id)
+                            .map_err(DecompileError::IdValidatorError)?;
+                        format!(
+                            "Some(Bucket({}))",
+                            buckets
+                                .get(&bucket_id)
+                                .map(|name| format!("\"{}\"", name))
+                                .unwrap_or(format!("{}u32", bucket_id))
+                        )
+                    }
+                    None => "None".to_string(),
+                };
+                buf.push_str(&format!(
+                    "CREATE_ACCOUNT {} {};\n",
+                    ScryptoValue::from_value(&withdraw_auth).to_string_with_context(
+                        &buckets,
+                        &proofs,
+                        Some(&bech32_encoder),
+                    ),
+                    bucket
                 ));
             }
             Instruction::PublishPackage { code } => {
@@ -267,6 +394,13 @@ pub fn decompile(tx: &Transaction) -> Result<String, DecompileError> {
                     hex::encode(&code)
                 ));
             }
+            Instruction::PublishPackageWithOwner { code, owner_badge } => {
+                buf.push_str(&format!(
+                    "PUBLISH_PACKAGE_WITH_OWNER Bytes(\"{}\") ResourceAddress(\"{}\");\n",
+                    hex::encode(&code),
+                    bech32_encoder.encode_resource_address(&owner_badge)
+                ));
+            }
             Instruction::Nonce { .. } => {
                 // TODO: add support for this
             }
@@ -283,11 +417,12 @@ mod tests {
 
     #[test]
     fn test_decompile() {
-        let tx = compile(include_str!("../examples/complex.rtm")).unwrap();
+        let network = NetworkDefinition::simulator();
+        let tx = compile(include_str!("../examples/complex.rtm"), &network).unwrap();
 
-        let manifest = &decompile(&tx).unwrap();
+        let manifest = &decompile(&tx, &network).unwrap();
         println!("{}", manifest);
 
-        assert_eq!(compile(manifest).unwrap(), tx);
+        assert_eq!(compile(manifest, &network).unwrap(), tx);
     }
 }
@@ -4,7 +4,12 @@ use radix_engine::model::*;
 use sbor::any::{encode_any, Value};
 use sbor::type_id::*;
 use sbor::Encoder;
+use scrypto::address::{AddressError, Bech32Decoder};
+use scrypto::buffer::scrypto_decode;
+use scrypto::core::NetworkDefinition;
 use scrypto::engine::types::*;
+use scrypto::math::{I256, U256};
+use scrypto::resource::AccessRule;
 use scrypto::rust::collections::BTreeSet;
 use scrypto::rust::collections::HashMap;
 use scrypto::rust::str::FromStr;
@@ -25,11 +30,14 @@ pub enum GeneratorError {
     InvalidComponentAddress(String),
     InvalidResourceAddress(String),
     InvalidDecimal(String),
+    InvalidI256(String),
+    InvalidU256(String),
     InvalidHash(String),
     InvalidLazyMapId(String),
     InvalidVaultId(String),
     InvalidNonFungibleId(String),
     InvalidNonFungibleAddress(String),
+    InvalidAccessRule(ast::Value),
     OddNumberOfElements(usize),
     NameResolverError(NameResolverError),
     IdValidatorError(IdValidatorError),
@@ -96,9 +104,13 @@ impl NameResolver {
     }
 }
 
-pub fn generate_transaction(tx: &ast::Transaction) -> Result<Transaction, GeneratorError> {
+pub fn generate_transaction(
+    tx: &ast::Transaction,
+    network: &NetworkDefinition,
+) -> Result<Transaction, GeneratorError> {
     let mut id_validator = IdValidator::new();
     let mut name_resolver = NameResolver::new();
+    let bech32_decoder = Bech32Decoder::new(network.id);
     let mut instructions = Vec::new();
 
     for instruction in &tx.instructions {
@@ -106,16 +118,23 @@ pub fn generate_transaction(tx: &ast::Transaction) -> Result<Transaction, Genera
             instruction,
             &mut id_validator,
             &mut name_resolver,
+            &bech32_decoder,
         )?);
     }
 
-    Ok(Transaction { instructions })
+    Ok(Transaction {
+        header: TransactionHeader {
+            network_id: network.id,
+        },
+        instructions,
+    })
 }
 
 pub fn generate_instruction(
     instruction: &ast::Instruction,
     id_validator: &mut IdValidator,
     resolver: &mut NameResolver,
+    bech32_decoder: &Bech32Decoder,
 ) -> Result<Instruction, GeneratorError> {
     Ok(match instruction {
         ast::Instruction::TakeFromWorktop {
@@ -128,7 +147,7 @@ pub fn generate_instruction(
             declare_bucket(new_bucket, resolver, bucket_id)?;
 
             Instruction::TakeFromWorktop {
-                resource_address: generate_resource_address(resource_address)?,
+                resource_address: generate_resource_address(resource_address, bech32_decoder)?,
             }
         }
         ast::Instruction::TakeFromWorktopByAmount {
@@ -143,7 +162,7 @@ pub fn generate_instruction(
 
             Instruction::TakeFromWorktopByAmount {
                 amount: generate_decimal(amount)?,
-                resource_address: generate_resource_address(resource_address)?,
+                resource_address: generate_resource_address(resource_address, bech32_decoder)?,
             }
         }
         ast::Instruction::TakeFromWorktopByIds {
@@ -158,7 +177,7 @@ pub fn generate_instruction(
 
             Instruction::TakeFromWorktopByIds {
                 ids: generate_non_fungible_ids(ids)?,
-                resource_address: generate_resource_address(resource_address)?,
+                resource_address: generate_resource_address(resource_address, bech32_decoder)?,
             }
         }
         ast::Instruction::ReturnToWorktop { bucket } => {
@@ -170,7 +189,7 @@ pub fn generate_instruction(
         }
         ast::Instruction::AssertWorktopContains { resource_address } => {
             Instruction::AssertWorktopContains {
-                resource_address: generate_resource_address(resource_address)?,
+                resource_address: generate_resource_address(resource_address, bech32_decoder)?,
             }
         }
         ast::Instruction::AssertWorktopContainsByAmount {
@@ -178,14 +197,14 @@ pub fn generate_instruction(
             resource_address,
         } => Instruction::AssertWorktopContainsByAmount {
             amount: generate_decimal(amount)?,
-            resource_address: generate_resource_address(resource_address)?,
+            resource_address: generate_resource_address(resource_address, bech32_decoder)?,
         },
         ast::Instruction::AssertWorktopContainsByIds {
             ids,
             resource_address,
         } => Instruction::AssertWorktopContainsByIds {
             ids: generate_non_fungible_ids(ids)?,
-            resource_address: generate_resource_address(resource_address)?,
+            resource_address: generate_resource_address(resource_address, bech32_decoder)?,
         },
         ast::Instruction::PopFromAuthZone { new_proof } => {
             let proof_id = id_validator
@@ -208,7 +227,7 @@ pub fn generate_instruction(
             resource_address,
             new_proof,
         } => {
-            let resource_address = generate_resource_address(resource_address)?;
+            let resource_address = generate_resource_address(resource_address, bech32_decoder)?;
             let proof_id = id_validator
                 .new_proof(ProofKind::AuthZoneProof)
                 .map_err(GeneratorError::IdValidatorError)?;
@@ -222,7 +241,7 @@ pub fn generate_instruction(
             new_proof,
         } => {
             let amount = generate_decimal(amount)?;
-            let resource_address = generate_resource_address(resource_address)?;
+            let resource_address = generate_resource_address(resource_address, bech32_decoder)?;
             let proof_id = id_validator
                 .new_proof(ProofKind::AuthZoneProof)
                 .map_err(GeneratorError::IdValidatorError)?;
@@ -239,7 +258,7 @@ pub fn generate_instruction(
             new_proof,
         } => {
             let ids = generate_non_fungible_ids(ids)?;
-            let resource_address = generate_resource_address(resource_address)?;
+            let resource_address = generate_resource_address(resource_address, bech32_decoder)?;
             let proof_id = id_validator
                 .new_proof(ProofKind::AuthZoneProof)
                 .map_err(GeneratorError::IdValidatorError)?;
@@ -275,13 +294,19 @@ pub fn generate_instruction(
                 .map_err(GeneratorError::IdValidatorError)?;
             Instruction::DropProof { proof_id }
         }
+        ast::Instruction::DropAllProofs => {
+            id_validator
+                .drop_all_proofs()
+                .map_err(GeneratorError::IdValidatorError)?;
+            Instruction::DropAllProofs
+        }
         ast::Instruction::CallFunction {
             package_address,
             blueprint_name,
             function,
             args,
         } => {
-            let args = generate_args(args, resolver)?;
+            let args = generate_args(args, resolver, bech32_decoder)?;
             for arg in &args {
                 let validated_arg = ScryptoValue::from_slice(arg).unwrap();
                 id_validator
@@ -289,7 +314,7 @@ pub fn generate_instruction(
                     .map_err(GeneratorError::IdValidatorError)?;
             }
             Instruction::CallFunction {
-                package_address: generate_package_address(package_address)?,
+                package_address: generate_package_address(package_address, bech32_decoder)?,
                 blueprint_name: generate_string(blueprint_name)?,
                 function: generate_string(function)?,
                 args,
@@ -300,7 +325,7 @@ pub fn generate_instruction(
             method,
             args,
         } => {
-            let args = generate_args(args, resolver)?;
+            let args = generate_args(args, resolver, bech32_decoder)?;
             for arg in &args {
                 let validated_arg = ScryptoValue::from_slice(arg).unwrap();
                 id_validator
@@ -308,7 +333,7 @@ pub fn generate_instruction(
                     .map_err(GeneratorError::IdValidatorError)?;
             }
             Instruction::CallMethod {
-                component_address: generate_component_address(component_address)?,
+                component_address: generate_component_address(component_address, bech32_decoder)?,
                 method: generate_string(method)?,
                 args,
             }
@@ -316,18 +341,82 @@ pub fn generate_instruction(
         ast::Instruction::CallMethodWithAllResources {
             component_address,
             method,
+            args,
         } => {
+            let args = generate_args(args, resolver, bech32_decoder)?;
             id_validator
                 .move_all_resources()
                 .map_err(GeneratorError::IdValidatorError)?;
             Instruction::CallMethodWithAllResources {
-                component_address: generate_component_address(component_address)?,
+                component_address: generate_component_address(component_address, bech32_decoder)?,
+                method: generate_string(method)?,
+                args,
+            }
+        }
+        ast::Instruction::CallResourceMethod {
+            resource_address,
+            method,
+            args,
+        } => {
+            let args = generate_args(args, resolver, bech32_decoder)?;
+            for arg in &args {
+                let validated_arg = ScryptoValue::from_slice(arg).unwrap();
+                id_validator
+                    .move_resources(&validated_arg)
+                    .map_err(GeneratorError::IdValidatorError)?;
+            }
+            Instruction::CallResourceMethod {
+                resource_address: generate_resource_address(resource_address, bech32_decoder)?,
                 method: generate_string(method)?,
+                args,
+            }
+        }
+        ast::Instruction::CreateLocker {
+            component_address,
+            bucket,
+            claim_rule,
+        } => {
+            let bucket_id = generate_bucket(bucket, resolver)?;
+            id_validator
+                .drop_bucket(bucket_id)
+                .map_err(GeneratorError::IdValidatorError)?;
+            Instruction::CreateLocker {
+                component_address: generate_component_address(component_address, bech32_decoder)?,
+                bucket_id,
+                claim_rule: generate_access_rule(claim_rule, resolver, bech32_decoder)?,
+            }
+        }
+        ast::Instruction::ClaimLocker {
+            component_address,
+            locker_id,
+        } => Instruction::ClaimLocker {
+            component_address: generate_component_address(component_address, bech32_decoder)?,
+            locker_id: generate_locker_id(locker_id)?,
+        },
+        ast::Instruction::CreateAccount {
+            withdraw_auth,
+            bucket,
+        } => {
+            let bucket_id = generate_optional_bucket(bucket, resolver)?;
+            if let Some(bucket_id) = bucket_id {
+                id_validator
+                    .drop_bucket(bucket_id)
+                    .map_err(GeneratorError::IdValidatorError)?;
+            }
+            Instruction::CreateAccount {
+                withdraw_auth: generate_access_rule(withdraw_auth, resolver, bech32_decoder)?,
+                bucket_id,
             }
         }
         ast::Instruction::PublishPackage { code } => Instruction::PublishPackage {
             code: generate_bytes(code)?,
         },
+        ast::Instruction::PublishPackageWithOwner { code, owner_badge } => {
+            Instruction::PublishPackageWithOwner {
+                code: generate_bytes(code)?,
+                owner_badge: generate_resource_address(owner_badge, bech32_decoder)?,
+            }
+        }
     })
 }
 
@@ -344,10 +433,11 @@ macro_rules! invalid_type {
 fn generate_args(
     values: &Vec<ast::Value>,
     resolver: &mut NameResolver,
+    bech32_decoder: &Bech32Decoder,
 ) -> Result<Vec<Vec<u8>>, GeneratorError> {
     let mut result = Vec::new();
     for v in values {
-        let value = generate_value(v, None, resolver)?;
+        let value = generate_value(v, None, resolver, bech32_decoder)?;
 
         let mut bytes = Vec::new();
         let mut enc = Encoder::with_type(&mut bytes);
@@ -407,39 +497,90 @@ fn generate_decimal(value: &ast::Value) -> Result<Decimal, GeneratorError> {
     }
 }
 
-fn generate_package_address(value: &ast::Value) -> Result<PackageAddress, GeneratorError> {
+fn generate_i256(value: &ast::Value) -> Result<I256, GeneratorError> {
+    match value {
+        ast::Value::I256(inner) => match &**inner {
+            ast::Value::String(s) => {
+                I256::from_str(s).map_err(|_| GeneratorError::InvalidI256(s.into()))
+            }
+            v @ _ => invalid_type!(v, ast::Type::String),
+        },
+        v @ _ => invalid_type!(v, ast::Type::I256),
+    }
+}
+
+fn generate_u256(value: &ast::Value) -> Result<U256, GeneratorError> {
+    match value {
+        ast::Value::U256(inner) => match &**inner {
+            ast::Value::String(s) => {
+                U256::from_str(s).map_err(|_| GeneratorError::InvalidU256(s.into()))
+            }
+            v @ _ => invalid_type!(v, ast::Type::String),
+        },
+        v @ _ => invalid_type!(v, ast::Type::U256),
+    }
+}
+
+fn generate_package_address(
+    value: &ast::Value,
+    bech32_decoder: &Bech32Decoder,
+) -> Result<PackageAddress, GeneratorError> {
     match value {
         ast::Value::PackageAddress(inner) => match &**inner {
-            ast::Value::String(s) => PackageAddress::from_str(s)
-                .map_err(|_| GeneratorError::InvalidPackageAddress(s.into())),
+            ast::Value::String(s) => {
+                parse_address(s, |s| bech32_decoder.validate_and_decode_package_address(s))
+                    .ok_or_else(|| GeneratorError::InvalidPackageAddress(s.into()))
+            }
             v @ _ => invalid_type!(v, ast::Type::String),
         },
         v @ _ => invalid_type!(v, ast::Type::PackageAddress),
     }
 }
 
-fn generate_component_address(value: &ast::Value) -> Result<ComponentAddress, GeneratorError> {
+fn generate_component_address(
+    value: &ast::Value,
+    bech32_decoder: &Bech32Decoder,
+) -> Result<ComponentAddress, GeneratorError> {
     match value {
         ast::Value::ComponentAddress(inner) => match &**inner {
-            ast::Value::String(s) => ComponentAddress::from_str(s)
-                .map_err(|_| GeneratorError::InvalidComponentAddress(s.into())),
+            ast::Value::String(s) => parse_address(s, |s| {
+                bech32_decoder.validate_and_decode_component_address(s)
+            })
+            .ok_or_else(|| GeneratorError::InvalidComponentAddress(s.into())),
             v @ _ => invalid_type!(v, ast::Type::String),
         },
         v @ _ => invalid_type!(v, ast::Type::ComponentAddress),
     }
 }
 
-fn generate_resource_address(value: &ast::Value) -> Result<ResourceAddress, GeneratorError> {
+fn generate_resource_address(
+    value: &ast::Value,
+    bech32_decoder: &Bech32Decoder,
+) -> Result<ResourceAddress, GeneratorError> {
     match value {
         ast::Value::ResourceAddress(inner) => match &**inner {
-            ast::Value::String(s) => ResourceAddress::from_str(s)
-                .map_err(|_| GeneratorError::InvalidResourceAddress(s.into())),
+            ast::Value::String(s) => parse_address(s, |s| {
+                bech32_decoder.validate_and_decode_resource_address(s)
+            })
+            .ok_or_else(|| GeneratorError::InvalidResourceAddress(s.into())),
             v @ _ => invalid_type!(v, ast::Type::String),
         },
         v @ _ => invalid_type!(v, ast::Type::ResourceAddress),
     }
 }
 
+/// Parses an address, either in hex or in the Bech32m form of the manifest's network.
+fn parse_address<T: FromStr>(
+    s: &str,
+    decode_bech32: impl FnOnce(&str) -> Result<T, AddressError>,
+) -> Option<T> {
+    if hex::decode(s).is_ok() {
+        T::from_str(s).ok()
+    } else {
+        decode_bech32(s).ok()
+    }
+}
+
 fn generate_hash(value: &ast::Value) -> Result<Hash, GeneratorError> {
     match value {
         ast::Value::Hash(inner) => match &**inner {
@@ -452,6 +593,29 @@ fn generate_hash(value: &ast::Value) -> Result<Hash, GeneratorError> {
     }
 }
 
+fn generate_locker_id(value: &ast::Value) -> Result<LockerId, GeneratorError> {
+    match value {
+        ast::Value::Tuple(elements) => match &elements[..] {
+            [hash, ast::Value::U32(index)] => Ok((generate_hash(hash)?, *index)),
+            [_, v] => invalid_type!(v, ast::Type::U32),
+            _ => invalid_type!(value, ast::Type::Tuple),
+        },
+        v @ _ => invalid_type!(v, ast::Type::Tuple),
+    }
+}
+
+fn generate_access_rule(
+    value: &ast::Value,
+    resolver: &mut NameResolver,
+    bech32_decoder: &Bech32Decoder,
+) -> Result<AccessRule, GeneratorError> {
+    let generated = generate_value(value, Some(ast::Type::Enum), resolver, bech32_decoder)?;
+    let mut bytes = Vec::new();
+    let mut enc = Encoder::with_type(&mut bytes);
+    encode_any(None, &generated, &mut enc);
+    scrypto_decode(&bytes).map_err(|_| GeneratorError::InvalidAccessRule(value.clone()))
+}
+
 fn declare_bucket(
     value: &ast::Value,
     resolver: &mut NameResolver,
@@ -484,6 +648,19 @@ fn generate_bucket(
     }
 }
 
+fn generate_optional_bucket(
+    value: &ast::Value,
+    resolver: &mut NameResolver,
+) -> Result<Option<BucketId>, GeneratorError> {
+    match value {
+        ast::Value::Option(inner) => match &**inner {
+            Some(bucket) => generate_bucket(bucket, resolver).map(Some),
+            None => Ok(None),
+        },
+        v @ _ => invalid_type!(v, ast::Type::Option),
+    }
+}
+
 fn declare_proof(
     value: &ast::Value,
     resolver: &mut NameResolver,
@@ -560,6 +737,7 @@ fn generate_value(
     value: &ast::Value,
     expected: Option<ast::Type>,
     resolver: &mut NameResolver,
+    bech32_decoder: &Bech32Decoder,
 ) -> Result<Value, GeneratorError> {
     if let Some(ty) = expected {
         if ty != value.kind() {
@@ -587,73 +765,89 @@ fn generate_value(
             value: value.clone(),
         }),
         ast::Value::Struct(fields) => Ok(Value::Struct {
-            fields: generate_singletons(fields, None, resolver)?,
+            fields: generate_singletons(fields, None, resolver, bech32_decoder)?,
         }),
         ast::Value::Enum(name, fields) => Ok(Value::Enum {
             name: name.clone(),
-            fields: generate_singletons(fields, None, resolver)?,
+            fields: generate_singletons(fields, None, resolver, bech32_decoder)?,
+        }),
+        ast::Value::IndexedEnum(index, fields) => Ok(Value::IndexedEnum {
+            index: *index,
+            fields: generate_singletons(fields, None, resolver, bech32_decoder)?,
         }),
         ast::Value::Option(value) => match &**value {
             Some(inner) => Ok(Value::Option {
-                value: Some(generate_value(inner, None, resolver)?).into(),
+                value: Some(generate_value(inner, None, resolver, bech32_decoder)?).into(),
             }),
             None => Ok(Value::Option { value: None.into() }),
         },
         ast::Value::Array(element_type, elements) => Ok(Value::Array {
             element_type_id: generate_type_id(element_type),
-            elements: generate_singletons(elements, Some(*element_type), resolver)?,
+            elements: generate_singletons(elements, Some(*element_type), resolver, bech32_decoder)?,
         }),
         ast::Value::Tuple(elements) => Ok(Value::Tuple {
-            elements: generate_singletons(elements, None, resolver)?,
+            elements: generate_singletons(elements, None, resolver, bech32_decoder)?,
         }),
         ast::Value::Result(value) => match &**value {
             Ok(inner) => Ok(Value::Result {
-                value: Ok(generate_value(inner, None, resolver)?).into(),
+                value: Ok(generate_value(inner, None, resolver, bech32_decoder)?).into(),
             }),
             Err(inner) => Ok(Value::Result {
-                value: Err(generate_value(inner, None, resolver)?).into(),
+                value: Err(generate_value(inner, None, resolver, bech32_decoder)?).into(),
             }),
         },
         ast::Value::Vec(element_type, elements) => Ok(Value::Vec {
             element_type_id: generate_type_id(element_type),
-            elements: generate_singletons(elements, Some(*element_type), resolver)?,
+            elements: generate_singletons(elements, Some(*element_type), resolver, bech32_decoder)?,
         }),
         ast::Value::TreeSet(element_type, elements) => Ok(Value::TreeSet {
             element_type_id: generate_type_id(element_type),
-            elements: generate_singletons(elements, Some(*element_type), resolver)?,
+            elements: generate_singletons(elements, Some(*element_type), resolver, bech32_decoder)?,
         }),
         ast::Value::TreeMap(key_type, value_type, elements) => Ok(Value::TreeMap {
             key_type_id: generate_type_id(key_type),
             value_type_id: generate_type_id(value_type),
-            elements: generate_pairs(elements, *key_type, *value_type, resolver)?,
+            elements: generate_pairs(elements, *key_type, *value_type, resolver, bech32_decoder)?,
         }),
         ast::Value::HashSet(element_type, elements) => Ok(Value::HashSet {
             element_type_id: generate_type_id(element_type),
-            elements: generate_singletons(elements, Some(*element_type), resolver)?,
+            elements: generate_singletons(elements, Some(*element_type), resolver, bech32_decoder)?,
         }),
         ast::Value::HashMap(key_type, value_type, elements) => Ok(Value::HashMap {
             key_type_id: generate_type_id(key_type),
             value_type_id: generate_type_id(value_type),
-            elements: generate_pairs(elements, *key_type, *value_type, resolver)?,
+            elements: generate_pairs(elements, *key_type, *value_type, resolver, bech32_decoder)?,
         }),
         ast::Value::Decimal(_) => generate_decimal(value).map(|v| Value::Custom {
             type_id: ScryptoType::Decimal.id(),
             bytes: v.to_vec(),
         }),
-        ast::Value::PackageAddress(_) => generate_package_address(value).map(|v| Value::Custom {
-            type_id: ScryptoType::PackageAddress.id(),
+        ast::Value::I256(_) => generate_i256(value).map(|v| Value::Custom {
+            type_id: ScryptoType::I256.id(),
             bytes: v.to_vec(),
         }),
+        ast::Value::U256(_) => generate_u256(value).map(|v| Value::Custom {
+            type_id: ScryptoType::U256.id(),
+            bytes: v.to_vec(),
+        }),
+        ast::Value::PackageAddress(_) => {
+            generate_package_address(value, bech32_decoder).map(|v| Value::Custom {
+                type_id: ScryptoType::PackageAddress.id(),
+                bytes: v.to_vec(),
+            })
+        }
         ast::Value::ComponentAddress(_) => {
-            generate_component_address(value).map(|v| Value::Custom {
+            generate_component_address(value, bech32_decoder).map(|v| Value::Custom {
                 type_id: ScryptoType::ComponentAddress.id(),
                 bytes: v.to_vec(),
             })
         }
-        ast::Value::ResourceAddress(_) => generate_resource_address(value).map(|v| Value::Custom {
-            type_id: ScryptoType::ResourceAddress.id(),
-            bytes: v.to_vec(),
-        }),
+        ast::Value::ResourceAddress(_) => {
+            generate_resource_address(value, bech32_decoder).map(|v| Value::Custom {
+                type_id: ScryptoType::ResourceAddress.id(),
+                bytes: v.to_vec(),
+            })
+        }
         ast::Value::Hash(_) => generate_hash(value).map(|v| Value::Custom {
             type_id: ScryptoType::Hash.id(),
             bytes: v.to_vec(),
@@ -696,10 +890,11 @@ fn generate_singletons(
     elements: &Vec<ast::Value>,
     ty: Option<ast::Type>,
     resolver: &mut NameResolver,
+    bech32_decoder: &Bech32Decoder,
 ) -> Result<Vec<Value>, GeneratorError> {
     let mut result = vec![];
     for element in elements {
-        result.push(generate_value(element, ty, resolver)?);
+        result.push(generate_value(element, ty, resolver, bech32_decoder)?);
     }
     Ok(result)
 }
@@ -709,17 +904,24 @@ fn generate_pairs(
     key_type: ast::Type,
     value_type: ast::Type,
     resolver: &mut NameResolver,
+    bech32_decoder: &Bech32Decoder,
 ) -> Result<Vec<Value>, GeneratorError> {
     if elements.len() % 2 != 0 {
         return Err(GeneratorError::OddNumberOfElements(elements.len()));
     }
     let mut result = vec![];
     for i in 0..elements.len() / 2 {
-        result.push(generate_value(&elements[2 * i], Some(key_type), resolver)?);
+        result.push(generate_value(
+            &elements[2 * i],
+            Some(key_type),
+            resolver,
+            bech32_decoder,
+        )?);
         result.push(generate_value(
             &elements[2 * i + 1],
             Some(value_type),
             resolver,
+            bech32_decoder,
         )?);
     }
     Ok(result)
@@ -742,6 +944,7 @@ fn generate_type_id(ty: &ast::Type) -> u8 {
         ast::Type::String => TYPE_STRING,
         ast::Type::Struct => TYPE_STRUCT,
         ast::Type::Enum => TYPE_ENUM,
+        ast::Type::IndexedEnum => TYPE_INDEXED_ENUM,
         ast::Type::Option => TYPE_OPTION,
         ast::Type::Array => TYPE_ARRAY,
         ast::Type::Tuple => TYPE_TUPLE,
@@ -752,6 +955,8 @@ fn generate_type_id(ty: &ast::Type) -> u8 {
         ast::Type::HashSet => TYPE_HASH_SET,
         ast::Type::HashMap => TYPE_HASH_MAP,
         ast::Type::Decimal => ScryptoType::Decimal.id(),
+        ast::Type::I256 => ScryptoType::I256.id(),
+        ast::Type::U256 => ScryptoType::U256.id(),
         ast::Type::PackageAddress => ScryptoType::PackageAddress.id(),
         ast::Type::ComponentAddress => ScryptoType::ComponentAddress.id(),
         ast::Type::ResourceAddress => ScryptoType::ResourceAddress.id(),
@@ -769,14 +974,19 @@ mod tests {
     use super::*;
     use crate::lexer::tokenize;
     use crate::parser::Parser;
+    use scrypto::address::{Bech32Encoder, MAINNET_NETWORK_ID};
     use scrypto::buffer::*;
+    use scrypto::resource::{AccessRuleNode, ProofRule, SoftResourceOrNonFungible};
 
     #[macro_export]
     macro_rules! generate_value_ok {
         ( $s:expr, $expected:expr ) => {{
             let value = Parser::new(tokenize($s).unwrap()).parse_value().unwrap();
             let mut resolver = NameResolver::new();
-            assert_eq!(generate_value(&value, None, &mut resolver), Ok($expected));
+            assert_eq!(
+                generate_value(&value, None, &mut resolver, &Bech32Decoder::for_simulator()),
+                Ok($expected)
+            );
         }};
     }
 
@@ -789,7 +999,12 @@ mod tests {
             let mut id_validator = IdValidator::new();
             let mut resolver = NameResolver::new();
             assert_eq!(
-                generate_instruction(&instruction, &mut id_validator, &mut resolver),
+                generate_instruction(
+                    &instruction,
+                    &mut id_validator,
+                    &mut resolver,
+                    &Bech32Decoder::for_simulator()
+                ),
                 Ok($expected)
             );
         }};
@@ -799,7 +1014,12 @@ mod tests {
     macro_rules! generate_value_error {
         ( $s:expr, $expected:expr ) => {{
             let value = Parser::new(tokenize($s).unwrap()).parse_value().unwrap();
-            match generate_value(&value, None, &mut NameResolver::new()) {
+            match generate_value(
+                &value,
+                None,
+                &mut NameResolver::new(),
+                &Bech32Decoder::for_simulator(),
+            ) {
                 Ok(_) => {
                     panic!("Expected {:?} but no error is thrown", $expected);
                 }
@@ -810,6 +1030,37 @@ mod tests {
         }};
     }
 
+    #[test]
+    fn test_formatted_indexed_enum_roundtrip() {
+        let value = Value::IndexedEnum {
+            index: 2,
+            fields: vec![
+                Value::String { value: "a".into() },
+                Value::IndexedEnum {
+                    index: 0,
+                    fields: vec![],
+                },
+            ],
+        };
+        let mut bytes = Vec::new();
+        encode_any(None, &value, &mut Encoder::with_type(&mut bytes));
+        let formatted = ScryptoValue::from_slice(&bytes).unwrap().to_string();
+
+        let parsed = Parser::new(tokenize(&formatted).unwrap())
+            .parse_value()
+            .unwrap();
+        let mut resolver = NameResolver::new();
+        assert_eq!(
+            generate_value(
+                &parsed,
+                None,
+                &mut resolver,
+                &Bech32Decoder::for_simulator()
+            ),
+            Ok(value)
+        );
+    }
+
     #[test]
     fn test_value() {
         generate_value_ok!(r#"()"#, Value::Unit);
@@ -819,6 +1070,33 @@ mod tests {
         generate_value_ok!(r#"1i128"#, Value::I128 { value: 1 });
         generate_value_ok!(r#"1u8"#, Value::U8 { value: 1 });
         generate_value_ok!(r#"1u128"#, Value::U128 { value: 1 });
+        generate_value_ok!(
+            r#"IndexedEnum(1u8, "Hello")"#,
+            Value::IndexedEnum {
+                index: 1,
+                fields: vec![Value::String {
+                    value: "Hello".into()
+                }]
+            }
+        );
+        generate_value_ok!(
+            r#"I256("-1")"#,
+            Value::Custom {
+                type_id: ScryptoType::I256.id(),
+                bytes: I256::from(-1i64).to_vec()
+            }
+        );
+        generate_value_ok!(
+            r#"U256("115792089237316195423570985008687907853269984665640564039457584007913129639935")"#,
+            Value::Custom {
+                type_id: ScryptoType::U256.id(),
+                bytes: U256::from_str(
+                    "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+                )
+                .unwrap()
+                .to_vec()
+            }
+        );
         generate_value_ok!(
             r#"Struct(Bucket(1u32), Proof(2u32), "bar")"#,
             Value::Struct {
@@ -951,6 +1229,7 @@ mod tests {
             r#"Decimal("invalid_decimal")"#,
             GeneratorError::InvalidDecimal("invalid_decimal".into())
         );
+        generate_value_error!(r#"U256("-1")"#, GeneratorError::InvalidU256("-1".into()));
         generate_value_error!(
             r#"HashMap<String, String>("abc")"#,
             GeneratorError::OddNumberOfElements(1)
@@ -1022,10 +1301,147 @@ mod tests {
                 )
                 .unwrap(),
                 method: "deposit_batch".into(),
+                args: vec![]
+            }
+        );
+        generate_instruction_ok!(
+            r#"CALL_RESOURCE_METHOD  ResourceAddress("030000000000000000000000000000000000000000000000000004") "update_metadata" HashMap<String, String>();"#,
+            Instruction::CallResourceMethod {
+                resource_address: ResourceAddress::from_str(
+                    "030000000000000000000000000000000000000000000000000004".into()
+                )
+                .unwrap(),
+                method: "update_metadata".into(),
+                args: vec![scrypto_encode(&HashMap::<String, String>::new())]
+            }
+        );
+        generate_instruction_ok!(
+            r#"CLAIM_LOCKER  ComponentAddress("0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1")  Tuple(Hash("2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"), 1025u32);"#,
+            Instruction::ClaimLocker {
+                component_address: ComponentAddress::from_str(
+                    "0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1".into()
+                )
+                .unwrap(),
+                locker_id: (
+                    Hash::from_str(
+                        "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+                    )
+                    .unwrap(),
+                    1025
+                )
+            }
+        );
+    }
+
+    #[test]
+    fn test_create_locker() {
+        let tx = r#"
+            TAKE_FROM_WORKTOP  ResourceAddress("030000000000000000000000000000000000000000000000000004")  Bucket("xrd");
+            CREATE_LOCKER  ComponentAddress("0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1")  Bucket("xrd")  Enum("Protected", Enum("ProofRule", Enum("Require", Enum("StaticResource", ResourceAddress("03cbdf875789d08cc80c97e2915b920824a69ea8d809e50b9fe09d")))));
+        "#;
+        let badge =
+            ResourceAddress::from_str("03cbdf875789d08cc80c97e2915b920824a69ea8d809e50b9fe09d")
+                .unwrap();
+        assert_eq!(
+            crate::compile(tx, &NetworkDefinition::simulator())
+                .unwrap()
+                .instructions[1],
+            Instruction::CreateLocker {
+                component_address: ComponentAddress::from_str(
+                    "0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1"
+                )
+                .unwrap(),
+                bucket_id: 512,
+                claim_rule: AccessRule::Protected(AccessRuleNode::ProofRule(ProofRule::Require(
+                    SoftResourceOrNonFungible::StaticResource(badge)
+                ))),
             }
         );
     }
 
+    #[test]
+    fn test_create_account() {
+        let tx = r#"
+            TAKE_FROM_WORKTOP  ResourceAddress("030000000000000000000000000000000000000000000000000004")  Bucket("xrd");
+            CREATE_ACCOUNT  Enum("AllowAll")  Some(Bucket("xrd"));
+            CREATE_ACCOUNT  Enum("DenyAll")  None;
+        "#;
+        let instructions = crate::compile(tx, &NetworkDefinition::simulator())
+            .unwrap()
+            .instructions;
+        assert_eq!(
+            instructions[1],
+            Instruction::CreateAccount {
+                withdraw_auth: AccessRule::AllowAll,
+                bucket_id: Some(512),
+            }
+        );
+        assert_eq!(
+            instructions[2],
+            Instruction::CreateAccount {
+                withdraw_auth: AccessRule::DenyAll,
+                bucket_id: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_publish_package_with_owner() {
+        let tx = r#"
+            PUBLISH_PACKAGE_WITH_OWNER  Bytes("0061736d")  ResourceAddress("030000000000000000000000000000000000000000000000000004");
+        "#;
+        let instructions = crate::compile(tx, &NetworkDefinition::simulator())
+            .unwrap()
+            .instructions;
+        assert_eq!(
+            instructions[0],
+            Instruction::PublishPackageWithOwner {
+                code: vec![0x00, 0x61, 0x73, 0x6d],
+                owner_badge: ResourceAddress::from_str(
+                    "030000000000000000000000000000000000000000000000000004"
+                )
+                .unwrap(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_create_locker_with_invalid_rule() {
+        let tx = r#"
+            TAKE_FROM_WORKTOP  ResourceAddress("030000000000000000000000000000000000000000000000000004")  Bucket("xrd");
+            CREATE_LOCKER  ComponentAddress("0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1")  Bucket("xrd")  Enum("Anyone");
+        "#;
+        assert_eq!(
+            crate::compile(tx, &NetworkDefinition::simulator()),
+            Err(crate::CompileError::GeneratorError(
+                GeneratorError::InvalidAccessRule(ast::Value::Enum("Anyone".into(), vec![]))
+            ))
+        );
+    }
+
+    #[test]
+    fn test_bech32_addresses_of_the_network() {
+        let resource_address =
+            ResourceAddress::from_str("030000000000000000000000000000000000000000000000000004")
+                .unwrap();
+        let simulator_address =
+            Bech32Encoder::for_simulator().encode_resource_address(&resource_address);
+        let mainnet_address =
+            Bech32Encoder::new(MAINNET_NETWORK_ID).encode_resource_address(&resource_address);
+
+        generate_instruction_ok!(
+            &format!(
+                r#"TAKE_FROM_WORKTOP ResourceAddress("{}") Bucket("xrd_bucket");"#,
+                simulator_address
+            ),
+            Instruction::TakeFromWorktop { resource_address }
+        );
+        generate_value_error!(
+            &format!(r#"ResourceAddress("{}")"#, mainnet_address),
+            GeneratorError::InvalidResourceAddress(mainnet_address)
+        );
+    }
+
     #[test]
     fn test_transaction() {
         let tx = include_str!("../examples/complex.rtm");
@@ -1047,8 +1463,11 @@ mod tests {
         ];
 
         assert_eq!(
-            crate::compile(tx).unwrap(),
+            crate::compile(tx, &NetworkDefinition::simulator()).unwrap(),
             Transaction {
+                header: TransactionHeader {
+                    network_id: NetworkDefinition::simulator().id,
+                },
                 instructions: vec![
                     Instruction::CallMethod {
                         component_address: ComponentAddress::from_str(
@@ -1139,6 +1558,7 @@ mod tests {
                         )
                         .unwrap(),
                         method: "deposit_batch".into(),
+                        args: vec![]
                     },
                     Instruction::PublishPackage { code: code.clone() },
                     Instruction::PublishPackage { code: code.clone() }
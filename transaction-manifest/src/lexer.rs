@@ -40,6 +40,7 @@ pub enum TokenKind {
     String,
     Struct,
     Enum,
+    IndexedEnum,
     Option,
     Box,
     Array,
@@ -51,6 +52,8 @@ pub enum TokenKind {
     HashSet,
     HashMap,
     Decimal,
+    I256,
+    U256,
     PackageAddress,
     ComponentAddress,
     ResourceAddress,
@@ -94,10 +97,16 @@ pub enum TokenKind {
     CreateProofFromBucket,
     CloneProof,
     DropProof,
+    DropAllProofs,
     CallFunction,
     CallMethod,
     CallMethodWithAllResources,
+    CallResourceMethod,
+    CreateLocker,
+    ClaimLocker,
+    CreateAccount,
     PublishPackage,
+    PublishPackageWithOwner,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -370,6 +379,7 @@ impl Lexer {
             "String" => Ok(TokenKind::String),
             "Struct" => Ok(TokenKind::Struct),
             "Enum" => Ok(TokenKind::Enum),
+            "IndexedEnum" => Ok(TokenKind::IndexedEnum),
             "Option" => Ok(TokenKind::Option),
             "Box" => Ok(TokenKind::Box),
             "Array" => Ok(TokenKind::Array),
@@ -381,6 +391,8 @@ impl Lexer {
             "HashSet" => Ok(TokenKind::HashSet),
             "HashMap" => Ok(TokenKind::HashMap),
             "Decimal" => Ok(TokenKind::Decimal),
+            "I256" => Ok(TokenKind::I256),
+            "U256" => Ok(TokenKind::U256),
             "PackageAddress" => Ok(TokenKind::PackageAddress),
             "ComponentAddress" => Ok(TokenKind::ComponentAddress),
             "ResourceAddress" => Ok(TokenKind::ResourceAddress),
@@ -415,10 +427,16 @@ impl Lexer {
             "CREATE_PROOF_FROM_BUCKET" => Ok(TokenKind::CreateProofFromBucket),
             "CLONE_PROOF" => Ok(TokenKind::CloneProof),
             "DROP_PROOF" => Ok(TokenKind::DropProof),
+            "DROP_ALL_PROOFS" => Ok(TokenKind::DropAllProofs),
             "CALL_FUNCTION" => Ok(TokenKind::CallFunction),
             "CALL_METHOD" => Ok(TokenKind::CallMethod),
             "CALL_METHOD_WITH_ALL_RESOURCES" => Ok(TokenKind::CallMethodWithAllResources),
+            "CALL_RESOURCE_METHOD" => Ok(TokenKind::CallResourceMethod),
+            "CREATE_LOCKER" => Ok(TokenKind::CreateLocker),
+            "CLAIM_LOCKER" => Ok(TokenKind::ClaimLocker),
+            "CREATE_ACCOUNT" => Ok(TokenKind::CreateAccount),
             "PUBLISH_PACKAGE" => Ok(TokenKind::PublishPackage),
+            "PUBLISH_PACKAGE_WITH_OWNER" => Ok(TokenKind::PublishPackageWithOwner),
 
             s @ _ => Err(LexerError::UnknownIdentifier(s.into())),
         }
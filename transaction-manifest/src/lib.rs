@@ -7,6 +7,7 @@ pub mod parser;
 pub use decompiler::{decompile, DecompileError};
 
 use radix_engine::model::Transaction;
+use scrypto::core::NetworkDefinition;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CompileError {
@@ -15,10 +16,11 @@ pub enum CompileError {
     GeneratorError(generator::GeneratorError),
 }
 
-pub fn compile(s: &str) -> Result<Transaction, CompileError> {
+/// Compiles a manifest into a transaction for the given network.
+pub fn compile(s: &str, network: &NetworkDefinition) -> Result<Transaction, CompileError> {
     let tokens = lexer::tokenize(s).map_err(CompileError::LexerError)?;
     let ast = parser::Parser::new(tokens)
         .parse_transaction()
         .map_err(CompileError::ParserError)?;
-    generator::generate_transaction(&ast).map_err(CompileError::GeneratorError)
+    generator::generate_transaction(&ast, network).map_err(CompileError::GeneratorError)
 }
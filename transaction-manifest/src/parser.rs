@@ -9,6 +9,7 @@ pub enum ParserError {
     InvalidNumberOfTypes { actual: usize, expected: usize },
     InvalidHex(String),
     MissingEnumName,
+    MissingEnumIndex,
 }
 
 pub struct Parser {
@@ -133,6 +134,7 @@ impl Parser {
             TokenKind::DropProof => Instruction::DropProof {
                 proof: self.parse_value()?,
             },
+            TokenKind::DropAllProofs => Instruction::DropAllProofs,
             TokenKind::CallFunction => Instruction::CallFunction {
                 package_address: self.parse_value()?,
                 blueprint_name: self.parse_value()?,
@@ -159,10 +161,45 @@ impl Parser {
             TokenKind::CallMethodWithAllResources => Instruction::CallMethodWithAllResources {
                 component_address: self.parse_value()?,
                 method: self.parse_value()?,
+                args: {
+                    let mut values = vec![];
+                    while self.peek()?.kind != TokenKind::Semicolon {
+                        values.push(self.parse_value()?);
+                    }
+                    values
+                },
+            },
+            TokenKind::CallResourceMethod => Instruction::CallResourceMethod {
+                resource_address: self.parse_value()?,
+                method: self.parse_value()?,
+                args: {
+                    let mut values = vec![];
+                    while self.peek()?.kind != TokenKind::Semicolon {
+                        values.push(self.parse_value()?);
+                    }
+                    values
+                },
+            },
+            TokenKind::CreateLocker => Instruction::CreateLocker {
+                component_address: self.parse_value()?,
+                bucket: self.parse_value()?,
+                claim_rule: self.parse_value()?,
+            },
+            TokenKind::ClaimLocker => Instruction::ClaimLocker {
+                component_address: self.parse_value()?,
+                locker_id: self.parse_value()?,
+            },
+            TokenKind::CreateAccount => Instruction::CreateAccount {
+                withdraw_auth: self.parse_value()?,
+                bucket: self.parse_value()?,
             },
             TokenKind::PublishPackage => Instruction::PublishPackage {
                 code: self.parse_value()?,
             },
+            TokenKind::PublishPackageWithOwner => Instruction::PublishPackageWithOwner {
+                code: self.parse_value()?,
+                owner_badge: self.parse_value()?,
+            },
             _ => {
                 return Err(ParserError::UnexpectedToken(token));
             }
@@ -193,6 +230,7 @@ impl Parser {
             TokenKind::StringLiteral(value) => advance_ok!(self, Value::String(value)),
             TokenKind::Struct => self.parse_struct(),
             TokenKind::Enum => self.parse_enum(),
+            TokenKind::IndexedEnum => self.parse_indexed_enum(),
             TokenKind::Some | TokenKind::None => self.parse_option(),
             TokenKind::Array => self.parse_array(),
             TokenKind::Tuple => self.parse_tuple(),
@@ -203,6 +241,8 @@ impl Parser {
             TokenKind::HashSet => self.parse_hash_set(),
             TokenKind::HashMap => self.parse_hash_map(),
             TokenKind::Decimal
+            | TokenKind::I256
+            | TokenKind::U256
             | TokenKind::PackageAddress
             | TokenKind::ComponentAddress
             | TokenKind::ResourceAddress
@@ -236,6 +276,20 @@ impl Parser {
         Ok(Value::Enum(name, name_and_fields))
     }
 
+    pub fn parse_indexed_enum(&mut self) -> Result<Value, ParserError> {
+        advance_match!(self, TokenKind::IndexedEnum);
+        let mut index_and_fields =
+            self.parse_values_any(TokenKind::OpenParenthesis, TokenKind::CloseParenthesis)?;
+        let index = match index_and_fields.get(0) {
+            Some(Value::U8(index)) => *index,
+            _ => {
+                return Err(ParserError::MissingEnumIndex);
+            }
+        };
+        index_and_fields.remove(0);
+        Ok(Value::IndexedEnum(index, index_and_fields))
+    }
+
     pub fn parse_option(&mut self) -> Result<Value, ParserError> {
         let token = self.advance()?;
         match token.kind {
@@ -338,6 +392,8 @@ impl Parser {
         let token = self.advance()?;
         match token.kind {
             TokenKind::Decimal => Ok(Value::Decimal(self.parse_values_one()?.into())),
+            TokenKind::I256 => Ok(Value::I256(self.parse_values_one()?.into())),
+            TokenKind::U256 => Ok(Value::U256(self.parse_values_one()?.into())),
             TokenKind::PackageAddress => Ok(Value::PackageAddress(self.parse_values_one()?.into())),
             TokenKind::ComponentAddress => {
                 Ok(Value::ComponentAddress(self.parse_values_one()?.into()))
@@ -426,6 +482,7 @@ impl Parser {
             TokenKind::String => Ok(Type::String),
             TokenKind::Struct => Ok(Type::Struct),
             TokenKind::Enum => Ok(Type::Enum),
+            TokenKind::IndexedEnum => Ok(Type::IndexedEnum),
             TokenKind::Option => Ok(Type::Option),
             TokenKind::Array => Ok(Type::Array),
             TokenKind::Tuple => Ok(Type::Tuple),
@@ -436,6 +493,8 @@ impl Parser {
             TokenKind::HashSet => Ok(Type::HashSet),
             TokenKind::HashMap => Ok(Type::HashMap),
             TokenKind::Decimal => Ok(Type::Decimal),
+            TokenKind::I256 => Ok(Type::I256),
+            TokenKind::U256 => Ok(Type::U256),
             TokenKind::PackageAddress => Ok(Type::PackageAddress),
             TokenKind::ComponentAddress => Ok(Type::ComponentAddress),
             TokenKind::ResourceAddress => Ok(Type::ResourceAddress),
@@ -502,6 +561,14 @@ mod tests {
         parse_value_ok!(r#"1u64"#, Value::U64(1));
         parse_value_ok!(r#"1u128"#, Value::U128(1));
         parse_value_ok!(r#""test""#, Value::String("test".into()));
+        parse_value_ok!(
+            r#"I256("-1")"#,
+            Value::I256(Value::String("-1".into()).into())
+        );
+        parse_value_ok!(
+            r#"U256("1")"#,
+            Value::U256(Value::String("1".into()).into())
+        );
     }
 
     #[test]
@@ -520,6 +587,11 @@ mod tests {
             Value::Enum("Variant".to_string(), vec![Value::String("Hello".into()), Value::U8(123)],)
         );
         parse_value_ok!(r#"Enum("Variant")"#, Value::Enum("Variant".to_string(), vec![]));
+        parse_value_ok!(
+            r#"IndexedEnum(1u8, "Hello", 123u8)"#,
+            Value::IndexedEnum(1, vec![Value::String("Hello".into()), Value::U8(123)])
+        );
+        parse_value_ok!(r#"IndexedEnum(0u8)"#, Value::IndexedEnum(0, vec![]));
     }
 
     #[test]
@@ -613,6 +685,7 @@ mod tests {
     #[test]
     fn test_failures() {
         parse_value_error!(r#"Enum(0u8"#, ParserError::UnexpectedEof);
+        parse_value_error!(r#"IndexedEnum("Variant")"#, ParserError::MissingEnumIndex);
         parse_value_error!(
             r#"Enum(0u8>"#,
             ParserError::UnexpectedToken(Token {
@@ -692,6 +765,8 @@ mod tests {
                 proof: Value::Proof(Value::String("admin_auth".into()).into()),
             }
         );
+        parse_instruction_ok!(r#"DROP_ALL_PROOFS;"#, Instruction::DropAllProofs);
+        parse_instruction_ok!(r#"CLEAR_AUTH_ZONE;"#, Instruction::ClearAuthZone);
         parse_instruction_ok!(
             r#"CALL_FUNCTION  PackageAddress("01d1f50010e4102d88aacc347711491f852c515134a9ecf67ba17c")  "Airdrop"  "new"  500u32  HashMap<String, U8>("key", 1u8);"#,
             Instruction::CallFunction {
@@ -747,6 +822,86 @@ mod tests {
                         .into()
                 ),
                 method: Value::String("deposit_batch".into()),
+                args: vec![]
+            }
+        );
+        parse_instruction_ok!(
+            r#"CALL_METHOD_WITH_ALL_RESOURCES  ComponentAddress("02d43f479e9b2beb9df98bc3888344fc25eda181e8f710ce1bf1de") "deposit_batch_safe" TreeSet<ResourceAddress>(ResourceAddress("030000000000000000000000000000000000000000000000000004"));"#,
+            Instruction::CallMethodWithAllResources {
+                component_address: Value::ComponentAddress(
+                    Value::String("02d43f479e9b2beb9df98bc3888344fc25eda181e8f710ce1bf1de".into())
+                        .into()
+                ),
+                method: Value::String("deposit_batch_safe".into()),
+                args: vec![Value::TreeSet(
+                    Type::ResourceAddress,
+                    vec![Value::ResourceAddress(
+                        Value::String(
+                            "030000000000000000000000000000000000000000000000000004".into()
+                        )
+                        .into()
+                    )]
+                )]
+            }
+        );
+        parse_instruction_ok!(
+            r#"CALL_RESOURCE_METHOD  ResourceAddress("030000000000000000000000000000000000000000000000000004") "update_metadata" HashMap<String, String>();"#,
+            Instruction::CallResourceMethod {
+                resource_address: Value::ResourceAddress(
+                    Value::String("030000000000000000000000000000000000000000000000000004".into())
+                        .into()
+                ),
+                method: Value::String("update_metadata".into()),
+                args: vec![Value::HashMap(Type::String, Type::String, vec![])]
+            }
+        );
+        parse_instruction_ok!(
+            r#"CREATE_LOCKER  ComponentAddress("0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1")  Bucket("xrd")  Enum("AllowAll");"#,
+            Instruction::CreateLocker {
+                component_address: Value::ComponentAddress(
+                    Value::String("0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1".into())
+                        .into()
+                ),
+                bucket: Value::Bucket(Value::String("xrd".into()).into()),
+                claim_rule: Value::Enum("AllowAll".into(), vec![]),
+            }
+        );
+        parse_instruction_ok!(
+            r#"CREATE_ACCOUNT  Enum("AllowAll")  Some(Bucket("xrd"));"#,
+            Instruction::CreateAccount {
+                withdraw_auth: Value::Enum("AllowAll".into(), vec![]),
+                bucket: Value::Option(
+                    Some(Value::Bucket(Value::String("xrd".into()).into())).into()
+                ),
+            }
+        );
+        parse_instruction_ok!(
+            r#"PUBLISH_PACKAGE_WITH_OWNER  Bytes("0061736d")  ResourceAddress("030000000000000000000000000000000000000000000000000004");"#,
+            Instruction::PublishPackageWithOwner {
+                code: Value::Bytes(vec![0x00, 0x61, 0x73, 0x6d]),
+                owner_badge: Value::ResourceAddress(
+                    Value::String("030000000000000000000000000000000000000000000000000004".into())
+                        .into()
+                ),
+            }
+        );
+        parse_instruction_ok!(
+            r#"CLAIM_LOCKER  ComponentAddress("0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1")  Tuple(Hash("2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"), 1025u32);"#,
+            Instruction::ClaimLocker {
+                component_address: Value::ComponentAddress(
+                    Value::String("0292566c83de7fd6b04fcc92b5e04b03228ccff040785673278ef1".into())
+                        .into()
+                ),
+                locker_id: Value::Tuple(vec![
+                    Value::Hash(
+                        Value::String(
+                            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+                                .into()
+                        )
+                        .into()
+                    ),
+                    Value::U32(1025),
+                ]),
             }
         );
     }